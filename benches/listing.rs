@@ -0,0 +1,82 @@
+//! Listing and filtering a large list: `cargo bench --bench listing`
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use rust_todo_cli::fuzzy;
+use rust_todo_cli::todo::{ListFilter, Status, Task, TodoList};
+
+const TASKS: usize = 100_000;
+const RUNS: u32 = 5;
+
+// A list like an import from a bug tracker: mostly plain tasks, some tagged,
+// in projects or waiting on others
+fn large_list() -> TodoList {
+    let mut todo: TodoList = (0..TASKS)
+        .map(|i| {
+            let mut builder = Task::builder(format!(
+                "Fix issue {} in module {} +proj{}",
+                i,
+                i % 97,
+                i % 20
+            ));
+            if i % 3 == 0 {
+                builder = builder.tag("bug");
+            }
+            if i % 4 == 0 {
+                builder = builder.status(Status::Completed);
+            }
+            builder.build().unwrap()
+        })
+        .collect();
+    for index in (10..TASKS).step_by(1_000) {
+        todo.add_dependency(
+            todo.task_ref(index).unwrap(),
+            todo.task_ref(index - 1).unwrap(),
+        )
+        .unwrap();
+    }
+    todo
+}
+
+// Average time of a few runs
+fn time<T>(label: &str, mut run: impl FnMut() -> T) {
+    let mut total = Duration::ZERO;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        black_box(run());
+        total += start.elapsed();
+    }
+    println!("{:<32} {:>10.2?}", label, total / RUNS);
+}
+
+fn main() {
+    let todo = large_list();
+    println!("{} tasks, average of {} runs", TASKS, RUNS);
+
+    time("filter_by_status", || {
+        todo.filter_by_status(Status::Todo).count()
+    });
+    let tagged = ListFilter {
+        tags: vec!["bug".to_string()],
+        ..ListFilter::default()
+    };
+    time("filter tag:bug", || todo.filter(&tagged).len());
+    let text = ListFilter {
+        text: vec!["module 42".to_string()],
+        ..ListFilter::default()
+    };
+    time("filter text", || todo.filter(&text).len());
+    let blocked = ListFilter {
+        blocked: true,
+        ..ListFilter::default()
+    };
+    time("filter blocked", || todo.filter(&blocked).len());
+    time("fuzzy score", || {
+        fuzzy::score("modle 42", "Fix issue 4242 in module 42 +proj2")
+    });
+    time("fuzzy rank", || {
+        fuzzy::rank(&todo, "isue modle 42", fuzzy::LIMIT).len()
+    });
+    time("render plain listing", || format!("{:#}", todo).len());
+}
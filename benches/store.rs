@@ -0,0 +1,125 @@
+//! Saving and loading a large list, and counting a large archive, with the
+//! memory each takes at its peak: `cargo bench --bench store`
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use rust_todo_cli::settings::Settings;
+use rust_todo_cli::store::Store;
+use rust_todo_cli::todo::{Status, Storable, Task, TodoList};
+
+const TASKS: usize = 100_000;
+const RUNS: u32 = 5;
+
+// The system allocator, keeping count of the bytes in use and the most
+// there have been since the last reset
+struct Counting;
+
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // SAFETY: passed on unchanged to the system allocator
+        let pointer = unsafe { System.alloc(layout) };
+        if !pointer.is_null() {
+            let now = IN_USE.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(now, Ordering::Relaxed);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        // SAFETY: passed on unchanged to the system allocator
+        unsafe { System.dealloc(pointer, layout) };
+        IN_USE.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn large_list() -> TodoList {
+    (0..TASKS)
+        .map(|i| {
+            let mut builder = Task::builder(format!(
+                "Fix issue {} in module {} +proj{}",
+                i,
+                i % 97,
+                i % 20
+            ));
+            if i % 3 == 0 {
+                builder = builder.tag("bug");
+            }
+            if i % 4 == 0 {
+                builder = builder.status(Status::Completed);
+            }
+            builder.build().unwrap()
+        })
+        .collect()
+}
+
+// Average time of a few runs, and the most memory any of them took on top
+// of what was in use before
+fn measure<T>(label: &str, mut run: impl FnMut() -> T) {
+    let mut total = Duration::ZERO;
+    let mut peak = 0;
+    for _ in 0..RUNS {
+        let before = IN_USE.load(Ordering::Relaxed);
+        PEAK.store(before, Ordering::Relaxed);
+        let start = Instant::now();
+        black_box(run());
+        total += start.elapsed();
+        peak = peak.max(PEAK.load(Ordering::Relaxed) - before);
+    }
+    println!(
+        "{:<14} {:>10.2?} {:>8.1} MB peak",
+        label,
+        total / RUNS,
+        peak as f64 / 1_000_000.0
+    );
+}
+
+fn main() {
+    let dir = std::env::temp_dir().join(format!("rust-todo-cli-bench-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json").to_string_lossy().into_owned();
+
+    let mut todo = large_list();
+    todo.save(&path).unwrap();
+    let size = fs::metadata(&path).unwrap().len();
+    println!(
+        "{} tasks, {:.1} MB file, average of {} runs",
+        TASKS,
+        size as f64 / 1_000_000.0,
+        RUNS
+    );
+
+    measure("save", || todo.save(&path).unwrap());
+    measure("load", || TodoList::load(&path).unwrap());
+
+    todo.set_settings(Settings {
+        compact_files: true,
+        ..Settings::default()
+    });
+    measure("save --compact", || todo.save(&path).unwrap());
+    println!(
+        "{:.1} MB file without indentation",
+        fs::metadata(&path).unwrap().len() as f64 / 1_000_000.0
+    );
+    measure("load --compact", || TodoList::load(&path).unwrap());
+    todo.set_settings(Settings::default());
+
+    // `archive stats` reads the file a task at a time; `list archived`
+    // loads it once a session and reuses it after
+    let store = Store::in_dir(&dir);
+    todo.save(&store.archive).unwrap();
+    measure("archive stats", || store.archive_stats().unwrap());
+    measure("archive load", || TodoList::load(&store.archive).unwrap());
+    store.load_archive().unwrap();
+    measure("archive cached", || store.load_archive().unwrap());
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,215 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::date::{Timestamp, now};
+use crate::history::{self, LogEntry};
+use crate::log;
+use crate::settings::Settings;
+use crate::store::Store;
+use crate::todo::{TodoError, TodoList, read_list_from, write_json};
+
+/// What the `format` field of every backup says, telling one from any other
+/// JSON file
+pub const FORMAT: &str = "rust-todo-cli-backup";
+/// Version of the backup format written here, and the newest one read
+pub const BACKUP_VERSION: u32 = 1;
+
+// Added to a file's name while its restored copy waits to replace it
+const RESTORING_SUFFIX: &str = ".restoring";
+
+/// A file the app keeps, as a section of a backup
+pub struct Section {
+    /// Key of the section in the backup
+    pub name: &'static str,
+    /// Where the store keeps the file
+    pub file: fn(&Store) -> &str,
+    // Whether a section's contents would load back as the file
+    check: fn(&Value) -> Result<(), TodoError>,
+}
+
+/// Every file a backup holds, in the order they are restored. The task file
+/// carries the templates, saved filters and snippets with the tasks. A new
+/// file kept by the app only needs a section here to be backed up.
+pub const SECTIONS: &[Section] = &[
+    Section {
+        name: "tasks",
+        file: |store| &store.tasks,
+        check: check_list,
+    },
+    Section {
+        name: "archive",
+        file: |store| &store.archive,
+        check: check_list,
+    },
+    Section {
+        name: "trash",
+        file: |store| &store.trash,
+        check: check_list,
+    },
+    Section {
+        name: "history",
+        file: |store| &store.log,
+        check: check_history,
+    },
+];
+
+fn check_list(contents: &Value) -> Result<(), TodoError> {
+    read_list_from(&serde_json::to_vec(contents)?[..]).map(drop)
+}
+
+fn check_history(contents: &Value) -> Result<(), TodoError> {
+    Vec::<LogEntry>::deserialize(contents)?;
+    Ok(())
+}
+
+/// Everything the app keeps, in one file. Each section holds the contents
+/// of its file as they were, or null for a file that did not exist.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Bundle {
+    pub format: String,
+    pub version: u32,
+    pub created_at: Timestamp,
+    /// Version of the app that made the backup
+    pub app_version: String,
+    pub sections: BTreeMap<String, Value>,
+}
+
+/// What [`restore`] did
+#[derive(Debug, Clone, PartialEq)]
+pub struct Restored {
+    /// Sections written back, in the order of [`SECTIONS`]
+    pub sections: Vec<&'static str>,
+    /// Sections this version knows nothing about, left out
+    pub skipped: Vec<String>,
+    /// Where what was there before went, see [`Store::restore_backup`]
+    pub previous: String,
+}
+
+impl Bundle {
+    /// Names of the sections held, as they are listed to the user
+    pub fn names(&self) -> Vec<&str> {
+        self.sections.keys().map(String::as_str).collect()
+    }
+
+    /// Write the backup to `path`, as compact JSON when the settings say so
+    pub fn write(&self, path: &str, settings: &Settings) -> Result<(), TodoError> {
+        log!(Debug, "writing a backup to {}", path);
+        write_json(path, self, settings.compact_files)
+    }
+}
+
+/// Back up every file in [`SECTIONS`] as it is on disk now, apart from the
+/// tasks and history, which are taken from `todo` as it would save them so
+/// changes not saved yet are kept too. A file that can't be read as JSON
+/// fails the backup rather than leaving it out.
+pub fn create(store: &Store, todo: &TodoList) -> Result<Bundle, TodoError> {
+    let mut sections = BTreeMap::new();
+    for section in SECTIONS {
+        let path = (section.file)(store);
+        let contents = match section.name {
+            "tasks" => todo.to_value()?,
+            "history" => serde_json::to_value(history::kept(todo.log(), &store.settings))?,
+            _ => match fs::read(path) {
+                Ok(bytes) => serde_json::from_slice(&bytes)?,
+                Err(error) if error.kind() == io::ErrorKind::NotFound => Value::Null,
+                Err(error) => return Err(TodoError::FileError(error)),
+            },
+        };
+        sections.insert(section.name.to_string(), contents);
+    }
+    Ok(Bundle {
+        format: FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at: now(),
+        app_version: crate::VERSION.to_string(),
+        sections,
+    })
+}
+
+/// Read the backup at `path`, turning down files that are not backups and
+/// backups newer than this version reads
+pub fn read(path: &str) -> Result<Bundle, TodoError> {
+    let not_a_backup = || TodoError::NotABackup(path.to_string());
+    let contents: Value = serde_json::from_slice(&fs::read(path)?).map_err(|_| not_a_backup())?;
+    if contents.get("format").and_then(Value::as_str) != Some(FORMAT) {
+        return Err(not_a_backup());
+    }
+    let bundle = Bundle::deserialize(contents).map_err(|_| not_a_backup())?;
+    if bundle.version > BACKUP_VERSION {
+        return Err(TodoError::BackupTooNew {
+            version: bundle.version,
+            supported: BACKUP_VERSION,
+        });
+    }
+    Ok(bundle)
+}
+
+/// Put the files back as the backup has them. Every section is checked
+/// before anything is written, and what is there now, with `current` as the
+/// list in use, is backed up first, so a backup that fails a check changes
+/// nothing and changes not saved yet can still be had back. Sections the
+/// backup does not have leave their files alone; null ones remove theirs.
+pub fn restore(store: &Store, bundle: &Bundle, current: &TodoList) -> Result<Restored, TodoError> {
+    if store.read_only {
+        return Err(TodoError::ReadOnly("backup"));
+    }
+    let mut restoring = Vec::new();
+    for section in SECTIONS {
+        let Some(contents) = bundle.sections.get(section.name) else {
+            continue;
+        };
+        if !contents.is_null() {
+            (section.check)(contents).map_err(|error| TodoError::BadBackupSection {
+                section: section.name.to_string(),
+                error: error.to_string(),
+            })?;
+        }
+        restoring.push((section, contents));
+    }
+    let skipped: Vec<String> = bundle
+        .names()
+        .into_iter()
+        .filter(|name| !SECTIONS.iter().any(|section| section.name == *name))
+        .map(str::to_string)
+        .collect();
+
+    let previous = store.restore_backup();
+    create(store, current)?.write(&previous, &store.settings)?;
+
+    // Every file is written beside the one it replaces before any of them
+    // is, so a failed write leaves the old ones in place
+    let mut written = Vec::new();
+    for (section, contents) in &restoring {
+        if contents.is_null() {
+            continue;
+        }
+        let path = format!("{}{}", (section.file)(store), RESTORING_SUFFIX);
+        if let Err(error) = write_json(&path, contents, store.settings.compact_files) {
+            written.iter().for_each(|path| drop(fs::remove_file(path)));
+            return Err(error);
+        }
+        written.push(path);
+    }
+    for (section, contents) in &restoring {
+        let path = (section.file)(store);
+        if contents.is_null() {
+            match fs::remove_file(path) {
+                Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error.into()),
+                _ => {}
+            }
+        } else {
+            fs::rename(format!("{}{}", path, RESTORING_SUFFIX), path)?;
+        }
+        log!(Debug, "restored {} to {}", section.name, path);
+    }
+    store.forget_cached();
+    Ok(Restored {
+        sections: restoring.iter().map(|(section, _)| section.name).collect(),
+        skipped,
+        previous,
+    })
+}
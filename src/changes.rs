@@ -0,0 +1,162 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
+
+use crate::todo::{Status, Task};
+
+/// One task a command added, removed or changed
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A task that was not there before, numbered as it is now
+    Added { index: usize, description: String },
+    /// A task that is gone, numbered as it was
+    Removed { index: usize, description: String },
+    /// A task that kept its place in the list but not its status or its
+    /// description, numbered as it is now
+    Modified {
+        index: usize,
+        description: String,
+        /// The description it had, when that changed
+        was: Option<String>,
+        /// The status it had and has, when that changed
+        status: Option<(Status, Status)>,
+    },
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Change::Added { index, description } => write!(f, "+ {}. {}", index, description),
+            Change::Removed { index, description } => write!(f, "- {}. {}", index, description),
+            Change::Modified {
+                index,
+                description,
+                was,
+                status,
+            } => {
+                write!(f, "~ {}. ", index)?;
+                match was {
+                    Some(was) => write!(f, "{} → {}", was, description)?,
+                    None => write!(f, "{}", description)?,
+                }
+                if let Some((from, to)) = status {
+                    write!(f, ": {} → {}", from, to)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// What a command did to the list, task by task: removed tasks first in
+/// their old order, then the rest in the new order. Subtasks are not
+/// looked at, nor is anything else about a task, such as its dates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ChangeSet {
+    pub changes: Vec<Change>,
+}
+
+impl ChangeSet {
+    /// The changes that turn `before` into `after`, telling tasks apart by
+    /// their IDs
+    pub fn between(before: &[Task], after: &[Task]) -> ChangeSet {
+        let old: HashMap<u64, &Task> = before.iter().map(|task| (task.id, task)).collect();
+        let kept: HashSet<u64> = after.iter().map(|task| task.id).collect();
+
+        let mut changes: Vec<Change> = before
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| !kept.contains(&task.id))
+            .map(|(i, task)| Change::Removed {
+                index: i + 1,
+                description: task.description.clone(),
+            })
+            .collect();
+        for (i, task) in after.iter().enumerate() {
+            let index = i + 1;
+            let description = task.description.clone();
+            let Some(old) = old.get(&task.id) else {
+                changes.push(Change::Added { index, description });
+                continue;
+            };
+            let was = (old.description != task.description).then(|| old.description.clone());
+            let status = (old.status != task.status).then_some((old.status, task.status));
+            if was.is_some() || status.is_some() {
+                changes.push(Change::Modified {
+                    index,
+                    description,
+                    was,
+                    status,
+                });
+            }
+        }
+        ChangeSet { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+}
+
+/// How many tasks a session added, completed, removed and edited, going by
+/// the list as it started and as it ends rather than by the commands run,
+/// so a task added and then removed again counts for nothing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SessionStats {
+    pub added: usize,
+    /// Done now and not at the start, new tasks included
+    pub completed: usize,
+    pub removed: usize,
+    /// Changed in any other way, subtasks and timers included
+    pub edited: usize,
+}
+
+impl SessionStats {
+    /// The net effect of a session that turned `before` into `after`,
+    /// telling tasks apart by their IDs as [`ChangeSet::between`] does
+    pub fn between(before: &[Task], after: &[Task]) -> SessionStats {
+        let old: HashMap<u64, &Task> = before.iter().map(|task| (task.id, task)).collect();
+        let kept: HashSet<u64> = after.iter().map(|task| task.id).collect();
+        let mut stats = SessionStats {
+            removed: before
+                .iter()
+                .filter(|task| !kept.contains(&task.id))
+                .count(),
+            ..SessionStats::default()
+        };
+        for task in after {
+            let old = old.get(&task.id);
+            if old.is_none() {
+                stats.added += 1;
+            }
+            if task.is_completed() && !old.is_some_and(|old| old.is_completed()) {
+                stats.completed += 1;
+            } else if old.is_some_and(|old| !same_but_touched(old, task)) {
+                stats.edited += 1;
+            }
+        }
+        stats
+    }
+
+    /// Whether the session left the list as it found it
+    pub fn is_empty(&self) -> bool {
+        *self == SessionStats::default()
+    }
+}
+
+// Whether two copies of a task differ at most in when they were last
+// touched, as one changed and then changed back does
+fn same_but_touched(old: &Task, new: &Task) -> bool {
+    let untouched = |task: &Task| {
+        let mut task = task.clone();
+        task.updated_at = None;
+        for subtask in &mut task.subtasks {
+            subtask.updated_at = None;
+        }
+        task
+    };
+    old == new || untouched(old) == untouched(new)
+}
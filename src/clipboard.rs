@@ -0,0 +1,146 @@
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use crate::command::OutputFormat;
+use crate::listing::IndexedTask;
+use crate::msg;
+use crate::todo::{TodoError, TodoList};
+
+/// Somewhere `copy` can put text, the system clipboard or a stand-in
+pub trait Clipboard {
+    /// Replace what the clipboard holds with `text`
+    fn set_text(&mut self, text: &str) -> Result<(), TodoError>;
+}
+
+/// The system clipboard, reached through the usual command-line tools:
+/// pbcopy on macOS, clip on Windows, and wl-copy, xclip or xsel elsewhere.
+/// These come with the system or its desktop, so a clipboard library and
+/// the display libraries it builds against are not needed to build this.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClipboard;
+
+/// How a clipboard program wants text on its stdin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    Utf8,
+    /// UTF-16, little-endian, after a byte order mark. Windows' clip reads
+    /// anything else in the console's code page, which mangles text outside
+    /// ASCII, but takes text with the mark as Unicode.
+    Utf16Le,
+}
+
+impl Encoding {
+    /// `text` as bytes to write to the program
+    pub fn encode(self, text: &str) -> Vec<u8> {
+        match self {
+            Encoding::Utf8 => text.as_bytes().to_vec(),
+            Encoding::Utf16Le => [0xFEFF]
+                .into_iter()
+                .chain(text.encode_utf16())
+                .flat_map(u16::to_le_bytes)
+                .collect(),
+        }
+    }
+}
+
+// Programs that put what they read on stdin onto the clipboard, in the order
+// they are tried
+fn tools() -> Vec<(&'static str, &'static [&'static str], Encoding)> {
+    if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[], Encoding::Utf8)]
+    } else if cfg!(target_os = "windows") {
+        vec![("clip", &[], Encoding::Utf16Le)]
+    } else {
+        let mut tools: Vec<(&str, &[&str], Encoding)> = Vec::new();
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+            tools.push(("wl-copy", &[], Encoding::Utf8));
+        }
+        if std::env::var_os("DISPLAY").is_some() {
+            tools.push(("xclip", &["-selection", "clipboard"], Encoding::Utf8));
+            tools.push(("xsel", &["--clipboard", "--input"], Encoding::Utf8));
+        }
+        tools
+    }
+}
+
+// Hand text to a clipboard program on its stdin
+fn pipe(program: &str, args: &[&str], encoding: Encoding, text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    child
+        .stdin
+        .take()
+        .expect("stdin is piped")
+        .write_all(&encoding.encode(text))?;
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(msg!(
+            "error.clipboard_exit",
+            program = program,
+            status = status
+        )))
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn set_text(&mut self, text: &str) -> Result<(), TodoError> {
+        let tools = tools();
+        if tools.is_empty() {
+            return Err(TodoError::ClipboardError(msg!("error.clipboard_headless")));
+        }
+        for &(program, args, encoding) in &tools {
+            match pipe(program, args, encoding, text) {
+                Ok(()) => return Ok(()),
+                // Not installed: try the next one
+                Err(error) if error.kind() == io::ErrorKind::NotFound => continue,
+                Err(error) => return Err(TodoError::ClipboardError(error.to_string())),
+            }
+        }
+        let names: Vec<&str> = tools.iter().map(|(program, ..)| *program).collect();
+        Err(TodoError::ClipboardError(msg!(
+            "error.clipboard_no_tool",
+            tools = names.join(", ")
+        )))
+    }
+}
+
+/// What `copy <num>` puts on the clipboard: the task's description, or with
+/// `--json` the whole task as `show --json` prints it
+pub fn task_text(todo: &TodoList, index: usize, format: OutputFormat) -> Result<String, TodoError> {
+    let task = todo.get_task(index)?;
+    match format {
+        OutputFormat::Human => Ok(task.description.clone()),
+        OutputFormat::Json => Ok(serde_json::to_string_pretty(&IndexedTask { index, task })?),
+    }
+}
+
+/// What `copy list` puts on the clipboard: the plain listing, one line per
+/// task and subtask, without emoji
+pub fn list_text(todo: &TodoList) -> String {
+    format!("{:#}", todo)
+}
+
+/// Copy a task to `clipboard`, returning the text copied
+pub fn copy_task(
+    clipboard: &mut impl Clipboard,
+    todo: &TodoList,
+    index: usize,
+    format: OutputFormat,
+) -> Result<String, TodoError> {
+    let text = task_text(todo, index, format)?;
+    clipboard.set_text(&text)?;
+    Ok(text)
+}
+
+/// Copy the whole list to `clipboard`, returning the text copied
+pub fn copy_list(clipboard: &mut impl Clipboard, todo: &TodoList) -> Result<String, TodoError> {
+    let text = list_text(todo);
+    clipboard.set_text(&text)?;
+    Ok(text)
+}
@@ -0,0 +1,2495 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use serde_json::json;
+use thiserror::Error;
+
+use crate::{
+    completion::Shell,
+    date::{Date, Time, parse_due, parse_duration},
+    import::Format,
+    log,
+    merge::{MergeReport, Side},
+    msg, snippet,
+    todo::{
+        Estimate, ListFilter, Marker, Recurrence, Selector, Status, TodoError, TodoList, Token,
+        normalize_filter_name, normalize_person, normalize_snippet_name, normalize_tag,
+        normalize_template_name,
+    },
+    width::{edit_distance, typo_limit},
+};
+
+/// Length of a focus session when `pomodoro` is given no minutes
+pub const DEFAULT_POMODORO_MINUTES: u64 = 25;
+
+/// Days laid out by `agenda` when it is given no number
+pub const DEFAULT_AGENDA_DAYS: usize = 7;
+
+/// How command results are written to stdout
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Human,
+    Json,
+}
+
+/// Why a line of input could not be turned into a command
+#[derive(Error, Debug)]
+pub enum ParseError {
+    #[error("{}", msg!("error.empty_command"))]
+    Empty,
+
+    /// The word typed and the closest known commands, if any are close
+    #[error("{}", unknown_command(.0, .1))]
+    UnknownCommand(String, Vec<&'static str>),
+
+    /// A prefix shared by several commands, with all of them
+    #[error("{}", ambiguous_command(.0, .1))]
+    AmbiguousCommand(String, Vec<&'static str>),
+
+    #[error("{}", msg!("error.usage", usage = .0))]
+    Usage(String),
+
+    #[error("{}", msg!("error.unknown_filter", filter = .0))]
+    UnknownFilter(String),
+
+    #[error("{}", msg!("error.unknown_count", target = .0, targets = CountTarget::names()))]
+    UnknownCountTarget(String),
+
+    #[error("{}", invalid_number(.0))]
+    InvalidNumber(&'static str),
+
+    #[error("{}", msg!("error.unquoted_name", action = .0))]
+    UnquotedName(String),
+
+    #[error("{}", msg!("error.missing_status", selector = .0))]
+    MissingStatus(String),
+
+    #[error(transparent)]
+    Invalid(#[from] TodoError),
+}
+
+impl ParseError {
+    /// A name for the kind of error that stays the same across versions and
+    /// languages. A command that failed on its values has the code of the
+    /// [`TodoError`] it ran into.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::Empty => "EmptyCommand",
+            ParseError::UnknownCommand(..) => "UnknownCommand",
+            ParseError::AmbiguousCommand(..) => "AmbiguousCommand",
+            ParseError::Usage(_) => "Usage",
+            ParseError::UnknownFilter(_) => "UnknownFilter",
+            ParseError::UnknownCountTarget(_) => "UnknownCountTarget",
+            ParseError::InvalidNumber(_) => "InvalidNumber",
+            ParseError::UnquotedName(_) => "UnquotedName",
+            ParseError::MissingStatus(_) => "MissingStatus",
+            ParseError::Invalid(error) => error.code(),
+        }
+    }
+}
+
+/// Written like a [`TodoError`]: the code, the message, and the words it is
+/// about
+impl Serialize for ParseError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fields = match self {
+            ParseError::Invalid(error) => return error.serialize(serializer),
+            ParseError::Empty => Vec::new(),
+            ParseError::UnknownCommand(word, suggestions) => {
+                vec![("word", json!(word)), ("suggestions", json!(suggestions))]
+            }
+            ParseError::AmbiguousCommand(word, candidates) => {
+                vec![("word", json!(word)), ("candidates", json!(candidates))]
+            }
+            ParseError::Usage(usage) => vec![("usage", json!(usage))],
+            ParseError::UnknownFilter(filter) => vec![("filter", json!(filter))],
+            ParseError::UnknownCountTarget(target) => vec![
+                ("target", json!(target)),
+                ("targets", json!(CountTarget::names())),
+            ],
+            ParseError::InvalidNumber(what) => vec![("what", json!(what))],
+            ParseError::UnquotedName(action) => vec![("action", json!(action))],
+            ParseError::MissingStatus(selector) => vec![("selector", json!(selector))],
+        };
+        let mut map = serializer.serialize_map(Some(fields.len() + 2))?;
+        map.serialize_entry("error", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        for (name, value) in &fields {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+/// A command the parser knows: the words that run it, and what `help` says
+/// about it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub aliases: &'static [&'static str],
+    /// Each way of calling the command with what it does, one line of the
+    /// `help` listing apiece. Lines starting with spaces explain the one
+    /// above.
+    pub usage: &'static [(&'static str, &'static str)],
+    /// The arguments and flags in more detail, shown by `help <command>`
+    pub description: &'static str,
+    pub examples: &'static [&'static str],
+}
+
+/// Every command, in the order `help` lists them. The parser only accepts
+/// names and aliases found here, or an unambiguous start of one, and
+/// unknown words are matched against them for suggestions.
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "add",
+        aliases: &["a"],
+        usage: &[
+            ("add <description>", "Add a new task"),
+            (
+                "add <a>; <b>; ...",
+                "Add several tasks at once (\\; for a literal ;)",
+            ),
+            (
+                "add --from <file>",
+                "Add one task per line of a text file (# for comments)",
+            ),
+            (
+                "add --stdin",
+                "Add one task per line typed or piped in, until it ends",
+            ),
+            (
+                "add @<snippet> [<key>=<value>...]",
+                "Add a task from a snippet, its placeholders filled in",
+            ),
+            (
+                "add ... --no-duplicates",
+                "Leave out tasks that match an open one instead of asking",
+            ),
+        ],
+        description: "Adds tasks to the end of the list. +project and @context words in \
+            a description are picked up as the task's projects and contexts. With \
+            --from, every non-empty line of the file becomes a task, except lines \
+            starting with #; --stdin does the same with standard input, and its \
+            lines are never run as commands. A task whose description matches an open one, \
+            ignoring case, spacing and trailing punctuation, gets a warning \
+            and a question whether to add it anyway; without a terminal to \
+            ask on it is added, or left out with --no-duplicates. \
+            @name followed by key=value words, or by nothing, adds the task \
+            made from that snippet.",
+        examples: &[
+            "add Buy milk",
+            "add Call mum; Book dentist",
+            "add @pr num=482 repo=todo-cli",
+            "add --from plan.txt",
+            "add --from plan.txt --no-duplicates",
+            "add --stdin",
+        ],
+    },
+    CommandSpec {
+        name: "list",
+        aliases: &["ls", "l"],
+        usage: &[
+            (
+                "list [filters] [--json]",
+                "List tasks matching every filter given:",
+            ),
+            (
+                "     <status> | blocked",
+                "status, or tasks waiting on others",
+            ),
+            ("     all", "waiting tasks too, hidden otherwise"),
+            ("     tag:<name>", "tasks with that tag"),
+            ("     waiting-on:<person>", "tasks delegated to them"),
+            (
+                "     +project | @context",
+                "tasks with that project or context",
+            ),
+            ("     <name>", "a filter kept with filter save"),
+            ("     <word>", "description contains the word"),
+            ("     [--limit <n>]", "Only the first n tasks"),
+            ("     [--last <n>]", "Only the last n tasks"),
+            ("     [--recent]", "Most recently changed first"),
+            (
+                "     [--stale <age>]",
+                "Only tasks unchanged for 14d, 2w, ...",
+            ),
+            ("     [--by-due]", "Earliest due first, undated last"),
+            ("     [--absolute]", "Due dates as dates, not \"due in 3d\""),
+            ("     [--grouped]", "A section per status, with counts"),
+            ("list archived [--json]", "Show archived tasks"),
+        ],
+        description: "Shows the tasks with their subtasks. Filters can be combined and a \
+            task has to match all of them. Waiting tasks are left out unless `all` or \
+            `waiting` is given. Any change to a task, from its status to a tag, counts \
+            for --recent and --stale. Due dates read as how far off they are, such \
+            as \"due tomorrow\" or \"2d overdue\", unless --absolute is given or they \
+            are more than two months away. --grouped splits the tasks into a section \
+            per status, in progress first, keeping their numbers. Long lists are shown a page at a time; \
+            `more` shows the next page. --json prints the tasks as JSON instead.",
+        examples: &[
+            "list",
+            "list todo tag:work",
+            "list +garden --limit 5",
+            "list all",
+            "list --stale 14d",
+            "list --by-due --absolute",
+            "list --grouped",
+        ],
+    },
+    CommandSpec {
+        name: "search",
+        aliases: &["grep"],
+        usage: &[(
+            "search <text> [--json]",
+            "List tasks containing the text, marked",
+        )],
+        description: "Lists the tasks whose description contains every word given, or a \
+            \"quoted phrase\" as a whole, with the matches marked.",
+        examples: &["search milk", "grep dentist --json"],
+    },
+    CommandSpec {
+        name: "fuzzy",
+        aliases: &[],
+        usage: &[(
+            "fuzzy <text>",
+            "List the tasks closest to the text, typos and all",
+        )],
+        description: "Ranks every task by how closely its description matches the text, \
+            ignoring case and punctuation, and lists the best ten. Words may be \
+            misspelled, cut short or missing letters. Better matches come first, \
+            then shorter descriptions; --verbose adds each task's score, out of 100.",
+        examples: &["fuzzy dentsit", "fuzzy grocer list"],
+    },
+    CommandSpec {
+        name: "show",
+        aliases: &[],
+        usage: &[("show <num> [--json]", "Show the details of one task")],
+        description: "Shows everything about one task: status, dates, tags, projects, \
+            contexts, time tracked, dependencies and subtasks.",
+        examples: &["show 3", "show 3.1", "show \"groceries\" --json"],
+    },
+    CommandSpec {
+        name: "more",
+        aliases: &["m"],
+        usage: &[("more", "Show the next page of the last list")],
+        description: "Continues the last `list` where its page ended.",
+        examples: &["more"],
+    },
+    CommandSpec {
+        name: "next",
+        aliases: &[],
+        usage: &[(
+            "next [count]",
+            "Suggest what to work on next (or the top few)",
+        )],
+        description: "Picks from the open tasks not waiting on others: tasks in progress \
+            first, then the soonest due, then the oldest. Offers to start the best \
+            pick.",
+        examples: &["next", "next 3"],
+    },
+    CommandSpec {
+        name: "random",
+        aliases: &[],
+        usage: &[(
+            "random [<filters>]",
+            "Pick an open task at random (filters as in list)",
+        )],
+        description: "Picks one task that is not done or cancelled, each as likely as the \
+            next, and offers to start it. Takes the same filters as `list`. Asking again \
+            picks a different task whenever there is more than one to choose from.",
+        examples: &["random", "random todo", "random tag:home +garden"],
+    },
+    CommandSpec {
+        name: "pick",
+        aliases: &[],
+        usage: &[("pick [<filters>]", "Choose a task from a menu and show it")],
+        description: "Lists the tasks matching the filters, as in `list`, and asks for the \
+            number of one. Any command that takes a task number takes `--pick` in its \
+            place; where the task is the last argument, filters may follow it. An empty \
+            answer or Escape cancels without changes.",
+        examples: &[
+            "pick todo +work",
+            "done --pick todo",
+            "remove --pick",
+            "tag --pick urgent",
+        ],
+    },
+    CommandSpec {
+        name: "open",
+        aliases: &[],
+        usage: &[(
+            "open <num> [n]",
+            "Open the first (or nth) link of a task in the browser",
+        )],
+        description: "Opens a link found in the task's description with the system's \
+            browser. Tasks with links are marked in listings.",
+        examples: &["open 4", "open 4 2"],
+    },
+    CommandSpec {
+        name: "copy",
+        aliases: &[],
+        usage: &[
+            (
+                "copy <num> [--json]",
+                "Copy a task's description to the clipboard",
+            ),
+            ("copy list", "Copy the whole list as plain text"),
+        ],
+        description: "Puts a task's description on the system clipboard, or with --json \
+            the whole task as `show --json` prints it. Uses pbcopy on macOS, clip on \
+            Windows, and wl-copy, xclip or xsel on Linux.",
+        examples: &["copy 3", "copy \"groceries\" --json", "copy list"],
+    },
+    CommandSpec {
+        name: "stats",
+        aliases: &[],
+        usage: &[("stats", "Show task counts and completion statistics")],
+        description: "Counts the tasks by status, shows how many were completed today and \
+            this week, the task open the longest and the current streak of days with \
+            something done.",
+        examples: &["stats"],
+    },
+    CommandSpec {
+        name: "count",
+        aliases: &[],
+        usage: &[
+            ("count", "Print how many tasks there are"),
+            (
+                "count <status>",
+                "  ...with a status: todo, in-progress, done, ...",
+            ),
+            ("count overdue", "  ...past their due date, or due-today"),
+        ],
+        description: "Counts tasks for a shell prompt or a status bar. Given as a command \
+            on the command line, or with --quiet, it prints the bare number and nothing \
+            else; in the interactive session it says what was counted. Overdue and \
+            due-today only count tasks still to do.",
+        examples: &["count", "count todo", "count overdue", "count due-today"],
+    },
+    CommandSpec {
+        name: "projects",
+        aliases: &[],
+        usage: &[("projects", "Show every +project with task counts")],
+        description: "Lists the +project words used in descriptions, with how many tasks \
+            carry each.",
+        examples: &["projects", "list +garden"],
+    },
+    CommandSpec {
+        name: "contexts",
+        aliases: &[],
+        usage: &[("contexts", "Show every @context with task counts")],
+        description: "Lists the @context words used in descriptions, with how many tasks \
+            carry each.",
+        examples: &["contexts", "list @phone"],
+    },
+    CommandSpec {
+        name: "week",
+        aliases: &["report", "w"],
+        usage: &[
+            ("week", "Summarize the last 7 days"),
+            (
+                "report md <path> [--force]",
+                "Write a Markdown status report to a file",
+            ),
+        ],
+        description: "Shows how many tasks were added and completed on each of the last \
+            seven days, and how many of the new ones are still open. `report md` writes \
+            the numbers of `stats`, what was completed since yesterday and the tasks by \
+            status to a Markdown file for a standup; an existing file is only replaced \
+            when you confirm or pass --force.",
+        examples: &["week", "report md standup.md"],
+    },
+    CommandSpec {
+        name: "chart",
+        aliases: &[],
+        usage: &[("chart", "Bar chart of tasks completed per day")],
+        description: "Draws a bar for each of the last 14 days, as long as the number of \
+            tasks completed that day.",
+        examples: &["chart"],
+    },
+    CommandSpec {
+        name: "review",
+        aliases: &[],
+        usage: &[("review", "Go through open tasks one by one")],
+        description: "Shows each open task in turn and asks what to do with it: mark it \
+            done, skip it, remove it, postpone its due date or edit its description. \
+            Needs an interactive terminal.",
+        examples: &["review"],
+    },
+    CommandSpec {
+        name: "due",
+        aliases: &[],
+        usage: &[
+            ("due <num> <date|none>", "Set or clear a task's due date"),
+            (
+                "     <date>",
+                "2025-06-01, today, tomorrow, friday, next week, in 3 days",
+            ),
+            ("     [<time>]", "At a time of day: 14:30, 9am, 9:15pm"),
+        ],
+        description: "Gives a task a due date, or takes it away with `none`. Tasks due \
+            soon or overdue stand out in listings and show up in `today` and `overdue`. \
+            A time after the date, in local time, orders the day's tasks; those due \
+            on a day without one come after its timed tasks. A time alone is today.",
+        examples: &[
+            "due 2 friday",
+            "due 2 2025-06-01",
+            "due 2 \"tomorrow 9am\"",
+            "due 2 2025-07-01 14:30",
+            "due 2 none",
+        ],
+    },
+    CommandSpec {
+        name: "repeat",
+        aliases: &[],
+        usage: &[(
+            "repeat <num> <interval>",
+            "Repeat a task when done (every 3d, weekly, none)",
+        )],
+        description: "Makes a task come back: when it is marked done, a fresh copy is added \
+            with its due date moved on by the interval. `none` stops it repeating.",
+        examples: &["repeat 5 weekly", "repeat 5 every 3d", "repeat 5 none"],
+    },
+    CommandSpec {
+        name: "snooze",
+        aliases: &["defer"],
+        usage: &[(
+            "snooze <num> <duration>",
+            "Push a due date back (2d, 1w, 12h)",
+        )],
+        description: "Moves a task's due date and time later by the duration; a due date \
+            without a time moves by whole days. A task without a due date is given one that \
+            far from now.",
+        examples: &["snooze 2 2d", "defer 2 1w"],
+    },
+    CommandSpec {
+        name: "estimate",
+        aliases: &[],
+        usage: &[
+            ("estimate <num> <time>", "Set how long a task should take"),
+            ("     <time>", "45m, 2h, 1h30m, none"),
+        ],
+        description: "Records how long a task is expected to take, as 45m, 2h or 1h30m, \
+            or takes it away with `none`. A word like ~90m in a description does the same. \
+            `stats` adds the estimates up, and filtered listings show what is left.",
+        examples: &["estimate 3 2h", "estimate 3 1h30m", "estimate 3 none"],
+    },
+    CommandSpec {
+        name: "escalate",
+        aliases: &[],
+        usage: &[(
+            "escalate [days]",
+            "Raise the priority of tasks left in todo too long",
+        )],
+        description: "Raises the priority of every task that has been in todo for more than \
+            the days given, or those of --escalate-after, by one level. A raised task is \
+            not raised again until that many more days go by.",
+        examples: &["escalate", "escalate 30"],
+    },
+    CommandSpec {
+        name: "track",
+        aliases: &[],
+        usage: &[("track <num>", "Start timing a task (stops any other timer)")],
+        description: "Starts a timer on a task; the time adds up across sessions and is \
+            shown by `show`. Only one task is timed at once.",
+        examples: &["track 3", "stop"],
+    },
+    CommandSpec {
+        name: "stop",
+        aliases: &[],
+        usage: &[("stop", "Stop the running timer")],
+        description: "Stops the timer started by `track` and adds the time to its task.",
+        examples: &["stop"],
+    },
+    CommandSpec {
+        name: "pomodoro",
+        aliases: &[],
+        usage: &[(
+            "pomodoro <num> [min]",
+            "Focus on a task for 25 (or min) minutes",
+        )],
+        description: "Counts down a focus session on a task and logs the time spent on it. \
+            A todo task is put in progress, and at the end you are asked whether it \
+            is done.",
+        examples: &["pomodoro 3", "pomodoro 3 50"],
+    },
+    CommandSpec {
+        name: "today",
+        aliases: &[],
+        usage: &[("today", "Open tasks due today or earlier")],
+        description: "Lists the tasks still to do that are due today or overdue.",
+        examples: &["today"],
+    },
+    CommandSpec {
+        name: "overdue",
+        aliases: &[],
+        usage: &[("overdue", "Open tasks past their due date")],
+        description: "Lists the tasks still to do whose due date has passed.",
+        examples: &["overdue"],
+    },
+    CommandSpec {
+        name: "agenda",
+        aliases: &[],
+        usage: &[
+            ("agenda", "Open tasks due over the next 7 days, day by day"),
+            ("agenda <days>", "The same over another number of days"),
+            ("agenda --empty", "Show days with nothing due as \"—\""),
+        ],
+        description: "Lays out the coming days from today, each with the open tasks due \
+            on it. Overdue tasks come first, whatever their date; tasks due later \
+            follow the last day, and those without a date are only counted.",
+        examples: &["agenda", "agenda 14", "agenda --empty"],
+    },
+    CommandSpec {
+        name: "update",
+        aliases: &["status", "u"],
+        usage: &[
+            (
+                "update <num> <status>",
+                "Update task status (todo/waiting/in-progress/done/cancelled)",
+            ),
+            (
+                "update all <status>",
+                "Give every task a status (update todo done: only todo)",
+            ),
+            ("     [--force]", "Even past --strict-transitions"),
+        ],
+        description: "Changes the status of a task, or of every task. With two statuses \
+            after `all`, only tasks with the first one are changed to the second. \
+            Under --strict-transitions a status moves one step at a time along todo, \
+            in-progress and done, either way, and open tasks can also be parked as \
+            waiting or cancelled; --force allows any change.",
+        examples: &[
+            "update 3 in-progress",
+            "update \"call mom\" done",
+            "update all todo done",
+        ],
+    },
+    CommandSpec {
+        name: "wait",
+        aliases: &["someday"],
+        usage: &[(
+            "wait <num> [reason]",
+            "Park a task as waiting, saying on what",
+        )],
+        description: "Sets a task aside until it can be acted on. Waiting tasks stay out \
+            of `list` and `next` until they move on; `list all` or `list waiting` shows \
+            them, and `show` tells what a task is waiting on. Any other status clears \
+            the reason.",
+        examples: &["wait 4", "wait 4 reply from Sam", "someday 7"],
+    },
+    CommandSpec {
+        name: "delegate",
+        aliases: &[],
+        usage: &[
+            (
+                "delegate <num> <person>",
+                "Hand a task to someone and wait on them",
+            ),
+            ("delegate <num> --clear", "Take it back"),
+        ],
+        description: "Notes who a task is waiting on and parks it as waiting, counting from \
+            now. Names are matched ignoring case and may start with @. --clear forgets \
+            the person but leaves the status alone; completing the task clears it \
+            too. `list waiting-on:<person>` shows what one person has, and `waiting` \
+            all of them.",
+        examples: &["delegate 4 alice", "delegate 4 @Sam", "delegate 4 --clear"],
+    },
+    CommandSpec {
+        name: "waiting",
+        aliases: &[],
+        usage: &[(
+            "waiting [--json]",
+            "Delegated tasks by person, longest wait first",
+        )],
+        description: "Lists the open tasks handed to someone with `delegate`, a section \
+            per person in alphabetical order, with how long each has been waiting \
+            since it was handed over. --json prints the same as JSON.",
+        examples: &["waiting", "waiting --json"],
+    },
+    CommandSpec {
+        name: "done",
+        aliases: &["d"],
+        usage: &[
+            ("done <num>...", "Mark one or more tasks done"),
+            ("     [--force]", "Even past --strict-transitions"),
+        ],
+        description: "Marks the tasks done. A repeating task comes back with its next due \
+            date. Under --strict-transitions a task has to be in progress first; \
+            --force skips that check.",
+        examples: &["done 3", "done 1 4 5", "done 2.1", "done --pick todo"],
+    },
+    CommandSpec {
+        name: "toggle",
+        aliases: &[],
+        usage: &[
+            (
+                "toggle <num>...",
+                "Mark tasks done, or reopen them if they are done",
+            ),
+            ("     [--force]", "Even past --strict-transitions"),
+        ],
+        description: "Flips each task between done and todo, which --strict-transitions \
+            only allows with --force.",
+        examples: &["toggle 3", "toggle 1 2"],
+    },
+    CommandSpec {
+        name: "reopen",
+        aliases: &["undone", "uncomplete"],
+        usage: &[
+            ("reopen <num>", "Take a done task back to todo"),
+            ("reopen last", "Reopen the task completed last"),
+            ("reopen", "Show the latest completed tasks"),
+        ],
+        description: "Takes a task marked done by mistake back to todo, even with \
+            --strict-transitions. `reopen last` goes by completion times, so the \
+            task done last is reopened without looking up its number; on its own, \
+            reopen lists the latest completed tasks, and --pick offers only \
+            completed ones. A recurring task adds no new occurrence, as the one its \
+            completion added is still there.",
+        examples: &["reopen 3", "reopen last", "undone 2.1"],
+    },
+    CommandSpec {
+        name: "sub",
+        aliases: &[],
+        usage: &[(
+            "sub <num> <description>",
+            "Add a subtask (address it later as 3.1)",
+        )],
+        description: "Adds a subtask under a task. Subtasks are numbered after their task, \
+            3.1, 3.2 and so on, and take those numbers wherever a task number goes.",
+        examples: &["sub 3 Buy paint", "done 3.1"],
+    },
+    CommandSpec {
+        name: "tag",
+        aliases: &[],
+        usage: &[("tag <num> <tag>...", "Tag a task")],
+        description: "Adds tags to a task. Tags are lowercase and shown as #tag; list a \
+            tag's tasks with `list tag:<name>`.",
+        examples: &["tag 3 work", "tag 3 urgent home", "list tag:work"],
+    },
+    CommandSpec {
+        name: "untag",
+        aliases: &[],
+        usage: &[("untag <num> <tag>...", "Remove tags from a task")],
+        description: "Takes tags off a task.",
+        examples: &["untag 3 urgent"],
+    },
+    CommandSpec {
+        name: "mark",
+        aliases: &[],
+        usage: &[
+            (
+                "mark <num> <marker> [<color>]",
+                "Show a word or emoji beside a task's status",
+            ),
+            ("mark <num> <color>", "Draw a task's row in a color"),
+            ("mark <num> --clear", "Take the marker and color away"),
+        ],
+        description: "Makes a task stand out in `list`. The marker is one word or emoji \
+            shown next to the status; the color replaces the status color of the row, \
+            and is left out in plain mode. Each part set stays until it is replaced \
+            or cleared.",
+        examples: &[
+            "mark 3 🔥",
+            "mark 3 red",
+            "mark 3 VIP magenta",
+            "mark 3 --clear",
+        ],
+    },
+    CommandSpec {
+        name: "append",
+        aliases: &[],
+        usage: &[(
+            "append <num> <text>",
+            "Add text to the end of a task's description",
+        )],
+        description: "Adds words after a task's description, picking up any +project and \
+            @context words in them.",
+        examples: &["append 2 before noon", "append 2 @phone"],
+    },
+    CommandSpec {
+        name: "prepend",
+        aliases: &[],
+        usage: &[(
+            "prepend <num> <text>",
+            "Add text to the start of a task's description",
+        )],
+        description: "Adds words before a task's description, picking up any +project and \
+            @context words in them.",
+        examples: &["prepend 2 URGENT:"],
+    },
+    CommandSpec {
+        name: "blocks",
+        aliases: &[],
+        usage: &[(
+            "blocks <num> on <num>",
+            "Mark a task as waiting on another one",
+        )],
+        description: "Makes the first task wait until the second is done. Blocked tasks \
+            are marked in listings, left out of `next` and shown by `list blocked`.",
+        examples: &["blocks 4 on 2", "list blocked"],
+    },
+    CommandSpec {
+        name: "remove",
+        aliases: &["delete", "rm"],
+        usage: &[(
+            "remove <num> [--yes]",
+            "Remove a task (or subtask, e.g. 3.1)",
+        )],
+        description: "Moves a task to the trash after asking; --yes skips the question. \
+            `restore` brings it back.",
+        examples: &["remove 2", "rm 3.1 --yes", "remove \"old idea\""],
+    },
+    CommandSpec {
+        name: "clear",
+        aliases: &[],
+        usage: &[
+            ("clear", "Remove completed and cancelled tasks"),
+            (
+                "clear <status>",
+                "Remove tasks with one status (todo, done, ...)",
+            ),
+            ("clear all", "Remove every task and start fresh"),
+            (
+                "clear [<status>|all] --dry-run",
+                "List what would be removed, changing nothing",
+            ),
+        ],
+        description: "Moves many tasks to the trash at once, after asking; --yes skips \
+            the question. --dry-run only lists the tasks it would take.",
+        examples: &[
+            "clear",
+            "clear cancelled",
+            "clear all --yes",
+            "clear --dry-run",
+        ],
+    },
+    CommandSpec {
+        name: "dedupe",
+        aliases: &[],
+        usage: &[(
+            "dedupe [--dry-run]",
+            "Merge tasks with the same description",
+        )],
+        description: "Finds tasks whose descriptions are the same apart from case and \
+            spacing, and keeps only the one furthest along in each group. --dry-run \
+            only shows what would be merged.",
+        examples: &["dedupe --dry-run", "dedupe"],
+    },
+    CommandSpec {
+        name: "doctor",
+        aliases: &[],
+        usage: &[(
+            "doctor [--fix]",
+            "Check the list for broken tasks, and repair them",
+        )],
+        description: "Looks for duplicate IDs, empty descriptions, tasks completed \
+            before they were created and dependencies on missing tasks, which \
+            hand edits or old versions can leave behind. --fix repairs all but \
+            the times, which need fixing in the file.",
+        examples: &["doctor", "doctor --fix"],
+    },
+    CommandSpec {
+        name: "renumber",
+        aliases: &[],
+        usage: &[(
+            "renumber [--yes]",
+            "Give the tasks IDs 1 to n in list order",
+        )],
+        description: "Shows which task IDs would change and asks before changing them; \
+            dependencies follow their tasks. Task numbers, the archive and the trash \
+            are left as they are. Duplicate IDs or dependencies on missing tasks \
+            need `doctor --fix` first.",
+        examples: &["renumber", "renumber --yes"],
+    },
+    CommandSpec {
+        name: "archive",
+        aliases: &[],
+        usage: &[
+            ("archive", "Move completed tasks to the archive file"),
+            (
+                "archive stats [--json]",
+                "Count the archived tasks without listing them",
+            ),
+        ],
+        description: "Takes the completed tasks out of the list and keeps them in the \
+            archive file, where `list archived` shows them. The archive is only \
+            read by the commands that need it; `archive stats` counts it without \
+            holding it all in memory.",
+        examples: &["archive", "list archived", "archive stats"],
+    },
+    CommandSpec {
+        name: "trash",
+        aliases: &[],
+        usage: &[
+            ("trash [--json]", "Show removed tasks"),
+            ("trash empty [--yes]", "Delete removed tasks for good"),
+        ],
+        description: "Lists the removed tasks, newest last; only the most recent are kept. `trash empty` deletes them for \
+            good after asking; --yes skips the question.",
+        examples: &["trash", "trash empty"],
+    },
+    CommandSpec {
+        name: "restore",
+        aliases: &[],
+        usage: &[("restore <num>", "Bring a task back from the trash")],
+        description: "Moves a task from the trash back to the end of the list. The number \
+            is the one `trash` shows.",
+        examples: &["trash", "restore 1"],
+    },
+    CommandSpec {
+        name: "history",
+        aliases: &[],
+        usage: &[
+            ("history [<count>]", "Show recent changes to the list"),
+            ("history clear [--yes]", "Forget the recorded changes"),
+        ],
+        description: "Shows the latest changes made to the list, newest first.",
+        examples: &["history", "history 50", "history clear"],
+    },
+    CommandSpec {
+        name: "template",
+        aliases: &["templates"],
+        usage: &[
+            (
+                "template save <name> <num>...",
+                "Keep the descriptions of these tasks as a template",
+            ),
+            (
+                "template apply <name>",
+                "Add fresh copies of a template's tasks",
+            ),
+            ("template list", "Show saved templates"),
+            ("template delete <name>", "Forget a template"),
+        ],
+        description: "Saves a set of tasks to add again later, such as a packing list. \
+            Names with spaces need quotes.",
+        examples: &[
+            "template save trip 3 4 5",
+            "template apply trip",
+            "template delete \"old trip\"",
+        ],
+    },
+    CommandSpec {
+        name: "filter",
+        aliases: &["filters"],
+        usage: &[
+            (
+                "filter save <name> <filter>",
+                "Keep words for list under a name",
+            ),
+            ("filter list", "Show saved filters"),
+            ("filter delete <name>", "Forget a saved filter"),
+        ],
+        description: "Saves a filter to list by name: after filter save urgent \
+            \"in-progress tag:work\", `list urgent` lists what `list in-progress \
+            tag:work` would, and says so. The filter is read again each time it is \
+            used, and can be added to, as in `list urgent --recent`. Statuses and \
+            list's own words, such as all and blocked, come first, so they can't be \
+            names.",
+        examples: &[
+            "filter save urgent \"in-progress tag:work\"",
+            "list urgent --recent",
+            "filter delete urgent",
+        ],
+    },
+    CommandSpec {
+        name: "snippet",
+        aliases: &["snippets"],
+        usage: &[
+            (
+                "snippet save <name> <text>",
+                "Keep a description with {placeholders}",
+            ),
+            ("snippet list", "Show saved snippets"),
+            ("snippet delete <name>", "Forget a snippet"),
+        ],
+        description: "Saves a task description with {placeholders} in it under a \
+            name, for `add @name key=value ...` to fill in and add as one task. \
+            Every placeholder needs a value, and a value for a placeholder the \
+            snippet doesn't have is an error rather than left out. {{ and }} are \
+            literal braces. A value runs until the next key=value word, so it can \
+            have spaces.",
+        examples: &[
+            "snippet save pr \"Review PR #{num} for {repo}\"",
+            "add @pr num=482 repo=todo-cli",
+            "snippet delete pr",
+        ],
+    },
+    CommandSpec {
+        name: "run",
+        aliases: &[],
+        usage: &[(
+            "run <file> [--continue-on-error]",
+            "Run the commands in a file, one per line",
+        )],
+        description: "Runs each line of a file as a command, showing the line before its \
+            output. Blank lines and lines starting with # are skipped. The run stops at \
+            the first command that fails unless --continue-on-error is given, and ends \
+            with how many lines ran and how many of them failed. A script can run \
+            another one, up to 8 deep.",
+        examples: &["run setup.todo", "run chores.todo --continue-on-error"],
+    },
+    CommandSpec {
+        name: "save",
+        aliases: &[],
+        usage: &[
+            ("save", "Save tasks to file"),
+            ("     [--force]", "Even over changes made to it elsewhere"),
+        ],
+        description: "Writes the tasks to the data file now. They are also saved on exit. \
+            When the file was changed by someone else since it was read, saving asks \
+            whether to merge those changes in first, replace them or leave the file \
+            alone; without a terminal to ask, the file is left alone. --force replaces \
+            them without asking.",
+        examples: &["save", "save --force"],
+    },
+    CommandSpec {
+        name: "reload",
+        aliases: &[],
+        usage: &[("reload [--yes]", "Read the tasks from file again")],
+        description: "Reads the data file again, dropping changes not saved yet. The file \
+            is also checked between commands: changes made elsewhere, such as by a \
+            sync client, are loaded by themselves when there is nothing unsaved, and \
+            otherwise you choose to reload, keep your list or merge the two.",
+        examples: &["reload", "reload --yes"],
+    },
+    CommandSpec {
+        name: "merge",
+        aliases: &[],
+        usage: &[(
+            "merge <path> [--ours|--theirs]",
+            "Combine another copy of the list with this one",
+        )],
+        description: "Brings in the tasks of another copy of the list, such as one edited \
+            on another machine. Tasks in only one copy are kept. Tasks in both, found by \
+            ID or else by description, take the status furthest along and the newer \
+            dates. Where both copies changed a description, you choose which to keep, \
+            or --ours and --theirs choose for every one.",
+        examples: &["merge laptop/tasks.json", "merge backup.json --theirs"],
+    },
+    CommandSpec {
+        name: "import",
+        aliases: &[],
+        usage: &[(
+            "import github <path>",
+            "Add or update tasks from a GitHub issues export",
+        )],
+        description: "Reads the JSON written by `gh issue list --json \
+            number,title,state,labels`. Each issue becomes a task with its title \
+            as the description, open issues to do and closed ones done, and its \
+            labels as tags. Importing again updates the tasks from earlier \
+            imports instead of adding copies. Nothing changes if the file has \
+            an error.",
+        examples: &["import github issues.json"],
+    },
+    CommandSpec {
+        name: "sync",
+        aliases: &[],
+        usage: &[
+            (
+                "sync [--ours|--theirs]",
+                "Merge in the remote copy, then send it the result",
+            ),
+            ("sync pull [--ours|--theirs]", "Merge in the remote copy"),
+            ("sync push", "Replace the remote copy with this list"),
+            ("          [--force]", "Even one not pulled or pushed here"),
+        ],
+        description: "Keeps the list in step with a copy on a server, given by --sync-url \
+            when starting the program. Pulling merges as `merge` does. A push only \
+            replaces the copy last pulled or pushed in this session, so one changed by \
+            somebody else in between is left alone; --force replaces whatever is there.",
+        examples: &[
+            "sync",
+            "sync pull --theirs",
+            "sync push",
+            "sync push --force",
+        ],
+    },
+    CommandSpec {
+        name: "backup",
+        aliases: &[],
+        usage: &[
+            (
+                "backup create <path>",
+                "Write the tasks and the files beside them to one file",
+            ),
+            (
+                "backup restore <path> [--yes]",
+                "Put everything back as a backup has it",
+            ),
+        ],
+        description: "A backup holds the task list with its templates, saved filters and \
+            snippets, the archive, the trash and the history, in one file to keep or move \
+            to another machine. Restoring checks the whole backup before changing \
+            anything, and first backs up what is there to the task file with .pre-restore \
+            added to its name; --yes skips the question.",
+        examples: &[
+            "backup create ~/tasks-backup.json",
+            "backup restore tasks-backup.json",
+        ],
+    },
+    CommandSpec {
+        name: "version",
+        aliases: &[],
+        usage: &[("version", "Show the version and the data file in use")],
+        description: "Shows the name and version of the program and where the task list \
+            is kept. Also available as --version or -V when starting the program, \
+            which prints the same and exits without touching the task file.",
+        examples: &["version"],
+    },
+    CommandSpec {
+        name: "completions",
+        aliases: &[],
+        usage: &[
+            ("completions <shell>", "Print a shell completion script:"),
+            ("     bash|zsh|fish", "the shells it knows"),
+        ],
+        description: "Prints a script that completes commands, their aliases, statuses \
+            and flags in bash, zsh or fish, made from the same table as this help. \
+            Load it in the shell's startup file, e.g. \
+            source <(rust-todo-cli completions bash) in ~/.bashrc.",
+        examples: &["completions bash", "completions zsh", "completions fish"],
+    },
+    CommandSpec {
+        name: "help",
+        aliases: &["h"],
+        usage: &[
+            ("help", "Show this help message"),
+            ("help <command>", "Show the details of one command"),
+        ],
+        description: "Lists every command, or explains one of them with examples.",
+        examples: &["help", "help add"],
+    },
+    CommandSpec {
+        name: "exit",
+        aliases: &["quit", "q"],
+        usage: &[("exit", "Save and exit")],
+        description: "Saves the tasks and leaves the prompt.",
+        examples: &["exit"],
+    },
+];
+
+impl CommandSpec {
+    // The name followed by the aliases
+    pub(crate) fn words(&self) -> impl Iterator<Item = &'static str> {
+        std::iter::once(self.name).chain(self.aliases.iter().copied())
+    }
+
+    /// The ways of calling the command on one line, as usage errors show it
+    pub fn synopsis(&self) -> String {
+        let forms: Vec<&str> = self
+            .usage
+            .iter()
+            .map(|(form, _)| *form)
+            .filter(|form| !form.starts_with(' '))
+            .collect();
+        forms.join(" | ")
+    }
+}
+
+/// The command a word runs, by name or alias
+pub fn find_command(word: &str) -> Option<&'static CommandSpec> {
+    COMMANDS
+        .iter()
+        .find(|spec| spec.words().any(|known| known == word))
+}
+
+/// The command a typed word runs. A name or alias wins outright; otherwise
+/// the word may be the start of one command's name or alias. A start shared
+/// by several commands is ambiguous, and a word matching nothing is unknown,
+/// with suggestions.
+pub fn resolve_command(word: &str) -> Result<&'static CommandSpec, ParseError> {
+    if let Some(spec) = find_command(word) {
+        return Ok(spec);
+    }
+    let candidates: Vec<&'static CommandSpec> = COMMANDS
+        .iter()
+        .filter(|spec| spec.words().any(|known| known.starts_with(word)))
+        .collect();
+    match candidates.as_slice() {
+        [spec] => {
+            log!(Trace, "took {:?} as the start of {:?}", word, spec.name);
+            Ok(spec)
+        }
+        [] => Err(ParseError::UnknownCommand(
+            word.to_string(),
+            suggestions(word),
+        )),
+        _ => Err(ParseError::AmbiguousCommand(
+            word.to_string(),
+            candidates.iter().map(|spec| spec.name).collect(),
+        )),
+    }
+}
+
+/// Known commands close enough to an unknown word to be what was meant,
+/// closest first. Equally close commands are all returned, up to three;
+/// a close alias suggests the command it belongs to.
+pub fn suggestions(word: &str) -> Vec<&'static str> {
+    let limit = typo_limit(word);
+    let mut best: Option<usize> = None;
+    let mut names: Vec<&'static str> = Vec::new();
+    for spec in COMMANDS {
+        let distance = spec
+            .words()
+            // Any word is a few edits from a short form like `d`
+            .filter(|known| known.len() > limit)
+            .map(|known| edit_distance(word, known))
+            .min()
+            .unwrap_or(usize::MAX);
+        if distance == 0 || distance > limit {
+            continue;
+        }
+        match best {
+            Some(closest) if distance > closest => {}
+            Some(closest) if distance == closest => names.push(spec.name),
+            _ => {
+                best = Some(distance);
+                names = vec![spec.name];
+            }
+        }
+    }
+    names.truncate(3);
+    names
+}
+
+fn ambiguous_command(word: &str, candidates: &[&str]) -> String {
+    let quoted: Vec<String> = candidates
+        .iter()
+        .map(|name| format!("'{}'", name))
+        .collect();
+    let (last, rest) = quoted
+        .split_last()
+        .expect("an ambiguous word has candidates");
+    msg!(
+        "error.ambiguous_command",
+        word = word,
+        candidates = msg!("list.or", rest = rest.join(", "), last = last)
+    )
+}
+
+fn invalid_number(what: &str) -> String {
+    match what {
+        "trash" => msg!("error.invalid_trash_number"),
+        _ => msg!("error.invalid_task_number"),
+    }
+}
+
+fn unknown_command(word: &str, suggestions: &[&str]) -> String {
+    let quoted: Vec<String> = suggestions
+        .iter()
+        .map(|name| format!("'{}'", name))
+        .collect();
+    match quoted.as_slice() {
+        [] => msg!("error.unknown_command", word = word),
+        [only] => msg!(
+            "error.unknown_command_suggest",
+            word = word,
+            suggestions = only
+        ),
+        [rest @ .., last] => msg!(
+            "error.unknown_command_suggest",
+            word = word,
+            suggestions = msg!("list.or", rest = rest.join(", "), last = last)
+        ),
+    }
+}
+
+// A usage error showing every way of calling the named command
+fn usage(name: &str) -> ParseError {
+    let spec = find_command(name).expect("usage is only shown for known commands");
+    ParseError::Usage(spec.synopsis())
+}
+
+/// Everything the app can be asked to do, as parsed from one line of input,
+/// and the settled merges the CLI works out on its way to running `merge`
+/// and `sync`. Task numbers in selectors and indices are 1-based, as typed
+/// by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Save and leave the interactive session
+    Exit,
+    /// Show the list of commands, or the details of one
+    Help(Option<&'static CommandSpec>),
+    /// Show the program's version and data file
+    Version,
+    /// Print a completion script for a shell
+    Completions(Shell),
+    /// Show the tasks matching a filter
+    List {
+        filter: ListFilter,
+        limit: Option<Limit>,
+        format: OutputFormat,
+    },
+    /// Show the tasks closest to a text, best first
+    Fuzzy(String),
+    /// Show every detail of one task
+    Show(Selector, OutputFormat),
+    /// Pick an open task matching the filter at random
+    Random(ListFilter),
+    /// Task and which of its links to open, counting from 1
+    Open(Selector, usize),
+    /// Put a task on the clipboard, its description or as JSON
+    Copy(Selector, OutputFormat),
+    /// Put the plain listing on the clipboard
+    CopyList,
+    /// Show the next page of the last listing
+    More,
+    /// How many suggestions to show
+    Next(usize),
+    /// Count tasks by status
+    Stats,
+    /// Print one count, of every task without a target
+    Count(Option<CountTarget>),
+    /// Count open tasks by +project
+    Projects,
+    /// Count open tasks by @context
+    Contexts,
+    /// Tasks due today or overdue
+    Today,
+    /// Tasks past their due date
+    Overdue,
+    /// Open tasks by due day over this many days; --empty shows days with
+    /// nothing due
+    Agenda(usize, bool),
+    /// Set or clear a due date, with the time of day if one was given
+    Due(Selector, Option<(Date, Option<Time>)>),
+    /// Set or clear how often a task comes back
+    Repeat(Selector, Option<Recurrence>),
+    /// Push a due date back by a duration
+    Snooze(Selector, Duration),
+    /// Set or clear how long a task should take
+    Estimate(Selector, Option<Estimate>),
+    /// Park a task as Waiting, with why
+    Wait(Selector, Option<String>),
+    /// Set or clear who a task waits on
+    Delegate(Selector, Option<String>),
+    /// Delegated tasks grouped by person
+    Waiting(OutputFormat),
+    /// Raise stale tasks one priority level, after the days given or those
+    /// of --escalate-after
+    Escalate(Option<u64>),
+    /// Start the timer on a task
+    Track(Selector),
+    /// Task and session length in minutes
+    Pomodoro(Selector, u64),
+    /// Walk through the open tasks one by one
+    Review,
+    /// Report on the last seven days
+    Week,
+    /// Write a Markdown status report to a file, replacing one that is
+    /// there without asking when the flag is set
+    Report(String, bool),
+    /// Draw completions per day
+    Chart,
+    /// Stop the running timer
+    Stop,
+    /// One description per task; `add a; b` gives two
+    Add(Vec<String>, bool),
+    /// Add one task per line of a file
+    AddFrom(String, bool),
+    /// Add one task per line of standard input; the flag is --no-duplicates
+    AddStdin(bool),
+    /// Snippet name and its placeholders' values, as typed, and
+    /// --no-duplicates
+    AddSnippet(String, Vec<(String, String)>, bool),
+    /// Task and its new status, as typed; the flag is --force, which gets
+    /// past strict transitions
+    Update(Selector, String, bool),
+    /// Tasks with this status, or all of them, and their new status, and
+    /// whether it is forced past strict transitions
+    UpdateAll(Option<Status>, Status, bool),
+    /// The flag is set by --yes and skips the confirmation
+    Remove(Selector, bool),
+    /// Complete each of the tasks, forced past strict transitions or not
+    Done(Vec<Selector>, bool),
+    /// Flip each task between done and not done, forced or not
+    Toggle(Vec<Selector>, bool),
+    /// Take a completed task back to todo
+    Reopen(Selector),
+    /// Reopen the task completed last
+    ReopenLast,
+    /// The completed tasks, the latest first
+    RecentlyCompleted,
+    /// Parent task and the description of its new subtask
+    Sub(Selector, String),
+    /// Add tags to a task
+    Tag(Selector, Vec<String>),
+    /// Take tags off a task
+    Untag(Selector, Vec<String>),
+    /// Set what is given of a task's marker and color, or clear both
+    Mark(Selector, Option<Marker>),
+    /// Add text to the end of a description
+    Append(Selector, String),
+    /// Add text to the start of a description
+    Prepend(Selector, String),
+    /// Task numbers: the first waits on the second
+    Blocks(usize, usize),
+    /// Closed tasks to clear, or only those with this status, --yes and
+    /// --dry-run
+    Clear(Option<ClearTarget>, bool, bool),
+    /// Move completed tasks to the archive
+    Archive,
+    /// The flag is set by --dry-run
+    Dedupe(bool),
+    /// Check the list for broken tasks; the flag is set by --fix
+    Doctor(bool),
+    /// Compact the task IDs; the flag is set by --yes and skips the
+    /// confirmation
+    Renumber(bool),
+    /// Show the archive
+    Archived(OutputFormat),
+    /// Count the archived tasks
+    ArchiveStats(OutputFormat),
+    /// Show the trash
+    Trash(OutputFormat),
+    /// The flag is set by --yes and skips the confirmation
+    EmptyTrash(bool),
+    /// Number of the task in the trash to bring back
+    Restore(usize),
+    /// How many of the latest changes to show
+    History(Option<usize>),
+    /// The flag is set by --yes and skips the confirmation
+    ClearHistory(bool),
+    /// Script to run, and whether to go on after a line fails
+    Run(String, bool),
+    /// Template name and the numbers of the tasks to keep in it
+    TemplateSave(String, Vec<usize>),
+    /// Add a template's tasks to the list
+    TemplateApply(String),
+    /// Show the saved templates
+    TemplateList,
+    /// Forget a template
+    TemplateDelete(String),
+    /// Filter name and the filter as typed after it
+    FilterSave(String, String),
+    /// Show the saved filters
+    FilterList,
+    /// Forget a saved filter
+    FilterDelete(String),
+    /// Snippet name and its text, placeholders and all
+    SnippetSave(String, String),
+    /// Show the snippets
+    SnippetList,
+    /// Forget a snippet
+    SnippetDelete(String),
+    /// Write the tasks to disk, with --force over changes made to the file
+    /// since it was read
+    Save(bool),
+    /// Read the tasks from disk again, in the interactive session;
+    /// true skips the question about dropping unsaved changes
+    Reload(bool),
+    /// Combine the list with the copy in a file; the side given by
+    /// --ours or --theirs settles every conflict without asking
+    Merge(String, Option<Side>),
+    /// Take in a merge with every conflict settled, naming where the other
+    /// copy came from. Never parsed: `merge` and `sync` work it out first.
+    TakeMerge(Box<MergeReport>, String),
+    /// Add or update tasks from another tool's export
+    Import(Format, String),
+    /// Pull from and push to the remote copy set by --sync-url, with the
+    /// side that settles conflicts when pulling
+    Sync(SyncAction, Option<Side>),
+    /// Write every file the app keeps to a backup at the path
+    BackupCreate(String),
+    /// Replace those files with the backup at the path; the flag is set by
+    /// --yes and skips the confirmation
+    BackupRestore(String, bool),
+}
+
+/// One-shot cut of a listing: the first or the last few tasks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    First(usize),
+    Last(usize),
+}
+
+impl Limit {
+    /// Narrow rows down to the requested end of the list
+    pub fn apply<T>(self, rows: &mut Vec<T>) {
+        match self {
+            Limit::First(count) => rows.truncate(count),
+            Limit::Last(count) => {
+                let skip = rows.len().saturating_sub(count);
+                rows.drain(..skip);
+            }
+        }
+    }
+}
+
+/// Which way `sync` goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SyncAction {
+    /// Merge in the remote copy, then send the result back
+    Both,
+    Pull,
+    /// Send the list; the flag is --force, which replaces whatever copy the
+    /// remote holds
+    Push(bool),
+}
+
+/// What `clear` removes besides the default of closed tasks
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClearTarget {
+    All,
+    Status(Status),
+}
+
+/// What `count` counts instead of every task
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CountTarget {
+    Status(Status),
+    /// Open tasks past their due date
+    Overdue,
+    /// Open tasks due today
+    DueToday,
+}
+
+impl CountTarget {
+    /// A status word, `overdue` or `due-today`
+    pub fn parse(word: &str) -> Option<CountTarget> {
+        match word.to_lowercase().as_str() {
+            "overdue" => Some(CountTarget::Overdue),
+            "due-today" | "today" => Some(CountTarget::DueToday),
+            _ => Status::from_str(word).ok().map(CountTarget::Status),
+        }
+    }
+
+    /// Every target, as errors list them
+    pub fn names() -> String {
+        format!("{}, overdue, due-today", Status::names())
+    }
+}
+
+impl Command {
+    /// Whether running this command can change the task list
+    pub fn mutates(&self) -> bool {
+        matches!(
+            self,
+            Command::Add(..)
+                | Command::AddFrom(..)
+                | Command::AddStdin(_)
+                | Command::AddSnippet(..)
+                | Command::Update(..)
+                | Command::UpdateAll(..)
+                | Command::Remove(..)
+                | Command::Done(..)
+                | Command::Toggle(..)
+                | Command::Reopen(_)
+                | Command::ReopenLast
+                | Command::Sub(..)
+                | Command::Tag(..)
+                | Command::Untag(..)
+                | Command::Mark(..)
+                | Command::Append(..)
+                | Command::Prepend(..)
+                | Command::Blocks(..)
+                | Command::Clear(_, _, false)
+                | Command::Archive
+                | Command::Dedupe(false)
+                | Command::Doctor(true)
+                | Command::Renumber(_)
+                | Command::Restore(_)
+                | Command::Due(..)
+                | Command::Repeat(..)
+                | Command::Snooze(..)
+                | Command::Escalate(_)
+                | Command::Estimate(..)
+                | Command::Wait(..)
+                | Command::Delegate(..)
+                | Command::ClearHistory(_)
+                | Command::Track(_)
+                | Command::Stop
+                | Command::Pomodoro(..)
+                | Command::Review
+                | Command::Next(_)
+                | Command::Random(_)
+                | Command::TemplateSave(..)
+                | Command::TemplateApply(_)
+                | Command::Run(..)
+                | Command::TemplateDelete(_)
+                | Command::FilterSave(..)
+                | Command::FilterDelete(_)
+                | Command::SnippetSave(..)
+                | Command::SnippetDelete(_)
+                | Command::Merge(..)
+                | Command::TakeMerge(..)
+                | Command::Import(..)
+                | Command::Sync(SyncAction::Both | SyncAction::Pull, _)
+        )
+    }
+
+    /// Whether the command writes the task file or the files kept beside
+    /// it: every command that [`mutates`](Command::mutates) the list, and
+    /// `save`, `trash empty` and `backup restore`
+    pub fn writes(&self) -> bool {
+        self.mutates()
+            || matches!(
+                self,
+                Command::Save(_) | Command::EmptyTrash(_) | Command::BackupRestore(..)
+            )
+    }
+
+    /// Name of the command in [`COMMANDS`] that this is parsed from. A
+    /// `search` is a `list` filtered by text, so it is named `list`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::Exit => "exit",
+            Command::Help(_) => "help",
+            Command::Version => "version",
+            Command::Completions(_) => "completions",
+            Command::List { .. } | Command::Archived(_) => "list",
+            Command::Fuzzy(_) => "fuzzy",
+            Command::Show(..) => "show",
+            Command::Open(..) => "open",
+            Command::Copy(..) | Command::CopyList => "copy",
+            Command::More => "more",
+            Command::Next(_) => "next",
+            Command::Random(_) => "random",
+            Command::Stats => "stats",
+            Command::Count(_) => "count",
+            Command::Projects => "projects",
+            Command::Contexts => "contexts",
+            Command::Today => "today",
+            Command::Overdue => "overdue",
+            Command::Agenda(..) => "agenda",
+            Command::Due(..) => "due",
+            Command::Repeat(..) => "repeat",
+            Command::Snooze(..) => "snooze",
+            Command::Escalate(_) => "escalate",
+            Command::Estimate(..) => "estimate",
+            Command::Track(_) => "track",
+            Command::Pomodoro(..) => "pomodoro",
+            Command::Review => "review",
+            Command::Week | Command::Report(..) => "week",
+            Command::Chart => "chart",
+            Command::Stop => "stop",
+            Command::Add(..)
+            | Command::AddFrom(..)
+            | Command::AddStdin(_)
+            | Command::AddSnippet(..) => "add",
+            Command::Update(..) | Command::UpdateAll(..) => "update",
+            Command::Wait(..) => "wait",
+            Command::Delegate(..) => "delegate",
+            Command::Waiting(_) => "waiting",
+            Command::Remove(..) => "remove",
+            Command::Done(..) => "done",
+            Command::Toggle(..) => "toggle",
+            Command::Reopen(_) | Command::ReopenLast | Command::RecentlyCompleted => "reopen",
+            Command::Sub(..) => "sub",
+            Command::Tag(..) => "tag",
+            Command::Untag(..) => "untag",
+            Command::Mark(..) => "mark",
+            Command::Append(..) => "append",
+            Command::Prepend(..) => "prepend",
+            Command::Blocks(..) => "blocks",
+            Command::Clear(..) => "clear",
+            Command::Archive | Command::ArchiveStats(_) => "archive",
+            Command::Dedupe(_) => "dedupe",
+            Command::Renumber(_) => "renumber",
+            Command::Doctor(_) => "doctor",
+            Command::Trash(_) | Command::EmptyTrash(_) => "trash",
+            Command::Restore(_) => "restore",
+            Command::History(_) | Command::ClearHistory(_) => "history",
+            Command::TemplateSave(..)
+            | Command::TemplateApply(_)
+            | Command::TemplateList
+            | Command::TemplateDelete(_) => "template",
+            Command::FilterSave(..) | Command::FilterList | Command::FilterDelete(_) => "filter",
+            Command::SnippetSave(..) | Command::SnippetList | Command::SnippetDelete(_) => {
+                "snippet"
+            }
+            Command::Run(..) => "run",
+            Command::Save(_) => "save",
+            Command::Reload(_) => "reload",
+            Command::Merge(..) | Command::TakeMerge(..) => "merge",
+            Command::Import(..) => "import",
+            Command::Sync(..) => "sync",
+            Command::BackupCreate(_) | Command::BackupRestore(..) => "backup",
+        }
+    }
+}
+
+// Pull --ours or --theirs out of a command's arguments. Both at once is
+// no answer, so None.
+fn split_side<'a>(args: &[&'a str]) -> Option<(Vec<&'a str>, Option<Side>)> {
+    let side = match (args.contains(&"--ours"), args.contains(&"--theirs")) {
+        (true, true) => return None,
+        (true, false) => Some(Side::Ours),
+        (false, true) => Some(Side::Theirs),
+        (false, false) => None,
+    };
+    let rest = args
+        .iter()
+        .copied()
+        .filter(|arg| !matches!(*arg, "--ours" | "--theirs"))
+        .collect();
+    Some((rest, side))
+}
+
+// Pull the --json flag out of a command's arguments
+fn split_format<'a>(args: &[&'a str]) -> (Vec<&'a str>, OutputFormat) {
+    let format = if args.contains(&"--json") {
+        OutputFormat::Json
+    } else {
+        OutputFormat::Human
+    };
+    let rest = args
+        .iter()
+        .copied()
+        .filter(|arg| *arg != "--json")
+        .collect();
+    (rest, format)
+}
+
+// Narrow a filter by one word of `list` or `random`: `blocked`, a status,
+// `status:` or `tag:`, a +project or @context, or else text to look for
+fn add_filter_word(filter: &mut ListFilter, arg: &str, command: &str) -> Result<(), ParseError> {
+    match arg.split_once(':') {
+        _ if arg == "blocked" => filter.blocked = true,
+        _ if arg == "all" => filter.all = true,
+        Some(("status", value)) => filter.status = Some(Status::from_str(value)?),
+        Some(("tag", value)) => match normalize_tag(value) {
+            Some(tag) => filter.tags.push(tag),
+            None => return Err(usage(command)),
+        },
+        Some(("waiting-on", value)) => match normalize_person(value) {
+            Some(person) => filter.waiting_on = Some(person),
+            None => return Err(usage(command)),
+        },
+        Some((key, _)) => return Err(ParseError::UnknownFilter(key.to_string())),
+        None => match (Status::from_str(arg), Token::parse(arg)) {
+            (Ok(status), _) => filter.status = Some(status),
+            (_, Some(Token::Project(project))) => filter.projects.push(project),
+            (_, Some(Token::Context(context))) => filter.contexts.push(context),
+            _ => filter.names.push(arg.to_string()),
+        },
+    }
+    Ok(())
+}
+
+// Pull `--yes`/`-y` out of the arguments of a destructive command
+fn split_yes<'a>(args: &[&'a str]) -> (Vec<&'a str>, bool) {
+    let yes = args.iter().any(|arg| matches!(*arg, "--yes" | "-y"));
+    let rest = args
+        .iter()
+        .copied()
+        .filter(|arg| !matches!(*arg, "--yes" | "-y"))
+        .collect();
+    (rest, yes)
+}
+
+// Take a single word or a "quoted phrase" off the front of the arguments.
+// Returns the text without its quotes, whether it was quoted, and the rest.
+fn split_phrase<'a, 'b>(args: &'b [&'a str]) -> Option<(String, bool, &'b [&'a str])> {
+    let first = *args.first()?;
+    if !first.starts_with('"') {
+        return Some((first.to_string(), false, &args[1..]));
+    }
+    // An unclosed quote runs to the end of the input
+    let end = args
+        .iter()
+        .enumerate()
+        .position(|(i, arg)| arg.ends_with('"') && (i > 0 || arg.len() > 1))
+        .unwrap_or(args.len() - 1);
+    let phrase = args[..=end].join(" ");
+    let text = phrase.trim_matches('"').to_string();
+    Some((text, true, &args[end + 1..]))
+}
+
+// Take the task selector off the front of the arguments: a number, a single
+// word, a "quoted phrase" or --pick. Returns the selector and the arguments
+// after it.
+fn split_selector<'a, 'b>(args: &'b [&'a str]) -> Option<(Selector, &'b [&'a str])> {
+    let (text, quoted, rest) = split_phrase(args)?;
+    if quoted {
+        Some((Selector::Text(text), rest))
+    } else if text == "--pick" {
+        Some((Selector::Pick(ListFilter::default()), rest))
+    } else {
+        Some((Selector::parse(&text), rest))
+    }
+}
+
+// Narrow the menu of a --pick selector by the words after it, as in
+// `done --pick todo`. Other selectors are left as they are.
+fn pick_filter(selector: Selector, rest: &[&str], command: &str) -> Result<Selector, ParseError> {
+    match selector {
+        Selector::Pick(mut filter) => {
+            for arg in rest {
+                add_filter_word(&mut filter, arg, command)?;
+            }
+            Ok(Selector::Pick(filter))
+        }
+        selector => Ok(selector),
+    }
+}
+
+// The single-task form: update <task_number> <new_status>
+fn parse_update(args: &[&str], force: bool) -> Result<Command, ParseError> {
+    // --json only changes how errors are reported, so it is no part of the status
+    let (args, _) = split_format(args);
+    match split_selector(&args) {
+        // A status may take more than one word: update 3 in progress
+        Some((index, status)) if !status.is_empty() => {
+            Ok(Command::Update(index, status.join(" "), force))
+        }
+        _ => Err(usage("update")),
+    }
+}
+
+// The words of `list` after the command, --json and `archived` aside
+fn parse_list_args(args: Vec<&str>) -> Result<(ListFilter, Option<Limit>), ParseError> {
+    let mut filter = ListFilter::default();
+    let mut limit = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg {
+            "--limit" | "--last" => match args.next().map(|count| count.parse::<usize>()) {
+                Some(Ok(count)) if count > 0 => {
+                    limit = Some(if arg == "--limit" {
+                        Limit::First(count)
+                    } else {
+                        Limit::Last(count)
+                    });
+                }
+                _ => {
+                    return Err(ParseError::Usage(msg!("error.list_count", flag = arg)));
+                }
+            },
+            "--recent" => filter.recent = true,
+            "--by-due" => filter.by_due = true,
+            "--absolute" => filter.absolute = true,
+            "--grouped" => filter.grouped = true,
+            "--stale" => match args.next() {
+                Some(age) => filter.untouched = Some(parse_duration(age)?),
+                None => return Err(usage("list")),
+            },
+            _ => add_filter_word(&mut filter, arg, "list")?,
+        }
+    }
+    Ok((filter, limit))
+}
+
+/// Read a saved filter the way `list` reads its words. A limit or `--json`
+/// is for one listing and has no place in it.
+pub fn parse_filter(text: &str) -> Result<ListFilter, ParseError> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words
+        .iter()
+        .any(|word| matches!(*word, "--limit" | "--last" | "--json" | "archived"))
+    {
+        return Err(usage("filter"));
+    }
+    Ok(parse_list_args(words)?.0)
+}
+
+/// The filter with each of its [`names`](ListFilter::names) that names one
+/// of the list's saved filters swapped for what was saved, parsed afresh,
+/// and the rest turned into text terms; along with the names used, in
+/// order. Names within a saved filter are taken as text, not looked up.
+pub fn expand_filter(
+    filter: &ListFilter,
+    list: &TodoList,
+) -> Result<(ListFilter, Vec<String>), TodoError> {
+    let mut expanded = ListFilter {
+        names: Vec::new(),
+        ..filter.clone()
+    };
+    let mut used = Vec::new();
+    for word in &filter.names {
+        let name = word.to_lowercase();
+        let Some(saved) = list.filters().get(&name) else {
+            expanded.text.push(word.clone());
+            continue;
+        };
+        let mut found = parse_filter(saved)
+            .map_err(|error| TodoError::InvalidSavedFilter(name.clone(), error.to_string()))?;
+        found.text.append(&mut found.names);
+        expanded.extend(found);
+        used.push(name);
+    }
+    Ok((expanded, used))
+}
+
+// `filter save|list|delete`
+fn parse_filter_command(args: &[&str]) -> Result<Command, ParseError> {
+    let usage = || usage("filter");
+    let Some((action, args)) = args.split_first() else {
+        return Ok(Command::FilterList);
+    };
+    match (action.to_lowercase().as_str(), args) {
+        ("list" | "ls", []) => Ok(Command::FilterList),
+        ("save", [name, rest @ ..]) if !rest.is_empty() => {
+            let name = normalize_filter_name(name)?;
+            // The filter may be quoted as a whole, or not at all
+            let text = rest.join(" ");
+            let text = text.trim_matches('"').trim().to_string();
+            parse_filter(&text)?;
+            Ok(Command::FilterSave(name, text))
+        }
+        ("delete" | "remove", [name]) => Ok(Command::FilterDelete(name.to_lowercase())),
+        _ => Err(usage()),
+    }
+}
+
+// `snippet save|list|delete`
+fn parse_snippet_command(args: &[&str]) -> Result<Command, ParseError> {
+    let usage = || usage("snippet");
+    let Some((action, args)) = args.split_first() else {
+        return Ok(Command::SnippetList);
+    };
+    match (action.to_lowercase().as_str(), args) {
+        ("list" | "ls", []) => Ok(Command::SnippetList),
+        ("save", [name, rest @ ..]) if !rest.is_empty() => {
+            let name = normalize_snippet_name(name)?;
+            // The text may be quoted as a whole, or not at all
+            let text = rest.join(" ");
+            let text = text.trim_matches('"').trim().to_string();
+            snippet::placeholders(&text)?;
+            Ok(Command::SnippetSave(name, text))
+        }
+        ("delete" | "remove", [name]) => Ok(Command::SnippetDelete(normalize_snippet_name(name)?)),
+        _ => Err(usage()),
+    }
+}
+
+// `add @name key=value ...`: the snippet's name and the values, or None
+// when the words are a description that starts with a context. A word
+// without `=` goes on the value before it, so values can have spaces.
+fn parse_snippet_add(words: &[&str]) -> Option<(String, Vec<(String, String)>)> {
+    let (first, rest) = words.split_first()?;
+    let name = first.strip_prefix('@')?;
+    if !snippet::is_placeholder_name(name) {
+        return None;
+    }
+    let mut values: Vec<(String, String)> = Vec::new();
+    for word in rest {
+        match word.split_once('=') {
+            Some((key, value)) if snippet::is_placeholder_name(key) => {
+                values.push((key.to_string(), value.to_string()))
+            }
+            _ => values.last_mut()?.1.push_str(&format!(" {}", word)),
+        }
+    }
+    for (_, value) in &mut values {
+        *value = value.trim_matches('"').to_string();
+    }
+    Some((name.to_lowercase(), values))
+}
+
+// `template save|apply|list|delete`; names with spaces have to be quoted
+fn parse_template(args: &[&str]) -> Result<Command, ParseError> {
+    let usage = || usage("template");
+    let Some((action, args)) = args.split_first() else {
+        return Ok(Command::TemplateList);
+    };
+    let action = action.to_lowercase();
+    if action == "list" || action == "ls" {
+        return Ok(Command::TemplateList);
+    }
+    let Some((name, _, rest)) = split_phrase(args) else {
+        return Err(usage());
+    };
+    let name = normalize_template_name(&name)?;
+    // Anything left over is most likely the rest of an unquoted name
+    let unquoted = || ParseError::UnquotedName(action.clone());
+    match action.as_str() {
+        "save" => {
+            if rest.is_empty() {
+                return Err(usage());
+            }
+            let mut indices = Vec::new();
+            for arg in rest {
+                match arg.parse::<usize>() {
+                    Ok(index) => indices.push(index),
+                    Err(_) => return Err(unquoted()),
+                }
+            }
+            Ok(Command::TemplateSave(name, indices))
+        }
+        "apply" | "use" if rest.is_empty() => Ok(Command::TemplateApply(name)),
+        "delete" | "remove" if rest.is_empty() => Ok(Command::TemplateDelete(name)),
+        "apply" | "use" | "delete" | "remove" => Err(unquoted()),
+        _ => Err(usage()),
+    }
+}
+
+// Split `add` input on `;`, keeping `\;` as a literal semicolon
+fn split_descriptions(text: &str) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&';') => {
+                current.push(';');
+                chars.next();
+            }
+            ';' => pieces.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    pieces.push(current);
+    pieces
+        .into_iter()
+        .map(|piece| piece.trim().to_string())
+        .collect()
+}
+
+/// Cut a line into the commands chained on it with `&&`, to be run left to
+/// right: `add Buy milk && done 1`. Only a `&&` standing on its own outside
+/// quotes chains; `\&&` is a literal `&&`. Each step has its words joined
+/// by single spaces, which is how commands read them anyway.
+pub fn split_chain(input: &str) -> Vec<String> {
+    let mut steps = Vec::new();
+    let mut words: Vec<&str> = Vec::new();
+    let mut quoted = false;
+    for word in input.split_whitespace() {
+        match word {
+            "&&" if !quoted => steps.push(std::mem::take(&mut words).join(" ")),
+            "\\&&" => words.push("&&"),
+            _ => {
+                if word.matches('"').count() % 2 == 1 {
+                    quoted = !quoted;
+                }
+                words.push(word);
+            }
+        }
+    }
+    steps.push(words.join(" "));
+    steps
+}
+
+/// Turn one line of input into a command, without running or printing anything
+pub fn parse_command(input: &str) -> Result<Command, ParseError> {
+    let parsed = parse_words(input);
+    match &parsed {
+        Ok(command) => log!(Trace, "parsed {:?} as {:?}", input, command),
+        Err(error) => log!(Debug, "could not parse {:?}: {:?}", input, error),
+    }
+    parsed
+}
+
+// The parsing itself, logged by parse_command
+fn parse_words(input: &str) -> Result<Command, ParseError> {
+    let parts: Vec<&str> = input.split_whitespace().collect();
+
+    if parts.is_empty() {
+        return Err(ParseError::Empty);
+    }
+
+    let word = parts[0].to_lowercase();
+    let spec = resolve_command(&word)?;
+
+    let command = match spec.name {
+        "exit" => Command::Exit,
+        "help" => match parts.get(1) {
+            Some(word) => Command::Help(Some(resolve_command(&word.to_lowercase())?)),
+            None => Command::Help(None),
+        },
+        "list" => {
+            // Support: list, list todo, list done --json, list done --limit 5
+            let (args, format) = split_format(&parts[1..]);
+            if args.first() == Some(&"archived") {
+                return Ok(Command::Archived(format));
+            }
+            let (filter, limit) = parse_list_args(args)?;
+            Command::List {
+                filter,
+                limit,
+                format,
+            }
+        }
+        "search" => {
+            // Words are looked for separately, a "quoted phrase" as a whole
+            let (args, format) = split_format(&parts[1..]);
+            let mut filter = ListFilter::default();
+            let mut args = args.as_slice();
+            while let Some((term, _, rest)) = split_phrase(args) {
+                filter.text.push(term);
+                args = rest;
+            }
+            if filter.text.is_empty() {
+                return Err(usage("search"));
+            }
+            Command::List {
+                filter,
+                limit: None,
+                format,
+            }
+        }
+        "fuzzy" => match parts[1..].join(" ") {
+            query if query.chars().any(char::is_alphanumeric) => Command::Fuzzy(query),
+            _ => return Err(usage("fuzzy")),
+        },
+        "more" => Command::More,
+        "next" => match parts.get(1).map(|count| count.parse::<usize>()) {
+            None => Command::Next(1),
+            Some(Ok(count)) if count > 0 => Command::Next(count),
+            Some(_) => return Err(usage("next")),
+        },
+        "stats" => Command::Stats,
+        "count" => match parts[1..] {
+            [] => Command::Count(None),
+            [target] => match CountTarget::parse(target) {
+                Some(target) => Command::Count(Some(target)),
+                None => return Err(ParseError::UnknownCountTarget(target.to_string())),
+            },
+            _ => return Err(usage("count")),
+        },
+        "projects" => Command::Projects,
+        "contexts" => Command::Contexts,
+        "repeat" => {
+            let Some((index, rest)) =
+                split_selector(&parts[1..]).filter(|(_, rest)| !rest.is_empty())
+            else {
+                return Err(usage("repeat"));
+            };
+            let interval = rest.join(" ");
+            if interval.eq_ignore_ascii_case("none") {
+                Command::Repeat(index, None)
+            } else {
+                Command::Repeat(index, Some(Recurrence::parse(&interval)?))
+            }
+        }
+        "snooze" => {
+            let Some((index, rest)) =
+                split_selector(&parts[1..]).filter(|(_, rest)| !rest.is_empty())
+            else {
+                return Err(usage("snooze"));
+            };
+            Command::Snooze(index, parse_duration(&rest.join(" "))?)
+        }
+        "estimate" => {
+            let Some((index, rest)) =
+                split_selector(&parts[1..]).filter(|(_, rest)| !rest.is_empty())
+            else {
+                return Err(usage("estimate"));
+            };
+            let time = rest.join(" ");
+            if time.eq_ignore_ascii_case("none") {
+                Command::Estimate(index, None)
+            } else {
+                Command::Estimate(index, Some(Estimate::parse(&time)?))
+            }
+        }
+        "escalate" => match parts[1..] {
+            [] => Command::Escalate(None),
+            [days] => match days.parse::<u64>() {
+                Ok(days) if days > 0 => Command::Escalate(Some(days)),
+                _ => return Err(usage("escalate")),
+            },
+            _ => return Err(usage("escalate")),
+        },
+        "today" => Command::Today,
+        "overdue" => Command::Overdue,
+        "agenda" => {
+            let empty = parts[1..].contains(&"--empty");
+            let args: Vec<&str> = parts[1..]
+                .iter()
+                .copied()
+                .filter(|arg| *arg != "--empty")
+                .collect();
+            match args[..] {
+                [] => Command::Agenda(DEFAULT_AGENDA_DAYS, empty),
+                [days] => match days.parse::<usize>() {
+                    Ok(days) if days > 0 => Command::Agenda(days, empty),
+                    _ => return Err(usage("agenda")),
+                },
+                _ => return Err(usage("agenda")),
+            }
+        }
+        "due" => {
+            let Some((index, rest)) =
+                split_selector(&parts[1..]).filter(|(_, rest)| !rest.is_empty())
+            else {
+                return Err(usage("due"));
+            };
+            let when = rest.join(" ");
+            if when.eq_ignore_ascii_case("none") {
+                Command::Due(index, None)
+            } else {
+                Command::Due(index, Some(parse_due(&when, Date::today())?))
+            }
+        }
+        "reopen" => match &parts[1..] {
+            [] => Command::RecentlyCompleted,
+            [word] if word.eq_ignore_ascii_case("last") => Command::ReopenLast,
+            args => match split_selector(args) {
+                // Only completed tasks can be reopened, so only they are offered
+                Some((Selector::Pick(mut filter), rest)) => {
+                    filter.status = Some(Status::Completed);
+                    Command::Reopen(pick_filter(Selector::Pick(filter), rest, "reopen")?)
+                }
+                Some((selector, [])) => Command::Reopen(selector),
+                _ => return Err(usage("reopen")),
+            },
+        },
+        "show" => {
+            let (args, format) = split_format(&parts[1..]);
+            match split_selector(&args) {
+                Some((index, rest)) => Command::Show(pick_filter(index, rest, "show")?, format),
+                None => return Err(usage("show")),
+            }
+        }
+        "pick" => {
+            let (args, format) = split_format(&parts[1..]);
+            let mut filter = ListFilter::default();
+            for arg in &args {
+                add_filter_word(&mut filter, arg, "pick")?;
+            }
+            Command::Show(Selector::Pick(filter), format)
+        }
+        "random" => {
+            let mut filter = ListFilter::default();
+            for arg in &parts[1..] {
+                add_filter_word(&mut filter, arg, "random")?;
+            }
+            Command::Random(filter)
+        }
+        "open" => {
+            let usage = || usage("open");
+            match split_selector(&parts[1..]) {
+                Some((selector, [])) => Command::Open(selector, 1),
+                Some((selector, [number])) => match number.parse::<usize>() {
+                    Ok(number) if number > 0 => Command::Open(selector, number),
+                    _ => return Err(usage()),
+                },
+                _ => return Err(usage()),
+            }
+        }
+        "copy" => {
+            let (args, format) = split_format(&parts[1..]);
+            match (args.as_slice(), format) {
+                ([word], OutputFormat::Human) if word.eq_ignore_ascii_case("list") => {
+                    Command::CopyList
+                }
+                _ => match split_selector(&args) {
+                    Some((selector @ Selector::Pick(_), rest)) => {
+                        Command::Copy(pick_filter(selector, rest, "copy")?, format)
+                    }
+                    Some((selector, [])) => Command::Copy(selector, format),
+                    _ => return Err(usage("copy")),
+                },
+            }
+        }
+        "track" => match split_selector(&parts[1..]) {
+            Some((selector, rest)) => Command::Track(pick_filter(selector, rest, "track")?),
+            None => return Err(usage("track")),
+        },
+        "stop" => Command::Stop,
+        "review" => Command::Review,
+        "week" => match parts[1..] {
+            [] => Command::Week,
+            ["md", ref rest @ ..] => {
+                let force = rest.contains(&"--force");
+                let path: Vec<&str> = rest
+                    .iter()
+                    .copied()
+                    .filter(|arg| *arg != "--force")
+                    .collect();
+                if path.is_empty() {
+                    return Err(usage("week"));
+                }
+                Command::Report(path.join(" "), force)
+            }
+            _ => return Err(usage("week")),
+        },
+        "chart" => Command::Chart,
+        "pomodoro" => {
+            let usage = || usage("pomodoro");
+            match split_selector(&parts[1..]) {
+                Some((selector, [])) => Command::Pomodoro(selector, DEFAULT_POMODORO_MINUTES),
+                Some((selector, [minutes])) => match minutes.parse::<u64>() {
+                    Ok(minutes) if minutes > 0 => Command::Pomodoro(selector, minutes),
+                    _ => return Err(usage()),
+                },
+                _ => return Err(usage()),
+            }
+        }
+        "run" => {
+            let keep_going = parts.contains(&"--continue-on-error");
+            let path: Vec<&str> = parts[1..]
+                .iter()
+                .copied()
+                .filter(|arg| *arg != "--continue-on-error")
+                .collect();
+            if path.is_empty() {
+                return Err(usage("run"));
+            }
+            Command::Run(path.join(" "), keep_going)
+        }
+        "add" => {
+            let skip_duplicates = parts.contains(&"--no-duplicates");
+            let rest: Vec<&str> = parts[1..]
+                .iter()
+                .copied()
+                .filter(|part| *part != "--no-duplicates")
+                .collect();
+            if rest.is_empty() {
+                return Err(usage("add"));
+            }
+            if rest[0] == "--from" {
+                if rest.len() < 2 {
+                    return Err(usage("add"));
+                }
+                return Ok(Command::AddFrom(rest[1..].join(" "), skip_duplicates));
+            }
+            if rest[0] == "--stdin" {
+                if rest.len() > 1 {
+                    return Err(usage("add"));
+                }
+                return Ok(Command::AddStdin(skip_duplicates));
+            }
+            if let Some((name, values)) = parse_snippet_add(&rest) {
+                return Ok(Command::AddSnippet(name, values, skip_duplicates));
+            }
+            Command::Add(split_descriptions(&rest.join(" ")), skip_duplicates)
+        }
+        "update" => {
+            // `all` or an unquoted status in place of the task picks many tasks
+            let picks_many = |target: &str| {
+                target == "all" || (!target.starts_with('"') && Status::from_str(target).is_ok())
+            };
+            let force = parts.contains(&"--force");
+            let args: Vec<&str> = parts[1..]
+                .iter()
+                .copied()
+                .filter(|arg| *arg != "--force")
+                .collect();
+            match args[..] {
+                // Two statuses after `all`: from the first to the second
+                ["all", from, to] if picks_many(from) && picks_many(to) => {
+                    Command::UpdateAll(Some(Status::from_str(from)?), Status::from_str(to)?, force)
+                }
+                [target, ref new @ ..] if !new.is_empty() && picks_many(target) => {
+                    Command::UpdateAll(
+                        Status::from_str(target).ok(),
+                        Status::from_str(&new.join(" "))?,
+                        force,
+                    )
+                }
+                [target] if picks_many(target) => {
+                    return Err(ParseError::MissingStatus(target.to_string()));
+                }
+                _ => parse_update(&args, force)?,
+            }
+        }
+        "remove" => {
+            let (args, yes) = split_yes(&parts[1..]);
+            match split_selector(&args) {
+                Some((index, rest)) => Command::Remove(pick_filter(index, rest, "remove")?, yes),
+                None => return Err(usage("remove")),
+            }
+        }
+        "done" | "toggle" => {
+            let name = spec.name;
+            let force = parts.contains(&"--force");
+            let parts: Vec<&str> = parts
+                .iter()
+                .copied()
+                .filter(|part| *part != "--force")
+                .collect();
+            if parts.len() < 2 {
+                return Err(usage(name));
+            }
+            let mut indices = Vec::new();
+            let mut args = &parts[1..];
+            while let Some((index, rest)) = split_selector(args) {
+                // Everything after --pick narrows its menu
+                if let Selector::Pick(_) = index {
+                    indices.push(pick_filter(index, rest, name)?);
+                    break;
+                }
+                indices.push(index);
+                args = rest;
+            }
+            if name == "done" {
+                Command::Done(indices, force)
+            } else {
+                Command::Toggle(indices, force)
+            }
+        }
+        "blocks" => {
+            // blocks <task> on <other>
+            let numbers = (parts.get(1), parts.get(2), parts.get(3));
+            let (Some(task), Some(&"on"), Some(on)) = numbers else {
+                return Err(usage("blocks"));
+            };
+            match (task.parse::<usize>(), on.parse::<usize>()) {
+                (Ok(task), Ok(on)) => Command::Blocks(task, on),
+                _ => return Err(ParseError::InvalidNumber("task")),
+            }
+        }
+        "sub" => match split_selector(&parts[1..]) {
+            Some((index, rest)) if !rest.is_empty() => Command::Sub(index, rest.join(" ")),
+            _ => return Err(usage("sub")),
+        },
+        "tag" | "untag" => {
+            let name = spec.name;
+            let Some((index, rest)) =
+                split_selector(&parts[1..]).filter(|(_, rest)| !rest.is_empty())
+            else {
+                return Err(usage(name));
+            };
+            let tags: Vec<String> = rest.iter().filter_map(|tag| normalize_tag(tag)).collect();
+            if name == "tag" {
+                Command::Tag(index, tags)
+            } else {
+                Command::Untag(index, tags)
+            }
+        }
+        "mark" => match split_selector(&parts[1..]) {
+            Some((index, ["--clear"])) => Command::Mark(index, None),
+            Some((index, rest)) if !rest.is_empty() => {
+                Command::Mark(index, Some(Marker::parse(rest)?))
+            }
+            _ => return Err(usage("mark")),
+        },
+        "wait" => {
+            let Some((index, rest)) = split_selector(&parts[1..]) else {
+                return Err(usage("wait"));
+            };
+            let reason = Some(rest.join(" ")).filter(|reason| !reason.is_empty());
+            Command::Wait(index, reason)
+        }
+        "delegate" => match split_selector(&parts[1..]) {
+            Some((index, ["--clear"])) => Command::Delegate(index, None),
+            Some((index, [person])) => match normalize_person(person) {
+                Some(person) => Command::Delegate(index, Some(person)),
+                None => return Err(TodoError::InvalidPerson(person.to_string()).into()),
+            },
+            _ => return Err(usage("delegate")),
+        },
+        "waiting" => match split_format(&parts[1..]) {
+            (args, format) if args.is_empty() => Command::Waiting(format),
+            _ => return Err(usage("waiting")),
+        },
+        "append" | "prepend" => {
+            let name = spec.name;
+            let Some((index, rest)) =
+                split_selector(&parts[1..]).filter(|(_, rest)| !rest.is_empty())
+            else {
+                return Err(usage(name));
+            };
+            let text = rest.join(" ");
+            if name == "append" {
+                Command::Append(index, text)
+            } else {
+                Command::Prepend(index, text)
+            }
+        }
+        "clear" => {
+            let (args, yes) = split_yes(&parts[1..]);
+            let dry_run = args.contains(&"--dry-run");
+            let args: Vec<&str> = args.into_iter().filter(|arg| *arg != "--dry-run").collect();
+            let target = match args.first() {
+                None => None,
+                Some(&"all") => Some(ClearTarget::All),
+                Some(arg) => match Status::from_str(arg) {
+                    Ok(status) => Some(ClearTarget::Status(status)),
+                    Err(_) => return Err(usage("clear")),
+                },
+            };
+            Command::Clear(target, yes, dry_run)
+        }
+        "save" => match parts[1..] {
+            [] => Command::Save(false),
+            ["--force"] => Command::Save(true),
+            _ => return Err(usage("save")),
+        },
+        "reload" => Command::Reload(split_yes(&parts[1..]).1),
+        "merge" => match split_side(&parts[1..]) {
+            Some((path, side)) if !path.is_empty() => Command::Merge(path.join(" "), side),
+            _ => return Err(usage("merge")),
+        },
+        "import" => match parts[1..] {
+            [format, ref path @ ..] if !path.is_empty() => match Format::from_name(format) {
+                Some(format) => Command::Import(format, path.join(" ")),
+                None => return Err(usage("import")),
+            },
+            _ => return Err(usage("import")),
+        },
+        "sync" => match split_side(&parts[1..]) {
+            Some((args, side)) => match args[..] {
+                [] => Command::Sync(SyncAction::Both, side),
+                ["pull"] => Command::Sync(SyncAction::Pull, side),
+                ["push"] if side.is_none() => Command::Sync(SyncAction::Push(false), None),
+                ["push", "--force"] | ["--force", "push"] if side.is_none() => {
+                    Command::Sync(SyncAction::Push(true), None)
+                }
+                _ => return Err(usage("sync")),
+            },
+            None => return Err(usage("sync")),
+        },
+        "backup" => {
+            let (args, yes) = split_yes(&parts[1..]);
+            match args[..] {
+                ["create", ref path @ ..] if !path.is_empty() && !yes => {
+                    Command::BackupCreate(path.join(" "))
+                }
+                ["restore", ref path @ ..] if !path.is_empty() => {
+                    Command::BackupRestore(path.join(" "), yes)
+                }
+                _ => return Err(usage("backup")),
+            }
+        }
+        "version" => Command::Version,
+        "completions" => match parts[1..] {
+            [name] => match Shell::from_name(name) {
+                Some(shell) => Command::Completions(shell),
+                None => return Err(usage("completions")),
+            },
+            _ => return Err(usage("completions")),
+        },
+        "template" => parse_template(&parts[1..])?,
+        "filter" => parse_filter_command(&parts[1..])?,
+        "snippet" => parse_snippet_command(&parts[1..])?,
+        "archive" => {
+            let (args, format) = split_format(&parts[1..]);
+            match args[..] {
+                [] if format == OutputFormat::Human => Command::Archive,
+                ["stats"] => Command::ArchiveStats(format),
+                _ => return Err(usage("archive")),
+            }
+        }
+        "dedupe" => Command::Dedupe(parts[1..].contains(&"--dry-run")),
+        "doctor" => Command::Doctor(parts[1..].contains(&"--fix")),
+        "renumber" => match split_yes(&parts[1..]) {
+            (args, yes) if args.is_empty() => Command::Renumber(yes),
+            _ => return Err(usage("renumber")),
+        },
+        "trash" => {
+            let (args, format) = split_format(&parts[1..]);
+            let (args, yes) = split_yes(&args);
+            match args.first() {
+                None => Command::Trash(format),
+                Some(&"empty") => Command::EmptyTrash(yes),
+                Some(_) => return Err(usage("trash")),
+            }
+        }
+        "history" => match parts.get(1) {
+            None => Command::History(None),
+            Some(&"clear") => Command::ClearHistory(split_yes(&parts[2..]).1),
+            Some(arg) => match arg.parse::<usize>() {
+                Ok(count) => Command::History(Some(count)),
+                Err(_) => return Err(usage("history")),
+            },
+        },
+        "restore" => {
+            if parts.len() < 2 {
+                return Err(usage("restore"));
+            }
+            match parts[1].parse::<usize>() {
+                Ok(index) => Command::Restore(index),
+                Err(_) => return Err(ParseError::InvalidNumber("trash")),
+            }
+        }
+        _ => return Err(ParseError::UnknownCommand(word, Vec::new())),
+    };
+    Ok(command)
+}
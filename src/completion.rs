@@ -0,0 +1,309 @@
+use std::fmt::{self, Write};
+
+use crate::command::{COMMANDS, CommandSpec};
+use crate::todo::Status;
+
+/// A shell that `completions` writes a script for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    /// Every supported shell
+    pub const ALL: [Shell; 3] = [Shell::Bash, Shell::Zsh, Shell::Fish];
+
+    /// Name as `completions` takes it
+    pub fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+        }
+    }
+
+    /// The shell a name stands for, in any case
+    pub fn from_name(name: &str) -> Option<Shell> {
+        Shell::ALL
+            .into_iter()
+            .find(|shell| shell.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// What can follow a command, read from its usage lines in [`COMMANDS`] so
+/// completion stays in step with `help` and the parser
+#[derive(Debug, Clone, PartialEq)]
+pub struct Completions {
+    /// Fixed words right after the command, such as `archived` in
+    /// `list archived`, and those its explaining lines list, like `blocked`
+    pub words: Vec<&'static str>,
+    /// Flags any of its usage lines mention, such as `--json`
+    pub flags: Vec<&'static str>,
+    /// Whether it takes a status, as `update <num> <status>` does
+    pub statuses: bool,
+}
+
+impl Completions {
+    /// What can follow the command `spec` describes
+    pub fn of(spec: &CommandSpec) -> Self {
+        let mut completions = Completions {
+            words: Vec::new(),
+            flags: Vec::new(),
+            statuses: false,
+        };
+        for (usage, _) in spec.usage {
+            let tokens: Vec<&str> = usage.split_whitespace().collect();
+            // A line calling the command offers the word after its name, as
+            // optional words in brackets are placeholders like [count]. Lines
+            // explaining the one above start with spaces and offer any word.
+            let offered = match tokens.first() {
+                Some(first) if spec.words().any(|word| word == *first) => {
+                    tokens.get(1..2).unwrap_or_default()
+                }
+                _ => &tokens[..],
+            };
+            for token in offered {
+                for word in token.split('|') {
+                    if is_literal(word) && !completions.words.contains(&word) {
+                        completions.words.push(word);
+                    }
+                }
+            }
+            for token in &tokens {
+                completions.statuses |= token.contains("<status>");
+                for flag in token.trim_matches(['[', ']']).split('|') {
+                    if flag.starts_with("--") && !completions.flags.contains(&flag) {
+                        completions.flags.push(flag);
+                    }
+                }
+            }
+        }
+        completions
+    }
+
+    // Every word to offer after the command
+    fn all(&self) -> Vec<&'static str> {
+        let statuses = Status::ALL.iter().map(|status| status.keyword());
+        let statuses = statuses.filter(|_| self.statuses);
+        self.words
+            .iter()
+            .copied()
+            .chain(statuses)
+            .chain(self.flags.iter().copied())
+            .collect()
+    }
+}
+
+// A fixed word, rather than a placeholder like <num> or a flag
+fn is_literal(word: &str) -> bool {
+    !word.is_empty()
+        && word.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+        && !word.starts_with('-')
+}
+
+/// A completion script for `shell`, for the program `program` with its own
+/// flags. Each flag comes with whether it takes a value, like `--page-size 20`.
+pub fn script(shell: Shell, program: &str, flags: &[(&str, bool)]) -> String {
+    let mut out = String::new();
+    match shell {
+        Shell::Bash => bash(&mut out, program, flags),
+        Shell::Zsh => zsh(&mut out, program, flags),
+        Shell::Fish => fish(&mut out, program, flags),
+    }
+    .expect("writing to a String cannot fail");
+    out
+}
+
+// Name of the shell function that completes `program`
+fn function_name(program: &str) -> String {
+    format!("_{}", program.replace(['-', '.'], "_"))
+}
+
+// The flags that take a value, as a shell case pattern: `--lang|--prompt`
+fn value_flags(flags: &[(&str, bool)]) -> String {
+    let taking: Vec<&str> = flags
+        .iter()
+        .filter(|(_, value)| *value)
+        .map(|(flag, _)| *flag)
+        .collect();
+    if taking.is_empty() {
+        // Matches no word, keeping the case statement valid
+        "--".to_string()
+    } else {
+        taking.join("|")
+    }
+}
+
+// The first word after the program's flags and their values is the command
+fn find_command(
+    script: &mut String,
+    words: &str,
+    first: usize,
+    flags: &[(&str, bool)],
+) -> fmt::Result {
+    writeln!(script, "    local i command=")?;
+    writeln!(
+        script,
+        "    for ((i = {}; i < {}; i++)); do",
+        first,
+        if first == 1 { "COMP_CWORD" } else { "CURRENT" }
+    )?;
+    writeln!(script, "        case ${{{}[i]}} in", words)?;
+    writeln!(script, "            {}) ((i++)) ;;", value_flags(flags))?;
+    writeln!(script, "            -*) ;;")?;
+    writeln!(script, "            *) command=${{{}[i]}}; break ;;", words)?;
+    writeln!(script, "        esac")?;
+    writeln!(script, "    done")
+}
+
+fn bash(script: &mut String, program: &str, flags: &[(&str, bool)]) -> fmt::Result {
+    let function = function_name(program);
+    writeln!(
+        script,
+        "# bash completion for {0}, from `{0} completions bash`",
+        program
+    )?;
+    writeln!(script, "{}() {{", function)?;
+    writeln!(script, "    local cur=${{COMP_WORDS[COMP_CWORD]}} words")?;
+    find_command(script, "COMP_WORDS", 1, flags)?;
+    writeln!(script, "    case $command in")?;
+    let mut top: Vec<&str> = flags.iter().map(|(flag, _)| *flag).collect();
+    for word in COMMANDS.iter().flat_map(|spec| spec.words()) {
+        top.push(word);
+    }
+    writeln!(script, "        \"\") words=\"{}\" ;;", top.join(" "))?;
+    for spec in COMMANDS {
+        let after = Completions::of(spec).all();
+        if !after.is_empty() {
+            let names: Vec<&str> = spec.words().collect();
+            writeln!(
+                script,
+                "        {}) words=\"{}\" ;;",
+                names.join("|"),
+                after.join(" ")
+            )?;
+        }
+    }
+    writeln!(script, "        *) words= ;;")?;
+    writeln!(script, "    esac")?;
+    writeln!(
+        script,
+        "    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))"
+    )?;
+    writeln!(script, "}}")?;
+    writeln!(script, "complete -o default -F {} {}", function, program)
+}
+
+// Text in single quotes for zsh and fish
+fn quoted(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+// What a command does, from the first line of its usage. A line ending in a
+// colon leads into the lines below it, which completion does not show.
+fn summary(spec: &CommandSpec) -> &'static str {
+    spec.usage
+        .first()
+        .map_or("", |(_, what)| what.trim_end_matches(':'))
+}
+
+fn zsh(script: &mut String, program: &str, flags: &[(&str, bool)]) -> fmt::Result {
+    let function = function_name(program);
+    writeln!(script, "#compdef {}", program)?;
+    writeln!(
+        script,
+        "# zsh completion for {0}, from `{0} completions zsh`",
+        program
+    )?;
+    writeln!(script, "{}() {{", function)?;
+    writeln!(script, "    local -a commands")?;
+    writeln!(script, "    commands=(")?;
+    for spec in COMMANDS {
+        for word in spec.words() {
+            let entry = format!("{}:{}", word, summary(spec).replace(':', "\\:"));
+            writeln!(script, "        {}", quoted(&entry))?;
+        }
+    }
+    writeln!(script, "    )")?;
+    find_command(script, "words", 2, flags)?;
+    writeln!(script, "    if [[ -z $command ]]; then")?;
+    writeln!(script, "        _describe -t commands command commands")?;
+    let top: Vec<&str> = flags.iter().map(|(flag, _)| *flag).collect();
+    writeln!(script, "        compadd -- {}", top.join(" "))?;
+    writeln!(script, "        return")?;
+    writeln!(script, "    fi")?;
+    writeln!(script, "    case $command in")?;
+    for spec in COMMANDS {
+        let after = Completions::of(spec).all();
+        if !after.is_empty() {
+            let names: Vec<&str> = spec.words().collect();
+            writeln!(
+                script,
+                "        {}) compadd -- {} ;;",
+                names.join("|"),
+                after.join(" ")
+            )?;
+        }
+    }
+    writeln!(script, "    esac")?;
+    writeln!(script, "    _files")?;
+    writeln!(script, "}}")?;
+    writeln!(script, "{} \"$@\"", function)
+}
+
+// A flag as fish's `complete` takes it: `-l page-size -r` or `-s q`
+fn fish_flag(flag: &str, value: bool) -> String {
+    let option = match flag.strip_prefix("--") {
+        Some(long) => format!("-l {}", long),
+        None => format!("-s {}", flag.trim_start_matches('-')),
+    };
+    if value {
+        format!("{} -r", option)
+    } else {
+        option
+    }
+}
+
+fn fish(script: &mut String, program: &str, flags: &[(&str, bool)]) -> fmt::Result {
+    writeln!(
+        script,
+        "# fish completion for {0}, from `{0} completions fish`",
+        program
+    )?;
+    let start = format!("complete -c {} -n __fish_use_subcommand", program);
+    for (flag, value) in flags {
+        writeln!(script, "{} {}", start, fish_flag(flag, *value))?;
+    }
+    for spec in COMMANDS {
+        for word in spec.words() {
+            writeln!(
+                script,
+                "{} -f -a {} -d {}",
+                start,
+                word,
+                quoted(summary(spec))
+            )?;
+        }
+    }
+    for spec in COMMANDS {
+        let completions = Completions::of(spec);
+        let names: Vec<&str> = spec.words().collect();
+        let seen = format!(
+            "complete -c {} -n {}",
+            program,
+            quoted(&format!("__fish_seen_subcommand_from {}", names.join(" ")))
+        );
+        let statuses = Status::ALL.iter().map(|status| status.keyword());
+        let statuses = statuses.filter(|_| completions.statuses);
+        let words: Vec<&str> = completions.words.iter().copied().chain(statuses).collect();
+        if !words.is_empty() {
+            writeln!(script, "{} -a {}", seen, quoted(&words.join(" ")))?;
+        }
+        for flag in &completions.flags {
+            writeln!(script, "{} {}", seen, fish_flag(flag, false))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Where a countdown gets its time from, so it can run without real waiting
+pub trait Clock {
+    /// Time passed since the clock was started
+    fn elapsed(&self) -> Duration;
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// The clock on the wall, started when made
+pub struct SystemClock {
+    start: Instant,
+}
+
+impl SystemClock {
+    pub fn new() -> Self {
+        SystemClock {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl Default for SystemClock {
+    fn default() -> Self {
+        SystemClock::new()
+    }
+}
+
+impl Clock for SystemClock {
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
+
+/// How a focus session ended and how long it ran
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Session {
+    pub spent: Duration,
+    pub finished: bool,
+}
+
+/// Count `length` down on `clock`, calling `tick` with the time left about
+/// once a second. Stops early, keeping the time spent so far, once `cancel`
+/// is set.
+pub fn countdown(
+    clock: &mut impl Clock,
+    length: Duration,
+    cancel: &AtomicBool,
+    mut tick: impl FnMut(Duration),
+) -> Session {
+    loop {
+        let spent = clock.elapsed();
+        if spent >= length {
+            return Session {
+                spent: length,
+                finished: true,
+            };
+        }
+        if cancel.load(Ordering::Relaxed) {
+            return Session {
+                spent,
+                finished: false,
+            };
+        }
+        let left = length - spent;
+        tick(left);
+        clock.sleep(left.min(Duration::from_secs(1)));
+    }
+}
@@ -0,0 +1,432 @@
+use std::fmt::Display;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, Offset, TimeZone};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::todo::TodoError;
+
+/// Seconds since the Unix epoch
+pub type Timestamp = u64;
+
+/// Length of a day, ignoring leap seconds
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+// Length of a day on a clock
+const MINUTES_PER_DAY: i64 = 24 * 60;
+
+/// Current time as a timestamp
+pub fn now() -> Timestamp {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or(0)
+}
+
+/// Parse a duration such as "2d", "1w", "12h", "90m" or "1h30m", shared by
+/// snoozing, recurrence and anything else that takes an amount of time
+pub fn parse_duration(text: &str) -> Result<Duration, TodoError> {
+    let invalid = || TodoError::InvalidDuration(text.to_string());
+    let lowered = text.trim().to_lowercase();
+    let mut rest = lowered.as_str();
+    if rest.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut total: u64 = 0;
+    while !rest.is_empty() {
+        let digits = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let count: u64 = rest[..digits].parse().map_err(|_| invalid())?;
+        rest = rest[digits..].trim_start();
+
+        let letters = rest
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(rest.len());
+        let unit_seconds = match &rest[..letters] {
+            "m" | "min" | "mins" | "minute" | "minutes" => 60,
+            "h" | "hr" | "hrs" | "hour" | "hours" => 3_600,
+            "d" | "day" | "days" => SECONDS_PER_DAY as u64,
+            "w" | "week" | "weeks" => 7 * SECONDS_PER_DAY as u64,
+            _ => return Err(invalid()),
+        };
+        rest = rest[letters..].trim_start();
+
+        total = count
+            .checked_mul(unit_seconds)
+            .and_then(|seconds| total.checked_add(seconds))
+            .ok_or_else(invalid)?;
+    }
+
+    if total == 0 {
+        return Err(invalid());
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// Whole days covered by a duration, rounding part days up
+pub fn whole_days(duration: Duration) -> i64 {
+    duration.as_secs().div_ceil(SECONDS_PER_DAY as u64) as i64
+}
+
+/// A due date, and the time it is due when there is one, pushed back by
+/// `by`. A time moves by the minute, part minutes rounding up, across
+/// midnight as needed; a date on its own moves by whole days, rounding up.
+pub fn due_later(due: Date, time: Option<Time>, by: Duration) -> (Date, Option<Time>) {
+    let Some(time) = time else {
+        return (due.add_days(whole_days(by)), None);
+    };
+    let minutes = i64::from(time.minutes()) + by.as_secs().div_ceil(60) as i64;
+    let minute_of_day = minutes.rem_euclid(MINUTES_PER_DAY) as u32;
+    (
+        due.add_days(minutes.div_euclid(MINUTES_PER_DAY)),
+        Time::new(minute_of_day / 60, minute_of_day % 60),
+    )
+}
+
+/// A due date with its time when it has one: 2025-07-01 or 2025-07-01 14:30
+pub fn format_due(due: Date, time: Option<Time>) -> String {
+    match time {
+        Some(time) => format!("{} {}", due, time),
+        None => due.to_string(),
+    }
+}
+
+/// Tracked time such as 45s, 25m or 3h 05m
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds < 60 {
+        format!("{}s", seconds)
+    } else if seconds < 3_600 {
+        format!("{}m", seconds / 60)
+    } else {
+        format!("{}h {:02}m", seconds / 3_600, seconds % 3_600 / 60)
+    }
+}
+
+/// Short age for list rows: 0d..6d, then weeks, months (30 days) and years
+pub fn humanize_days(days: i64) -> String {
+    let days = days.max(0);
+    if days < 7 {
+        format!("{}d", days)
+    } else if days < 30 {
+        format!("{}w", days / 7)
+    } else if days < 365 {
+        format!("{}mo", days / 30)
+    } else {
+        format!("{}y", days / 365)
+    }
+}
+
+/// Local date and time of a timestamp, e.g. 2025-06-01 09:12
+pub fn format_timestamp(timestamp: Timestamp) -> String {
+    format!(
+        "{} {}",
+        Date::from_timestamp(timestamp),
+        Time::from_timestamp(timestamp)
+    )
+}
+
+/// Offset of local time from UTC in seconds at the given moment, by the
+/// time zone rules of the system (or of `TZ`), summer time included; 0 for
+/// a moment too far off to look up
+pub fn utc_offset(timestamp: Timestamp) -> i64 {
+    i64::try_from(timestamp)
+        .ok()
+        .and_then(|seconds| Local.timestamp_opt(seconds, 0).single())
+        .map_or(0, |local| i64::from(local.offset().fix().local_minus_utc()))
+}
+
+/// A calendar day in the proleptic Gregorian calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Self> {
+        if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// Parse an ISO date like 2025-06-01
+    pub fn parse(text: &str) -> Result<Self, TodoError> {
+        let invalid = || TodoError::InvalidDate(text.to_string());
+        let mut parts = text.trim().splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+        let year = year.parse().map_err(|_| invalid())?;
+        let month = month.parse().map_err(|_| invalid())?;
+        let day = day.parse().map_err(|_| invalid())?;
+        Date::new(year, month, day).ok_or_else(invalid)
+    }
+
+    /// Parse a date as people type it, relative to `today`: an ISO date,
+    /// today, tomorrow, a weekday (the next one, never today), next week,
+    /// or "in 3 days" / "in 2w"
+    pub fn parse_natural(text: &str, today: Date) -> Result<Self, TodoError> {
+        let invalid = || TodoError::InvalidDate(text.to_string());
+        let lowered = text.trim().trim_matches('"').trim().to_lowercase();
+        let words: Vec<&str> = lowered.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["today"] => Ok(today),
+            ["tomorrow"] => Ok(today.add_days(1)),
+            ["next", "week"] => Ok(today.add_days(7)),
+            ["in", amount @ ..] if !amount.is_empty() => {
+                let duration = parse_duration(&amount.join(" ")).map_err(|_| invalid())?;
+                Ok(today.add_days(whole_days(duration)))
+            }
+            [day] | ["next", day] if weekday_number(day).is_some() => {
+                let target = weekday_number(day).unwrap_or_default();
+                let ahead = (target - today.weekday()).rem_euclid(7);
+                Ok(today.add_days(if ahead == 0 { 7 } else { ahead }))
+            }
+            [iso] => Date::parse(iso).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+
+    /// Today's date in local time
+    pub fn today() -> Self {
+        Date::from_timestamp(now())
+    }
+
+    /// Day on which a timestamp falls in local time
+    pub fn from_timestamp(timestamp: Timestamp) -> Self {
+        let local = timestamp as i64 + utc_offset(timestamp);
+        Date::from_days(local.div_euclid(SECONDS_PER_DAY))
+    }
+
+    /// Date from days since 1970-01-01 (Howard Hinnant's civil_from_days)
+    pub fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = (yoe + era * 400 + if month <= 2 { 1 } else { 0 }) as i32;
+        Date { year, month, day }
+    }
+
+    /// Days since 1970-01-01 (Howard Hinnant's days_from_civil)
+    pub fn days(&self) -> i64 {
+        let year = if self.month <= 2 {
+            self.year as i64 - 1
+        } else {
+            self.year as i64
+        };
+        let era = year.div_euclid(400);
+        let yoe = year.rem_euclid(400);
+        let month = self.month as i64;
+        let mp = if month > 2 { month - 3 } else { month + 9 };
+        let doy = (153 * mp + 2) / 5 + self.day as i64 - 1;
+        let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+        era * 146_097 + doe - 719_468
+    }
+
+    /// The day `days` after this one, or before it when negative
+    pub fn add_days(&self, days: i64) -> Self {
+        Date::from_days(self.days() + days)
+    }
+
+    /// Day of the week, Monday = 0 (1970-01-01 was a Thursday)
+    pub fn weekday(&self) -> i64 {
+        (self.days() + 3).rem_euclid(7)
+    }
+
+    /// Three-letter weekday name, e.g. "Mon"
+    pub fn short_weekday(&self) -> &'static str {
+        ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"][self.weekday() as usize]
+    }
+
+    /// Weekday, day and month, e.g. "Wed 12 Jun"
+    pub fn short_label(&self) -> String {
+        let month = [
+            "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+        ][self.month as usize - 1];
+        format!("{} {} {}", self.short_weekday(), self.day, month)
+    }
+
+    /// Whole days from `self` until `other` (negative when other is earlier)
+    pub fn days_until(&self, other: Date) -> i64 {
+        other.days() - self.days()
+    }
+}
+
+/// A time of day in local time, to the minute
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Time {
+    hour: u32,
+    minute: u32,
+}
+
+impl Time {
+    pub fn new(hour: u32, minute: u32) -> Option<Self> {
+        (hour < 24 && minute < 60).then_some(Time { hour, minute })
+    }
+
+    /// Parse a time like 14:30, 9am, 9:15pm or 12pm (noon)
+    pub fn parse(text: &str) -> Result<Self, TodoError> {
+        let invalid = || TodoError::InvalidDate(text.to_string());
+        let lowered = text.trim().to_lowercase();
+        let (clock, half) = match lowered.strip_suffix("am") {
+            Some(clock) => (clock, Some(0)),
+            None => match lowered.strip_suffix("pm") {
+                Some(clock) => (clock, Some(12)),
+                None => (lowered.as_str(), None),
+            },
+        };
+        let (hour, minute) = match clock.trim_end().split_once(':') {
+            Some((hour, minute)) if minute.len() == 2 => (hour, minute),
+            Some(_) => return Err(invalid()),
+            // A bare number is only a time with am or pm after it
+            None if half.is_some() => (clock.trim_end(), "00"),
+            None => return Err(invalid()),
+        };
+        if !(1..=2).contains(&hour.len()) || !hour.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let mut hour: u32 = hour.parse().map_err(|_| invalid())?;
+        let minute: u32 = minute.parse().map_err(|_| invalid())?;
+        if let Some(half) = half {
+            if !(1..=12).contains(&hour) {
+                return Err(invalid());
+            }
+            hour = hour % 12 + half;
+        }
+        Time::new(hour, minute).ok_or_else(invalid)
+    }
+
+    /// The time now in local time
+    pub fn now() -> Self {
+        Time::from_timestamp(now())
+    }
+
+    /// Time of day at which a timestamp falls in local time
+    pub fn from_timestamp(timestamp: Timestamp) -> Self {
+        let local = timestamp as i64 + utc_offset(timestamp);
+        let minutes = local.rem_euclid(SECONDS_PER_DAY) / 60;
+        Time {
+            hour: (minutes / 60) as u32,
+            minute: (minutes % 60) as u32,
+        }
+    }
+
+    /// Minutes since midnight
+    pub fn minutes(&self) -> u32 {
+        self.hour * 60 + self.minute
+    }
+}
+
+/// Parse a due date with an optional time, relative to `today`: anything
+/// [`Date::parse_natural`] takes, then a time such as 14:30, 9am or 9 am,
+/// optionally after "at". A time on its own is today.
+pub fn parse_due(text: &str, today: Date) -> Result<(Date, Option<Time>), TodoError> {
+    let invalid = || TodoError::InvalidDate(text.to_string());
+    let lowered = text.trim().trim_matches('"').trim().to_lowercase();
+    let mut words: Vec<&str> = lowered.split_whitespace().collect();
+    let clock = match words.as_slice() {
+        [.., hour, half @ ("am" | "pm")] => {
+            let clock = format!("{}{}", hour, half);
+            words.truncate(words.len() - 2);
+            Some(clock)
+        }
+        [.., last] if last.contains(':') || last.ends_with("am") || last.ends_with("pm") => {
+            let clock = last.to_string();
+            words.pop();
+            Some(clock)
+        }
+        _ => None,
+    };
+    let time = clock
+        .map(|clock| Time::parse(&clock))
+        .transpose()
+        .map_err(|_| invalid())?;
+    if time.is_some() && words.last() == Some(&"at") {
+        words.pop();
+    }
+    let date = match (words.is_empty(), time) {
+        (true, Some(_)) => today,
+        _ => Date::parse_natural(&words.join(" "), today).map_err(|_| invalid())?,
+    };
+    Ok((date, time))
+}
+
+impl Display for Time {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:02}:{:02}", self.hour, self.minute)
+    }
+}
+
+// Times are stored as 24-hour strings such as "14:30"
+impl Serialize for Time {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Time {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Time::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Display for Date {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+// Dates are stored as ISO strings in the task file
+impl Serialize for Date {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Date {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Date::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
+
+// Monday = 0 for a weekday name or its three-letter short form
+fn weekday_number(name: &str) -> Option<i64> {
+    const NAMES: [&str; 7] = [
+        "monday",
+        "tuesday",
+        "wednesday",
+        "thursday",
+        "friday",
+        "saturday",
+        "sunday",
+    ];
+    NAMES
+        .iter()
+        .position(|full| name.len() >= 3 && full.starts_with(name))
+        .map(|i| i as i64)
+}
+
+fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
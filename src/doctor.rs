@@ -0,0 +1,205 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::todo::{Task, TaskIndex};
+
+/// What a task with an empty description is given by [`fix`], so it can be
+/// found and renamed
+pub const UNTITLED: &str = "(no description)";
+
+/// Something wrong with a task that the rest of the code takes for granted
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    /// The task has the ID of the earlier task numbered `first`
+    DuplicateId { id: u64, first: usize },
+    /// The description is empty or only spaces
+    EmptyDescription,
+    /// `completed_at` is earlier than `created_at`
+    CompletedBeforeCreated,
+    /// `depends_on` names an ID that no task has
+    DanglingDependency(u64),
+}
+
+impl Problem {
+    /// Whether [`fix`] repairs it; the others are left for the user
+    pub fn fixable(&self) -> bool {
+        !matches!(self, Problem::CompletedBeforeCreated)
+    }
+}
+
+/// A problem and the task or subtask it was found on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub at: TaskIndex,
+    pub problem: Problem,
+}
+
+/// Repairs the tasks a rule found, returning what it repaired
+pub type Fix = fn(&mut [Task]) -> Vec<Finding>;
+
+/// One thing a list must hold to: how to find the tasks that break it and,
+/// when that loses nothing, how to repair them
+pub struct Rule {
+    pub name: &'static str,
+    pub check: fn(&[Task]) -> Vec<Finding>,
+    /// None when what `check` finds has no safe repair
+    pub fix: Option<Fix>,
+}
+
+/// Every rule, in the order their findings are reported and fixed. IDs are
+/// made unique before dependencies are checked against them.
+pub const RULES: &[Rule] = &[
+    Rule {
+        name: "duplicate-ids",
+        check: duplicate_ids,
+        fix: Some(fix_duplicate_ids),
+    },
+    Rule {
+        name: "empty-descriptions",
+        check: empty_descriptions,
+        fix: Some(fix_empty_descriptions),
+    },
+    Rule {
+        name: "completion-times",
+        check: completed_before_created,
+        fix: None,
+    },
+    Rule {
+        name: "dependencies",
+        check: dangling_dependencies,
+        fix: Some(fix_dangling_dependencies),
+    },
+];
+
+/// Everything the rules find, rule by rule
+pub fn check(tasks: &[Task]) -> Vec<Finding> {
+    RULES.iter().flat_map(|rule| (rule.check)(tasks)).collect()
+}
+
+/// Repair what the rules can repair safely, returning what was repaired.
+/// Problems without a fix are left as they are.
+pub fn fix(tasks: &mut [Task]) -> Vec<Finding> {
+    RULES
+        .iter()
+        .filter_map(|rule| rule.fix)
+        .flat_map(|fix| fix(tasks))
+        .collect()
+}
+
+/// Tasks sharing an ID with an earlier one. IDs of 0 belong to tasks that
+/// have not joined a list yet and are skipped.
+pub fn duplicate_ids(tasks: &[Task]) -> Vec<Finding> {
+    let mut first_with = HashMap::new();
+    let mut found = Vec::new();
+    for (i, task) in tasks.iter().enumerate() {
+        if task.id == 0 {
+            continue;
+        }
+        match first_with.get(&task.id) {
+            Some(&first) => found.push(Finding {
+                at: top(i),
+                problem: Problem::DuplicateId { id: task.id, first },
+            }),
+            None => {
+                first_with.insert(task.id, i + 1);
+            }
+        }
+    }
+    found
+}
+
+// The first task keeps the ID, so dependencies on it still mean that task
+fn fix_duplicate_ids(tasks: &mut [Task]) -> Vec<Finding> {
+    let found = duplicate_ids(tasks);
+    let first = tasks.iter().map(|task| task.id).max().unwrap_or(0) + 1;
+    for (id, finding) in (first..).zip(&found) {
+        tasks[finding.at.task - 1].id = id;
+    }
+    found
+}
+
+/// Tasks and subtasks whose description is empty or blank
+pub fn empty_descriptions(tasks: &[Task]) -> Vec<Finding> {
+    every_task(tasks)
+        .filter(|(_, task)| task.description.trim().is_empty())
+        .map(|(at, _)| Finding {
+            at,
+            problem: Problem::EmptyDescription,
+        })
+        .collect()
+}
+
+fn fix_empty_descriptions(tasks: &mut [Task]) -> Vec<Finding> {
+    let found = empty_descriptions(tasks);
+    for finding in &found {
+        let task = &mut tasks[finding.at.task - 1];
+        let task = match finding.at.sub {
+            Some(sub) => &mut task.subtasks[sub - 1],
+            None => task,
+        };
+        task.description = UNTITLED.to_string();
+    }
+    found
+}
+
+/// Tasks and subtasks completed before they were created. Either time could
+/// be the wrong one, so there is no fix.
+pub fn completed_before_created(tasks: &[Task]) -> Vec<Finding> {
+    every_task(tasks)
+        .filter(|(_, task)| match (task.created_at, task.completed_at) {
+            (Some(created), Some(completed)) => completed < created,
+            _ => false,
+        })
+        .map(|(at, _)| Finding {
+            at,
+            problem: Problem::CompletedBeforeCreated,
+        })
+        .collect()
+}
+
+/// Dependencies on IDs that no task in the list has
+pub fn dangling_dependencies(tasks: &[Task]) -> Vec<Finding> {
+    let ids: HashSet<u64> = tasks.iter().map(|task| task.id).collect();
+    let mut found = Vec::new();
+    for (i, task) in tasks.iter().enumerate() {
+        for &id in &task.depends_on {
+            if !ids.contains(&id) {
+                found.push(Finding {
+                    at: top(i),
+                    problem: Problem::DanglingDependency(id),
+                });
+            }
+        }
+    }
+    found
+}
+
+fn fix_dangling_dependencies(tasks: &mut [Task]) -> Vec<Finding> {
+    let found = dangling_dependencies(tasks);
+    let ids: HashSet<u64> = tasks.iter().map(|task| task.id).collect();
+    for task in tasks.iter_mut() {
+        task.depends_on.retain(|id| ids.contains(id));
+    }
+    found
+}
+
+// The task at slice position `i`, as a task number
+fn top(i: usize) -> TaskIndex {
+    TaskIndex {
+        task: i + 1,
+        sub: None,
+    }
+}
+
+// Tasks and their subtasks, each with its number
+fn every_task(tasks: &[Task]) -> impl Iterator<Item = (TaskIndex, &Task)> {
+    tasks.iter().enumerate().flat_map(|(i, task)| {
+        let subtasks = task.subtasks.iter().enumerate().map(move |(j, sub)| {
+            let at = TaskIndex {
+                task: i + 1,
+                sub: Some(j + 1),
+            };
+            (at, sub)
+        });
+        std::iter::once((top(i), task)).chain(subtasks)
+    })
+}
@@ -0,0 +1,139 @@
+//! Ranking tasks by how closely their descriptions match a query that may
+//! be misspelled or cut short, for `fuzzy`.
+//!
+//! Each word of the query is scored against the closest word of the
+//! description, ignoring case and punctuation, and a description's score
+//! is the average over the query's words, from 0 to 100:
+//!
+//! | the query word...                               | scores |
+//! |-------------------------------------------------|--------|
+//! | is a word of the description                    | 100    |
+//! | starts one                                      | 90     |
+//! | is inside one (3 letters or more)               | 70     |
+//! | is a typo, or two if it is long, away from one  | 60, 50 |
+//! | is a typo away from how one starts              | 50     |
+//! | has its letters in order in one, from its first | 40     |
+//! | none of these                                   | 0      |
+
+use crate::todo::{Task, TodoList};
+use crate::width::edit_distance;
+
+/// How many matches `fuzzy` shows
+pub const LIMIT: usize = 10;
+
+/// The lowest score still counted as a match
+pub const MIN_SCORE: u32 = 40;
+
+// Query words this long or longer may be two typos away from a word;
+// shorter ones only one
+const LONG_WORD: usize = 6;
+
+/// A task matching a query, with its 1-based number and its score
+#[derive(Debug, Clone, PartialEq)]
+pub struct Match<'a> {
+    pub index: usize,
+    pub task: &'a Task,
+    pub score: u32,
+}
+
+/// How closely `text` matches `query`, from [`MIN_SCORE`] to 100, or
+/// `None` when it doesn't match well enough to be worth showing
+pub fn score(query: &str, text: &str) -> Option<u32> {
+    score_words(&words(query), text)
+}
+
+/// The tasks matching `query` best, at most `limit` of them: the highest
+/// scores first, then the shorter descriptions, then the lower numbers
+pub fn rank<'a>(todo: &'a TodoList, query: &str, limit: usize) -> Vec<Match<'a>> {
+    let query = words(query);
+    let mut matches: Vec<Match> = todo
+        .tasks()
+        .iter()
+        .enumerate()
+        .filter_map(|(i, task)| {
+            score_words(&query, &task.description).map(|score| Match {
+                index: i + 1,
+                task,
+                score,
+            })
+        })
+        .collect();
+    matches.sort_by_key(|found| {
+        (
+            std::cmp::Reverse(found.score),
+            found.task.description.chars().count(),
+            found.index,
+        )
+    });
+    matches.truncate(limit);
+    matches
+}
+
+// Lowercase words, with anything but letters and digits taken as a space
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn score_words(query: &[String], text: &str) -> Option<u32> {
+    if query.is_empty() {
+        return None;
+    }
+    let text = words(text);
+    let total: u32 = query
+        .iter()
+        .map(|word| text.iter().map(|w| word_score(word, w)).max().unwrap_or(0))
+        .sum();
+    let score = total / query.len() as u32;
+    (score >= MIN_SCORE).then_some(score)
+}
+
+// How closely one query word matches one word of the text, as in the table
+// at the top of the module
+fn word_score(query: &str, word: &str) -> u32 {
+    let length = query.chars().count();
+    if query == word {
+        return 100;
+    }
+    if word.starts_with(query) {
+        return 90;
+    }
+    if length >= 3 && word.contains(query) {
+        return 70;
+    }
+    if length >= 3 {
+        let typos = if length >= LONG_WORD { 2 } else { 1 };
+        let word_length = word.chars().count();
+        // Words whose lengths differ by more than that can't be close enough
+        if word_length.abs_diff(length) <= typos {
+            let distance = edit_distance(query, word);
+            if distance <= typos {
+                return 70 - 10 * distance as u32;
+            }
+        }
+        if word_length > length {
+            let start: String = word.chars().take(length).collect();
+            if edit_distance(query, &start) == 1 {
+                return 50;
+            }
+        }
+    }
+    if length >= 2 && is_anchored_subsequence(query, word) {
+        return 40;
+    }
+    0
+}
+
+// Whether the letters of `query` appear in `word` in order, the first of
+// them starting it
+fn is_anchored_subsequence(query: &str, word: &str) -> bool {
+    let mut letters = word.chars();
+    let mut query = query.chars();
+    if query.next() != letters.next() {
+        return false;
+    }
+    query.all(|c| letters.any(|l| l == c))
+}
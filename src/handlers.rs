@@ -0,0 +1,3387 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{self, IsTerminal, Write};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use rust_todo_cli::{
+    NAME, VERSION, backup,
+    changes::{Change, ChangeSet},
+    clipboard::{self, SystemClipboard},
+    command::{
+        COMMANDS, ClearTarget, Command, CommandSpec, CountTarget, Limit, OutputFormat, ParseError,
+        SyncAction, expand_filter,
+    },
+    completion,
+    date::{
+        Date, SECONDS_PER_DAY, Time, format_due, format_duration, format_timestamp, humanize_days,
+        now,
+    },
+    doctor::{Finding, Problem},
+    import::Format,
+    link,
+    listing::{
+        self, IndexedTask, Row, age_label, described, due_date, due_label, format_tags, has_link,
+        relative_due_at,
+    },
+    log, menu,
+    merge::{self, MergeReport, Side},
+    messages::message,
+    msg,
+    outcome::{self, Added, CommandOutcome, StatusChange},
+    random::{Rng, XorShift},
+    remote::{self, Expected},
+    report,
+    store::{ARCHIVE_FILE, Store},
+    todo::{
+        self, Estimate, ListFilter, Marker, Recurrence, Selector, Status, TRASH_LIMIT, Task,
+        TaskIndex, TaskRef, TodoError, TodoList,
+    },
+    view::ListView,
+    width::{display_width, pad_right, wrap},
+};
+use serde::Serialize;
+
+use crate::{
+    FLAGS,
+    output::{self, Color, Icon, fail, info, notice, show, warning},
+    pomodoro,
+    prompt::{choose, confirm, confirm_from},
+    review::handle_review,
+    script::handle_run,
+    table::{Column, Table},
+};
+
+// Run one parsed command against the list, printing its results
+pub fn execute(todo: &mut TodoList, store: &Store, view: &mut ListView, command: Command) {
+    log!(Debug, "running {:?}", command);
+    // Any change to the list makes the page cursor point at stale rows
+    if command.mutates() {
+        view.reset();
+    }
+
+    match command {
+        // Exit and reload only mean something inside the interactive loop
+        Command::Exit | Command::Reload(_) => {}
+        Command::Help(None) => print_help(),
+        Command::Help(Some(spec)) => print_command_help(spec),
+        Command::Version => print_version(store),
+        Command::Completions(shell) => {
+            // Scripts end in a newline of their own
+            let script = completion::script(shell, NAME, FLAGS);
+            show!("{}", script.strip_suffix('\n').unwrap_or(&script))
+        }
+        Command::List {
+            filter,
+            limit,
+            format,
+        } => list_tasks(todo, &filter, limit, format, view),
+        Command::More => handle_more(todo, view),
+        Command::Next(count) => handle_next(todo, count),
+        Command::Random(filter) => handle_random(todo, &filter, &mut XorShift::from_clock()),
+        Command::Stats => handle_stats(todo),
+        Command::Count(target) => handle_count(todo, target),
+        Command::Projects => handle_projects(todo),
+        Command::Contexts => handle_contexts(todo),
+        Command::Fuzzy(text) => handle_fuzzy(todo, &text),
+        Command::Today => handle_due_view(todo, false),
+        Command::Overdue => handle_due_view(todo, true),
+        Command::Agenda(days, empty) => handle_agenda(todo, days, empty),
+        Command::Due(selector, due) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_due(todo, task, due);
+            }
+        }
+        Command::Repeat(selector, recurrence) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_repeat(todo, task, recurrence);
+            }
+        }
+        Command::Snooze(selector, by) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_snooze(todo, task, by);
+            }
+        }
+        Command::Track(selector) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_track(todo, task);
+            }
+        }
+        Command::Estimate(selector, estimate) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_estimate(todo, task, estimate);
+            }
+        }
+        Command::Wait(selector, reason) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_wait(todo, task, reason);
+            }
+        }
+        Command::Delegate(selector, person) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_delegate(todo, task, person);
+            }
+        }
+        Command::Waiting(format) => handle_waiting(todo, format),
+        Command::Escalate(days) => handle_escalate(todo, days),
+        Command::Stop => handle_stop(todo),
+        Command::Review => handle_review(todo, store),
+        Command::Run(path, keep_going) => handle_run(todo, store, view, &path, keep_going),
+        Command::Week => handle_week(todo),
+        Command::Report(path, force) => handle_report(todo, &path, force),
+        Command::Chart => handle_chart(todo),
+        Command::Pomodoro(selector, minutes) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_pomodoro(todo, task, minutes);
+            }
+        }
+        Command::Show(selector, format) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_show(todo, task.index(), format);
+            }
+        }
+        Command::Open(selector, number) => {
+            if let Some(index) = resolve(todo, &selector) {
+                handle_open(todo, index, number);
+            }
+        }
+        Command::Copy(selector, format) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_copy(todo, task.index(), format);
+            }
+        }
+        Command::CopyList => handle_copy_list(todo),
+        Command::Add(description, skip_duplicates) => {
+            handle_add(todo, description, skip_duplicates)
+        }
+        Command::AddFrom(path, skip_duplicates) => handle_add_from(todo, &path, skip_duplicates),
+        Command::AddStdin(skip_duplicates) => handle_add_stdin(todo, skip_duplicates),
+        Command::AddSnippet(name, values, skip_duplicates) => {
+            handle_add_snippet(todo, &name, &values, skip_duplicates)
+        }
+        Command::Update(selector, status_str, force) => {
+            if let Some(index) = resolve(todo, &selector) {
+                handle_update(todo, index, &status_str, force);
+            }
+        }
+        Command::UpdateAll(filter, status, force) => handle_update_all(todo, filter, status, force),
+        Command::Remove(selector, yes) => {
+            if let Some(index) = resolve(todo, &selector) {
+                handle_remove(todo, store, index, yes);
+            }
+        }
+        Command::Done(indices, force) => handle_done(todo, indices, force),
+        Command::Toggle(indices, force) => handle_toggle(todo, indices, force),
+        Command::Reopen(selector) => handle_reopen(todo, &selector),
+        Command::ReopenLast => handle_reopen_last(todo),
+        Command::RecentlyCompleted => handle_recently_completed(todo),
+        Command::Sub(selector, description) => {
+            if let Some(parent) = resolve_task(todo, &selector) {
+                handle_sub(todo, parent, description);
+            }
+        }
+        Command::Tag(selector, tags) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_tag(todo, task, &tags, false);
+            }
+        }
+        Command::Untag(selector, tags) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_tag(todo, task, &tags, true);
+            }
+        }
+        Command::Mark(selector, marker) => {
+            if let Some(task) = resolve_task(todo, &selector) {
+                handle_mark(todo, task, marker);
+            }
+        }
+        Command::Append(selector, text) => {
+            if let Some(index) = resolve(todo, &selector) {
+                handle_append(todo, index, &text, false);
+            }
+        }
+        Command::Prepend(selector, text) => {
+            if let Some(index) = resolve(todo, &selector) {
+                handle_append(todo, index, &text, true);
+            }
+        }
+        Command::Blocks(index, on) => handle_blocks(todo, index, on),
+        Command::Clear(only, yes, dry_run) => handle_clear(todo, store, only, yes, dry_run),
+        Command::Archive => handle_archive(todo, store),
+        Command::Dedupe(dry_run) => handle_dedupe(todo, dry_run),
+        Command::Renumber(yes) => {
+            handle_renumber(todo, yes);
+        }
+        Command::Doctor(fix) => handle_doctor(todo, fix),
+        Command::Trash(format) => handle_trash(store, format),
+        Command::EmptyTrash(yes) => handle_empty_trash(store, yes),
+        Command::History(count) => handle_history(todo, count),
+        Command::ClearHistory(yes) => handle_clear_history(todo, yes),
+        Command::Restore(index) => handle_restore(todo, store, index),
+        Command::Archived(format) => handle_archived(store, format),
+        Command::ArchiveStats(format) => handle_archive_stats(store, format),
+        Command::TemplateSave(name, indices) => handle_template_save(todo, &name, &indices),
+        Command::TemplateApply(name) => handle_template_apply(todo, &name),
+        Command::TemplateList => handle_template_list(todo),
+        Command::TemplateDelete(name) => handle_template_delete(todo, &name),
+        Command::FilterSave(name, filter) => handle_filter_save(todo, &name, &filter),
+        Command::FilterList => handle_filter_list(todo),
+        Command::FilterDelete(name) => handle_filter_delete(todo, &name),
+        Command::SnippetSave(name, text) => handle_snippet_save(todo, &name, &text),
+        Command::SnippetList => handle_snippet_list(todo),
+        Command::SnippetDelete(name) => handle_snippet_delete(todo, &name),
+        Command::Save(force) => {
+            handle_save(todo, store, force);
+        }
+        Command::Merge(path, side) => handle_merge(todo, &path, side),
+        Command::TakeMerge(report, source) => take_merge(todo, *report, &source),
+        Command::Import(format, path) => handle_import(todo, format, &path),
+        Command::Sync(action, side) => handle_sync(todo, action, side),
+        Command::BackupCreate(path) => handle_backup_create(todo, store, &path),
+        Command::BackupRestore(path, yes) => {
+            handle_backup_restore(todo, store, &path, yes);
+        }
+    }
+}
+
+// Explain why a line of input was not understood
+pub fn report_parse_error(error: &ParseError) {
+    match error {
+        ParseError::Empty => {}
+        _ if output::json_errors() => output::report_error(error),
+        ParseError::UnknownCommand(..) | ParseError::AmbiguousCommand(..) => {
+            fail!("{} {}", Icon::Unknown, error);
+            info!("{} {}", Icon::Hint, msg!("hint.help"));
+        }
+        _ => fail!("{} {}", Icon::Warning, error),
+    }
+}
+
+// Find the task a selector names. Failures are reported here, listing the
+// candidates when text matches more than one task.
+pub fn resolve(todo: &TodoList, selector: &Selector) -> Option<TaskIndex> {
+    if let Selector::Pick(filter) = selector
+        && io::stdin().is_terminal()
+    {
+        return pick(todo, filter).map(|task| TaskIndex { task, sub: None });
+    }
+    match todo.select(selector) {
+        Ok(index) => Some(index),
+        Err(error) => {
+            output::report_error(&error);
+            if let (TodoError::AmbiguousMatch(..), Selector::Text(text)) = (&error, selector) {
+                for (index, task) in todo.matching(text) {
+                    fail!("  {}. {}", index, task.description);
+                }
+            }
+            None
+        }
+    }
+}
+
+// Ask which of the tasks matching a --pick filter is meant. Cancelling is
+// not a failure; the command just leaves everything as it was.
+fn pick(todo: &TodoList, filter: &ListFilter) -> Option<usize> {
+    if todo.filter(filter).is_empty() {
+        fail!("{} {}", Icon::Empty, msg!("pick.none"));
+        return None;
+    }
+    let picked = menu::select_task(
+        todo,
+        filter,
+        output::theme(),
+        &mut io::stdin().lock(),
+        &mut io::stdout(),
+    );
+    match picked {
+        Ok(Some(index)) => Some(index),
+        Ok(None) => {
+            info!("{}", msg!("pick.cancelled"));
+            None
+        }
+        Err(error) => {
+            output::report_error(&TodoError::from(error));
+            None
+        }
+    }
+}
+
+// Like `resolve`, for commands that only work on top-level tasks
+pub fn resolve_task(todo: &TodoList, selector: &Selector) -> Option<TaskRef> {
+    let index = resolve(todo, selector)?;
+    todo.select_task(&Selector::Index(index))
+        .map_err(|error| output::report_error(&error))
+        .ok()
+}
+
+// The selector for a task `resolve_task` already found
+fn chosen(task: TaskRef) -> Selector {
+    Selector::Index(TaskIndex {
+        task: task.index(),
+        sub: None,
+    })
+}
+
+// Run a command through the library, reporting why it failed
+fn run(todo: &mut TodoList, command: Command) -> Option<CommandOutcome<'_>> {
+    outcome::execute(todo, command)
+        .map_err(|error| output::report_error(&error))
+        .ok()
+}
+
+// Like `run`, for commands that only read the list, whose outcome leaves
+// it free to look things up in
+fn query(todo: &TodoList, command: Command) -> Option<CommandOutcome<'_>> {
+    outcome::query(todo, command)
+        .map_err(|error| output::report_error(&error))
+        .ok()
+}
+
+pub fn handle_add(todo: &mut TodoList, descriptions: Vec<String>, skip_duplicates: bool) {
+    let single = descriptions.len() == 1;
+    let added = if asks_about_duplicates(skip_duplicates) {
+        let added = add_asking(todo, descriptions);
+        if let (true, [(_, error)]) = (single, added.rejected.as_slice()) {
+            output::report_error(error);
+            return;
+        }
+        added
+    } else {
+        let command = Command::Add(descriptions, skip_duplicates);
+        let Some(CommandOutcome::Added(added)) = run(todo, command) else {
+            return;
+        };
+        report_duplicates(todo, &added);
+        added
+    };
+
+    if single {
+        if !added.added.is_empty() {
+            info!("{} {}", Icon::Success, msg!("add.added"));
+        }
+        return;
+    }
+    let count = added.added.len();
+    info!("{} {}", Icon::Success, msg!("add.count", count = count));
+    if !added.rejected.is_empty() {
+        let rejected: Vec<String> = added
+            .rejected
+            .iter()
+            .map(|(number, _)| number.to_string())
+            .collect();
+        warning!(
+            "{}  {}",
+            Icon::Warning,
+            msg!(
+                "add.skipped",
+                count = rejected.len(),
+                items = rejected.join(", ")
+            )
+        );
+    }
+}
+
+pub fn handle_add_from(todo: &mut TodoList, path: &str, skip_duplicates: bool) {
+    let added = if asks_about_duplicates(skip_duplicates) {
+        let file = match todo::read_task_file(path, todo.settings()) {
+            Ok(file) => file,
+            Err(error) => {
+                output::report_error(&error);
+                return;
+            }
+        };
+        Added {
+            rejected: file.failed,
+            ..add_asking(todo, file.lines)
+        }
+    } else {
+        let command = Command::AddFrom(path.to_string(), skip_duplicates);
+        let Some(CommandOutcome::Added(added)) = run(todo, command) else {
+            return;
+        };
+        report_duplicates(todo, &added);
+        added
+    };
+    let count = added.added.len();
+    let done = msg!("add.from_file", count = count, path = path);
+    info!("{} {}", Icon::Success, done);
+    for (line, error) in added.rejected {
+        let skipped = msg!("add.line_skipped", line = line, error = error);
+        warning!("{}  {}", Icon::Warning, skipped);
+    }
+}
+
+// Fill in a snippet and add what it makes, as `add` would the text typed out
+pub fn handle_add_snippet(
+    todo: &mut TodoList,
+    name: &str,
+    values: &[(String, String)],
+    skip_duplicates: bool,
+) {
+    let description = match todo.expand_snippet(name, values) {
+        Ok(description) => description,
+        Err(error) => return output::report_error(&error),
+    };
+    info!(
+        "{} {}",
+        Icon::Hint,
+        msg!("snippet.expanded", name = name, text = description)
+    );
+    handle_add(todo, vec![description], skip_duplicates);
+}
+
+// Whether to ask about each new task that repeats an open one rather
+// than go by --no-duplicates
+fn asks_about_duplicates(skip_duplicates: bool) -> bool {
+    !skip_duplicates && io::stdin().is_terminal() && !output::is_quiet()
+}
+
+// Add tasks one at a time, asking about each repeat as it comes up.
+// Descriptions that could not be tasks are numbered from 1 among the others.
+fn add_asking(todo: &mut TodoList, descriptions: Vec<String>) -> Added {
+    let mut added = Added::default();
+    for (i, description) in descriptions.into_iter().enumerate() {
+        match add_unless_duplicate(todo, description, false) {
+            Ok(index) => added.added.extend(index),
+            Err(error) => added.rejected.push((i + 1, error)),
+        }
+    }
+    added
+}
+
+// Warn about each new task that repeats an open one, as `add_unless_duplicate`
+// does for tasks it is given one at a time
+fn report_duplicates(todo: &TodoList, added: &Added) {
+    for duplicate in &added.duplicates {
+        let existing = todo
+            .get_task(duplicate.of)
+            .map_or("", |task| &task.description);
+        let warning = msg!(
+            "add.duplicate",
+            text = duplicate.description,
+            index = duplicate.of,
+            existing = existing
+        );
+        warning!("{}  {}", Icon::Warning, warning);
+        if !duplicate.added {
+            info!(
+                "{} {}",
+                Icon::Hint,
+                msg!("add.duplicate_left_out", index = duplicate.of)
+            );
+        }
+    }
+}
+
+// Add one task per line of standard input, until it ends. Lines are only
+// ever descriptions, so "remove old fence" is added rather than run. Lines
+// that can't be tasks are reported once the rest are in, and fail the command.
+pub fn handle_add_stdin(todo: &mut TodoList, skip_duplicates: bool) {
+    if io::stdin().is_terminal() {
+        info!("{} {}", Icon::Hint, msg!("add.stdin_prompt"));
+    }
+    match todo::read_tasks(io::stdin().lock(), todo.settings()) {
+        Ok(input) => {
+            let mut added = 0;
+            for line in input.lines {
+                match add_unless_duplicate(todo, line, skip_duplicates) {
+                    Ok(index) => added += usize::from(index.is_some()),
+                    Err(error) => output::report_error(&error),
+                }
+            }
+            info!("{} {}", Icon::Success, msg!("add.count", count = added));
+            for (line, error) in input.failed {
+                let skipped = msg!("add.line_skipped", line = line, error = error);
+                fail!("{}  {}", Icon::Warning, skipped);
+            }
+        }
+        Err(error) => output::report_error(&error),
+    }
+}
+
+// Add a task unless it repeats an open one and should be left out. A
+// repeat is warned about, naming the task it matches; on a terminal the
+// user decides, elsewhere it is added unless `skip` is set. Returns the
+// number of the new task, if it was added.
+fn add_unless_duplicate(
+    todo: &mut TodoList,
+    description: String,
+    skip: bool,
+) -> Result<Option<usize>, TodoError> {
+    let task = Task::builder(description.as_str()).build()?;
+    if let Some((index, existing)) = todo.find_similar(&task.description) {
+        let duplicate = msg!(
+            "add.duplicate",
+            text = task.description,
+            index = index,
+            existing = existing.description
+        );
+        warning!("{}  {}", Icon::Warning, duplicate);
+        let add = if skip {
+            false
+        } else if io::stdin().is_terminal() && !output::is_quiet() {
+            confirm_from(&msg!("add.ask_duplicate"), &mut io::stdin().lock())
+        } else {
+            true
+        };
+        if !add {
+            info!(
+                "{} {}",
+                Icon::Hint,
+                msg!("add.duplicate_left_out", index = index)
+            );
+            return Ok(None);
+        }
+    }
+    // The repeat is settled, so the library has nothing left to leave out
+    match outcome::execute(todo, Command::Add(vec![description], false))? {
+        CommandOutcome::Added(added) => Ok(added.added.first().copied()),
+        _ => Ok(None),
+    }
+}
+
+pub fn handle_tag(todo: &mut TodoList, task: TaskRef, tags: &[String], remove: bool) {
+    let command = if remove {
+        Command::Untag(chosen(task), tags.to_vec())
+    } else {
+        Command::Tag(chosen(task), tags.to_vec())
+    };
+    let Some(CommandOutcome::Edited(index, task)) = run(todo, command) else {
+        return;
+    };
+    match task.tags.as_slice() {
+        [] => info!("{} {}", Icon::Success, msg!("tag.none", index = index)),
+        tags => info!(
+            "{} {}",
+            Icon::Success,
+            msg!("task.text", index = index, text = format_tags(tags))
+        ),
+    }
+}
+
+pub fn handle_mark(todo: &mut TodoList, task: TaskRef, marker: Option<Marker>) {
+    let Some(CommandOutcome::Edited(index, task)) = run(todo, Command::Mark(chosen(task), marker))
+    else {
+        return;
+    };
+    let marker: Vec<&str> = [task.marker.as_deref(), task.color().map(Color::name)]
+        .into_iter()
+        .flatten()
+        .collect();
+    let done = match task.color() {
+        _ if marker.is_empty() => msg!("mark.cleared", index = index),
+        Some(color) => msg!(
+            "mark.marked",
+            index = index,
+            marker = output::paint(&marker.join(" "), color)
+        ),
+        None => msg!("mark.marked", index = index, marker = marker.join(" ")),
+    };
+    info!("{} {}", Icon::Success, done);
+}
+
+pub fn handle_append(todo: &mut TodoList, index: TaskIndex, text: &str, prepend: bool) {
+    let command = if prepend {
+        Command::Prepend(Selector::Index(index), text.to_string())
+    } else {
+        Command::Append(Selector::Index(index), text.to_string())
+    };
+    if let Some(CommandOutcome::Edited(index, task)) = run(todo, command) {
+        info!(
+            "{} {}",
+            Icon::Success,
+            msg!("task.text", index = index, text = task.description)
+        );
+    }
+}
+
+pub fn handle_update(todo: &mut TodoList, index: TaskIndex, status: &str, force: bool) {
+    let moves: Vec<(TaskIndex, Status)> = status
+        .parse::<Status>()
+        .map(|status| (index, status))
+        .into_iter()
+        .collect();
+    let force = forced(todo, &moves, force);
+    let command = Command::Update(Selector::Index(index), status.to_string(), force);
+    if let Some(CommandOutcome::StatusChanged(changes)) = run(todo, command) {
+        report_status_changes(todo, changes, |_| msg!("update.updated"));
+    }
+}
+
+pub fn handle_done(todo: &mut TodoList, selectors: Vec<Selector>, force: bool) {
+    let Some(targets) = resolve_all(todo, &selectors) else {
+        return;
+    };
+    let moves: Vec<(TaskIndex, Status)> =
+        targets.iter().map(|&at| (at, Status::Completed)).collect();
+    let force = forced(todo, &moves, force);
+    let selectors = targets.into_iter().map(Selector::Index).collect();
+    if let Some(CommandOutcome::StatusChanged(changes)) = run(todo, Command::Done(selectors, force))
+    {
+        report_status_changes(todo, changes, |change| {
+            msg!("task.marked_done", index = change.at)
+        });
+    }
+}
+
+pub fn handle_toggle(todo: &mut TodoList, selectors: Vec<Selector>, force: bool) {
+    let Some(targets) = resolve_all(todo, &selectors) else {
+        return;
+    };
+    let moves: Vec<(TaskIndex, Status)> = targets
+        .iter()
+        .filter_map(|&at| {
+            let task = todo.get_at(at).ok()?;
+            let to = if task.is_completed() {
+                Status::Todo
+            } else {
+                Status::Completed
+            };
+            Some((at, to))
+        })
+        .collect();
+    let force = forced(todo, &moves, force);
+    let selectors = targets.into_iter().map(Selector::Index).collect();
+    if let Some(CommandOutcome::StatusChanged(changes)) =
+        run(todo, Command::Toggle(selectors, force))
+    {
+        report_status_changes(todo, changes, |change| match change.status {
+            Status::Completed => msg!("toggle.now", index = change.at, status = change.status),
+            _ => msg!("toggle.reopened", index = change.at),
+        });
+    }
+}
+
+pub fn handle_reopen(todo: &mut TodoList, selector: &Selector) {
+    let Some(at) = resolve(todo, selector) else {
+        return;
+    };
+    reopen_and_report(todo, Command::Reopen(Selector::Index(at)));
+}
+
+pub fn handle_reopen_last(todo: &mut TodoList) {
+    reopen_and_report(todo, Command::ReopenLast);
+}
+
+// Run a `reopen` and say which task it reopened
+fn reopen_and_report(todo: &mut TodoList, command: Command) {
+    let Some(CommandOutcome::Reopened(at)) = run(todo, command) else {
+        return;
+    };
+    let description = todo.get_at(at).map_or("", |task| &task.description);
+    info!(
+        "{} {}",
+        Icon::Success,
+        msg!("reopen.reopened", index = at, description = description)
+    );
+}
+
+// Completed tasks bare `reopen` lists; older ones are only counted
+const RECENTLY_COMPLETED_SHOWN: usize = 10;
+
+// The latest completed tasks, for `reopen` to take one back
+pub fn handle_recently_completed(todo: &TodoList) {
+    let Some(CommandOutcome::RecentlyCompleted(done)) = query(todo, Command::RecentlyCompleted)
+    else {
+        return;
+    };
+    if done.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("reopen.none"));
+        return;
+    }
+    show!("\n{} {}", Icon::Header, msg!("reopen.title"));
+    output::separator();
+    let shown = &done[..done.len().min(RECENTLY_COMPLETED_SHOWN)];
+    let width = shown
+        .iter()
+        .map(|(at, _)| display_width(&format!("{}.", at)))
+        .max()
+        .unwrap_or(0);
+    for (at, task) in shown {
+        let when = task.completed_at.map_or(String::new(), |at| {
+            format!("  ({})", msg!("reopen.done_at", at = format_timestamp(at)))
+        });
+        show!(
+            "  {} {}{}",
+            pad_right(&format!("{}.", at), width),
+            task.description,
+            when
+        );
+    }
+    output::separator();
+    if done.len() > shown.len() {
+        info!(
+            "{} {}",
+            Icon::Hint,
+            msg!("reopen.more", count = done.len() - shown.len())
+        );
+    }
+    info!("{} {}", Icon::Hint, msg!("reopen.hint"));
+}
+
+// Look up every task before any is touched, so a typo in the last one
+// leaves the others as they were
+fn resolve_all(todo: &TodoList, selectors: &[Selector]) -> Option<Vec<TaskIndex>> {
+    selectors
+        .iter()
+        .map(|selector| resolve(todo, selector))
+        .collect()
+}
+
+// Whether status changes go ahead past strict transitions: when forced, or
+// when asked about the moves they don't allow at a terminal and told yes.
+// Otherwise those moves fail, saying which ones are allowed.
+fn forced(todo: &TodoList, moves: &[(TaskIndex, Status)], force: bool) -> bool {
+    let strict = todo.settings().strict_transitions;
+    if force || !strict || !io::stdin().is_terminal() || output::is_quiet() {
+        return force;
+    }
+    let moves: Vec<String> = moves
+        .iter()
+        .filter_map(|&(at, to)| {
+            let from = todo.get_at(at).ok()?.status;
+            let moving = msg!(
+                "transition.move",
+                index = at,
+                from = from.keyword(),
+                to = to.keyword()
+            );
+            (!from.is_valid_transition(to)).then_some(moving)
+        })
+        .collect();
+    !moves.is_empty() && confirm(&msg!("transition.confirm", moves = moves.join(", ")))
+}
+
+// One line per task whose status changed, after a warning when it was
+// completed while still blocked, followed by what completing it led to
+fn report_status_changes(
+    todo: &TodoList,
+    changes: Vec<Result<StatusChange, TodoError>>,
+    changed: impl Fn(&StatusChange) -> String,
+) {
+    for change in changes {
+        let change = match change {
+            Ok(change) => change,
+            Err(error) => {
+                output::report_error(&error);
+                continue;
+            }
+        };
+        if !change.blocked_by.is_empty() {
+            let numbers: Vec<String> = change.blocked_by.iter().map(usize::to_string).collect();
+            warning!(
+                "{}  {}",
+                Icon::Warning,
+                msg!(
+                    "blocked.still",
+                    index = change.at.task,
+                    count = numbers.len(),
+                    numbers = numbers.join(", ")
+                )
+            );
+        }
+        info!("{} {}", Icon::Success, changed(&change));
+        report_next_occurrence(todo, change.next);
+        if change.parent_ready {
+            info!(
+                "{} {}",
+                Icon::Hint,
+                msg!("sub.all_done", index = change.at.task)
+            );
+        }
+    }
+}
+
+// Lines of a change summary shown before the rest are only counted
+const MAX_CHANGE_LINES: usize = 20;
+
+// What a command did to many tasks at once, one line per task: + added in
+// green, - removed in red and ~ changed in yellow
+fn print_changes(changes: &ChangeSet) {
+    for change in changes.changes.iter().take(MAX_CHANGE_LINES) {
+        let color = match change {
+            Change::Added { .. } => Color::Green,
+            Change::Removed { .. } => Color::Red,
+            Change::Modified { .. } => Color::Yellow,
+        };
+        info!("  {}", output::paint(&change.to_string(), color));
+    }
+    if changes.len() > MAX_CHANGE_LINES {
+        let more = msg!("changes.more", count = changes.len() - MAX_CHANGE_LINES);
+        info!("  {}", more);
+    }
+}
+
+pub fn handle_update_all(
+    todo: &mut TodoList,
+    filter: Option<Status>,
+    new_status: Status,
+    force: bool,
+) {
+    let kind = filter.map(|status| status.to_string().to_lowercase());
+    let new = new_status.to_string().to_lowercase();
+    let Some(CommandOutcome::UpdatedAll {
+        count,
+        skipped,
+        changes,
+    }) = run(todo, Command::UpdateAll(filter, new_status, force))
+    else {
+        return;
+    };
+    match (count, kind) {
+        (0, None) => info!("{} {}", Icon::Empty, msg!("update_all.none", new = new)),
+        (0, Some(kind)) => info!(
+            "{} {}",
+            Icon::Empty,
+            msg!("update_all.none_kind", kind = kind, new = new)
+        ),
+        (count, None) => info!(
+            "{} {}",
+            Icon::Success,
+            msg!("update_all.marked", count = count, new = new)
+        ),
+        (count, Some(kind)) => info!(
+            "{} {}",
+            Icon::Success,
+            msg!(
+                "update_all.marked_kind",
+                count = count,
+                kind = kind,
+                new = new
+            )
+        ),
+    }
+    print_changes(&changes);
+    if skipped > 0 {
+        info!(
+            "{} {}",
+            Icon::Hint,
+            msg!("update_all.skipped", count = skipped, new = new)
+        );
+    }
+}
+
+pub fn handle_blocks(todo: &mut TodoList, index: usize, on: usize) {
+    if run(todo, Command::Blocks(index, on)).is_some() {
+        info!(
+            "{} {}",
+            Icon::Blocked,
+            msg!("blocked.added", index = index, on = on)
+        );
+    }
+}
+
+pub fn handle_sub(todo: &mut TodoList, parent: TaskRef, description: String) {
+    if let Some(CommandOutcome::SubtaskAdded(index)) =
+        run(todo, Command::Sub(chosen(parent), description))
+    {
+        info!("{} {}", Icon::Success, msg!("sub.added", index = index));
+    }
+}
+
+fn report_next_occurrence(todo: &TodoList, next: Option<usize>) {
+    if let Some(index) = next
+        && let Ok(task) = todo.get_task(index)
+        && let Some(due) = due_date(task)
+    {
+        info!(
+            "{} {}",
+            Icon::Repeat,
+            msg!("repeat.next", index = index, due = due)
+        );
+    }
+}
+
+pub fn handle_repeat(todo: &mut TodoList, task: TaskRef, recurrence: Option<Recurrence>) {
+    let command = Command::Repeat(chosen(task), recurrence);
+    let Some(CommandOutcome::Edited(index, task)) = run(todo, command) else {
+        return;
+    };
+    match task.recurrence {
+        Some(recurrence) => info!(
+            "{} {}",
+            Icon::Repeat,
+            msg!("repeat.set", index = index, recurrence = recurrence)
+        ),
+        None => info!("{} {}", Icon::Success, msg!("repeat.off", index = index)),
+    }
+}
+
+pub fn handle_remove(todo: &mut TodoList, store: &Store, index: TaskIndex, yes: bool) {
+    let description = match todo.get_at(index) {
+        Ok(task) => task.description.clone(),
+        Err(error) => {
+            output::report_error(&error);
+            return;
+        }
+    };
+    if !yes
+        && !confirm(&msg!(
+            "remove.ask",
+            index = index,
+            description = description
+        ))
+    {
+        info!("{} {}", Icon::Hint, msg!("remove.cancelled"));
+        return;
+    }
+
+    let Some(CommandOutcome::Removed(task)) =
+        run(todo, Command::Remove(Selector::Index(index), true))
+    else {
+        return;
+    };
+    if task.subtasks.is_empty() {
+        info!(
+            "{} {}",
+            Icon::Success,
+            msg!("remove.removed", description = task.description)
+        );
+    } else {
+        info!(
+            "{} {}",
+            Icon::Success,
+            msg!(
+                "remove.removed_with_subtasks",
+                description = task.description,
+                count = task.subtasks.len()
+            )
+        );
+    }
+
+    match store.move_to_trash(vec![*task]) {
+        Ok(position) => info!(
+            "{} {}",
+            Icon::Hint,
+            msg!("remove.undo", position = position)
+        ),
+        Err(error) => warning!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("remove.not_kept", path = store.trash, error = error)
+        ),
+    }
+}
+
+pub fn handle_trash(store: &Store, format: OutputFormat) {
+    let trash = match store.load_trash() {
+        Ok(trash) => trash,
+        Err(error) => {
+            fail!("{}", msg!("error.read", path = store.trash, error = error));
+            return;
+        }
+    };
+    print_saved_list(&trash, &msg!("title.trash"), format);
+    if format == OutputFormat::Human && !trash.is_empty() {
+        info!("{} {}", Icon::Hint, msg!("trash.hint"));
+    }
+}
+
+pub fn handle_restore(todo: &mut TodoList, store: &Store, index: usize) {
+    let mut trash = match store.load_trash() {
+        Ok(trash) => trash,
+        Err(error) => {
+            fail!("{}", msg!("error.read", path = store.trash, error = error));
+            return;
+        }
+    };
+    let task = match trash
+        .task_ref(index)
+        .and_then(|task| trash.remove_task(task))
+    {
+        Ok(task) => task,
+        Err(error) => {
+            output::report_error(&error);
+            return;
+        }
+    };
+    // Saving the trash first means a failure leaves the task where it was
+    if let Err(error) = store.save_trash(&trash) {
+        fail!("{}", msg!("error.write", path = store.trash, error = error));
+        return;
+    }
+    let description = task.description.clone();
+    let position = todo.restore(task);
+    info!(
+        "{} {}",
+        Icon::Success,
+        msg!(
+            "restore.restored",
+            description = description,
+            position = position
+        )
+    );
+}
+
+pub fn handle_empty_trash(store: &Store, yes: bool) {
+    let count = match store.load_trash() {
+        Ok(trash) => trash.len(),
+        // A corrupt trash is worth emptying too
+        Err(_) => 0,
+    };
+    if !yes && !confirm(&msg!("trash.ask_empty", count = count)) {
+        info!("{} {}", Icon::Hint, msg!("trash.kept"));
+        return;
+    }
+    match store.save_trash(&store.new_list()) {
+        Ok(_) => info!(
+            "{}  {}",
+            Icon::Cleared,
+            msg!("trash.emptied", count = count)
+        ),
+        Err(error) => fail!("{}", msg!("error.write", path = store.trash, error = error)),
+    }
+}
+
+fn handle_backup_create(todo: &TodoList, store: &Store, path: &str) {
+    let created = backup::create(store, todo).and_then(|bundle| {
+        bundle.write(path, &store.settings)?;
+        Ok(bundle.names().join(", "))
+    });
+    match created {
+        Ok(sections) => info!(
+            "{} {}",
+            Icon::Success,
+            msg!("backup.created", sections = sections, path = path)
+        ),
+        Err(error) => fail!("{}", msg!("error.write", path = path, error = error)),
+    }
+}
+
+// Put the files back as the backup at `path` has them and read the list
+// again from them, returning whether that happened
+pub fn handle_backup_restore(todo: &mut TodoList, store: &Store, path: &str, yes: bool) -> bool {
+    let bundle = match backup::read(path) {
+        Ok(bundle) => bundle,
+        Err(error) => {
+            fail!(
+                "{}  {}",
+                Icon::Warning,
+                msg!("file.unreadable", path = path, error = error)
+            );
+            return false;
+        }
+    };
+    if !yes && !confirm(&msg!("backup.ask_restore", path = path)) {
+        info!("{} {}", Icon::Hint, msg!("backup.kept"));
+        return false;
+    }
+    let restored = match backup::restore(store, &bundle, todo) {
+        Ok(restored) => restored,
+        Err(error) => {
+            fail!("{}  {}", Icon::Warning, error);
+            return false;
+        }
+    };
+    for section in &restored.skipped {
+        warning!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("backup.skipped", section = section)
+        );
+    }
+    match store.load() {
+        Ok(mut list) => {
+            load_history(&mut list, store);
+            *todo = list;
+        }
+        Err(error) => warning!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("file.unreadable", path = store.tasks, error = error)
+        ),
+    }
+    info!(
+        "{} {}",
+        Icon::Reload,
+        msg!(
+            "backup.restored",
+            sections = restored.sections.join(", "),
+            path = path,
+            previous = restored.previous
+        )
+    );
+    true
+}
+
+pub fn handle_history(todo: &TodoList, count: Option<usize>) {
+    let Some(CommandOutcome::History(entries)) = query(todo, Command::History(count)) else {
+        return;
+    };
+    if entries.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("history.empty"));
+        return;
+    }
+    show!("{} {}", Icon::Header, msg!("history.title"));
+    for entry in entries {
+        show!("  {}", entry);
+    }
+}
+
+pub fn handle_clear_history(todo: &mut TodoList, yes: bool) {
+    let count = todo.log().len();
+    if !yes && !confirm(&msg!("history.ask_clear", count = count)) {
+        info!("{} {}", Icon::Hint, msg!("history.kept"));
+        return;
+    }
+    if let Some(CommandOutcome::HistoryCleared(count)) = run(todo, Command::ClearHistory(true)) {
+        info!(
+            "{}  {}",
+            Icon::Cleared,
+            msg!("history.cleared", count = count)
+        );
+    }
+}
+
+// Read the history saved next to the tasks into a freshly loaded list
+pub fn load_history(todo: &mut TodoList, store: &Store) {
+    if let Err(error) = store.load_history(todo) {
+        notice!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("history.unreadable", path = store.log, error = error)
+        );
+    }
+}
+
+pub fn handle_doctor(todo: &mut TodoList, fix: bool) {
+    let Some(CommandOutcome::Checked { fixed, left }) = run(todo, Command::Doctor(fix)) else {
+        return;
+    };
+    if fixed.is_empty() && left.is_empty() {
+        info!(
+            "{} {}",
+            Icon::Success,
+            msg!("doctor.healthy", count = todo.len())
+        );
+        return;
+    }
+    if !fixed.is_empty() {
+        info!(
+            "{} {}",
+            Icon::Success,
+            msg!("doctor.fixed", count = fixed.len())
+        );
+        for finding in &fixed {
+            info!("  - {}", describe_finding(finding));
+        }
+    }
+
+    for finding in &left {
+        warning!("{}  {}", Icon::Warning, describe_finding(finding));
+    }
+    let fixable = left
+        .iter()
+        .filter(|finding| finding.problem.fixable())
+        .count();
+    if fixable > 0 {
+        info!("{} {}", Icon::Hint, msg!("doctor.can_fix", count = fixable));
+    }
+    if left.len() > fixable {
+        let by_hand = left.len() - fixable;
+        info!("{} {}", Icon::Hint, msg!("doctor.by_hand", count = by_hand));
+    }
+}
+
+// A line like "Task 3 has no description"
+fn describe_finding(finding: &Finding) -> String {
+    let index = finding.at;
+    match finding.problem {
+        Problem::DuplicateId { id, first } => {
+            msg!("doctor.duplicate_id", index = index, id = id, first = first)
+        }
+        Problem::EmptyDescription => msg!("doctor.empty_description", index = index),
+        Problem::CompletedBeforeCreated => {
+            msg!("doctor.completed_before_created", index = index)
+        }
+        Problem::DanglingDependency(id) => {
+            msg!("doctor.dangling_dependency", index = index, id = id)
+        }
+    }
+}
+
+// Mention problems in a freshly loaded list; silent when there are none
+pub fn warn_problems(todo: &TodoList) {
+    let count = todo.problems().len();
+    if count > 0 {
+        notice!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("doctor.startup", count = count)
+        );
+    }
+}
+
+pub fn handle_dedupe(todo: &mut TodoList, dry_run: bool) {
+    let Some(CommandOutcome::Deduplicated { groups, changes }) =
+        run(todo, Command::Dedupe(dry_run))
+    else {
+        return;
+    };
+    if groups.is_empty() {
+        info!("{} {}", Icon::Success, msg!("dedupe.none"));
+        return;
+    }
+
+    let count: usize = groups.iter().map(|group| group.removed.len()).sum();
+    if !dry_run {
+        info!(
+            "{} {}",
+            Icon::Success,
+            msg!("dedupe.merged", count = count, groups = groups.len())
+        );
+        print_changes(&changes);
+        return;
+    }
+
+    for group in &groups {
+        let removed: Vec<String> = group.removed.iter().map(usize::to_string).collect();
+        let line = msg!(
+            "dedupe.would_remove",
+            description = group.description,
+            kept = group.kept,
+            removed = removed.join(", ")
+        );
+        show!("  {}", line);
+    }
+    info!("{} {}", Icon::Hint, msg!("dedupe.found", count = count));
+}
+
+// Show the IDs that would change, then change them once agreed to. Returns
+// the (old, new) pairs changed, for a session to follow in its own copies.
+pub fn handle_renumber(todo: &mut TodoList, yes: bool) -> Vec<(u64, u64)> {
+    let renumbering = match todo.renumbering() {
+        Ok(renumbering) => renumbering,
+        Err(error) => {
+            output::report_error(&error);
+            return Vec::new();
+        }
+    };
+    if renumbering.is_empty() {
+        info!(
+            "{} {}",
+            Icon::Success,
+            msg!("renumber.compact", count = todo.len())
+        );
+        return Vec::new();
+    }
+
+    let changed: HashSet<u64> = renumbering.iter().map(|(old, _)| *old).collect();
+    let moving = todo
+        .tasks()
+        .iter()
+        .filter(|task| changed.contains(&task.id));
+    for ((old, new), task) in renumbering.iter().zip(moving) {
+        let line = msg!(
+            "renumber.change",
+            old = old,
+            new = new,
+            description = task.description
+        );
+        show!("  {}", line);
+    }
+    let question = msg!("renumber.ask", count = renumbering.len());
+    if !yes && !confirm(&question) {
+        info!("{} {}", Icon::Hint, msg!("renumber.cancelled"));
+        return Vec::new();
+    }
+
+    let Some(CommandOutcome::Renumbered(renumbered)) = run(todo, Command::Renumber(true)) else {
+        return Vec::new();
+    };
+    // The last pick is remembered by ID
+    LAST_PICK.store(0, Ordering::Relaxed);
+    info!(
+        "{} {}",
+        Icon::Success,
+        msg!(
+            "renumber.done",
+            count = renumbered.len(),
+            total = todo.len()
+        )
+    );
+    renumbered
+}
+
+pub fn handle_clear(
+    todo: &mut TodoList,
+    store: &Store,
+    target: Option<ClearTarget>,
+    yes: bool,
+    dry_run: bool,
+) {
+    let kind = match target {
+        None => Some(msg!("clear.closed")),
+        Some(ClearTarget::All) => None,
+        Some(ClearTarget::Status(status)) => Some(status.to_string().to_lowercase()),
+    };
+    let count = outcome::to_clear(todo, target).len();
+    if count == 0 {
+        let none = match &kind {
+            Some(kind) => msg!("clear.none_kind", kind = kind),
+            None => msg!("clear.none"),
+        };
+        warning!("{}  {}", Icon::Warning, none);
+        return;
+    }
+    if dry_run {
+        let Some(CommandOutcome::Tasks(listed)) = query(todo, Command::Clear(target, yes, true))
+        else {
+            return;
+        };
+        let changes = ChangeSet {
+            changes: listed
+                .iter()
+                .map(|(index, task)| Change::Removed {
+                    index: *index,
+                    description: task.description.clone(),
+                })
+                .collect(),
+        };
+        print_changes(&changes);
+        let would = match &kind {
+            Some(kind) => msg!("clear.would_clear_kind", count = listed.len(), kind = kind),
+            None => msg!("clear.would_clear", count = listed.len()),
+        };
+        info!("{} {}", Icon::Hint, would);
+        return;
+    }
+
+    let question = match &kind {
+        Some(kind) => msg!("clear.ask_kind", count = count, kind = kind),
+        None => msg!("clear.ask_all", count = count),
+    };
+    if !yes && !confirm(&question) {
+        info!("{} {}", Icon::Hint, msg!("clear.cancelled"));
+        return;
+    }
+
+    let before = todo.clone();
+    let Some(CommandOutcome::Cleared { tasks, changes }) =
+        run(todo, Command::Clear(target, true, false))
+    else {
+        return;
+    };
+    // A list emptied over a full file is saved before the trash takes the
+    // tasks, so a failed save leaves both as they were. Clearing was agreed
+    // to, by --yes or by answering, which is the go-ahead the wipe guard
+    // would otherwise ask for; the file is still copied aside first.
+    if store.would_wipe(todo).is_some() {
+        match store.save_anyway(todo) {
+            Ok(()) => info!(
+                "{} {}",
+                Icon::Hint,
+                msg!("save.backed_up", path = store.wipe_backup())
+            ),
+            Err(error) => {
+                *todo = before;
+                fail!(
+                    "{}  {}",
+                    Icon::Warning,
+                    msg!("session.save_failed", error = error)
+                );
+                info!("{} {}", Icon::Hint, msg!("clear.cancelled"));
+                return;
+            }
+        }
+    }
+    let count = tasks.len();
+    let done = match &kind {
+        Some(kind) => msg!("clear.cleared_kind", count = count, kind = kind),
+        None => msg!("clear.cleared", count = count),
+    };
+    info!("{}  {}", Icon::Cleared, done);
+    print_changes(&changes);
+
+    match store.move_to_trash(tasks) {
+        Ok(_) if count > TRASH_LIMIT => info!(
+            "{} {}",
+            Icon::Hint,
+            msg!("clear.restorable_last", limit = TRASH_LIMIT)
+        ),
+        Ok(_) => info!("{} {}", Icon::Hint, msg!("clear.restorable")),
+        Err(error) => warning!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("clear.not_kept", path = store.trash, error = error)
+        ),
+    }
+}
+
+pub fn handle_archive(todo: &mut TodoList, store: &Store) {
+    let completed: Vec<Task> = todo
+        .filter_by_status(Status::Completed)
+        .map(|(_, task)| task.clone())
+        .collect();
+    if completed.is_empty() {
+        warning!("{}  {}", Icon::Warning, msg!("archive.none"));
+        return;
+    }
+
+    // Tasks only leave the list once the archive holds them safely
+    if let Err(error) = store.archive(completed) {
+        let error = msg!("archive.failed", path = store.archive, error = error);
+        fail!("{}", error);
+        info!("{} {}", Icon::Hint, msg!("archive.cancelled"));
+        return;
+    }
+
+    let before = todo.tasks().to_vec();
+    let archived = todo.archive_completed();
+    info!(
+        "{}  {}",
+        Icon::Cleared,
+        msg!(
+            "archive.archived",
+            count = archived.len(),
+            path = store.archive
+        )
+    );
+    print_changes(&ChangeSet::between(&before, todo.tasks()));
+}
+
+// Move tasks completed more than `days` days ago to the archive at startup,
+// as --auto-archive-after asks; true when any moved. As with `archive`, they
+// only leave the list once the archive holds them, so an archive that is
+// missing is started while one that can't be read or written keeps them here.
+// One-shot commands pass `report: false` so nothing comes before their output.
+pub fn auto_archive(todo: &mut TodoList, store: &Store, days: u64, report: bool) -> bool {
+    if days == 0 {
+        return false;
+    }
+    let numbers = todo.archivable(Date::today(), days);
+    log!(Debug, "auto-archiving {:?} after {} days", numbers, days);
+    if numbers.is_empty() {
+        return false;
+    }
+    // Emptying the list would stop it being saved, leaving the tasks in both
+    // files to be archived again next time
+    if numbers.len() == todo.len() && !store.force && store.would_wipe(&TodoList::new()).is_some() {
+        log!(Debug, "not auto-archiving: the list would be left empty");
+        return false;
+    }
+    let tasks = numbers
+        .iter()
+        .map(|&index| todo[index - 1].clone())
+        .collect();
+    if let Err(error) = store.archive(tasks) {
+        notice!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("archive.auto_failed", path = store.archive, error = error)
+        );
+        return false;
+    }
+    let archived = todo.archive_tasks(&numbers);
+    if report {
+        info!(
+            "{} {}",
+            Icon::Archive,
+            msg!("archive.auto", count = archived.len(), days = days)
+        );
+    }
+    true
+}
+
+pub fn handle_archived(store: &Store, format: OutputFormat) {
+    match store.load_archive() {
+        Ok(archive) => print_saved_list(&archive, &msg!("title.archived"), format),
+        Err(error) => fail!(
+            "{}",
+            msg!("error.read", path = store.archive, error = error)
+        ),
+    }
+}
+
+pub fn handle_archive_stats(store: &Store, format: OutputFormat) {
+    let stats = match store.archive_stats() {
+        Ok(stats) => stats,
+        Err(error) => {
+            fail!(
+                "{}",
+                msg!("error.read", path = store.archive, error = error)
+            );
+            return;
+        }
+    };
+    if format == OutputFormat::Json {
+        print_json(&stats);
+        return;
+    }
+    if stats.tasks == 0 {
+        info!("{} {}", Icon::Empty, msg!("saved.empty"));
+        return;
+    }
+
+    let label = |key: &'static str| pad_right(&message(key, &[]), 13);
+    show!(
+        "\n{} {}",
+        Icon::Stats,
+        msg!("archive.stats_title", path = store.archive)
+    );
+    output::separator();
+    show!("  {}{:>7}", label("stats.total"), stats.tasks);
+    show!("  {}{:>7}", label("stats.done"), stats.completed);
+    show!("  {}{:>7}", label("stats.cancelled"), stats.cancelled);
+    if stats.other > 0 {
+        show!("  {}{:>7}", label("archive.other"), stats.other);
+    }
+    if stats.subtasks > 0 {
+        show!("  {}{:>7}", label("archive.subtasks"), stats.subtasks);
+    }
+    if let (Some(first), Some(last)) = (stats.first_completed, stats.last_completed) {
+        show!(
+            "  {}",
+            msg!(
+                "archive.span",
+                first = format_timestamp(first),
+                last = format_timestamp(last)
+            )
+        );
+    }
+    show!(
+        "  {}",
+        msg!("archive.size", size = format_bytes(stats.bytes))
+    );
+}
+
+// A file size in the largest unit that keeps it above 1
+fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_000 => format!("{} B", bytes),
+        1_000..1_000_000 => format!("{:.1} KB", bytes as f64 / 1e3),
+        _ => format!("{:.1} MB", bytes as f64 / 1e6),
+    }
+}
+
+// Read-only listing of a side file like the archive or the trash
+fn print_saved_list(list: &TodoList, title: &str, format: OutputFormat) {
+    if format == OutputFormat::Json {
+        // Written as it goes rather than gathered into rows first
+        print_json(&SavedRows(list));
+        return;
+    }
+
+    let tasks: Vec<(usize, &Task)> = list.list_tasks().collect();
+    if tasks.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("saved.empty"));
+        return;
+    }
+    print_rows(list, title, &tasks, &absolute_due, &no_highlights);
+}
+
+pub fn handle_template_save(todo: &mut TodoList, name: &str, indices: &[usize]) {
+    let command = Command::TemplateSave(name.to_string(), indices.to_vec());
+    if let Some(CommandOutcome::TemplateSaved { replaced }) = run(todo, command) {
+        let count = indices.len();
+        let saved = if replaced {
+            msg!("template.replaced", name = name, count = count)
+        } else {
+            msg!("template.saved", name = name, count = count)
+        };
+        info!("{} {}", Icon::Success, saved);
+    }
+}
+
+pub fn handle_template_apply(todo: &mut TodoList, name: &str) {
+    let command = Command::TemplateApply(name.to_string());
+    if let Some(CommandOutcome::TemplateApplied(added)) = run(todo, command) {
+        info!(
+            "{} {}",
+            Icon::Success,
+            msg!("template.applied", count = added, name = name)
+        );
+    }
+}
+
+pub fn handle_template_list(todo: &TodoList) {
+    let Some(CommandOutcome::Templates(templates)) = query(todo, Command::TemplateList) else {
+        return;
+    };
+    if templates.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("template.none"));
+        return;
+    }
+    show!("\n{} {}", Icon::Header, msg!("template.title"));
+    output::separator();
+    for (name, descriptions) in templates {
+        let count = msg!("template.count", count = descriptions.len());
+        show!("  {} ({})", name, count);
+        for description in descriptions {
+            show!("     {}", description);
+        }
+    }
+    output::separator();
+}
+
+pub fn handle_template_delete(todo: &mut TodoList, name: &str) {
+    let command = Command::TemplateDelete(name.to_string());
+    if run(todo, command).is_some() {
+        info!(
+            "{}  {}",
+            Icon::Cleared,
+            msg!("template.deleted", name = name)
+        );
+    }
+}
+
+pub fn handle_filter_save(todo: &mut TodoList, name: &str, filter: &str) {
+    let command = Command::FilterSave(name.to_string(), filter.to_string());
+    if let Some(CommandOutcome::FilterSaved { replaced }) = run(todo, command) {
+        let saved = if replaced {
+            msg!("filter.replaced", name = name, filter = filter)
+        } else {
+            msg!("filter.saved", name = name, filter = filter)
+        };
+        info!("{} {}", Icon::Success, saved);
+    }
+}
+
+pub fn handle_filter_list(todo: &TodoList) {
+    let Some(CommandOutcome::Filters(filters)) = query(todo, Command::FilterList) else {
+        return;
+    };
+    if filters.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("filter.none"));
+        return;
+    }
+    show!("\n{} {}", Icon::Header, msg!("filter.title"));
+    output::separator();
+    let width = filters
+        .keys()
+        .map(|name| display_width(name))
+        .max()
+        .unwrap_or(0);
+    for (name, filter) in filters {
+        show!("  {}  {}", pad_right(name, width), filter);
+    }
+    output::separator();
+}
+
+pub fn handle_filter_delete(todo: &mut TodoList, name: &str) {
+    let command = Command::FilterDelete(name.to_string());
+    if run(todo, command).is_some() {
+        info!("{}  {}", Icon::Cleared, msg!("filter.deleted", name = name));
+    }
+}
+
+pub fn handle_snippet_save(todo: &mut TodoList, name: &str, text: &str) {
+    let command = Command::SnippetSave(name.to_string(), text.to_string());
+    if let Some(CommandOutcome::SnippetSaved { replaced }) = run(todo, command) {
+        let saved = if replaced {
+            msg!("snippet.replaced", name = name, text = text)
+        } else {
+            msg!("snippet.saved", name = name, text = text)
+        };
+        info!("{} {}", Icon::Success, saved);
+    }
+}
+
+pub fn handle_snippet_list(todo: &TodoList) {
+    let Some(CommandOutcome::Snippets(snippets)) = query(todo, Command::SnippetList) else {
+        return;
+    };
+    if snippets.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("snippet.none"));
+        return;
+    }
+    show!("\n{} {}", Icon::Header, msg!("snippet.title"));
+    output::separator();
+    let width = snippets
+        .keys()
+        .map(|name| display_width(name) + 1)
+        .max()
+        .unwrap_or(0);
+    for (name, text) in snippets {
+        show!("  {}  {}", pad_right(&format!("@{}", name), width), text);
+    }
+    output::separator();
+}
+
+pub fn handle_snippet_delete(todo: &mut TodoList, name: &str) {
+    let command = Command::SnippetDelete(name.to_string());
+    if run(todo, command).is_some() {
+        info!(
+            "{}  {}",
+            Icon::Cleared,
+            msg!("snippet.deleted", name = name)
+        );
+    }
+}
+
+// Warn about saved filters that `list` reads as its own words instead,
+// as after a newer version took the name for a status
+pub fn warn_shadowed_filters(todo: &TodoList) {
+    for name in todo.shadowed_filters() {
+        notice!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("warn.filter_shadowed", name = name)
+        );
+    }
+}
+
+// Name, version and the data file, for `version` and `--version`
+pub fn print_version(store: &Store) {
+    show!("{} {}", NAME, VERSION);
+    let path = std::path::absolute(&store.tasks)
+        .map_or(store.tasks.clone(), |path| path.display().to_string());
+    show!("{}", msg!("version.data_file", path = path));
+}
+
+// Save, asking first when an empty list would replace a file full of tasks.
+// Without a terminal to ask on that save is turned down; --force is the way
+// past it.
+pub fn save_checked(todo: &TodoList, store: &Store) -> Result<(), TodoError> {
+    let replacing = store.is_unreadable();
+    match store.save(todo) {
+        Err(TodoError::WouldWipe(count)) if io::stdin().is_terminal() && !output::is_quiet() => {
+            let backup = store.wipe_backup();
+            if !confirm(&msg!("save.ask_wipe", count = count, backup = backup)) {
+                return Err(TodoError::WouldWipe(count));
+            }
+            store.save_anyway(todo)?;
+            info!("{} {}", Icon::Hint, msg!("save.backed_up", path = backup));
+            Ok(())
+        }
+        Ok(()) if replacing => {
+            report_unreadable_copy(store);
+            Ok(())
+        }
+        result => result,
+    }
+}
+
+// Say where the file that could not be read went once a save replaced it
+pub fn report_unreadable_copy(store: &Store) {
+    info!(
+        "{} {}",
+        Icon::Hint,
+        msg!("save.backed_up", path = store.unreadable_copy())
+    );
+}
+
+// Save now, with --force over changes made to the file elsewhere,
+// returning whether that worked
+pub fn handle_save(todo: &TodoList, store: &Store, force: bool) -> bool {
+    if force {
+        store.mark_read();
+    }
+    report_save(save_checked(todo, store), store)
+}
+
+// Say how `save` went, returning whether it worked
+pub fn report_save(saved: Result<(), TodoError>, store: &Store) -> bool {
+    match saved {
+        Ok(_) => {
+            show!(" {}", msg!("save.saved", path = store.tasks));
+            true
+        }
+        Err(error) => {
+            fail!("{}", msg!("save.failed", error = error));
+            false
+        }
+    }
+}
+
+// Combine the list with the copy in a file, asking about each conflict
+// unless a side was given
+fn handle_merge(todo: &mut TodoList, path: &str, side: Option<Side>) {
+    let theirs = match merge::read_tasks(path) {
+        Ok(tasks) => tasks,
+        Err(error) => {
+            fail!(
+                "{}  {}",
+                Icon::Warning,
+                msg!("file.unreadable", path = path, error = error)
+            );
+            return;
+        }
+    };
+    let report = merge::merge(todo.tasks(), &theirs);
+    apply_merge(todo, report, path, side);
+}
+
+// Bring in another tool's export. A file with any error changes nothing.
+fn handle_import(todo: &mut TodoList, format: Format, path: &str) {
+    // Reading the file is all that can fail
+    let (summary, changes) = match outcome::execute(todo, Command::Import(format, path.to_string()))
+    {
+        Ok(CommandOutcome::Imported { summary, changes }) => (summary, changes),
+        Ok(_) => return,
+        Err(error) => {
+            fail!(
+                "{}  {}",
+                Icon::Warning,
+                msg!("file.unreadable", path = path, error = error)
+            );
+            return;
+        }
+    };
+    info!(
+        "{} {}",
+        Icon::Success,
+        msg!(
+            "import.imported",
+            path = path,
+            created = summary.created,
+            updated = summary.updated,
+            skipped = summary.skipped
+        )
+    );
+    print_changes(&changes);
+}
+
+// Settle a merge's conflicts, asking about each unless a side was given,
+// then take its tasks
+fn apply_merge(todo: &mut TodoList, mut report: MergeReport, source: &str, side: Option<Side>) {
+    match side {
+        Some(side) => report.resolve_all(side),
+        None => {
+            for n in 0..report.conflicts.len() {
+                let conflict = &report.conflicts[n];
+                info!(
+                    "{} {}",
+                    Icon::Unknown,
+                    msg!("merge.conflict", index = conflict.at)
+                );
+                info!("   ours:   {}", conflict.ours);
+                info!("   theirs: {}", conflict.theirs);
+                if choose(&msg!("merge.keep_which"), &["ours", "theirs"]) == Some(1) {
+                    report.resolve(n, Side::Theirs);
+                }
+            }
+        }
+    }
+
+    info!(
+        "{} {}",
+        Icon::Success,
+        msg!(
+            "merge.merged",
+            source = source,
+            matched = report.matched,
+            updated = report.updated,
+            only_ours = report.only_ours,
+            only_theirs = report.only_theirs
+        )
+    );
+    if side.is_none() && !report.conflicts.is_empty() && !io::stdin().is_terminal() {
+        info!("{} {}", Icon::Hint, msg!("merge.kept_ours"));
+    }
+    take_merge(todo, report, source);
+}
+
+// Take in a merge whose conflicts are settled
+fn take_merge(todo: &mut TodoList, report: MergeReport, source: &str) {
+    let command = Command::TakeMerge(Box::new(report), source.to_string());
+    if let Some(CommandOutcome::Merged(changes)) = run(todo, command) {
+        print_changes(&changes);
+    }
+}
+
+// The remote copy as last pulled or pushed in this session, which a plain
+// `sync push` expects to find still there. None before the first sync, and
+// after a copy that came without a version.
+static LAST_SEEN: Mutex<Option<Expected>> = Mutex::new(None);
+
+// Pull from the remote copy, push to it, or both. A failed pull leaves the
+// list as it was and nothing is pushed. A push replaces only the copy last
+// seen, unless forced.
+fn handle_sync(todo: &mut TodoList, action: SyncAction, side: Option<Side>) {
+    let Some(mut server) = remote::endpoint() else {
+        fail!("{}  {}", Icon::Warning, msg!("sync.no_remote"));
+        return;
+    };
+    let url = server.url().to_string();
+    let mut last_seen = LAST_SEEN.lock().unwrap_or_else(|error| error.into_inner());
+
+    if matches!(action, SyncAction::Both | SyncAction::Pull) {
+        match remote::pull(&mut server, todo.tasks()) {
+            Ok(pulled) => {
+                if pulled.report.matched + pulled.report.only_theirs == 0 {
+                    info!("{} {}", Icon::Empty, msg!("sync.remote_empty", url = url));
+                } else {
+                    apply_merge(todo, pulled.report, &url, side);
+                }
+                *last_seen = pulled.expected;
+            }
+            Err(error) => {
+                fail!("{}  {}", Icon::Warning, error);
+                return;
+            }
+        }
+    }
+
+    let expected = match (action, last_seen.clone()) {
+        (SyncAction::Pull, _) => return,
+        (SyncAction::Push(true), _) => Expected::Any,
+        (_, Some(expected)) => expected,
+        (_, None) => {
+            fail!("{}  {}", Icon::Warning, msg!("sync.unseen", url = url));
+            return;
+        }
+    };
+    match remote::push(&mut server, todo, &expected) {
+        Ok(version) => {
+            *last_seen = version.map(Expected::Version);
+            info!(
+                "{} {}",
+                Icon::Success,
+                msg!("sync.sent", url = url, summary = todo.summary())
+            )
+        }
+        Err(error) => fail!("{}  {}", Icon::Warning, error),
+    }
+}
+
+// The tasks of a side file as `--json` writes them, one at a time
+struct SavedRows<'a>(&'a TodoList);
+
+impl Serialize for SavedRows<'_> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            self.0
+                .list_tasks()
+                .map(|(index, task)| IndexedTask { index, task }),
+        )
+    }
+}
+
+// Written straight to stdout, so a large listing is never held as one string
+fn print_json<T: Serialize>(value: &T) {
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    let written = serde_json::to_writer_pretty(&mut out, value)
+        .map_err(TodoError::from)
+        .and_then(|()| {
+            writeln!(out)
+                .and_then(|()| out.flush())
+                .map_err(TodoError::from)
+        });
+    if let Err(error) = written {
+        output::report_error(&error);
+    }
+}
+
+pub fn handle_show(todo: &TodoList, index: usize, format: OutputFormat) {
+    let selector = Selector::Index(TaskIndex {
+        task: index,
+        sub: None,
+    });
+    let Some(CommandOutcome::Task(index, task)) = query(todo, Command::Show(selector, format))
+    else {
+        return;
+    };
+
+    match format {
+        OutputFormat::Json => print_json(&IndexedTask { index, task }),
+        OutputFormat::Human => {
+            let (icon, _) = status_style(task);
+            show!("{} {}", icon, msg!("show.task", index = index));
+            // Values line up after the longest label in the current language
+            let width = SHOW_LABELS
+                .iter()
+                .map(|key| display_width(&message(key, &[])))
+                .max()
+                .unwrap_or(0);
+            let field = |key: &'static str, value: &dyn std::fmt::Display| {
+                show!("  {} {}", pad_right(&message(key, &[]), width), value);
+            };
+            field("show.description", &task.description);
+            field("show.status", &task.status);
+            if let Some(reason) = &task.wait_reason {
+                field("show.waiting_on", reason);
+            }
+            if let Some(person) = &task.waiting_on {
+                let since = task.delegated_at.map_or(String::new(), |at| {
+                    format!(" ({})", msg!("show.since", time = format_timestamp(at)))
+                });
+                field("show.delegated_to", &format!("{}{}", person, since));
+            }
+            field("show.id", &task.id);
+            if !task.tags.is_empty() {
+                field("show.tags", &format_tags(&task.tags));
+            }
+            if !task.projects.is_empty() {
+                let projects: Vec<String> =
+                    task.projects.iter().map(|p| format!("+{}", p)).collect();
+                field("show.projects", &projects.join(" "));
+            }
+            if !task.contexts.is_empty() {
+                let contexts: Vec<String> =
+                    task.contexts.iter().map(|c| format!("@{}", c)).collect();
+                field("show.contexts", &contexts.join(" "));
+            }
+            let blockers = todo.blockers(task);
+            if !blockers.is_empty() {
+                let numbers: Vec<String> = blockers.iter().map(usize::to_string).collect();
+                field("show.blocked_by", &numbers.join(", "));
+            }
+            if let Some(due) = due_date(task) {
+                field("show.due", &due);
+            }
+            if let Some(recurrence) = task.recurrence {
+                field("show.repeats", &recurrence);
+            }
+            if !task.priority.is_normal() {
+                field("show.priority", &task.priority);
+            }
+            if let Some(estimate) = task.estimate {
+                field("show.estimate", &estimate);
+            }
+            let spent = task.time_spent(now());
+            if task.timer_started.is_some() {
+                let time = format_duration(spent);
+                field("show.time_spent", &msg!("show.timer_running", time = time));
+            } else if !spent.is_zero() {
+                field("show.time_spent", &format_duration(spent));
+            }
+            for (i, sub) in task.subtasks.iter().enumerate() {
+                let (icon, _) = status_style(sub);
+                show!("    {} {}.{}. {}", icon, index, i + 1, sub);
+            }
+        }
+    }
+}
+
+// Labels of the fields `show` prints, as message keys
+const SHOW_LABELS: [&str; 14] = [
+    "show.description",
+    "show.status",
+    "show.waiting_on",
+    "show.delegated_to",
+    "show.id",
+    "show.tags",
+    "show.projects",
+    "show.contexts",
+    "show.blocked_by",
+    "show.due",
+    "show.repeats",
+    "show.priority",
+    "show.estimate",
+    "show.time_spent",
+];
+
+// Open the chosen link of a task's description in the browser
+pub fn handle_open(todo: &TodoList, index: TaskIndex, number: usize) {
+    let task = match todo.get_at(index) {
+        Ok(task) => task,
+        Err(error) => {
+            output::report_error(&error);
+            return;
+        }
+    };
+    let urls = link::find_urls(&task.description);
+    let url = match urls.get(number - 1) {
+        Some(url) => url,
+        None if urls.is_empty() => {
+            output::report_error(&TodoError::NoLink(index));
+            return;
+        }
+        None => {
+            output::report_error(&TodoError::LinkOutOfBound(index, urls.len()));
+            return;
+        }
+    };
+    match link::open(url) {
+        Ok(_) => info!("{} {}", Icon::Link, msg!("open.opened", url = url)),
+        Err(error) => fail!("{}", msg!("open.failed", url = url, error = error)),
+    }
+}
+
+// Put a task's description, or the whole task as JSON, on the clipboard
+pub fn handle_copy(todo: &TodoList, index: usize, format: OutputFormat) {
+    match clipboard::copy_task(&mut SystemClipboard, todo, index, format) {
+        Ok(_) => match format {
+            OutputFormat::Human => {
+                info!("{} {}", Icon::Success, msg!("copy.copied", index = index))
+            }
+            OutputFormat::Json => info!(
+                "{} {}",
+                Icon::Success,
+                msg!("copy.copied_json", index = index)
+            ),
+        },
+        Err(error) => output::report_error(&error),
+    }
+}
+
+// Put the plain listing on the clipboard
+pub fn handle_copy_list(todo: &TodoList) {
+    match clipboard::copy_list(&mut SystemClipboard, todo) {
+        Ok(_) => info!(
+            "{} {}",
+            Icon::Success,
+            msg!("copy.copied_list", count = todo.len())
+        ),
+        Err(error) => output::report_error(&error),
+    }
+}
+
+pub fn handle_due(todo: &mut TodoList, task: TaskRef, due: Option<(Date, Option<Time>)>) {
+    let Some(CommandOutcome::Edited(index, task)) = run(todo, Command::Due(chosen(task), due))
+    else {
+        return;
+    };
+    match due_date(task) {
+        Some(date) => info!(
+            "{} {}",
+            Icon::Success,
+            msg!("due.set", index = index, date = date)
+        ),
+        None => info!("{} {}", Icon::Success, msg!("due.removed", index = index)),
+    }
+}
+
+pub fn handle_wait(todo: &mut TodoList, task: TaskRef, reason: Option<String>) {
+    let Some(CommandOutcome::Edited(index, task)) = run(todo, Command::Wait(chosen(task), reason))
+    else {
+        return;
+    };
+    match &task.wait_reason {
+        Some(reason) => info!(
+            "{} {}",
+            Icon::Waiting,
+            msg!("wait.set_reason", index = index, reason = reason)
+        ),
+        None => info!("{} {}", Icon::Waiting, msg!("wait.set", index = index)),
+    }
+}
+
+pub fn handle_delegate(todo: &mut TodoList, task: TaskRef, person: Option<String>) {
+    let command = Command::Delegate(chosen(task), person);
+    let Some(CommandOutcome::Edited(index, task)) = run(todo, command) else {
+        return;
+    };
+    match &task.waiting_on {
+        Some(person) => info!(
+            "{} {}",
+            Icon::Waiting,
+            msg!("delegate.set", index = index, person = person)
+        ),
+        None => info!(
+            "{} {}",
+            Icon::Success,
+            msg!("delegate.cleared", index = index)
+        ),
+    }
+}
+
+// One person's section of `waiting --json`
+#[derive(Serialize)]
+struct WaitingOn<'a> {
+    person: &'a str,
+    tasks: Vec<WaitingTask<'a>>,
+}
+
+#[derive(Serialize)]
+struct WaitingTask<'a> {
+    #[serde(flatten)]
+    task: IndexedTask<'a>,
+    waited_days: Option<u64>,
+}
+
+// Full days waited; the first day reads as 0d
+fn waited_days(waited: Duration) -> u64 {
+    waited.as_secs() / SECONDS_PER_DAY as u64
+}
+
+// Delegated tasks in a section per person, each with how long it has
+// been waiting
+pub fn handle_waiting(todo: &TodoList, format: OutputFormat) {
+    let Some(CommandOutcome::Delegated(groups)) = query(todo, Command::Waiting(format)) else {
+        return;
+    };
+    if format == OutputFormat::Json {
+        let groups: Vec<WaitingOn> = groups
+            .iter()
+            .map(|group| WaitingOn {
+                person: &group.person,
+                tasks: group
+                    .tasks
+                    .iter()
+                    .map(|&(index, task, waited)| WaitingTask {
+                        task: IndexedTask { index, task },
+                        waited_days: waited.map(waited_days),
+                    })
+                    .collect(),
+            })
+            .collect();
+        print_json(&groups);
+        return;
+    }
+    if groups.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("waiting.none"));
+        return;
+    }
+    for group in &groups {
+        show!(
+            "\n{} {}",
+            Icon::Waiting,
+            msg!(
+                "waiting.person",
+                person = group.person,
+                count = group.tasks.len()
+            )
+        );
+        for &(index, task, waited) in &group.tasks {
+            let waited = match waited {
+                Some(waited) => humanize_days(waited_days(waited) as i64),
+                None => "?".to_string(),
+            };
+            show!(
+                "  {}. {} ({})",
+                index,
+                described(task),
+                msg!("waiting.for", time = waited)
+            );
+        }
+    }
+}
+
+pub fn handle_estimate(todo: &mut TodoList, task: TaskRef, estimate: Option<Estimate>) {
+    let command = Command::Estimate(chosen(task), estimate);
+    let Some(CommandOutcome::Edited(index, task)) = run(todo, command) else {
+        return;
+    };
+    match task.estimate {
+        Some(estimate) => info!(
+            "{} {}",
+            Icon::Success,
+            msg!("estimate.set", index = index, estimate = estimate)
+        ),
+        None => info!(
+            "{} {}",
+            Icon::Success,
+            msg!("estimate.removed", index = index)
+        ),
+    }
+}
+
+pub fn handle_snooze(todo: &mut TodoList, task: TaskRef, by: Duration) {
+    if let Some(CommandOutcome::Snoozed { task, old, new }) =
+        run(todo, Command::Snooze(chosen(task), by))
+    {
+        let old = old.map_or(msg!("snooze.no_due"), |(due, time)| format_due(due, time));
+        let new = format_due(new.0, new.1);
+        info!(
+            "{} {}",
+            Icon::Reminder,
+            msg!("snooze.snoozed", index = task, old = old, new = new)
+        );
+    }
+}
+
+// Raise the priority of tasks left in todo past the aging limit, given or
+// from --escalate-after
+pub fn handle_escalate(todo: &mut TodoList, days: Option<u64>) {
+    let Some(days) = days.or(Some(output::escalate_after()).filter(|days| *days > 0)) else {
+        fail!("{}  {}", Icon::Warning, msg!("escalate.no_days"));
+        return;
+    };
+    let Some(CommandOutcome::Escalated { days, raised }) = run(todo, Command::Escalate(Some(days)))
+    else {
+        return;
+    };
+    if raised.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("escalate.none", days = days));
+    } else {
+        report_escalated(todo, &raised);
+    }
+}
+
+// Escalate at startup, as --auto-escalate asks; true when a task was raised.
+// One-shot commands pass `report: false` so nothing comes before their output.
+pub fn auto_escalate(todo: &mut TodoList, report: bool) -> bool {
+    let days = output::escalate_after();
+    if days == 0 {
+        return false;
+    }
+    let raised = todo.escalate(now(), days);
+    log!(Debug, "escalated {:?} after {} days", raised, days);
+    if report && !raised.is_empty() {
+        report_escalated(todo, &raised);
+    }
+    !raised.is_empty()
+}
+
+fn report_escalated(todo: &TodoList, raised: &[usize]) {
+    let numbers: Vec<String> = raised
+        .iter()
+        .map(|&index| format!("{} ({})", index, todo[index - 1].priority))
+        .collect();
+    info!(
+        "{} {}",
+        Icon::Reminder,
+        msg!(
+            "escalate.raised",
+            count = raised.len(),
+            numbers = numbers.join(", ")
+        )
+    );
+}
+
+pub fn handle_track(todo: &mut TodoList, task: TaskRef) {
+    if let Some(CommandOutcome::TimerStarted { task, stopped }) =
+        run(todo, Command::Track(chosen(task)))
+    {
+        if let Some(stopped) = stopped {
+            info!("{} {}", Icon::Timer, msg!("timer.stopped", index = stopped));
+        }
+        info!("{} {}", Icon::Timer, msg!("timer.started", index = task));
+    }
+}
+
+pub fn handle_stop(todo: &mut TodoList) {
+    match run(todo, Command::Stop) {
+        Some(CommandOutcome::TimerStopped(Some((index, session)))) => info!(
+            "{} {}",
+            Icon::Timer,
+            msg!(
+                "timer.stopped_after",
+                index = index,
+                time = format_duration(session)
+            )
+        ),
+        Some(_) => info!("{} {}", Icon::Empty, msg!("timer.none")),
+        None => {}
+    }
+}
+
+// Focus on one task for a while, then log the time against it
+pub fn handle_pomodoro(todo: &mut TodoList, task: TaskRef, minutes: u64) {
+    let index = task.index();
+    match todo.get(task) {
+        Ok(task) if task.is_completed() => {
+            output::report_error(&TodoError::AlreadyCompleted(index));
+            return;
+        }
+        Ok(_) => {}
+        Err(error) => {
+            output::report_error(&error);
+            return;
+        }
+    }
+    // The session's time is logged at the end, so no timer may run alongside it
+    if let Some(CommandOutcome::TimerStopped(Some((stopped, _)))) = run(todo, Command::Stop) {
+        info!("{} {}", Icon::Timer, msg!("timer.stopped", index = stopped));
+    }
+    if todo.get(task).is_ok_and(|task| task.status == Status::Todo) {
+        let command = Command::Update(chosen(task), "in-progress".to_string(), true);
+        let _ = outcome::execute(todo, command);
+    }
+
+    let session = pomodoro::run(Duration::from_secs(minutes * 60), index);
+    if let Err(error) = todo.log_time(task, session.spent) {
+        output::report_error(&error);
+        return;
+    }
+    if !session.finished {
+        info!(
+            "{} {}",
+            Icon::Timer,
+            msg!(
+                "pomodoro.stopped",
+                time = format_duration(session.spent),
+                index = task
+            )
+        );
+        return;
+    }
+
+    info!(
+        "{} {}",
+        Icon::Timer,
+        msg!(
+            "pomodoro.done",
+            time = format_duration(session.spent),
+            index = task
+        )
+    );
+    if confirm(&msg!("pomodoro.ask_done", index = task))
+        && let Some(CommandOutcome::StatusChanged(changes)) =
+            run(todo, Command::Done(vec![chosen(task)], true))
+    {
+        report_status_changes(todo, changes, |change| {
+            msg!("task.marked_done", index = change.at)
+        });
+    }
+}
+
+// The tasks closest to the text, best first, with their scores under --verbose
+pub fn handle_fuzzy(todo: &TodoList, text: &str) {
+    let Some(CommandOutcome::Ranked(matches)) = query(todo, Command::Fuzzy(text.to_string()))
+    else {
+        return;
+    };
+    if matches.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("fuzzy.none", text = text));
+        return;
+    }
+
+    let today = Date::today();
+    let clock = Time::now();
+    let tasks: Vec<(usize, &Task)> = matches
+        .iter()
+        .map(|found| (found.index, found.task))
+        .collect();
+    let due_label = |task: &Task| due_label(task, today, clock);
+    // Subtasks are listed under their task but not scored themselves
+    let score = |task: &Task| {
+        matches
+            .iter()
+            .find(|found| std::ptr::eq(found.task, task))
+            .map(|found| found.score.to_string())
+    };
+    let heading = msg!("table.score");
+    let trailing: Trailing = if log::enabled(log::Level::Debug) {
+        Some((&heading, &score))
+    } else {
+        None
+    };
+    print_rows_with(
+        todo,
+        &msg!("fuzzy.title", text = text),
+        &tasks,
+        &due_label,
+        &no_highlights,
+        trailing,
+    );
+}
+
+// Open tasks due today or earlier (`today`), or strictly before today (`overdue`)
+pub fn handle_due_view(todo: &TodoList, overdue_only: bool) {
+    let today = Date::today();
+    let clock = Time::now();
+    let command = if overdue_only {
+        Command::Overdue
+    } else {
+        Command::Today
+    };
+    let Some(CommandOutcome::Tasks(tasks)) = query(todo, command) else {
+        return;
+    };
+
+    if tasks.is_empty() {
+        if overdue_only {
+            info!("{} {}", Icon::Empty, msg!("due.none_overdue"));
+        } else {
+            info!("{} {}", Icon::Empty, msg!("due.none_today"));
+        }
+        return;
+    }
+
+    let due_label = |task: &Task| due_label(task, today, clock);
+    print_rows(
+        todo,
+        &msg!("title.tasks"),
+        &tasks,
+        &due_label,
+        &no_highlights,
+    );
+    print_estimate_left(&tasks);
+}
+
+// The coming days as sections, each with the tasks due on it. Overdue
+// tasks stand out on top and undated ones are only counted.
+pub fn handle_agenda(todo: &TodoList, days: usize, show_empty: bool) {
+    let today = Date::today();
+    let clock = Time::now();
+    let Some(CommandOutcome::Agenda(agenda)) = query(todo, Command::Agenda(days, show_empty))
+    else {
+        return;
+    };
+    let nothing_dated = agenda.overdue.is_empty()
+        && agenda.later.is_empty()
+        && agenda.days.iter().all(|day| day.tasks.is_empty());
+    if nothing_dated && !show_empty {
+        info!("{} {}", Icon::Empty, msg!("agenda.none", days = days));
+    } else {
+        if !agenda.overdue.is_empty() {
+            let heading = format!("{} {}", Icon::Warning, msg!("agenda.overdue"));
+            show!("\n{}", output::paint(&heading, Color::Red));
+            for (index, task) in &agenda.overdue {
+                let line = format!(
+                    "  {}. {} ({})",
+                    index,
+                    described(task),
+                    due_label(task, today, clock).unwrap_or_default()
+                );
+                show!("{}", output::paint(&line, Color::Red));
+            }
+        }
+        for day in &agenda.days {
+            if day.tasks.is_empty() && !show_empty {
+                continue;
+            }
+            let heading = match today.days_until(day.date) {
+                0 => msg!("agenda.today"),
+                1 => msg!("agenda.tomorrow"),
+                _ => day.date.short_label(),
+            };
+            show!("\n{} {}", Icon::Header, heading);
+            if day.tasks.is_empty() {
+                show!("  —");
+            }
+            for (index, task) in &day.tasks {
+                match task.due_time {
+                    Some(time) => show!("  {}. {} ({})", index, described(task), time),
+                    None => show!("  {}. {}", index, described(task)),
+                }
+            }
+        }
+        if !agenda.later.is_empty() {
+            show!("\n{} {}", Icon::Header, msg!("agenda.later"));
+            for (index, task) in &agenda.later {
+                let due = due_label(task, today, clock).unwrap_or_default();
+                show!("  {}. {} ({})", index, described(task), due);
+            }
+        }
+    }
+    if agenda.undated > 0 {
+        show!(
+            "\n{} {}",
+            Icon::Hint,
+            msg!("agenda.undated", count = agenda.undated)
+        );
+    }
+}
+
+// Suggest what to work on, offering to start the best pick when it is
+// still todo. Piped sessions only get the suggestion.
+pub fn handle_next(todo: &mut TodoList, count: usize) {
+    let today = Date::today();
+    let clock = Time::now();
+    let Some(CommandOutcome::Tasks(suggestions)) = query(todo, Command::Next(count)) else {
+        return;
+    };
+    let Some(&(index, task)) = suggestions.first() else {
+        info!("{} {}", Icon::Empty, msg!("next.none"));
+        return;
+    };
+    let due = |task: &Task| {
+        due_label(task, today, clock).map_or(String::new(), |due| format!(" ({})", due))
+    };
+
+    show!(
+        "\n{} {}",
+        Icon::Next,
+        msg!(
+            "next.first",
+            index = index,
+            task = described(task),
+            due = due(task)
+        )
+    );
+    if suggestions.len() > 1 {
+        show!("   {}", msg!("next.then"));
+        for (index, task) in &suggestions[1..] {
+            show!("   {}. {}{}", index, described(task), due(task));
+        }
+    }
+
+    let start = task.status == Status::Todo;
+    offer_to_start(todo, index, start);
+}
+
+// In a terminal, ask whether to put a suggested todo task in progress
+fn offer_to_start(todo: &mut TodoList, index: usize, start: bool) {
+    if start && io::stdin().is_terminal() && confirm(&msg!("next.ask_start", index = index)) {
+        let started = todo.task_ref(index).and_then(|task| {
+            let command = Command::Update(chosen(task), "in-progress".to_string(), true);
+            outcome::execute(todo, command)
+        });
+        match started {
+            Ok(_) => info!("{} {}", Icon::Success, msg!("next.started", index = index)),
+            Err(error) => output::report_error(&error),
+        }
+    }
+}
+
+// Id of the task `random` picked last, 0 before the first pick, so asking
+// again in the same session gives another one
+static LAST_PICK: AtomicU64 = AtomicU64::new(0);
+
+pub fn handle_random(todo: &mut TodoList, filter: &ListFilter, rng: &mut impl Rng) {
+    let avoid = Some(LAST_PICK.load(Ordering::Relaxed)).filter(|id| *id != 0);
+    let Some(index) = todo.pick_random(filter, rng, avoid) else {
+        let message = if filter.is_empty() {
+            msg!("random.none")
+        } else {
+            msg!("random.no_match")
+        };
+        info!("{} {}", Icon::Empty, message);
+        return;
+    };
+    let task = &todo[index - 1];
+    LAST_PICK.store(task.id, Ordering::Relaxed);
+    let due = due_label(task, Date::today(), Time::now())
+        .map_or(String::new(), |due| format!(" ({})", due));
+    show!(
+        "\n{} {}",
+        Icon::Next,
+        msg!(
+            "random.picked",
+            index = index,
+            task = described(task),
+            due = due
+        )
+    );
+    let start = task.status == Status::Todo;
+    offer_to_start(todo, index, start);
+}
+
+// Warn about descriptions over the length cap; they load fine, but
+// editing them has to bring them under it
+pub fn warn_too_long(todo: &TodoList) {
+    let indices = todo.too_long();
+    if indices.is_empty() {
+        return;
+    }
+    let numbers: Vec<String> = indices.iter().map(usize::to_string).collect();
+    notice!(
+        "{}  {}",
+        Icon::Warning,
+        msg!(
+            "warn.too_long",
+            count = numbers.len(),
+            numbers = numbers.join(", "),
+            max = todo.settings().max_length
+        )
+    );
+}
+
+// Warn about statuses from a newer version, which load as todo and
+// would be saved as todo
+pub fn warn_unknown_statuses(todo: &TodoList) {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for name in todo.unknown_statuses() {
+        *counts.entry(name).or_default() += 1;
+    }
+    for (name, count) in counts {
+        notice!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("warn.unknown_status", count = count, name = name)
+        );
+    }
+}
+
+// Startup line like "🔥 5-day streak"; silent without a streak going
+pub fn print_streak(todo: &TodoList) {
+    let current = todo.streaks(Date::today()).current;
+    if current > 0 {
+        info!("{} {}", Icon::Streak, msg!("streak", days = current));
+    }
+}
+
+// Startup block like "⏰ 2 tasks overdue, 1 due today"; silent when nothing is due
+pub fn print_reminders(todo: &TodoList) {
+    let today = Date::today();
+    let clock = Time::now();
+    let reminders = todo.reminders(today);
+    if reminders.is_empty() {
+        return;
+    }
+
+    info!(
+        "{} {}",
+        Icon::Reminder,
+        msg!(
+            "reminders",
+            overdue = reminders.overdue,
+            today = reminders.due_today
+        )
+    );
+    for (index, task) in &reminders.top {
+        if let Some(due) = due_label(task, today, clock) {
+            show!("   {}. {} ({})", index, task.description, due);
+        }
+    }
+}
+
+// A bare number for prompts and status bars, unless someone at the
+// interactive session asked
+pub fn handle_count(todo: &TodoList, target: Option<CountTarget>) {
+    let Some(CommandOutcome::Count(count)) = query(todo, Command::Count(target)) else {
+        return;
+    };
+    if !output::in_session() || output::is_quiet() {
+        show!("{}", count);
+        return;
+    }
+    let counted = match target {
+        None => msg!("count.all", count = count),
+        Some(CountTarget::Status(status)) => {
+            msg!("count.status", count = count, status = status.keyword())
+        }
+        Some(CountTarget::Overdue) => msg!("count.overdue", count = count),
+        Some(CountTarget::DueToday) => msg!("count.due_today", count = count),
+    };
+    show!("{} {}", Icon::Stats, counted);
+}
+
+pub fn handle_stats(todo: &TodoList) {
+    let Some(CommandOutcome::Stats { stats, streaks }) = query(todo, Command::Stats) else {
+        return;
+    };
+    let label = |key: &'static str| pad_right(&message(key, &[]), 13);
+    // Each status's estimated time follows its count, once there are any
+    let estimated = Status::ALL
+        .iter()
+        .any(|&status| !stats.estimated(status).is_zero());
+    let line = |key: &'static str, status: Status, count: usize| {
+        let estimate = stats.estimated(status);
+        let estimate = if estimated && !estimate.is_zero() {
+            format!("  ~{}", estimate)
+        } else {
+            String::new()
+        };
+        show!(
+            "  {}{:>5} ({:.0}%){}",
+            label(key),
+            count,
+            stats.percent(count),
+            estimate
+        );
+    };
+
+    show!("\n{} {}", Icon::Stats, msg!("stats.title"));
+    output::separator();
+    show!("  {}{:>5}", label("stats.total"), stats.total);
+    line("stats.todo", Status::Todo, stats.todo);
+    line("stats.waiting", Status::Waiting, stats.waiting);
+    line("stats.in_progress", Status::InProgress, stats.in_progress);
+    line("stats.done", Status::Completed, stats.completed);
+    line("stats.cancelled", Status::Cancelled, stats.cancelled);
+    show!(
+        "  {}",
+        msg!(
+            "stats.completed",
+            today = stats.completed_today,
+            week = stats.completed_this_week
+        )
+    );
+    if let Some(open) = &stats.longest_open {
+        show!(
+            "  {}",
+            msg!(
+                "stats.longest_open",
+                index = open.index,
+                description = open.description,
+                days = open.days_open
+            )
+        );
+    }
+    show!(
+        "  {}",
+        msg!(
+            "stats.streak",
+            current = streaks.current,
+            best = streaks.best
+        )
+    );
+    if !stats.time_spent.is_zero() {
+        let time = format_duration(stats.time_spent);
+        show!("  {}", msg!("stats.time_tracked", time = time));
+    }
+    if estimated {
+        let time = stats.estimated_left();
+        show!("  {}", msg!("stats.estimated_left", time = time));
+    }
+    output::separator();
+}
+
+pub fn handle_projects(todo: &TodoList) {
+    if let Some(CommandOutcome::Tokens(counts)) = query(todo, Command::Projects) {
+        let empty = msg!("projects.none");
+        print_token_counts(&msg!("projects.title"), '+', &empty, &counts);
+    }
+}
+
+pub fn handle_contexts(todo: &TodoList) {
+    if let Some(CommandOutcome::Tokens(counts)) = query(todo, Command::Contexts) {
+        let empty = msg!("contexts.none");
+        print_token_counts(&msg!("contexts.title"), '@', &empty, &counts);
+    }
+}
+
+// Every known project or context with the number of tasks carrying it
+fn print_token_counts(title: &str, sign: char, empty: &str, counts: &BTreeMap<String, usize>) {
+    if counts.is_empty() {
+        info!("{} {}", Icon::Empty, empty);
+        return;
+    }
+    show!("\n{} {}:", Icon::Header, title);
+    output::separator();
+    for (name, count) in counts {
+        let tasks = msg!("tokens.count", count = format!("{:>3}", count));
+        let label = format!("{}{}", sign, name);
+        show!("  {} {}", pad_right(&label, 21), tasks);
+    }
+    output::separator();
+}
+
+pub fn handle_week(todo: &TodoList) {
+    let Some(CommandOutcome::Week(summary)) = query(todo, Command::Week) else {
+        return;
+    };
+    if let (Some(first), Some(last)) = (summary.days.first(), summary.days.last()) {
+        show!(
+            "\n{} {}",
+            Icon::Stats,
+            msg!("week.title", first = first.date, last = last.date)
+        );
+    }
+    output::separator();
+    for day in &summary.days {
+        show!(
+            "  {} {}  {}",
+            day.date.short_weekday(),
+            day.date,
+            msg!(
+                "week.day",
+                added = format!("{:>3}", day.added),
+                done = format!("{:>3}", day.completed)
+            )
+        );
+    }
+    output::separator();
+    show!(
+        "  {}",
+        msg!(
+            "week.totals",
+            added = summary.added,
+            completed = summary.completed,
+            open = summary.still_open
+        )
+    );
+    let rate = format!("{:.0}", summary.completion_rate);
+    show!("  {}", msg!("week.rate", rate = rate));
+}
+
+pub fn handle_report(todo: &TodoList, path: &str, force: bool) {
+    // confirm's hint names --yes, which this command calls --force
+    if !force && Path::new(path).exists() {
+        if !io::stdin().is_terminal() {
+            fail!("{}  {}", Icon::Warning, msg!("report.exists", path = path));
+            return;
+        }
+        if !confirm(&msg!("report.ask_replace", path = path)) {
+            info!("{} {}", Icon::Hint, msg!("report.kept", path = path));
+            return;
+        }
+    }
+    match fs::write(path, report::markdown(todo, now())) {
+        Ok(()) => info!("{} {}", Icon::Success, msg!("report.written", path = path)),
+        Err(error) => fail!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("file.unwritable", path = path, error = error)
+        ),
+    }
+}
+
+pub fn handle_chart(todo: &TodoList) {
+    let Some(CommandOutcome::Chart(days)) = query(todo, Command::Chart) else {
+        return;
+    };
+    let max = days.iter().map(|day| day.completed).max().unwrap_or(0);
+    show!(
+        "\n{} {}",
+        Icon::Stats,
+        msg!("chart.title", days = outcome::CHART_DAYS)
+    );
+    output::separator();
+    for day in &days {
+        let bar = output::histogram_bar(day.completed, max);
+        show!(
+            "  {} {}  {:<width$} {}",
+            day.date.short_weekday(),
+            day.date,
+            bar,
+            day.completed,
+            width = output::HISTOGRAM_WIDTH.min(max)
+        );
+    }
+    output::separator();
+}
+
+pub fn list_tasks(
+    todo: &TodoList,
+    filter: &ListFilter,
+    limit: Option<Limit>,
+    format: OutputFormat,
+    view: &mut ListView,
+) {
+    // Saved filters are looked up here, so what they stand for can be shown
+    // and is what the rest of the listing goes by
+    let (filter, used) = match expand_filter(filter, todo) {
+        Ok(expanded) => expanded,
+        Err(error) => return output::report_error(&error),
+    };
+    let filter = &filter;
+    if !used.is_empty() && format != OutputFormat::Json {
+        info!(
+            "{} {}",
+            Icon::Hint,
+            msg!("filter.using", names = used.join(", "), filter = filter)
+        );
+    }
+    let command = Command::List {
+        filter: filter.clone(),
+        limit,
+        format,
+    };
+    let Some(CommandOutcome::Tasks(tasks)) = query(todo, command) else {
+        return;
+    };
+
+    // JSON output is never paged
+    if format == OutputFormat::Json {
+        let rows: Vec<IndexedTask> = tasks
+            .into_iter()
+            .map(|(index, task)| IndexedTask { index, task })
+            .collect();
+        print_json(&rows);
+        return;
+    }
+
+    // Say what was left out, so the listing is not taken for the whole list
+    let hidden = todo.hidden_waiting(filter);
+    let print_hidden = || {
+        if hidden > 0 {
+            info!(
+                "{} {}",
+                Icon::Hint,
+                msg!("list.waiting_hidden", count = hidden)
+            );
+        }
+    };
+
+    if tasks.is_empty() {
+        view.reset();
+        if !filter.is_empty() {
+            info!("{} {}", Icon::Empty, msg!("list.no_match", filter = filter));
+        } else if hidden == 0 {
+            info!("{} {}", Icon::Empty, msg!("list.empty"));
+        }
+        print_hidden();
+        return;
+    }
+
+    // A limited listing is already short, so it is printed whole, as is a
+    // grouped one, whose pages would split its sections
+    if filter.grouped {
+        view.reset();
+        let highlights = |task: &Task| filter.highlights(&task.description);
+        let due = |task: &Task| list_due(task, filter.absolute);
+        for group in listing::group_by_status(&tasks) {
+            print_rows(todo, &group.header(), &group.tasks, &due, &highlights);
+        }
+    } else if limit.is_some() {
+        view.reset();
+        let highlights = |task: &Task| filter.highlights(&task.description);
+        let due = |task: &Task| list_due(task, filter.absolute);
+        print_rows(todo, &msg!("title.tasks"), &tasks, &due, &highlights);
+    } else {
+        let page = view.first_page(filter, tasks.len());
+        print_page(todo, &tasks, page, view);
+    }
+    // A filtered view's bar would only count part of the list, so it gets
+    // the time its open tasks should take instead
+    if filter.is_empty() {
+        print_progress(todo);
+    } else {
+        print_estimate_left(&tasks);
+    }
+    print_hidden();
+}
+
+// Total estimate of the open tasks among those listed, when any has one
+fn print_estimate_left(tasks: &[(usize, &Task)]) {
+    let estimates: Vec<Estimate> = tasks
+        .iter()
+        .filter(|(_, task)| !task.is_closed())
+        .filter_map(|(_, task)| task.estimate)
+        .collect();
+    if !estimates.is_empty() {
+        let count = estimates.len();
+        let time: Estimate = estimates.into_iter().sum();
+        info!(
+            "{} {}",
+            Icon::Timer,
+            msg!("list.estimated_left", time = time, count = count)
+        );
+    }
+}
+
+fn print_progress(todo: &TodoList) {
+    let stats = todo.statistics();
+    if stats.active() > 0 {
+        show!("{}", output::progress_bar(stats.completed, stats.active()));
+    }
+}
+
+// Continue the last listing with its next page
+pub fn handle_more(todo: &TodoList, view: &mut ListView) {
+    let tasks = todo.filter(view.filter());
+    match view.next_page(tasks.len()) {
+        Some(page) => print_page(todo, &tasks, page, view),
+        None => info!("{} {}", Icon::Empty, msg!("list.no_more")),
+    }
+}
+
+fn print_page(todo: &TodoList, tasks: &[(usize, &Task)], page: Range<usize>, view: &ListView) {
+    let (first, last) = (page.start + 1, page.end);
+    let highlights = |task: &Task| view.filter().highlights(&task.description);
+    let due = |task: &Task| list_due(task, view.filter().absolute);
+    print_rows(todo, &msg!("title.tasks"), &tasks[page], &due, &highlights);
+    if view.has_more() || first > 1 {
+        let dash = if output::is_plain() { "-" } else { "–" };
+        let range = format!("{}{}{}", first, dash, last);
+        let showing = if view.has_more() {
+            msg!("list.showing_more", range = range, total = tasks.len())
+        } else {
+            msg!("list.showing", range = range, total = tasks.len())
+        };
+        info!("{} {}", Icon::Hint, showing);
+    }
+}
+
+// Text for a task's due-date column, if it has one
+type DueLabel<'a> = &'a dyn Fn(&Task) -> Option<String>;
+
+// Parts of a task's description to highlight, such as search matches
+type Highlights<'a> = &'a dyn Fn(&Task) -> Vec<Range<usize>>;
+
+fn no_highlights(_: &Task) -> Vec<Range<usize>> {
+    Vec::new()
+}
+
+// A column after the description, its heading and what it holds for a task
+type Trailing<'a> = Option<(&'a str, &'a dyn Fn(&Task) -> Option<String>)>;
+
+fn absolute_due(task: &Task) -> Option<String> {
+    due_date(task)
+}
+
+// The due column of `list`: how far off the date is, unless --absolute
+// asks for the date. Closed tasks keep the date, as nothing of theirs is
+// late any more. Overdue dates stand out: marked with ! in plain mode and
+// highlighted otherwise.
+fn list_due(task: &Task, absolute: bool) -> Option<String> {
+    let due = task.due?;
+    let today = Date::today();
+    if absolute || task.is_closed() {
+        return due_date(task);
+    }
+    let label = relative_due_at(due, task.due_time, today, Time::now());
+    Some(if due >= today {
+        label
+    } else if output::is_plain() {
+        format!("!{}", label)
+    } else {
+        let whole = 0..label.len();
+        output::highlight(&label, std::slice::from_ref(&whole))
+    })
+}
+
+fn print_rows(
+    todo: &TodoList,
+    title: &str,
+    tasks: &[(usize, &Task)],
+    due_label: DueLabel,
+    highlights: Highlights,
+) {
+    print_rows_with(todo, title, tasks, due_label, highlights, None);
+}
+
+// Rows as `print_rows` prints them, with a column after the description.
+// Plain rows end with it in parentheses.
+fn print_rows_with(
+    todo: &TodoList,
+    title: &str,
+    tasks: &[(usize, &Task)],
+    due_label: DueLabel,
+    highlights: Highlights,
+    trailing: Trailing,
+) {
+    show!("\n{} {}:", Icon::Header, title);
+    output::separator();
+    let rows = listing::rows(tasks.iter().copied());
+    if output::is_plain() {
+        // Compact single-line rows keep plain output easy to grep. They are
+        // written as they are made, through one buffer and one stdout lock.
+        let today = Date::today();
+        let theme = output::theme();
+        let stale = stale_ids(todo);
+        let mut out = io::stdout().lock();
+        let mut line = String::new();
+        for row in rows {
+            let description = output::highlight(&row.task.description, &highlights(row.task));
+            let due = due_label(row.task);
+            line.clear();
+            row.write_line(&mut line, todo, theme, &description, due.as_deref(), today)
+                .expect("writing to a String cannot fail");
+            if !row.is_sub && stale.contains(&row.task.id) {
+                line.push(' ');
+                line.push_str(&theme.stale);
+            }
+            if let Some((heading, value)) = trailing
+                && let Some(value) = value(row.task)
+            {
+                line.push_str(&format!(" ({} {})", heading.to_lowercase(), value));
+            }
+            writeln!(out, "{}", line).expect("failed printing to stdout");
+        }
+    } else {
+        print_task_table(
+            todo,
+            &rows.collect::<Vec<_>>(),
+            due_label,
+            highlights,
+            trailing,
+        );
+    }
+    output::separator();
+}
+
+// Ids of the tasks the aging rule counts as stale, none unless
+// --escalate-after turns it on
+fn stale_ids(todo: &TodoList) -> HashSet<u64> {
+    let days = output::escalate_after();
+    if days == 0 {
+        return HashSet::new();
+    }
+    todo.stale_tasks(now(), days)
+        .into_iter()
+        .map(|index| todo[index - 1].id)
+        .collect()
+}
+
+// Open tasks left alone for longer than --stale-days
+fn is_stale(task: &Task, today: Date) -> bool {
+    let threshold = output::stale_days();
+    match task.created_at {
+        Some(created) if threshold > 0 && !task.is_closed() => {
+            Date::from_timestamp(created).days_until(today) > threshold as i64
+        }
+        _ => false,
+    }
+}
+
+// Icon and row color for a task's status
+fn status_style(task: &Task) -> (Icon, Color) {
+    let icon = match task.status {
+        Status::Todo => Icon::Todo,
+        Status::Waiting => Icon::Waiting,
+        Status::InProgress => Icon::InProgress,
+        Status::Completed => Icon::Completed,
+        Status::Cancelled => Icon::Cancelled,
+    };
+    (icon, output::theme().color(task.status))
+}
+
+fn print_task_table(
+    todo: &TodoList,
+    rows: &[Row],
+    due_label: DueLabel,
+    highlights: Highlights,
+    trailing: Trailing,
+) {
+    let dues: Vec<Option<String>> = rows.iter().map(|row| due_label(row.task)).collect();
+    // The due column only appears when something in view has a date
+    let show_due = dues.iter().any(Option::is_some);
+    let today = Date::today();
+    let ages: Vec<Option<String>> = rows.iter().map(|row| age_label(row.task, today)).collect();
+    // Tasks saved by older versions have no creation time to show
+    let show_age = ages.iter().any(Option::is_some);
+    let estimates: Vec<Option<String>> = rows
+        .iter()
+        .map(|row| row.task.estimate.map(|estimate| estimate.to_string()))
+        .collect();
+    let show_estimate = estimates.iter().any(Option::is_some);
+    let stale = stale_ids(todo);
+
+    let mut columns = vec![Column::right("#"), Column::left(&msg!("table.status"))];
+    if show_due {
+        columns.push(Column::left(&msg!("table.due")));
+    }
+    if show_age {
+        columns.push(Column::right(&msg!("table.age")));
+    }
+    if show_estimate {
+        columns.push(Column::right(&msg!("table.estimate")));
+    }
+    columns.push(Column::flexible(&msg!("table.description")));
+    if let Some((heading, _)) = trailing {
+        columns.push(Column::right(heading));
+    }
+
+    let mut table = Table::new(columns);
+    table.set_wrap(output::wraps());
+    for (
+        (
+            Row {
+                label,
+                task,
+                is_sub,
+            },
+            due,
+        ),
+        (age, estimate),
+    ) in rows.iter().zip(dues).zip(ages.into_iter().zip(estimates))
+    {
+        let (icon, _) = status_style(task);
+        let mut status = match &task.marker {
+            Some(marker) => format!("{} {} {}", icon, marker, task.status),
+            None => format!("{} {}", icon, task.status),
+        };
+        if !is_sub && todo.is_blocked(task) {
+            status = format!("{} {}", status, Icon::Blocked);
+        }
+        if has_link(task) {
+            status = format!("{} {}", status, Icon::Link);
+        }
+        if !is_sub && stale.contains(&task.id) {
+            status = format!("{} {}", status, output::theme().stale);
+        }
+        let mut row = vec![label.clone(), status];
+        if show_due {
+            row.push(due.unwrap_or_default());
+        }
+        if show_age {
+            row.push(age.unwrap_or_default());
+        }
+        if show_estimate {
+            row.push(estimate.unwrap_or_default());
+        }
+        // The description comes first in `described`, so its ranges still apply
+        let description = output::highlight(&described(task), &highlights(task));
+        if *is_sub {
+            row.push(format!("↳ {}", description));
+        } else {
+            row.push(description);
+        }
+        if let Some((_, value)) = trailing {
+            row.push(value(task).unwrap_or_default());
+        }
+        table.add_row(row);
+    }
+
+    let mut lines = table.render(output::terminal_width()).into_iter();
+    if let Some(header) = lines.next() {
+        for line in header {
+            show!("{}", line);
+        }
+    }
+    for (Row { task, .. }, lines) in rows.iter().zip(lines) {
+        // A color chosen with `mark` wins over the ones given by the rules
+        let color = if let Some(color) = task.color() {
+            color
+        } else if is_stale(task, today) {
+            output::theme().stale_color
+        } else {
+            status_style(task).1
+        };
+        for line in output::finish_highlights(lines) {
+            show!("{}", output::paint(&line, color));
+        }
+    }
+}
+
+// Each status with the other words for it: `todo (to-do, open), ...`
+fn status_words() -> String {
+    let words: Vec<String> = Status::ALL
+        .iter()
+        .map(|status| {
+            let others: Vec<&str> = status.synonyms().skip(1).collect();
+            if others.is_empty() {
+                status.keyword().to_string()
+            } else {
+                format!("{} ({})", status.keyword(), others.join(", "))
+            }
+        })
+        .collect();
+    words.join(", ")
+}
+
+// Width of the usage column in `help`; longer usages get a line of their own
+const HELP_COLUMN: usize = 24;
+
+// Width the description in `help <command>` is wrapped to
+const HELP_WIDTH: usize = 76;
+
+// A command's usage lines, naming its aliases after the first one when asked
+fn print_usage(spec: &CommandSpec, with_aliases: bool) {
+    for (i, (usage, description)) in spec.usage.iter().enumerate() {
+        let also = if with_aliases && i == 0 && !spec.aliases.is_empty() {
+            format!(
+                " ({})",
+                msg!("help.also_inline", aliases = spec.aliases.join(", "))
+            )
+        } else {
+            String::new()
+        };
+        if usage.len() > HELP_COLUMN {
+            show!("  {}", usage);
+            show!("       {}{}", description, also);
+        } else {
+            show!(
+                "  {:<width$} {}{}",
+                usage,
+                description,
+                also,
+                width = HELP_COLUMN
+            );
+        }
+    }
+}
+
+pub fn print_help() {
+    show!("{}", msg!("help.commands"));
+    for spec in COMMANDS {
+        print_usage(spec, true);
+    }
+    show!("  {}", msg!("help.details"));
+    show!("  {}", msg!("help.confirm"));
+    show!("  {}", msg!("help.prefixes"));
+    show!("  {}", msg!("help.text_selector"));
+    show!("  {}", msg!("help.pick_selector"));
+    let statuses = msg!("help.statuses", statuses = status_words());
+    for (i, line) in wrap(&statuses, HELP_WIDTH).iter().enumerate() {
+        // Later lines line up under the list
+        let indent = if i == 0 { "  " } else { "    " };
+        show!("{}{}", indent, line);
+    }
+    show!("  {}", msg!("help.archive", path = ARCHIVE_FILE));
+    show!();
+    show!("{}", msg!("help.examples"));
+    show!("  add Buy groceries");
+    show!("  list done");
+    show!("  update 1 in-progress");
+    show!("  remove 2");
+}
+
+// Everything about one command: how to call it, what it does and examples
+pub fn print_command_help(spec: &CommandSpec) {
+    show!("{}", msg!("help.usage"));
+    print_usage(spec, false);
+    show!();
+    for line in wrap(spec.description, HELP_WIDTH) {
+        show!("  {}", line);
+    }
+    if !spec.aliases.is_empty() {
+        show!();
+        show!("{}", msg!("help.also", aliases = spec.aliases.join(", ")));
+    }
+    show!();
+    show!("{}", msg!("help.examples"));
+    for example in spec.examples {
+        show!("  {}", example);
+    }
+}
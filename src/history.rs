@@ -0,0 +1,59 @@
+use std::fmt::Display;
+use std::fs;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::date::{Timestamp, format_timestamp, now};
+use crate::settings::Settings;
+use crate::todo::{TodoError, write_json};
+
+/// Entries kept in the log file unless --history-size says otherwise
+pub const DEFAULT_HISTORY_LIMIT: usize = 500;
+
+/// One change to the task list, as shown by `history`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LogEntry {
+    pub timestamp: Timestamp,
+    /// Short machine-friendly name such as "add" or "status"
+    pub action: String,
+    /// What happened, in words
+    pub details: String,
+}
+
+impl LogEntry {
+    pub fn new(action: &str, details: String) -> Self {
+        LogEntry {
+            timestamp: now(),
+            action: action.to_string(),
+            details,
+        }
+    }
+}
+
+impl Display for LogEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", format_timestamp(self.timestamp), self.details)
+    }
+}
+
+/// Read the log, treating a missing file as an empty log
+pub fn load(path: &str) -> Result<Vec<LogEntry>, TodoError> {
+    match fs::read_to_string(path) {
+        Ok(json) => Ok(serde_json::from_str(&json)?),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(error) => Err(TodoError::FileError(error)),
+    }
+}
+
+/// Write the newest entries, as many as the settings keep, replacing the
+/// file only once the new copy is complete
+pub fn save(path: &str, entries: &[LogEntry], settings: &Settings) -> Result<(), TodoError> {
+    write_json(path, kept(entries, settings), settings.compact_files)
+}
+
+/// The newest entries, as many as [`save`] keeps
+pub fn kept<'a>(entries: &'a [LogEntry], settings: &Settings) -> &'a [LogEntry] {
+    let skip = entries.len().saturating_sub(settings.history_limit);
+    &entries[skip..]
+}
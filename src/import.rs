@@ -0,0 +1,98 @@
+use std::fs;
+
+use serde::Deserialize;
+
+use crate::todo::{Status, Task, TodoError, normalize_tag};
+
+/// Exports `import` reads tasks from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    /// `gh issue list --json number,title,state,labels`
+    GitHub,
+}
+
+impl Format {
+    /// Every supported format
+    pub const ALL: [Format; 1] = [Format::GitHub];
+
+    /// Name as `import` takes it
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::GitHub => "github",
+        }
+    }
+
+    /// The format a name stands for, in any case
+    pub fn from_name(name: &str) -> Option<Format> {
+        Format::ALL
+            .into_iter()
+            .find(|format| format.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// What [`TodoList::import`](crate::todo::TodoList::import) did, in tasks
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ImportSummary {
+    pub created: usize,
+    pub updated: usize,
+    /// Tasks already in the list just as they were imported
+    pub skipped: usize,
+}
+
+// An issue as gh writes it. Only the number and title have to be there.
+#[derive(Debug, Deserialize)]
+struct Issue {
+    number: u64,
+    title: String,
+    #[serde(default)]
+    state: Option<String>,
+    #[serde(default)]
+    labels: Vec<Label>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Label {
+    name: String,
+}
+
+impl Issue {
+    // Closed issues are done; open ones, or ones without a state, are to do
+    fn status(&self) -> Status {
+        match self.state.as_deref() {
+            Some(state) if state.eq_ignore_ascii_case("closed") => Status::Completed,
+            _ => Status::Todo,
+        }
+    }
+
+    fn into_task(self) -> Result<Task, TodoError> {
+        let status = self.status();
+        let mut task = Task::builder(self.title).status(status).build()?;
+        // Labels such as "good first issue" become good-first-issue
+        for label in &self.labels {
+            let tag = label.name.split_whitespace().collect::<Vec<_>>().join("-");
+            if let Some(tag) = normalize_tag(&tag)
+                && !task.tags.contains(&tag)
+            {
+                task.tags.push(tag);
+            }
+        }
+        task.source = Some(format!("github#{}", self.number));
+        Ok(task)
+    }
+}
+
+/// The tasks in an export, each with its [`Task::source`] set so importing
+/// again finds it. Nothing is returned unless every entry is valid.
+pub fn parse(format: Format, json: &str) -> Result<Vec<Task>, TodoError> {
+    match format {
+        Format::GitHub => {
+            let issues: Vec<Issue> = serde_json::from_str(json)?;
+            issues.into_iter().map(Issue::into_task).collect()
+        }
+    }
+}
+
+/// Like [`parse`], reading the export from a file
+pub fn read(format: Format, path: &str) -> Result<Vec<Task>, TodoError> {
+    parse(format, &fs::read_to_string(path)?)
+}
@@ -0,0 +1,79 @@
+//! A task list with statuses, due dates, tags, subtasks and history, and the
+//! command language of the `rust-todo-cli` binary.
+//!
+//! Nothing here prints, apart from the [`log`](mod@log) when it is turned on.
+//! [`command::parse_command`] turns a line of input into
+//! a [`command::Command`], [`todo::TodoList`] holds and changes the tasks, and
+//! [`store::Store`] reads and writes them along with the archive, the trash
+//! and the history. [`outcome::execute`] runs a command and hands back what
+//! it did, which is what the binary prints.
+//!
+//! Task numbers taken by the list's methods and by commands are 1-based, as
+//! shown to the user. Only indexing, `list[0]`, counts from 0 like a slice.
+
+/// Name of this crate, as published
+pub const NAME: &str = env!("CARGO_PKG_NAME");
+/// Version of this crate, as in its Cargo.toml
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Everything the app keeps, written to and restored from a single file
+pub mod backup;
+/// What a command changed in the list, task by task
+pub mod changes;
+/// Putting text on the system clipboard
+pub mod clipboard;
+/// Turning a line of input into a [`command::Command`]
+pub mod command;
+/// Shell completion scripts made from the command table
+pub mod completion;
+/// Counting a focus session down on a clock that tests can fake
+pub mod countdown;
+/// Calendar days, durations and timestamps
+pub mod date;
+/// Checks that a loaded list holds to what the rest of the code expects
+pub mod doctor;
+/// Ranking tasks by how closely they match a query, typos and all
+pub mod fuzzy;
+/// The log of changes made to a list
+pub mod history;
+/// Reading tasks from other tools' exports, such as GitHub issues
+pub mod import;
+/// Finding and opening links in task descriptions
+pub mod link;
+/// Text listings of tasks, shared by the CLI and `Display`
+pub mod listing;
+/// Debug logging to stderr, silent unless asked for
+pub mod log;
+/// Asking the user: yes or no, or a task from a numbered menu for `--pick`
+pub mod menu;
+/// Reconciling two copies of a list that went their own ways
+pub mod merge;
+/// User-facing messages and their translations
+pub mod messages;
+/// Running a command without printing, for programs that embed the list
+pub mod outcome;
+/// The interactive prompt, filled in from the state of the list
+pub mod prompt_line;
+/// Random picks that tests can replay
+pub mod random;
+/// Pulling and pushing the list to a copy kept elsewhere
+pub mod remote;
+/// Reports on the list to share, such as the Markdown one for standups
+pub mod report;
+/// Making input safe to keep and print: no escape sequences or control
+/// characters, and lines of a bounded length
+pub mod sanitize;
+/// Choices made once for a run, such as how long a description may be
+pub mod settings;
+/// Task descriptions with placeholders filled in at `add` time
+pub mod snippet;
+/// Files the tasks are kept in
+pub mod store;
+/// The markers, rules and colors listings are drawn with
+pub mod theme;
+/// Tasks and the list that holds them
+pub mod todo;
+/// Paging through a listing a screenful at a time
+pub mod view;
+/// Measuring, cutting and wrapping text as it appears in a terminal
+pub mod width;
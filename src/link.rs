@@ -0,0 +1,74 @@
+use std::io;
+use std::process::{Command, Stdio};
+
+use crate::msg;
+
+// Schemes recognised as the start of a link
+const SCHEMES: &[&str] = &["https://", "http://"];
+
+// Punctuation that ends a sentence rather than a link
+const TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', '\'', '"', ')', ']', '}', '>'];
+
+/// Every http(s) link in some text, in order. Punctuation hugging a link,
+/// as in "(see https://example.com).", is left out; a closing bracket is
+/// kept when the link opened it itself.
+pub fn find_urls(text: &str) -> Vec<&str> {
+    let mut urls = Vec::new();
+    for word in text.split_whitespace() {
+        let Some(start) = SCHEMES.iter().filter_map(|scheme| word.find(scheme)).min() else {
+            continue;
+        };
+        let mut url = &word[start..];
+        while let Some(last) = url.chars().last() {
+            if !TRAILING.contains(&last) || closes_own_bracket(url, last) {
+                break;
+            }
+            url = &url[..url.len() - last.len_utf8()];
+        }
+        let scheme_only = SCHEMES.iter().any(|scheme| url.len() <= scheme.len());
+        if !scheme_only {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+// Whether a closing bracket at the end of a link pairs with one inside it
+fn closes_own_bracket(url: &str, last: char) -> bool {
+    let open = match last {
+        ')' => '(',
+        ']' => '[',
+        '}' => '{',
+        _ => return false,
+    };
+    url.matches(open).count() >= url.matches(last).count()
+}
+
+/// Hand a link to the system's browser
+pub fn open(url: &str) -> io::Result<()> {
+    let mut command = if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else if cfg!(target_os = "windows") {
+        // Not `cmd /C start`: cmd would run whatever follows a `&`, `|` or
+        // `^` in a link that came in with an import, a merge or a sync
+        let mut command = Command::new("rundll32");
+        command.arg("url.dll,FileProtocolHandler");
+        command
+    } else {
+        Command::new("xdg-open")
+    };
+    let status = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(msg!(
+            "error.browser_exit",
+            status = status
+        )))
+    }
+}
@@ -0,0 +1,325 @@
+use std::fmt::{self, Display, Write};
+
+use serde::Serialize;
+
+use crate::date::{Date, Time, format_due, humanize_days};
+use crate::link;
+use crate::msg;
+use crate::theme::Theme;
+use crate::todo::{Status, Task, TodoList};
+
+/// A task together with the 1-based number shown to the user, as `--json`
+/// output writes it
+#[derive(Debug, Serialize)]
+pub struct IndexedTask<'a> {
+    pub index: usize,
+    #[serde(flatten)]
+    pub task: &'a Task,
+}
+
+/// One row of a listing: a task or one of its subtasks
+#[derive(Debug, Clone, PartialEq)]
+pub struct Row<'a> {
+    /// Number as shown: `3`, or `3.1` for a subtask
+    pub label: String,
+    pub task: &'a Task,
+    pub is_sub: bool,
+}
+
+/// Listed tasks followed by their subtasks, in display order, made as
+/// they are needed
+pub fn rows<'a>(
+    tasks: impl IntoIterator<Item = (usize, &'a Task)>,
+) -> impl Iterator<Item = Row<'a>> {
+    tasks.into_iter().flat_map(|(index, task)| {
+        let row = Row {
+            label: index.to_string(),
+            task,
+            is_sub: false,
+        };
+        let subtasks = task.subtasks.iter().enumerate().map(move |(i, sub)| Row {
+            label: format!("{}.{}", index, i + 1),
+            task: sub,
+            is_sub: true,
+        });
+        std::iter::once(row).chain(subtasks)
+    })
+}
+
+impl Row<'_> {
+    /// The compact single-line form of the row, as the plain CLI listing
+    /// prints it: `[ ] 1. Buy milk [TODO] (2d) #shop (2025-06-01)`, with
+    /// the task's marker, if any, after the status marker: `[ ] 🔥 1. ...`.
+    /// `description` is the text to show, which lets the caller mark up
+    /// search matches; `due` is the label for the due date, if any.
+    pub fn line(
+        &self,
+        todo: &TodoList,
+        theme: &Theme,
+        description: &str,
+        due: Option<&str>,
+        today: Date,
+    ) -> String {
+        let mut line = String::new();
+        self.write_line(&mut line, todo, theme, description, due, today)
+            .expect("writing to a String cannot fail");
+        line
+    }
+
+    /// Write the [`line`](Row::line) straight to `out`, which spares long
+    /// listings an allocation per row
+    pub fn write_line(
+        &self,
+        out: &mut impl Write,
+        todo: &TodoList,
+        theme: &Theme,
+        description: &str,
+        due: Option<&str>,
+        today: Date,
+    ) -> fmt::Result {
+        let task = self.task;
+        let indent = if self.is_sub { "   " } else { "" };
+        write!(out, "{}{}", indent, theme.status(task.status))?;
+        if let Some(marker) = &task.marker {
+            write!(out, " {}", marker)?;
+        }
+        write!(out, " {}. {} [{}]", self.label, description, task.status)?;
+        if let Some(age) = age_label(task, today) {
+            write!(out, " ({})", age)?;
+        }
+        if let Some(estimate) = task.estimate {
+            write!(out, " ~{}", estimate)?;
+        }
+        let tokens = missing_tokens(task);
+        if !tokens.is_empty() {
+            write!(out, " {}", tokens)?;
+        }
+        for tag in &task.tags {
+            write!(out, " #{}", tag)?;
+        }
+        if !self.is_sub && todo.is_blocked(task) {
+            write!(out, " {}", theme.blocked)?;
+        }
+        if has_link(task) {
+            write!(out, " {}", theme.link)?;
+        }
+        if let Some(due) = due {
+            write!(out, " ({})", due)?;
+        }
+        Ok(())
+    }
+}
+
+/// Tags as shown next to a description: `#work #home`
+pub fn format_tags(tags: &[String]) -> String {
+    let tags: Vec<String> = tags.iter().map(|tag| format!("#{}", tag)).collect();
+    tags.join(" ")
+}
+
+/// The task's +project and @context tokens that its description does not
+/// show itself, as happens with --strip-tokens
+pub fn missing_tokens(task: &Task) -> String {
+    if task.projects.is_empty() && task.contexts.is_empty() {
+        return String::new();
+    }
+    let words: Vec<String> = task
+        .description
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+    let projects = task.projects.iter().map(|project| format!("+{}", project));
+    let contexts = task.contexts.iter().map(|context| format!("@{}", context));
+    let missing: Vec<String> = projects
+        .chain(contexts)
+        .filter(|token| !words.contains(token))
+        .collect();
+    missing.join(" ")
+}
+
+/// Description followed by any tokens it lacks and the task's tags
+pub fn described(task: &Task) -> String {
+    let mut text = task.description.clone();
+    for extra in [missing_tokens(task), format_tags(&task.tags)] {
+        if !extra.is_empty() {
+            text = format!("{} {}", text, extra);
+        }
+    }
+    text
+}
+
+/// Whether the description holds something for `open` to open
+pub fn has_link(task: &Task) -> bool {
+    !link::find_urls(&task.description).is_empty()
+}
+
+/// Due dates further ahead than this many days are shown as dates, since
+/// "due in 200d" says less than the date itself
+pub const RELATIVE_DUE_DAYS: i64 = 60;
+
+/// How far off a due date is from `today`: "due today", "due tomorrow",
+/// "due in 3d" or "2d overdue". Dates more than [`RELATIVE_DUE_DAYS`] ahead
+/// are given as they are.
+pub fn relative_due(due: Date, today: Date) -> String {
+    match today.days_until(due) {
+        0 => msg!("due.today"),
+        1 => msg!("due.tomorrow"),
+        days if days > RELATIVE_DUE_DAYS => due.to_string(),
+        days if days > 1 => msg!("due.in_days", days = days),
+        days => msg!("due.overdue", days = -days),
+    }
+}
+
+/// How far off a due date and time are from `today` at `now`. A time later
+/// today reads "due in 2h" or "due in 45m", and one already past "due today
+/// 14:30"; on later days the time follows the day, as in "due tomorrow
+/// 09:00". Overdue days read as in [`relative_due`], and so does a date
+/// without a time.
+pub fn relative_due_at(due: Date, time: Option<Time>, today: Date, now: Time) -> String {
+    let Some(time) = time else {
+        return relative_due(due, today);
+    };
+    let days = today.days_until(due);
+    if days == 0 {
+        let minutes = time.minutes() as i64 - now.minutes() as i64;
+        if minutes >= 60 {
+            return msg!("due.in_hours", hours = minutes / 60);
+        } else if minutes > 0 {
+            return msg!("due.in_minutes", minutes = minutes);
+        }
+    } else if days < 0 {
+        return relative_due(due, today);
+    }
+    format!("{} {}", relative_due(due, today), time)
+}
+
+/// How far off a task's due date is, with its time, from `today` at `now`
+pub fn due_label(task: &Task, today: Date, now: Time) -> Option<String> {
+    Some(relative_due_at(task.due?, task.due_time, today, now))
+}
+
+/// A task's due date as it is, followed by its time if it has one
+pub fn due_date(task: &Task) -> Option<String> {
+    Some(format_due(task.due?, task.due_time))
+}
+
+/// How long a task has been open, or for a completed one how long it took
+pub fn age_label(task: &Task, today: Date) -> Option<String> {
+    let created = Date::from_timestamp(task.created_at?);
+    match task.completed_at {
+        Some(completed) if task.is_completed() => Some(msg!(
+            "listing.took",
+            time = humanize_days(created.days_until(Date::from_timestamp(completed)))
+        )),
+        _ => Some(humanize_days(created.days_until(today))),
+    }
+}
+
+/// Statuses in the order `list --grouped` shows their sections: work under
+/// way first, finished work last
+pub const GROUP_ORDER: [Status; 5] = [
+    Status::InProgress,
+    Status::Todo,
+    Status::Waiting,
+    Status::Completed,
+    Status::Cancelled,
+];
+
+/// The listed tasks of one status
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusGroup<'a> {
+    pub status: Status,
+    /// With their numbers in the whole list, in the order they were listed
+    pub tasks: Vec<(usize, &'a Task)>,
+}
+
+impl StatusGroup<'_> {
+    /// The section header: `IN PROGRESS (2)`
+    pub fn header(&self) -> String {
+        let status = self.status.to_string().replace('-', " ");
+        msg!("listing.group", status = status, count = self.tasks.len())
+    }
+}
+
+/// Listed tasks split by status in [`GROUP_ORDER`], leaving out statuses
+/// with no task. Tasks keep their numbers, so a grouped listing can be
+/// acted on like a flat one.
+pub fn group_by_status<'a>(tasks: &[(usize, &'a Task)]) -> Vec<StatusGroup<'a>> {
+    GROUP_ORDER
+        .into_iter()
+        .map(|status| StatusGroup {
+            status,
+            tasks: tasks
+                .iter()
+                .copied()
+                .filter(|(_, task)| task.status == status)
+                .collect(),
+        })
+        .filter(|group| !group.tasks.is_empty())
+        .collect()
+}
+
+/// The listed tasks one line each, as the plain CLI listing writes them
+/// without search marks, with due dates as dates
+pub fn flat(todo: &TodoList, tasks: &[(usize, &Task)], theme: &Theme, today: Date) -> String {
+    let mut out = String::new();
+    write_rows(&mut out, todo, tasks, theme, today).expect("writing to a String cannot fail");
+    out
+}
+
+/// The listed tasks as [`flat`] writes them, under a header per status as
+/// [`group_by_status`] splits them, with a blank line between sections
+pub fn grouped(todo: &TodoList, tasks: &[(usize, &Task)], theme: &Theme, today: Date) -> String {
+    let sections: Vec<String> = group_by_status(tasks)
+        .iter()
+        .map(|group| {
+            format!(
+                "{}\n{}",
+                group.header(),
+                flat(todo, &group.tasks, theme, today)
+            )
+        })
+        .collect();
+    sections.join("\n\n")
+}
+
+// One line per task and subtask, with no line break after the last
+fn write_rows(
+    out: &mut impl Write,
+    todo: &TodoList,
+    tasks: &[(usize, &Task)],
+    theme: &Theme,
+    today: Date,
+) -> fmt::Result {
+    for (i, row) in rows(tasks.iter().copied()).enumerate() {
+        if i > 0 {
+            writeln!(out)?;
+        }
+        let due = due_date(row.task);
+        row.write_line(
+            out,
+            todo,
+            theme,
+            &row.task.description,
+            due.as_deref(),
+            today,
+        )?;
+    }
+    Ok(())
+}
+
+/// The whole list, one line per task and subtask as in the plain CLI
+/// listing. `{}` marks statuses with emoji; `{:#}` keeps to ASCII.
+impl Display for TodoList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return write!(f, "{}", msg!("listing.empty"));
+        }
+        let theme = if f.alternate() {
+            Theme::ascii()
+        } else {
+            Theme::default()
+        };
+        let tasks: Vec<(usize, &Task)> = self.list_tasks().collect();
+        write_rows(f, self, &tasks, &theme, Date::today())
+    }
+}
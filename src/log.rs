@@ -0,0 +1,152 @@
+use std::error::Error;
+use std::fmt::Arguments;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// How much goes into the log, from nothing at all to every step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    /// Every level, quietest first
+    pub const ALL: [Level; 6] = [
+        Level::Off,
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    /// Name as `RUST_LOG` takes it
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Off => "off",
+            Level::Error => "error",
+            Level::Warn => "warn",
+            Level::Info => "info",
+            Level::Debug => "debug",
+            Level::Trace => "trace",
+        }
+    }
+
+    /// The level a name stands for, in any case
+    pub fn from_name(name: &str) -> Option<Level> {
+        Level::ALL
+            .into_iter()
+            .find(|level| level.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// The level a `RUST_LOG` filter asks for this crate: `debug`, or
+    /// `rust_todo_cli=trace,other_crate=info`. Directives for other crates
+    /// are ignored, and of the rest the most verbose wins. A target without a
+    /// level, such as `rust_todo_cli`, logs everything.
+    pub fn from_filter(filter: &str) -> Option<Level> {
+        filter
+            .split(',')
+            .filter_map(|directive| match directive.split_once('=') {
+                Some((target, level)) if for_this_crate(target) => Level::from_name(level),
+                Some(_) => None,
+                None if for_this_crate(directive) => Some(Level::Trace),
+                None => Level::from_name(directive),
+            })
+            .max()
+    }
+
+    /// The level `RUST_LOG` asks for, Off when it is unset or names none
+    pub fn from_env() -> Level {
+        std::env::var("RUST_LOG")
+            .ok()
+            .and_then(|filter| Level::from_filter(&filter))
+            .unwrap_or(Level::Off)
+    }
+}
+
+// Whether a RUST_LOG target names this crate or one of its modules
+fn for_this_crate(target: &str) -> bool {
+    let target = target.trim();
+    let name = env!("CARGO_CRATE_NAME");
+    target == name || target.starts_with(&format!("{}::", name))
+}
+
+static LEVEL: AtomicUsize = AtomicUsize::new(Level::Off as usize);
+
+/// Log everything up to `level`; [`Level::Off`], the default, logs nothing
+pub fn set_level(level: Level) {
+    LEVEL.store(level as usize, Ordering::Relaxed);
+}
+
+/// The most verbose level being logged
+pub fn level() -> Level {
+    Level::ALL[LEVEL.load(Ordering::Relaxed)]
+}
+
+/// Whether lines at `level` are written
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && level <= self::level()
+}
+
+/// A log line as written: `[DEBUG rust_todo_cli::store] saved 3 tasks`
+pub fn format_line(level: Level, target: &str, text: Arguments) -> String {
+    format!("[{} {}] {}", level.name().to_uppercase(), target, text)
+}
+
+/// Write a line to stderr when `level` is being logged. stdout is left to
+/// the data, so logging never gets in the way of `--json`.
+/// [`log!`](crate::log!) is the shorter way to call it.
+pub fn write(level: Level, target: &str, text: Arguments) {
+    if enabled(level) {
+        eprintln!("{}", format_line(level, target, text));
+    }
+}
+
+/// An error followed by each error that caused it, outermost first
+pub fn chain(error: &dyn Error) -> Vec<String> {
+    let mut chain = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(error) = source {
+        chain.push(error.to_string());
+        source = error.source();
+    }
+    chain
+}
+
+/// Log an error with its whole source chain, one cause a line.
+/// [`log_error!`](crate::log_error!) is the shorter way to call it.
+pub fn error(target: &str, error: &dyn Error) {
+    if !enabled(Level::Error) {
+        return;
+    }
+    let chain = chain(error);
+    write(Level::Error, target, format_args!("{}", chain[0]));
+    for cause in &chain[1..] {
+        write(Level::Error, target, format_args!("  caused by: {}", cause));
+    }
+}
+
+/// Log a line at a level, formatted like `format!`, with the module it came
+/// from: `log!(Debug, "saved {} tasks to {}", count, path)`
+#[macro_export]
+macro_rules! log {
+    ($level:ident, $($arg:tt)+) => {
+        $crate::log::write(
+            $crate::log::Level::$level,
+            module_path!(),
+            format_args!($($arg)+),
+        )
+    };
+}
+
+/// Log an error and everything that caused it: `log_error!(error)`
+#[macro_export]
+macro_rules! log_error {
+    ($error:expr) => {
+        $crate::log::error(module_path!(), &$error)
+    };
+}
@@ -1,78 +1,805 @@
-use std::io::{self, Write};
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+
+use rust_todo_cli::{
+    VERSION,
+    changes::SessionStats,
+    command::{Command, ParseError, parse_command, split_chain},
+    history,
+    log::{self, Level},
+    messages::{self, Language},
+    msg,
+    prompt_line::{self, DEFAULT_PROMPT, PromptStatus},
+    remote,
+    sanitize::{self, sanitize_line},
+    settings::Settings,
+    store::{self, Store},
+    theme::Theme,
+    todo::{self, Task, TodoError, TodoList},
+    view::{DEFAULT_PAGE_SIZE, ListView},
+};
 
 use crate::{
-    parse::{
-        Command, handle_add, handle_clear, handle_remove, handle_save, handle_update, list_tasks,
-        parse_command, print_help,
+    handlers::{
+        auto_archive, auto_escalate, execute, handle_backup_restore, handle_renumber, load_history,
+        print_reminders, print_streak, print_version, report_parse_error, report_save,
+        report_unreadable_copy, warn_problems, warn_shadowed_filters, warn_too_long,
+        warn_unknown_statuses,
     },
-    todo::{Storable, TodoList},
+    output::{Icon, fail, info, notice, prompt, show, warning},
+    shutdown::Lines,
+    sync::FileSync,
 };
 
-mod todo;
+mod handlers;
+
+mod output;
+
+mod table;
+
+mod prompt;
+
+mod pomodoro;
+
+mod review;
+
+mod script;
+
+mod shutdown;
+
+mod sync;
+
+// Every flag parse_args takes, and whether it takes a value, for the
+// completion scripts
+pub const FLAGS: &[(&str, bool)] = &[
+    ("--plain", false),
+    ("--no-color", false),
+    ("--wrap", false),
+    ("--page-size", true),
+    ("--no-reminders", false),
+    ("--stale-days", true),
+    ("--escalate-after", true),
+    ("--auto-escalate", false),
+    ("--auto-archive-after", true),
+    ("--no-auto-archive", false),
+    ("--strict-transitions", false),
+    ("--history-size", true),
+    ("--strip-tokens", false),
+    ("--max-length", true),
+    ("--max-line", true),
+    ("--compact", false),
+    ("--read-only", false),
+    ("--force", false),
+    ("--wipe-guard", true),
+    ("--quiet", false),
+    ("-q", false),
+    ("--verbose", false),
+    ("-v", false),
+    ("--sync-url", true),
+    ("--sync-token", true),
+    ("--lang", true),
+    ("--prompt", true),
+    ("--theme", true),
+    ("--file", true),
+    ("--no-banner", false),
+    ("--version", false),
+    ("-V", false),
+];
+
+// Command-line flags given when starting the app
+struct Options {
+    plain: bool,
+    no_color: bool,
+    wrap: bool,
+    page_size: usize,
+    stale_days: u64,
+    // Days in todo before a task counts as stale for `escalate`, 0 for never
+    escalate_after: u64,
+    auto_escalate: bool,
+    // Days after completion when tasks move to the archive at startup, 0 for never
+    auto_archive_after: u64,
+    // Off for this run by --no-auto-archive, whatever the days say
+    auto_archive: bool,
+    // Statuses only move a step at a time, unless a command says --force
+    strict_transitions: bool,
+    history_size: usize,
+    max_length: usize,
+    // Longest line the prompt and files read in take, in bytes, 0 for any
+    max_line: usize,
+    reminders: bool,
+    strip_tokens: bool,
+    compact: bool,
+    // Turn down every command that would write the files, and save nothing
+    read_only: bool,
+    // Let an empty list be saved over a full file without asking
+    force: bool,
+    // Tasks the file may hold before emptying it needs a go-ahead, 0 for never
+    wipe_guard: usize,
+    // Leave out confirmations and hints, for scripts
+    quiet: bool,
+    // How many times --verbose was given: once for debug logs, twice for trace
+    verbose: usize,
+    sync_url: Option<String>,
+    sync_token: Option<String>,
+    language: Language,
+    // Template of the interactive prompt, see prompt_line::render
+    prompt: String,
+    // Markers, rules and colors of listings, from a preset or a theme file
+    theme: Theme,
+    // Task file to use instead of tasks.json in the current directory
+    file: Option<String>,
+    // Start the interactive session without the welcome lines and rule
+    no_banner: bool,
+    version: bool,
+    // Words of a one-shot command, e.g. `todo list --json`
+    command: Vec<String>,
+}
+
+fn parse_args() -> Options {
+    let mut options = Options {
+        plain: false,
+        no_color: false,
+        wrap: false,
+        page_size: DEFAULT_PAGE_SIZE,
+        stale_days: output::DEFAULT_STALE_DAYS,
+        escalate_after: 0,
+        auto_escalate: false,
+        // Set once in the environment, the archiving never needs thinking about
+        auto_archive_after: std::env::var("TODO_AUTO_ARCHIVE_AFTER")
+            .ok()
+            .and_then(|value| value.trim().parse().ok())
+            .unwrap_or(0),
+        auto_archive: true,
+        strict_transitions: std::env::var("TODO_STRICT_TRANSITIONS")
+            .is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false")),
+        history_size: history::DEFAULT_HISTORY_LIMIT,
+        max_length: todo::DEFAULT_MAX_LENGTH,
+        max_line: sanitize::DEFAULT_MAX_LINE,
+        reminders: true,
+        strip_tokens: false,
+        compact: false,
+        read_only: std::env::var("TODO_READONLY")
+            .is_ok_and(|value| !matches!(value.trim(), "" | "0" | "false")),
+        force: false,
+        wipe_guard: store::DEFAULT_WIPE_GUARD,
+        quiet: false,
+        verbose: 0,
+        sync_url: None,
+        // A flag shows in process listings, so the token can come from the environment
+        sync_token: std::env::var("TODO_SYNC_TOKEN").ok(),
+        language: Language::from_env(),
+        prompt: DEFAULT_PROMPT.to_string(),
+        theme: Theme::default(),
+        file: None,
+        no_banner: false,
+        version: false,
+        command: Vec::new(),
+    };
+    // Warnings about later flags already come in the language --lang names
+    messages::set_language(options.language);
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        // Everything after the first command word belongs to the command
+        if !options.command.is_empty() {
+            options.command.push(arg);
+            continue;
+        }
+        match arg.as_str() {
+            "--plain" => options.plain = true,
+            "--no-color" => options.no_color = true,
+            "--wrap" => options.wrap = true,
+            "--no-reminders" => options.reminders = false,
+            "--strip-tokens" => options.strip_tokens = true,
+            "--compact" => options.compact = true,
+            "--no-banner" => options.no_banner = true,
+            "--read-only" => options.read_only = true,
+            "--force" => options.force = true,
+            "--auto-escalate" => options.auto_escalate = true,
+            "--no-auto-archive" => options.auto_archive = false,
+            "--strict-transitions" => options.strict_transitions = true,
+            "--quiet" | "-q" => options.quiet = true,
+            "--verbose" | "-v" => options.verbose += 1,
+            "--sync-url" => match args.next() {
+                Some(url) => options.sync_url = Some(url),
+                None => notice!("{}", msg!("flag.sync_url")),
+            },
+            "--sync-token" => match args.next() {
+                Some(token) => options.sync_token = Some(token),
+                None => notice!("{}", msg!("flag.sync_token")),
+            },
+            "--lang" => match args.next() {
+                Some(code) => match Language::from_locale(&code) {
+                    Some(language) => {
+                        options.language = language;
+                        messages::set_language(language);
+                    }
+                    None => notice!(
+                        "{}",
+                        msg!(
+                            "flag.lang_unknown",
+                            code = code,
+                            language = options.language.code()
+                        )
+                    ),
+                },
+                None => notice!("{}", msg!("flag.lang")),
+            },
+            "--prompt" => match args.next() {
+                Some(template) => {
+                    for name in prompt_line::unknown_placeholders(&template) {
+                        let name = format!("{{{}}}", name);
+                        notice!("{}", msg!("flag.prompt_unknown", name = name));
+                    }
+                    options.prompt = template;
+                }
+                None => notice!("{}", msg!("flag.prompt")),
+            },
+            "--theme" => match args.next() {
+                Some(name) => match Theme::preset(&name) {
+                    Some(theme) => options.theme = theme,
+                    None => match Theme::load(&name) {
+                        Ok((theme, warnings)) => {
+                            for warning in warnings {
+                                notice!("{}", warning);
+                            }
+                            options.theme = theme;
+                        }
+                        Err(error) => {
+                            notice!("{}", msg!("flag.theme_failed", path = name, error = error))
+                        }
+                    },
+                },
+                None => notice!("{}", msg!("flag.theme")),
+            },
+            "--file" => match args.next() {
+                Some(path) => options.file = Some(path),
+                None => notice!("{}", msg!("flag.file", default = store::DATA_FILE)),
+            },
+            "--version" | "-V" => options.version = true,
+            "--page-size" => match args.next().map(|value| value.parse()) {
+                Some(Ok(size)) => options.page_size = size,
+                _ => notice!(
+                    "{}",
+                    msg!(
+                        "flag.number",
+                        flag = "--page-size",
+                        default = DEFAULT_PAGE_SIZE
+                    )
+                ),
+            },
+            "--stale-days" => match args.next().map(|value| value.parse()) {
+                Some(Ok(days)) => options.stale_days = days,
+                _ => notice!(
+                    "{}",
+                    msg!(
+                        "flag.number",
+                        flag = "--stale-days",
+                        default = output::DEFAULT_STALE_DAYS
+                    )
+                ),
+            },
+            "--escalate-after" => match args.next().map(|value| value.parse()) {
+                Some(Ok(days)) => options.escalate_after = days,
+                _ => notice!(
+                    "{}",
+                    msg!("flag.number", flag = "--escalate-after", default = 0)
+                ),
+            },
+            "--auto-archive-after" => match args.next().map(|value| value.parse()) {
+                Some(Ok(days)) => options.auto_archive_after = days,
+                _ => notice!(
+                    "{}",
+                    msg!("flag.number", flag = "--auto-archive-after", default = 0)
+                ),
+            },
+            "--wipe-guard" => match args.next().map(|value| value.parse()) {
+                Some(Ok(count)) => options.wipe_guard = count,
+                _ => notice!(
+                    "{}",
+                    msg!(
+                        "flag.number",
+                        flag = "--wipe-guard",
+                        default = store::DEFAULT_WIPE_GUARD
+                    )
+                ),
+            },
+            "--history-size" => match args.next().map(|value| value.parse()) {
+                Some(Ok(size)) => options.history_size = size,
+                _ => notice!(
+                    "{}",
+                    msg!(
+                        "flag.number",
+                        flag = "--history-size",
+                        default = history::DEFAULT_HISTORY_LIMIT
+                    )
+                ),
+            },
+            "--max-length" => match args.next().map(|value| value.parse()) {
+                Some(Ok(max)) => options.max_length = max,
+                _ => notice!(
+                    "{}",
+                    msg!(
+                        "flag.number",
+                        flag = "--max-length",
+                        default = todo::DEFAULT_MAX_LENGTH
+                    )
+                ),
+            },
+            "--max-line" => match args.next().map(|value| value.parse()) {
+                Some(Ok(max)) => options.max_line = max,
+                _ => notice!(
+                    "{}",
+                    msg!(
+                        "flag.number",
+                        flag = "--max-line",
+                        default = sanitize::DEFAULT_MAX_LINE
+                    )
+                ),
+            },
+            _ if arg.starts_with("--") => notice!("{}", msg!("flag.unknown", arg = arg)),
+            _ => options.command.push(arg),
+        }
+    }
+    options
+}
+
+fn main() -> ExitCode {
+    let options = parse_args();
+    let verbose = match options.verbose {
+        0 => Level::Off,
+        1 => Level::Debug,
+        _ => Level::Trace,
+    };
+    log::set_level(Level::from_env().max(verbose));
+    output::init(options.plain, options.no_color);
+    output::set_theme(options.theme.clone());
+    output::set_quiet(options.quiet);
+    output::set_stale_days(options.stale_days);
+    output::set_escalate_after(options.escalate_after);
+    if options.auto_escalate && options.escalate_after == 0 {
+        notice!("{}", msg!("flag.auto_escalate"));
+    }
+    output::set_wrap(options.wrap);
+    if let Some(url) = &options.sync_url
+        && let Err(error) = remote::set_endpoint(url, options.sync_token.as_deref())
+    {
+        notice!("{}  {}", Icon::Warning, error);
+    }
 
-mod parse;
+    // Nothing is loaded, so asking for the version never creates files
+    if options.version {
+        print_version(&store_at(&options));
+        return ExitCode::SUCCESS;
+    }
 
-const DATA_FILE: &str = "tasks.json";
+    if !options.command.is_empty() {
+        return run_once(&options.command.join(" "), &options);
+    }
 
-fn main() {
-    println!("Welcome to the Todo CLI!");
-    // println!("Type commands like: add \"Buy groceries\"");
-    println!("Type 'exit' to quit the application.");
-    println!("💡 Type 'help' to see available commands");
-    println!("-----------------------------------");
+    if !options.no_banner {
+        info!("{}", msg!("session.welcome", version = VERSION));
+        // show!("Type commands like: add \"Buy groceries\"");
+        info!("{}", msg!("session.exit_hint"));
+        info!("{} {}", Icon::Hint, msg!("hint.help"));
+    }
+    let store = open_store(&options);
+    if store.read_only {
+        info!("{} {}", Icon::Warning, msg!("session.read_only"));
+    }
+    if !options.no_banner {
+        output::banner_rule();
+    }
 
     // Load existing tasks using the Storable trait
-    let mut todo = match TodoList::load(DATA_FILE) {
+    let mut todo = match store.load() {
         Ok(list) => {
             if !list.is_empty() {
-                println!("✅ Loaded {} from existing tasks", list.len());
+                info!(
+                    "{} {}",
+                    Icon::Success,
+                    msg!("session.loaded", summary = list.summary())
+                );
             }
             list
         }
+        // A first run has nothing to load, which is no cause for a warning
+        Err(TodoError::FileError(error)) if error.kind() == io::ErrorKind::NotFound => {
+            store.new_list()
+        }
         Err(error) => {
-            println!("⚠️  Could not load tasks: {}", error);
-            TodoList::new()
+            warning!(
+                "{}  {}",
+                Icon::Warning,
+                msg!("session.load_failed", error = error)
+            );
+            store.new_list()
         }
     };
+    load_history(&mut todo, &store);
+    warn_too_long(&todo);
+    warn_unknown_statuses(&todo);
+    warn_shadowed_filters(&todo);
+    warn_problems(&todo);
+    if options.auto_escalate && !store.read_only {
+        auto_escalate(&mut todo, true);
+    }
+    if options.auto_archive && !store.read_only {
+        auto_archive(&mut todo, &store, options.auto_archive_after, true);
+    }
+
+    // Reminders would only clutter the output of piped sessions
+    if options.reminders && io::stdin().is_terminal() {
+        print_streak(&todo);
+        print_reminders(&todo);
+    }
+
+    // Pages only help someone reading along; a pipe gets every row
+    let page_size = if io::stdout().is_terminal() {
+        options.page_size
+    } else {
+        0
+    };
+    let mut view = ListView::new(page_size);
+    output::set_session(true);
+    shutdown::install();
+    let lines = Lines::new();
+    let mut sync = FileSync::new(&store, &todo);
+    // What the exit recap measures the session against
+    let mut started = todo.tasks().to_vec();
+    // Whether the save on the way out went through, for the exit code
+    let saved;
 
     loop {
-        print!("\n> ");
-        io::stdout().flush().unwrap();
+        let status = PromptStatus::new(&todo, &store.tasks, sync.has_unsaved_changes(&todo));
+        prompt!("\n{}", prompt_line::render(&options.prompt, &status));
 
-        let mut input = String::new();
-        if io::stdin().read_line(&mut input).is_err() {
-            println!("Error reading input");
-            continue;
-        }
+        let input = match lines.next() {
+            Some(Ok(input)) => input,
+            Some(Err(_)) => {
+                fail!("{}", msg!("session.input_error"));
+                continue;
+            }
+            // Interrupted: leave as `exit` would
+            None => {
+                show!();
+                saved = save_and_exit(&mut todo, &store, &mut sync, &started);
+                break;
+            }
+        };
 
-        let input = input.trim();
+        // A pasted blob is turned down whole, and what is left of a line once
+        // escape sequences and control characters are out is what runs
+        let line = input.trim_end_matches(['\n', '\r']);
+        let input = match sanitize_line(line, store.settings.max_line) {
+            Ok(input) => input,
+            Err(error) => {
+                output::report_error(&error);
+                continue;
+            }
+        };
 
         if input.is_empty() {
             continue;
         }
 
-        match parse_command(input) {
-            Command::Exit => {
-                if let Err(error) = todo.save(DATA_FILE) {
-                    println!("⚠️  Failed to save tasks: {}", error);
-                } else {
-                    println!("✅ Tasks saved successfully!");
+        // Changes made to the file elsewhere are picked up before the command
+        // runs. The page cursor and filter are kept: `more` filters afresh.
+        sync.check(&mut todo, &store);
+
+        let Some(commands) = parse_chain(&input) else {
+            continue;
+        };
+        let total = commands.len();
+        let mut exited = None;
+        for (step, command) in commands.into_iter().enumerate() {
+            output::set_failed(false);
+            match command {
+                Command::Exit => {
+                    exited = Some(save_and_exit(&mut todo, &store, &mut sync, &started));
+                    break;
+                }
+                command if refused(&command, &store) => {}
+                Command::Save(force) => {
+                    report_save(sync.save(&mut todo, &store, force), &store);
+                }
+                Command::Reload(yes) => sync.handle_reload(&mut todo, &store, yes),
+                // The files now hold what was restored, and so does the list
+                Command::BackupRestore(path, yes) => {
+                    if handle_backup_restore(&mut todo, &store, &path, yes) {
+                        sync.saved(&todo);
+                    }
                 }
-                println!(" Goodbye!");
+                // Copies kept by ID follow the tasks to their new IDs
+                Command::Renumber(yes) => {
+                    let renumbered = handle_renumber(&mut todo, yes);
+                    todo::rename_ids(&mut started, &renumbered);
+                    sync.renumbered(&renumbered);
+                }
+                command => execute(&mut todo, &store, &mut view, command),
+            }
+            if output::failed() {
+                report_failed_step(step + 1, total);
                 break;
             }
-            Command::Help => print_help(),
-            Command::List => list_tasks(&todo, None),
-            Command::ListByStatus(status) => list_tasks(&todo, Some(status)),
-            Command::Add(description) => handle_add(&mut todo, description),
-            Command::Update(index, status_str) => handle_update(&mut todo, index, &status_str),
-            Command::Remove(index) => handle_remove(&mut todo, index),
-            Command::Clear => handle_clear(&mut todo),
-            Command::Save => handle_save(&todo),
-            Command::Unknown(cmd) => {
-                println!("❓ Unknown command: '{}'", cmd);
-                println!("💡 Type 'help' to see available commands");
+        }
+        if let Some(exited) = exited {
+            saved = exited;
+            break;
+        }
+    }
+    if saved {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+// The commands chained on a line with `&&`, or None when one of them does
+// not parse. Nothing on the line runs then, so a typo further along cannot
+// leave the chain half done.
+fn parse_chain(input: &str) -> Option<Vec<Command>> {
+    let steps = split_chain(input);
+    let total = steps.len();
+    let mut commands = Vec::new();
+    for (step, words) in steps.iter().enumerate() {
+        match parse_command(words) {
+            Ok(command) => commands.push(command),
+            Err(ParseError::Empty) if total > 1 => {
+                fail!(
+                    "{} {}",
+                    Icon::Warning,
+                    msg!("chain.empty_step", step = step + 1)
+                );
+                return None;
+            }
+            Err(error) => {
+                report_parse_error(&error);
+                if total > 1 {
+                    info!(
+                        "{} {}",
+                        Icon::Hint,
+                        msg!("chain.not_run", step = step + 1, total = total)
+                    );
+                }
+                return None;
+            }
+        }
+    }
+    Some(commands)
+}
+
+// Say which step of a chain failed when the steps after it are skipped
+fn report_failed_step(step: usize, total: usize) {
+    if step < total {
+        info!(
+            "{} {}",
+            Icon::Hint,
+            msg!(
+                "chain.stopped",
+                step = step,
+                total = total,
+                skipped = total - step
+            )
+        );
+    }
+}
+
+// The data files: those next to --file when it was given, otherwise the
+// usual ones in the current directory
+fn store_at(options: &Options) -> Store {
+    match &options.file {
+        Some(path) => Store::at(path),
+        None => Store::default(),
+    }
+}
+
+// The data files, opened read-only when asked to or when the task file
+// cannot be written, say on a mounted backup, which is pointed out
+fn open_store(options: &Options) -> Store {
+    let mut store = Store {
+        wipe_guard: options.wipe_guard,
+        force: options.force,
+        settings: Settings {
+            max_length: options.max_length,
+            strict_transitions: options.strict_transitions,
+            strip_tokens: options.strip_tokens,
+            compact_files: options.compact,
+            max_line: options.max_line,
+            history_limit: options.history_size,
+        },
+        ..store_at(options)
+    };
+    if !options.read_only && !store.is_writable() {
+        warning!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("session.not_writable", path = store.tasks)
+        );
+        store.read_only = true;
+    }
+    store.read_only |= options.read_only;
+    store
+}
+
+// Whether a command that would write the files is turned down, as it is in a
+// read-only session. Reporting it fails the command like any other error.
+fn refused(command: &Command, store: &Store) -> bool {
+    let refused = store.read_only && command.writes();
+    if refused {
+        output::report_error(&TodoError::ReadOnly(command.name()));
+    }
+    refused
+}
+
+// Save before leaving the interactive session, on `exit` or when interrupted,
+// returning whether that worked. A read-only session has nothing to save and
+// does not try. Once interrupted there is no asking, so an empty list is not
+// saved over a full file, nor over changes made to it elsewhere.
+fn save_and_exit(
+    todo: &mut TodoList,
+    store: &Store,
+    sync: &mut FileSync,
+    started: &[Task],
+) -> bool {
+    if store.read_only {
+        info!(" {}", msg!("session.goodbye"));
+        return true;
+    }
+    let saved = if shutdown::requested() {
+        store.save(todo)
+    } else {
+        sync.save(todo, store, false)
+    };
+    let ok = saved.is_ok();
+    if let Err(error) = saved {
+        fail!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("session.save_failed", error = error)
+        );
+        keep_aside(&error, todo, store);
+    } else {
+        info!("{} {}", Icon::Success, msg!("session.saved"));
+        if io::stdin().is_terminal() {
+            print_recap(SessionStats::between(started, todo.tasks()));
+        }
+    }
+    info!(" {}", msg!("session.goodbye"));
+    ok
+}
+
+// When the file changed elsewhere and the list could not replace it, write
+// the list next to it, so neither side's changes are lost
+fn keep_aside(error: &TodoError, todo: &TodoList, store: &Store) {
+    if !matches!(
+        error,
+        TodoError::ConcurrentModification(_) | TodoError::UnreadableTasks(_)
+    ) {
+        return;
+    }
+    match store.save_aside(todo) {
+        Ok(path) => info!("{} {}", Icon::Hint, msg!("save.kept_aside", path = path)),
+        Err(error) => fail!(
+            "{}  {}",
+            Icon::Warning,
+            msg!("session.save_failed", error = error)
+        ),
+    }
+}
+
+// One line on what the session did, left out when it did nothing:
+// "This session: +3 added, ✔2 completed, ✖1 removed"
+fn print_recap(stats: SessionStats) {
+    if stats.is_empty() {
+        return;
+    }
+    let (done, gone) = if output::is_plain() {
+        ("*", "-")
+    } else {
+        ("✔", "✖")
+    };
+    let parts: Vec<String> = [
+        (
+            stats.added,
+            "+",
+            msg!("session.recap_added", count = stats.added),
+        ),
+        (
+            stats.completed,
+            done,
+            msg!("session.recap_completed", count = stats.completed),
+        ),
+        (
+            stats.removed,
+            gone,
+            msg!("session.recap_removed", count = stats.removed),
+        ),
+        (
+            stats.edited,
+            "~",
+            msg!("session.recap_edited", count = stats.edited),
+        ),
+    ]
+    .into_iter()
+    .filter(|(count, _, _)| *count > 0)
+    .map(|(_, mark, text)| format!("{}{}", mark, text))
+    .collect();
+    info!("{}", msg!("session.recap", parts = parts.join(", ")));
+}
+
+// Run a single command given on the command line, without banner or prompt.
+// Fails when any error was reported, so scripts can check the exit code.
+fn run_once(input: &str, options: &Options) -> ExitCode {
+    let store = open_store(options);
+    let mut todo = match store.load() {
+        Ok(list) => list,
+        Err(TodoError::FileError(error)) if error.kind() == io::ErrorKind::NotFound => {
+            store.new_list()
+        }
+        Err(error) => {
+            notice!(
+                "{}  {}",
+                Icon::Warning,
+                msg!("session.load_failed", error = error)
+            );
+            store.new_list()
+        }
+    };
+    load_history(&mut todo, &store);
+    warn_too_long(&todo);
+    warn_unknown_statuses(&todo);
+    warn_shadowed_filters(&todo);
+    warn_problems(&todo);
+
+    // Looked for in the words rather than the command, so a command that
+    // does not parse is reported as JSON too
+    output::set_json_errors(input.split_whitespace().any(|word| word == "--json"));
+    let Some(commands) = parse_chain(input) else {
+        return ExitCode::FAILURE;
+    };
+    let escalated = options.auto_escalate && !store.read_only && auto_escalate(&mut todo, false);
+    let archived = options.auto_archive
+        && !store.read_only
+        && auto_archive(&mut todo, &store, options.auto_archive_after, false);
+    let mutates = commands.iter().any(Command::mutates) || escalated || archived;
+    // There is no `more` after a single command, so it lists everything
+    let mut view = ListView::new(0);
+    let total = commands.len();
+    // The exit code tells of the first failure, after which nothing runs
+    for (step, command) in commands.into_iter().enumerate() {
+        if !refused(&command, &store) {
+            execute(&mut todo, &store, &mut view, command);
+        }
+        if output::failed() {
+            report_failed_step(step + 1, total);
+            break;
+        }
+    }
+
+    if mutates && !store.read_only {
+        let wiping = store.would_wipe(&todo).is_some();
+        let replacing = store.is_unreadable();
+        match store.save(&todo) {
+            Ok(()) if wiping => info!(
+                "{} {}",
+                Icon::Hint,
+                msg!("save.backed_up", path = store.wipe_backup())
+            ),
+            Ok(()) if replacing => report_unreadable_copy(&store),
+            Ok(()) => {}
+            Err(error) => {
+                fail!(
+                    "{}  {}",
+                    Icon::Warning,
+                    msg!("session.save_failed", error = error)
+                );
+                keep_aside(&error, &todo, &store);
             }
         }
     }
+    if output::failed() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
 }
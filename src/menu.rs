@@ -0,0 +1,68 @@
+use std::io::{self, BufRead, Write};
+
+use crate::date::Date;
+use crate::listing;
+use crate::msg;
+use crate::theme::Theme;
+use crate::todo::{ListFilter, TodoList};
+
+/// Ask which of the tasks matching `filter` is meant. They are listed with
+/// their numbers, drawn with `theme`, and the number typed is read from
+/// `input`. An answer not in the menu is asked again; an empty one, Escape,
+/// or the end of input cancels with `None`, as does a menu with nothing in
+/// it.
+pub fn select_task(
+    todo: &TodoList,
+    filter: &ListFilter,
+    theme: &Theme,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+) -> io::Result<Option<usize>> {
+    let candidates: Vec<usize> = todo
+        .filter(filter)
+        .iter()
+        .map(|(index, _)| *index)
+        .collect();
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+    writeln!(out, "{}", msg!("pick.heading"))?;
+    let today = Date::today();
+    for row in listing::rows(todo.filter(filter)).filter(|row| !row.is_sub) {
+        let due = listing::due_date(row.task);
+        let line = row.line(todo, theme, &row.task.description, due.as_deref(), today);
+        writeln!(out, "  {}", line)?;
+    }
+
+    loop {
+        write!(out, "{} ", msg!("pick.prompt"))?;
+        out.flush()?;
+        let mut answer = String::new();
+        if input.read_line(&mut answer)? == 0 {
+            return Ok(None);
+        }
+        let answer = answer.trim();
+        if answer.is_empty() || answer.contains('\u{1b}') {
+            return Ok(None);
+        }
+        match answer.parse::<usize>() {
+            Ok(index) if candidates.contains(&index) => return Ok(Some(index)),
+            _ => writeln!(out, "{}", msg!("pick.not_listed", answer = answer))?,
+        }
+    }
+}
+
+/// Ask `question` and read a yes or no from `input`. Only y or yes, or the
+/// words for them in the current language, in any case, count as yes;
+/// anything else is no, an empty answer and the end of input included.
+pub fn confirm(question: &str, input: &mut impl BufRead, out: &mut impl Write) -> io::Result<bool> {
+    write!(out, "{} {} ", question, msg!("prompt.yes_no"))?;
+    out.flush()?;
+    let mut answer = String::new();
+    input.read_line(&mut answer)?;
+    let answer = answer.trim().to_lowercase();
+    Ok(matches!(answer.as_str(), "y" | "yes")
+        || msg!("prompt.yes_words")
+            .split('|')
+            .any(|word| word == answer))
+}
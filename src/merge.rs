@@ -0,0 +1,362 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use crate::todo::{Status, Task, TaskIndex, TodoError, read_list};
+
+/// Which copy of the list to take a conflicting description from
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+/// A task whose description was edited differently in the two copies.
+/// Until it is resolved, the merged task has our description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    /// Where the task is in the merged tasks, numbered from 1
+    pub at: TaskIndex,
+    pub ours: String,
+    pub theirs: String,
+}
+
+/// The outcome of [`merge`]: the merged tasks, what happened to them and
+/// the conflicts left to settle. Counts are of tasks, not subtasks.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MergeReport {
+    /// Our tasks in our order, each merged with its match, then the tasks
+    /// only they have in their order
+    pub tasks: Vec<Task>,
+    /// Tasks found in both copies
+    pub matched: usize,
+    /// Matched tasks that took a status, date or anything else but the
+    /// description from their copy
+    pub updated: usize,
+    /// Tasks only in our copy
+    pub only_ours: usize,
+    /// Tasks only in their copy
+    pub only_theirs: usize,
+    /// Descriptions edited differently, subtasks included
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergeReport {
+    /// Settle the `n`th conflict, counting from 0, with one copy's
+    /// description. Conflicts stay listed once resolved.
+    pub fn resolve(&mut self, n: usize, side: Side) {
+        let Some(conflict) = self.conflicts.get(n) else {
+            return;
+        };
+        let description = match side {
+            Side::Ours => conflict.ours.clone(),
+            Side::Theirs => conflict.theirs.clone(),
+        };
+        let task = &mut self.tasks[conflict.at.task - 1];
+        let task = match conflict.at.sub {
+            Some(sub) => &mut task.subtasks[sub - 1],
+            None => task,
+        };
+        task.description = description;
+    }
+
+    /// Settle every conflict the same way, as `merge --ours` and
+    /// `merge --theirs` do
+    pub fn resolve_all(&mut self, side: Side) {
+        for n in 0..self.conflicts.len() {
+            self.resolve(n, side);
+        }
+    }
+}
+
+/// Read another copy of the list to merge. Tasks saved before IDs existed
+/// keep ID 0, so they are matched by description.
+pub fn read_tasks(path: &str) -> Result<Vec<Task>, TodoError> {
+    Ok(read_list(path)?.tasks().to_vec())
+}
+
+/// Pair up the tasks of two copies of a list, by ID where both tasks have
+/// one, otherwise by description, ignoring case. Tasks with the same ID but
+/// created at different times were added to each copy on its own, so they
+/// are not paired, while ones with the same description created at the same
+/// time are one task under another ID, as `renumber` leaves it. Each pair holds positions in `ours` and `theirs`; a task
+/// without a match has None on the other side. Our tasks come first in our
+/// order, then the rest of theirs.
+pub fn match_tasks(ours: &[Task], theirs: &[Task]) -> Vec<(Option<usize>, Option<usize>)> {
+    let mut partners: Vec<Option<usize>> = vec![None; ours.len()];
+    let mut taken = vec![false; theirs.len()];
+
+    let by_id: HashMap<u64, usize> = theirs
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| task.id != 0)
+        .map(|(j, task)| (task.id, j))
+        .collect();
+    for (i, task) in ours.iter().enumerate() {
+        if task.id == 0 {
+            continue;
+        }
+        if let Some(&j) = by_id.get(&task.id)
+            && !taken[j]
+            && same_creation(task, &theirs[j])
+        {
+            partners[i] = Some(j);
+            taken[j] = true;
+        }
+    }
+
+    let mut by_description: HashMap<String, Vec<usize>> = HashMap::new();
+    for (j, task) in theirs.iter().enumerate() {
+        if !taken[j] {
+            by_description.entry(key(task)).or_default().push(j);
+        }
+    }
+    for (i, task) in ours.iter().enumerate() {
+        if partners[i].is_some() {
+            continue;
+        }
+        let Some(candidates) = by_description.get(&key(task)) else {
+            continue;
+        };
+        let found = candidates.iter().copied().find(|&j| {
+            !taken[j]
+                && (task.id == 0
+                    || theirs[j].id == 0
+                    || (task.created_at.is_some() && task.created_at == theirs[j].created_at))
+        });
+        if let Some(j) = found {
+            partners[i] = Some(j);
+            taken[j] = true;
+        }
+    }
+
+    let mut pairs: Vec<(Option<usize>, Option<usize>)> = partners
+        .into_iter()
+        .enumerate()
+        .map(|(i, j)| (Some(i), j))
+        .collect();
+    pairs.extend(
+        (0..theirs.len())
+            .filter(|&j| !taken[j])
+            .map(|j| (None, Some(j))),
+    );
+    pairs
+}
+
+// Whether two tasks could have been created as one, as far as is known
+fn same_creation(ours: &Task, theirs: &Task) -> bool {
+    match (ours.created_at, theirs.created_at) {
+        (Some(ours), Some(theirs)) => ours == theirs,
+        _ => true,
+    }
+}
+
+// What descriptions are matched by
+fn key(task: &Task) -> String {
+    task.description.trim().to_lowercase()
+}
+
+// How far along a status is. Done and cancelled are both as far as a task
+// goes; parking a task does not move it along.
+fn progress(status: Status) -> u8 {
+    match status {
+        Status::Todo | Status::Waiting => 0,
+        Status::InProgress => 1,
+        Status::Completed | Status::Cancelled => 2,
+    }
+}
+
+/// Combine two copies of the same task, keeping our ID and description.
+/// The status furthest along wins, with its completion time; between two
+/// closed tasks the one closed later wins. Otherwise the newer timestamps
+/// and the later due date, with its time, are kept, except that the task was created at
+/// the earlier time. Tags, tokens and dependencies are combined, and
+/// subtasks are merged like tasks.
+pub fn merge_task(ours: &Task, theirs: &Task) -> Task {
+    let their_status = match progress(theirs.status).cmp(&progress(ours.status)) {
+        Ordering::Greater => true,
+        Ordering::Less => false,
+        Ordering::Equal => theirs.completed_at > ours.completed_at,
+    };
+    let (status, completed_at, wait_reason) = if their_status {
+        (theirs.status, theirs.completed_at, &theirs.wait_reason)
+    } else {
+        (ours.status, ours.completed_at, &ours.wait_reason)
+    };
+    // The later delegation, unless the task ended up done
+    let (waiting_on, delegated_at) = if status == Status::Completed {
+        (None, None)
+    } else if theirs.delegated_at > ours.delegated_at {
+        (theirs.waiting_on.clone(), theirs.delegated_at)
+    } else {
+        (ours.waiting_on.clone(), ours.delegated_at)
+    };
+    let created_at = match (ours.created_at, theirs.created_at) {
+        (Some(ours), Some(theirs)) => Some(ours.min(theirs)),
+        (ours, theirs) => ours.or(theirs),
+    };
+
+    let (due, due_time) = (ours.due, ours.due_time).max((theirs.due, theirs.due_time));
+
+    let subtasks = match_tasks(&ours.subtasks, &theirs.subtasks)
+        .into_iter()
+        .map(|pair| match pair {
+            (Some(i), Some(j)) => merge_task(&ours.subtasks[i], &theirs.subtasks[j]),
+            (Some(i), None) => ours.subtasks[i].clone(),
+            (None, Some(j)) => theirs.subtasks[j].clone(),
+            (None, None) => unreachable!("every pair has a task"),
+        })
+        .collect();
+
+    Task {
+        id: ours.id,
+        description: ours.description.clone(),
+        status,
+        created_at,
+        completed_at,
+        updated_at: ours.updated_at.max(theirs.updated_at),
+        due,
+        due_time,
+        recurrence: ours.recurrence.or(theirs.recurrence),
+        priority: ours.priority.max(theirs.priority),
+        escalated_at: ours.escalated_at.max(theirs.escalated_at),
+        estimate: ours.estimate.or(theirs.estimate),
+        wait_reason: wait_reason.clone(),
+        waiting_on,
+        delegated_at,
+        tags: union(&ours.tags, &theirs.tags),
+        projects: union(&ours.projects, &theirs.projects),
+        contexts: union(&ours.contexts, &theirs.contexts),
+        marker: ours.marker.clone().or_else(|| theirs.marker.clone()),
+        marker_color: ours
+            .marker_color
+            .clone()
+            .or_else(|| theirs.marker_color.clone()),
+        source: ours.source.clone().or_else(|| theirs.source.clone()),
+        subtasks,
+        depends_on: union(&ours.depends_on, &theirs.depends_on),
+        accumulated: ours.accumulated.max(theirs.accumulated),
+        timer_started: ours.timer_started.max(theirs.timer_started),
+    }
+}
+
+// Ours, followed by what only theirs has, in order
+fn union<T: Clone + PartialEq>(ours: &[T], theirs: &[T]) -> Vec<T> {
+    let mut all = ours.to_vec();
+    for item in theirs {
+        if !all.contains(item) {
+            all.push(item.clone());
+        }
+    }
+    all
+}
+
+/// Reconcile two copies of a list that went their own ways, such as the
+/// list here and one edited on another machine. Nothing is dropped: tasks
+/// in one copy only are kept, and matched tasks are combined by
+/// [`merge_task`]. Their tasks keep their IDs where ours do not use them
+/// and get fresh ones otherwise, with their dependencies following along.
+pub fn merge(ours: &[Task], theirs: &[Task]) -> MergeReport {
+    let pairs = match_tasks(ours, theirs);
+
+    // Their IDs as they are in the merged list, by position and by old ID
+    let mut used: HashSet<u64> = ours.iter().map(|task| task.id).collect();
+    let mut next = ours
+        .iter()
+        .chain(theirs)
+        .map(|task| task.id)
+        .max()
+        .unwrap_or(0)
+        + 1;
+    let mut new_ids = vec![0; theirs.len()];
+    for pair in &pairs {
+        match *pair {
+            (Some(i), Some(j)) => new_ids[j] = ours[i].id,
+            (None, Some(j)) => {
+                let id = theirs[j].id;
+                new_ids[j] = if id != 0 && used.insert(id) {
+                    id
+                } else {
+                    next += 1;
+                    next - 1
+                };
+            }
+            _ => {}
+        }
+    }
+    let ids: HashMap<u64, u64> = theirs
+        .iter()
+        .zip(&new_ids)
+        .filter(|(task, _)| task.id != 0)
+        .map(|(task, &new)| (task.id, new))
+        .collect();
+    let renumbered = |j: usize| Task {
+        id: new_ids[j],
+        depends_on: theirs[j]
+            .depends_on
+            .iter()
+            .filter_map(|id| ids.get(id).copied())
+            .collect(),
+        ..theirs[j].clone()
+    };
+
+    let mut report = MergeReport::default();
+    for (position, pair) in pairs.into_iter().enumerate() {
+        let task = match pair {
+            (Some(i), Some(j)) => {
+                let (ours, theirs) = (&ours[i], renumbered(j));
+                let merged = merge_task(ours, &theirs);
+                report.matched += 1;
+                if merged != *ours {
+                    report.updated += 1;
+                }
+                report
+                    .conflicts
+                    .extend(conflicts(position + 1, ours, &theirs));
+                merged
+            }
+            (Some(i), None) => {
+                report.only_ours += 1;
+                ours[i].clone()
+            }
+            (None, Some(j)) => {
+                report.only_theirs += 1;
+                renumbered(j)
+            }
+            (None, None) => unreachable!("every pair has a task"),
+        };
+        report.tasks.push(task);
+    }
+    report
+}
+
+// Descriptions of a matched task and its matched subtasks that differ
+fn conflicts(number: usize, ours: &Task, theirs: &Task) -> Vec<Conflict> {
+    let mut found = Vec::new();
+    if ours.description != theirs.description {
+        found.push(Conflict {
+            at: TaskIndex {
+                task: number,
+                sub: None,
+            },
+            ours: ours.description.clone(),
+            theirs: theirs.description.clone(),
+        });
+    }
+    let pairs = match_tasks(&ours.subtasks, &theirs.subtasks);
+    for (position, pair) in pairs.into_iter().enumerate() {
+        if let (Some(i), Some(j)) = pair {
+            let (ours, theirs) = (&ours.subtasks[i], &theirs.subtasks[j]);
+            if ours.description != theirs.description {
+                found.push(Conflict {
+                    at: TaskIndex {
+                        task: number,
+                        sub: Some(position + 1),
+                    },
+                    ours: ours.description.clone(),
+                    theirs: theirs.description.clone(),
+                });
+            }
+        }
+    }
+    found
+}
@@ -0,0 +1,152 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+mod en;
+mod es;
+
+/// A language the messages come in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Language {
+    English,
+    Spanish,
+}
+
+impl Language {
+    /// Every bundled language, English first
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    /// Two-letter code, as `--lang` takes it
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// The bundled language of a locale name such as `es_ES.UTF-8`, `es`
+    /// or `en-GB`. `C` and `POSIX` are English.
+    pub fn from_locale(locale: &str) -> Option<Language> {
+        let code = locale.split(['_', '-', '.', '@']).next().unwrap_or("");
+        match code.to_ascii_lowercase().as_str() {
+            "en" | "c" | "posix" => Some(Language::English),
+            "es" => Some(Language::Spanish),
+            _ => None,
+        }
+    }
+
+    /// The language the environment asks for: the first of `LC_ALL`,
+    /// `LC_MESSAGES` and `LANG` that is set, or English when that one is
+    /// not bundled
+    pub fn from_env() -> Language {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|locale| Language::from_locale(&locale))
+            .unwrap_or(Language::English)
+    }
+
+    /// Every message in this language, as key and text
+    pub fn catalog(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => en::MESSAGES,
+            Language::Spanish => es::MESSAGES,
+        }
+    }
+
+    // Whether a count takes the singular form
+    fn is_singular(self, count: &str) -> bool {
+        match self {
+            Language::English | Language::Spanish => count.trim() == "1",
+        }
+    }
+}
+
+// Position in Language::ALL of the language messages are shown in
+static LANGUAGE: AtomicUsize = AtomicUsize::new(0);
+
+/// Show messages in this language from now on
+pub fn set_language(language: Language) {
+    let position = Language::ALL
+        .iter()
+        .position(|&known| known == language)
+        .unwrap_or(0);
+    LANGUAGE.store(position, Ordering::Relaxed);
+}
+
+/// The language messages are shown in, English unless set
+pub fn language() -> Language {
+    Language::ALL[LANGUAGE.load(Ordering::Relaxed)]
+}
+
+/// The text under `key` in `language`, or in English when the language
+/// lacks it. An unknown key comes back as it is.
+pub fn lookup(language: Language, key: &'static str) -> &'static str {
+    let find = |language: Language| {
+        language
+            .catalog()
+            .iter()
+            .find(|(known, _)| *known == key)
+            .map(|(_, text)| *text)
+    };
+    find(language)
+        .or_else(|| find(Language::English))
+        .unwrap_or(key)
+}
+
+/// Fill in a message's placeholders. `{name}` is replaced with the
+/// argument of that name, and `{name|one|other}` with the form that suits
+/// it as a count: `{count} {count|task|tasks}`. Placeholders without an
+/// argument are left as they are.
+pub fn render(language: Language, text: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let inside = &rest[open + 1..];
+        let Some(close) = inside.find('}') else {
+            rest = &rest[open..];
+            break;
+        };
+        let field = &inside[..close];
+        let mut parts = field.split('|');
+        let name = parts.next().unwrap_or_default();
+        match args.iter().find(|(known, _)| *known == name) {
+            Some((_, value)) => {
+                let value = value.to_string();
+                match (parts.next(), parts.next()) {
+                    (Some(one), Some(_)) if language.is_singular(&value) => out.push_str(one),
+                    (Some(_), Some(other)) => out.push_str(other),
+                    _ => out.push_str(&value),
+                }
+            }
+            None => {
+                out.push('{');
+                out.push_str(field);
+                out.push('}');
+            }
+        }
+        rest = &inside[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The message under `key` in the current language, filled in with `args`.
+/// [`msg!`](crate::msg) is the shorter way to call it.
+pub fn message(key: &'static str, args: &[(&str, &dyn Display)]) -> String {
+    let language = language();
+    render(language, lookup(language, key), args)
+}
+
+/// The message under a key in the current language, with named arguments:
+/// `msg!("task.marked_done", index = 3)`
+#[macro_export]
+macro_rules! msg {
+    ($key:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::messages::message(
+            $key,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),*],
+        )
+    };
+}
@@ -0,0 +1,983 @@
+// English, which messages missing from another language fall back to
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    ("error.invalid_index", "Index must start from 1"),
+    (
+        "error.invalid_status",
+        "Status {status} not recognized. Use: {statuses}",
+    ),
+    (
+        "error.invalid_status_suggest",
+        "Status {status} not recognized, did you mean {suggestion}? Use: {statuses}",
+    ),
+    (
+        "error.invalid_transition",
+        "Strict transitions don't let a task go from {from} to {to}; from {from} it can go to {allowed}. Add --force to do it anyway",
+    ),
+    (
+        "error.index_out_of_bound",
+        "No task exists at that index {index}",
+    ),
+    (
+        "error.empty_description",
+        "Task description cannot be empty",
+    ),
+    (
+        "error.description_too_long",
+        "Description is {len} characters long, the limit is {max}",
+    ),
+    (
+        "error.line_too_long",
+        "Line is {len} bytes long, the limit is {max} (see --max-line), so it was left alone: {start}…",
+    ),
+    (
+        "error.invalid_date",
+        "Date {date} not recognized. Use e.g.: 2025-06-01, today, tomorrow, friday, next week, in 3 days, tomorrow 9am",
+    ),
+    (
+        "error.invalid_duration",
+        "Duration {duration} not recognized. Use e.g.: 2d, 1w, 12h, 1h30m",
+    ),
+    (
+        "error.invalid_estimate",
+        "Estimate {estimate} not recognized. Use e.g.: 45m, 2h, 1h30m, 1d",
+    ),
+    ("error.task_gone", "Task {index} is no longer in the list"),
+    ("error.not_in_view", "Task {index} is not in this view"),
+    ("error.unknown_id", "No task has ID {id}"),
+    (
+        "error.subtask_out_of_bound",
+        "Task {task} has no subtask {sub}",
+    ),
+    ("error.no_match", "No task matches \"{text}\""),
+    (
+        "error.ambiguous_match",
+        "\"{text}\" matches {count} tasks, use a task number or more text",
+    ),
+    (
+        "error.subtask_not_allowed",
+        "{index} is a subtask; this command takes a task number",
+    ),
+    (
+        "error.dependency_cycle",
+        "Task {task} cannot depend on task {on}: that would create a dependency cycle",
+    ),
+    (
+        "error.already_completed",
+        "Task {index} is already completed",
+    ),
+    (
+        "error.not_completed",
+        "Task {index} is {status}, not done, so there is nothing to reopen",
+    ),
+    ("error.nothing_completed", "No completed task to reopen"),
+    (
+        "error.invalid_interval",
+        "Interval {interval} not recognized. Use e.g.: every 3d, every 2w, daily, weekly",
+    ),
+    (
+        "error.invalid_marker",
+        "Marker '{marker}' not valid. Use one word or emoji, such as 🔥 or VIP",
+    ),
+    (
+        "error.unknown_color",
+        "Unknown color '{color}'. Use one of: {colors}",
+    ),
+    (
+        "error.invalid_tag",
+        "Tag {tag} not valid. Use a word, with or without the leading #",
+    ),
+    (
+        "error.invalid_person",
+        "Person {person} not valid. Use a name, with or without the leading @",
+    ),
+    (
+        "error.conflicting_options",
+        "Conflicting options: {options}",
+    ),
+    (
+        "error.closed_cannot_repeat",
+        "a completed or cancelled task cannot repeat",
+    ),
+    (
+        "error.no_link",
+        "Task {index} has no link in its description",
+    ),
+    (
+        "error.link_out_of_bound",
+        "Task {index} has only {count} {count|link|links}",
+    ),
+    (
+        "error.invalid_template_name",
+        "Template name {name} not valid. Use a word, or \"quote\" a name with spaces",
+    ),
+    (
+        "error.unknown_template",
+        "No template named \"{name}\". See 'template list'",
+    ),
+    (
+        "error.invalid_filter_name",
+        "Filter name {name} not valid. Use one word of letters, digits, - and _, starting with a letter",
+    ),
+    (
+        "error.reserved_filter_name",
+        "\"{name}\" already means something to list, so it can't name a filter",
+    ),
+    (
+        "error.unknown_saved_filter",
+        "No saved filter named \"{name}\". See 'filter list'",
+    ),
+    (
+        "error.invalid_saved_filter",
+        "Saved filter \"{name}\" no longer works: {error}",
+    ),
+    (
+        "error.invalid_snippet_name",
+        "Snippet name {name} not valid. Use one word of letters, digits, - and _, starting with a letter",
+    ),
+    (
+        "error.unknown_snippet",
+        "No snippet named \"{name}\". See 'snippet list'",
+    ),
+    (
+        "error.invalid_snippet",
+        "Snippet \"{text}\" has a brace that isn't around a placeholder's name. Double a brace to keep it as text",
+    ),
+    (
+        "error.missing_placeholders",
+        "Missing {count|a value|values} for {missing}. This snippet takes: {needed}",
+    ),
+    (
+        "error.unknown_placeholder",
+        "This snippet has no placeholder {key}. It takes: {needed}",
+    ),
+    ("error.no_placeholders", "no values"),
+    (
+        "error.renumber_blocked",
+        "Can't renumber while {count} {count|task shares its ID or depends on a missing task|tasks share their IDs or depend on missing tasks}; run 'doctor --fix' first",
+    ),
+    ("error.serialization", "Failed to serialize tasks: {error}"),
+    ("error.file", "Failed to access file: {error}"),
+    ("error.sync", "Sync failed: {error}"),
+    (
+        "error.clipboard",
+        "Could not use the clipboard: {error} (use show to see the task instead)",
+    ),
+    ("error.clipboard_exit", "{program} exited with {status}"),
+    (
+        "error.clipboard_headless",
+        "there is no display to reach it through",
+    ),
+    ("error.clipboard_no_tool", "none of {tools} is installed"),
+    (
+        "error.not_interactive",
+        "--pick needs a terminal to ask which task, give its number instead",
+    ),
+    (
+        "error.read_only",
+        "{command} would write to the task files, which are open read-only",
+    ),
+    (
+        "error.would_wipe",
+        "the list is empty and would replace the {count} tasks in the file; --force saves it anyway",
+    ),
+    (
+        "error.unreadable_tasks",
+        "{path} could not be read, so it is not saved over; fix it and reload, or use --force to replace it (a copy is kept)",
+    ),
+    (
+        "error.concurrent_modification",
+        "{path} was changed elsewhere since it was read; reload or merge first, or `save --force` to replace those changes",
+    ),
+    (
+        "error.not_a_backup",
+        "{path} is not a backup made by this app",
+    ),
+    (
+        "error.backup_too_new",
+        "the backup is format version {version}, newer than the {supported} this version reads; upgrade to restore it",
+    ),
+    (
+        "error.bad_backup_section",
+        "the backup's {section} section is damaged: {error}",
+    ),
+    (
+        "error.cli_only",
+        "{command} needs the command-line app, with its terminal and its files",
+    ),
+    ("error.browser_exit", "the browser exited with {status}"),
+    ("error.empty_command", "No command given"),
+    ("error.usage", "Usage: {usage}"),
+    (
+        "error.unknown_filter",
+        "Unknown filter '{filter}'. Supported: status:<status>, tag:<name>, waiting-on:<name>",
+    ),
+    (
+        "error.unknown_count",
+        "Cannot count '{target}'. Count one of: {targets}",
+    ),
+    ("error.invalid_task_number", "Invalid task number."),
+    ("error.invalid_trash_number", "Invalid trash number."),
+    (
+        "error.unquoted_name",
+        "Template names with spaces need quotes, e.g.: template {action} \"new release\"",
+    ),
+    (
+        "error.missing_status",
+        "Missing the new status. Usage: update {selector} <new_status>",
+    ),
+    (
+        "error.ambiguous_command",
+        "Ambiguous command '{word}': could be {candidates}",
+    ),
+    ("error.unknown_command", "Unknown command '{word}'"),
+    (
+        "error.unknown_command_suggest",
+        "Unknown command '{word}'. Did you mean {suggestions}?",
+    ),
+    (
+        "error.list_count",
+        "list [status] {flag} <count>\n   The count must be a number of at least 1.",
+    ),
+    ("error.shown", "Error: {error}"),
+    ("error.read", "Error: could not read {path}: {error}"),
+    ("error.write", "Error: could not write {path}: {error}"),
+    ("list.or", "{rest} or {last}"),
+    ("list.no_match", "No tasks match: {filter}"),
+    (
+        "list.waiting_hidden",
+        "{count} waiting {count|task|tasks} not shown; `list all` includes them",
+    ),
+    (
+        "list.estimated_left",
+        "Estimated time left: {time} over {count} {count|task|tasks}",
+    ),
+    ("fuzzy.none", "No good matches for \"{text}\""),
+    ("fuzzy.title", "Closest to \"{text}\""),
+    (
+        "list.empty",
+        "No tasks yet. Add one with: add <description>",
+    ),
+    (
+        "list.no_more",
+        "Nothing more to show. Type 'list' to start over",
+    ),
+    ("list.showing", "Showing {range} of {total}"),
+    (
+        "list.showing_more",
+        "Showing {range} of {total}, type 'more' for the next page",
+    ),
+    ("summary.empty", "No tasks"),
+    (
+        "summary.counts",
+        "{total} {total|task|tasks}: {todo} todo, {in_progress} in-progress, {done} done",
+    ),
+    ("summary.cancelled", ", {count} cancelled"),
+    ("summary.waiting", ", {count} waiting"),
+    ("sync.not_a_list", "remote copy is not a task list: {error}"),
+    (
+        "sync.url_scheme",
+        "URL {url} is not supported; use an https:// or http:// URL",
+    ),
+    (
+        "sync.url_no_scheme",
+        "URL {url} needs to start with https:// or http://",
+    ),
+    ("sync.url_port", "URL {url} has an invalid port"),
+    ("sync.url_host", "URL {url} has no host"),
+    ("sync.unreachable", "could not reach {url}: {error}"),
+    ("sync.unresolved", "could not resolve {host}"),
+    (
+        "sync.token_in_clear",
+        "the token would cross the network unencrypted to {url}; use an https:// URL",
+    ),
+    (
+        "sync.too_large",
+        "{url} answered with more than {limit} bytes",
+    ),
+    (
+        "sync.changed",
+        "the remote copy changed since it was last pulled or pushed; sync again to merge it",
+    ),
+    (
+        "sync.unseen",
+        "Not sending to {url}: its copy was not pulled here or came without a version, so changes made there could be lost. Run `sync` to merge it first, or `sync push --force` to replace it",
+    ),
+    ("sync.refused", "{url} refused the token with HTTP {status}"),
+    ("sync.status", "{url} answered with HTTP {status}"),
+    (
+        "sync.no_remote",
+        "No remote to sync with. Start with --sync-url <url>",
+    ),
+    ("sync.remote_empty", "{url} has no tasks yet"),
+    ("sync.sent", "Sent to {url}: {summary}"),
+    ("report.title", "Status report, {date}"),
+    ("report.empty", "No tasks yet."),
+    ("report.summary", "Summary"),
+    ("report.recent", "Completed since yesterday"),
+    ("report.nothing", "Nothing yet."),
+    ("report.due", "due {date}"),
+    (
+        "report.exists",
+        "{path} already exists. Pass --force to replace it",
+    ),
+    ("report.ask_replace", "{path} already exists. Replace it?"),
+    ("report.kept", "{path} was left alone"),
+    ("report.written", "Wrote the report to {path}"),
+    ("count.all", "{count} task(s) in the list"),
+    ("count.status", "{count} task(s) {status}"),
+    ("count.overdue", "{count} task(s) overdue"),
+    ("count.due_today", "{count} task(s) due today"),
+    ("stats.total", "Total"),
+    ("stats.todo", "Todo"),
+    ("stats.waiting", "Waiting"),
+    ("stats.in_progress", "In progress"),
+    ("stats.done", "Done"),
+    ("stats.cancelled", "Cancelled"),
+    (
+        "stats.completed",
+        "Completed today: {today}, this week: {week}",
+    ),
+    (
+        "stats.longest_open",
+        "Longest open: {index}. {description} ({days} {days|day|days})",
+    ),
+    (
+        "stats.streak",
+        "Streak: {current} {current|day|days}, best {best} {best|day|days}",
+    ),
+    ("stats.time_tracked", "Time tracked: {time}"),
+    ("stats.estimated_left", "Estimated time left: {time}"),
+    ("stats.title", "Task Statistics:"),
+    ("listing.took", "took {time}"),
+    ("listing.group", "{status} ({count})"),
+    ("listing.empty", "No tasks yet"),
+    ("hint.help", "Type 'help' to see available commands"),
+    ("add.added", "Task added successfully!"),
+    ("add.count", "Added {count} {count|task|tasks}"),
+    (
+        "add.skipped",
+        "Skipped empty {count|item|items} {items} of the list",
+    ),
+    (
+        "add.from_file",
+        "Added {count} {count|task|tasks} from {path}",
+    ),
+    (
+        "add.stdin_prompt",
+        "Enter tasks, one per line, Ctrl-D to finish",
+    ),
+    ("add.line_skipped", "Line {line} skipped: {error}"),
+    (
+        "add.duplicate",
+        "'{text}' looks like task {index}: {existing}",
+    ),
+    ("add.ask_duplicate", "Add it anyway?"),
+    (
+        "add.duplicate_left_out",
+        "Not added; task {index} is already there",
+    ),
+    ("tag.none", "Task {index} has no tags"),
+    ("mark.marked", "Task {index} marked {marker}"),
+    ("mark.cleared", "Task {index} has no marker now"),
+    ("task.text", "Task {index}: {text}"),
+    ("task.marked_done", "Task {index} marked done"),
+    ("update.updated", "Task status updated successfully!"),
+    ("toggle.now", "Task {index} is now {status}"),
+    ("toggle.reopened", "Task {index} reopened"),
+    ("reopen.reopened", "Reopened task {index}: {description}"),
+    ("reopen.none", "No completed tasks to reopen"),
+    ("reopen.title", "Latest completed:"),
+    ("reopen.done_at", "done {at}"),
+    ("reopen.more", "{count} older {count|one|ones} not shown"),
+    (
+        "reopen.hint",
+        "Reopen one with: reopen <num>, or the latest with: reopen last",
+    ),
+    ("update_all.none", "No tasks left to mark {new}"),
+    ("update_all.none_kind", "No {kind} tasks left to mark {new}"),
+    (
+        "update_all.marked",
+        "Marked {count} {count|task|tasks} {new}",
+    ),
+    (
+        "update_all.marked_kind",
+        "Marked {count} {kind} {count|task|tasks} {new}",
+    ),
+    (
+        "blocked.still",
+        "Task {index} is still blocked by {count|task|tasks} {numbers}",
+    ),
+    ("blocked.added", "Task {index} now waits on task {on}"),
+    ("sub.added", "Subtask {index} added"),
+    (
+        "sub.all_done",
+        "All subtasks of task {index} are done. Mark it done with: done {index}",
+    ),
+    (
+        "repeat.next",
+        "Next occurrence added as task {index}, due {due}",
+    ),
+    ("repeat.set", "Task {index} repeats {recurrence}"),
+    ("repeat.off", "Task {index} no longer repeats"),
+    ("remove.ask", "Remove task {index}: {description}?"),
+    ("remove.cancelled", "Nothing was removed"),
+    ("remove.removed", "Removed: {description}"),
+    (
+        "remove.removed_with_subtasks",
+        "Removed: {description} (and {count} {count|subtask|subtasks})",
+    ),
+    (
+        "remove.undo",
+        "Changed your mind? Bring it back with: restore {position}",
+    ),
+    ("remove.not_kept", "Could not keep it in {path}: {error}"),
+    ("title.trash", "Trash"),
+    ("title.archived", "Archived Tasks"),
+    ("title.tasks", "Your Tasks"),
+    ("trash.hint", "Bring one back with: restore <num>"),
+    (
+        "trash.ask_empty",
+        "Delete {count} {count|task|tasks} in the trash for good?",
+    ),
+    ("trash.kept", "The trash was left alone"),
+    (
+        "trash.emptied",
+        "Emptied the trash ({count} {count|task|tasks})",
+    ),
+    ("backup.created", "Backed up {sections} to {path}"),
+    (
+        "backup.ask_restore",
+        "Replace the tasks, archive, trash and history with the backup in {path}? Unsaved changes are kept in the backup of what it replaces",
+    ),
+    ("backup.kept", "Nothing was restored"),
+    (
+        "backup.restored",
+        "Restored {sections} from {path}; what was there before is in {previous}",
+    ),
+    (
+        "backup.skipped",
+        "Skipped the {section} section, which this version does not know",
+    ),
+    (
+        "restore.restored",
+        "Restored: {description} (now task {position})",
+    ),
+    ("history.empty", "No changes recorded yet"),
+    ("history.title", "History:"),
+    (
+        "history.ask_clear",
+        "Forget {count} recorded {count|change|changes}?",
+    ),
+    ("history.kept", "The history was left alone"),
+    (
+        "history.cleared",
+        "Cleared the history ({count} {count|change|changes})",
+    ),
+    (
+        "history.unreadable",
+        "Could not read {path}, starting a new history: {error}",
+    ),
+    ("changes.more", "... and {count} more"),
+    (
+        "agenda.none",
+        "Nothing due in the next {days} {days|day|days}",
+    ),
+    ("agenda.overdue", "Overdue"),
+    ("agenda.today", "Today"),
+    ("agenda.tomorrow", "Tomorrow"),
+    ("agenda.later", "Later"),
+    ("agenda.undated", "No date: {count} {count|task|tasks}"),
+    ("dedupe.none", "No duplicate tasks found"),
+    (
+        "doctor.healthy",
+        "No problems found in {count} {count|task|tasks}",
+    ),
+    ("doctor.fixed", "Repaired {count} {count|problem|problems}:"),
+    ("doctor.can_fix", "'doctor --fix' repairs {count} of these"),
+    (
+        "doctor.by_hand",
+        "{count} {count|needs|need} fixing by hand in the task file",
+    ),
+    (
+        "doctor.duplicate_id",
+        "Task {index} has ID {id}, the same as task {first}",
+    ),
+    (
+        "doctor.empty_description",
+        "Task {index} has no description",
+    ),
+    (
+        "doctor.completed_before_created",
+        "Task {index} was completed before it was created",
+    ),
+    (
+        "doctor.dangling_dependency",
+        "Task {index} waits on ID {id}, which no task has",
+    ),
+    (
+        "doctor.startup",
+        "{count} {count|problem|problems} in the task list; 'doctor' lists them",
+    ),
+    (
+        "dedupe.would_remove",
+        "\"{description}\": keeping {kept}, would remove {removed}",
+    ),
+    (
+        "dedupe.found",
+        "{count} {count|duplicate|duplicates} found. Run 'dedupe' to merge them",
+    ),
+    (
+        "dedupe.merged",
+        "Merged {count} {count|duplicate|duplicates} in {groups} {groups|group|groups}",
+    ),
+    ("renumber.compact", "Task IDs already run from 1 to {count}"),
+    ("renumber.change", "ID {old} → {new}: {description}"),
+    (
+        "renumber.ask",
+        "Give {count} {count|task a new ID|tasks new IDs}?",
+    ),
+    ("renumber.cancelled", "The IDs were left as they were"),
+    (
+        "renumber.done",
+        "Renumbered {count} {count|task|tasks}; IDs now run from 1 to {total}",
+    ),
+    ("clear.closed", "completed or cancelled"),
+    ("clear.none", "No tasks to clear"),
+    ("clear.none_kind", "No {kind} tasks to clear"),
+    ("clear.ask_all", "Remove ALL {count} {count|task|tasks}?"),
+    (
+        "clear.ask_kind",
+        "Remove {count} {kind} {count|task|tasks}?",
+    ),
+    ("clear.cancelled", "Nothing was cleared"),
+    ("clear.cleared", "Cleared {count} {count|task|tasks}"),
+    (
+        "clear.cleared_kind",
+        "Cleared {count} {kind} {count|task|tasks}",
+    ),
+    (
+        "clear.restorable_last",
+        "Only the last {limit} can be restored from the trash",
+    ),
+    (
+        "clear.would_clear",
+        "{count} {count|task|tasks} would be cleared; nothing was changed",
+    ),
+    (
+        "clear.would_clear_kind",
+        "{count} {kind} {count|task|tasks} would be cleared; nothing was changed",
+    ),
+    ("clear.restorable", "They can be restored from the trash"),
+    ("clear.not_kept", "Could not keep them in {path}: {error}"),
+    ("archive.none", "No completed tasks to archive"),
+    ("archive.failed", "Error: could not add to {path}: {error}"),
+    ("archive.cancelled", "Nothing was archived"),
+    (
+        "archive.archived",
+        "Archived {count} completed {count|task|tasks} to {path}",
+    ),
+    (
+        "archive.auto",
+        "Archived {count} {count|task|tasks} completed more than {days} days ago",
+    ),
+    (
+        "archive.auto_failed",
+        "Could not add to {path}, so finished tasks stay in the list: {error}",
+    ),
+    ("archive.stats_title", "Archive {path}:"),
+    ("archive.other", "Other"),
+    ("archive.subtasks", "Subtasks"),
+    ("archive.span", "Completed from {first} to {last}"),
+    ("archive.size", "File size: {size}"),
+    ("transition.move", "task {index} from {from} to {to}"),
+    (
+        "transition.confirm",
+        "Strict transitions don't allow {moves}. Change anyway?",
+    ),
+    (
+        "update_all.skipped",
+        "{count} {count|task was|tasks were} left alone: strict transitions don't allow {new} from where {count|it is|they are}; add --force to change {count|it|them} too",
+    ),
+    ("saved.empty", "Nothing here yet"),
+    (
+        "template.saved",
+        "Saved template \"{name}\" with {count} {count|task|tasks}",
+    ),
+    (
+        "template.replaced",
+        "Replaced template \"{name}\" with {count} {count|task|tasks}",
+    ),
+    (
+        "template.applied",
+        "Added {count} {count|task|tasks} from template \"{name}\"",
+    ),
+    (
+        "template.none",
+        "No templates yet. Save one with: template save <name> <num>...",
+    ),
+    ("template.title", "Templates:"),
+    ("template.count", "{count} {count|task|tasks}"),
+    ("template.deleted", "Deleted template \"{name}\""),
+    ("filter.saved", "Saved filter \"{name}\": {filter}"),
+    ("filter.replaced", "Replaced filter \"{name}\": {filter}"),
+    (
+        "filter.none",
+        "No saved filters yet. Save one with: filter save <name> <filter>",
+    ),
+    ("filter.title", "Saved filters:"),
+    ("filter.deleted", "Deleted filter \"{name}\""),
+    ("filter.using", "Using {names}: list {filter}"),
+    ("snippet.saved", "Saved snippet \"{name}\": {text}"),
+    ("snippet.replaced", "Replaced snippet \"{name}\": {text}"),
+    (
+        "snippet.none",
+        "No snippets yet. Save one with: snippet save <name> <text>",
+    ),
+    ("snippet.title", "Snippets:"),
+    ("snippet.deleted", "Deleted snippet \"{name}\""),
+    ("snippet.expanded", "From @{name}: {text}"),
+    ("version.data_file", "Data file: {path}"),
+    ("save.saved", "Tasks saved to {path}"),
+    ("save.failed", "Failed to save: {error}"),
+    (
+        "save.ask_wipe",
+        "The list is empty, and saving would wipe out the {count} tasks in the file. Save anyway, keeping a copy in {backup}?",
+    ),
+    (
+        "save.backed_up",
+        "The tasks that were in the file are kept in {path}",
+    ),
+    (
+        "save.kept_aside",
+        "Your list is kept in {path}; merge it in with: merge {path}",
+    ),
+    ("file.unreadable", "Could not read {path}: {error}"),
+    ("file.unwritable", "Could not write {path}: {error}"),
+    ("merge.conflict", "Task {index} was changed in both copies"),
+    ("merge.keep_which", "Keep which?"),
+    (
+        "merge.merged",
+        "Merged {source}: {matched} in both copies ({updated} updated), {only_ours} only here, {only_theirs} added",
+    ),
+    (
+        "import.imported",
+        "Imported {path}: {created} added, {updated} updated, {skipped} already up to date",
+    ),
+    (
+        "merge.kept_ours",
+        "Kept our descriptions; pass --theirs to take theirs",
+    ),
+    ("show.task", "Task {index}"),
+    ("show.description", "Description:"),
+    ("show.status", "Status:"),
+    ("show.waiting_on", "Waiting on:"),
+    ("show.delegated_to", "Delegated to:"),
+    ("show.since", "since {time}"),
+    ("show.id", "ID:"),
+    ("show.tags", "Tags:"),
+    ("show.projects", "Projects:"),
+    ("show.contexts", "Contexts:"),
+    ("show.blocked_by", "Blocked by:"),
+    ("show.due", "Due:"),
+    ("show.repeats", "Repeats:"),
+    ("show.priority", "Priority:"),
+    ("show.estimate", "Estimate:"),
+    ("show.time_spent", "Time spent:"),
+    ("show.timer_running", "{time} (timer running)"),
+    ("open.opened", "Opened {url}"),
+    ("open.failed", "Error: could not open {url}: {error}"),
+    ("copy.copied", "Copied the description of task {index}"),
+    ("copy.copied_json", "Copied task {index} as JSON"),
+    (
+        "copy.copied_list",
+        "Copied the list of {count} {count|task|tasks}",
+    ),
+    ("due.set", "Task {index} is due {date}"),
+    (
+        "chain.empty_step",
+        "Error: step {step} of the chain has no command",
+    ),
+    (
+        "chain.not_run",
+        "Step {step} of {total} was not understood, so none of them ran",
+    ),
+    (
+        "chain.stopped",
+        "Step {step} of {total} failed, so {skipped} {skipped|step was|steps were} skipped",
+    ),
+    (
+        "run.summary",
+        "{path}: ran {executed} {executed|line|lines}, {succeeded} succeeded, {failed} failed",
+    ),
+    (
+        "run.stopped",
+        "Stopped at line {line}; --continue-on-error runs the rest anyway",
+    ),
+    (
+        "run.too_deep",
+        "Error: not running {path}: scripts can only run other scripts {depth} deep",
+    ),
+    ("wait.set", "Task {index} is waiting"),
+    ("wait.set_reason", "Task {index} is waiting on {reason}"),
+    ("delegate.set", "Task {index} is waiting on {person}"),
+    ("delegate.cleared", "Task {index} no longer waits on anyone"),
+    (
+        "waiting.none",
+        "Nothing delegated. Hand a task over with: delegate <num> <person>",
+    ),
+    ("waiting.person", "{person} ({count})"),
+    ("waiting.for", "waiting {time}"),
+    ("due.removed", "Due date removed from task {index}"),
+    ("estimate.set", "Task {index} should take {estimate}"),
+    ("estimate.removed", "Estimate removed from task {index}"),
+    ("due.none_overdue", "Nothing is overdue"),
+    ("due.none_today", "Nothing due today"),
+    ("due.today", "due today"),
+    ("due.tomorrow", "due tomorrow"),
+    ("due.in_days", "due in {days}d"),
+    ("due.in_hours", "due in {hours}h"),
+    ("due.in_minutes", "due in {minutes}m"),
+    ("due.overdue", "{days}d overdue"),
+    ("snooze.no_due", "no due date"),
+    ("snooze.snoozed", "Task {index} snoozed: {old} -> {new}"),
+    (
+        "escalate.raised",
+        "Raised the priority of {count} stale {count|task|tasks}: {numbers}",
+    ),
+    (
+        "escalate.none",
+        "No task has been in todo for more than {days} days",
+    ),
+    (
+        "escalate.no_days",
+        "Say how many days make a task stale, as in 'escalate 30', or start with --escalate-after <days>",
+    ),
+    ("timer.stopped", "Stopped the timer on task {index}"),
+    ("timer.started", "Timing task {index}. Run 'stop' when done"),
+    (
+        "timer.stopped_after",
+        "Stopped the timer on task {index} after {time}",
+    ),
+    ("timer.none", "No timer is running"),
+    (
+        "pomodoro.stopped",
+        "Session stopped after {time}, logged on task {index}",
+    ),
+    (
+        "pomodoro.done",
+        "Session done: {time} logged on task {index}",
+    ),
+    ("pomodoro.ask_done", "Mark task {index} done?"),
+    (
+        "pomodoro.left",
+        "{left} left on task {index} (Ctrl-C to stop)",
+    ),
+    ("next.none", "Nothing to do. Enjoy the break!"),
+    ("next.first", "Next: {index}. {task}{due}"),
+    ("next.then", "Then:"),
+    ("next.ask_start", "Start task {index} now?"),
+    ("next.started", "Task {index} is in progress"),
+    ("random.picked", "How about {index}. {task}{due}"),
+    ("random.none", "Nothing open to pick from. Enjoy the break!"),
+    ("random.no_match", "No open task matches that filter"),
+    ("pick.heading", "Which task?"),
+    ("pick.prompt", "Task number (Enter to cancel):"),
+    ("pick.not_listed", "{answer} is not one of the tasks above"),
+    ("pick.cancelled", "Nothing picked, no changes made"),
+    ("pick.none", "No task matches that filter"),
+    (
+        "warn.too_long",
+        "{count|Task|Tasks} {numbers} {count|has a description|have descriptions} over {max} characters",
+    ),
+    (
+        "warn.filter_shadowed",
+        "Saved filter \"{name}\" is now a word of list's own, which wins. Save it under another name and 'filter delete {name}'",
+    ),
+    (
+        "warn.unknown_status",
+        "{count} {count|task has|tasks have} the unknown status '{name}' and {count|was|were} loaded as todo",
+    ),
+    ("streak", "{days}-day streak"),
+    (
+        "reminders",
+        "{overdue} {overdue|task|tasks} overdue, {today} due today",
+    ),
+    ("projects.title", "Projects"),
+    (
+        "projects.none",
+        "No projects yet. Add one with e.g.: add Call accountant +name",
+    ),
+    ("contexts.title", "Contexts"),
+    (
+        "contexts.none",
+        "No contexts yet. Add one with e.g.: add Call accountant @name",
+    ),
+    ("tokens.count", "{count} {count|task|tasks}"),
+    ("week.title", "Last 7 days ({first} to {last}):"),
+    ("week.day", "{added} added  {done} done"),
+    (
+        "week.totals",
+        "{added} added, {completed} completed, {open} of the new ones still open",
+    ),
+    ("week.rate", "Completion rate: {rate}%"),
+    ("chart.title", "Completed per day, last {days} days:"),
+    ("table.status", "STATUS"),
+    ("table.due", "DUE"),
+    ("table.age", "AGE"),
+    ("table.estimate", "EST"),
+    ("table.score", "SCORE"),
+    ("table.description", "DESCRIPTION"),
+    ("help.also_inline", "also: {aliases}"),
+    ("help.commands", "Commands:"),
+    (
+        "help.details",
+        "Type 'help <command>' for the details of one command",
+    ),
+    (
+        "help.confirm",
+        "(remove, clear and trash empty ask first; add --yes to skip)",
+    ),
+    (
+        "help.prefixes",
+        "Any unambiguous start of a command works too: li, upd, temp",
+    ),
+    (
+        "help.text_selector",
+        "<num> may also be text from the description: done \"groceries\"",
+    ),
+    (
+        "help.pick_selector",
+        "<num> may also be --pick, to choose from a menu: done --pick todo",
+    ),
+    ("help.statuses", "Statuses: {statuses}"),
+    ("help.archive", "The archive file is {path}"),
+    ("help.examples", "Examples:"),
+    ("help.usage", "Usage:"),
+    ("help.also", "Also: {aliases}"),
+    ("flag.sync_url", "--sync-url needs a URL, sync is off"),
+    ("flag.sync_token", "--sync-token needs a token"),
+    ("flag.lang", "--lang needs a language code such as en or es"),
+    (
+        "flag.lang_unknown",
+        "No messages in {code}, using {language}",
+    ),
+    (
+        "flag.prompt",
+        "--prompt needs a template, e.g. \"{list} [{open}/{total}] {dirty}> \"",
+    ),
+    (
+        "flag.prompt_unknown",
+        "Prompt placeholder {name} not recognized. Use: {list}, {open}, {done}, {total}, {dirty}",
+    ),
+    ("flag.file", "--file needs a path, using {default}"),
+    ("flag.number", "{flag} needs a number, using {default}"),
+    ("flag.unknown", "Ignoring unknown argument: {arg}"),
+    (
+        "flag.auto_escalate",
+        "--auto-escalate needs --escalate-after <days>, nothing is escalated",
+    ),
+    (
+        "flag.theme",
+        "--theme needs ascii, default or the path of a theme file",
+    ),
+    (
+        "flag.theme_failed",
+        "Could not read the theme {path}: {error}, using the default look",
+    ),
+    (
+        "theme.invalid_line",
+        "Theme line {line} is not key = value, ignored",
+    ),
+    (
+        "theme.unknown_key",
+        "Unknown theme setting {key} on line {line}, ignored",
+    ),
+    (
+        "theme.unknown_color",
+        "Unknown color {value} for {key}, keeping the default. Colors: {colors}",
+    ),
+    (
+        "theme.empty_icon",
+        "{key} cannot be empty, keeping {default}",
+    ),
+    (
+        "theme.not_boolean",
+        "{key} takes true or false, keeping {default}",
+    ),
+    (
+        "theme.unknown_preset",
+        "Unknown theme preset {name}, starting from the default. Presets: {presets}",
+    ),
+    ("session.welcome", "Welcome to the Todo CLI {version}!"),
+    ("session.exit_hint", "Type 'exit' to quit the application."),
+    ("session.loaded", "Loaded {summary}"),
+    ("session.load_failed", "Could not load tasks: {error}"),
+    ("session.input_error", "Error reading input"),
+    ("session.save_failed", "Failed to save tasks: {error}"),
+    ("session.saved", "Tasks saved successfully!"),
+    (
+        "session.read_only",
+        "Read-only: commands that change tasks are turned down and nothing is saved",
+    ),
+    (
+        "session.not_writable",
+        "{path} cannot be written, so this session is read-only",
+    ),
+    ("session.recap", "This session: {parts}"),
+    ("session.recap_added", "{count} added"),
+    ("session.recap_completed", "{count} completed"),
+    ("session.recap_removed", "{count} removed"),
+    ("session.recap_edited", "{count} edited"),
+    ("session.goodbye", "Goodbye!"),
+    ("file_sync.changed", "{path} changed on disk"),
+    (
+        "file_sync.changed_unsaved",
+        "{path} changed on disk, and your changes are not saved.",
+    ),
+    (
+        "file_sync.kept",
+        "Keeping your list. Saving will replace the file.",
+    ),
+    (
+        "file_sync.changed_before_save",
+        "{path} was changed elsewhere since it was read. Merge those changes in, overwrite them, or cancel?",
+    ),
+    (
+        "file_sync.ask_reload",
+        "Drop your unsaved changes and reload?",
+    ),
+    ("file_sync.reloaded", "Reloaded {summary}"),
+    ("file_sync.reload_failed", "Could not reload tasks: {error}"),
+    ("file_sync.merged", "Merged: {summary}"),
+    (
+        "file_sync.save_hint",
+        "Type 'save' to write the merged list",
+    ),
+    ("file_sync.merge_failed", "Could not merge tasks: {error}"),
+    (
+        "review.needs_terminal",
+        "Error: review needs an interactive terminal",
+    ),
+    ("review.none", "Nothing to review"),
+    ("review.progress", "Review {n} of {total}"),
+    (
+        "review.actions",
+        "(d)one (s)kip (r)emove (p)ostpone (e)dit (q)uit",
+    ),
+    ("review.postpone_by", "Postpone by (e.g. 2d, 1w) [1d]"),
+    ("review.new_description", "New description (empty keeps it)"),
+    ("review.updated", "Task {index} updated"),
+    ("review.keys", "Type d, s, r, p, e or q"),
+    (
+        "review.finished",
+        "Review finished: {done} done, {removed} removed, {postponed} postponed, {edited} edited, {skipped} skipped",
+    ),
+    (
+        "prompt.not_confirmed",
+        "Not confirmed: pass --yes to skip the question",
+    ),
+    ("prompt.not_asked", "Not asked in a piped session"),
+    ("prompt.yes_no", "[y/N]"),
+    ("prompt.yes_words", "y|yes"),
+    ("progress", "{done}/{total} done ({percent}%)"),
+];
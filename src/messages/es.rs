@@ -0,0 +1,1091 @@
+// Spanish
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    ("error.invalid_index", "El número debe empezar en 1"),
+    (
+        "error.invalid_status",
+        "Estado {status} no reconocido. Usa: {statuses}",
+    ),
+    (
+        "error.invalid_status_suggest",
+        "Estado {status} no reconocido, ¿quisiste decir {suggestion}? Usa: {statuses}",
+    ),
+    (
+        "error.invalid_transition",
+        "Las transiciones estrictas no dejan pasar una tarea de {from} a {to}; desde {from} puede pasar a {allowed}. Añade --force para hacerlo de todos modos",
+    ),
+    (
+        "error.index_out_of_bound",
+        "No hay ninguna tarea con el número {index}",
+    ),
+    (
+        "error.empty_description",
+        "La descripción de la tarea no puede estar vacía",
+    ),
+    (
+        "error.description_too_long",
+        "La descripción tiene {len} caracteres, el límite es {max}",
+    ),
+    (
+        "error.line_too_long",
+        "La línea tiene {len} bytes, el límite es {max} (ver --max-line), así que se dejó de lado: {start}…",
+    ),
+    (
+        "error.invalid_date",
+        "Fecha {date} no reconocida. Usa p. ej.: 2025-06-01, today, tomorrow, friday, next week, in 3 days, tomorrow 9am",
+    ),
+    (
+        "error.invalid_duration",
+        "Duración {duration} no reconocida. Usa p. ej.: 2d, 1w, 12h, 1h30m",
+    ),
+    (
+        "error.invalid_estimate",
+        "Estimación {estimate} no reconocida. Usa p. ej.: 45m, 2h, 1h30m, 1d",
+    ),
+    ("error.task_gone", "La tarea {index} ya no está en la lista"),
+    (
+        "error.not_in_view",
+        "La tarea {index} no está en esta vista",
+    ),
+    ("error.unknown_id", "Ninguna tarea tiene el ID {id}"),
+    (
+        "error.subtask_out_of_bound",
+        "La tarea {task} no tiene la subtarea {sub}",
+    ),
+    ("error.no_match", "Ninguna tarea coincide con \"{text}\""),
+    (
+        "error.ambiguous_match",
+        "\"{text}\" coincide con {count} tareas, usa un número de tarea o más texto",
+    ),
+    (
+        "error.subtask_not_allowed",
+        "{index} es una subtarea; este comando toma un número de tarea",
+    ),
+    (
+        "error.dependency_cycle",
+        "La tarea {task} no puede depender de la tarea {on}: se crearía un ciclo de dependencias",
+    ),
+    (
+        "error.already_completed",
+        "La tarea {index} ya está completada",
+    ),
+    (
+        "error.not_completed",
+        "La tarea {index} está en {status}, no hecha, así que no hay nada que reabrir",
+    ),
+    (
+        "error.nothing_completed",
+        "No hay ninguna tarea completada que reabrir",
+    ),
+    (
+        "error.invalid_interval",
+        "Intervalo {interval} no reconocido. Usa p. ej.: every 3d, every 2w, daily, weekly",
+    ),
+    (
+        "error.invalid_marker",
+        "Marca '{marker}' no válida. Usa una palabra o un emoji, como 🔥 o VIP",
+    ),
+    (
+        "error.unknown_color",
+        "Color '{color}' desconocido. Usa uno de: {colors}",
+    ),
+    (
+        "error.invalid_tag",
+        "Etiqueta {tag} no válida. Usa una palabra, con o sin # delante",
+    ),
+    (
+        "error.invalid_person",
+        "Persona {person} no válida. Usa un nombre, con o sin @ delante",
+    ),
+    (
+        "error.conflicting_options",
+        "Opciones incompatibles: {options}",
+    ),
+    (
+        "error.closed_cannot_repeat",
+        "una tarea completada o cancelada no puede repetirse",
+    ),
+    (
+        "error.no_link",
+        "La descripción de la tarea {index} no tiene ningún enlace",
+    ),
+    (
+        "error.link_out_of_bound",
+        "La tarea {index} solo tiene {count} {count|enlace|enlaces}",
+    ),
+    (
+        "error.invalid_template_name",
+        "Nombre de plantilla {name} no válido. Usa una palabra, o pon \"entre comillas\" un nombre con espacios",
+    ),
+    (
+        "error.unknown_template",
+        "No hay ninguna plantilla llamada \"{name}\". Mira 'template list'",
+    ),
+    (
+        "error.invalid_filter_name",
+        "El nombre de filtro {name} no es válido. Usa una palabra de letras, cifras, - y _ que empiece por una letra",
+    ),
+    (
+        "error.reserved_filter_name",
+        "\"{name}\" ya significa algo para list, así que no puede nombrar un filtro",
+    ),
+    (
+        "error.unknown_saved_filter",
+        "No hay ningún filtro guardado llamado \"{name}\". Consulta 'filter list'",
+    ),
+    (
+        "error.invalid_saved_filter",
+        "El filtro guardado \"{name}\" ya no funciona: {error}",
+    ),
+    (
+        "error.invalid_snippet_name",
+        "El nombre de fragmento {name} no es válido. Usa una palabra de letras, cifras, - y _ que empiece por una letra",
+    ),
+    (
+        "error.unknown_snippet",
+        "No hay ningún fragmento llamado \"{name}\". Consulta 'snippet list'",
+    ),
+    (
+        "error.invalid_snippet",
+        "El fragmento \"{text}\" tiene una llave que no rodea el nombre de un hueco. Duplica una llave para dejarla como texto",
+    ),
+    (
+        "error.missing_placeholders",
+        "{count|Falta un valor|Faltan valores} para {missing}. Este fragmento lleva: {needed}",
+    ),
+    (
+        "error.unknown_placeholder",
+        "Este fragmento no tiene el hueco {key}. Lleva: {needed}",
+    ),
+    ("error.no_placeholders", "ningún valor"),
+    (
+        "error.renumber_blocked",
+        "No se puede renumerar mientras {count} {count|tarea comparte su ID o depende de una tarea que falta|tareas comparten su ID o dependen de tareas que faltan}; ejecuta 'doctor --fix' antes",
+    ),
+    (
+        "error.serialization",
+        "No se pudieron serializar las tareas: {error}",
+    ),
+    ("error.file", "No se pudo acceder al archivo: {error}"),
+    ("error.sync", "La sincronización falló: {error}"),
+    (
+        "error.clipboard",
+        "No se pudo usar el portapapeles: {error} (usa show para ver la tarea)",
+    ),
+    ("error.clipboard_exit", "{program} terminó con {status}"),
+    (
+        "error.clipboard_headless",
+        "no hay una pantalla a través de la cual llegar a él",
+    ),
+    (
+        "error.clipboard_no_tool",
+        "no está instalado ninguno de {tools}",
+    ),
+    (
+        "error.not_interactive",
+        "--pick necesita una terminal para preguntar la tarea, indica su número",
+    ),
+    (
+        "error.read_only",
+        "{command} escribiría en los archivos de tareas, que están abiertos en solo lectura",
+    ),
+    (
+        "error.would_wipe",
+        "la lista está vacía y reemplazaría las {count} tareas del archivo; --force la guarda de todos modos",
+    ),
+    (
+        "error.unreadable_tasks",
+        "{path} no se pudo leer, así que no se guarda encima; arréglalo y recarga, o usa --force para reemplazarlo (se guarda una copia)",
+    ),
+    (
+        "error.concurrent_modification",
+        "{path} cambió en otro sitio desde que se leyó; recarga o combina antes, o usa `save --force` para reemplazar esos cambios",
+    ),
+    (
+        "error.not_a_backup",
+        "{path} no es una copia de seguridad de esta aplicación",
+    ),
+    (
+        "error.backup_too_new",
+        "la copia de seguridad usa el formato {version}, más nuevo que el {supported} que lee esta versión; actualiza para restaurarla",
+    ),
+    (
+        "error.bad_backup_section",
+        "la sección {section} de la copia de seguridad está dañada: {error}",
+    ),
+    (
+        "error.cli_only",
+        "{command} necesita la aplicación de línea de comandos, con su terminal y sus archivos",
+    ),
+    ("error.browser_exit", "el navegador terminó con {status}"),
+    ("error.empty_command", "No se dio ningún comando"),
+    ("error.usage", "Uso: {usage}"),
+    (
+        "error.unknown_filter",
+        "Filtro '{filter}' desconocido. Se admiten: status:<status>, tag:<name>, waiting-on:<name>",
+    ),
+    (
+        "error.unknown_count",
+        "No se puede contar '{target}'. Cuenta uno de: {targets}",
+    ),
+    ("error.invalid_task_number", "Número de tarea no válido."),
+    (
+        "error.invalid_trash_number",
+        "Número de papelera no válido.",
+    ),
+    (
+        "error.unquoted_name",
+        "Los nombres de plantilla con espacios van entre comillas, p. ej.: template {action} \"new release\"",
+    ),
+    (
+        "error.missing_status",
+        "Falta el estado nuevo. Uso: update {selector} <new_status>",
+    ),
+    (
+        "error.ambiguous_command",
+        "Comando '{word}' ambiguo: puede ser {candidates}",
+    ),
+    ("error.unknown_command", "Comando '{word}' desconocido"),
+    (
+        "error.unknown_command_suggest",
+        "Comando '{word}' desconocido. ¿Querías decir {suggestions}?",
+    ),
+    (
+        "error.list_count",
+        "list [status] {flag} <count>\n   La cantidad debe ser un número mayor o igual que 1.",
+    ),
+    ("error.shown", "Error: {error}"),
+    ("error.read", "Error: no se pudo leer {path}: {error}"),
+    ("error.write", "Error: no se pudo escribir {path}: {error}"),
+    ("list.or", "{rest} o {last}"),
+    ("list.no_match", "Ninguna tarea coincide con: {filter}"),
+    (
+        "list.waiting_hidden",
+        "{count} {count|tarea|tareas} en espera sin mostrar; `list all` las incluye",
+    ),
+    (
+        "list.estimated_left",
+        "Tiempo estimado restante: {time} en {count} {count|tarea|tareas}",
+    ),
+    ("fuzzy.none", "Nada se parece lo bastante a \"{text}\""),
+    ("fuzzy.title", "Lo más parecido a \"{text}\""),
+    (
+        "list.empty",
+        "Aún no hay tareas. Añade una con: add <descripción>",
+    ),
+    (
+        "list.no_more",
+        "No hay nada más que mostrar. Escribe 'list' para empezar de nuevo",
+    ),
+    ("list.showing", "Mostrando {range} de {total}"),
+    (
+        "list.showing_more",
+        "Mostrando {range} de {total}, escribe 'more' para la página siguiente",
+    ),
+    ("summary.empty", "Sin tareas"),
+    (
+        "summary.counts",
+        "{total} {total|tarea|tareas}: {todo} por hacer, {in_progress} en curso, {done} hechas",
+    ),
+    (
+        "summary.cancelled",
+        ", {count} {count|cancelada|canceladas}",
+    ),
+    ("summary.waiting", ", {count} en espera"),
+    (
+        "sync.not_a_list",
+        "la copia remota no es una lista de tareas: {error}",
+    ),
+    (
+        "sync.url_scheme",
+        "La URL {url} no es compatible; usa una URL https:// o http://",
+    ),
+    (
+        "sync.url_no_scheme",
+        "La URL {url} debe empezar por https:// o http://",
+    ),
+    ("sync.url_port", "La URL {url} tiene un puerto no válido"),
+    ("sync.url_host", "La URL {url} no tiene servidor"),
+    ("sync.unreachable", "no se pudo conectar con {url}: {error}"),
+    ("sync.unresolved", "no se pudo resolver {host}"),
+    (
+        "sync.token_in_clear",
+        "el token viajaría sin cifrar hasta {url}; usa una URL https://",
+    ),
+    ("sync.too_large", "{url} respondió con más de {limit} bytes"),
+    (
+        "sync.changed",
+        "la copia remota cambió desde la última descarga o envío; sincroniza otra vez para combinarla",
+    ),
+    (
+        "sync.unseen",
+        "No se envía a {url}: su copia no se descargó aquí o llegó sin versión, así que se podrían perder cambios hechos allí. Ejecuta `sync` para combinarla antes, o `sync push --force` para reemplazarla",
+    ),
+    ("sync.refused", "{url} rechazó el token con HTTP {status}"),
+    ("sync.status", "{url} respondió con HTTP {status}"),
+    (
+        "sync.no_remote",
+        "No hay copia remota con la que sincronizar. Arranca con --sync-url <url>",
+    ),
+    ("sync.remote_empty", "{url} aún no tiene tareas"),
+    ("sync.sent", "Enviado a {url}: {summary}"),
+    ("report.title", "Informe de estado, {date}"),
+    ("report.empty", "Aún no hay tareas."),
+    ("report.summary", "Resumen"),
+    ("report.recent", "Completadas desde ayer"),
+    ("report.nothing", "Nada todavía."),
+    ("report.due", "vence {date}"),
+    (
+        "report.exists",
+        "{path} ya existe. Añade --force para reemplazarlo",
+    ),
+    ("report.ask_replace", "{path} ya existe. ¿Reemplazarlo?"),
+    ("report.kept", "{path} se dejó como estaba"),
+    ("report.written", "Informe escrito en {path}"),
+    ("count.all", "{count} tarea(s) en la lista"),
+    ("count.status", "{count} tarea(s) {status}"),
+    ("count.overdue", "{count} tarea(s) vencidas"),
+    ("count.due_today", "{count} tarea(s) para hoy"),
+    ("stats.total", "Total"),
+    ("stats.todo", "Por hacer"),
+    ("stats.waiting", "En espera"),
+    ("stats.in_progress", "En curso"),
+    ("stats.done", "Hechas"),
+    ("stats.cancelled", "Canceladas"),
+    (
+        "stats.completed",
+        "Completadas hoy: {today}, esta semana: {week}",
+    ),
+    (
+        "stats.longest_open",
+        "Abierta desde hace más tiempo: {index}. {description} ({days} {days|día|días})",
+    ),
+    (
+        "stats.streak",
+        "Racha: {current} {current|día|días}, la mejor {best} {best|día|días}",
+    ),
+    ("stats.time_tracked", "Tiempo registrado: {time}"),
+    ("stats.estimated_left", "Tiempo estimado restante: {time}"),
+    ("stats.title", "Estadísticas de tareas:"),
+    ("listing.took", "llevó {time}"),
+    ("listing.group", "{status} ({count})"),
+    ("listing.empty", "Aún no hay tareas"),
+    (
+        "hint.help",
+        "Escribe 'help' para ver los comandos disponibles",
+    ),
+    ("add.added", "¡Tarea añadida!"),
+    (
+        "add.count",
+        "{count|Añadida|Añadidas} {count} {count|tarea|tareas}",
+    ),
+    (
+        "add.skipped",
+        "Se {count|omitió el elemento vacío|omitieron los elementos vacíos} {items} de la lista",
+    ),
+    (
+        "add.from_file",
+        "{count|Añadida|Añadidas} {count} {count|tarea|tareas} de {path}",
+    ),
+    (
+        "add.stdin_prompt",
+        "Escribe las tareas, una por línea; Ctrl-D para terminar",
+    ),
+    ("add.line_skipped", "Se omitió la línea {line}: {error}"),
+    (
+        "add.duplicate",
+        "'{text}' se parece a la tarea {index}: {existing}",
+    ),
+    ("add.ask_duplicate", "¿Añadirla de todos modos?"),
+    (
+        "add.duplicate_left_out",
+        "No se añadió; la tarea {index} ya existe",
+    ),
+    ("tag.none", "La tarea {index} no tiene etiquetas"),
+    ("mark.marked", "Tarea {index} marcada con {marker}"),
+    ("mark.cleared", "La tarea {index} ya no tiene marca"),
+    ("task.text", "Tarea {index}: {text}"),
+    ("task.marked_done", "Tarea {index} marcada como hecha"),
+    ("update.updated", "¡Estado de la tarea actualizado!"),
+    ("toggle.now", "La tarea {index} ahora está {status}"),
+    ("toggle.reopened", "Tarea {index} reabierta"),
+    ("reopen.reopened", "Tarea {index} reabierta: {description}"),
+    ("reopen.none", "No hay tareas completadas que reabrir"),
+    ("reopen.title", "Últimas completadas:"),
+    ("reopen.done_at", "hecha el {at}"),
+    (
+        "reopen.more",
+        "{count} {count|anterior|anteriores} sin mostrar",
+    ),
+    (
+        "reopen.hint",
+        "Reabre una con: reopen <núm>, o la última con: reopen last",
+    ),
+    ("update_all.none", "No quedan tareas que marcar como {new}"),
+    (
+        "update_all.none_kind",
+        "No quedan tareas {kind} que marcar como {new}",
+    ),
+    (
+        "update_all.marked",
+        "{count|Marcada|Marcadas} {count} {count|tarea|tareas} como {new}",
+    ),
+    (
+        "update_all.marked_kind",
+        "{count|Marcada|Marcadas} {count} {count|tarea|tareas} {kind} como {new}",
+    ),
+    (
+        "blocked.still",
+        "La tarea {index} sigue bloqueada por {count|la tarea|las tareas} {numbers}",
+    ),
+    (
+        "blocked.added",
+        "La tarea {index} ahora espera a la tarea {on}",
+    ),
+    ("sub.added", "Subtarea {index} añadida"),
+    (
+        "sub.all_done",
+        "Todas las subtareas de la tarea {index} están hechas. Márcala como hecha con: done {index}",
+    ),
+    (
+        "repeat.next",
+        "Siguiente repetición añadida como tarea {index}, vence {due}",
+    ),
+    ("repeat.set", "La tarea {index} se repite {recurrence}"),
+    ("repeat.off", "La tarea {index} ya no se repite"),
+    ("remove.ask", "¿Quitar la tarea {index}: {description}?"),
+    ("remove.cancelled", "No se quitó nada"),
+    ("remove.removed", "Quitada: {description}"),
+    (
+        "remove.removed_with_subtasks",
+        "Quitada: {description} (y {count} {count|subtarea|subtareas})",
+    ),
+    (
+        "remove.undo",
+        "¿Cambiaste de idea? Recupérala con: restore {position}",
+    ),
+    ("remove.not_kept", "No se pudo guardar en {path}: {error}"),
+    ("title.trash", "Papelera"),
+    ("title.archived", "Tareas archivadas"),
+    ("title.tasks", "Tus tareas"),
+    ("trash.hint", "Recupera una con: restore <num>"),
+    (
+        "trash.ask_empty",
+        "¿Borrar para siempre {count} {count|tarea|tareas} de la papelera?",
+    ),
+    ("trash.kept", "La papelera se dejó como estaba"),
+    (
+        "trash.emptied",
+        "Papelera vaciada ({count} {count|tarea|tareas})",
+    ),
+    ("backup.created", "Copia de {sections} guardada en {path}"),
+    (
+        "backup.ask_restore",
+        "¿Reemplazar las tareas, el archivo, la papelera y el historial con la copia de {path}? Los cambios sin guardar se guardan en la copia de lo que se reemplaza",
+    ),
+    ("backup.kept", "No se restauró nada"),
+    (
+        "backup.restored",
+        "Restaurado {sections} desde {path}; lo que había antes está en {previous}",
+    ),
+    (
+        "backup.skipped",
+        "Se omitió la sección {section}, que esta versión no conoce",
+    ),
+    (
+        "restore.restored",
+        "Recuperada: {description} (ahora es la tarea {position})",
+    ),
+    ("history.empty", "Aún no hay cambios registrados"),
+    ("history.title", "Historial:"),
+    (
+        "history.ask_clear",
+        "¿Olvidar {count} {count|cambio registrado|cambios registrados}?",
+    ),
+    ("history.kept", "El historial se dejó como estaba"),
+    (
+        "history.cleared",
+        "Historial borrado ({count} {count|cambio|cambios})",
+    ),
+    (
+        "history.unreadable",
+        "No se pudo leer {path}, se empieza un historial nuevo: {error}",
+    ),
+    (
+        "agenda.none",
+        "Nada vence en los próximos {days} {days|día|días}",
+    ),
+    ("agenda.overdue", "Vencidas"),
+    ("agenda.today", "Hoy"),
+    ("agenda.tomorrow", "Mañana"),
+    ("agenda.later", "Más adelante"),
+    ("agenda.undated", "Sin fecha: {count} {count|tarea|tareas}"),
+    ("dedupe.none", "No se encontraron tareas duplicadas"),
+    (
+        "doctor.healthy",
+        "No hay problemas en {count} {count|tarea|tareas}",
+    ),
+    (
+        "doctor.fixed",
+        "{count|Reparado|Reparados} {count} {count|problema|problemas}:",
+    ),
+    ("doctor.can_fix", "'doctor --fix' repara {count} de ellos"),
+    (
+        "doctor.by_hand",
+        "Hay que arreglar {count} a mano en el archivo de tareas",
+    ),
+    (
+        "doctor.duplicate_id",
+        "La tarea {index} tiene el ID {id}, igual que la tarea {first}",
+    ),
+    (
+        "doctor.empty_description",
+        "La tarea {index} no tiene descripción",
+    ),
+    (
+        "doctor.completed_before_created",
+        "La tarea {index} se completó antes de crearse",
+    ),
+    (
+        "doctor.dangling_dependency",
+        "La tarea {index} espera al ID {id}, que no tiene ninguna tarea",
+    ),
+    (
+        "doctor.startup",
+        "{count} {count|problema|problemas} en la lista de tareas; 'doctor' los muestra",
+    ),
+    ("changes.more", "... y {count} más"),
+    (
+        "dedupe.would_remove",
+        "\"{description}\": se queda {kept}, se quitaría {removed}",
+    ),
+    (
+        "dedupe.found",
+        "{count} {count|duplicado encontrado|duplicados encontrados}. Ejecuta 'dedupe' para combinarlos",
+    ),
+    (
+        "dedupe.merged",
+        "{count} {count|duplicado combinado|duplicados combinados} en {groups} {groups|grupo|grupos}",
+    ),
+    (
+        "renumber.compact",
+        "Los ID de las tareas ya van del 1 al {count}",
+    ),
+    ("renumber.change", "ID {old} → {new}: {description}"),
+    (
+        "renumber.ask",
+        "¿Dar un ID nuevo a {count} {count|tarea|tareas}?",
+    ),
+    ("renumber.cancelled", "Los ID se quedaron como estaban"),
+    (
+        "renumber.done",
+        "{count} {count|tarea renumerada|tareas renumeradas}; los ID van ahora del 1 al {total}",
+    ),
+    ("clear.closed", "completadas o canceladas"),
+    ("clear.none", "No hay tareas que borrar"),
+    ("clear.none_kind", "No hay tareas {kind} que borrar"),
+    ("clear.ask_all", "¿Quitar TODAS las tareas ({count})?"),
+    (
+        "clear.ask_kind",
+        "¿Quitar {count} {count|tarea|tareas} {kind}?",
+    ),
+    ("clear.cancelled", "No se borró nada"),
+    (
+        "clear.cleared",
+        "{count|Borrada|Borradas} {count} {count|tarea|tareas}",
+    ),
+    (
+        "clear.cleared_kind",
+        "{count|Borrada|Borradas} {count} {count|tarea|tareas} {kind}",
+    ),
+    (
+        "clear.restorable_last",
+        "Solo las últimas {limit} se pueden recuperar de la papelera",
+    ),
+    (
+        "clear.would_clear",
+        "Se {count|borraría|borrarían} {count} {count|tarea|tareas}; no se cambió nada",
+    ),
+    (
+        "clear.would_clear_kind",
+        "Se {count|borraría|borrarían} {count} {count|tarea|tareas} {kind}; no se cambió nada",
+    ),
+    ("clear.restorable", "Se pueden recuperar de la papelera"),
+    (
+        "clear.not_kept",
+        "No se pudieron guardar en {path}: {error}",
+    ),
+    ("archive.none", "No hay tareas completadas que archivar"),
+    (
+        "archive.failed",
+        "Error: no se pudo añadir a {path}: {error}",
+    ),
+    ("archive.cancelled", "No se archivó nada"),
+    (
+        "archive.archived",
+        "{count|Archivada|Archivadas} {count} {count|tarea completada|tareas completadas} en {path}",
+    ),
+    (
+        "archive.auto",
+        "{count|Archivada|Archivadas} {count} {count|tarea completada|tareas completadas} hace más de {days} días",
+    ),
+    (
+        "archive.auto_failed",
+        "No se pudo añadir a {path}, así que las tareas terminadas siguen en la lista: {error}",
+    ),
+    ("archive.stats_title", "Archivo {path}:"),
+    ("archive.other", "Otras"),
+    ("archive.subtasks", "Subtareas"),
+    ("archive.span", "Completadas del {first} al {last}"),
+    ("archive.size", "Tamaño del archivo: {size}"),
+    ("transition.move", "la tarea {index} de {from} a {to}"),
+    (
+        "transition.confirm",
+        "Las transiciones estrictas no permiten {moves}. ¿Cambiar de todos modos?",
+    ),
+    (
+        "update_all.skipped",
+        "{count} {count|tarea sigue|tareas siguen} igual: las transiciones estrictas no permiten pasar a {new} desde donde {count|está|están}; añade --force para cambiar{count|la|las} también",
+    ),
+    ("saved.empty", "Aún no hay nada aquí"),
+    (
+        "template.saved",
+        "Plantilla \"{name}\" guardada con {count} {count|tarea|tareas}",
+    ),
+    (
+        "template.replaced",
+        "Plantilla \"{name}\" reemplazada con {count} {count|tarea|tareas}",
+    ),
+    (
+        "template.applied",
+        "{count|Añadida|Añadidas} {count} {count|tarea|tareas} de la plantilla \"{name}\"",
+    ),
+    (
+        "template.none",
+        "Aún no hay plantillas. Guarda una con: template save <name> <num>...",
+    ),
+    ("template.title", "Plantillas:"),
+    ("template.count", "{count} {count|tarea|tareas}"),
+    ("template.deleted", "Plantilla \"{name}\" borrada"),
+    ("filter.saved", "Filtro \"{name}\" guardado: {filter}"),
+    ("filter.replaced", "Filtro \"{name}\" reemplazado: {filter}"),
+    (
+        "filter.none",
+        "Aún no hay filtros guardados. Guarda uno con: filter save <nombre> <filtro>",
+    ),
+    ("filter.title", "Filtros guardados:"),
+    ("filter.deleted", "Filtro \"{name}\" borrado"),
+    ("filter.using", "Usando {names}: list {filter}"),
+    ("snippet.saved", "Fragmento \"{name}\" guardado: {text}"),
+    (
+        "snippet.replaced",
+        "Fragmento \"{name}\" reemplazado: {text}",
+    ),
+    (
+        "snippet.none",
+        "Aún no hay fragmentos. Guarda uno con: snippet save <nombre> <texto>",
+    ),
+    ("snippet.title", "Fragmentos:"),
+    ("snippet.deleted", "Fragmento \"{name}\" borrado"),
+    ("snippet.expanded", "De @{name}: {text}"),
+    ("version.data_file", "Archivo de datos: {path}"),
+    ("save.saved", "Tareas guardadas en {path}"),
+    ("save.failed", "No se pudo guardar: {error}"),
+    (
+        "save.ask_wipe",
+        "La lista está vacía y guardar borraría las {count} tareas del archivo. ¿Guardar de todos modos, con una copia en {backup}?",
+    ),
+    (
+        "save.backed_up",
+        "Las tareas que había en el archivo se guardan en {path}",
+    ),
+    (
+        "save.kept_aside",
+        "Tu lista se guarda en {path}; combínala con: merge {path}",
+    ),
+    ("file.unreadable", "No se pudo leer {path}: {error}"),
+    ("file.unwritable", "No se pudo escribir {path}: {error}"),
+    (
+        "merge.conflict",
+        "La tarea {index} cambió en las dos copias",
+    ),
+    ("merge.keep_which", "¿Cuál se queda?"),
+    (
+        "merge.merged",
+        "Combinado {source}: {matched} en las dos copias ({updated} actualizadas), {only_ours} solo aquí, {only_theirs} añadidas",
+    ),
+    (
+        "import.imported",
+        "Importado {path}: {created} añadidas, {updated} actualizadas, {skipped} ya al día",
+    ),
+    (
+        "merge.kept_ours",
+        "Se quedan nuestras descripciones; añade --theirs para tomar las suyas",
+    ),
+    ("show.task", "Tarea {index}"),
+    ("show.description", "Descripción:"),
+    ("show.status", "Estado:"),
+    ("show.waiting_on", "En espera de:"),
+    ("show.delegated_to", "Delegada a:"),
+    ("show.since", "desde {time}"),
+    ("show.id", "ID:"),
+    ("show.tags", "Etiquetas:"),
+    ("show.projects", "Proyectos:"),
+    ("show.contexts", "Contextos:"),
+    ("show.blocked_by", "Bloqueada por:"),
+    ("show.due", "Vence:"),
+    ("show.repeats", "Se repite:"),
+    ("show.priority", "Prioridad:"),
+    ("show.estimate", "Estimación:"),
+    ("show.time_spent", "Tiempo:"),
+    ("show.timer_running", "{time} (cronómetro en marcha)"),
+    ("open.opened", "Abierto {url}"),
+    ("open.failed", "Error: no se pudo abrir {url}: {error}"),
+    ("copy.copied", "Copiada la descripción de la tarea {index}"),
+    ("copy.copied_json", "Copiada la tarea {index} como JSON"),
+    (
+        "copy.copied_list",
+        "Copiada la lista de {count} {count|tarea|tareas}",
+    ),
+    ("due.set", "La tarea {index} vence el {date}"),
+    (
+        "chain.empty_step",
+        "Error: el paso {step} de la cadena no tiene ningún comando",
+    ),
+    (
+        "chain.not_run",
+        "El paso {step} de {total} no se entendió, así que no se ejecutó ninguno",
+    ),
+    (
+        "chain.stopped",
+        "El paso {step} de {total} falló, así que se {skipped|omitió|omitieron} {skipped} {skipped|paso|pasos}",
+    ),
+    (
+        "run.summary",
+        "{path}: {executed} {executed|línea ejecutada|líneas ejecutadas}, {succeeded} {succeeded|correcta|correctas}, {failed} {failed|fallida|fallidas}",
+    ),
+    (
+        "run.stopped",
+        "Detenido en la línea {line}; --continue-on-error ejecuta el resto igualmente",
+    ),
+    (
+        "run.too_deep",
+        "Error: no se ejecuta {path}: un script solo puede ejecutar otros hasta {depth} niveles",
+    ),
+    ("wait.set", "La tarea {index} queda en espera"),
+    (
+        "wait.set_reason",
+        "La tarea {index} queda en espera de {reason}",
+    ),
+    (
+        "delegate.set",
+        "La tarea {index} queda en espera de {person}",
+    ),
+    ("delegate.cleared", "La tarea {index} ya no espera a nadie"),
+    (
+        "waiting.none",
+        "Nada delegado. Pasa una tarea a alguien con: delegate <num> <persona>",
+    ),
+    ("waiting.person", "{person} ({count})"),
+    ("waiting.for", "esperando {time}"),
+    (
+        "due.removed",
+        "Fecha de vencimiento quitada de la tarea {index}",
+    ),
+    ("estimate.set", "La tarea {index} debería llevar {estimate}"),
+    ("estimate.removed", "Estimación quitada de la tarea {index}"),
+    ("due.none_overdue", "Nada está vencido"),
+    ("due.none_today", "Nada vence hoy"),
+    ("due.today", "vence hoy"),
+    ("due.tomorrow", "vence mañana"),
+    ("due.in_days", "vence en {days}d"),
+    ("due.in_hours", "vence en {hours}h"),
+    ("due.in_minutes", "vence en {minutes}m"),
+    ("due.overdue", "vencida hace {days}d"),
+    ("snooze.no_due", "sin fecha"),
+    ("snooze.snoozed", "Tarea {index} aplazada: {old} -> {new}"),
+    (
+        "escalate.raised",
+        "Subida la prioridad de {count} {count|tarea estancada|tareas estancadas}: {numbers}",
+    ),
+    (
+        "escalate.none",
+        "Ninguna tarea lleva más de {days} días por hacer",
+    ),
+    (
+        "escalate.no_days",
+        "Indica cuántos días estanca una tarea, como en 'escalate 30', o empieza con --escalate-after <días>",
+    ),
+    ("timer.stopped", "Cronómetro de la tarea {index} parado"),
+    (
+        "timer.started",
+        "Cronometrando la tarea {index}. Ejecuta 'stop' al terminar",
+    ),
+    (
+        "timer.stopped_after",
+        "Cronómetro de la tarea {index} parado tras {time}",
+    ),
+    ("timer.none", "No hay ningún cronómetro en marcha"),
+    (
+        "pomodoro.stopped",
+        "Sesión parada tras {time}, registrada en la tarea {index}",
+    ),
+    (
+        "pomodoro.done",
+        "Sesión terminada: {time} registrado en la tarea {index}",
+    ),
+    ("pomodoro.ask_done", "¿Marcar la tarea {index} como hecha?"),
+    (
+        "pomodoro.left",
+        "quedan {left} en la tarea {index} (Ctrl-C para parar)",
+    ),
+    ("next.none", "Nada que hacer. ¡Disfruta del descanso!"),
+    ("next.first", "Siguiente: {index}. {task}{due}"),
+    ("next.then", "Después:"),
+    ("next.ask_start", "¿Empezar ahora la tarea {index}?"),
+    ("next.started", "La tarea {index} está en curso"),
+    ("random.picked", "¿Qué tal {index}. {task}{due}?"),
+    (
+        "random.none",
+        "No hay tareas abiertas entre las que elegir. ¡Disfruta del descanso!",
+    ),
+    (
+        "random.no_match",
+        "Ninguna tarea abierta coincide con ese filtro",
+    ),
+    ("pick.heading", "¿Qué tarea?"),
+    ("pick.prompt", "Número de tarea (Enter para cancelar):"),
+    (
+        "pick.not_listed",
+        "{answer} no es ninguna de las tareas de arriba",
+    ),
+    ("pick.cancelled", "No se eligió nada, no hay cambios"),
+    ("pick.none", "Ninguna tarea coincide con ese filtro"),
+    (
+        "warn.too_long",
+        "{count|La tarea|Las tareas} {numbers} {count|tiene una descripción|tienen descripciones} de más de {max} caracteres",
+    ),
+    (
+        "warn.filter_shadowed",
+        "El filtro guardado \"{name}\" es ahora una palabra propia de list, que tiene prioridad. Guárdalo con otro nombre y usa 'filter delete {name}'",
+    ),
+    (
+        "warn.unknown_status",
+        "{count} {count|tarea tiene|tareas tienen} el estado desconocido '{name}' y se {count|cargó|cargaron} como todo",
+    ),
+    ("streak", "Racha de {days} {days|día|días}"),
+    (
+        "reminders",
+        "{overdue} {overdue|tarea vencida|tareas vencidas}, {today} para hoy",
+    ),
+    ("projects.title", "Proyectos"),
+    (
+        "projects.none",
+        "Aún no hay proyectos. Añade uno con p. ej.: add Llamar al gestor +nombre",
+    ),
+    ("contexts.title", "Contextos"),
+    (
+        "contexts.none",
+        "Aún no hay contextos. Añade uno con p. ej.: add Llamar al gestor @nombre",
+    ),
+    ("tokens.count", "{count} {count|tarea|tareas}"),
+    ("week.title", "Últimos 7 días ({first} a {last}):"),
+    ("week.day", "{added} añadidas  {done} hechas"),
+    (
+        "week.totals",
+        "{added} añadidas, {completed} completadas, {open} de las nuevas siguen abiertas",
+    ),
+    ("week.rate", "Tasa de finalización: {rate}%"),
+    ("chart.title", "Completadas por día, últimos {days} días:"),
+    ("table.status", "ESTADO"),
+    ("table.due", "VENCE"),
+    ("table.age", "EDAD"),
+    ("table.estimate", "EST"),
+    ("table.score", "PUNTOS"),
+    ("table.description", "DESCRIPCIÓN"),
+    ("help.also_inline", "también: {aliases}"),
+    ("help.commands", "Comandos:"),
+    (
+        "help.details",
+        "Escribe 'help <comando>' para ver los detalles de un comando",
+    ),
+    (
+        "help.confirm",
+        "(remove, clear y trash empty preguntan antes; añade --yes para no preguntar)",
+    ),
+    (
+        "help.prefixes",
+        "Cualquier comienzo no ambiguo de un comando también vale: li, upd, temp",
+    ),
+    (
+        "help.text_selector",
+        "<num> también puede ser texto de la descripción: done \"groceries\"",
+    ),
+    (
+        "help.pick_selector",
+        "<num> también puede ser --pick, para elegir de un menú: done --pick todo",
+    ),
+    ("help.statuses", "Estados: {statuses}"),
+    ("help.archive", "El archivo de tareas archivadas es {path}"),
+    ("help.examples", "Ejemplos:"),
+    ("help.usage", "Uso:"),
+    ("help.also", "También: {aliases}"),
+    (
+        "flag.sync_url",
+        "--sync-url necesita una URL, la sincronización está desactivada",
+    ),
+    ("flag.sync_token", "--sync-token necesita un token"),
+    (
+        "flag.lang",
+        "--lang necesita un código de idioma como en o es",
+    ),
+    (
+        "flag.lang_unknown",
+        "No hay mensajes en {code}, se usa {language}",
+    ),
+    (
+        "flag.prompt",
+        "--prompt necesita una plantilla, p. ej. \"{list} [{open}/{total}] {dirty}> \"",
+    ),
+    (
+        "flag.prompt_unknown",
+        "Marcador {name} no reconocido en el prompt. Usa: {list}, {open}, {done}, {total}, {dirty}",
+    ),
+    ("flag.file", "--file necesita una ruta, se usa {default}"),
+    ("flag.number", "{flag} necesita un número, se usa {default}"),
+    ("flag.unknown", "Se ignora el argumento desconocido: {arg}"),
+    (
+        "flag.auto_escalate",
+        "--auto-escalate necesita --escalate-after <días>, no se escala nada",
+    ),
+    (
+        "flag.theme",
+        "--theme necesita ascii, default o la ruta de un archivo de tema",
+    ),
+    (
+        "flag.theme_failed",
+        "No se pudo leer el tema {path}: {error}, se usa el aspecto por defecto",
+    ),
+    (
+        "theme.invalid_line",
+        "La línea {line} del tema no es clave = valor, se ignora",
+    ),
+    (
+        "theme.unknown_key",
+        "Ajuste de tema desconocido {key} en la línea {line}, se ignora",
+    ),
+    (
+        "theme.unknown_color",
+        "Color desconocido {value} para {key}, se mantiene el de por defecto. Colores: {colors}",
+    ),
+    (
+        "theme.empty_icon",
+        "{key} no puede estar vacío, se mantiene {default}",
+    ),
+    (
+        "theme.not_boolean",
+        "{key} admite true o false, se mantiene {default}",
+    ),
+    (
+        "theme.unknown_preset",
+        "Tema predefinido desconocido {name}, se parte del de por defecto. Temas: {presets}",
+    ),
+    ("session.welcome", "¡Bienvenido a Todo CLI {version}!"),
+    (
+        "session.exit_hint",
+        "Escribe 'exit' para salir de la aplicación.",
+    ),
+    ("session.loaded", "{summary} cargadas"),
+    (
+        "session.load_failed",
+        "No se pudieron cargar las tareas: {error}",
+    ),
+    ("session.input_error", "Error al leer la entrada"),
+    (
+        "session.save_failed",
+        "No se pudieron guardar las tareas: {error}",
+    ),
+    ("session.saved", "¡Tareas guardadas!"),
+    (
+        "session.read_only",
+        "Solo lectura: se rechazan los comandos que cambian tareas y no se guarda nada",
+    ),
+    (
+        "session.not_writable",
+        "{path} no se puede escribir, así que esta sesión es de solo lectura",
+    ),
+    ("session.recap", "Esta sesión: {parts}"),
+    ("session.recap_added", "{count} {count|añadida|añadidas}"),
+    (
+        "session.recap_completed",
+        "{count} {count|completada|completadas}",
+    ),
+    (
+        "session.recap_removed",
+        "{count} {count|eliminada|eliminadas}",
+    ),
+    ("session.recap_edited", "{count} {count|editada|editadas}"),
+    ("session.goodbye", "¡Hasta luego!"),
+    ("file_sync.changed", "{path} cambió en el disco"),
+    (
+        "file_sync.changed_unsaved",
+        "{path} cambió en el disco y tus cambios no están guardados.",
+    ),
+    (
+        "file_sync.kept",
+        "Se queda tu lista. Guardar reemplazará el archivo.",
+    ),
+    (
+        "file_sync.changed_before_save",
+        "{path} cambió en otro sitio desde que se leyó. ¿Combinar esos cambios, sobrescribirlos o cancelar?",
+    ),
+    (
+        "file_sync.ask_reload",
+        "¿Descartar los cambios sin guardar y recargar?",
+    ),
+    ("file_sync.reloaded", "Recargado: {summary}"),
+    (
+        "file_sync.reload_failed",
+        "No se pudieron recargar las tareas: {error}",
+    ),
+    ("file_sync.merged", "Combinado: {summary}"),
+    (
+        "file_sync.save_hint",
+        "Escribe 'save' para escribir la lista combinada",
+    ),
+    (
+        "file_sync.merge_failed",
+        "No se pudieron combinar las tareas: {error}",
+    ),
+    (
+        "review.needs_terminal",
+        "Error: review necesita un terminal interactivo",
+    ),
+    ("review.none", "Nada que revisar"),
+    ("review.progress", "Revisión {n} de {total}"),
+    (
+        "review.actions",
+        "(d) hecha, (s) saltar, (r) quitar, (p) aplazar, (e) editar, (q) salir",
+    ),
+    ("review.postpone_by", "Aplazar (p. ej. 2d, 1w) [1d]"),
+    (
+        "review.new_description",
+        "Descripción nueva (vacía la deja igual)",
+    ),
+    ("review.updated", "Tarea {index} actualizada"),
+    ("review.keys", "Escribe d, s, r, p, e o q"),
+    (
+        "review.finished",
+        "Revisión terminada: {done} hechas, {removed} quitadas, {postponed} aplazadas, {edited} editadas, {skipped} saltadas",
+    ),
+    (
+        "prompt.not_confirmed",
+        "No confirmado: añade --yes para no preguntar",
+    ),
+    (
+        "prompt.not_asked",
+        "No se pregunta en una sesión con la entrada redirigida",
+    ),
+    ("prompt.yes_no", "[s/N]"),
+    ("prompt.yes_words", "s|si|sí"),
+    ("progress", "{done}/{total} hechas ({percent}%)"),
+];
@@ -0,0 +1,700 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::changes::ChangeSet;
+use crate::command::{ClearTarget, Command, CountTarget, expand_filter};
+use crate::date::{Date, Time, now};
+use crate::doctor::Finding;
+use crate::fuzzy::{self, Match};
+use crate::history::LogEntry;
+use crate::import::{self, ImportSummary};
+use crate::random::XorShift;
+use crate::todo::{
+    self, Agenda, DayActivity, Delegated, DuplicateGroup, Selector, Status, Streaks, Task,
+    TaskIndex, TaskRef, TodoError, TodoList, TodoStats, WeeklySummary,
+};
+
+/// Days covered by `chart`
+pub const CHART_DAYS: usize = 14;
+
+/// What running a command did or found, for the caller to show as it likes.
+/// Tasks are borrowed from the list the command ran against, numbered as
+/// they are after it ran.
+#[derive(Debug)]
+pub enum CommandOutcome<'a> {
+    /// The command only means something to whoever runs the loop: `exit`
+    Nothing,
+    /// Tasks with their numbers, in the order to show them: `list`,
+    /// `today`, `overdue`, `next` and `clear --dry-run`
+    Tasks(Vec<(usize, &'a Task)>),
+    /// The tasks `fuzzy` found closest to its text, best first
+    Ranked(Vec<Match<'a>>),
+    /// One task looked up by `show`
+    Task(usize, &'a Task),
+    /// The task `random` picked, if any was open
+    Picked(Option<(usize, &'a Task)>),
+    Stats {
+        stats: TodoStats,
+        streaks: Streaks,
+    },
+    Count(usize),
+    /// Projects or contexts with how many tasks carry each
+    Tokens(BTreeMap<String, usize>),
+    Agenda(Agenda<'a>),
+    /// Delegated tasks by person, for `waiting`
+    Delegated(Vec<Delegated<'a>>),
+    Week(WeeklySummary),
+    /// Tasks completed on each of the last [`CHART_DAYS`] days
+    Chart(Vec<DayActivity>),
+    /// What `add` and `add --from` added or left out
+    Added(Added),
+    /// A task or subtask as a command left it: `due`, `repeat`, `estimate`,
+    /// `wait`, `delegate`, `tag`, `untag`, `mark`, `append`, `prepend` and `blocks`
+    Edited(TaskIndex, &'a Task),
+    /// Where `sub` put the new subtask
+    SubtaskAdded(TaskIndex),
+    /// Due dates with their times, before and after `snooze`
+    Snoozed {
+        task: usize,
+        old: Option<(Date, Option<Time>)>,
+        new: (Date, Option<Time>),
+    },
+    /// Each task `done`, `toggle` or `update` changed, or why it could not be.
+    /// The tasks are all found before any is changed.
+    StatusChanged(Vec<Result<StatusChange, TodoError>>),
+    /// The task `reopen` took back to todo
+    Reopened(TaskIndex),
+    /// Completed tasks and subtasks, the latest first
+    RecentlyCompleted(Vec<(TaskIndex, &'a Task)>),
+    /// How many tasks `update all` changed, and how many strict transitions
+    /// made it leave alone
+    UpdatedAll {
+        count: usize,
+        skipped: usize,
+        changes: ChangeSet,
+    },
+    /// The task `remove` took out, subtasks and all
+    Removed(Box<Task>),
+    /// The tasks `clear` took out, as they were
+    Cleared {
+        tasks: Vec<Task>,
+        changes: ChangeSet,
+    },
+    Deduplicated {
+        groups: Vec<DuplicateGroup>,
+        changes: ChangeSet,
+    },
+    /// The IDs `renumber` changed, as (old, new) pairs
+    Renumbered(Vec<(u64, u64)>),
+    /// What `doctor` repaired and what is still wrong
+    Checked {
+        fixed: Vec<Finding>,
+        left: Vec<Finding>,
+    },
+    /// The task whose timer `track` started, and the one it stopped
+    TimerStarted {
+        task: usize,
+        stopped: Option<usize>,
+    },
+    /// The task whose timer `stop` stopped and how long it ran, if one did
+    TimerStopped(Option<(usize, Duration)>),
+    /// Numbers of the tasks `escalate` raised a priority
+    Escalated {
+        days: u64,
+        raised: Vec<usize>,
+    },
+    /// The newest entries of the history, oldest first
+    History(&'a [LogEntry]),
+    /// How many entries `history clear` removed
+    HistoryCleared(usize),
+    /// Whether `template save` replaced a template of the same name
+    TemplateSaved {
+        replaced: bool,
+    },
+    /// How many tasks `template apply` added
+    TemplateApplied(usize),
+    Templates(&'a BTreeMap<String, Vec<String>>),
+    /// The descriptions the deleted template held
+    TemplateDeleted(Vec<String>),
+    /// Whether `filter save` replaced a filter of the same name
+    FilterSaved {
+        replaced: bool,
+    },
+    Filters(&'a BTreeMap<String, String>),
+    /// What the deleted filter held
+    FilterDeleted(String),
+    /// Whether `snippet save` replaced a snippet of the same name
+    SnippetSaved {
+        replaced: bool,
+    },
+    Snippets(&'a BTreeMap<String, String>),
+    /// The text the deleted snippet held
+    SnippetDeleted(String),
+    Imported {
+        summary: ImportSummary,
+        changes: ChangeSet,
+    },
+    /// What taking in a settled merge changed
+    Merged(ChangeSet),
+}
+
+/// Tasks added from several descriptions or the lines of a file
+#[derive(Debug, Default)]
+pub struct Added {
+    /// Numbers of the new tasks, in order
+    pub added: Vec<usize>,
+    /// New tasks that repeat an open one
+    pub duplicates: Vec<Duplicate>,
+    /// Descriptions, or lines of the file, that could not become tasks,
+    /// numbered from 1, with the reason
+    pub rejected: Vec<(usize, TodoError)>,
+}
+
+/// A new task that repeats one already open
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    pub description: String,
+    /// Number of the open task it repeats
+    pub of: usize,
+    /// False when `--no-duplicates` left it out
+    pub added: bool,
+}
+
+/// One task's new status and what came of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusChange {
+    pub at: TaskIndex,
+    pub status: Status,
+    /// Open tasks it still waited on when it was completed
+    pub blocked_by: Vec<usize>,
+    /// Number of the next occurrence completing a recurring task added
+    pub next: Option<usize>,
+    /// Whether this completed the last open subtask of a parent still open
+    pub parent_ready: bool,
+}
+
+/// Run a command against the list without printing or asking anything, and
+/// say what it did. Questions the CLI asks first, such as `remove`'s, are
+/// for the caller to settle before running the command; `--pick` selectors
+/// turn into [`TodoError::NotInteractive`]. Commands that need a terminal,
+/// the files next to the list or the CLI's session, like `save` or `more`,
+/// give [`TodoError::CliOnly`].
+pub fn execute(list: &mut TodoList, command: Command) -> Result<CommandOutcome<'_>, TodoError> {
+    let today = Date::today();
+    match command {
+        Command::Due(selector, due) => {
+            let task = list.select_task(&selector)?;
+            let (due, time) = due.unzip();
+            list.set_due_at(task, due, time.flatten())?;
+            edited(list, task)
+        }
+        Command::Repeat(selector, recurrence) => {
+            let task = list.select_task(&selector)?;
+            list.set_recurrence(task, recurrence)?;
+            edited(list, task)
+        }
+        Command::Snooze(selector, by) => {
+            let task = list.select_task(&selector)?;
+            let (old, new) = list.snooze(task, by, today, Time::now())?;
+            Ok(CommandOutcome::Snoozed {
+                task: task.index(),
+                old,
+                new,
+            })
+        }
+        Command::Estimate(selector, estimate) => {
+            let task = list.select_task(&selector)?;
+            list.set_estimate(task, estimate)?;
+            edited(list, task)
+        }
+        Command::Wait(selector, reason) => {
+            let task = list.select_task(&selector)?;
+            list.wait(task, reason)?;
+            edited(list, task)
+        }
+        Command::Delegate(selector, person) => {
+            let task = list.select_task(&selector)?;
+            list.delegate(task, person.as_deref())?;
+            edited(list, task)
+        }
+        // How long counts as too long otherwise comes from the CLI's options
+        Command::Escalate(None) => Err(TodoError::CliOnly("escalate")),
+        Command::Escalate(Some(days)) => Ok(CommandOutcome::Escalated {
+            days,
+            raised: list.escalate(now(), days),
+        }),
+        Command::Track(selector) => {
+            let task = list.select_task(&selector)?;
+            let stopped = list.start_timer(task, now())?;
+            Ok(CommandOutcome::TimerStarted {
+                task: task.index(),
+                stopped,
+            })
+        }
+        Command::Stop => Ok(CommandOutcome::TimerStopped(list.stop_timer(now()))),
+        Command::Add(descriptions, skip_duplicates) => {
+            if let [description] = descriptions.as_slice() {
+                let task = Task::builder(description.clone()).build()?;
+                let mut added = Added::default();
+                add_unless_duplicate(list, task, skip_duplicates, &mut added);
+                return Ok(CommandOutcome::Added(added));
+            }
+            let mut added = Added::default();
+            for (i, description) in descriptions.into_iter().enumerate() {
+                match Task::builder(description).build() {
+                    Ok(task) => add_unless_duplicate(list, task, skip_duplicates, &mut added),
+                    Err(error) => added.rejected.push((i + 1, error)),
+                }
+            }
+            Ok(CommandOutcome::Added(added))
+        }
+        Command::AddSnippet(name, values, skip_duplicates) => {
+            let description = list.expand_snippet(&name, &values)?;
+            execute(list, Command::Add(vec![description], skip_duplicates))
+        }
+        Command::AddFrom(path, skip_duplicates) => {
+            let file = todo::read_task_file(&path, list.settings())?;
+            let mut added = Added {
+                rejected: file.failed,
+                ..Added::default()
+            };
+            for task in file.tasks {
+                add_unless_duplicate(list, task, skip_duplicates, &mut added);
+            }
+            Ok(CommandOutcome::Added(added))
+        }
+        Command::Update(selector, status, force) => {
+            let at = list.select(&selector)?;
+            let status = Status::from_str(&status)?;
+            let change = change_status(list, at, Some(status), strict(list, force))?;
+            Ok(CommandOutcome::StatusChanged(vec![Ok(change)]))
+        }
+        Command::UpdateAll(filter, status, force) => {
+            let before = list.tasks().to_vec();
+            let skipped = if strict(list, force) {
+                list.update_all_skipped(filter, status)
+            } else {
+                0
+            };
+            let count = list.update_all_status(filter, status, strict(list, force));
+            Ok(CommandOutcome::UpdatedAll {
+                count,
+                skipped,
+                changes: ChangeSet::between(&before, list.tasks()),
+            })
+        }
+        Command::Remove(selector, _) => {
+            let at = list.select(&selector)?;
+            Ok(CommandOutcome::Removed(Box::new(list.remove_at(at)?)))
+        }
+        Command::Done(selectors, force) => {
+            let changes = select_all(list, &selectors)?
+                .into_iter()
+                .map(|at| change_status(list, at, Some(Status::Completed), strict(list, force)))
+                .collect();
+            Ok(CommandOutcome::StatusChanged(changes))
+        }
+        Command::Toggle(selectors, force) => {
+            let changes = select_all(list, &selectors)?
+                .into_iter()
+                .map(|at| change_status(list, at, None, strict(list, force)))
+                .collect();
+            Ok(CommandOutcome::StatusChanged(changes))
+        }
+        Command::Reopen(selector) => {
+            let at = list.select(&selector)?;
+            list.reopen(at)?;
+            Ok(CommandOutcome::Reopened(at))
+        }
+        Command::ReopenLast => {
+            let at = list
+                .most_recently_completed()
+                .ok_or(TodoError::NothingCompleted)?;
+            list.reopen(at)?;
+            Ok(CommandOutcome::Reopened(at))
+        }
+        Command::Sub(selector, description) => {
+            let parent = list.select_task(&selector)?;
+            Ok(CommandOutcome::SubtaskAdded(
+                list.add_subtask(parent, description)?,
+            ))
+        }
+        Command::Tag(selector, tags) => {
+            let task = list.select_task(&selector)?;
+            list.add_tags(task, &tags)?;
+            edited(list, task)
+        }
+        Command::Untag(selector, tags) => {
+            let task = list.select_task(&selector)?;
+            list.remove_tags(task, &tags)?;
+            edited(list, task)
+        }
+        Command::Mark(selector, marker) => {
+            let task = list.select_task(&selector)?;
+            list.set_marker(task, marker)?;
+            edited(list, task)
+        }
+        Command::Append(selector, text) => {
+            let at = list.select(&selector)?;
+            list.append_description(at, &text)?;
+            Ok(CommandOutcome::Edited(at, list.get_at(at)?))
+        }
+        Command::Prepend(selector, text) => {
+            let at = list.select(&selector)?;
+            list.prepend_description(at, &text)?;
+            Ok(CommandOutcome::Edited(at, list.get_at(at)?))
+        }
+        Command::Blocks(index, on) => {
+            let task = list.task_ref(index)?;
+            let on = list.task_ref(on)?;
+            list.add_dependency(task, on)?;
+            edited(list, task)
+        }
+        Command::Clear(target, _, false) => {
+            let tasks: Vec<Task> = to_clear(list, target)
+                .into_iter()
+                .map(|(_, task)| task.clone())
+                .collect();
+            let before = list.tasks().to_vec();
+            match target {
+                None => list.clear_closed(),
+                Some(ClearTarget::All) => list.clear_all(),
+                Some(ClearTarget::Status(status)) => list.clear_by_status(status),
+            };
+            Ok(CommandOutcome::Cleared {
+                tasks,
+                changes: ChangeSet::between(&before, list.tasks()),
+            })
+        }
+        Command::Dedupe(dry_run) => {
+            let before = list.tasks().to_vec();
+            let groups = list.deduplicate(dry_run);
+            Ok(CommandOutcome::Deduplicated {
+                groups,
+                changes: ChangeSet::between(&before, list.tasks()),
+            })
+        }
+        Command::Renumber(_) => Ok(CommandOutcome::Renumbered(list.renumber()?)),
+        Command::Doctor(true) => Ok(CommandOutcome::Checked {
+            fixed: list.repair(),
+            left: list.problems(),
+        }),
+        Command::ClearHistory(_) => Ok(CommandOutcome::HistoryCleared(list.clear_log())),
+        Command::TemplateSave(name, indices) => Ok(CommandOutcome::TemplateSaved {
+            replaced: list.save_template(&name, &indices)?,
+        }),
+        Command::TemplateApply(name) => {
+            Ok(CommandOutcome::TemplateApplied(list.apply_template(&name)?))
+        }
+        Command::TemplateDelete(name) => Ok(CommandOutcome::TemplateDeleted(
+            list.delete_template(&name)?,
+        )),
+        Command::FilterSave(name, filter) => Ok(CommandOutcome::FilterSaved {
+            replaced: list.save_filter(&name, &filter)?,
+        }),
+        Command::FilterDelete(name) => {
+            Ok(CommandOutcome::FilterDeleted(list.delete_filter(&name)?))
+        }
+        Command::SnippetSave(name, text) => Ok(CommandOutcome::SnippetSaved {
+            replaced: list.save_snippet(&name, &text)?,
+        }),
+        Command::SnippetDelete(name) => {
+            Ok(CommandOutcome::SnippetDeleted(list.delete_snippet(&name)?))
+        }
+        Command::Import(format, path) => {
+            let tasks = import::read(format, &path)?;
+            let before = list.tasks().to_vec();
+            let summary = list.import(tasks, &path);
+            Ok(CommandOutcome::Imported {
+                summary,
+                changes: ChangeSet::between(&before, list.tasks()),
+            })
+        }
+        Command::TakeMerge(report, source) => {
+            let before = list.tasks().to_vec();
+            list.apply_merge(*report, &source);
+            Ok(CommandOutcome::Merged(ChangeSet::between(
+                &before,
+                list.tasks(),
+            )))
+        }
+        // Commands that only read the list, and those left to the CLI
+        command @ (Command::Exit
+        | Command::List { .. }
+        | Command::Fuzzy(_)
+        | Command::Show(..)
+        | Command::Random(_)
+        | Command::Next(_)
+        | Command::Stats
+        | Command::Count(_)
+        | Command::Projects
+        | Command::Contexts
+        | Command::Today
+        | Command::Overdue
+        | Command::Agenda(..)
+        | Command::Week
+        | Command::Waiting(_)
+        | Command::Chart
+        | Command::Clear(_, _, true)
+        | Command::History(_)
+        | Command::TemplateList
+        | Command::FilterList
+        | Command::RecentlyCompleted
+        | Command::SnippetList
+        | Command::Doctor(false)
+        | Command::Help(_)
+        | Command::Version
+        | Command::Completions(_)
+        | Command::More
+        | Command::Open(..)
+        | Command::Copy(..)
+        | Command::CopyList
+        | Command::Pomodoro(..)
+        | Command::Review
+        | Command::Report(..)
+        | Command::AddStdin(_)
+        | Command::Archive
+        | Command::Archived(_)
+        | Command::ArchiveStats(_)
+        | Command::Trash(_)
+        | Command::EmptyTrash(_)
+        | Command::Restore(_)
+        | Command::Run(..)
+        | Command::Save(_)
+        | Command::Reload(_)
+        | Command::Merge(..)
+        | Command::Sync(..)
+        | Command::BackupCreate(_)
+        | Command::BackupRestore(..)) => query(list, command),
+    }
+}
+
+/// Run a command that only reads the list, as [`execute`] would. The
+/// outcome borrows the list without locking it, so the caller can keep
+/// looking things up in it. Commands that change the list give
+/// [`TodoError::ReadOnly`].
+pub fn query(list: &TodoList, command: Command) -> Result<CommandOutcome<'_>, TodoError> {
+    let today = Date::today();
+    match command {
+        Command::Exit => Ok(CommandOutcome::Nothing),
+        Command::List { filter, limit, .. } => {
+            let (filter, _) = expand_filter(&filter, list)?;
+            let mut tasks = list.filter(&filter);
+            if let Some(limit) = limit {
+                limit.apply(&mut tasks);
+            }
+            Ok(CommandOutcome::Tasks(tasks))
+        }
+        Command::Fuzzy(text) => Ok(CommandOutcome::Ranked(fuzzy::rank(
+            list,
+            &text,
+            fuzzy::LIMIT,
+        ))),
+        Command::Show(selector, _) => {
+            let index = list.select_task(&selector)?.index();
+            Ok(CommandOutcome::Task(index, list.get_task(index)?))
+        }
+        Command::Random(filter) => {
+            let index = list.pick_random(&filter, &mut XorShift::from_clock(), None);
+            Ok(CommandOutcome::Picked(
+                index.map(|index| (index, &list[index - 1])),
+            ))
+        }
+        Command::Next(count) => Ok(CommandOutcome::Tasks(list.suggest_next(count))),
+        Command::Stats => Ok(CommandOutcome::Stats {
+            stats: list.statistics(),
+            streaks: list.streaks(today),
+        }),
+        Command::Count(target) => Ok(CommandOutcome::Count(match target {
+            None => list.len(),
+            Some(CountTarget::Status(status)) => list.count_status(status),
+            Some(CountTarget::Overdue) => list.count_overdue(today),
+            Some(CountTarget::DueToday) => list.count_due_on(today),
+        })),
+        Command::Projects => Ok(CommandOutcome::Tokens(list.project_counts())),
+        Command::Contexts => Ok(CommandOutcome::Tokens(list.context_counts())),
+        Command::Today => Ok(CommandOutcome::Tasks(list.due_on_or_before(today))),
+        Command::Overdue => Ok(CommandOutcome::Tasks(
+            list.due_on_or_before(today.add_days(-1)),
+        )),
+        Command::Agenda(days, _) => Ok(CommandOutcome::Agenda(list.agenda(today, days))),
+        Command::Week => Ok(CommandOutcome::Week(list.weekly_summary(now()))),
+        Command::Waiting(_) => Ok(CommandOutcome::Delegated(list.delegated(now()))),
+        Command::Chart => Ok(CommandOutcome::Chart(
+            list.daily_activity(today, CHART_DAYS),
+        )),
+        Command::Clear(target, _, true) => Ok(CommandOutcome::Tasks(to_clear(list, target))),
+        Command::History(count) => {
+            let log = list.log();
+            let skip = count.map_or(0, |count| log.len().saturating_sub(count));
+            Ok(CommandOutcome::History(&log[skip..]))
+        }
+        Command::TemplateList => Ok(CommandOutcome::Templates(list.templates())),
+        Command::FilterList => Ok(CommandOutcome::Filters(list.filters())),
+        Command::RecentlyCompleted => {
+            Ok(CommandOutcome::RecentlyCompleted(list.recently_completed()))
+        }
+        Command::SnippetList => Ok(CommandOutcome::Snippets(list.snippets())),
+        Command::Doctor(false) => Ok(CommandOutcome::Checked {
+            fixed: Vec::new(),
+            left: list.problems(),
+        }),
+        // These need a terminal, the files kept next to the list or the
+        // state of an interactive session
+        command @ (Command::Help(_)
+        | Command::Version
+        | Command::Completions(_)
+        | Command::More
+        | Command::Open(..)
+        | Command::Copy(..)
+        | Command::CopyList
+        | Command::Pomodoro(..)
+        | Command::Review
+        | Command::Report(..)
+        | Command::AddStdin(_)
+        | Command::Archive
+        | Command::Archived(_)
+        | Command::ArchiveStats(_)
+        | Command::Trash(_)
+        | Command::EmptyTrash(_)
+        | Command::Restore(_)
+        | Command::Run(..)
+        | Command::Save(_)
+        | Command::Reload(_)
+        | Command::Merge(..)
+        | Command::Sync(..)
+        | Command::BackupCreate(_)
+        | Command::BackupRestore(..)) => Err(TodoError::CliOnly(command.name())),
+        command @ (Command::Due(..)
+        | Command::Repeat(..)
+        | Command::Snooze(..)
+        | Command::Estimate(..)
+        | Command::Wait(..)
+        | Command::Delegate(..)
+        | Command::Escalate(_)
+        | Command::Track(_)
+        | Command::Stop
+        | Command::Add(..)
+        | Command::AddSnippet(..)
+        | Command::AddFrom(..)
+        | Command::Update(..)
+        | Command::UpdateAll(..)
+        | Command::Remove(..)
+        | Command::Done(..)
+        | Command::Toggle(..)
+        | Command::Reopen(_)
+        | Command::ReopenLast
+        | Command::Sub(..)
+        | Command::Tag(..)
+        | Command::Untag(..)
+        | Command::Mark(..)
+        | Command::Append(..)
+        | Command::Prepend(..)
+        | Command::Blocks(..)
+        | Command::Clear(_, _, false)
+        | Command::Dedupe(_)
+        | Command::Renumber(_)
+        | Command::Doctor(true)
+        | Command::ClearHistory(_)
+        | Command::TemplateSave(..)
+        | Command::TemplateApply(_)
+        | Command::TemplateDelete(_)
+        | Command::FilterSave(..)
+        | Command::FilterDelete(_)
+        | Command::SnippetSave(..)
+        | Command::SnippetDelete(_)
+        | Command::Import(..)
+        | Command::TakeMerge(..)) => Err(TodoError::ReadOnly(command.name())),
+    }
+}
+
+/// The tasks `clear` takes out: the closed ones, those with the status, or
+/// all of them
+pub fn to_clear(list: &TodoList, target: Option<ClearTarget>) -> Vec<(usize, &Task)> {
+    match target {
+        None => list.closed_tasks().collect(),
+        Some(ClearTarget::All) => list.list_tasks().collect(),
+        Some(ClearTarget::Status(status)) => list.filter_by_status(status).collect(),
+    }
+}
+
+/// Numbers of the open tasks a task waits on, as completing it would leave
+/// them. Subtasks and closed tasks wait on nothing.
+pub fn blockers_at(list: &TodoList, at: TaskIndex) -> Vec<usize> {
+    match list.get_task(at.task) {
+        Ok(task) if at.sub.is_none() && !task.is_closed() => list.blockers(task),
+        _ => Vec::new(),
+    }
+}
+
+fn edited(list: &TodoList, task: TaskRef) -> Result<CommandOutcome<'_>, TodoError> {
+    let at = TaskIndex {
+        task: task.index(),
+        sub: None,
+    };
+    Ok(CommandOutcome::Edited(at, list.get(task)?))
+}
+
+// Every task is looked up before any is touched
+fn select_all(list: &TodoList, selectors: &[Selector]) -> Result<Vec<TaskIndex>, TodoError> {
+    selectors
+        .iter()
+        .map(|selector| list.select(selector))
+        .collect()
+}
+
+// Whether a status change is held to the allowed transitions: when the
+// list's settings turn them on and the command isn't forced
+fn strict(list: &TodoList, force: bool) -> bool {
+    list.settings().strict_transitions && !force
+}
+
+// Give a task a status, or toggle it between done and todo with None
+fn change_status(
+    list: &mut TodoList,
+    at: TaskIndex,
+    status: Option<Status>,
+    strict: bool,
+) -> Result<StatusChange, TodoError> {
+    let status = match status {
+        Some(status) => status,
+        None if list.get_at(at)?.is_completed() => Status::Todo,
+        None => Status::Completed,
+    };
+    let completing = status == Status::Completed;
+    let blocked_by = if completing {
+        blockers_at(list, at)
+    } else {
+        Vec::new()
+    };
+    let next = list.update_status_at(at, status, strict)?;
+    let parent_ready = completing
+        && at.sub.is_some()
+        && list
+            .get_task(at.task)
+            .is_ok_and(|parent| !parent.is_closed() && parent.subtasks_done());
+    Ok(StatusChange {
+        at,
+        status,
+        blocked_by,
+        next,
+        parent_ready,
+    })
+}
+
+// Add the task unless it repeats an open one and `skip` says to leave
+// such tasks out
+fn add_unless_duplicate(list: &mut TodoList, task: Task, skip: bool, added: &mut Added) {
+    if let Some((of, _)) = list.find_similar(&task.description) {
+        added.duplicates.push(Duplicate {
+            description: task.description.clone(),
+            of,
+            added: !skip,
+        });
+        if skip {
+            return;
+        }
+    }
+    added.added.push(list.add(task));
+}
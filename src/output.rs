@@ -0,0 +1,461 @@
+use std::fmt::{Arguments, Display};
+use std::io::{self, IsTerminal, Write};
+use std::ops::Range;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+pub use rust_todo_cli::theme::Color;
+use rust_todo_cli::{msg, theme::Theme, todo::Status};
+use serde::Serialize;
+use serde_json::json;
+use terminal_size::Width;
+
+// Plain mode swaps emoji for ASCII and drops decorative separators
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+// ANSI colors are only written when this is set
+static COLOR: AtomicBool = AtomicBool::new(false);
+
+// Long descriptions in tables go on further lines instead of being cut short
+static WRAP: AtomicBool = AtomicBool::new(false);
+
+// Quiet mode drops informational messages, leaving requested output and problems
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+// Commands come from the interactive session rather than the command line
+static SESSION: AtomicBool = AtomicBool::new(false);
+
+// Set once an error is reported, so a one-shot command can exit with failure
+static FAILED: AtomicBool = AtomicBool::new(false);
+
+// A one-shot command given --json reports errors as JSON objects on stderr,
+// one per line, so scripts need not parse the text
+static JSON_ERRORS: AtomicBool = AtomicBool::new(false);
+
+// Open tasks older than this many days are highlighted, 0 turns it off
+pub const DEFAULT_STALE_DAYS: u64 = 14;
+static STALE_DAYS: AtomicU64 = AtomicU64::new(DEFAULT_STALE_DAYS);
+
+// The look picked with --theme, the default one until set
+static THEME: OnceLock<Theme> = OnceLock::new();
+
+// What plain mode uses whatever the theme, so piped output stays ASCII
+static ASCII_THEME: OnceLock<Theme> = OnceLock::new();
+
+// Todo tasks older than this many days are marked stale for `escalate`, 0
+// leaves the aging rule off
+static ESCALATE_AFTER: AtomicU64 = AtomicU64::new(0);
+
+// Decide plain and color modes from the flags, NO_COLOR and whether stdout is
+// a terminal. CLICOLOR_FORCE treats piped output as a terminal, say for `less -R`.
+pub fn init(plain_flag: bool, no_color_flag: bool) {
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+    let forced =
+        std::env::var("CLICOLOR_FORCE").is_ok_and(|value| !matches!(value.trim(), "" | "0"));
+    let terminal = forced || io::stdout().is_terminal();
+    set_plain(plain_flag || no_color || !terminal);
+    set_color(!plain_flag && !no_color_flag && !no_color && terminal);
+}
+
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+// Only the first theme set counts, as it is picked once at startup
+pub fn set_theme(theme: Theme) {
+    let _ = THEME.set(theme);
+}
+
+// Task markers, rules and colors, ASCII in plain mode
+pub fn theme() -> &'static Theme {
+    if is_plain() {
+        ASCII_THEME.get_or_init(Theme::ascii)
+    } else {
+        THEME.get_or_init(Theme::default)
+    }
+}
+
+pub fn set_wrap(wrap: bool) {
+    WRAP.store(wrap, Ordering::Relaxed);
+}
+
+pub fn wraps() -> bool {
+    WRAP.load(Ordering::Relaxed)
+}
+
+pub fn set_stale_days(days: u64) {
+    STALE_DAYS.store(days, Ordering::Relaxed);
+}
+
+pub fn stale_days() -> u64 {
+    STALE_DAYS.load(Ordering::Relaxed)
+}
+
+pub fn set_escalate_after(days: u64) {
+    ESCALATE_AFTER.store(days, Ordering::Relaxed);
+}
+
+pub fn escalate_after() -> u64 {
+    ESCALATE_AFTER.load(Ordering::Relaxed)
+}
+
+pub fn set_color(color: bool) {
+    COLOR.store(color, Ordering::Relaxed);
+}
+
+pub fn is_color() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+pub fn set_session(session: bool) {
+    SESSION.store(session, Ordering::Relaxed);
+}
+
+pub fn in_session() -> bool {
+    SESSION.load(Ordering::Relaxed)
+}
+
+pub fn set_failed(failed: bool) {
+    FAILED.store(failed, Ordering::Relaxed);
+}
+
+pub fn failed() -> bool {
+    FAILED.load(Ordering::Relaxed)
+}
+
+pub fn set_json_errors(json: bool) {
+    JSON_ERRORS.store(json, Ordering::Relaxed);
+}
+
+pub fn json_errors() -> bool {
+    JSON_ERRORS.load(Ordering::Relaxed)
+}
+
+// Report an error that stopped a command: as `Error: ...`, or as its JSON
+// object with a stable code when errors are machine-readable
+pub fn report_error<E: Serialize + Display>(error: &E) {
+    if json_errors() {
+        set_failed(true);
+        match serde_json::to_string(error) {
+            Ok(json) => eprintln!("{}", json),
+            Err(_) => fail!("{}", msg!("error.shown", error = error)),
+        }
+    } else {
+        fail!("{}", msg!("error.shown", error = error));
+    }
+}
+
+// How much a message matters, which decides whether and where it is printed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Level {
+    // What the user asked for, such as lists and tasks: stdout, quiet or not
+    Output,
+    // A question or countdown on a line left open: stdout, shown at once
+    Prompt,
+    // Confirmations and hints, dropped in quiet mode
+    Info,
+    // Problems that did not stop the command: stdout, or stderr when quiet
+    Warning,
+    // Problems outside any command, such as a bad flag or a damaged file at
+    // startup: always stderr, as text, and nothing fails
+    Notice,
+    // A command that failed: always stderr, and the exit code says so
+    Error,
+}
+
+// Print one line of text at its level; a prompt leaves its line open
+pub fn emit(level: Level, text: Arguments) {
+    match level {
+        Level::Output => println!("{}", text),
+        Level::Prompt => {
+            print!("{}", text);
+            io::stdout().flush().unwrap();
+        }
+        Level::Info if is_quiet() => {}
+        Level::Info => println!("{}", text),
+        Level::Warning if is_quiet() => eprintln!("{}", text),
+        Level::Warning => println!("{}", text),
+        Level::Notice => eprintln!("{}", text),
+        // Problems without an error of their own still come out as objects
+        Level::Error if json_errors() => {
+            set_failed(true);
+            eprintln!("{}", json!({"error": "Other", "message": text.to_string()}));
+        }
+        Level::Error => {
+            set_failed(true);
+            eprintln!("{}", text);
+        }
+    }
+}
+
+// Print requested output, like println!
+macro_rules! show {
+    () => {
+        $crate::output::emit($crate::output::Level::Output, format_args!(""))
+    };
+    ($($arg:tt)*) => {
+        $crate::output::emit($crate::output::Level::Output, format_args!($($arg)*))
+    };
+}
+
+// Ask for an answer on the same line, like print! followed by a flush
+macro_rules! prompt {
+    ($($arg:tt)*) => {
+        $crate::output::emit($crate::output::Level::Prompt, format_args!($($arg)*))
+    };
+}
+
+// Print an informational message, like println!
+macro_rules! info {
+    ($($arg:tt)*) => {
+        $crate::output::emit($crate::output::Level::Info, format_args!($($arg)*))
+    };
+}
+
+// Print a warning, like println!
+macro_rules! warning {
+    ($($arg:tt)*) => {
+        $crate::output::emit($crate::output::Level::Warning, format_args!($($arg)*))
+    };
+}
+
+// Print a notice, like eprintln!
+macro_rules! notice {
+    ($($arg:tt)*) => {
+        $crate::output::emit($crate::output::Level::Notice, format_args!($($arg)*))
+    };
+}
+
+// Report an error, like println! to stderr, and mark the command failed
+macro_rules! fail {
+    ($($arg:tt)*) => {
+        $crate::output::emit($crate::output::Level::Error, format_args!($($arg)*))
+    };
+}
+
+pub(crate) use {fail, info, notice, prompt, show, warning};
+
+// Wrap text in an ANSI color, or return it untouched when color is off
+pub fn paint(text: &str, color: Color) -> String {
+    if is_color() {
+        format!("\x1b[{}m{}\x1b[0m", color.code(), text)
+    } else {
+        text.to_string()
+    }
+}
+
+// Stand-ins for the highlight codes until a line is complete. Being control
+// characters they take no cells, so tables measure and cut text as usual.
+const HIGHLIGHT_START: char = '\u{1}';
+const HIGHLIGHT_END: char = '\u{2}';
+
+// Mark parts of some text: inverse video in color mode, [brackets] otherwise.
+// Ranges have to be in order, apart, and on character boundaries.
+pub fn highlight(text: &str, ranges: &[Range<usize>]) -> String {
+    let (open, close) = if is_color() {
+        (HIGHLIGHT_START, HIGHLIGHT_END)
+    } else {
+        ('[', ']')
+    };
+    let mut result = String::new();
+    let mut last = 0;
+    for range in ranges {
+        result.push_str(&text[last..range.start]);
+        result.push(open);
+        result.push_str(&text[range.clone()]);
+        result.push(close);
+        last = range.end;
+    }
+    result.push_str(&text[last..]);
+    result
+}
+
+// Swap the highlight stand-ins in the finished lines of one row for ANSI
+// codes. A highlight wrapped onto the next line is picked up again there;
+// one cut open by truncation is ended by the reset of a painted line.
+pub fn finish_highlights(lines: Vec<String>) -> Vec<String> {
+    let mut open = false;
+    lines
+        .into_iter()
+        .map(|line| {
+            let line = if open {
+                format!("{}{}", HIGHLIGHT_START, line)
+            } else {
+                line
+            };
+            open = match (line.rfind(HIGHLIGHT_START), line.rfind(HIGHLIGHT_END)) {
+                (Some(start), Some(end)) => start > end,
+                (start, _) => start.is_some(),
+            };
+            line.replace(HIGHLIGHT_START, "\x1b[7m")
+                .replace(HIGHLIGHT_END, "\x1b[27m")
+        })
+        .collect()
+}
+
+// Every marker printed in front of a message or task
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Icon {
+    Success,
+    Warning,
+    Unknown,
+    Hint,
+    Cleared,
+    Archive,
+    Empty,
+    Header,
+    Stats,
+    Repeat,
+    Reminder,
+    Timer,
+    Streak,
+    Link,
+    Next,
+    Reload,
+    Blocked,
+    Todo,
+    Waiting,
+    InProgress,
+    Completed,
+    Cancelled,
+}
+
+impl Icon {
+    pub fn glyph(self) -> &'static str {
+        let theme = theme();
+        let plain = is_plain() || !theme.emoji;
+        match self {
+            // Task markers are shared with the library's listings
+            Icon::Todo => theme.status(Status::Todo),
+            Icon::Waiting => theme.status(Status::Waiting),
+            Icon::InProgress => theme.status(Status::InProgress),
+            Icon::Completed => theme.status(Status::Completed),
+            Icon::Cancelled => theme.status(Status::Cancelled),
+            Icon::Blocked => &theme.blocked,
+            Icon::Link => &theme.link,
+            Icon::Warning | Icon::Unknown | Icon::Reminder if plain => "!",
+            Icon::Next if plain => ">",
+            _ if plain => "*",
+            Icon::Success => "✅",
+            Icon::Warning => "⚠️",
+            Icon::Unknown => "❓",
+            Icon::Hint => "💡",
+            Icon::Cleared => "🗑️",
+            Icon::Archive => "📦",
+            Icon::Empty => "📝",
+            Icon::Header => "📋",
+            Icon::Stats => "📊",
+            Icon::Repeat => "🔁",
+            Icon::Reminder => "⏰",
+            Icon::Timer => "⏱️",
+            Icon::Streak => "🔥",
+            Icon::Next => "👉",
+            Icon::Reload => "🔄",
+        }
+    }
+}
+
+impl Display for Icon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.glyph())
+    }
+}
+
+// Width used when a terminal does not report its own size
+const DEFAULT_WIDTH: usize = 80;
+
+// Width of the terminal stdout goes to, or else $COLUMNS, which shells set
+// but seldom export, falling back to 80 columns
+pub fn terminal_width() -> usize {
+    terminal_size::terminal_size()
+        .map(|(Width(width), _)| usize::from(width))
+        .filter(|&width| width > 0)
+        .or_else(|| {
+            std::env::var("COLUMNS")
+                .ok()
+                .and_then(|value| value.trim().parse().ok())
+                .filter(|&width| width > 0)
+        })
+        .unwrap_or(DEFAULT_WIDTH)
+}
+
+// Progress bar look, kept together so it is easy to theme
+pub const BAR_WIDTH: usize = 15;
+const BAR_FILLED: &str = "█";
+const BAR_EMPTY: &str = "░";
+const PLAIN_BAR_FILLED: &str = "#";
+const PLAIN_BAR_EMPTY: &str = "-";
+
+// Completion summary like `[████████░░░░░░░] 8/15 done (53%)`
+pub fn progress_bar(done: usize, total: usize) -> String {
+    let ratio = if total == 0 {
+        0.0
+    } else {
+        done as f64 / total as f64
+    };
+    let filled = ((ratio * BAR_WIDTH as f64).round() as usize).min(BAR_WIDTH);
+    let (full, empty) = if is_plain() || !theme().emoji {
+        (PLAIN_BAR_FILLED, PLAIN_BAR_EMPTY)
+    } else {
+        (BAR_FILLED, BAR_EMPTY)
+    };
+    let percent = format!("{:.0}", ratio * 100.0);
+    format!(
+        "[{}{}] {}",
+        full.repeat(filled),
+        empty.repeat(BAR_WIDTH - filled),
+        msg!("progress", done = done, total = total, percent = percent)
+    )
+}
+
+// Widest bar `histogram_bar` draws
+pub const HISTOGRAM_WIDTH: usize = 40;
+
+// Bar of one block per count, scaled down when `max` would not fit.
+// Any non-zero count keeps at least one block.
+pub fn histogram_bar(count: usize, max: usize) -> String {
+    let length = if max <= HISTOGRAM_WIDTH {
+        count
+    } else {
+        ((count * HISTOGRAM_WIDTH) as f64 / max as f64).round() as usize
+    };
+    let length = if count > 0 { length.max(1) } else { 0 };
+    let block = if is_plain() || !theme().emoji {
+        PLAIN_BAR_FILLED
+    } else {
+        BAR_FILLED
+    };
+    block.repeat(length)
+}
+
+// Length of the rules in characters
+const SEPARATOR_WIDTH: usize = 37;
+const BANNER_WIDTH: usize = 35;
+
+// Horizontal rule around task listings, unless the theme has none
+pub fn separator() {
+    let separator = &theme().separator;
+    if !is_plain() && !separator.is_empty() {
+        show!("{}", separator.repeat(SEPARATOR_WIDTH));
+    }
+}
+
+// Rule under the startup banner, left out with the banner in quiet mode
+pub fn banner_rule() {
+    let banner = &theme().banner;
+    if !is_plain() && !is_quiet() && !banner.is_empty() {
+        show!("{}", banner.repeat(BANNER_WIDTH));
+    }
+}
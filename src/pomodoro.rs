@@ -0,0 +1,41 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use rust_todo_cli::{
+    countdown::{Session, SystemClock, countdown},
+    msg,
+};
+
+use crate::output::{Icon, prompt, show};
+use crate::shutdown;
+
+// Set by Ctrl-C while a countdown is running
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+// Run a session in real time, redrawing one countdown line in place.
+// Ctrl-C ends the session instead of the program.
+pub fn run(length: Duration, task: usize) -> Session {
+    INTERRUPTED.store(false, Ordering::Relaxed);
+    catch_interrupt(true);
+    let session = countdown(&mut SystemClock::new(), length, &INTERRUPTED, |left| {
+        let seconds = left.as_secs_f64().ceil() as u64;
+        let left = format!("{:02}:{:02}", seconds / 60, seconds % 60);
+        prompt!(
+            "\r{} {} ",
+            Icon::Timer,
+            msg!("pomodoro.left", left = left, index = task)
+        );
+    });
+    catch_interrupt(false);
+    // Leave the cursor on a fresh line whichever way the session ended
+    show!();
+    if session.finished {
+        prompt!("\x07");
+    }
+    session
+}
+
+// Route Ctrl-C to `INTERRUPTED`, or back to ending the session
+fn catch_interrupt(catch: bool) {
+    shutdown::route_interrupt(catch.then_some(&INTERRUPTED));
+}
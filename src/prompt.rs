@@ -0,0 +1,64 @@
+use std::io::{self, BufRead, IsTerminal};
+
+use rust_todo_cli::{menu, msg};
+
+use crate::output::{Icon, fail, prompt, warning};
+
+// Ask a yes/no question on the terminal. Piped sessions are never blocked
+// waiting for an answer and get "no", so scripts have to pass --yes.
+pub fn confirm(question: &str) -> bool {
+    if !io::stdin().is_terminal() {
+        fail!(
+            "{}  {} {}",
+            Icon::Warning,
+            question,
+            msg!("prompt.not_confirmed")
+        );
+        return false;
+    }
+    confirm_from(question, &mut io::stdin().lock())
+}
+
+// Ask which of a few answers to take, each typed in full or by its first
+// letter. Piped sessions are never blocked and get None, as does an answer
+// that matches none of them.
+pub fn choose(question: &str, answers: &[&str]) -> Option<usize> {
+    if !io::stdin().is_terminal() {
+        warning!(
+            "{}  {} {}",
+            Icon::Warning,
+            question,
+            msg!("prompt.not_asked")
+        );
+        return None;
+    }
+    choose_from(question, answers, &mut io::stdin().lock())
+}
+
+// Ask which answer to take, reading it from `input`: `[r]eload/[k]eep`
+pub fn choose_from(question: &str, answers: &[&str], input: &mut impl BufRead) -> Option<usize> {
+    let listed: Vec<String> = answers
+        .iter()
+        .map(|answer| format!("[{}]{}", &answer[..1], &answer[1..]))
+        .collect();
+    prompt!("{} {} {} ", Icon::Warning, question, listed.join("/"));
+
+    let mut answer = String::new();
+    if input.read_line(&mut answer).is_err() {
+        return None;
+    }
+    let answer = answer.trim().to_lowercase();
+    if answer.is_empty() {
+        return None;
+    }
+    answers
+        .iter()
+        .position(|choice| *choice == answer || choice[..1] == answer)
+}
+
+// Ask a yes/no question, reading the answer from `input`; see
+// `menu::confirm` for what counts as yes
+pub fn confirm_from(question: &str, input: &mut impl BufRead) -> bool {
+    let question = format!("{} {}", Icon::Warning, question);
+    menu::confirm(&question, input, &mut io::stdout()).unwrap_or(false)
+}
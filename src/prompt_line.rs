@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use crate::todo::TodoList;
+
+/// The prompt when none is configured, as it always was
+pub const DEFAULT_PROMPT: &str = "> ";
+
+/// What `{dirty}` shows while there are unsaved changes
+pub const DIRTY_MARKER: &str = "*";
+
+/// Placeholders a prompt template may use
+pub const PLACEHOLDERS: [&str; 5] = ["list", "open", "done", "total", "dirty"];
+
+/// The state of the session a prompt can show
+#[derive(Debug, Clone, PartialEq)]
+pub struct PromptStatus {
+    /// Name of the list: its data file without the extension
+    pub list: String,
+    /// Tasks still to do or in progress
+    pub open: usize,
+    pub done: usize,
+    pub total: usize,
+    /// Whether the list has changes not saved yet
+    pub dirty: bool,
+}
+
+impl PromptStatus {
+    /// The status of `todo`, kept in the data file at `path`
+    pub fn new(todo: &TodoList, path: &str, dirty: bool) -> Self {
+        let list = Path::new(path)
+            .file_stem()
+            .map_or(path.to_string(), |stem| stem.to_string_lossy().into_owned());
+        let stats = todo.statistics();
+        PromptStatus {
+            list,
+            open: stats.todo + stats.in_progress,
+            done: stats.completed,
+            total: stats.total,
+            dirty,
+        }
+    }
+
+    // The text of one placeholder, None for names it does not know
+    fn value(&self, name: &str) -> Option<String> {
+        match name {
+            "list" => Some(self.list.clone()),
+            "open" => Some(self.open.to_string()),
+            "done" => Some(self.done.to_string()),
+            "total" => Some(self.total.to_string()),
+            "dirty" if self.dirty => Some(DIRTY_MARKER.to_string()),
+            "dirty" => Some(String::new()),
+            _ => None,
+        }
+    }
+}
+
+// The name in a placeholder that `text` starts with: `{open}` gives `open`
+fn placeholder(text: &str) -> Option<&str> {
+    let inside = text.strip_prefix('{')?;
+    let name = &inside[..inside.find('}')?];
+    (!name.contains('{')).then_some(name)
+}
+
+/// Fill in a prompt template: `"{list} [{open}/{total}] {dirty}> "` shows as
+/// `work [4/12] *> `. `{{` and `}}` stand for literal braces, and an unknown
+/// placeholder is shown as it was written.
+pub fn render(template: &str, status: &PromptStatus) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(at) = rest.find(['{', '}']) {
+        out.push_str(&rest[..at]);
+        let tail = &rest[at..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+        } else if let Some(name) = placeholder(tail)
+            && let Some(value) = status.value(name)
+        {
+            out.push_str(&value);
+            rest = &tail[name.len() + 2..];
+        } else {
+            out.push_str(&tail[..1]);
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Placeholders in a template that [`render`] does not know, to warn about
+pub fn unknown_placeholders(template: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    let mut rest = template;
+    while let Some(at) = rest.find('{') {
+        let tail = &rest[at..];
+        if let Some(after) = tail.strip_prefix("{{") {
+            rest = after;
+        } else if let Some(name) = placeholder(tail) {
+            if !PLACEHOLDERS.contains(&name) {
+                unknown.push(name.to_string());
+            }
+            rest = &tail[name.len() + 2..];
+        } else {
+            rest = &tail[1..];
+        }
+    }
+    unknown
+}
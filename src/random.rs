@@ -0,0 +1,54 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A source of random numbers, so picks can be replayed in tests
+pub trait Rng {
+    /// The next 64 random bits
+    fn next_u64(&mut self) -> u64;
+
+    /// A number from 0 up to but not including `bound`, which must not be 0
+    fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "nothing to pick from");
+        // The high half of the product spreads evenly over 0..bound
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+}
+
+/// A small, fast xorshift generator: plenty for picking a task, and not
+/// meant for anything secret
+#[derive(Debug, Clone)]
+pub struct XorShift {
+    state: u64,
+}
+
+impl XorShift {
+    /// A generator that always gives the same numbers for the same seed
+    pub fn new(seed: u64) -> Self {
+        // Xorshift never leaves a state of 0, so stir the seed first
+        let mut state = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        state = (state ^ (state >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        state = (state ^ (state >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        state ^= state >> 31;
+        XorShift {
+            state: if state == 0 { 1 } else { state },
+        }
+    }
+
+    /// A generator seeded from the clock and the process id
+    pub fn from_clock() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_nanos() as u64);
+        XorShift::new(nanos ^ ((std::process::id() as u64) << 32))
+    }
+}
+
+impl Rng for XorShift {
+    fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+}
@@ -0,0 +1,282 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use crate::log;
+use crate::merge::{MergeReport, merge};
+use crate::msg;
+use crate::todo::{Task, TodoError, TodoList, read_list_from};
+
+/// A copy of the list kept somewhere else, such as on a server, that
+/// `sync` pulls from and pushes to
+pub trait RemoteStore {
+    /// The copy as it is now, or None when nothing has been pushed yet
+    fn get(&mut self) -> Result<Option<Fetched>, TodoError>;
+
+    /// Replace the copy with `body`, but only while the remote holds what
+    /// is `expected`; otherwise fail with the `sync.changed` error. Returns
+    /// the version of the new copy, if the remote tells.
+    fn put(&mut self, body: &str, expected: &Expected) -> Result<Option<String>, TodoError>;
+}
+
+/// What a push expects the remote to hold, so that a copy changed since it
+/// was last seen is not replaced unseen
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expected {
+    /// The copy at this version
+    Version(String),
+    /// No copy at all, as before the first push
+    Nothing,
+    /// Whatever is there, as `sync push --force` sends
+    Any,
+}
+
+/// The remote copy of the list, as fetched
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fetched {
+    /// The list's JSON, laid out as in the data file
+    pub body: String,
+    /// Version of the copy, such as an HTTP ETag, for a later `put`
+    pub version: Option<String>,
+}
+
+/// The remote copy merged with the local tasks, not yet applied
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pulled {
+    pub report: MergeReport,
+    /// What a push made after this pull expects to find: the copy that was
+    /// merged, or nothing when there was none. None for a copy the remote
+    /// gave no version for, which can't be told from a later one.
+    pub expected: Option<Expected>,
+}
+
+/// Fetch the remote copy and merge it with `tasks`, as `merge` does with a
+/// file. A copy that is not a task list is an error, so nothing is merged
+/// from it. Templates stay as they are here.
+pub fn pull(remote: &mut impl RemoteStore, tasks: &[Task]) -> Result<Pulled, TodoError> {
+    let Some(fetched) = remote.get()? else {
+        return Ok(Pulled {
+            report: merge(tasks, &[]),
+            expected: Some(Expected::Nothing),
+        });
+    };
+    let theirs = read_list_from(fetched.body.as_bytes())
+        .map_err(|error| TodoError::SyncError(msg!("sync.not_a_list", error = error)))?;
+    Ok(Pulled {
+        report: merge(tasks, theirs.tasks()),
+        expected: fetched.version.map(Expected::Version),
+    })
+}
+
+/// Send the list to the remote in place of its copy. Unless the remote
+/// still holds what is `expected`, such as the copy that was pulled, it is
+/// left alone and the push fails, so edits made elsewhere meanwhile are not
+/// lost.
+pub fn push(
+    remote: &mut impl RemoteStore,
+    todo: &TodoList,
+    expected: &Expected,
+) -> Result<Option<String>, TodoError> {
+    remote.put(&todo.to_json()?, expected)
+}
+
+// Where `sync` goes, set by --sync-url and the token
+static ENDPOINT: Mutex<Option<HttpRemote>> = Mutex::new(None);
+
+/// Sync with the list at `url` from now on, sending `token` if given
+pub fn set_endpoint(url: &str, token: Option<&str>) -> Result<(), TodoError> {
+    let remote = HttpRemote::new(url, token)?;
+    *ENDPOINT.lock().unwrap_or_else(|error| error.into_inner()) = Some(remote);
+    Ok(())
+}
+
+/// The remote to sync with, if one was set
+pub fn endpoint() -> Option<HttpRemote> {
+    ENDPOINT
+        .lock()
+        .unwrap_or_else(|error| error.into_inner())
+        .clone()
+}
+
+// How long a request may take, from connecting to the last byte of the answer
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest answer read from the server, in bytes; a longer one is an error
+/// rather than something to hold in memory
+pub const MAX_BODY: u64 = 64 * 1024 * 1024;
+
+/// A list kept at a URL, read with GET and written with PUT. Versions are
+/// ETags: a PUT expecting one sends it back in If-Match, one expecting no
+/// copy sends `If-None-Match: *`, and a forced one sends neither. The token
+/// goes in a Bearer Authorization header. `https://` servers need a certificate from a public authority,
+/// and a token is only sent over plain `http://` to this machine.
+#[derive(Clone, PartialEq)]
+pub struct HttpRemote {
+    url: String,
+    host: String,
+    token: Option<String>,
+}
+
+// Keeps the token out of debug output
+impl std::fmt::Debug for HttpRemote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HttpRemote")
+            .field("url", &self.url)
+            .finish()
+    }
+}
+
+// A response as far as `sync` cares
+struct Response {
+    status: u16,
+    etag: Option<String>,
+    body: String,
+}
+
+impl HttpRemote {
+    /// The list at `url`, such as `https://example.com:8443/tasks.json`
+    pub fn new(url: &str, token: Option<&str>) -> Result<Self, TodoError> {
+        let (secure, rest) = match url.split_once("://") {
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("https") => (true, rest),
+            Some((scheme, rest)) if scheme.eq_ignore_ascii_case("http") => (false, rest),
+            Some(_) => return Err(TodoError::SyncError(msg!("sync.url_scheme", url = url))),
+            None => return Err(TodoError::SyncError(msg!("sync.url_no_scheme", url = url))),
+        };
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or("");
+        let host = match authority.rsplit_once(':') {
+            // A bracketed IPv6 address without a port
+            Some((_, port)) if port.ends_with(']') => authority,
+            Some((host, port)) => {
+                port.parse::<u16>()
+                    .map_err(|_| TodoError::SyncError(msg!("sync.url_port", url = url)))?;
+                host
+            }
+            None => authority,
+        };
+        if host.is_empty() {
+            return Err(TodoError::SyncError(msg!("sync.url_host", url = url)));
+        }
+        if token.is_some() && !secure && !is_loopback(host) {
+            return Err(TodoError::SyncError(msg!("sync.token_in_clear", url = url)));
+        }
+        Ok(HttpRemote {
+            url: url.to_string(),
+            host: host.to_string(),
+            token: token.map(str::to_string),
+        })
+    }
+
+    /// The URL as given
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        body: &str,
+        expected: &Expected,
+    ) -> Result<Response, TodoError> {
+        let failed = |error: ureq::Error| match error {
+            ureq::Error::BodyExceedsLimit(limit) => {
+                TodoError::SyncError(msg!("sync.too_large", url = self.url, limit = limit))
+            }
+            ureq::Error::HostNotFound => {
+                TodoError::SyncError(msg!("sync.unresolved", host = self.host))
+            }
+            error => TodoError::SyncError(msg!("sync.unreachable", url = self.url, error = error)),
+        };
+        // Statuses are answers here, not errors; `get` and `put` tell them apart
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .timeout_global(Some(TIMEOUT))
+            .http_status_as_error(false)
+            .build()
+            .into();
+        log!(Debug, "{} {}", method, self.url);
+        let authorization = self.token.as_ref().map(|token| format!("Bearer {}", token));
+        let sent = if method == "PUT" {
+            let mut request = agent.put(&self.url).header("Accept", "application/json");
+            if let Some(authorization) = &authorization {
+                request = request.header("Authorization", authorization);
+            }
+            request = match expected {
+                Expected::Version(version) => request.header("If-Match", version),
+                Expected::Nothing => request.header("If-None-Match", "*"),
+                Expected::Any => request,
+            };
+            request
+                .header("Content-Type", "application/json")
+                .send(body)
+        } else {
+            let mut request = agent.get(&self.url).header("Accept", "application/json");
+            if let Some(authorization) = &authorization {
+                request = request.header("Authorization", authorization);
+            }
+            request.call()
+        };
+        let mut answer = sent.map_err(failed)?;
+        let etag = answer
+            .headers()
+            .get("ETag")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let body = answer
+            .body_mut()
+            .with_config()
+            .limit(MAX_BODY)
+            .read_to_string()
+            .map_err(failed)?;
+        let response = Response {
+            status: answer.status().as_u16(),
+            etag,
+            body,
+        };
+        log!(
+            Debug,
+            "{} {} answered {} with {} bytes",
+            method,
+            self.url,
+            response.status,
+            response.body.len()
+        );
+        Ok(response)
+    }
+}
+
+// Whether `host` is this machine, which a token may reach without TLS
+fn is_loopback(host: &str) -> bool {
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost")
+        || host
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|address| address.is_loopback())
+}
+
+impl RemoteStore for HttpRemote {
+    fn get(&mut self) -> Result<Option<Fetched>, TodoError> {
+        let response = self.request("GET", "", &Expected::Any)?;
+        match response.status {
+            200 => Ok(Some(Fetched {
+                body: response.body,
+                version: response.etag,
+            })),
+            404 => Ok(None),
+            status => Err(status_error(&self.url, status)),
+        }
+    }
+
+    fn put(&mut self, body: &str, expected: &Expected) -> Result<Option<String>, TodoError> {
+        let response = self.request("PUT", body, expected)?;
+        match response.status {
+            200..=299 => Ok(response.etag),
+            412 => Err(TodoError::SyncError(msg!("sync.changed"))),
+            status => Err(status_error(&self.url, status)),
+        }
+    }
+}
+
+fn status_error(url: &str, status: u16) -> TodoError {
+    TodoError::SyncError(match status {
+        401 | 403 => msg!("sync.refused", url = url, status = status),
+        _ => msg!("sync.status", url = url, status = status),
+    })
+}
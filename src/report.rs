@@ -0,0 +1,145 @@
+use std::fmt::{self, Write};
+
+use crate::date::{Date, Timestamp, format_duration};
+use crate::listing::{described, due_date};
+use crate::messages::message;
+use crate::msg;
+use crate::todo::{Status, Task, TodoList};
+
+// Sections listing the tasks, in the order they appear, with the message
+// key of their heading
+const SECTIONS: [(Status, &str); 5] = [
+    (Status::InProgress, "stats.in_progress"),
+    (Status::Todo, "stats.todo"),
+    (Status::Waiting, "stats.waiting"),
+    (Status::Completed, "stats.done"),
+    (Status::Cancelled, "stats.cancelled"),
+];
+
+/// A Markdown status report of the list as of `now`, for `report md`: the
+/// numbers `stats` shows, what was completed since yesterday, and the tasks
+/// under a heading per status. Completions are only listed when the list
+/// has completion times, which lists saved by older versions lack.
+pub fn markdown(todo: &TodoList, now: Timestamp) -> String {
+    let mut out = String::new();
+    write_markdown(&mut out, todo, now).expect("writing to a String cannot fail");
+    out
+}
+
+fn write_markdown(out: &mut String, todo: &TodoList, now: Timestamp) -> fmt::Result {
+    let today = Date::from_timestamp(now);
+    writeln!(out, "# {}", msg!("report.title", date = today))?;
+    if todo.is_empty() {
+        return writeln!(out, "\n{}", msg!("report.empty"));
+    }
+
+    let stats = todo.statistics_at(now);
+    writeln!(out, "\n## {}\n", msg!("report.summary"))?;
+    writeln!(out, "- {}: {}", msg!("stats.total"), stats.total)?;
+    for (label, count) in [
+        ("stats.todo", stats.todo),
+        ("stats.waiting", stats.waiting),
+        ("stats.in_progress", stats.in_progress),
+        ("stats.done", stats.completed),
+        ("stats.cancelled", stats.cancelled),
+    ]
+    .into_iter()
+    // Most lists never park anything, so a line of none would be noise
+    .filter(|&(label, count)| label != "stats.waiting" || count > 0)
+    {
+        let percent = stats.percent(count);
+        writeln!(
+            out,
+            "- {}: {} ({:.0}%)",
+            message(label, &[]),
+            count,
+            percent
+        )?;
+    }
+    writeln!(
+        out,
+        "- {}",
+        msg!(
+            "stats.completed",
+            today = stats.completed_today,
+            week = stats.completed_this_week
+        )
+    )?;
+    if let Some(open) = &stats.longest_open {
+        writeln!(
+            out,
+            "- {}",
+            msg!(
+                "stats.longest_open",
+                index = open.index,
+                description = open.description,
+                days = open.days_open
+            )
+        )?;
+    }
+    let streaks = todo.streaks(today);
+    writeln!(
+        out,
+        "- {}",
+        msg!(
+            "stats.streak",
+            current = streaks.current,
+            best = streaks.best
+        )
+    )?;
+    if !stats.time_spent.is_zero() {
+        let time = format_duration(stats.time_spent);
+        writeln!(out, "- {}", msg!("stats.time_tracked", time = time))?;
+    }
+
+    if todo.tasks().iter().any(|task| task.completed_at.is_some()) {
+        writeln!(out, "\n## {}\n", msg!("report.recent"))?;
+        let yesterday = today.add_days(-1);
+        let recent: Vec<&Task> = todo
+            .tasks()
+            .iter()
+            .filter(|task| {
+                task.status == Status::Completed
+                    && task
+                        .completed_at
+                        .is_some_and(|at| Date::from_timestamp(at) >= yesterday)
+            })
+            .collect();
+        if recent.is_empty() {
+            writeln!(out, "{}", msg!("report.nothing"))?;
+        }
+        for task in recent {
+            writeln!(out, "- {}", described(task))?;
+        }
+    }
+
+    for (status, heading) in SECTIONS {
+        let mut tasks = todo.tasks().iter().filter(|task| task.status == status);
+        let Some(first) = tasks.next() else {
+            continue;
+        };
+        writeln!(out, "\n## {}\n", message(heading, &[]))?;
+        for task in std::iter::once(first).chain(tasks) {
+            write_task(out, task)?;
+        }
+    }
+    Ok(())
+}
+
+// A task as a bullet, with its subtasks as a checklist below it
+fn write_task(out: &mut String, task: &Task) -> fmt::Result {
+    write!(out, "- {}", described(task))?;
+    if let Some(due) = due_date(task) {
+        write!(out, " ({})", msg!("report.due", date = due))?;
+    }
+    writeln!(out)?;
+    for sub in &task.subtasks {
+        let mark = if sub.status == Status::Completed {
+            'x'
+        } else {
+            ' '
+        };
+        writeln!(out, "  - [{}] {}", mark, described(sub))?;
+    }
+    Ok(())
+}
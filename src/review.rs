@@ -0,0 +1,151 @@
+use std::io::{self, BufRead, IsTerminal};
+
+use rust_todo_cli::{
+    command::OutputFormat,
+    date::parse_duration,
+    msg,
+    store::Store,
+    todo::{TaskIndex, TodoList},
+};
+
+use crate::{
+    handlers::{handle_remove, handle_show, handle_snooze, handle_update},
+    output::{self, Icon, fail, info, prompt, show},
+};
+
+// What a review changed, reported when it ends
+#[derive(Debug, Default)]
+struct Summary {
+    done: usize,
+    skipped: usize,
+    removed: usize,
+    postponed: usize,
+    edited: usize,
+}
+
+// Walk through every open task, one action each. Piped input would
+// answer the prompts with whatever comes next, so it is refused.
+pub fn handle_review(todo: &mut TodoList, store: &Store) {
+    if !io::stdin().is_terminal() {
+        fail!("{}", msg!("review.needs_terminal"));
+        return;
+    }
+    review(todo, store, &mut io::stdin().lock());
+}
+
+fn review(todo: &mut TodoList, store: &Store, input: &mut impl BufRead) {
+    // Tasks are followed by ID since removing one renumbers the rest
+    let ids: Vec<u64> = todo
+        .tasks()
+        .iter()
+        .filter(|task| !task.is_closed())
+        .map(|task| task.id)
+        .collect();
+    if ids.is_empty() {
+        info!("{} {}", Icon::Empty, msg!("review.none"));
+        return;
+    }
+
+    let mut summary = Summary::default();
+    'tasks: for (n, &id) in ids.iter().enumerate() {
+        let Ok(task) = todo.ref_by_id(id) else {
+            continue;
+        };
+        let index = task.index();
+        show!(
+            "\n{} {}",
+            Icon::Header,
+            msg!("review.progress", n = n + 1, total = ids.len())
+        );
+        handle_show(todo, index, OutputFormat::Human);
+        let at = TaskIndex {
+            task: index,
+            sub: None,
+        };
+
+        loop {
+            let Some(answer) = ask(&msg!("review.actions"), input) else {
+                break 'tasks;
+            };
+            match answer.to_lowercase().as_str() {
+                "d" | "done" => {
+                    handle_update(todo, at, "done", false);
+                    summary.done += 1;
+                }
+                "s" | "skip" | "" => summary.skipped += 1,
+                "r" | "remove" => {
+                    handle_remove(todo, store, at, true);
+                    summary.removed += 1;
+                }
+                "p" | "postpone" => {
+                    let Some(text) = ask(&msg!("review.postpone_by"), input) else {
+                        break 'tasks;
+                    };
+                    let text = if text.is_empty() { "1d" } else { &text };
+                    match parse_duration(text) {
+                        Ok(by) => {
+                            handle_snooze(todo, task, by);
+                            summary.postponed += 1;
+                        }
+                        Err(error) => {
+                            output::report_error(&error);
+                            continue;
+                        }
+                    }
+                }
+                "e" | "edit" => {
+                    let Some(text) = ask(&msg!("review.new_description"), input) else {
+                        break 'tasks;
+                    };
+                    if text.is_empty() {
+                        continue;
+                    }
+                    match todo.set_description(task, text) {
+                        Ok(()) => {
+                            info!(
+                                "{} {}",
+                                Icon::Success,
+                                msg!("review.updated", index = index)
+                            );
+                            summary.edited += 1;
+                        }
+                        Err(error) => {
+                            output::report_error(&error);
+                            continue;
+                        }
+                    }
+                }
+                "q" | "quit" => break 'tasks,
+                _ => {
+                    info!("{} {}", Icon::Hint, msg!("review.keys"));
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+
+    show!(
+        "\n{} {}",
+        Icon::Stats,
+        msg!(
+            "review.finished",
+            done = summary.done,
+            removed = summary.removed,
+            postponed = summary.postponed,
+            edited = summary.edited,
+            skipped = summary.skipped
+        )
+    );
+}
+
+// Prompt on the review's own line; None once input runs out
+fn ask(question: &str, input: &mut impl BufRead) -> Option<String> {
+    prompt!("{} {}: ", Icon::Hint, question);
+
+    let mut answer = String::new();
+    match input.read_line(&mut answer) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(answer.trim().to_string()),
+    }
+}
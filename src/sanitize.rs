@@ -0,0 +1,88 @@
+use crate::todo::TodoError;
+
+/// Longest line of input taken by default, in bytes
+pub const DEFAULT_MAX_LINE: usize = 4096;
+
+/// How much of a line that is too long its error shows, in bytes
+pub const PREVIEW_LEN: usize = 40;
+
+/// Text that is safe to keep and to print: escape sequences and control
+/// characters are taken out, so a task cannot restyle or clear the terminal
+/// when listed, and runs of whitespace, tabs and line breaks among them,
+/// become a single space. Leading and trailing whitespace is trimmed.
+pub fn sanitize(text: &str) -> String {
+    let mut clean = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    // A space is held back until something follows it
+    let mut space = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => match chars.next() {
+                Some('[') => skip_csi(&mut chars),
+                // Strings ended by BEL or ESC \, such as window titles
+                Some(']' | 'P' | 'X' | '^' | '_') => skip_string(&mut chars),
+                // Anything else is a two-character sequence
+                _ => {}
+            },
+            '\u{9b}' => skip_csi(&mut chars),
+            c if c.is_whitespace() => space = true,
+            c if c.is_control() => {}
+            c => {
+                if space && !clean.is_empty() {
+                    clean.push(' ');
+                }
+                space = false;
+                clean.push(c);
+            }
+        }
+    }
+    clean
+}
+
+// The parameters and final byte of a control sequence, after ESC [
+fn skip_csi(chars: &mut impl Iterator<Item = char>) {
+    for c in chars {
+        if ('\x40'..='\x7e').contains(&c) {
+            break;
+        }
+    }
+}
+
+// The rest of a string sequence, up to and with its BEL or ESC \
+fn skip_string(chars: &mut std::iter::Peekable<impl Iterator<Item = char>>) {
+    while let Some(c) = chars.next() {
+        if c == '\x07' || c == '\u{9c}' {
+            break;
+        }
+        if c == '\x1b' {
+            chars.next_if_eq(&'\\');
+            break;
+        }
+    }
+}
+
+/// The longest start of `text` at most `max` bytes long that ends on a
+/// character boundary
+pub fn truncate(text: &str, max: usize) -> &str {
+    if text.len() <= max {
+        return text;
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
+/// A line of input, [`sanitize`]d, as long as it is no longer than `max`
+/// bytes (0 for any length). A longer one is an error showing how it starts.
+pub fn sanitize_line(line: &str, max: usize) -> Result<String, TodoError> {
+    if max > 0 && line.len() > max {
+        return Err(TodoError::LineTooLong {
+            len: line.len(),
+            max,
+            start: sanitize(truncate(line, PREVIEW_LEN)),
+        });
+    }
+    Ok(sanitize(line))
+}
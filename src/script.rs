@@ -0,0 +1,99 @@
+use std::fs;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rust_todo_cli::{
+    command::parse_command, msg, sanitize::sanitize_line, store::Store, todo::TodoError,
+    todo::TodoList, view::ListView,
+};
+
+use crate::{
+    handlers::{execute, report_parse_error},
+    output::{self, Icon, fail, info},
+};
+
+// Scripts may run other scripts, but only this many deep, so one that ends
+// up running itself stops instead of going round forever
+const MAX_DEPTH: usize = 8;
+
+// Scripts running right now, the one that runs this one included
+static DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+// What a script did, reported when it ends
+#[derive(Debug, Default)]
+struct Summary {
+    executed: usize,
+    succeeded: usize,
+    failed: usize,
+}
+
+// Run the commands in a file one line at a time, as if they were typed,
+// echoing each before its output. Blank lines and # comments are skipped,
+// and a line longer than --max-line fails without running.
+// The first line that fails ends the run unless `keep_going` is set.
+pub fn handle_run(
+    todo: &mut TodoList,
+    store: &Store,
+    view: &mut ListView,
+    path: &str,
+    keep_going: bool,
+) {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(error) => {
+            output::report_error(&TodoError::from(error));
+            return;
+        }
+    };
+    if DEPTH.load(Ordering::Relaxed) >= MAX_DEPTH {
+        fail!("{}", msg!("run.too_deep", path = path, depth = MAX_DEPTH));
+        return;
+    }
+
+    // Whether a line failed is told by the failure flag, so it starts out
+    // clear for each line and the earlier state is put back at the end
+    let failed_before = output::failed();
+    DEPTH.fetch_add(1, Ordering::Relaxed);
+    let mut summary = Summary::default();
+    let mut stopped_at = None;
+    for (number, line) in text.lines().enumerate() {
+        output::set_failed(false);
+        match sanitize_line(line, store.settings.max_line) {
+            Ok(line) if line.is_empty() || line.starts_with('#') => continue,
+            Ok(line) => {
+                info!("{}:{}> {}", path, number + 1, line);
+                match parse_command(&line) {
+                    Ok(command) => execute(todo, store, view, command),
+                    Err(error) => report_parse_error(&error),
+                }
+            }
+            Err(error) => output::report_error(&error),
+        }
+        summary.executed += 1;
+        if output::failed() {
+            summary.failed += 1;
+            if !keep_going {
+                stopped_at = Some(number + 1);
+                break;
+            }
+        } else {
+            summary.succeeded += 1;
+        }
+    }
+    DEPTH.fetch_sub(1, Ordering::Relaxed);
+    output::set_failed(failed_before || summary.failed > 0);
+
+    info!(
+        "{} {}",
+        Icon::Stats,
+        msg!(
+            "run.summary",
+            path = path,
+            executed = summary.executed,
+            succeeded = summary.succeeded,
+            failed = summary.failed
+        )
+    );
+    if let Some(line) = stopped_at {
+        info!("{} {}", Icon::Hint, msg!("run.stopped", line = line));
+    }
+}
@@ -0,0 +1,42 @@
+use crate::history::DEFAULT_HISTORY_LIMIT;
+use crate::sanitize::DEFAULT_MAX_LINE;
+use crate::todo::DEFAULT_MAX_LENGTH;
+
+/// How tasks are made and checked and how files are written, as picked once
+/// for a run, such as from the command line. A [`Store`](crate::store::Store)
+/// carries its settings and hands them to every list it loads; a list made
+/// some other way has the defaults until given others with
+/// [`TodoList::set_settings`](crate::todo::TodoList::set_settings).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Settings {
+    /// Cap on description length in characters, 0 for none
+    pub max_length: usize,
+    /// Hold status changes to [`Status::is_valid_transition`] unless they
+    /// are forced
+    ///
+    /// [`Status::is_valid_transition`]: crate::todo::Status::is_valid_transition
+    pub strict_transitions: bool,
+    /// Take +project and @context tokens out of the descriptions of tasks
+    /// added or edited
+    pub strip_tokens: bool,
+    /// Save files as compact JSON, without the indentation that makes them
+    /// easy to read but larger and slower to write
+    pub compact_files: bool,
+    /// Cap on the length of a line of input in bytes, 0 for none
+    pub max_line: usize,
+    /// Entries of the history kept when it is saved
+    pub history_limit: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            max_length: DEFAULT_MAX_LENGTH,
+            strict_transitions: false,
+            strip_tokens: false,
+            compact_files: false,
+            max_line: DEFAULT_MAX_LINE,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+        }
+    }
+}
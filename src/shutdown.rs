@@ -0,0 +1,157 @@
+use std::ffi::c_int;
+use std::io;
+use std::ptr;
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, AtomicPtr, AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(windows)]
+use signal_hook::consts::SIGBREAK;
+#[cfg(unix)]
+use signal_hook::consts::SIGHUP;
+use signal_hook::consts::{SIGINT, SIGTERM};
+use signal_hook::low_level;
+
+// Ctrl-C, `kill` and, on Unix, a closed terminal end the interactive session
+// the way `exit` does. The signal handler only raises a flag; the input loop
+// notices it, saves and leaves. A second Ctrl-C soon after the first ends the
+// program at once, for when that is taking too long.
+
+// Set once a shutdown has been asked for
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+// When the latest request came in, in seconds since the epoch
+static REQUESTED_AT: AtomicU64 = AtomicU64::new(0);
+
+// Whether the handlers are in place, so others know what to hand SIGINT back to
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+
+// A second request within this many seconds of the first forces an exit
+const FORCE_WINDOW_SECS: u64 = 2;
+
+// How often a wait for input checks whether a shutdown was asked for
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+// Exit status after being interrupted, as shells report it for SIGINT
+const INTERRUPTED_STATUS: i32 = 130;
+
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::Relaxed)
+}
+
+// Note a shutdown request. Runs inside the signal handler, so it only
+// touches atomics and the clock, except when forcing the exit.
+fn request() {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs());
+    let before = REQUESTED_AT.swap(now, Ordering::Relaxed);
+    if REQUESTED.swap(true, Ordering::Relaxed) && now.saturating_sub(before) <= FORCE_WINDOW_SECS {
+        force_exit();
+    }
+}
+
+// Lines of stdin, read on a helper thread so that waiting for one can be cut
+// short by a shutdown request. A line is only read when asked for, which
+// leaves stdin to prompts while a command runs.
+pub struct Lines {
+    wanted: Sender<()>,
+    lines: Receiver<io::Result<String>>,
+}
+
+impl Lines {
+    pub fn new() -> Self {
+        let (wanted, wanted_rx) = mpsc::channel::<()>();
+        let (lines_tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for () in wanted_rx {
+                let mut line = String::new();
+                let result = match io::stdin().read_line(&mut line) {
+                    // The end of the input, as when a piped script runs out
+                    Ok(0) => break,
+                    result => result.map(|_| line),
+                };
+                if lines_tx.send(result).is_err() {
+                    break;
+                }
+            }
+        });
+        Lines { wanted, lines }
+    }
+
+    // The next line typed, or None once a shutdown has been asked for or
+    // the input has ended
+    pub fn next(&self) -> Option<io::Result<String>> {
+        if requested() || self.wanted.send(()).is_err() {
+            return None;
+        }
+        loop {
+            match self.lines.recv_timeout(POLL_INTERVAL) {
+                Ok(line) => return Some(line),
+                Err(RecvTimeoutError::Timeout) if !requested() => {}
+                Err(_) => return None,
+            }
+        }
+    }
+}
+
+// Where Ctrl-C goes instead while something else wants it, as a pointer to
+// a `'static` flag, or null
+static INTERRUPT_TO: AtomicPtr<AtomicBool> = AtomicPtr::new(ptr::null_mut());
+
+// SIGINT is taken over once, by whichever of `install` and `route_interrupt`
+// comes first, and then told apart by the flags above
+static CATCH_INTERRUPT: Once = Once::new();
+
+// Signals other than SIGINT that end the session. Windows raises SIGBREAK
+// for Ctrl-Break and has no hangup.
+#[cfg(unix)]
+const TERMINATION: [c_int; 2] = [SIGTERM, SIGHUP];
+#[cfg(windows)]
+const TERMINATION: [c_int; 2] = [SIGTERM, SIGBREAK];
+
+fn on_interrupt() {
+    let route = INTERRUPT_TO.load(Ordering::Relaxed);
+    if !route.is_null() {
+        // SAFETY: only `'static` flags are routed to
+        unsafe { (*route).store(true, Ordering::Relaxed) };
+    } else if INSTALLED.load(Ordering::Relaxed) {
+        request();
+    } else {
+        let _ = low_level::emulate_default_handler(SIGINT);
+    }
+}
+
+fn catch_interrupt() {
+    CATCH_INTERRUPT.call_once(|| {
+        // SAFETY: the handler only touches atomics, or ends the process in
+        // ways signal-hook documents as async-signal-safe
+        unsafe { low_level::register(SIGINT, on_interrupt) }.expect("SIGINT can be caught");
+    });
+}
+
+// Route Ctrl-C, termination and hangup to the shutdown flag
+pub fn install() {
+    INSTALLED.store(true, Ordering::Relaxed);
+    catch_interrupt();
+    for signal in TERMINATION {
+        // SAFETY: the handler only stores to atomics and reads the clock,
+        // which are signal-safe, or ends the process with `_exit`
+        unsafe { low_level::register(signal, request) }.expect("termination signals can be caught");
+    }
+}
+
+// Send Ctrl-C to `flag` for a while, as `pomodoro` does to end a session
+// early, or with None back to where it went before
+pub fn route_interrupt(flag: Option<&'static AtomicBool>) {
+    catch_interrupt();
+    let route = flag.map_or(ptr::null_mut(), |flag| ptr::from_ref(flag).cast_mut());
+    INTERRUPT_TO.store(route, Ordering::Relaxed);
+}
+
+fn force_exit() -> ! {
+    // `_exit` skips destructors and buffers, as a signal handler must
+    low_level::exit(INTERRUPTED_STATUS)
+}
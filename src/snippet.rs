@@ -0,0 +1,105 @@
+use crate::todo::TodoError;
+
+// One stretch of a snippet's text: as written, or a placeholder's name
+#[derive(Debug, Clone, PartialEq)]
+enum Piece<'a> {
+    Text(String),
+    Placeholder(&'a str),
+}
+
+// Cut a snippet's text into plain text and placeholders. `{{` and `}}` are
+// literal braces; any other brace has to open or close a placeholder.
+fn pieces(text: &str) -> Result<Vec<Piece<'_>>, TodoError> {
+    let invalid = || TodoError::InvalidSnippet(text.to_string());
+    let mut pieces = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(at) = rest.find(['{', '}']) {
+        plain.push_str(&rest[..at]);
+        let brace = &rest[at..at + 1];
+        let after = &rest[at + 1..];
+        if after.starts_with(brace) {
+            plain.push_str(brace);
+            rest = &after[1..];
+            continue;
+        }
+        if brace == "}" {
+            return Err(invalid());
+        }
+        let end = after.find('}').ok_or_else(invalid)?;
+        let name = &after[..end];
+        if !is_placeholder_name(name) {
+            return Err(invalid());
+        }
+        if !plain.is_empty() {
+            pieces.push(Piece::Text(std::mem::take(&mut plain)));
+        }
+        pieces.push(Piece::Placeholder(name));
+        rest = &after[end + 1..];
+    }
+    plain.push_str(rest);
+    if !plain.is_empty() {
+        pieces.push(Piece::Text(plain));
+    }
+    Ok(pieces)
+}
+
+/// Whether a word can name a placeholder: letters, digits, `-` and `_`,
+/// starting with a letter
+pub fn is_placeholder_name(name: &str) -> bool {
+    name.chars().next().is_some_and(char::is_alphabetic)
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The placeholders of a snippet's text, each once, in the order they first
+/// appear
+pub fn placeholders(text: &str) -> Result<Vec<String>, TodoError> {
+    let mut names: Vec<String> = Vec::new();
+    for piece in pieces(text)? {
+        if let Piece::Placeholder(name) = piece
+            && !names.iter().any(|known| known == name)
+        {
+            names.push(name.to_string());
+        }
+    }
+    Ok(names)
+}
+
+/// Fill in a snippet's placeholders with `values`, given as (name, value)
+/// pairs; a name given twice takes the later value. Every placeholder needs
+/// a value, and every value a placeholder to go in, so a mistyped name is
+/// caught rather than left out.
+pub fn expand(text: &str, values: &[(String, String)]) -> Result<String, TodoError> {
+    let pieces = pieces(text)?;
+    let needed = placeholders(text)?;
+    if let Some((key, _)) = values.iter().find(|(key, _)| !needed.contains(key)) {
+        return Err(TodoError::UnknownPlaceholder {
+            key: key.clone(),
+            needed,
+        });
+    }
+    let value = |name: &str| {
+        values
+            .iter()
+            .rev()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    };
+    let missing: Vec<String> = needed
+        .iter()
+        .filter(|name| value(name).is_none())
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(TodoError::MissingPlaceholders { missing, needed });
+    }
+    Ok(pieces
+        .iter()
+        .map(|piece| match piece {
+            Piece::Text(text) => text.as_str(),
+            Piece::Placeholder(name) => value(name).unwrap_or_default(),
+        })
+        .collect())
+}
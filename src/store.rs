@@ -0,0 +1,613 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::fs::OpenOptions;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+use std::time::SystemTime;
+
+use serde::Serialize;
+
+use crate::date::Timestamp;
+use crate::history;
+use crate::settings::Settings;
+use crate::todo::{
+    Status, Storable, TRASH_LIMIT, Task, TodoError, TodoList, for_each_saved_task, read_list,
+};
+use crate::{log, log_error};
+
+/// Name of the file holding the live task list
+pub const DATA_FILE: &str = "tasks.json";
+/// Completed tasks moved out of the live list by `archive`
+pub const ARCHIVE_FILE: &str = "archive.json";
+/// Removed tasks, restorable until they fall off the end
+pub const TRASH_FILE: &str = "trash.json";
+/// Every change made to the tasks, shown by `history`
+pub const LOG_FILE: &str = "tasks.log.json";
+/// Added to the task file's name for the copy kept before it is emptied
+pub const PRE_WIPE_SUFFIX: &str = ".pre-wipe";
+/// Added to the task file's name for the backup of everything taken before
+/// `backup restore` replaces it
+pub const PRE_RESTORE_SUFFIX: &str = ".pre-restore";
+/// Added to the task file's name for the list written next to it when it
+/// could not replace changes made to the file elsewhere
+pub const UNSAVED_SUFFIX: &str = ".unsaved";
+/// Added to the task file's name for the copy kept before a list replaces a
+/// file that could not be read
+pub const UNREADABLE_SUFFIX: &str = ".unreadable";
+/// Tasks the file may hold before saving an empty list over it needs a
+/// go-ahead
+pub const DEFAULT_WIPE_GUARD: usize = 10;
+
+/// What a store has learned about its files so far: how each was when last
+/// read or written, and the archive as read. Clones of a store share it, so
+/// a clone made to save once with other options still sees the same files.
+#[derive(Debug, Clone, Default)]
+pub struct Seen(Rc<SeenFiles>);
+
+#[derive(Debug, Default)]
+struct SeenFiles {
+    // The archive as last read or written, with its path and stamp then, so
+    // a session reads it once however often it is listed
+    archive: RefCell<Option<(String, Stamp, Rc<TodoList>)>>,
+    // Each task file as last read or written, by path. Saving over one that
+    // changed since is turned down, see [`Store::save`]; one never read or
+    // written has nothing to check against.
+    versions: RefCell<HashMap<String, Version>>,
+    // Task files that failed to load, by path, with whether the user has
+    // since given the go-ahead to replace them
+    unreadable: RefCell<HashMap<String, bool>>,
+}
+
+// Stores are the same whatever they have seen
+impl PartialEq for Seen {
+    fn eq(&self, _: &Seen) -> bool {
+        true
+    }
+}
+
+// Modification time and size of a file, None for a missing one
+type Stamp = Option<(SystemTime, u64)>;
+
+// Hash of a file's contents, None for a missing one
+type Version = Option<u64>;
+
+/// What `archive stats` reports, counted as the archive is read rather than
+/// from a list of it
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ArchiveStats {
+    pub tasks: usize,
+    pub subtasks: usize,
+    pub completed: usize,
+    pub cancelled: usize,
+    /// Tasks archived with any other status, e.g. moved there by hand
+    pub other: usize,
+    /// Earliest and latest completion times; older versions left none
+    pub first_completed: Option<Timestamp>,
+    pub last_completed: Option<Timestamp>,
+    /// Size of the archive file
+    pub bytes: u64,
+}
+
+impl ArchiveStats {
+    fn count(&mut self, task: &Task) {
+        self.tasks += 1;
+        self.subtasks += task.subtasks.len();
+        match task.status {
+            Status::Completed => self.completed += 1,
+            Status::Cancelled => self.cancelled += 1,
+            _ => self.other += 1,
+        }
+        if let Some(at) = task.completed_at {
+            self.first_completed = Some(self.first_completed.map_or(at, |first| first.min(at)));
+            self.last_completed = Some(self.last_completed.map_or(at, |last| last.max(at)));
+        }
+    }
+}
+
+/// Where the task list and the files kept next to it live.
+/// The default is the usual file names in the current directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Store {
+    pub tasks: String,
+    pub archive: String,
+    pub trash: String,
+    pub log: String,
+    /// Turn down every write, leaving the files as they were found
+    pub read_only: bool,
+    /// Saving an empty list over a file with more tasks than this is turned
+    /// down, see [`Store::save`]; 0 turns the check off
+    pub wipe_guard: usize,
+    /// Save an empty list over a full file, or any list over one that could
+    /// not be read, all the same, after a backup
+    pub force: bool,
+    /// How the lists loaded from here check their tasks, and how files are
+    /// written
+    pub settings: Settings,
+    /// What the store has seen of its files, starting from nothing
+    pub seen: Seen,
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Store {
+            tasks: DATA_FILE.to_string(),
+            archive: ARCHIVE_FILE.to_string(),
+            trash: TRASH_FILE.to_string(),
+            log: LOG_FILE.to_string(),
+            read_only: false,
+            wipe_guard: DEFAULT_WIPE_GUARD,
+            force: false,
+            settings: Settings::default(),
+            seen: Seen::default(),
+        }
+    }
+}
+
+impl Store {
+    /// The usual file names inside another directory
+    pub fn in_dir(dir: &Path) -> Self {
+        let path = |name: &str| dir.join(name).to_string_lossy().into_owned();
+        Store {
+            tasks: path(DATA_FILE),
+            archive: path(ARCHIVE_FILE),
+            trash: path(TRASH_FILE),
+            log: path(LOG_FILE),
+            read_only: false,
+            wipe_guard: DEFAULT_WIPE_GUARD,
+            force: false,
+            settings: Settings::default(),
+            seen: Seen::default(),
+        }
+    }
+
+    /// The task file at `path`, with the archive, trash and history under
+    /// their usual names in the same directory
+    pub fn at(path: &str) -> Self {
+        let dir = Path::new(path).parent().unwrap_or(Path::new(""));
+        Store {
+            tasks: path.to_string(),
+            ..Store::in_dir(dir)
+        }
+    }
+
+    /// Whether the task file can be written. A file that does not exist yet
+    /// can be, as far as this knows; opening it to append changes nothing.
+    pub fn is_writable(&self) -> bool {
+        match fs::metadata(&self.tasks) {
+            Ok(metadata) => {
+                !metadata.permissions().readonly()
+                    && OpenOptions::new().append(true).open(&self.tasks).is_ok()
+            }
+            Err(_) => true,
+        }
+    }
+
+    // Every write checks here first
+    fn check_writable(&self) -> Result<(), TodoError> {
+        if self.read_only {
+            log!(Debug, "not writing {}: read-only", self.tasks);
+            return Err(TodoError::ReadOnly("save"));
+        }
+        Ok(())
+    }
+
+    /// Load the task list, without its history. A file that is there but
+    /// does not load is not saved over until it loads again or the user
+    /// says so, see [`Store::save`].
+    pub fn load(&self) -> Result<TodoList, TodoError> {
+        // Hashed first: a write landing in between then shows as a change
+        // at the next save rather than going unnoticed. A missing file is
+        // noted too, so one created meanwhile is not saved over, and so is
+        // one that fails to load, so one mended meanwhile is not either.
+        let version = hash(&self.tasks);
+        let loaded = TodoList::load(&self.tasks);
+        self.note_read(version);
+        let mut unreadable = self.seen.0.unreadable.borrow_mut();
+        if loaded.is_err() && version.is_some() {
+            unreadable.insert(self.tasks.clone(), false);
+        } else {
+            unreadable.remove(&self.tasks);
+        }
+        let list = self.with_settings(logged(&self.tasks, loaded)?);
+        log!(Debug, "loaded {} tasks from {}", list.len(), self.tasks);
+        Ok(list)
+    }
+
+    /// Read the saved history into a freshly loaded list. On failure the
+    /// list keeps an empty history.
+    pub fn load_history(&self, todo: &mut TodoList) -> Result<(), TodoError> {
+        let entries = logged(&self.log, history::load(&self.log))?;
+        log!(
+            Debug,
+            "loaded {} history entries from {}",
+            entries.len(),
+            self.log
+        );
+        todo.set_log(entries);
+        Ok(())
+    }
+
+    /// Save the tasks and then their history. A task file that was changed
+    /// by someone else since it was last read or written here is left as it
+    /// is, with [`TodoError::ConcurrentModification`], until the changes are
+    /// merged in or [`Store::mark_read`] gives them up. One that failed to
+    /// load is left as it is too, with [`TodoError::UnreadableTasks`],
+    /// unless forced or marked read, and then copied aside first.
+    pub fn save(&self, todo: &TodoList) -> Result<(), TodoError> {
+        self.check_writable()?;
+        self.check_unchanged()?;
+        self.check_readable()?;
+        self.check_wipe(todo)?;
+        log!(Debug, "saving {} tasks to {}", todo.len(), self.tasks);
+        logged(&self.tasks, todo.write(&self.tasks, &self.settings))?;
+        self.note_read(hash(&self.tasks));
+        self.seen.0.unreadable.borrow_mut().remove(&self.tasks);
+        log!(
+            Debug,
+            "saving {} history entries to {}",
+            todo.log().len(),
+            self.log
+        );
+        logged(
+            &self.log,
+            history::save(&self.log, todo.log(), &self.settings),
+        )?;
+        log!(Debug, "saved {}", self.tasks);
+        Ok(())
+    }
+
+    /// Save like [`Store::save`], letting an empty list replace a full file
+    /// once the user has agreed to it
+    pub fn save_anyway(&self, todo: &TodoList) -> Result<(), TodoError> {
+        let forced = Store {
+            force: true,
+            ..self.clone()
+        };
+        forced.save(todo)
+    }
+
+    /// Take the task file as it is now as the one the list was read from,
+    /// so the next save replaces whatever was changed in it since. Only for
+    /// when the user has chosen their list over those changes.
+    pub fn mark_read(&self) {
+        log!(Debug, "taking {} as it is now as read", self.tasks);
+        self.note_read(hash(&self.tasks));
+        if let Some(go_ahead) = self.seen.0.unreadable.borrow_mut().get_mut(&self.tasks) {
+            *go_ahead = true;
+        }
+    }
+
+    /// Whether the task file failed to load when last read, so that a save
+    /// over it needs a go-ahead and copies it to [`Store::unreadable_copy`]
+    pub fn is_unreadable(&self) -> bool {
+        self.seen.0.unreadable.borrow().contains_key(&self.tasks)
+    }
+
+    /// Whether the task file was changed by someone else since it was last
+    /// read or written
+    pub fn changed_since_read(&self) -> bool {
+        self.seen
+            .0
+            .versions
+            .borrow()
+            .get(&self.tasks)
+            .is_some_and(|&version| hash(&self.tasks) != version)
+    }
+
+    // Remember the task file as read or written just now
+    fn note_read(&self, version: Version) {
+        self.seen
+            .0
+            .versions
+            .borrow_mut()
+            .insert(self.tasks.clone(), version);
+    }
+
+    // Someone else's write is not replaced without a say
+    fn check_unchanged(&self) -> Result<(), TodoError> {
+        if !self.changed_since_read() {
+            return Ok(());
+        }
+        log!(
+            Debug,
+            "not saving {}: it changed since it was read",
+            self.tasks
+        );
+        Err(TodoError::ConcurrentModification(self.tasks.clone()))
+    }
+
+    /// Where the list goes when saving it would lose changes made to the
+    /// task file elsewhere
+    pub fn unsaved_copy(&self) -> String {
+        format!("{}{}", self.tasks, UNSAVED_SUFFIX)
+    }
+
+    /// Write the list to [`Store::unsaved_copy`], leaving the task file and
+    /// the history as they are, and give back where it went
+    pub fn save_aside(&self, todo: &TodoList) -> Result<String, TodoError> {
+        self.check_writable()?;
+        let path = self.unsaved_copy();
+        log!(Debug, "saving {} tasks aside to {}", todo.len(), path);
+        logged(&path, todo.write(&path, &self.settings))?;
+        Ok(path)
+    }
+
+    /// Where a task file that could not be read is copied before a list
+    /// replaces it
+    pub fn unreadable_copy(&self) -> String {
+        format!("{}{}", self.tasks, UNREADABLE_SUFFIX)
+    }
+
+    // A file that failed to load is only replaced when forced or marked
+    // read, and then it is copied aside first
+    fn check_readable(&self) -> Result<(), TodoError> {
+        let Some(go_ahead) = self.seen.0.unreadable.borrow().get(&self.tasks).copied() else {
+            return Ok(());
+        };
+        if !go_ahead && !self.force {
+            log!(Debug, "not saving {}: it could not be read", self.tasks);
+            return Err(TodoError::UnreadableTasks(self.tasks.clone()));
+        }
+        let copy = self.unreadable_copy();
+        log!(
+            Debug,
+            "copying {} to {} before replacing it",
+            self.tasks,
+            copy
+        );
+        logged(&copy, fs::copy(&self.tasks, &copy).map_err(TodoError::from))?;
+        Ok(())
+    }
+
+    /// Where the task file is copied before an empty list replaces it
+    pub fn wipe_backup(&self) -> String {
+        format!("{}{}", self.tasks, PRE_WIPE_SUFFIX)
+    }
+
+    /// Where everything is backed up before a backup is restored over it
+    pub fn restore_backup(&self) -> String {
+        format!("{}{}", self.tasks, PRE_RESTORE_SUFFIX)
+    }
+
+    // Files were replaced behind the cache's back, so the next read is
+    // from disk whatever their stamps say
+    pub(crate) fn forget_cached(&self) {
+        self.seen.0.archive.take();
+    }
+
+    /// How many tasks saving `todo` would wipe out: Some when the list is
+    /// empty and the file holds more than `wipe_guard` tasks. A file that
+    /// can't be read holds nothing to count here; saving over one is turned
+    /// down as [`TodoError::UnreadableTasks`] instead.
+    pub fn would_wipe(&self, todo: &TodoList) -> Option<usize> {
+        if !todo.is_empty() || self.wipe_guard == 0 {
+            return None;
+        }
+        let on_disk = read_list(&self.tasks).map_or(0, |list| list.len());
+        (on_disk > self.wipe_guard).then_some(on_disk)
+    }
+
+    // An empty list only replaces a full file when forced, and then the file
+    // is copied aside first
+    fn check_wipe(&self, todo: &TodoList) -> Result<(), TodoError> {
+        let Some(on_disk) = self.would_wipe(todo) else {
+            return Ok(());
+        };
+        if !self.force {
+            log!(
+                Debug,
+                "not emptying {}: it holds {} tasks",
+                self.tasks,
+                on_disk
+            );
+            return Err(TodoError::WouldWipe(on_disk));
+        }
+        let backup = self.wipe_backup();
+        log!(
+            Debug,
+            "copying {} to {} before emptying it",
+            self.tasks,
+            backup
+        );
+        logged(
+            &backup,
+            fs::copy(&self.tasks, &backup).map_err(TodoError::from),
+        )?;
+        Ok(())
+    }
+
+    /// The archived tasks; an archive that was never written is empty.
+    /// Only read when a command needs it, and then kept for the session
+    /// until the file changes.
+    pub fn load_archive(&self) -> Result<Rc<TodoList>, TodoError> {
+        if let Some(archive) = self.cached_archive() {
+            log!(Debug, "using {} as read before", self.archive);
+            return Ok(archive);
+        }
+        let archive = logged(&self.archive, load_or_empty(&self.archive))?;
+        let archive = Rc::new(self.with_settings(archive));
+        self.cache_archive(Rc::clone(&archive));
+        Ok(archive)
+    }
+
+    /// Count the archived tasks without keeping them: from the session's
+    /// copy when there is one, otherwise reading the file a task at a time
+    pub fn archive_stats(&self) -> Result<ArchiveStats, TodoError> {
+        let mut stats = ArchiveStats {
+            bytes: stamp(&self.archive).map_or(0, |(_, size)| size),
+            ..ArchiveStats::default()
+        };
+        if let Some(archive) = self.cached_archive() {
+            archive.tasks().iter().for_each(|task| stats.count(task));
+            return Ok(stats);
+        }
+        let read = for_each_saved_task(&self.archive, |task| stats.count(&task));
+        match read {
+            Err(TodoError::FileError(error)) if error.kind() == io::ErrorKind::NotFound => {
+                log!(Debug, "{} does not exist yet", self.archive);
+            }
+            read => logged(&self.archive, read)?,
+        }
+        Ok(stats)
+    }
+
+    /// Add tasks to the end of the archive
+    pub fn archive(&self, tasks: Vec<Task>) -> Result<(), TodoError> {
+        self.check_writable()?;
+        let archive = self.load_archive()?;
+        // Dropping the cached copy leaves this one the only one, to change
+        // in place rather than copy
+        self.seen.0.archive.take();
+        let mut archive = Rc::unwrap_or_clone(archive);
+        log!(Debug, "archiving {} tasks to {}", tasks.len(), self.archive);
+        archive.append(tasks);
+        logged(&self.archive, archive.write(&self.archive, &self.settings))?;
+        self.cache_archive(Rc::new(archive));
+        Ok(())
+    }
+
+    // The archive as read or written earlier in the session, unless the file
+    // was changed since
+    fn cached_archive(&self) -> Option<Rc<TodoList>> {
+        match &*self.seen.0.archive.borrow() {
+            Some((path, stamp_then, archive))
+                if *path == self.archive && *stamp_then == stamp(path) =>
+            {
+                Some(Rc::clone(archive))
+            }
+            _ => None,
+        }
+    }
+
+    fn cache_archive(&self, archive: Rc<TodoList>) {
+        let stamp = stamp(&self.archive);
+        self.seen
+            .0
+            .archive
+            .replace(Some((self.archive.clone(), stamp, archive)));
+    }
+
+    /// The removed tasks, oldest first
+    pub fn load_trash(&self) -> Result<TodoList, TodoError> {
+        logged(&self.trash, load_or_empty(&self.trash)).map(|trash| self.with_settings(trash))
+    }
+
+    /// An empty list, checking its tasks by the store's settings, for when
+    /// there is no task file yet
+    pub fn new_list(&self) -> TodoList {
+        self.with_settings(TodoList::new())
+    }
+
+    // A list read from one of the store's files, taking its settings
+    fn with_settings(&self, mut list: TodoList) -> TodoList {
+        list.set_settings(self.settings);
+        list
+    }
+
+    /// Replace the trash, e.g. after taking a task out of it
+    pub fn save_trash(&self, trash: &TodoList) -> Result<(), TodoError> {
+        self.check_writable()?;
+        log!(Debug, "saving {} tasks to {}", trash.len(), self.trash);
+        logged(&self.trash, trash.write(&self.trash, &self.settings))
+    }
+
+    /// Keep removed tasks in the trash, returning the number of the last one
+    /// there. Only the newest [`TRASH_LIMIT`] are kept.
+    pub fn move_to_trash(&self, tasks: Vec<Task>) -> Result<usize, TodoError> {
+        let mut trash = self.load_trash()?;
+        trash.append(tasks);
+        trash.keep_newest(TRASH_LIMIT);
+        self.save_trash(&trash)?;
+        Ok(trash.len())
+    }
+}
+
+/// Notices when a file is changed by someone other than this program, such
+/// as a sync client or an editor, by polling its modification time and size.
+/// Only when those differ is the file read, to tell a real change from a
+/// touch or from this program's own save.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Watch {
+    path: String,
+    // Modification time and size when last marked
+    stamp: Stamp,
+    // Hash of the contents when last marked
+    hash: Option<u64>,
+}
+
+impl Watch {
+    /// Watch the file at `path`, as it is now
+    pub fn new(path: &str) -> Self {
+        let mut watch = Watch {
+            path: path.to_string(),
+            stamp: None,
+            hash: None,
+        };
+        watch.mark();
+        watch
+    }
+
+    /// Take the file as it is now as the latest known version, after it was
+    /// loaded or saved, or after deciding to keep the list as it is
+    pub fn mark(&mut self) {
+        self.stamp = stamp(&self.path);
+        self.hash = hash(&self.path);
+    }
+
+    /// Whether the file has changed since it was last marked. A file whose
+    /// contents are the same as before only has its new time noted.
+    pub fn changed(&mut self) -> bool {
+        let stamp = stamp(&self.path);
+        if stamp == self.stamp {
+            return false;
+        }
+        if hash(&self.path) != self.hash {
+            log!(Debug, "{} changed on disk", self.path);
+            return true;
+        }
+        self.stamp = stamp;
+        false
+    }
+}
+
+fn stamp(path: &str) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+fn hash(path: &str) -> Version {
+    let contents = fs::read(path).ok()?;
+    let mut hasher = DefaultHasher::new();
+    contents.hash(&mut hasher);
+    Some(hasher.finish())
+}
+
+// Log a failure at `path` with its causes before handing it back. A missing
+// file is usual on a first run, so it is only noted.
+fn logged<T>(path: &str, result: Result<T, TodoError>) -> Result<T, TodoError> {
+    match &result {
+        Err(TodoError::FileError(error)) if error.kind() == io::ErrorKind::NotFound => {
+            log!(Debug, "{} does not exist yet", path);
+        }
+        Err(error) => {
+            log!(Error, "{} failed", path);
+            log_error!(error);
+        }
+        Ok(_) => {}
+    }
+    result
+}
+
+/// Load a side file such as the archive, treating a missing file as empty
+pub fn load_or_empty(path: &str) -> Result<TodoList, TodoError> {
+    match TodoList::load(path) {
+        Err(TodoError::FileError(error)) if error.kind() == io::ErrorKind::NotFound => {
+            Ok(TodoList::new())
+        }
+        result => result,
+    }
+}
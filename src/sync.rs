@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+
+use rust_todo_cli::{
+    msg,
+    store::{Store, Watch},
+    todo::{TodoError, TodoList},
+};
+
+use crate::{
+    handlers::{load_history, save_checked, warn_too_long, warn_unknown_statuses},
+    output::{Icon, info, warning},
+    prompt::{choose, confirm},
+};
+
+// Keeps the session's list in step with the data file, which a sync client
+// or another machine may change while the session runs. The file is checked
+// between commands, so nothing changes under a command while it runs.
+pub struct FileSync {
+    watch: Watch,
+    // The list as last read or written, to tell unsaved changes by and to
+    // merge from
+    base: TodoList,
+    // IDs `renumber` changed since then, as (old, new) pairs; the file still
+    // has the old ones
+    renumbered: Vec<(u64, u64)>,
+}
+
+impl FileSync {
+    pub fn new(store: &Store, todo: &TodoList) -> Self {
+        FileSync {
+            watch: Watch::new(&store.tasks),
+            base: todo.clone(),
+            renumbered: Vec::new(),
+        }
+    }
+
+    // Note that the list was just saved, so the write is not taken for a
+    // change made elsewhere
+    pub fn saved(&mut self, todo: &TodoList) {
+        self.watch.mark();
+        self.base = todo.clone();
+        self.renumbered.clear();
+    }
+
+    // Note IDs `renumber` changed, on top of any changed before the file was
+    // next written, so a merge can match the file's tasks to them
+    pub fn renumbered(&mut self, renumbered: &[(u64, u64)]) {
+        let now: HashMap<u64, u64> = renumbered.iter().copied().collect();
+        let earlier: HashSet<u64> = self.renumbered.iter().map(|&(_, new)| new).collect();
+        for (_, new) in &mut self.renumbered {
+            *new = now.get(new).copied().unwrap_or(*new);
+        }
+        self.renumbered
+            .extend(renumbered.iter().filter(|(old, _)| !earlier.contains(old)));
+        self.renumbered.retain(|(old, new)| old != new);
+    }
+
+    // Whether the list was changed since the file was last read or written
+    pub fn has_unsaved_changes(&self, todo: &TodoList) -> bool {
+        !todo.saves_as(&self.base) || !self.renumbered.is_empty()
+    }
+
+    // Pick up changes made to the file since it was last read or written:
+    // by themselves when nothing here is unsaved, otherwise as the user says
+    pub fn check(&mut self, todo: &mut TodoList, store: &Store) {
+        if !self.watch.changed() {
+            return;
+        }
+        if !self.has_unsaved_changes(todo) {
+            info!(
+                "{} {}",
+                Icon::Reload,
+                msg!("file_sync.changed", path = store.tasks)
+            );
+            self.reload(todo, store);
+            return;
+        }
+        let question = msg!("file_sync.changed_unsaved", path = store.tasks);
+        match choose(&question, &["reload", "keep", "merge"]) {
+            Some(0) => self.reload(todo, store),
+            Some(1) => {
+                self.watch.mark();
+                store.mark_read();
+                info!("{} {}", Icon::Hint, msg!("file_sync.kept"));
+            }
+            Some(2) => {
+                if self.merge(todo, store) {
+                    info!("{} {}", Icon::Hint, msg!("file_sync.save_hint"));
+                }
+            }
+            // Not asked again until the file changes once more, but saving
+            // over it still needs a say
+            _ => self.watch.mark(),
+        }
+    }
+
+    // Save the list. When the file was changed elsewhere since it was read,
+    // ask once whether to merge those changes in first, replace them or
+    // leave the file as it is; with `force` they are replaced.
+    pub fn save(
+        &mut self,
+        todo: &mut TodoList,
+        store: &Store,
+        force: bool,
+    ) -> Result<(), TodoError> {
+        if force {
+            store.mark_read();
+        }
+        let saved = match save_checked(todo, store) {
+            Err(TodoError::ConcurrentModification(path)) => {
+                let question = msg!("file_sync.changed_before_save", path = path);
+                match choose(&question, &["merge", "overwrite", "cancel"]) {
+                    Some(0) if self.merge(todo, store) => save_checked(todo, store),
+                    Some(1) => {
+                        store.mark_read();
+                        save_checked(todo, store)
+                    }
+                    _ => Err(TodoError::ConcurrentModification(path)),
+                }
+            }
+            result => result,
+        };
+        if saved.is_ok() {
+            self.saved(todo);
+        }
+        saved
+    }
+
+    // The `reload` command, which asks before dropping unsaved changes
+    pub fn handle_reload(&mut self, todo: &mut TodoList, store: &Store, yes: bool) {
+        if !yes && self.has_unsaved_changes(todo) && !confirm(&msg!("file_sync.ask_reload")) {
+            return;
+        }
+        self.reload(todo, store);
+    }
+
+    // Read the file again in place of the list
+    fn reload(&mut self, todo: &mut TodoList, store: &Store) {
+        self.watch.mark();
+        match store.load() {
+            Ok(mut list) => {
+                load_history(&mut list, store);
+                warn_too_long(&list);
+                warn_unknown_statuses(&list);
+                info!(
+                    "{} {}",
+                    Icon::Reload,
+                    msg!("file_sync.reloaded", summary = list.summary())
+                );
+                self.base = list.clone();
+                self.renumbered.clear();
+                *todo = list;
+            }
+            Err(error) => warning!(
+                "{}  {}",
+                Icon::Warning,
+                msg!("file_sync.reload_failed", error = error)
+            ),
+        }
+    }
+
+    // Bring the changes in the file into the list, keeping those made here,
+    // returning whether that worked
+    fn merge(&mut self, todo: &mut TodoList, store: &Store) -> bool {
+        self.watch.mark();
+        match store.load() {
+            Ok(mut theirs) => {
+                warn_unknown_statuses(&theirs);
+                let mut base = std::mem::replace(&mut self.base, theirs.clone());
+                // Tasks renumbered here are matched under their new IDs
+                base.rename_ids(&self.renumbered);
+                theirs.rename_ids(&self.renumbered);
+                todo.merge(&base, theirs);
+                info!(
+                    "{} {}",
+                    Icon::Reload,
+                    msg!("file_sync.merged", summary = todo.summary())
+                );
+                true
+            }
+            Err(error) => {
+                warning!(
+                    "{}  {}",
+                    Icon::Warning,
+                    msg!("file_sync.merge_failed", error = error)
+                );
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use rust_todo_cli::width::{display_width, pad_left, pad_right, truncate, wrap};
+
+// Gap between two columns
+const COLUMN_GAP: &str = "  ";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub header: String,
+    pub align: Align,
+    // A flexible column absorbs the remaining width and is truncated to fit
+    pub flexible: bool,
+}
+
+impl Column {
+    pub fn left(header: &str) -> Self {
+        Column {
+            header: header.to_string(),
+            align: Align::Left,
+            flexible: false,
+        }
+    }
+
+    pub fn right(header: &str) -> Self {
+        Column {
+            header: header.to_string(),
+            align: Align::Right,
+            flexible: false,
+        }
+    }
+
+    pub fn flexible(header: &str) -> Self {
+        Column {
+            header: header.to_string(),
+            align: Align::Left,
+            flexible: true,
+        }
+    }
+}
+
+// Aligned table with a header row, rendered to lines no wider than the terminal
+#[derive(Debug, Clone)]
+pub struct Table {
+    columns: Vec<Column>,
+    rows: Vec<Vec<String>>,
+    // Whether flexible cells too long for their column go on further lines
+    // instead of being cut short
+    wrap: bool,
+}
+
+impl Table {
+    pub fn new(columns: Vec<Column>) -> Self {
+        Table {
+            columns,
+            rows: Vec::new(),
+            wrap: false,
+        }
+    }
+
+    pub fn set_wrap(&mut self, wrap: bool) {
+        self.wrap = wrap;
+    }
+
+    pub fn add_row(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    // Widest cell of each column, header included
+    fn natural_widths(&self) -> Vec<usize> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                self.rows
+                    .iter()
+                    .filter_map(|row| row.get(i))
+                    .map(|cell| display_width(cell))
+                    .fold(display_width(&column.header), usize::max)
+            })
+            .collect()
+    }
+
+    // Render the header followed by every row, fitting into `max_width` cells.
+    // A row is one line, or more when wrapping. The columns never shrink below
+    // their headers, so very narrow terminals get lines that overflow instead.
+    pub fn render(&self, max_width: usize) -> Vec<Vec<String>> {
+        let mut widths = self.natural_widths();
+
+        let gaps = display_width(COLUMN_GAP) * self.columns.len().saturating_sub(1);
+        let fixed: usize = self
+            .columns
+            .iter()
+            .zip(&widths)
+            .filter(|(column, _)| !column.flexible)
+            .map(|(_, width)| *width)
+            .sum();
+        let available = max_width.saturating_sub(fixed + gaps);
+        for (column, width) in self.columns.iter().zip(widths.iter_mut()) {
+            if column.flexible {
+                *width = (*width).min(available.max(display_width(&column.header)));
+            }
+        }
+
+        let header: Vec<String> = self.columns.iter().map(|c| c.header.clone()).collect();
+        std::iter::once(&header)
+            .chain(&self.rows)
+            .map(|row| self.render_row(row, &widths))
+            .collect()
+    }
+
+    fn render_row(&self, row: &[String], widths: &[usize]) -> Vec<String> {
+        let cells: Vec<Vec<String>> = self
+            .columns
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(i, (column, &width))| {
+                let cell = row.get(i).map(String::as_str).unwrap_or("");
+                if column.flexible && self.wrap {
+                    wrap(cell, width)
+                } else {
+                    vec![truncate(cell, width)]
+                }
+            })
+            .collect();
+        let height = cells.iter().map(Vec::len).max().unwrap_or(1);
+
+        // Continuation lines leave the other columns blank
+        (0..height)
+            .map(|line| {
+                let parts: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(widths)
+                    .zip(&cells)
+                    .map(|((column, &width), cell)| {
+                        let text = cell.get(line).map(String::as_str).unwrap_or("");
+                        match column.align {
+                            Align::Left => pad_right(text, width),
+                            Align::Right => pad_left(text, width),
+                        }
+                    })
+                    .collect();
+                parts.join(COLUMN_GAP).trim_end().to_string()
+            })
+            .collect()
+    }
+}
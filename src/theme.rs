@@ -0,0 +1,309 @@
+use std::fs;
+use std::str::FromStr;
+
+use crate::msg;
+use crate::todo::{Status, TodoError};
+
+/// A text color for task rows, written as an ANSI code
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    /// The terminal's own color
+    Plain,
+    Gray,
+    Red,
+    Green,
+    DimGreen,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+}
+
+impl Color {
+    /// The names a theme file can use, as `help` and warnings list them
+    pub const NAMES: &'static str =
+        "plain, gray, red, green, dim-green, yellow, blue, magenta, cyan, white";
+
+    /// The name a theme file or `mark` would use for the color
+    pub fn name(self) -> &'static str {
+        match self {
+            Color::Plain => "plain",
+            Color::Gray => "gray",
+            Color::Red => "red",
+            Color::Green => "green",
+            Color::DimGreen => "dim-green",
+            Color::Yellow => "yellow",
+            Color::Blue => "blue",
+            Color::Magenta => "magenta",
+            Color::Cyan => "cyan",
+            Color::White => "white",
+        }
+    }
+
+    /// The SGR parameters selecting the color
+    pub fn code(self) -> &'static str {
+        match self {
+            Color::Plain => "0",
+            Color::Gray => "90",
+            Color::Red => "31",
+            Color::Green => "32",
+            Color::DimGreen => "2;32",
+            Color::Yellow => "33",
+            Color::Blue => "34",
+            Color::Magenta => "35",
+            Color::Cyan => "36",
+            Color::White => "37",
+        }
+    }
+}
+
+/// Any case, with `-`, `_` or a space allowed between words: `dim-green`
+impl FromStr for Color {
+    type Err = ();
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let name: String = text
+            .chars()
+            .filter(|c| !matches!(c, '-' | '_' | ' '))
+            .collect::<String>()
+            .to_lowercase();
+        match name.as_str() {
+            "plain" | "none" => Ok(Color::Plain),
+            "gray" | "grey" => Ok(Color::Gray),
+            "red" => Ok(Color::Red),
+            "green" => Ok(Color::Green),
+            "dimgreen" => Ok(Color::DimGreen),
+            "yellow" => Ok(Color::Yellow),
+            "blue" => Ok(Color::Blue),
+            "magenta" => Ok(Color::Magenta),
+            "cyan" => Ok(Color::Cyan),
+            "white" => Ok(Color::White),
+            _ => Err(()),
+        }
+    }
+}
+
+/// How listings look: the marker in front of each status, the markers after
+/// a description, the rules around a listing and the colors of its rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub todo: String,
+    pub waiting: String,
+    pub in_progress: String,
+    pub completed: String,
+    pub cancelled: String,
+    /// After a task waiting on unfinished dependencies
+    pub blocked: String,
+    /// After a task left in todo past the aging limit
+    pub stale: String,
+    /// After a task with a link in its description
+    pub link: String,
+    /// Repeated into the rule above and below a listing, none when empty
+    pub separator: String,
+    /// Repeated into the rule under the startup banner, none when empty
+    pub banner: String,
+    pub todo_color: Color,
+    pub waiting_color: Color,
+    pub in_progress_color: Color,
+    pub completed_color: Color,
+    pub cancelled_color: Color,
+    /// Rows of open tasks older than `--stale-days`
+    pub stale_color: Color,
+    /// Whether the icons of messages and the progress bars may use emoji and
+    /// block characters, rather than ASCII as in plain mode
+    pub emoji: bool,
+}
+
+/// The emoji look the app has always had
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            todo: "⚪".to_string(),
+            waiting: "⏳".to_string(),
+            in_progress: "🔵".to_string(),
+            completed: "✅".to_string(),
+            cancelled: "🚫".to_string(),
+            blocked: "🔒".to_string(),
+            stale: "⚠️".to_string(),
+            link: "🔗".to_string(),
+            separator: "─".to_string(),
+            banner: "-".to_string(),
+            todo_color: Color::Gray,
+            waiting_color: Color::Blue,
+            in_progress_color: Color::Yellow,
+            completed_color: Color::DimGreen,
+            cancelled_color: Color::Gray,
+            stale_color: Color::Red,
+            emoji: true,
+        }
+    }
+}
+
+impl Theme {
+    /// Names of the built-in themes
+    pub const PRESETS: [&'static str; 2] = ["default", "ascii"];
+
+    /// Plain ASCII markers for fonts without emoji, as plain mode uses
+    pub fn ascii() -> Self {
+        Theme {
+            todo: "[ ]".to_string(),
+            waiting: "[w]".to_string(),
+            in_progress: "[~]".to_string(),
+            completed: "[x]".to_string(),
+            cancelled: "[-]".to_string(),
+            blocked: "[blocked]".to_string(),
+            stale: "[stale]".to_string(),
+            link: "[link]".to_string(),
+            separator: "-".to_string(),
+            banner: "-".to_string(),
+            emoji: false,
+            ..Theme::default()
+        }
+    }
+
+    /// A built-in theme by name
+    pub fn preset(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "default" => Some(Theme::default()),
+            "ascii" => Some(Theme::ascii()),
+            _ => None,
+        }
+    }
+
+    /// Marker in front of a task with this status
+    pub fn status(&self, status: Status) -> &str {
+        match status {
+            Status::Todo => &self.todo,
+            Status::Waiting => &self.waiting,
+            Status::InProgress => &self.in_progress,
+            Status::Completed => &self.completed,
+            Status::Cancelled => &self.cancelled,
+        }
+    }
+
+    /// Color of the row of a task with this status
+    pub fn color(&self, status: Status) -> Color {
+        match status {
+            Status::Todo => self.todo_color,
+            Status::Waiting => self.waiting_color,
+            Status::InProgress => self.in_progress_color,
+            Status::Completed => self.completed_color,
+            Status::Cancelled => self.cancelled_color,
+        }
+    }
+
+    /// Read the `[theme]` section of a file, see [`Theme::parse`]
+    pub fn load(path: &str) -> Result<(Self, Vec<String>), TodoError> {
+        Ok(Theme::parse(&fs::read_to_string(path)?))
+    }
+
+    /// A theme from the `key = value` lines of a `[theme]` section, with a
+    /// warning for each line that could not be used. Values may be quoted.
+    /// `preset = "ascii"` starts from another built-in theme; settings that
+    /// are missing or invalid keep the value of the preset. Other sections
+    /// and lines starting with `#` or `;` are skipped.
+    pub fn parse(text: &str) -> (Self, Vec<String>) {
+        let mut settings = Vec::new();
+        let mut warnings = Vec::new();
+        let mut in_theme = false;
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if line.starts_with('[') && line.ends_with(']') {
+                in_theme = line[1..line.len() - 1].trim() == "theme";
+                continue;
+            }
+            if !in_theme {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) => settings.push((number + 1, key.trim(), unquote(value))),
+                None => warnings.push(msg!("theme.invalid_line", line = number + 1)),
+            }
+        }
+
+        let mut theme = Theme::default();
+        for (_, _, value) in settings.iter().filter(|(_, key, _)| *key == "preset") {
+            match Theme::preset(value) {
+                Some(preset) => theme = preset,
+                None => warnings.push(msg!(
+                    "theme.unknown_preset",
+                    name = value,
+                    presets = Theme::PRESETS.join(", ")
+                )),
+            }
+        }
+        for (line, key, value) in settings {
+            if key == "preset" {
+                continue;
+            }
+            if let Some(icon) = theme.icon_mut(key) {
+                // Rules may be empty to leave them out, markers may not
+                if value.is_empty() && !matches!(key, "separator" | "banner") {
+                    warnings.push(msg!("theme.empty_icon", key = key, default = icon));
+                } else {
+                    *icon = value.to_string();
+                }
+            } else if key == "emoji" {
+                match value {
+                    "true" => theme.emoji = true,
+                    "false" => theme.emoji = false,
+                    _ => warnings.push(msg!("theme.not_boolean", key = key, default = theme.emoji)),
+                }
+            } else if let Some(color) = theme.color_mut(key) {
+                match value.parse() {
+                    Ok(parsed) => *color = parsed,
+                    Err(()) => warnings.push(msg!(
+                        "theme.unknown_color",
+                        value = value,
+                        key = key,
+                        colors = Color::NAMES
+                    )),
+                }
+            } else {
+                warnings.push(msg!("theme.unknown_key", key = key, line = line));
+            }
+        }
+        (theme, warnings)
+    }
+
+    fn icon_mut(&mut self, key: &str) -> Option<&mut String> {
+        match key {
+            "todo" => Some(&mut self.todo),
+            "waiting" => Some(&mut self.waiting),
+            "in_progress" => Some(&mut self.in_progress),
+            "completed" => Some(&mut self.completed),
+            "cancelled" => Some(&mut self.cancelled),
+            "blocked" => Some(&mut self.blocked),
+            "stale" => Some(&mut self.stale),
+            "link" => Some(&mut self.link),
+            "separator" => Some(&mut self.separator),
+            "banner" => Some(&mut self.banner),
+            _ => None,
+        }
+    }
+
+    fn color_mut(&mut self, key: &str) -> Option<&mut Color> {
+        match key {
+            "todo_color" => Some(&mut self.todo_color),
+            "waiting_color" => Some(&mut self.waiting_color),
+            "in_progress_color" => Some(&mut self.in_progress_color),
+            "completed_color" => Some(&mut self.completed_color),
+            "cancelled_color" => Some(&mut self.cancelled_color),
+            "stale_color" => Some(&mut self.stale_color),
+            _ => None,
+        }
+    }
+}
+
+// A value without the quotes around it, if it has them
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    value
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(value)
+}
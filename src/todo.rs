@@ -1,203 +1,4088 @@
-use serde::{Deserialize, Serialize};
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Value, json};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Display;
-use std::fs;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::ops::{Index, Range};
+use std::str::FromStr;
+use std::time::Duration;
 use thiserror::Error;
 
+use crate::date::{
+    Date, SECONDS_PER_DAY, Time, Timestamp, due_later, format_due, format_duration, now,
+    parse_duration,
+};
+use crate::doctor::{self, Finding};
+use crate::history::LogEntry;
+use crate::import::ImportSummary;
+use crate::log;
+use crate::merge::MergeReport;
+use crate::msg;
+use crate::random::Rng;
+use crate::sanitize::{sanitize, sanitize_line};
+use crate::settings::Settings;
+use crate::snippet;
+use crate::theme::Color;
+use crate::width::{clusters, edit_distance, typo_limit};
+
+/// Something that can be written to and read back from a JSON file
 pub trait Storable {
+    /// Write to `path`, replacing it only once the new copy is complete
     fn save(&self, path: &str) -> Result<(), TodoError>;
+    /// Read back what `save` wrote
     fn load(path: &str) -> Result<Self, TodoError>
     where
         Self: Sized;
 }
 
-// Improved error handling using thiserror
+/// Everything that can go wrong with a list, its files or what is typed into it
 #[derive(Error, Debug)]
 pub enum TodoError {
-    #[error("Index must start from 1")]
+    #[error("{}", msg!("error.invalid_index"))]
     InvalidIndex,
 
-    #[error("Status {0} not recognized. Use: todo, in-progress, done")]
+    #[error("{}", invalid_status(.0))]
     InvalidStatus(String),
 
-    #[error("No task exists at that index {0}")]
+    /// Strict transitions don't allow this change of status without --force
+    #[error("{}", invalid_transition(*.from, *.to))]
+    InvalidTransition { from: Status, to: Status },
+
+    #[error("{}", msg!("error.index_out_of_bound", index = .0))]
     IndexOutOfBound(usize),
 
-    #[error("Task description cannot be empty")]
+    #[error("{}", msg!("error.empty_description"))]
     EmptyDescription,
 
-    #[error("Failed to serialize tasks: {0}")]
+    #[error("{}", msg!("error.description_too_long", len = .len, max = .max))]
+    DescriptionTooLong { len: usize, max: usize },
+
+    #[error("{}", msg!("error.line_too_long", len = .len, max = .max, start = .start))]
+    LineTooLong {
+        len: usize,
+        max: usize,
+        /// How the line starts, sanitized
+        start: String,
+    },
+
+    #[error("{}", msg!("error.invalid_date", date = .0))]
+    InvalidDate(String),
+
+    #[error("{}", msg!("error.invalid_duration", duration = .0))]
+    InvalidDuration(String),
+
+    #[error("{}", msg!("error.invalid_estimate", estimate = .0))]
+    InvalidEstimate(String),
+
+    #[error("{}", msg!("error.task_gone", index = .0))]
+    TaskGone(usize),
+
+    #[error("{}", msg!("error.not_in_view", index = .0))]
+    NotInView(usize),
+
+    #[error("{}", msg!("error.unknown_id", id = .0))]
+    UnknownId(u64),
+
+    #[error("{}", msg!("error.subtask_out_of_bound", task = .0, sub = .1))]
+    SubtaskOutOfBound(usize, usize),
+
+    #[error("{}", msg!("error.no_match", text = .0))]
+    NoMatch(String),
+
+    #[error("{}", msg!("error.ambiguous_match", text = .0, count = .1))]
+    AmbiguousMatch(String, usize),
+
+    #[error("{}", msg!("error.subtask_not_allowed", index = .0))]
+    SubtaskNotAllowed(TaskIndex),
+
+    #[error("{}", msg!("error.dependency_cycle", task = .0, on = .1))]
+    DependencyCycle(usize, usize),
+
+    #[error("{}", msg!("error.already_completed", index = .0))]
+    AlreadyCompleted(usize),
+
+    /// Only a completed task can be reopened; this one has another status
+    #[error("{}", msg!("error.not_completed", index = .0, status = .1.keyword()))]
+    NotCompleted(TaskIndex, Status),
+
+    /// `reopen last` with no completed task to go back to
+    #[error("{}", msg!("error.nothing_completed"))]
+    NothingCompleted,
+
+    #[error("{}", msg!("error.invalid_interval", interval = .0))]
+    InvalidInterval(String),
+
+    #[error("{}", msg!("error.invalid_tag", tag = .0))]
+    InvalidTag(String),
+
+    #[error("{}", msg!("error.invalid_person", person = .0))]
+    InvalidPerson(String),
+
+    /// The reason is the key of its message
+    #[error(
+        "{}",
+        msg!("error.conflicting_options", options = crate::messages::message(*.0, &[]))
+    )]
+    ConflictingOptions(&'static str),
+
+    #[error("{}", msg!("error.no_link", index = .0))]
+    NoLink(TaskIndex),
+
+    #[error("{}", msg!("error.link_out_of_bound", index = .0, count = .1))]
+    LinkOutOfBound(TaskIndex, usize),
+
+    #[error("{}", msg!("error.invalid_marker", marker = .0))]
+    InvalidMarker(String),
+
+    #[error("{}", msg!("error.unknown_color", color = .0, colors = Color::NAMES))]
+    UnknownColor(String),
+
+    #[error("{}", msg!("error.invalid_template_name", name = .0))]
+    InvalidTemplateName(String),
+
+    #[error("{}", msg!("error.unknown_template", name = .0))]
+    UnknownTemplate(String),
+
+    #[error("{}", msg!("error.invalid_filter_name", name = .0))]
+    InvalidFilterName(String),
+
+    /// A filter name `list` would read as one of its own words, such as a status
+    #[error("{}", msg!("error.reserved_filter_name", name = .0))]
+    ReservedFilterName(String),
+
+    #[error("{}", msg!("error.unknown_saved_filter", name = .0))]
+    UnknownSavedFilter(String),
+
+    #[error("{}", msg!("error.invalid_snippet_name", name = .0))]
+    InvalidSnippetName(String),
+
+    #[error("{}", msg!("error.unknown_snippet", name = .0))]
+    UnknownSnippet(String),
+
+    /// Snippet text with a brace that neither doubles nor encloses a
+    /// placeholder's name
+    #[error("{}", msg!("error.invalid_snippet", text = .0))]
+    InvalidSnippet(String),
+
+    /// Placeholders of a snippet left without a value, with all it takes
+    #[error("{}", msg!("error.missing_placeholders", count = .missing.len(), missing = .missing.join(", "), needed = needed_placeholders(.needed)))]
+    MissingPlaceholders {
+        missing: Vec<String>,
+        needed: Vec<String>,
+    },
+
+    /// A value given for a placeholder the snippet does not have
+    #[error("{}", msg!("error.unknown_placeholder", key = .key, needed = needed_placeholders(.needed)))]
+    UnknownPlaceholder { key: String, needed: Vec<String> },
+
+    /// A saved filter that no longer parses, as when the file was edited
+    /// by hand, with why
+    #[error("{}", msg!("error.invalid_saved_filter", name = .0, error = .1))]
+    InvalidSavedFilter(String, String),
+
+    /// Renumbering would point references at the wrong tasks: this many
+    /// tasks share an ID or depend on one no task has
+    #[error("{}", msg!("error.renumber_blocked", count = .0))]
+    RenumberBlocked(usize),
+
+    #[error("{}", msg!("error.serialization", error = .0))]
     SerializationError(#[from] serde_json::Error),
 
-    #[error("Failed to access file: {0}")]
+    #[error("{}", msg!("error.file", error = .0))]
     FileError(#[from] std::io::Error),
+
+    #[error("{}", msg!("error.sync", error = .0))]
+    SyncError(String),
+
+    #[error("{}", msg!("error.clipboard", error = .0))]
+    ClipboardError(String),
+
+    /// A task was to be picked from a menu with no one there to answer it
+    #[error("{}", msg!("error.not_interactive"))]
+    NotInteractive,
+
+    /// A command would have written the files of a read-only session
+    #[error("{}", msg!("error.read_only", command = .0))]
+    ReadOnly(&'static str),
+
+    /// Saving would have replaced a file holding this many tasks with an
+    /// empty list, without a go-ahead
+    #[error("{}", msg!("error.would_wipe", count = .0))]
+    WouldWipe(usize),
+
+    /// The task file at this path was changed by someone else since it was
+    /// read, so saving would have lost their changes
+    #[error("{}", msg!("error.concurrent_modification", path = .0))]
+    ConcurrentModification(String),
+
+    /// The task file at this path could not be read, so saving would have
+    /// replaced what is in it unseen
+    #[error("{}", msg!("error.unreadable_tasks", path = .0))]
+    UnreadableTasks(String),
+
+    /// The file at this path is not a backup bundle
+    #[error("{}", msg!("error.not_a_backup", path = .0))]
+    NotABackup(String),
+
+    /// A backup bundle in a format version newer than this one reads
+    #[error("{}", msg!("error.backup_too_new", version = .version, supported = .supported))]
+    BackupTooNew { version: u32, supported: u32 },
+
+    /// A section of a backup bundle that would not read back as its file
+    #[error("{}", msg!("error.bad_backup_section", section = .section, error = .error))]
+    BadBackupSection { section: String, error: String },
+
+    /// A command was run through [`crate::outcome::execute`] that needs a
+    /// terminal, the task files or the session of the CLI
+    #[error("{}", msg!("error.cli_only", command = .0))]
+    CliOnly(&'static str),
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+impl TodoError {
+    /// A name for the kind of error that stays the same across versions and
+    /// languages, for scripts to match on
+    pub fn code(&self) -> &'static str {
+        match self {
+            TodoError::InvalidIndex => "InvalidIndex",
+            TodoError::InvalidStatus(_) => "InvalidStatus",
+            TodoError::InvalidTransition { .. } => "InvalidTransition",
+            TodoError::IndexOutOfBound(_) => "IndexOutOfBound",
+            TodoError::EmptyDescription => "EmptyDescription",
+            TodoError::DescriptionTooLong { .. } => "DescriptionTooLong",
+            TodoError::LineTooLong { .. } => "LineTooLong",
+            TodoError::InvalidDate(_) => "InvalidDate",
+            TodoError::InvalidDuration(_) => "InvalidDuration",
+            TodoError::InvalidEstimate(_) => "InvalidEstimate",
+            TodoError::TaskGone(_) => "TaskGone",
+            TodoError::NotInView(_) => "NotInView",
+            TodoError::UnknownId(_) => "UnknownId",
+            TodoError::SubtaskOutOfBound(..) => "SubtaskOutOfBound",
+            TodoError::NoMatch(_) => "NoMatch",
+            TodoError::AmbiguousMatch(..) => "AmbiguousMatch",
+            TodoError::SubtaskNotAllowed(_) => "SubtaskNotAllowed",
+            TodoError::DependencyCycle(..) => "DependencyCycle",
+            TodoError::AlreadyCompleted(_) => "AlreadyCompleted",
+            TodoError::NotCompleted(..) => "NotCompleted",
+            TodoError::NothingCompleted => "NothingCompleted",
+            TodoError::InvalidInterval(_) => "InvalidInterval",
+            TodoError::InvalidTag(_) => "InvalidTag",
+            TodoError::InvalidPerson(_) => "InvalidPerson",
+            TodoError::ConflictingOptions(_) => "ConflictingOptions",
+            TodoError::NoLink(_) => "NoLink",
+            TodoError::LinkOutOfBound(..) => "LinkOutOfBound",
+            TodoError::InvalidMarker(_) => "InvalidMarker",
+            TodoError::UnknownColor(_) => "UnknownColor",
+            TodoError::InvalidTemplateName(_) => "InvalidTemplateName",
+            TodoError::UnknownTemplate(_) => "UnknownTemplate",
+            TodoError::InvalidFilterName(_) => "InvalidFilterName",
+            TodoError::ReservedFilterName(_) => "ReservedFilterName",
+            TodoError::UnknownSavedFilter(_) => "UnknownSavedFilter",
+            TodoError::InvalidSavedFilter(..) => "InvalidSavedFilter",
+            TodoError::InvalidSnippetName(_) => "InvalidSnippetName",
+            TodoError::UnknownSnippet(_) => "UnknownSnippet",
+            TodoError::InvalidSnippet(_) => "InvalidSnippet",
+            TodoError::MissingPlaceholders { .. } => "MissingPlaceholders",
+            TodoError::UnknownPlaceholder { .. } => "UnknownPlaceholder",
+            TodoError::RenumberBlocked(_) => "RenumberBlocked",
+            TodoError::SerializationError(_) => "SerializationError",
+            TodoError::FileError(_) => "FileError",
+            TodoError::SyncError(_) => "SyncError",
+            TodoError::ClipboardError(_) => "ClipboardError",
+            TodoError::NotInteractive => "NotInteractive",
+            TodoError::ReadOnly(_) => "ReadOnly",
+            TodoError::WouldWipe(_) => "WouldWipe",
+            TodoError::ConcurrentModification(_) => "ConcurrentModification",
+            TodoError::UnreadableTasks(_) => "UnreadableTasks",
+            TodoError::NotABackup(_) => "NotABackup",
+            TodoError::BackupTooNew { .. } => "BackupTooNew",
+            TodoError::BadBackupSection { .. } => "BadBackupSection",
+            TodoError::CliOnly(_) => "CliOnly",
+        }
+    }
+
+    // The values in the message, named as its placeholders are
+    fn fields(&self) -> Vec<(&'static str, Value)> {
+        // A subtask index is split up, so `index` is a number either way
+        let task_index = |index: &TaskIndex| {
+            let mut fields = vec![("index", json!(index.task))];
+            if let Some(sub) = index.sub {
+                fields.push(("sub", json!(sub)));
+            }
+            fields
+        };
+        match self {
+            TodoError::InvalidStatus(status) => {
+                let mut fields = vec![("status", json!(status))];
+                if let Some(suggestion) = Status::suggest(status) {
+                    fields.push(("suggestion", json!(suggestion)));
+                }
+                fields
+            }
+            TodoError::InvalidTransition { from, to } => vec![
+                ("from", json!(from.keyword())),
+                ("to", json!(to.keyword())),
+                ("allowed", json!(transitions_from(*from))),
+            ],
+            TodoError::IndexOutOfBound(index)
+            | TodoError::AlreadyCompleted(index)
+            | TodoError::TaskGone(index)
+            | TodoError::NotInView(index) => {
+                vec![("index", json!(index))]
+            }
+            TodoError::UnknownId(id) => vec![("id", json!(id))],
+            TodoError::DescriptionTooLong { len, max } => {
+                vec![("len", json!(len)), ("max", json!(max))]
+            }
+            TodoError::LineTooLong { len, max, start } => vec![
+                ("len", json!(len)),
+                ("max", json!(max)),
+                ("start", json!(start)),
+            ],
+            TodoError::InvalidDate(date) => vec![("date", json!(date))],
+            TodoError::InvalidDuration(duration) => vec![("duration", json!(duration))],
+            TodoError::InvalidEstimate(estimate) => vec![("estimate", json!(estimate))],
+            TodoError::SubtaskOutOfBound(task, sub) => {
+                vec![("task", json!(task)), ("sub", json!(sub))]
+            }
+            TodoError::NoMatch(text) => vec![("text", json!(text))],
+            TodoError::AmbiguousMatch(text, count) => {
+                vec![("text", json!(text)), ("count", json!(count))]
+            }
+            TodoError::SubtaskNotAllowed(index) | TodoError::NoLink(index) => task_index(index),
+            TodoError::NotCompleted(index, status) => {
+                let mut fields = task_index(index);
+                fields.push(("status", json!(status.keyword())));
+                fields
+            }
+            TodoError::DependencyCycle(task, on) => {
+                vec![("task", json!(task)), ("on", json!(on))]
+            }
+            TodoError::InvalidInterval(interval) => vec![("interval", json!(interval))],
+            TodoError::InvalidTag(tag) => vec![("tag", json!(tag))],
+            TodoError::InvalidPerson(person) => vec![("person", json!(person))],
+            TodoError::InvalidMarker(marker) => vec![("marker", json!(marker))],
+            TodoError::UnknownColor(color) => {
+                vec![("color", json!(color)), ("colors", json!(Color::NAMES))]
+            }
+            TodoError::LinkOutOfBound(index, count) => {
+                let mut fields = task_index(index);
+                fields.push(("count", json!(count)));
+                fields
+            }
+            TodoError::InvalidTemplateName(name)
+            | TodoError::UnknownTemplate(name)
+            | TodoError::InvalidFilterName(name)
+            | TodoError::ReservedFilterName(name)
+            | TodoError::UnknownSavedFilter(name)
+            | TodoError::InvalidSnippetName(name)
+            | TodoError::UnknownSnippet(name) => {
+                vec![("name", json!(name))]
+            }
+            TodoError::InvalidSnippet(text) => vec![("text", json!(text))],
+            TodoError::MissingPlaceholders { missing, needed } => {
+                vec![("missing", json!(missing)), ("needed", json!(needed))]
+            }
+            TodoError::UnknownPlaceholder { key, needed } => {
+                vec![("key", json!(key)), ("needed", json!(needed))]
+            }
+            TodoError::InvalidSavedFilter(name, error) => {
+                vec![("name", json!(name)), ("error", json!(error))]
+            }
+            TodoError::ReadOnly(command) => vec![("command", json!(command))],
+            TodoError::WouldWipe(count) | TodoError::RenumberBlocked(count) => {
+                vec![("count", json!(count))]
+            }
+            TodoError::CliOnly(command) => vec![("command", json!(command))],
+            TodoError::ConcurrentModification(path)
+            | TodoError::UnreadableTasks(path)
+            | TodoError::NotABackup(path) => {
+                vec![("path", json!(path))]
+            }
+            TodoError::BackupTooNew { version, supported } => {
+                vec![("version", json!(version)), ("supported", json!(supported))]
+            }
+            TodoError::BadBackupSection { section, error } => {
+                vec![("section", json!(section)), ("error", json!(error))]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Written as an object with the [`code`](TodoError::code) under `error`,
+/// the message under `message`, and the values in the message beside them
+impl Serialize for TodoError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let fields = self.fields();
+        let mut map = serializer.serialize_map(Some(fields.len() + 2))?;
+        map.serialize_entry("error", self.code())?;
+        map.serialize_entry("message", &self.to_string())?;
+        for (name, value) in &fields {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+/// Saved under its variant name. A name this version does not know, say from
+/// a newer one, loads as `Todo` and is listed by [`TodoList::unknown_statuses`].
+#[derive(Debug, Default, Serialize, Clone, Copy, PartialEq)]
 pub enum Status {
+    #[default]
     Todo,
+    /// Parked until it can be acted on; left out of lists unless asked for
+    Waiting,
     InProgress,
     Completed,
+    /// Dropped without being done, kept for the record
+    Cancelled,
+}
+
+impl Status {
+    /// Every status, in the order a task usually goes through them
+    pub const ALL: [Status; 5] = [
+        Status::Todo,
+        Status::Waiting,
+        Status::InProgress,
+        Status::Completed,
+        Status::Cancelled,
+    ];
+
+    /// Every word typed for a status, its keyword first. Parsing, `help` and
+    /// the error for a word not in here all go by this table.
+    pub const SYNONYMS: &'static [(&'static str, Status)] = &[
+        ("todo", Status::Todo),
+        ("to-do", Status::Todo),
+        ("open", Status::Todo),
+        ("waiting", Status::Waiting),
+        ("wait", Status::Waiting),
+        ("someday", Status::Waiting),
+        ("in-progress", Status::InProgress),
+        ("inprogress", Status::InProgress),
+        ("doing", Status::InProgress),
+        ("done", Status::Completed),
+        ("completed", Status::Completed),
+        ("complete", Status::Completed),
+        ("finished", Status::Completed),
+        ("cancelled", Status::Cancelled),
+        ("canceled", Status::Cancelled),
+        ("wontdo", Status::Cancelled),
+        ("won't-do", Status::Cancelled),
+    ];
+
+    /// The word a status is typed as, which `from_str` reads back
+    pub fn keyword(self) -> &'static str {
+        self.synonyms()
+            .next()
+            .expect("every status has a word in SYNONYMS")
+    }
+
+    /// The words for this status, its keyword first
+    pub fn synonyms(self) -> impl Iterator<Item = &'static str> {
+        Status::SYNONYMS
+            .iter()
+            .filter(move |(_, status)| *status == self)
+            .map(|(name, _)| *name)
+    }
+
+    /// Whether strict transitions let a task go from this status to `to`:
+    /// one step at a time along todo, in-progress, done, either way. Parking
+    /// a task as waiting or dropping it as cancelled is no step of the work,
+    /// so open tasks can do either and come back. Staying put is always fine.
+    pub fn is_valid_transition(self, to: Status) -> bool {
+        use Status::*;
+        self == to
+            || matches!(
+                (self, to),
+                (Todo, InProgress)
+                    | (InProgress, Todo)
+                    | (InProgress, Completed)
+                    | (Completed, InProgress)
+                    | (Todo | InProgress, Waiting)
+                    | (Waiting, Todo | InProgress)
+                    | (Todo | Waiting | InProgress, Cancelled)
+                    | (Cancelled, Todo)
+            )
+    }
+
+    /// The keywords of every status, as errors list them: `todo, in-progress, ...`
+    pub fn names() -> String {
+        let names: Vec<&str> = Status::ALL.iter().map(|status| status.keyword()).collect();
+        names.join(", ")
+    }
+
+    /// The keyword of the status a mistyped word is closest to, if any is a
+    /// likely typo away: `dnoe` suggests `done`
+    pub fn suggest(text: &str) -> Option<&'static str> {
+        let word = normalize_status(text);
+        let limit = typo_limit(&word);
+        Status::SYNONYMS
+            .iter()
+            .map(|(name, status)| (edit_distance(&word, name), *status))
+            .filter(|(distance, _)| *distance <= limit)
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, status)| status.keyword())
+    }
 }
 
 impl Display for Status {
     fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Status::Todo => write!(formatter, "TODO"),
+            Status::Waiting => write!(formatter, "WAITING"),
             Status::InProgress => write!(formatter, "IN-PROGRESS"),
             Status::Completed => write!(formatter, "DONE"),
+            Status::Cancelled => write!(formatter, "CANCELLED"),
         }
     }
 }
 
-impl Status {
-    // to parse a status from a string
-    pub fn from_str(status_str: &str) -> Result<Self, TodoError> {
-        match status_str.to_lowercase().as_str() {
-            "todo" | "to-do" => Ok(Status::Todo),
-            "done" | "completed" => Ok(Status::Completed),
-            "in-progress" | "inprogress" => Ok(Status::InProgress),
-            _ => Err(TodoError::InvalidStatus(status_str.to_string())),
+thread_local! {
+    // Unknown status names met while deserializing, collected by `load`
+    static UNKNOWN_STATUSES: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+impl<'de> Deserialize<'de> for Status {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(match name.as_str() {
+            "Todo" => Status::Todo,
+            "Waiting" => Status::Waiting,
+            "InProgress" => Status::InProgress,
+            "Completed" => Status::Completed,
+            "Cancelled" => Status::Cancelled,
+            _ => {
+                UNKNOWN_STATUSES.with_borrow_mut(|names| names.push(name));
+                Status::Todo
+            }
+        })
+    }
+}
+
+impl FromStr for Status {
+    type Err = TodoError;
+
+    /// Parse a status the way it is typed: any of [`Status::SYNONYMS`], in any
+    /// case, with spaces or `_` for `-` and stray punctuation around it, so
+    /// `Done!` and `"in progress"` both work
+    fn from_str(status_str: &str) -> Result<Self, TodoError> {
+        let word = normalize_status(status_str);
+        Status::SYNONYMS
+            .iter()
+            .find(|(name, _)| *name == word)
+            .map(|(_, status)| *status)
+            .ok_or_else(|| TodoError::InvalidStatus(status_str.to_string()))
+    }
+}
+
+// A status as typed, trimmed of spaces and punctuation, lowercased and with
+// its words joined by `-`: `"In Progress!"` becomes `in-progress`
+fn normalize_status(text: &str) -> String {
+    let text = text
+        .trim_matches(|c: char| c.is_whitespace() || c.is_ascii_punctuation())
+        .to_lowercase();
+    let words: Vec<&str> = text
+        .split(|c: char| c.is_whitespace() || c == '_')
+        .filter(|word| !word.is_empty())
+        .collect();
+    words.join("-")
+}
+
+fn check_transition(from: Status, to: Status, strict: bool) -> Result<(), TodoError> {
+    if strict && !from.is_valid_transition(to) {
+        return Err(TodoError::InvalidTransition { from, to });
+    }
+    Ok(())
+}
+
+// Keywords of the statuses strict transitions allow from `from`
+fn transitions_from(from: Status) -> String {
+    let allowed: Vec<&str> = Status::ALL
+        .iter()
+        .filter(|&&to| to != from && from.is_valid_transition(to))
+        .map(|status| status.keyword())
+        .collect();
+    allowed.join(", ")
+}
+
+fn invalid_transition(from: Status, to: Status) -> String {
+    msg!(
+        "error.invalid_transition",
+        from = from.keyword(),
+        to = to.keyword(),
+        allowed = transitions_from(from)
+    )
+}
+
+// The placeholders a snippet takes, as they would be typed: num=… repo=…
+fn needed_placeholders(needed: &[String]) -> String {
+    if needed.is_empty() {
+        return msg!("error.no_placeholders");
+    }
+    let typed: Vec<String> = needed.iter().map(|name| format!("{}=…", name)).collect();
+    typed.join(" ")
+}
+
+// Why a status was not understood, with the closest one when there is one
+fn invalid_status(text: &str) -> String {
+    match Status::suggest(text) {
+        Some(suggestion) => msg!(
+            "error.invalid_status_suggest",
+            status = text,
+            suggestion = suggestion,
+            statuses = Status::names()
+        ),
+        None => msg!(
+            "error.invalid_status",
+            status = text,
+            statuses = Status::names()
+        ),
+    }
+}
+
+/// How often a task comes back once completed, stored as a number of days
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(transparent)]
+pub struct Recurrence {
+    pub days: u32,
+}
+
+impl Recurrence {
+    /// Parse intervals like "every 3d", "2w", "week", "daily"
+    pub fn parse(interval: &str) -> Result<Self, TodoError> {
+        let invalid = || TodoError::InvalidInterval(interval.to_string());
+        let text = interval.trim().to_lowercase();
+        let text = text.strip_prefix("every").unwrap_or(&text).trim();
+
+        let days = match text {
+            "day" | "daily" => 1,
+            "week" | "weekly" => 7,
+            _ => {
+                let seconds = parse_duration(text).map_err(|_| invalid())?.as_secs();
+                // Recurrence works on whole days only
+                if seconds % SECONDS_PER_DAY as u64 != 0 {
+                    return Err(invalid());
+                }
+                u32::try_from(seconds / SECONDS_PER_DAY as u64).map_err(|_| invalid())?
+            }
+        };
+        Ok(Recurrence { days })
+    }
+}
+
+/// How long a task is expected to take, kept in whole minutes
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct Estimate {
+    pub minutes: u32,
+}
+
+impl Estimate {
+    /// Parse an amount of time as `snooze` takes it: "45m", "2h", "1h30m", "1d"
+    pub fn parse(text: &str) -> Result<Self, TodoError> {
+        let invalid = || TodoError::InvalidEstimate(text.to_string());
+        let seconds = parse_duration(text).map_err(|_| invalid())?.as_secs();
+        let minutes = u32::try_from(seconds / 60).map_err(|_| invalid())?;
+        Ok(Estimate { minutes })
+    }
+
+    /// The estimate a description word gives, such as `~90m`
+    pub fn from_word(word: &str) -> Option<Self> {
+        Estimate::parse(word.strip_prefix('~')?).ok()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minutes == 0
+    }
+}
+
+impl std::iter::Sum for Estimate {
+    fn sum<I: Iterator<Item = Estimate>>(estimates: I) -> Self {
+        let minutes = estimates.fold(0u32, |total, estimate| {
+            total.saturating_add(estimate.minutes)
+        });
+        Estimate { minutes }
+    }
+}
+
+impl Display for Estimate {
+    /// `45m`, `2h` or `1h30m`, which [`Estimate::parse`] reads back
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.minutes / 60, self.minutes % 60) {
+            (0, minutes) => write!(f, "{}m", minutes),
+            (hours, 0) => write!(f, "{}h", hours),
+            (hours, minutes) => write!(f, "{}h{}m", hours, minutes),
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Task {
-    pub description: String,
-    pub status: Status,
+/// How pressing a task is. Saved under its variant name, and left out of the
+/// file at the default, Normal. Names load in any case, and one this version
+/// does not know loads as Normal.
+#[derive(Debug, Default, Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    Low,
+    #[default]
+    Normal,
+    High,
+    Urgent,
 }
 
-impl Task {
-    pub fn new(description: String) -> Result<Self, TodoError> {
-        if description.trim().is_empty() {
-            return Err(TodoError::EmptyDescription);
+impl Priority {
+    /// The level above this one, None at the top
+    pub fn raised(self) -> Option<Priority> {
+        match self {
+            Priority::Low => Some(Priority::Normal),
+            Priority::Normal => Some(Priority::High),
+            Priority::High => Some(Priority::Urgent),
+            Priority::Urgent => None,
         }
-        Ok(Task {
-            description: description.trim().to_string(),
-            status: Status::Todo,
+    }
+
+    /// Whether this is the default level, which files leave out
+    pub fn is_normal(&self) -> bool {
+        *self == Priority::Normal
+    }
+}
+
+impl<'de> Deserialize<'de> for Priority {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(match name.to_lowercase().as_str() {
+            "low" => Priority::Low,
+            "high" => Priority::High,
+            "urgent" => Priority::Urgent,
+            _ => Priority::Normal,
         })
     }
+}
 
-    // Task Helper Method
-    pub fn is_completed(&self) -> bool {
-        self.status == Status::Completed
+impl Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "LOW"),
+            Priority::Normal => write!(f, "NORMAL"),
+            Priority::High => write!(f, "HIGH"),
+            Priority::Urgent => write!(f, "URGENT"),
+        }
     }
 }
 
-impl Display for Task {
+impl Display for Recurrence {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} [{}]", self.description, self.status)
+        match self.days {
+            1 => write!(f, "every day"),
+            7 => write!(f, "every week"),
+            days if days % 7 == 0 => write!(f, "every {} weeks", days / 7),
+            days => write!(f, "every {} days", days),
+        }
     }
 }
 
-// TodoList - Main data structure
-#[derive(Debug, Serialize, Deserialize)]
-pub struct TodoList {
-    pub tasks: Vec<Task>,
+// A string read from a file, with what `sanitize` takes out left out
+fn sanitized<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    String::deserialize(deserializer).map(|text| sanitize(&text))
 }
 
-impl TodoList {
-    pub fn new() -> Self {
-        Self { tasks: Vec::new() }
+/// One thing to do, with its subtasks
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Task {
+    /// Stable identifier that survives reordering; 0 until the task joins a list
+    #[serde(default)]
+    pub id: u64,
+    /// Sanitized as it is read, so a file edited by hand, merged in or
+    /// pulled can't put escape sequences on the terminal either
+    #[serde(deserialize_with = "sanitized")]
+    pub description: String,
+    #[serde(default)]
+    pub status: Status,
+    /// Timestamps are missing on tasks saved by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<Timestamp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<Timestamp>,
+    /// Last change made to the task, set by [`Task::touch`]; missing until
+    /// the task is first changed after it was added
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<Timestamp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due: Option<Date>,
+    /// Time of day the task is due, in local time; only set with `due`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_time: Option<Time>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recurrence: Option<Recurrence>,
+    #[serde(default, skip_serializing_if = "Priority::is_normal")]
+    pub priority: Priority,
+    /// When `escalate` last raised the priority; staleness counts from here
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalated_at: Option<Timestamp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimate: Option<Estimate>,
+    /// Why the task is waiting, given with `wait`; dropped once it moves on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wait_reason: Option<String>,
+    /// Who the task waits on, given with `delegate` and kept as typed;
+    /// matched ignoring case. Dropped once the task is completed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waiting_on: Option<String>,
+    /// When the task was delegated to `waiting_on`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delegated_at: Option<Timestamp>,
+    /// Lowercase labels without the leading #
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// todo.txt-style +project and @context tokens, lowercase without the sign
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub projects: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub contexts: Vec<String>,
+    /// A word or emoji of the user's shown beside the status, set with `mark`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marker: Option<String>,
+    /// Name of the color the task's row is drawn in instead of its status
+    /// color; a name this version does not know is ignored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub marker_color: Option<String>,
+    /// Where the task was imported from, such as `github#12`, so importing
+    /// again updates it instead of adding a copy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// One level of steps under this task
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subtasks: Vec<Task>,
+    /// IDs of tasks that must be completed before this one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<u64>,
+    /// Time tracked with `track`, not counting a session still running
+    #[serde(default, skip_serializing_if = "Duration::is_zero")]
+    pub accumulated: Duration,
+    /// Start of the running session, if the timer is on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timer_started: Option<Timestamp>,
+}
+
+/// A task number as typed by the user: `3` for a task, `3.2` for its second subtask
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaskIndex {
+    pub task: usize,
+    pub sub: Option<usize>,
+}
+
+impl TaskIndex {
+    pub fn parse(text: &str) -> Option<Self> {
+        match text.split_once('.') {
+            Some((task, sub)) => Some(TaskIndex {
+                task: task.parse().ok()?,
+                sub: Some(sub.parse().ok()?),
+            }),
+            None => Some(TaskIndex {
+                task: text.parse().ok()?,
+                sub: None,
+            }),
+        }
     }
+}
 
-    // Add a task - now uses Task::new for validation
-    pub fn add_tasks(&mut self, description: String) -> Result<(), TodoError> {
-        let task = Task::new(description)?;
-        self.tasks.push(task);
-        Ok(())
+impl Display for TaskIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.sub {
+            Some(sub) => write!(f, "{}.{}", self.task, sub),
+            None => write!(f, "{}", self.task),
+        }
     }
+}
 
-    // Get number of tasks
-    pub fn len(&self) -> usize {
-        self.tasks.len()
+/// A task as a [`TodoList`] lookup found it, which is what the methods that
+/// change a task take. It cannot be made from a bare number, and it holds
+/// the task's ID, so it still means the same task after others before it
+/// are removed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TaskRef {
+    id: u64,
+    index: usize,
+}
+
+impl TaskRef {
+    pub fn id(self) -> u64 {
+        self.id
     }
 
-    pub fn is_empty(&self) -> bool {
-        self.tasks.is_empty()
+    /// The number the task had when it was looked up, which is the one
+    /// messages about it should show
+    pub fn index(self) -> usize {
+        self.index
     }
+}
 
-    // List all tasks with a functional appraoch
-    pub fn list_tasks(&self) -> Vec<(usize, &Task)> {
-        self.tasks
-            .iter()
-            .enumerate()
-            .map(|(i, task)| (i + 1, task))
-            .collect()
+impl Display for TaskRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.index)
     }
+}
 
-    // Filter tasks by status
-    pub fn filter_by_status(&self, status: Status) -> Vec<(usize, &Task)> {
-        self.tasks
-            .iter()
-            .enumerate()
-            .filter(|(_, task)| task.status == status)
-            .map(|(i, task)| (i + 1, task))
-            .collect()
+/// How a command names a task: by number, by text in its description, or
+/// as the one chosen from a menu of the tasks matching a filter
+#[derive(Debug, Clone, PartialEq)]
+pub enum Selector {
+    Index(TaskIndex),
+    Text(String),
+    Pick(ListFilter),
+}
+
+impl Selector {
+    /// Anything that reads as a task number keeps meaning one
+    pub fn parse(text: &str) -> Self {
+        match TaskIndex::parse(text) {
+            Some(index) => Selector::Index(index),
+            None => Selector::Text(text.to_string()),
+        }
     }
+}
 
-    // Update task status with better error handling
-    pub fn update_task_status(
-        &mut self,
-        index: usize,
-        new_status: Status,
-    ) -> Result<(), TodoError> {
-        self.validate_index(index)?;
-        self.tasks[index - 1].status = new_status;
-        Ok(())
+/// A new task's optional fields, collected before [`TaskBuilder::build`]
+/// checks them all at once
+#[derive(Debug, Clone, PartialEq)]
+pub struct TaskBuilder {
+    description: String,
+    status: Status,
+    due: Option<Date>,
+    recurrence: Option<Recurrence>,
+    tags: Vec<String>,
+    settings: Settings,
+}
+
+impl TaskBuilder {
+    /// Start as something other than todo
+    pub fn status(mut self, status: Status) -> Self {
+        self.status = status;
+        self
     }
 
-    // supports user input like: status 2 done
-    pub fn update_task_status_str(
-        &mut self,
-        index: usize,
-        status_str: &str,
-    ) -> Result<(), TodoError> {
-        let new_status = Status::from_str(status_str)?;
-        self.update_task_status(index, new_status)?;
-        Ok(())
+    /// Give the task a due date
+    pub fn due(mut self, due: Date) -> Self {
+        self.due = Some(due);
+        self
     }
 
-    // Remove a task
-    pub fn remove_task(&mut self, index: usize) -> Result<Task, TodoError> {
-        self.validate_index(index)?;
-        Ok(self.tasks.remove(index - 1))
+    /// Make the task come back this often once completed
+    pub fn recurrence(mut self, recurrence: Recurrence) -> Self {
+        self.recurrence = Some(recurrence);
+        self
     }
 
-    // Clear all completed tasks
-    pub fn clear_completed(&mut self) -> usize {
-        let original_len = self.tasks.len();
-        self.tasks.retain(|task| !task.is_completed());
-        original_len - self.tasks.len()
+    /// Add a tag, with or without its leading #
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.to_string());
+        self
     }
 
-    // Helper to validate index
-    fn validate_index(&self, index: usize) -> Result<(), TodoError> {
-        if index == 0 {
-            return Err(TodoError::InvalidIndex);
+    /// Check and shape the description by these settings rather than the
+    /// defaults, as the list the task is for does
+    pub fn settings(mut self, settings: Settings) -> Self {
+        self.settings = settings;
+        self
+    }
+
+    /// The task, once the description, the tags and the options together
+    /// are valid. The task has no ID until it joins a list.
+    pub fn build(self) -> Result<Task, TodoError> {
+        if self.recurrence.is_some() && matches!(self.status, Status::Completed | Status::Cancelled)
+        {
+            return Err(TodoError::ConflictingOptions("error.closed_cannot_repeat"));
         }
-        if index > self.tasks.len() {
-            return Err(TodoError::IndexOutOfBound(index));
+        let mut task = Task {
+            id: 0,
+            description: String::new(),
+            status: Status::Todo,
+            created_at: Some(now()),
+            completed_at: None,
+            updated_at: None,
+            due: self.due,
+            due_time: None,
+            recurrence: self.recurrence,
+            priority: Priority::Normal,
+            escalated_at: None,
+            estimate: None,
+            wait_reason: None,
+            waiting_on: None,
+            delegated_at: None,
+            tags: Vec::new(),
+            projects: Vec::new(),
+            contexts: Vec::new(),
+            marker: None,
+            marker_color: None,
+            source: None,
+            subtasks: Vec::new(),
+            depends_on: Vec::new(),
+            accumulated: Duration::ZERO,
+            timer_started: None,
+        };
+        task.describe(&self.description, &self.settings)?;
+        for tag in &self.tags {
+            let tag = normalize_tag(tag).ok_or_else(|| TodoError::InvalidTag(tag.clone()))?;
+            if !task.tags.contains(&tag) {
+                task.tags.push(tag);
+            }
         }
-        Ok(())
+        task.set_status(self.status);
+        Ok(task)
     }
 }
 
-// IIMPLEMENT THE STORABLE TRAIT
-impl Storable for TodoList {
-    fn save(&self, path: &str) -> Result<(), TodoError> {
-        let json = serde_json::to_string_pretty(&self.tasks)?;
-        fs::write(path, json)?;
-        Ok(())
+impl Task {
+    /// Start building a task with more than a description
+    pub fn builder(description: impl Into<String>) -> TaskBuilder {
+        TaskBuilder {
+            description: description.into(),
+            status: Status::Todo,
+            due: None,
+            recurrence: None,
+            tags: Vec::new(),
+            settings: Settings::default(),
+        }
     }
 
-    fn load(path: &str) -> Result<Self, TodoError> {
-        match fs::read_to_string(path) {
-            Ok(json) => {
-                let tasks = serde_json::from_str(&json)?;
-                Ok(TodoList { tasks })
+    /// A todo task with nothing but a description, checked by the default
+    /// settings
+    pub fn new(description: String) -> Result<Self, TodoError> {
+        Task::builder(description).build()
+    }
+
+    // Set the description, picking up its +project and @context tokens.
+    // Tokens already on the task are kept, since with --strip-tokens they
+    // are no longer in the text. A description of only tokens is empty.
+    fn describe(&mut self, text: &str, settings: &Settings) -> Result<(), TodoError> {
+        // Whatever the text came from, nothing in it may reach the terminal
+        // as an escape sequence when the task is listed
+        let text = sanitize(text);
+        // A word like ~90m is the estimate, never part of the description
+        let (text, estimate) = take_estimate(&text);
+        let (rest, tokens) = extract_tokens(&text);
+        if rest.is_empty() {
+            return Err(TodoError::EmptyDescription);
+        }
+        let description = if settings.strip_tokens {
+            rest
+        } else {
+            text.trim().to_string()
+        };
+        check_length(&description, settings.max_length)?;
+        self.description = description;
+        if estimate.is_some() {
+            self.estimate = estimate;
+        }
+        for project in tokens.projects {
+            if !self.projects.contains(&project) {
+                self.projects.push(project);
+            }
+        }
+        for context in tokens.contexts {
+            if !self.contexts.contains(&context) {
+                self.contexts.push(context);
             }
-            Err(error) => Err(TodoError::FileError(error)),
         }
+        Ok(())
+    }
+
+    /// Change status, stamping or clearing the completion time.
+    /// Closing a task stops its timer.
+    pub fn set_status(&mut self, status: Status) {
+        if status == Status::Completed && self.status != Status::Completed {
+            self.completed_at = Some(now());
+        } else if status != Status::Completed {
+            self.completed_at = None;
+        }
+        if matches!(status, Status::Completed | Status::Cancelled) {
+            self.stop_timer(now());
+        }
+        if status != Status::Waiting {
+            self.wait_reason = None;
+        }
+        if status == Status::Completed {
+            self.waiting_on = None;
+            self.delegated_at = None;
+        }
+        self.status = status;
+    }
+
+    /// Tracked time as of `now`, including a running session
+    pub fn time_spent(&self, now: Timestamp) -> Duration {
+        let running = self
+            .timer_started
+            .map_or(0, |started| now.saturating_sub(started));
+        self.accumulated + Duration::from_secs(running)
+    }
+
+    // Fold a running session into the tracked time, returning its length
+    fn stop_timer(&mut self, now: Timestamp) -> Option<Duration> {
+        let started = self.timer_started.take()?;
+        let session = Duration::from_secs(now.saturating_sub(started));
+        self.accumulated += session;
+        Some(session)
+    }
+
+    // Copy for saving: a running session is counted up to `now` and restarts
+    // there, so the file never holds more than the latest stretch untracked
+    fn checkpointed(&self, now: Timestamp) -> Task {
+        let mut task = self.clone();
+        if task.stop_timer(now).is_some() {
+            task.timer_started = Some(now);
+        }
+        task
+    }
+
+    /// Fresh Todo copy of a recurring task, due one interval after the old due date
+    /// (or after `today` when it had none)
+    pub fn next_occurrence(&self, today: Date) -> Task {
+        let days = self.recurrence.map_or(0, |recurrence| recurrence.days);
+        Task {
+            id: 0,
+            description: self.description.clone(),
+            status: Status::Todo,
+            created_at: Some(now()),
+            completed_at: None,
+            updated_at: None,
+            due: Some(self.due.unwrap_or(today).add_days(i64::from(days))),
+            due_time: self.due_time,
+            recurrence: self.recurrence,
+            priority: self.priority,
+            escalated_at: None,
+            estimate: self.estimate,
+            wait_reason: None,
+            waiting_on: None,
+            delegated_at: None,
+            tags: self.tags.clone(),
+            projects: self.projects.clone(),
+            contexts: self.contexts.clone(),
+            marker: self.marker.clone(),
+            marker_color: self.marker_color.clone(),
+            source: None,
+            subtasks: self
+                .subtasks
+                .iter()
+                .map(|sub| Task {
+                    status: Status::Todo,
+                    created_at: Some(now()),
+                    completed_at: None,
+                    updated_at: None,
+                    wait_reason: None,
+                    waiting_on: None,
+                    delegated_at: None,
+                    accumulated: Duration::ZERO,
+                    timer_started: None,
+                    ..sub.clone()
+                })
+                .collect(),
+            depends_on: Vec::new(),
+            accumulated: Duration::ZERO,
+            timer_started: None,
+        }
+    }
+
+    /// Whether the task waits on this person, ignoring case
+    pub fn is_waiting_on(&self, person: &str) -> bool {
+        self.waiting_on
+            .as_ref()
+            .is_some_and(|own| own.to_lowercase() == person.to_lowercase())
+    }
+
+    /// Whether the task carries a tag, given as stored
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|own| own == tag)
+    }
+
+    /// Task Helper Method
+    pub fn is_completed(&self) -> bool {
+        self.status == Status::Completed
+    }
+
+    /// Completed or cancelled: nothing is left to do either way
+    pub fn is_closed(&self) -> bool {
+        matches!(self.status, Status::Completed | Status::Cancelled)
+    }
+
+    /// Whether the task has sat in Todo for more than `threshold` days by
+    /// `now`, counting from when it was created or last escalated. Tasks
+    /// saved without a creation time never are.
+    pub fn is_stale(&self, now: Timestamp, threshold: u64) -> bool {
+        let Some(since) = self.escalated_at.or(self.created_at) else {
+            return false;
+        };
+        self.status == Status::Todo
+            && now.saturating_sub(since) > threshold.saturating_mul(SECONDS_PER_DAY as u64)
+    }
+
+    /// Record that the task was changed just now. [`TodoList`] calls this on
+    /// every change it makes to a task.
+    pub fn touch(&mut self) {
+        self.updated_at = Some(now());
+    }
+
+    /// Due date and minute of the day, for putting tasks in due order. A
+    /// task due on a day without a time counts as due at the end of it,
+    /// after that day's timed tasks.
+    pub fn due_order(&self) -> Option<(Date, u32)> {
+        let minutes = self.due_time.map_or(24 * 60, |time| time.minutes());
+        self.due.map(|due| (due, minutes))
+    }
+
+    /// When the task was last changed, or added if it never was since.
+    /// None for tasks saved by older versions without timestamps.
+    pub fn last_touched(&self) -> Option<Timestamp> {
+        self.updated_at.or(self.created_at)
+    }
+
+    /// True once the task has subtasks and every one of them is closed
+    pub fn subtasks_done(&self) -> bool {
+        !self.subtasks.is_empty() && self.subtasks.iter().all(Task::is_closed)
+    }
+
+    /// The color set with `mark`, if it is one this version knows
+    pub fn color(&self) -> Option<Color> {
+        self.marker_color.as_deref()?.parse().ok()
+    }
+}
+
+impl Display for Task {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} [{}]", self.description, self.status)
+    }
+}
+
+/// What `mark` sets on a task: a word or emoji to show beside its status,
+/// a color for its row, or both. A part left out stays as it was.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Marker {
+    pub text: Option<String>,
+    pub color: Option<Color>,
+}
+
+impl Marker {
+    /// Read `mark`'s arguments: `🔥`, `red` or `🔥 red`. A lone word that is
+    /// nearly a color name is taken for a misspelled color, not a marker.
+    pub fn parse(words: &[&str]) -> Result<Marker, TodoError> {
+        let unknown_color = |word: &str| TodoError::UnknownColor(word.to_string());
+        // Quotes would only have held several words together
+        if words.iter().any(|word| word.contains('"')) {
+            return Err(TodoError::InvalidMarker(words.join(" ").replace('"', "")));
+        }
+        match words {
+            [word] => match word.parse::<Color>() {
+                Ok(color) => Ok(Marker {
+                    text: None,
+                    color: Some(color),
+                }),
+                Err(_) if looks_like_color(word) => Err(unknown_color(word)),
+                Err(_) => Ok(Marker {
+                    text: Some(word.to_string()),
+                    color: None,
+                }),
+            },
+            [text, color] => Ok(Marker {
+                text: Some(text.to_string()),
+                color: Some(color.parse().map_err(|_| unknown_color(color))?),
+            }),
+            _ => Err(TodoError::InvalidMarker(words.join(" "))),
+        }
+    }
+}
+
+// A word of letters a typo away from a color name
+fn looks_like_color(word: &str) -> bool {
+    let word = word.to_lowercase();
+    word.chars().all(|c| c.is_ascii_alphabetic() || c == '-')
+        && Color::NAMES
+            .split(", ")
+            .any(|name| edit_distance(&word, name) <= typo_limit(name))
+}
+
+/// Tag as stored: lowercase with any leading # dropped, None when nothing is left
+pub fn normalize_tag(text: &str) -> Option<String> {
+    let tag = text.trim().trim_start_matches('#').to_lowercase();
+    if tag.is_empty() { None } else { Some(tag) }
+}
+
+/// A person's name or handle as `delegate` takes it: one word of letters
+/// and digits, which may hold `-`, `_`, `.` or `'`, with a leading @ dropped.
+/// The case is kept, though names are matched ignoring it.
+pub fn normalize_person(text: &str) -> Option<String> {
+    let person = text.trim().trim_start_matches('@');
+    let mut chars = person.chars();
+    let valid = chars.next().is_some_and(char::is_alphanumeric)
+        && chars.all(|c| c.is_alphanumeric() || "-_.'".contains(c));
+    valid.then(|| person.to_string())
+}
+
+/// Longest description allowed unless --max-length says otherwise
+pub const DEFAULT_MAX_LENGTH: usize = 200;
+
+/// Characters as a reader counts them, so an emoji or an accented
+/// letter is one however many bytes or code points it takes
+pub fn description_length(description: &str) -> usize {
+    clusters(description).len()
+}
+
+// Turn down a description over `max` characters, unless `max` is 0
+fn check_length(description: &str, max: usize) -> Result<(), TodoError> {
+    let len = description_length(description);
+    if max > 0 && len > max {
+        return Err(TodoError::DescriptionTooLong { len, max });
+    }
+    Ok(())
+}
+
+/// A todo.txt-style word naming a project or a context
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Project(String),
+    Context(String),
+}
+
+impl Token {
+    /// `+taxes` or `@phone`; the sign has to be followed by a letter,
+    /// so "+1" or "@5pm" stay part of the text. Punctuation ending the
+    /// word, as in "@phone," or "+taxes.", is not part of the name.
+    pub fn parse(word: &str) -> Option<Self> {
+        let mut chars = word.chars();
+        let sign = chars.next()?;
+        let name = chars
+            .as_str()
+            .trim_end_matches(|c: char| c.is_ascii_punctuation() && c != '-' && c != '_');
+        if !name.starts_with(char::is_alphabetic) {
+            return None;
+        }
+        let name = name.to_lowercase();
+        match sign {
+            '+' => Some(Token::Project(name)),
+            '@' => Some(Token::Context(name)),
+            _ => None,
+        }
+    }
+}
+
+/// Tokens found in a description, in order and without repeats
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tokens {
+    pub projects: Vec<String>,
+    pub contexts: Vec<String>,
+}
+
+/// Split a description into its plain words and its tokens. The words are
+/// joined by single spaces; an empty string means there were only tokens.
+pub fn extract_tokens(text: &str) -> (String, Tokens) {
+    let mut words = Vec::new();
+    let mut tokens = Tokens::default();
+    for word in text.split_whitespace() {
+        let (list, name) = match Token::parse(word) {
+            Some(Token::Project(name)) => (&mut tokens.projects, name),
+            Some(Token::Context(name)) => (&mut tokens.contexts, name),
+            None => {
+                words.push(word);
+                continue;
+            }
+        };
+        if !list.contains(&name) {
+            list.push(name);
+        }
+    }
+    (words.join(" "), tokens)
+}
+
+// Take the last `~` estimate word out of a description, leaving the text
+// as it was when there is none
+fn take_estimate(text: &str) -> (String, Option<Estimate>) {
+    let mut estimate = None;
+    let mut words = Vec::new();
+    for word in text.split_whitespace() {
+        match Estimate::from_word(word) {
+            Some(found) => estimate = Some(found),
+            None => words.push(word),
+        }
+    }
+    match estimate {
+        Some(_) => (words.join(" "), estimate),
+        None => (text.to_string(), None),
+    }
+}
+
+/// Template name as stored: lowercase with runs of spaces collapsed.
+/// Quotes would make the name impossible to type back.
+pub fn normalize_template_name(text: &str) -> Result<String, TodoError> {
+    let name = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if name.is_empty() || name.contains('"') {
+        return Err(TodoError::InvalidTemplateName(text.to_string()));
+    }
+    Ok(name.to_lowercase())
+}
+
+/// Snippet name as stored: lowercase, one word of letters, digits, `-` and
+/// `_` starting with a letter, so that `add @name` can't be mistaken
+pub fn normalize_snippet_name(text: &str) -> Result<String, TodoError> {
+    let name = text.strip_prefix('@').unwrap_or(text).to_lowercase();
+    if !snippet::is_placeholder_name(&name) {
+        return Err(TodoError::InvalidSnippetName(text.to_string()));
+    }
+    Ok(name)
+}
+
+/// Words `list` reads as filters of its own, besides the statuses
+pub const FILTER_KEYWORDS: &[&str] = &["all", "blocked", "archived"];
+
+/// Whether `list` reads the word as one of its own filters, a status or one
+/// of [`FILTER_KEYWORDS`], rather than as the name of a saved filter
+pub fn is_filter_keyword(word: &str) -> bool {
+    let word = word.to_lowercase();
+    FILTER_KEYWORDS.contains(&word.as_str()) || Status::from_str(&word).is_ok()
+}
+
+/// Saved filter name as stored: lowercase. It has to be a single word of
+/// letters, digits, `-` and `_` starting with a letter, to be told apart
+/// from the other words `list` takes, and not be one of its own.
+pub fn normalize_filter_name(text: &str) -> Result<String, TodoError> {
+    let name = text.to_lowercase();
+    let valid = name.chars().next().is_some_and(char::is_alphabetic)
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(TodoError::InvalidFilterName(text.to_string()));
+    }
+    if is_filter_keyword(&name) {
+        return Err(TodoError::ReservedFilterName(name));
+    }
+    Ok(name)
+}
+
+/// Tasks read from a text file, one per line
+#[derive(Debug, Default)]
+pub struct TaskFile {
+    pub tasks: Vec<Task>,
+    /// The line each task was made from, sanitized, in the same order
+    pub lines: Vec<String>,
+    /// Lines that could not become tasks, numbered from 1, with the reason
+    pub failed: Vec<(usize, TodoError)>,
+}
+
+/// Read one task per line of a text file, skipping blank lines and
+/// # comments. Lines are sanitized, one longer than the settings'
+/// `max_line` is turned down like an invalid one, and tasks are made by
+/// the settings.
+pub fn read_task_file(path: &str, settings: &Settings) -> Result<TaskFile, TodoError> {
+    read_tasks(BufReader::new(File::open(path)?), settings)
+}
+
+/// Read one task per line until the input ends, as [`read_task_file`] does.
+/// Every line is a description; none is taken for a command.
+pub fn read_tasks(reader: impl BufRead, settings: &Settings) -> Result<TaskFile, TodoError> {
+    let mut file = TaskFile::default();
+    for (number, line) in reader.lines().enumerate() {
+        let line = match sanitize_line(&line?, settings.max_line) {
+            Ok(line) => line,
+            Err(error) => {
+                file.failed.push((number + 1, error));
+                continue;
+            }
+        };
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match Task::builder(line.as_str()).settings(*settings).build() {
+            Ok(task) => {
+                file.tasks.push(task);
+                file.lines.push(line);
+            }
+            Err(error) => file.failed.push((number + 1, error)),
+        }
+    }
+    Ok(file)
+}
+
+/// Criteria for narrowing down `list`; an empty filter matches every task
+/// but the waiting ones
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ListFilter {
+    pub status: Option<Status>,
+    /// Waiting tasks too, which are left out unless asked for by this or
+    /// by their status
+    pub all: bool,
+    /// Only tasks waiting on unfinished dependencies
+    pub blocked: bool,
+    /// Every tag must be present
+    pub tags: Vec<String>,
+    /// Every project and context must be present
+    pub projects: Vec<String>,
+    pub contexts: Vec<String>,
+    /// Only tasks delegated to this person, ignoring case; these show
+    /// whether or not they are Waiting
+    pub waiting_on: Option<String>,
+    /// Every term must appear in the description, ignoring case
+    pub text: Vec<String>,
+    /// Bare words given to `list`. One naming a saved filter stands for it
+    /// once [`expand_filter`](crate::command::expand_filter) has run;
+    /// until then, and otherwise, it is a term like those in `text`.
+    pub names: Vec<String>,
+    /// Only tasks left unchanged for at least this long, see [`Task::last_touched`]
+    pub untouched: Option<Duration>,
+    /// Most recently changed first, rather than in list order
+    pub recent: bool,
+    /// Earliest due date first, tasks without one last
+    pub by_due: bool,
+    /// Due dates shown as dates rather than as how far off they are
+    pub absolute: bool,
+    /// Shown in a section per status rather than as one list
+    pub grouped: bool,
+}
+
+impl ListFilter {
+    /// True when no criterion is set. `all` is not one: it narrows nothing.
+    pub fn is_empty(&self) -> bool {
+        self.status.is_none()
+            && !self.blocked
+            && self.tags.is_empty()
+            && self.projects.is_empty()
+            && self.contexts.is_empty()
+            && self.waiting_on.is_none()
+            && self.text.is_empty()
+            && self.names.is_empty()
+            && self.untouched.is_none()
+    }
+
+    /// Add the criteria of another filter to these. Where both give a
+    /// status or an age, this filter's stays.
+    pub fn extend(&mut self, other: ListFilter) {
+        self.status = self.status.or(other.status);
+        self.all |= other.all;
+        self.blocked |= other.blocked;
+        self.tags.extend(other.tags);
+        self.projects.extend(other.projects);
+        self.contexts.extend(other.contexts);
+        self.waiting_on = self.waiting_on.take().or(other.waiting_on);
+        self.text.extend(other.text);
+        self.names.extend(other.names);
+        self.untouched = self.untouched.or(other.untouched);
+        self.recent |= other.recent;
+        self.by_due |= other.by_due;
+        self.absolute |= other.absolute;
+        self.grouped |= other.grouped;
+    }
+
+    // Text terms and the bare words still standing for themselves
+    fn terms(&self) -> impl Iterator<Item = &String> {
+        self.text.iter().chain(&self.names)
+    }
+
+    /// Whether Waiting tasks make it into the result
+    pub fn shows_waiting(&self) -> bool {
+        self.all || self.status == Some(Status::Waiting) || self.waiting_on.is_some()
+    }
+
+    // Whether the task has gone unchanged as long as asked by `now`. Tasks
+    // from older versions, with no timestamps, have no age to go by.
+    fn untouched_by(&self, task: &Task, now: Timestamp) -> bool {
+        self.untouched.is_none_or(|age| {
+            task.last_touched()
+                .is_some_and(|touched| now.saturating_sub(touched) >= age.as_secs())
+        })
+    }
+
+    // Criteria that only look at the task itself
+    fn matches(&self, task: &Task) -> bool {
+        self.tags.iter().all(|tag| task.has_tag(tag))
+            && self
+                .projects
+                .iter()
+                .all(|project| task.projects.contains(project))
+            && self
+                .contexts
+                .iter()
+                .all(|context| task.contexts.contains(context))
+            && self
+                .waiting_on
+                .as_ref()
+                .is_none_or(|person| task.is_waiting_on(person))
+            && self
+                .terms()
+                .all(|term| !find_matches(&task.description, term).is_empty())
+    }
+
+    /// Where the text terms occur in a description, in order, with
+    /// overlapping matches of different terms merged
+    pub fn highlights(&self, description: &str) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = self
+            .terms()
+            .flat_map(|term| find_matches(description, term))
+            .collect();
+        ranges.sort_by_key(|range| range.start);
+        let mut merged: Vec<Range<usize>> = Vec::new();
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+}
+
+/// Byte ranges of every occurrence of `term` in `text`, ignoring case.
+/// Ranges always fall on character boundaries of the original text, even
+/// where lowercasing changes how long a character is.
+pub fn find_matches(text: &str, term: &str) -> Vec<Range<usize>> {
+    let term: Vec<char> = term.to_lowercase().chars().map(fold_case).collect();
+    let mut ranges = Vec::new();
+    if term.is_empty() {
+        return ranges;
+    }
+    let mut from = 0;
+    for (start, _) in text.char_indices() {
+        if start < from {
+            continue;
+        }
+        if let Some(length) = match_length(&text[start..], &term) {
+            ranges.push(start..start + length);
+            from = start + length;
+        }
+    }
+    ranges
+}
+
+// Bytes of `text` matching the lowercase `term` from its start, if it does.
+// A character whose lowercase form runs past the end of the term is no match.
+fn match_length(text: &str, term: &[char]) -> Option<usize> {
+    let mut matched = 0;
+    for (offset, c) in text.char_indices() {
+        for lower in c.to_lowercase() {
+            if term.get(matched) != Some(&fold_case(lower)) {
+                return None;
+            }
+            matched += 1;
+        }
+        if matched == term.len() {
+            return Some(offset + c.len_utf8());
+        }
+    }
+    None
+}
+
+// Greek capital sigma lowercases to ς at the end of a word and σ elsewhere,
+// so the two are one letter here
+fn fold_case(c: char) -> char {
+    if c == 'ς' { 'σ' } else { c }
+}
+
+// Written back the way it would be typed after `list`
+impl Display for ListFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if self.all {
+            parts.push("all".to_string());
+        }
+        if let Some(status) = self.status {
+            parts.push(status.to_string().to_lowercase());
+        }
+        if self.blocked {
+            parts.push("blocked".to_string());
+        }
+        for tag in &self.tags {
+            parts.push(format!("tag:{}", tag));
+        }
+        for project in &self.projects {
+            parts.push(format!("+{}", project));
+        }
+        for context in &self.contexts {
+            parts.push(format!("@{}", context));
+        }
+        if let Some(person) = &self.waiting_on {
+            parts.push(format!("waiting-on:{}", person));
+        }
+        for term in &self.text {
+            parts.push(format!("\"{}\"", term));
+        }
+        parts.extend(self.names.iter().cloned());
+        if let Some(age) = self.untouched {
+            let seconds = age.as_secs();
+            let day = SECONDS_PER_DAY as u64;
+            if seconds % day == 0 {
+                parts.push(format!("--stale {}d", seconds / day));
+            } else {
+                parts.push(format!("--stale {}m", seconds / 60));
+            }
+        }
+        if self.recent {
+            parts.push("--recent".to_string());
+        }
+        if self.by_due {
+            parts.push("--by-due".to_string());
+        }
+        if self.absolute {
+            parts.push("--absolute".to_string());
+        }
+        if self.grouped {
+            parts.push("--grouped".to_string());
+        }
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
+/// Deletions kept in the trash before the oldest are dropped
+pub const TRASH_LIMIT: usize = 50;
+
+/// Tasks listed under the startup reminder
+pub const REMINDER_LIMIT: usize = 3;
+
+/// Open tasks delegated to one person, for `waiting`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Delegated<'a> {
+    /// The name as given on the first of the tasks in list order; names
+    /// differing only in case are one person
+    pub person: String,
+    /// Task numbers with their tasks and how long each has waited since it
+    /// was delegated, longest first. A task delegated by editing the file,
+    /// without a time, has none.
+    pub tasks: Vec<(usize, &'a Task, Option<Duration>)>,
+}
+
+/// Tasks sharing one description; numbers are from before the merge
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateGroup {
+    pub description: String,
+    pub kept: usize,
+    pub removed: Vec<usize>,
+}
+
+/// Overdue and due-today summary shown when the app starts
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reminders<'a> {
+    pub overdue: usize,
+    pub due_today: usize,
+    /// Most overdue first, at most REMINDER_LIMIT tasks
+    pub top: Vec<(usize, &'a Task)>,
+}
+
+impl Reminders<'_> {
+    /// True when nothing is overdue or due today
+    pub fn is_empty(&self) -> bool {
+        self.overdue == 0 && self.due_today == 0
+    }
+}
+
+/// Open tasks due on one day of an [`Agenda`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AgendaDay<'a> {
+    pub date: Date,
+    /// In list order
+    pub tasks: Vec<(usize, &'a Task)>,
+}
+
+/// Open tasks laid out by due date over a number of days, for `agenda`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Agenda<'a> {
+    /// Due before the first day, most overdue first
+    pub overdue: Vec<(usize, &'a Task)>,
+    /// One entry per day from the first, days with nothing due included
+    pub days: Vec<AgendaDay<'a>>,
+    /// Due after the last day, earliest first
+    pub later: Vec<(usize, &'a Task)>,
+    /// Open tasks without a due date
+    pub undated: usize,
+}
+
+/// The oldest task that is still open
+#[derive(Debug, Clone, PartialEq)]
+pub struct OpenTask {
+    pub index: usize,
+    pub description: String,
+    pub created_at: Timestamp,
+    pub days_open: i64,
+}
+
+/// A task's due date and time before [`TodoList::snooze`], if it had one,
+/// and after
+pub type Snoozed = (Option<(Date, Option<Time>)>, (Date, Option<Time>));
+
+/// Tasks added and completed on one local day
+#[derive(Debug, Clone, PartialEq)]
+pub struct DayActivity {
+    pub date: Date,
+    pub added: usize,
+    pub completed: usize,
+}
+
+/// Activity over the seven local days ending today, for `week`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeeklySummary {
+    /// Oldest day first
+    pub days: Vec<DayActivity>,
+    pub added: usize,
+    pub completed: usize,
+    /// Tasks added this week that are still open
+    pub still_open: usize,
+    /// Completed share of the tasks that are not cancelled, over the whole list
+    pub completion_rate: f64,
+}
+
+/// Runs of consecutive local days with at least one completion
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Streaks {
+    /// Run ending today, or yesterday while today has nothing done yet
+    pub current: usize,
+    pub best: usize,
+}
+
+/// Snapshot of the list's numbers, shared by `stats` and reports
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TodoStats {
+    pub total: usize,
+    pub todo: usize,
+    /// Parked with `wait`, counted apart from todo
+    pub waiting: usize,
+    pub in_progress: usize,
+    pub completed: usize,
+    pub cancelled: usize,
+    /// Completions by local day; tasks from older versions have no timestamp
+    pub completed_today: usize,
+    pub completed_this_week: usize,
+    pub longest_open: Option<OpenTask>,
+    /// Tracked time over all tasks, running timers included
+    pub time_spent: Duration,
+    /// Estimated time of the tasks with each status, in [`Status::ALL`] order
+    pub estimated: [Estimate; 5],
+}
+
+impl TodoStats {
+    /// Share of all tasks, 0 for an empty list
+    pub fn percent(&self, count: usize) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            count as f64 * 100.0 / self.total as f64
+        }
+    }
+
+    /// Tasks that count towards progress; cancelled ones never get done
+    pub fn active(&self) -> usize {
+        self.total - self.cancelled
+    }
+
+    /// Estimated time of the tasks with this status
+    pub fn estimated(&self, status: Status) -> Estimate {
+        let position = Status::ALL.iter().position(|&each| each == status);
+        position.map_or(Estimate::default(), |i| self.estimated[i])
+    }
+
+    /// Estimated time of the tasks still to finish: todo, waiting and in progress
+    pub fn estimated_left(&self) -> Estimate {
+        [Status::Todo, Status::Waiting, Status::InProgress]
+            .into_iter()
+            .map(|status| self.estimated(status))
+            .sum()
+    }
+}
+
+/// The tasks in the order they are shown, with their templates, saved
+/// filters and history.
+/// Methods taking a task number count from 1, like the CLI; indexing with
+/// `list[i]` counts from 0 like a slice.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct TodoList {
+    tasks: Vec<Task>,
+    // Task descriptions saved under a name by `template save`
+    #[serde(default)]
+    templates: BTreeMap<String, Vec<String>>,
+    // `list` filters saved under a name by `filter save`, as typed
+    #[serde(default)]
+    filters: BTreeMap<String, String>,
+    // Task descriptions with placeholders saved by `snippet save`
+    #[serde(default)]
+    snippets: BTreeMap<String, String>,
+    // Changes made to the list, saved to their own file
+    #[serde(skip)]
+    log: Vec<LogEntry>,
+    // Statuses in the loaded file that this version does not know
+    #[serde(skip)]
+    unknown_statuses: Vec<String>,
+    // How tasks are checked and the list is saved, from whoever loaded it
+    #[serde(skip)]
+    settings: Settings,
+}
+
+impl TodoList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every task, in list order
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    fn record(&mut self, action: &str, details: String) {
+        self.log.push(LogEntry::new(action, details));
+    }
+
+    /// Status names in the loaded file that this version does not know, one
+    /// per task or subtask, in file order. Those tasks were loaded as todo.
+    pub fn unknown_statuses(&self) -> &[String] {
+        &self.unknown_statuses
+    }
+
+    /// Every recorded change, oldest first
+    pub fn log(&self) -> &[LogEntry] {
+        &self.log
+    }
+
+    /// How new and edited tasks are checked and the list is saved
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Check and save by other settings from now on; tasks already in the
+    /// list are left as they are
+    pub fn set_settings(&mut self, settings: Settings) {
+        self.settings = settings;
+    }
+
+    /// Replace the recorded changes, e.g. with those read from disk
+    pub fn set_log(&mut self, log: Vec<LogEntry>) {
+        self.log = log;
+    }
+
+    /// Forget the history, returning how many entries there were
+    pub fn clear_log(&mut self) -> usize {
+        std::mem::take(&mut self.log).len()
+    }
+
+    /// Add a todo task with just a description, checked by the task builder
+    pub fn add_tasks(&mut self, description: String) -> Result<(), TodoError> {
+        self.add(Task::builder(description).settings(self.settings).build()?);
+        Ok(())
+    }
+
+    /// Add a task built elsewhere, e.g. with [`Task::builder`], giving it an
+    /// ID. Returns its 1-based number.
+    pub fn add(&mut self, task: Task) -> usize {
+        self.push(task)
+    }
+
+    /// Add one task per line of a text file, skipping blank lines and
+    /// # comments. Every line is checked before anything is added; the
+    /// result is the number added and the failed lines with their errors.
+    pub fn add_from_file(
+        &mut self,
+        path: &str,
+    ) -> Result<(usize, Vec<(usize, TodoError)>), TodoError> {
+        let file = read_task_file(path, &self.settings)?;
+        let added = file.tasks.len();
+        for task in file.tasks {
+            self.push(task);
+        }
+        Ok((added, file.failed))
+    }
+
+    /// The open task whose description reads the same as `description`
+    /// once case, spacing and trailing punctuation are ignored, with its
+    /// 1-based number. Completed and cancelled tasks never match.
+    pub fn find_similar(&self, description: &str) -> Option<(usize, &Task)> {
+        let key = similar_key(description);
+        if key.is_empty() {
+            return None;
+        }
+        self.tasks
+            .iter()
+            .enumerate()
+            .find(|(_, task)| {
+                !matches!(task.status, Status::Completed | Status::Cancelled)
+                    && similar_key(&task.description) == key
+            })
+            .map(|(i, task)| (i + 1, task))
+    }
+
+    // Append a task under a fresh ID, returning its 1-based number
+    fn push(&mut self, mut task: Task) -> usize {
+        task.id = self.next_id();
+        self.record("add", format!("added '{}'", task.description));
+        self.tasks.push(task);
+        self.tasks.len()
+    }
+
+    fn next_id(&self) -> u64 {
+        self.tasks.iter().map(|task| task.id).max().unwrap_or(0) + 1
+    }
+
+    // Give IDs to tasks saved before IDs existed
+    fn assign_missing_ids(&mut self) {
+        let first = self.next_id();
+        let missing = self.tasks.iter_mut().filter(|task| task.id == 0);
+        for (id, task) in (first..).zip(missing) {
+            task.id = id;
+        }
+    }
+
+    /// Get number of tasks
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// True when there are no tasks
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Counts by status on one line, e.g. "12 tasks: 5 todo, 4 in-progress,
+    /// 3 done". Cancelled tasks are only mentioned when there are some.
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return msg!("summary.empty");
+        }
+        let count = |status| self.filter_by_status(status).count();
+        let mut summary = msg!(
+            "summary.counts",
+            total = self.len(),
+            todo = count(Status::Todo),
+            in_progress = count(Status::InProgress),
+            done = count(Status::Completed)
+        );
+        let waiting = count(Status::Waiting);
+        if waiting > 0 {
+            summary.push_str(&msg!("summary.waiting", count = waiting));
+        }
+        let cancelled = count(Status::Cancelled);
+        if cancelled > 0 {
+            summary.push_str(&msg!("summary.cancelled", count = cancelled));
+        }
+        summary
+    }
+
+    /// Every task with its 1-based number, the one the CLI shows and takes
+    pub fn list_tasks(&self) -> impl Iterator<Item = (usize, &Task)> {
+        self.tasks.iter().enumerate().map(|(i, task)| (i + 1, task))
+    }
+
+    /// Tasks with this status, with their 1-based numbers
+    pub fn filter_by_status(&self, status: Status) -> impl Iterator<Item = (usize, &Task)> {
+        self.list_tasks()
+            .filter(move |(_, task)| task.status == status)
+    }
+
+    /// Completed and cancelled tasks, with their 1-based numbers
+    pub fn closed_tasks(&self) -> impl Iterator<Item = (usize, &Task)> {
+        self.list_tasks().filter(|(_, task)| task.is_closed())
+    }
+
+    /// The tasks in list order, without their numbers
+    pub fn iter(&self) -> std::slice::Iter<'_, Task> {
+        self.tasks.iter()
+    }
+
+    /// Update task status with better error handling.
+    /// Completing a recurring task adds its next occurrence, whose index is returned.
+    /// With `strict`, a change [`Status::is_valid_transition`] doesn't allow
+    /// fails with `InvalidTransition` and leaves the task as it was.
+    pub fn update_task_status(
+        &mut self,
+        task: TaskRef,
+        new_status: Status,
+        strict: bool,
+    ) -> Result<Option<usize>, TodoError> {
+        let index = self.position(task)?;
+        let task = self.task_mut(index)?;
+        check_transition(task.status, new_status, strict)?;
+        let completing = new_status == Status::Completed && !task.is_completed();
+        task.set_status(new_status);
+        let details = format!(
+            "marked #{} '{}' {}",
+            index,
+            task.description,
+            new_status.to_string().to_lowercase()
+        );
+        self.record("status", details);
+        let task = &mut self.tasks[index - 1];
+
+        if completing && task.recurrence.is_some() {
+            let next = task.next_occurrence(Date::today());
+            // The open copy carries the recurrence on from here
+            task.recurrence = None;
+            return Ok(Some(self.push(next)));
+        }
+        Ok(None)
+    }
+
+    /// Give every task with the `filter` status (or every task at all) the new
+    /// status, returning how many changed. Tasks already there are left alone;
+    /// recurring tasks that get completed add their next occurrence as usual.
+    /// With `strict`, so are tasks the change isn't allowed from; see
+    /// [`update_all_skipped`](Self::update_all_skipped) for how many.
+    pub fn update_all_status(
+        &mut self,
+        filter: Option<Status>,
+        new_status: Status,
+        strict: bool,
+    ) -> usize {
+        let tasks: Vec<TaskRef> = self
+            .update_all_targets(filter, new_status)
+            .filter(|(_, task)| !strict || task.status.is_valid_transition(new_status))
+            .map(|(index, task)| TaskRef { id: task.id, index })
+            .collect();
+        for &task in &tasks {
+            let _ = self.update_task_status(task, new_status, false);
+        }
+        tasks.len()
+    }
+
+    /// How many tasks a strict [`update_all_status`](Self::update_all_status)
+    /// would leave alone for not being allowed the change
+    pub fn update_all_skipped(&self, filter: Option<Status>, new_status: Status) -> usize {
+        self.update_all_targets(filter, new_status)
+            .filter(|(_, task)| !task.status.is_valid_transition(new_status))
+            .count()
+    }
+
+    fn update_all_targets(
+        &self,
+        filter: Option<Status>,
+        new_status: Status,
+    ) -> impl Iterator<Item = (usize, &Task)> {
+        self.list_tasks()
+            .filter(move |(_, task)| task.status != new_status)
+            .filter(move |(_, task)| filter.is_none_or(|status| task.status == status))
+    }
+
+    /// Update a task or one of its subtasks
+    pub fn update_status_at(
+        &mut self,
+        at: TaskIndex,
+        new_status: Status,
+        strict: bool,
+    ) -> Result<Option<usize>, TodoError> {
+        match at.sub {
+            None => self.update_task_status(self.task_ref(at.task)?, new_status, strict),
+            Some(_) => {
+                let task = self.get_mut(at)?;
+                check_transition(task.status, new_status, strict)?;
+                task.set_status(new_status);
+                let details = format!(
+                    "marked #{} '{}' {}",
+                    at,
+                    task.description,
+                    new_status.to_string().to_lowercase()
+                );
+                self.record("status", details);
+                Ok(None)
+            }
+        }
+    }
+
+    /// Complete an unfinished task or subtask, or reopen a completed one as
+    /// todo. Returns the new status and, as with any completion, the number
+    /// of a recurring task's next occurrence.
+    pub fn toggle_task(
+        &mut self,
+        at: TaskIndex,
+        strict: bool,
+    ) -> Result<(Status, Option<usize>), TodoError> {
+        let status = if self.get_at(at)?.is_completed() {
+            Status::Todo
+        } else {
+            Status::Completed
+        };
+        let next = self.update_status_at(at, status, strict)?;
+        Ok((status, next))
+    }
+
+    /// Take a completed task or subtask back to todo, as when it was marked
+    /// done by mistake. Strict transitions don't stand in the way, and a
+    /// recurring task adds no occurrence: the one its completion added
+    /// stays, carrying the recurrence on.
+    pub fn reopen(&mut self, at: TaskIndex) -> Result<(), TodoError> {
+        let status = self.get_at(at)?.status;
+        if status != Status::Completed {
+            return Err(TodoError::NotCompleted(at, status));
+        }
+        self.update_status_at(at, Status::Todo, false)?;
+        Ok(())
+    }
+
+    /// Completed tasks and subtasks, the latest completed first. Those
+    /// without a completion time, from older versions, come last.
+    pub fn recently_completed(&self) -> Vec<(TaskIndex, &Task)> {
+        let mut done: Vec<(TaskIndex, &Task)> = Vec::new();
+        for (index, task) in self.list_tasks() {
+            if task.is_completed() {
+                let at = TaskIndex {
+                    task: index,
+                    sub: None,
+                };
+                done.push((at, task));
+            }
+            for (sub, subtask) in task.subtasks.iter().enumerate() {
+                if subtask.is_completed() {
+                    let at = TaskIndex {
+                        task: index,
+                        sub: Some(sub + 1),
+                    };
+                    done.push((at, subtask));
+                }
+            }
+        }
+        // Stable, so of tasks completed at the same second the one further
+        // down the list, likely completed after, comes first
+        done.reverse();
+        done.sort_by_key(|(_, task)| std::cmp::Reverse(task.completed_at));
+        done
+    }
+
+    /// The task or subtask completed last, which `reopen last` reopens
+    pub fn most_recently_completed(&self) -> Option<TaskIndex> {
+        self.recently_completed()
+            .first()
+            .filter(|(_, task)| task.completed_at.is_some())
+            .map(|(at, _)| *at)
+    }
+
+    /// supports user input like: status 2 done, status 2.1 done
+    pub fn update_task_status_str(
+        &mut self,
+        at: TaskIndex,
+        status_str: &str,
+        strict: bool,
+    ) -> Result<Option<usize>, TodoError> {
+        let new_status = Status::from_str(status_str)?;
+        self.update_status_at(at, new_status, strict)
+    }
+
+    /// Add a step under a task, returning its dotted index
+    pub fn add_subtask(
+        &mut self,
+        parent: TaskRef,
+        description: String,
+    ) -> Result<TaskIndex, TodoError> {
+        let parent = self.position(parent)?;
+        let task = Task::builder(description).settings(self.settings).build()?;
+        let details = format!("added subtask '{}' to #{}", task.description, parent);
+        let subtasks = &mut self.task_mut(parent)?.subtasks;
+        subtasks.push(task);
+        let sub = subtasks.len();
+        self.record("add", details);
+        Ok(TaskIndex {
+            task: parent,
+            sub: Some(sub),
+        })
+    }
+
+    /// Make a task repeat, or stop it repeating with None
+    pub fn set_recurrence(
+        &mut self,
+        task: TaskRef,
+        recurrence: Option<Recurrence>,
+    ) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        self.task_mut(index)?.recurrence = recurrence;
+        let details = match recurrence {
+            Some(recurrence) => format!("set #{} to repeat {}", index, recurrence),
+            None => format!("stopped #{} repeating", index),
+        };
+        self.record("repeat", details);
+        Ok(())
+    }
+
+    /// Add text to the end of a description, returning the new description
+    pub fn append_description(&mut self, at: TaskIndex, text: &str) -> Result<&str, TodoError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(TodoError::EmptyDescription);
+        }
+        let settings = self.settings;
+        let task = self.get_mut(at)?;
+        task.describe(&format!("{} {}", task.description, text), &settings)?;
+        let details = format!("renamed #{} to '{}'", at, task.description);
+        self.record("edit", details);
+        Ok(&self.get_mut(at)?.description)
+    }
+
+    /// Add text to the start of a description, returning the new description
+    pub fn prepend_description(&mut self, at: TaskIndex, text: &str) -> Result<&str, TodoError> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Err(TodoError::EmptyDescription);
+        }
+        let settings = self.settings;
+        let task = self.get_mut(at)?;
+        task.describe(&format!("{} {}", text, task.description), &settings)?;
+        let details = format!("renamed #{} to '{}'", at, task.description);
+        self.record("edit", details);
+        Ok(&self.get_mut(at)?.description)
+    }
+
+    /// Set or clear a task's due date, leaving it without a time
+    pub fn set_due(&mut self, task: TaskRef, due: Option<Date>) -> Result<(), TodoError> {
+        self.set_due_at(task, due, None)
+    }
+
+    /// Set a task's due date along with the time of day it is due, or clear
+    /// both. A time without a date is dropped.
+    pub fn set_due_at(
+        &mut self,
+        task: TaskRef,
+        due: Option<Date>,
+        time: Option<Time>,
+    ) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        let task = self.task_mut(index)?;
+        task.due = due;
+        task.due_time = due.and(time);
+        let details = match (due, time) {
+            (Some(due), Some(time)) => format!("set #{} due {} {}", index, due, time),
+            (Some(due), None) => format!("set #{} due {}", index, due),
+            (None, _) => format!("cleared the due date of #{}", index),
+        };
+        self.record("due", details);
+        Ok(())
+    }
+
+    /// Park a task as Waiting, with why when a reason is given. Waiting
+    /// again replaces the reason.
+    pub fn wait(&mut self, task: TaskRef, reason: Option<String>) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        let task = self.task_mut(index)?;
+        task.set_status(Status::Waiting);
+        task.wait_reason = reason;
+        let details = match &task.wait_reason {
+            Some(reason) => format!(
+                "parked #{} '{}' waiting on {}",
+                index, task.description, reason
+            ),
+            None => format!("parked #{} '{}' as waiting", index, task.description),
+        };
+        self.record("status", details);
+        Ok(())
+    }
+
+    /// Note who a task waits on and park it as Waiting, or with None stop
+    /// waiting on anyone, leaving its status as it is. Delegating again to
+    /// the same person, in any case, keeps when it was first delegated.
+    pub fn delegate(&mut self, task: TaskRef, person: Option<&str>) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        let person = person
+            .map(|person| {
+                normalize_person(person).ok_or_else(|| TodoError::InvalidPerson(person.to_string()))
+            })
+            .transpose()?;
+        let task = self.task_mut(index)?;
+        let details = match person {
+            Some(person) => {
+                if task.is_completed() {
+                    return Err(TodoError::AlreadyCompleted(index));
+                }
+                if !task.is_waiting_on(&person) {
+                    task.delegated_at = Some(now());
+                }
+                if task.status != Status::Waiting {
+                    task.set_status(Status::Waiting);
+                }
+                let details = format!("delegated #{} '{}' to {}", index, task.description, person);
+                task.waiting_on = Some(person);
+                details
+            }
+            None => {
+                task.waiting_on = None;
+                task.delegated_at = None;
+                format!(
+                    "#{} '{}' no longer waits on anyone",
+                    index, task.description
+                )
+            }
+        };
+        self.record("delegate", details);
+        Ok(())
+    }
+
+    /// Open tasks delegated with `delegate`, a group per person, people in
+    /// alphabetical order ignoring case. Within a group the tasks that have
+    /// waited longest as of `now` come first.
+    pub fn delegated(&self, now: Timestamp) -> Vec<Delegated<'_>> {
+        let mut groups: BTreeMap<String, Delegated> = BTreeMap::new();
+        for (index, task) in self.list_tasks() {
+            let Some(person) = task.waiting_on.as_ref().filter(|_| !task.is_closed()) else {
+                continue;
+            };
+            let waited = task
+                .delegated_at
+                .map(|at| Duration::from_secs(now.saturating_sub(at)));
+            groups
+                .entry(person.to_lowercase())
+                .or_insert_with(|| Delegated {
+                    person: person.clone(),
+                    tasks: Vec::new(),
+                })
+                .tasks
+                .push((index, task, waited));
+        }
+        let mut groups: Vec<Delegated> = groups.into_values().collect();
+        for group in &mut groups {
+            // Tasks delegated by hand without a time go last
+            group
+                .tasks
+                .sort_by_key(|&(index, _, waited)| (std::cmp::Reverse(waited), index));
+        }
+        groups
+    }
+
+    /// Set or clear a task's estimate
+    pub fn set_estimate(
+        &mut self,
+        task: TaskRef,
+        estimate: Option<Estimate>,
+    ) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        self.task_mut(index)?.estimate = estimate;
+        let details = match estimate {
+            Some(estimate) => format!("estimated #{} at {}", index, estimate),
+            None => format!("cleared the estimate of #{}", index),
+        };
+        self.record("estimate", details);
+        Ok(())
+    }
+
+    /// Give a task a marker, a row color or both, or with None take both
+    /// away
+    pub fn set_marker(&mut self, task: TaskRef, marker: Option<Marker>) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        let task = self.task_mut(index)?;
+        match marker {
+            Some(marker) => {
+                if let Some(text) = marker.text {
+                    task.marker = Some(text);
+                }
+                if let Some(color) = marker.color {
+                    task.marker_color = Some(color.name().to_string());
+                }
+            }
+            None => {
+                task.marker = None;
+                task.marker_color = None;
+            }
+        }
+        let details = match (&task.marker, &task.marker_color) {
+            (None, None) => format!("cleared the marker of #{}", index),
+            (text, color) => {
+                let parts: Vec<&str> = [text, color]
+                    .into_iter()
+                    .flatten()
+                    .map(String::as_str)
+                    .collect();
+                format!("marked #{} with {}", index, parts.join(" "))
+            }
+        };
+        self.record("mark", details);
+        Ok(())
+    }
+
+    /// Push a task's due date and time back, as [`due_later`] does. One
+    /// without a due date gets one counted from `today`, and from `now` as
+    /// well when `by` is not a whole number of days. Returns the old and new
+    /// due dates with their times.
+    pub fn snooze(
+        &mut self,
+        task: TaskRef,
+        by: Duration,
+        today: Date,
+        now: Time,
+    ) -> Result<Snoozed, TodoError> {
+        let index = self.position(task)?;
+        if self.get_task(index)?.is_completed() {
+            return Err(TodoError::AlreadyCompleted(index));
+        }
+        let task = self.task_mut(index)?;
+        let old = task.due.map(|due| (due, task.due_time));
+        let (due, time) = match old {
+            Some((due, time)) => due_later(due, time, by),
+            None if by.as_secs().is_multiple_of(SECONDS_PER_DAY as u64) => {
+                due_later(today, None, by)
+            }
+            None => due_later(today, Some(now), by),
+        };
+        task.due = Some(due);
+        task.due_time = time;
+        let details = format!("snoozed #{} to {}", index, format_due(due, time));
+        self.record("due", details);
+        Ok((old, (due, time)))
+    }
+
+    /// Tasks added and completed on each of the `count` local days ending with
+    /// `today`, oldest first. Days without activity are included; timestamps
+    /// outside the window and tasks from older versions without them are not.
+    pub fn daily_activity(&self, today: Date, count: usize) -> Vec<DayActivity> {
+        let first = today.add_days(1 - count as i64);
+        let mut days: Vec<DayActivity> = (0..count)
+            .map(|offset| DayActivity {
+                date: first.add_days(offset as i64),
+                added: 0,
+                completed: 0,
+            })
+            .collect();
+        // Position of a timestamp's local day in `days`, if it is one of them
+        let slot = |timestamp: Timestamp| {
+            usize::try_from(first.days_until(Date::from_timestamp(timestamp)))
+                .ok()
+                .filter(|&offset| offset < count)
+        };
+
+        for task in &self.tasks {
+            if let Some(offset) = task.created_at.and_then(slot) {
+                days[offset].added += 1;
+            }
+            if let Some(offset) = task.completed_at.and_then(slot) {
+                days[offset].completed += 1;
+            }
+        }
+        days
+    }
+
+    /// Seven days of activity ending on the local day of `now`
+    pub fn weekly_summary(&self, now: Timestamp) -> WeeklySummary {
+        let days = self.daily_activity(Date::from_timestamp(now), 7);
+        let first = days[0].date;
+        let still_open = self
+            .tasks
+            .iter()
+            .filter(|task| !task.is_closed())
+            .filter_map(|task| task.created_at)
+            .filter(|&created_at| Date::from_timestamp(created_at) >= first)
+            .count();
+
+        let stats = self.statistics_at(now);
+        let completion_rate = if stats.active() == 0 {
+            0.0
+        } else {
+            stats.completed as f64 * 100.0 / stats.active() as f64
+        };
+        WeeklySummary {
+            added: days.iter().map(|day| day.added).sum(),
+            completed: days.iter().map(|day| day.completed).sum(),
+            days,
+            still_open,
+            completion_rate,
+        }
+    }
+
+    /// Completion streaks as of `today`, worked out from the completion times
+    /// so they can never disagree with the tasks. Completions that were
+    /// cleared or archived no longer count.
+    pub fn streaks(&self, today: Date) -> Streaks {
+        let mut days: Vec<Date> = self
+            .tasks
+            .iter()
+            .filter_map(|task| task.completed_at)
+            .map(Date::from_timestamp)
+            .filter(|&day| day <= today)
+            .collect();
+        days.sort();
+        days.dedup();
+
+        let mut streaks = Streaks::default();
+        let mut run = 0;
+        let mut previous: Option<Date> = None;
+        for &day in &days {
+            run = match previous {
+                Some(previous) if previous.add_days(1) == day => run + 1,
+                _ => 1,
+            };
+            streaks.best = streaks.best.max(run);
+            previous = Some(day);
+        }
+        if previous.is_some_and(|last| last.days_until(today) <= 1) {
+            streaks.current = run;
+        }
+        streaks
+    }
+
+    /// Templates by name, in name order
+    pub fn templates(&self) -> &BTreeMap<String, Vec<String>> {
+        &self.templates
+    }
+
+    /// Store the descriptions of these tasks under a name, replacing any
+    /// template of that name. Returns whether one was replaced.
+    pub fn save_template(&mut self, name: &str, indices: &[usize]) -> Result<bool, TodoError> {
+        let name = normalize_template_name(name)?;
+        let mut descriptions = Vec::new();
+        for &index in indices {
+            descriptions.push(self.get_task(index)?.description.clone());
+        }
+        let details = format!(
+            "saved template '{}' with {} task(s)",
+            name,
+            descriptions.len()
+        );
+        let replaced = self.templates.insert(name, descriptions).is_some();
+        self.record("template", details);
+        Ok(replaced)
+    }
+
+    /// Add a fresh Todo task for each description in a template,
+    /// returning how many were added
+    pub fn apply_template(&mut self, name: &str) -> Result<usize, TodoError> {
+        let name = normalize_template_name(name)?;
+        let descriptions = self
+            .templates
+            .get(&name)
+            .ok_or(TodoError::UnknownTemplate(name))?;
+        let tasks = descriptions
+            .iter()
+            .map(|description| {
+                Task::builder(description.as_str())
+                    .settings(self.settings)
+                    .build()
+            })
+            .collect::<Result<Vec<Task>, TodoError>>()?;
+        let added = tasks.len();
+        for task in tasks {
+            self.push(task);
+        }
+        Ok(added)
+    }
+
+    /// Forget a template, returning its descriptions
+    pub fn delete_template(&mut self, name: &str) -> Result<Vec<String>, TodoError> {
+        let name = normalize_template_name(name)?;
+        let descriptions = self
+            .templates
+            .remove(&name)
+            .ok_or_else(|| TodoError::UnknownTemplate(name.clone()))?;
+        self.record("template", format!("deleted template '{}'", name));
+        Ok(descriptions)
+    }
+
+    /// Saved filters by name, in name order, each as it was typed
+    pub fn filters(&self) -> &BTreeMap<String, String> {
+        &self.filters
+    }
+
+    /// Keep a filter for `list` under a name, replacing any filter of that
+    /// name. The filter is stored as given and only parsed when used.
+    /// Returns whether one was replaced.
+    pub fn save_filter(&mut self, name: &str, filter: &str) -> Result<bool, TodoError> {
+        let name = normalize_filter_name(name)?;
+        let details = format!("saved filter '{}' as '{}'", name, filter);
+        let replaced = self.filters.insert(name, filter.to_string()).is_some();
+        self.record("filter", details);
+        Ok(replaced)
+    }
+
+    /// Forget a saved filter, returning what it held
+    pub fn delete_filter(&mut self, name: &str) -> Result<String, TodoError> {
+        let name = name.to_lowercase();
+        let filter = self
+            .filters
+            .remove(&name)
+            .ok_or_else(|| TodoError::UnknownSavedFilter(name.clone()))?;
+        self.record("filter", format!("deleted filter '{}'", name));
+        Ok(filter)
+    }
+
+    /// Snippets by name, in name order
+    pub fn snippets(&self) -> &BTreeMap<String, String> {
+        &self.snippets
+    }
+
+    /// Keep a description with `{placeholders}` under a name, replacing any
+    /// snippet of that name. Returns whether one was replaced.
+    pub fn save_snippet(&mut self, name: &str, text: &str) -> Result<bool, TodoError> {
+        let name = normalize_snippet_name(name)?;
+        snippet::placeholders(text)?;
+        let details = format!("saved snippet '{}' as '{}'", name, text);
+        let replaced = self.snippets.insert(name, text.to_string()).is_some();
+        self.record("snippet", details);
+        Ok(replaced)
+    }
+
+    /// Forget a snippet, returning its text
+    pub fn delete_snippet(&mut self, name: &str) -> Result<String, TodoError> {
+        let name = normalize_snippet_name(name)?;
+        let text = self
+            .snippets
+            .remove(&name)
+            .ok_or_else(|| TodoError::UnknownSnippet(name.clone()))?;
+        self.record("snippet", format!("deleted snippet '{}'", name));
+        Ok(text)
+    }
+
+    /// The description a snippet makes with these values, see
+    /// [`snippet::expand`]
+    pub fn expand_snippet(
+        &self,
+        name: &str,
+        values: &[(String, String)],
+    ) -> Result<String, TodoError> {
+        let name = normalize_snippet_name(name)?;
+        let text = self
+            .snippets
+            .get(&name)
+            .ok_or(TodoError::UnknownSnippet(name))?;
+        snippet::expand(text, values)
+    }
+
+    /// Saved filters `list` can no longer reach because their names are now
+    /// its own words, as when a newer version took the word for a status
+    pub fn shadowed_filters(&self) -> Vec<&str> {
+        self.filters
+            .keys()
+            .map(String::as_str)
+            .filter(|name| is_filter_keyword(name))
+            .collect()
+    }
+
+    /// Start timing a task at `now`, stopping any other running timer.
+    /// Returns the number of the task whose timer was stopped.
+    pub fn start_timer(
+        &mut self,
+        task: TaskRef,
+        now: Timestamp,
+    ) -> Result<Option<usize>, TodoError> {
+        let index = self.position(task)?;
+        if self.tasks[index - 1].is_completed() {
+            return Err(TodoError::AlreadyCompleted(index));
+        }
+        if self.tasks[index - 1].timer_started.is_some() {
+            return Ok(None);
+        }
+        let stopped = self.stop_timer(now).map(|(stopped, _)| stopped);
+        self.task_mut(index)?.timer_started = Some(now);
+        self.record("timer", format!("started the timer on #{}", index));
+        Ok(stopped)
+    }
+
+    /// Stop the running timer, returning its task and the session's length
+    pub fn stop_timer(&mut self, now: Timestamp) -> Option<(usize, Duration)> {
+        let index = self.running_timer()?;
+        let session = self.task_mut(index).ok()?.stop_timer(now)?;
+        self.record(
+            "timer",
+            format!(
+                "stopped the timer on #{} after {}",
+                index,
+                format_duration(session)
+            ),
+        );
+        Some((index, session))
+    }
+
+    /// Add time spent outside the timer, such as a focus session
+    pub fn log_time(&mut self, task: TaskRef, spent: Duration) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        self.task_mut(index)?.accumulated += spent;
+        self.record(
+            "timer",
+            format!("logged {} on #{}", format_duration(spent), index),
+        );
+        Ok(())
+    }
+
+    /// Replace a task's description
+    pub fn set_description(&mut self, task: TaskRef, description: String) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        let settings = self.settings;
+        let task = self.task_mut(index)?;
+        task.describe(&description, &settings)?;
+        let details = format!("renamed #{} to '{}'", index, task.description);
+        self.record("edit", details);
+        Ok(())
+    }
+
+    /// Current number of the task with this ID
+    pub fn index_of(&self, id: u64) -> Option<usize> {
+        self.tasks
+            .iter()
+            .position(|task| task.id == id)
+            .map(|i| i + 1)
+    }
+
+    /// Number of the task being timed, if any
+    pub fn running_timer(&self) -> Option<usize> {
+        self.tasks
+            .iter()
+            .position(|task| task.timer_started.is_some())
+            .map(|i| i + 1)
+    }
+
+    /// Open tasks due on or before `date`, earliest first
+    pub fn due_on_or_before(&self, date: Date) -> Vec<(usize, &Task)> {
+        let mut tasks: Vec<(usize, &Task)> = self
+            .list_tasks()
+            .filter(|(_, task)| !task.is_closed() && task.due.is_some_and(|due| due <= date))
+            .collect();
+        tasks.sort_by_key(|(_, task)| task.due_order());
+        tasks
+    }
+
+    /// How many tasks have this status
+    pub fn count_status(&self, status: Status) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| task.status == status)
+            .count()
+    }
+
+    /// How many open tasks were due before `today`
+    pub fn count_overdue(&self, today: Date) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| !task.is_closed() && task.due.is_some_and(|due| due < today))
+            .count()
+    }
+
+    /// How many open tasks are due on `date`
+    pub fn count_due_on(&self, date: Date) -> usize {
+        self.tasks
+            .iter()
+            .filter(|task| !task.is_closed() && task.due == Some(date))
+            .count()
+    }
+
+    /// Open tasks by due day for the `days` days from `start`, with what is
+    /// overdue before them, what is due after them and how many have no date
+    pub fn agenda(&self, start: Date, days: usize) -> Agenda<'_> {
+        let mut agenda = Agenda {
+            overdue: Vec::new(),
+            days: (0..days)
+                .map(|n| AgendaDay {
+                    date: start.add_days(n as i64),
+                    tasks: Vec::new(),
+                })
+                .collect(),
+            later: Vec::new(),
+            undated: 0,
+        };
+        for (index, task) in self.list_tasks().filter(|(_, task)| !task.is_closed()) {
+            let Some(due) = task.due else {
+                agenda.undated += 1;
+                continue;
+            };
+            match start.days_until(due) {
+                ahead if ahead < 0 => agenda.overdue.push((index, task)),
+                ahead if ahead as usize >= days => agenda.later.push((index, task)),
+                ahead => agenda.days[ahead as usize].tasks.push((index, task)),
+            }
+        }
+        for day in &mut agenda.days {
+            day.tasks.sort_by_key(|(_, task)| task.due_order());
+        }
+        agenda.overdue.sort_by_key(|(_, task)| task.due_order());
+        agenda.later.sort_by_key(|(_, task)| task.due_order());
+        agenda
+    }
+
+    /// What to remind about at startup: counts plus the most pressing few tasks
+    pub fn reminders(&self, today: Date) -> Reminders<'_> {
+        let pressing = self.due_on_or_before(today);
+        let due_today = pressing
+            .iter()
+            .filter(|(_, task)| task.due == Some(today))
+            .count();
+        Reminders {
+            overdue: pressing.len() - due_today,
+            due_today,
+            top: pressing.into_iter().take(REMINDER_LIMIT).collect(),
+        }
+    }
+
+    /// Up to `count` open tasks to work on next, best first: in-progress
+    /// before todo, then the earliest due date, then the oldest. Blocked
+    /// tasks cannot be started and are left out. Ties go to the lower
+    /// number, so the same list always gives the same suggestion.
+    pub fn suggest_next(&self, count: usize) -> Vec<(usize, &Task)> {
+        let mut tasks: Vec<(usize, &Task)> = self
+            .list_tasks()
+            .filter(|(_, task)| !task.is_closed() && !self.is_blocked(task))
+            .filter(|(_, task)| task.status != Status::Waiting)
+            .collect();
+        // No due date comes after any date; no creation time counts as the
+        // oldest, since only tasks from older versions lack one
+        tasks.sort_by_key(|(index, task)| {
+            (
+                task.status != Status::InProgress,
+                task.due.is_none(),
+                task.due_order(),
+                task.created_at,
+                *index,
+            )
+        });
+        tasks.truncate(count);
+        tasks
+    }
+
+    /// Pick an open task matching `filter` at random, each one as likely as
+    /// the others. The task with the id `avoid`, say the last pick, is left
+    /// out unless nothing else matches. None when no open task matches.
+    pub fn pick_random(
+        &self,
+        filter: &ListFilter,
+        rng: &mut impl Rng,
+        avoid: Option<u64>,
+    ) -> Option<usize> {
+        let mut candidates: Vec<(usize, &Task)> = self
+            .filter(filter)
+            .into_iter()
+            .filter(|(_, task)| !task.is_closed())
+            .collect();
+        if candidates.len() > 1 {
+            candidates.retain(|(_, task)| Some(task.id) != avoid);
+        }
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(candidates[rng.below(candidates.len())].0)
+    }
+
+    /// Numbers of the tasks left in Todo for more than `threshold` days by
+    /// `now`, as [`Task::is_stale`] decides. Started, completed and
+    /// cancelled tasks never are.
+    pub fn stale_tasks(&self, now: Timestamp, threshold: u64) -> Vec<usize> {
+        self.list_tasks()
+            .filter(|(_, task)| task.is_stale(now, threshold))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Raise the priority of each stale task one level, returning the
+    /// numbers of those raised. A raised task counts as fresh again until
+    /// another `threshold` days go by; one already urgent stays stale.
+    pub fn escalate(&mut self, now: Timestamp, threshold: u64) -> Vec<usize> {
+        let mut raised = Vec::new();
+        for index in self.stale_tasks(now, threshold) {
+            // The list aging a task is no change anyone made, so it is left
+            // untouched: `list --stale` should still find it
+            let task = &mut self.tasks[index - 1];
+            if let Some(priority) = task.priority.raised() {
+                task.priority = priority;
+                task.escalated_at = Some(now);
+                raised.push(index);
+            }
+        }
+        if !raised.is_empty() {
+            let numbers: Vec<String> = raised.iter().map(|index| format!("#{}", index)).collect();
+            self.record("escalate", format!("raised {}", numbers.join(", ")));
+        }
+        raised
+    }
+
+    /// Numbers of the tasks whose descriptions are over the length cap, as
+    /// can happen with files saved before the cap or under a higher one
+    pub fn too_long(&self) -> Vec<usize> {
+        self.list_tasks()
+            .filter(|(_, task)| check_length(&task.description, self.settings.max_length).is_err())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Look up a task by its 1-based number
+    pub fn get_task(&self, index: usize) -> Result<&Task, TodoError> {
+        self.validate_index(index)?;
+        Ok(&self.tasks[index - 1])
+    }
+
+    /// The task numbered `index` in the whole list
+    pub fn task_ref(&self, index: usize) -> Result<TaskRef, TodoError> {
+        let task = self.get_task(index)?;
+        Ok(TaskRef { id: task.id, index })
+    }
+
+    /// The task with this ID, wherever it is in the list now
+    pub fn ref_by_id(&self, id: u64) -> Result<TaskRef, TodoError> {
+        let index = self.index_of(id).ok_or(TodoError::UnknownId(id))?;
+        Ok(TaskRef { id, index })
+    }
+
+    /// The task numbered `index`, as long as `view` shows it. Views number
+    /// tasks as the whole list does, so a number read off one page of
+    /// `list` means the same task here.
+    pub fn ref_in(&self, view: &ListFilter, index: usize) -> Result<TaskRef, TodoError> {
+        let task = self.task_ref(index)?;
+        if self.filter(view).iter().any(|(shown, _)| *shown == index) {
+            Ok(task)
+        } else {
+            Err(TodoError::NotInView(index))
+        }
+    }
+
+    /// The one task whose description contains `text`
+    pub fn ref_matching(&self, text: &str) -> Result<TaskRef, TodoError> {
+        match self.matching(text).as_slice() {
+            [] => Err(TodoError::NoMatch(text.to_string())),
+            [(index, task)] => Ok(TaskRef {
+                id: task.id,
+                index: *index,
+            }),
+            matches => Err(TodoError::AmbiguousMatch(text.to_string(), matches.len())),
+        }
+    }
+
+    /// The task a [`TaskRef`] found, if it is still in the list
+    pub fn get(&self, task: TaskRef) -> Result<&Task, TodoError> {
+        Ok(&self.tasks[self.position(task)? - 1])
+    }
+
+    /// Tasks whose description contains `text`, ignoring case
+    pub fn matching(&self, text: &str) -> Vec<(usize, &Task)> {
+        let text = text.to_lowercase();
+        self.list_tasks()
+            .filter(|(_, task)| task.description.to_lowercase().contains(&text))
+            .collect()
+    }
+
+    /// Turn a selector into an index; text has to match exactly one task
+    pub fn select(&self, selector: &Selector) -> Result<TaskIndex, TodoError> {
+        match selector {
+            Selector::Index(index) => Ok(*index),
+            Selector::Text(text) => Ok(TaskIndex {
+                task: self.ref_matching(text)?.index,
+                sub: None,
+            }),
+            // Only someone at a terminal can answer the menu, see menu::select_task
+            Selector::Pick(_) => Err(TodoError::NotInteractive),
+        }
+    }
+
+    /// Like [`select`](TodoList::select), for commands that change a whole
+    /// task and so turn down subtasks
+    pub fn select_task(&self, selector: &Selector) -> Result<TaskRef, TodoError> {
+        match self.select(selector)? {
+            TaskIndex { task, sub: None } => self.task_ref(task),
+            index => Err(TodoError::SubtaskNotAllowed(index)),
+        }
+    }
+
+    /// A task or a subtask by its dotted index
+    pub fn get_at(&self, at: TaskIndex) -> Result<&Task, TodoError> {
+        let task = self.get_task(at.task)?;
+        match at.sub {
+            None => Ok(task),
+            Some(0) => Err(TodoError::InvalidIndex),
+            Some(sub) => task
+                .subtasks
+                .get(sub - 1)
+                .ok_or(TodoError::SubtaskOutOfBound(at.task, sub)),
+        }
+    }
+
+    /// Remove a task
+    pub fn remove_task(&mut self, task: TaskRef) -> Result<Task, TodoError> {
+        let index = self.position(task)?;
+        let task = self.tasks.remove(index - 1);
+        self.prune_dependencies();
+        self.record("remove", format!("removed '{}'", task.description));
+        Ok(task)
+    }
+
+    /// Remove a task together with its subtasks, or a single subtask
+    pub fn remove_at(&mut self, at: TaskIndex) -> Result<Task, TodoError> {
+        match at.sub {
+            None => self.remove_task(self.task_ref(at.task)?),
+            Some(sub) => {
+                self.get_mut(at)?;
+                let task = self.tasks[at.task - 1].subtasks.remove(sub - 1);
+                self.record("remove", format!("removed subtask '{}'", task.description));
+                Ok(task)
+            }
+        }
+    }
+
+    /// Move completed tasks out of the list, returning them in list order
+    pub fn archive_completed(&mut self) -> Vec<Task> {
+        self.archive_where(Task::is_completed)
+    }
+
+    /// Numbers of the completed tasks finished more than `days` days before
+    /// `today`, which `--auto-archive-after` moves to the archive. A task
+    /// done exactly `days` days ago stays, as does one with no completion
+    /// time, since nothing says how long ago it was done.
+    pub fn archivable(&self, today: Date, days: u64) -> Vec<usize> {
+        self.list_tasks()
+            .filter(|(_, task)| {
+                task.is_completed()
+                    && task.completed_at.is_some_and(|completed_at| {
+                        Date::from_timestamp(completed_at).days_until(today) > days as i64
+                    })
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Move the tasks with the given numbers out of the list, returning them
+    /// in list order. Numbers past the end are ignored.
+    pub fn archive_tasks(&mut self, numbers: &[usize]) -> Vec<Task> {
+        let ids: HashSet<u64> = numbers
+            .iter()
+            .filter_map(|&index| self.tasks.get(index.wrapping_sub(1)))
+            .map(|task| task.id)
+            .collect();
+        self.archive_where(|task| ids.contains(&task.id))
+    }
+
+    fn archive_where(&mut self, archived: impl Fn(&Task) -> bool) -> Vec<Task> {
+        let (archived, kept) = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .partition(|task| archived(task));
+        self.tasks = kept;
+        self.prune_dependencies();
+        if !archived.is_empty() {
+            self.record("archive", format!("archived {} task(s)", archived.len()));
+        }
+        archived
+    }
+
+    /// Add tasks as they are, keeping their IDs and timestamps
+    pub fn append(&mut self, tasks: Vec<Task>) {
+        self.tasks.extend(tasks);
+    }
+
+    /// Drop the oldest tasks until at most `limit` remain
+    pub fn keep_newest(&mut self, limit: usize) {
+        let excess = self.tasks.len().saturating_sub(limit);
+        self.tasks.drain(..excess);
+    }
+
+    /// Put a previously removed task back at the end of the list, giving it
+    /// a fresh ID if its old one has been reused meanwhile
+    pub fn restore(&mut self, mut task: Task) -> usize {
+        if self.tasks.iter().any(|other| other.id == task.id) {
+            task.id = self.next_id();
+        }
+        task.touch();
+        self.record("restore", format!("restored '{}'", task.description));
+        self.tasks.push(task);
+        self.prune_dependencies();
+        self.tasks.len()
+    }
+
+    /// Bring in a copy of this list that was changed elsewhere, such as the
+    /// saved file after an edit on another machine. `base` is the version
+    /// both started from. A task changed on one side only takes that side's
+    /// version, and one changed on both keeps this list's. A task removed on
+    /// one side stays removed unless the other side changed it. Tasks added
+    /// here go last, with fresh IDs where the other side took theirs.
+    pub fn merge(&mut self, base: &TodoList, theirs: TodoList) {
+        let base_tasks: HashMap<u64, &Task> =
+            base.tasks.iter().map(|task| (task.id, task)).collect();
+        let changed = |task: &Task| base_tasks.get(&task.id) != Some(&task);
+        let positions: HashMap<u64, usize> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .map(|(i, task)| (task.id, i))
+            .collect();
+        let mut ours: Vec<Option<Task>> = std::mem::take(&mut self.tasks)
+            .into_iter()
+            .map(Some)
+            .collect();
+
+        for task in theirs.tasks {
+            // A task added on both sides under the same ID is not the same task
+            let mine = if base_tasks.contains_key(&task.id) {
+                positions.get(&task.id).and_then(|&i| ours[i].take())
+            } else {
+                None
+            };
+            match mine {
+                Some(mine) if changed(&mine) => self.tasks.push(mine),
+                Some(_) => self.tasks.push(task),
+                // Removed here and left alone there
+                None if !changed(&task) => {}
+                None => self.tasks.push(task),
+            }
+        }
+
+        // The rest of ours was added here, or removed there
+        let start = self.tasks.len();
+        let mut taken: HashSet<u64> = self.tasks.iter().map(|task| task.id).collect();
+        let mut next = taken
+            .iter()
+            .chain(ours.iter().flatten().map(|task| &task.id))
+            .max()
+            .map_or(1, |id| id + 1);
+        let mut renumbered = HashMap::new();
+        for mut task in ours.into_iter().flatten().filter(|task| changed(task)) {
+            if !taken.insert(task.id) {
+                renumbered.insert(task.id, next);
+                task.id = next;
+                taken.insert(next);
+                next += 1;
+            }
+            self.tasks.push(task);
+        }
+        for task in &mut self.tasks[start..] {
+            for id in &mut task.depends_on {
+                if let Some(&new) = renumbered.get(id) {
+                    *id = new;
+                }
+            }
+        }
+        self.prune_dependencies();
+
+        self.templates = merge_named(&self.templates, &base.templates, theirs.templates);
+        self.filters = merge_named(&self.filters, &base.filters, theirs.filters);
+        self.snippets = merge_named(&self.snippets, &base.snippets, theirs.snippets);
+        self.unknown_statuses = theirs.unknown_statuses;
+        self.record("merge", "merged changes made elsewhere".to_string());
+    }
+
+    /// Take the tasks of a [`merge`](crate::merge::merge) with another copy
+    /// of the list, read from `source`
+    pub fn apply_merge(&mut self, report: MergeReport, source: &str) {
+        self.tasks = report.tasks;
+        self.prune_dependencies();
+        self.record("merge", format!("merged '{}'", source));
+    }
+
+    /// Bring in tasks read by [`crate::import`]. A task whose
+    /// [`Task::source`] is already in the list updates that task instead:
+    /// its description, whether it is open or done, and any tags it lacks.
+    /// Tasks that would change nothing are skipped.
+    pub fn import(&mut self, tasks: Vec<Task>, source: &str) -> ImportSummary {
+        let mut summary = ImportSummary::default();
+        for task in tasks {
+            let found = task.source.as_ref().and_then(|wanted| {
+                self.tasks
+                    .iter()
+                    .position(|existing| existing.source.as_ref() == Some(wanted))
+            });
+            let Some(i) = found else {
+                self.push(task);
+                summary.created += 1;
+                continue;
+            };
+
+            let existing = &self.tasks[i];
+            let reopened_or_closed = existing.is_closed() != task.is_closed();
+            let new_tags: Vec<String> = task
+                .tags
+                .iter()
+                .filter(|tag| !existing.tags.contains(tag))
+                .cloned()
+                .collect();
+            if existing.description == task.description
+                && !reopened_or_closed
+                && new_tags.is_empty()
+            {
+                summary.skipped += 1;
+                continue;
+            }
+            let existing = self.task_mut(i + 1).expect("position is in the list");
+            existing.description = task.description;
+            existing.projects = task.projects;
+            existing.contexts = task.contexts;
+            existing.tags.extend(new_tags);
+            if reopened_or_closed {
+                existing.set_status(task.status);
+            }
+            summary.updated += 1;
+        }
+        if summary.updated > 0 {
+            let details = format!("updated {} task(s) from '{}'", summary.updated, source);
+            self.record("import", details);
+        }
+        summary
+    }
+
+    /// Clear completed and cancelled tasks
+    pub fn clear_closed(&mut self) -> usize {
+        let ids = self.closed_tasks().map(|(_, task)| task.id).collect();
+        self.clear_ids("closed ", ids)
+    }
+
+    /// Clear every task with the given status
+    pub fn clear_by_status(&mut self, status: Status) -> usize {
+        let kind = format!("{} ", status.to_string().to_lowercase());
+        let ids = self
+            .filter_by_status(status)
+            .map(|(_, task)| task.id)
+            .collect();
+        self.clear_ids(&kind, ids)
+    }
+
+    /// Start over with an empty list
+    pub fn clear_all(&mut self) -> usize {
+        let ids = self.list_tasks().map(|(_, task)| task.id).collect();
+        self.clear_ids("", ids)
+    }
+
+    // Each clear takes out what its query lists, so a dry run going by the
+    // same query shows exactly what the real one removes
+    fn clear_ids(&mut self, kind: &str, ids: HashSet<u64>) -> usize {
+        let original_len = self.tasks.len();
+        self.tasks.retain(|task| !ids.contains(&task.id));
+        self.prune_dependencies();
+        let cleared = original_len - self.tasks.len();
+        if cleared > 0 {
+            self.record("clear", format!("cleared {} {}task(s)", cleared, kind));
+        }
+        cleared
+    }
+
+    /// What the [`doctor`](crate::doctor) rules find wrong with the tasks
+    pub fn problems(&self) -> Vec<Finding> {
+        doctor::check(&self.tasks)
+    }
+
+    /// Repair the problems the doctor rules can fix safely, returning the
+    /// ones repaired
+    pub fn repair(&mut self) -> Vec<Finding> {
+        let fixed = doctor::fix(&mut self.tasks);
+        if !fixed.is_empty() {
+            self.record("doctor", format!("repaired {} problem(s)", fixed.len()));
+        }
+        fixed
+    }
+
+    /// The IDs [`renumber`](TodoList::renumber) would change, as (old, new)
+    /// pairs in list order; none when they already run from 1 to n
+    pub fn renumbering(&self) -> Result<Vec<(u64, u64)>, TodoError> {
+        let blocked = doctor::duplicate_ids(&self.tasks).len()
+            + doctor::dangling_dependencies(&self.tasks).len();
+        if blocked > 0 {
+            return Err(TodoError::RenumberBlocked(blocked));
+        }
+        Ok(self
+            .tasks
+            .iter()
+            .zip(1..)
+            .filter(|(task, new)| task.id != *new)
+            .map(|(task, new)| (task.id, new))
+            .collect())
+    }
+
+    /// Give the tasks IDs 1 to n in list order, rewriting the dependencies
+    /// on them in the same step, and return the IDs that changed as
+    /// [`renumbering`](TodoList::renumbering) gives them. Turned down while
+    /// the list has duplicate IDs or dependencies on missing tasks, which
+    /// would come out pointing at the wrong tasks; `doctor --fix` repairs both.
+    pub fn renumber(&mut self) -> Result<Vec<(u64, u64)>, TodoError> {
+        let renumbered = self.renumbering()?;
+        if !renumbered.is_empty() {
+            self.rename_ids(&renumbered);
+            self.record(
+                "renumber",
+                format!("renumbered {} task(s)", renumbered.len()),
+            );
+        }
+        Ok(renumbered)
+    }
+
+    /// Follow a [`renumber`](TodoList::renumber) made on another copy of
+    /// this list, such as the one last saved
+    pub fn rename_ids(&mut self, renamed: &[(u64, u64)]) {
+        rename_ids(&mut self.tasks, renamed);
+    }
+
+    /// Merge tasks whose descriptions match ignoring case and spacing, keeping
+    /// the copy with the most advanced status (the earliest one on a tie)
+    pub fn deduplicate(&mut self, dry_run: bool) -> Vec<DuplicateGroup> {
+        let mut by_key: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut keys = Vec::new();
+        for (i, task) in self.tasks.iter().enumerate() {
+            let key = duplicate_key(&task.description);
+            let group = by_key.entry(key.clone()).or_default();
+            if group.is_empty() {
+                keys.push(key);
+            }
+            group.push(i);
+        }
+
+        let mut groups = Vec::new();
+        for key in keys {
+            let positions = &by_key[&key];
+            if positions.len() < 2 {
+                continue;
+            }
+            let kept = *positions
+                .iter()
+                .rev()
+                .max_by_key(|&&i| status_rank(self.tasks[i].status))
+                .unwrap();
+            groups.push(DuplicateGroup {
+                description: self.tasks[kept].description.clone(),
+                kept: kept + 1,
+                removed: positions
+                    .iter()
+                    .filter(|&&i| i != kept)
+                    .map(|i| i + 1)
+                    .collect(),
+            });
+        }
+        if dry_run || groups.is_empty() {
+            return groups;
+        }
+
+        // Tasks that waited on a dropped copy now wait on the kept one
+        let mut replaced: HashMap<u64, u64> = HashMap::new();
+        for group in &groups {
+            let kept_id = self.tasks[group.kept - 1].id;
+            for &index in &group.removed {
+                replaced.insert(self.tasks[index - 1].id, kept_id);
+            }
+        }
+        self.tasks.retain(|task| !replaced.contains_key(&task.id));
+        for task in &mut self.tasks {
+            let own_id = task.id;
+            let mut depends_on = Vec::new();
+            for id in &task.depends_on {
+                let id = replaced.get(id).copied().unwrap_or(*id);
+                if id != own_id && !depends_on.contains(&id) {
+                    depends_on.push(id);
+                }
+            }
+            task.depends_on = depends_on;
+        }
+        self.record("dedupe", format!("merged {} duplicate(s)", replaced.len()));
+        groups
+    }
+
+    /// Counts per status and completion activity, measured at `now`
+    pub fn statistics(&self) -> TodoStats {
+        self.statistics_at(now())
+    }
+
+    /// Numbers for the list as of `now`
+    pub fn statistics_at(&self, now: Timestamp) -> TodoStats {
+        let today = Date::from_timestamp(now);
+        let mut stats = TodoStats {
+            total: self.tasks.len(),
+            ..TodoStats::default()
+        };
+
+        for (i, task) in self.tasks.iter().enumerate() {
+            stats.time_spent += task.time_spent(now);
+            if let Some(estimate) = task.estimate
+                && let Some(i) = Status::ALL.iter().position(|&each| each == task.status)
+            {
+                stats.estimated[i] = [stats.estimated[i], estimate].into_iter().sum();
+            }
+            match task.status {
+                Status::Todo => stats.todo += 1,
+                Status::Waiting => stats.waiting += 1,
+                Status::InProgress => stats.in_progress += 1,
+                Status::Completed => stats.completed += 1,
+                Status::Cancelled => stats.cancelled += 1,
+            }
+
+            if let Some(completed_at) = task.completed_at {
+                let days_ago = Date::from_timestamp(completed_at).days_until(today);
+                if days_ago == 0 {
+                    stats.completed_today += 1;
+                }
+                if (0..7).contains(&days_ago) {
+                    stats.completed_this_week += 1;
+                }
+            }
+
+            if !task.is_closed()
+                && let Some(created_at) = task.created_at
+            {
+                let older = stats
+                    .longest_open
+                    .as_ref()
+                    .is_none_or(|open| created_at < open.created_at);
+                if older {
+                    stats.longest_open = Some(OpenTask {
+                        index: i + 1,
+                        description: task.description.clone(),
+                        created_at,
+                        days_open: Date::from_timestamp(created_at).days_until(today),
+                    });
+                }
+            }
+        }
+        stats
+    }
+
+    /// How many tasks carry each project, by name
+    pub fn project_counts(&self) -> BTreeMap<String, usize> {
+        self.token_counts(|task| &task.projects)
+    }
+
+    /// How many tasks carry each context, by name
+    pub fn context_counts(&self) -> BTreeMap<String, usize> {
+        self.token_counts(|task| &task.contexts)
+    }
+
+    fn token_counts(&self, names: impl Fn(&Task) -> &[String]) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::new();
+        for name in self.tasks.iter().flat_map(names) {
+            *counts.entry(name.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Tasks matching every criterion of the filter, in list order or most
+    /// recently changed first. Waiting tasks are only among them when the
+    /// filter asks for them, see [`ListFilter::shows_waiting`].
+    pub fn filter(&self, filter: &ListFilter) -> Vec<(usize, &Task)> {
+        let now = now();
+        let mut tasks: Vec<(usize, &Task)> = self
+            .list_tasks()
+            .filter(|(_, task)| filter.status.is_none_or(|status| task.status == status))
+            .filter(|(_, task)| filter.shows_waiting() || task.status != Status::Waiting)
+            .filter(|(_, task)| filter.matches(task))
+            .filter(|(_, task)| !filter.blocked || self.is_blocked(task))
+            .filter(|(_, task)| filter.untouched_by(task, now))
+            .collect();
+        // Tasks never touched, from older versions, come last
+        if filter.recent {
+            tasks.sort_by_key(|(_, task)| std::cmp::Reverse(task.last_touched()));
+        } else if filter.by_due {
+            tasks.sort_by_key(|(_, task)| (task.due.is_none(), task.due_order()));
+        }
+        tasks
+    }
+
+    /// How many Waiting tasks match the filter but are left out of its
+    /// result, so a listing can say that it is not the whole list
+    pub fn hidden_waiting(&self, filter: &ListFilter) -> usize {
+        if filter.shows_waiting() {
+            return 0;
+        }
+        let everything = ListFilter {
+            all: true,
+            ..filter.clone()
+        };
+        self.filter(&everything)
+            .iter()
+            .filter(|(_, task)| task.status == Status::Waiting)
+            .count()
+    }
+
+    /// Add tags to a task, returning its tags afterwards
+    pub fn add_tags(&mut self, task: TaskRef, tags: &[String]) -> Result<&[String], TodoError> {
+        let index = self.position(task)?;
+        let task = self.task_mut(index)?;
+        for tag in tags {
+            if !task.has_tag(tag) {
+                task.tags.push(tag.clone());
+            }
+        }
+        self.record("tag", format!("tagged #{} {}", index, tags.join(", ")));
+        Ok(&self.tasks[index - 1].tags)
+    }
+
+    /// Remove tags from a task, returning its tags afterwards
+    pub fn remove_tags(&mut self, task: TaskRef, tags: &[String]) -> Result<&[String], TodoError> {
+        let index = self.position(task)?;
+        let task = self.task_mut(index)?;
+        task.tags.retain(|own| !tags.contains(own));
+        self.record("tag", format!("untagged #{} {}", index, tags.join(", ")));
+        Ok(&self.tasks[index - 1].tags)
+    }
+
+    /// Record that task `index` cannot start until task `on` is completed
+    pub fn add_dependency(&mut self, task: TaskRef, on: TaskRef) -> Result<(), TodoError> {
+        let index = self.position(task)?;
+        let on = self.position(on)?;
+        let task_id = self.tasks[index - 1].id;
+        let on_id = self.tasks[on - 1].id;
+        if task_id == on_id || self.depends_on(on_id, task_id) {
+            return Err(TodoError::DependencyCycle(index, on));
+        }
+
+        let task = self.task_mut(index)?;
+        if !task.depends_on.contains(&on_id) {
+            task.depends_on.push(on_id);
+        }
+        self.record("blocks", format!("made #{} wait on #{}", index, on));
+        Ok(())
+    }
+
+    // Whether task `from` depends on task `target`, directly or through others
+    fn depends_on(&self, from: u64, target: u64) -> bool {
+        let mut stack = vec![from];
+        let mut seen = HashSet::new();
+        while let Some(id) = stack.pop() {
+            if id == target {
+                return true;
+            }
+            if !seen.insert(id) {
+                continue;
+            }
+            if let Some(task) = self.tasks.iter().find(|task| task.id == id) {
+                stack.extend(&task.depends_on);
+            }
+        }
+        false
+    }
+
+    /// Numbers of the unfinished tasks this task is waiting on
+    pub fn blockers(&self, task: &Task) -> Vec<usize> {
+        self.list_tasks()
+            .filter(|(_, other)| task.depends_on.contains(&other.id) && !other.is_closed())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Whether a task waits on dependencies that are still open
+    pub fn is_blocked(&self, task: &Task) -> bool {
+        // Most tasks wait on nothing, and need no look through the list
+        !task.depends_on.is_empty()
+            && self
+                .tasks
+                .iter()
+                .any(|other| task.depends_on.contains(&other.id) && !other.is_closed())
+    }
+
+    // Drop dependencies on tasks that no longer exist
+    fn prune_dependencies(&mut self) {
+        let ids: HashSet<u64> = self.tasks.iter().map(|task| task.id).collect();
+        for task in &mut self.tasks {
+            task.depends_on.retain(|id| ids.contains(id));
+        }
+    }
+
+    // Look up a task or subtask to change it. Every change to a task goes
+    // through here or `task_mut`, which touch it, and the task a subtask is
+    // under along with it, so no command can forget to.
+    fn get_mut(&mut self, at: TaskIndex) -> Result<&mut Task, TodoError> {
+        self.validate_index(at.task)?;
+        let task = &mut self.tasks[at.task - 1];
+        match at.sub {
+            None => {
+                task.touch();
+                Ok(task)
+            }
+            Some(0) => Err(TodoError::InvalidIndex),
+            Some(sub) if sub > task.subtasks.len() => {
+                Err(TodoError::SubtaskOutOfBound(at.task, sub))
+            }
+            Some(sub) => {
+                task.touch();
+                let subtask = &mut task.subtasks[sub - 1];
+                subtask.touch();
+                Ok(subtask)
+            }
+        }
+    }
+
+    // The number a task found earlier has now
+    fn position(&self, task: TaskRef) -> Result<usize, TodoError> {
+        self.index_of(task.id)
+            .ok_or(TodoError::TaskGone(task.index))
+    }
+
+    // A top-level task to change, see `get_mut`
+    fn task_mut(&mut self, index: usize) -> Result<&mut Task, TodoError> {
+        self.get_mut(TaskIndex {
+            task: index,
+            sub: None,
+        })
+    }
+
+    // Helper to validate index
+    fn validate_index(&self, index: usize) -> Result<(), TodoError> {
+        if index == 0 {
+            return Err(TodoError::InvalidIndex);
+        }
+        if index > self.tasks.len() {
+            return Err(TodoError::IndexOutOfBound(index));
+        }
+        Ok(())
+    }
+}
+
+/// Tasks by position, counting from **0**: `list[0]` is the task the CLI
+/// shows as number 1. Panics when out of range; methods such as
+/// [`TodoList::get_task`] take the 1-based number and return an error instead.
+impl Index<usize> for TodoList {
+    type Output = Task;
+
+    fn index(&self, position: usize) -> &Task {
+        &self.tasks[position]
+    }
+}
+
+impl<'a> IntoIterator for &'a TodoList {
+    type Item = &'a Task;
+    type IntoIter = std::slice::Iter<'a, Task>;
+
+    /// The tasks in list order; use [`TodoList::list_tasks`] for their numbers
+    fn into_iter(self) -> Self::IntoIter {
+        self.tasks.iter()
+    }
+}
+
+impl IntoIterator for TodoList {
+    type Item = Task;
+    type IntoIter = std::vec::IntoIter<Task>;
+
+    /// The tasks in list order, leaving templates and history behind
+    fn into_iter(self) -> Self::IntoIter {
+        self.tasks.into_iter()
+    }
+}
+
+/// Add tasks the way `add` does: each gets a fresh ID and an entry in the
+/// history. Tasks keep everything else, status and timestamps included.
+impl Extend<Task> for TodoList {
+    fn extend<I: IntoIterator<Item = Task>>(&mut self, tasks: I) {
+        for task in tasks {
+            self.push(task);
+        }
+    }
+}
+
+/// A new list of these tasks, added as by [`Extend`]
+impl FromIterator<Task> for TodoList {
+    fn from_iter<I: IntoIterator<Item = Task>>(tasks: I) -> Self {
+        let mut todo = TodoList::new();
+        todo.extend(tasks);
+        todo
+    }
+}
+
+// Templates, saved filters or snippets after a merge: theirs, with the ones changed
+// here put in and the ones removed here taken out, unless changed there
+fn merge_named<V: Clone + PartialEq>(
+    ours: &BTreeMap<String, V>,
+    base: &BTreeMap<String, V>,
+    mut theirs: BTreeMap<String, V>,
+) -> BTreeMap<String, V> {
+    for (name, value) in ours {
+        if base.get(name) != Some(value) {
+            theirs.insert(name.clone(), value.clone());
+        }
+    }
+    for (name, value) in base {
+        if !ours.contains_key(name) && theirs.get(name) == Some(value) {
+            theirs.remove(name);
+        }
+    }
+    theirs
+}
+
+// Descriptions compared case-insensitively with runs of spaces collapsed
+fn duplicate_key(description: &str) -> String {
+    description
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+// Like `duplicate_key`, also ignoring punctuation at the end, so "Buy milk"
+// and "buy milk!" read the same
+fn similar_key(description: &str) -> String {
+    duplicate_key(description)
+        .trim_end_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace())
+        .to_string()
+}
+
+// How far along a status is; cancelled tasks lose to any live copy
+fn status_rank(status: Status) -> u8 {
+    match status {
+        Status::Cancelled => 0,
+        Status::Waiting => 1,
+        Status::Todo => 2,
+        Status::InProgress => 3,
+        Status::Completed => 4,
+    }
+}
+
+// Write JSON to a file by way of a temporary sibling, so a failed write
+// never leaves a half-written file behind. The JSON is streamed to the file
+// rather than built up in memory first.
+pub(crate) fn write_json<T: Serialize + ?Sized>(
+    path: &str,
+    value: &T,
+    compact: bool,
+) -> Result<(), TodoError> {
+    let temporary = format!("{}.tmp", path);
+    let written = File::create(&temporary)
+        .map_err(TodoError::FileError)
+        .and_then(|file| {
+            let mut writer = BufWriter::new(file);
+            let result = if compact {
+                serde_json::to_writer(&mut writer, value)
+            } else {
+                serde_json::to_writer_pretty(&mut writer, value)
+            };
+            result.map_err(json_error)?;
+            writer.flush()?;
+            Ok(())
+        });
+    if written.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+    written?;
+    fs::rename(&temporary, path)?;
+    log!(Trace, "wrote {} by way of {}", path, temporary);
+    Ok(())
+}
+
+// Bytes read from a file at a time when loading
+const READ_BUFFER: usize = 64 * 1024;
+
+// serde_json reports failed reads and writes as its own errors. They are
+// file errors, as they were when whole files went through a String.
+fn json_error(error: serde_json::Error) -> TodoError {
+    if error.is_io() {
+        TodoError::FileError(error.into())
+    } else {
+        TodoError::SerializationError(error)
+    }
+}
+
+// The tasks as saved, with running timers checkpointed. Only those tasks
+// are copied; the rest are written as they are.
+struct Checkpointed<'a> {
+    tasks: &'a [Task],
+    now: Timestamp,
+}
+
+impl Serialize for Checkpointed<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.tasks.len()))?;
+        for task in self.tasks {
+            if task.timer_started.is_some() {
+                seq.serialize_element(&task.checkpointed(self.now))?;
+            } else {
+                seq.serialize_element(task)?;
+            }
+        }
+        seq.end()
+    }
+}
+
+// Layout of a save file with templates, saved filters or snippets, as
+// written
+#[derive(Serialize)]
+struct SaveFileRef<'a> {
+    tasks: Checkpointed<'a>,
+    templates: &'a BTreeMap<String, Vec<String>>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    filters: &'a BTreeMap<String, String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    snippets: &'a BTreeMap<String, String>,
+}
+
+// A list as written: the bare task array unless there are templates, saved
+// filters or snippets
+#[derive(Serialize)]
+#[serde(untagged)]
+enum Saved<'a> {
+    Tasks(Checkpointed<'a>),
+    WithTemplates(SaveFileRef<'a>),
+}
+
+impl TodoList {
+    // Save the list as the settings say, which may be a store's rather than
+    // its own
+    pub(crate) fn write(&self, path: &str, settings: &Settings) -> Result<(), TodoError> {
+        write_json(path, &self.saved(), settings.compact_files)
+    }
+
+    /// The list as JSON, just as [`Storable::save`] would write it
+    pub fn to_value(&self) -> Result<serde_json::Value, TodoError> {
+        Ok(serde_json::to_value(self.saved())?)
+    }
+
+    /// Whether saving this list would write the same as saving `other`: the
+    /// same tasks, templates, saved filters and snippets. The log is kept in
+    /// a file of its own and left out.
+    pub fn saves_as(&self, other: &TodoList) -> bool {
+        // Taken apart so that a field added later has to be thought about here
+        let TodoList {
+            tasks,
+            templates,
+            filters,
+            snippets,
+            log: _,
+            unknown_statuses: _,
+            settings: _,
+        } = self;
+        *tasks == other.tasks
+            && *templates == other.templates
+            && *filters == other.filters
+            && *snippets == other.snippets
+    }
+
+    fn saved(&self) -> Saved<'_> {
+        let tasks = Checkpointed {
+            tasks: &self.tasks,
+            now: now(),
+        };
+        if self.templates.is_empty() && self.filters.is_empty() && self.snippets.is_empty() {
+            Saved::Tasks(tasks)
+        } else {
+            Saved::WithTemplates(SaveFileRef {
+                tasks,
+                templates: &self.templates,
+                filters: &self.filters,
+                snippets: &self.snippets,
+            })
+        }
+    }
+
+    // The JSON that `save` would write, for sending elsewhere
+    pub(crate) fn to_json(&self) -> Result<String, TodoError> {
+        Ok(serde_json::to_string(&self.saved())?)
+    }
+}
+
+// Whether the JSON coming up is a bare task array, as older versions wrote,
+// rather than an object. Leading whitespace is skipped, nothing else read.
+fn starts_with_array(reader: &mut impl BufRead) -> std::io::Result<bool> {
+    loop {
+        let buffer = reader.fill_buf()?;
+        if buffer.is_empty() {
+            return Ok(false);
+        }
+        match buffer.iter().position(|byte| !byte.is_ascii_whitespace()) {
+            Some(start) => {
+                let array = buffer[start] == b'[';
+                reader.consume(start);
+                return Ok(array);
+            }
+            None => {
+                let length = buffer.len();
+                reader.consume(length);
+            }
+        }
+    }
+}
+
+// Layout of a save file that has more than tasks in it. Lists without
+// templates, saved filters or snippets are saved as the bare task array
+// older versions read.
+#[derive(Deserialize)]
+struct SaveFile {
+    tasks: Vec<Task>,
+    #[serde(default)]
+    templates: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    filters: BTreeMap<String, String>,
+    #[serde(default)]
+    snippets: BTreeMap<String, String>,
+}
+
+// IIMPLEMENT THE STORABLE TRAIT
+impl Storable for TodoList {
+    fn save(&self, path: &str) -> Result<(), TodoError> {
+        self.write(path, &self.settings)
+    }
+
+    fn load(path: &str) -> Result<Self, TodoError> {
+        let mut list = read_list(path)?;
+        list.assign_missing_ids();
+        Ok(list)
+    }
+}
+
+// Read a saved list as it is in the file, with tasks saved before IDs
+// existed still at 0
+pub(crate) fn read_list(path: &str) -> Result<TodoList, TodoError> {
+    log!(Trace, "reading {}", path);
+    // serde_json reads byte by byte, so a larger buffer means fewer reads
+    read_list_from(BufReader::with_capacity(READ_BUFFER, File::open(path)?))
+}
+
+// Read a list in the layout `save` writes, from anywhere
+pub(crate) fn read_list_from(mut reader: impl BufRead) -> Result<TodoList, TodoError> {
+    UNKNOWN_STATUSES.with_borrow_mut(Vec::clear);
+    let file = if starts_with_array(&mut reader)? {
+        SaveFile {
+            tasks: serde_json::from_reader(reader).map_err(json_error)?,
+            templates: BTreeMap::new(),
+            filters: BTreeMap::new(),
+            snippets: BTreeMap::new(),
+        }
+    } else {
+        serde_json::from_reader(reader).map_err(json_error)?
+    };
+    Ok(TodoList {
+        tasks: file.tasks,
+        templates: file.templates,
+        filters: file.filters,
+        snippets: file.snippets,
+        log: Vec::new(),
+        unknown_statuses: UNKNOWN_STATUSES.take(),
+        settings: Settings::default(),
+    })
+}
+
+/// Change the IDs of tasks as the (old, new) pairs say, and the dependencies
+/// on them with them. Every ID changes at once, so one task may take the
+/// ID another gives up.
+pub fn rename_ids(tasks: &mut [Task], renamed: &[(u64, u64)]) {
+    if renamed.is_empty() {
+        return;
+    }
+    let renamed: HashMap<u64, u64> = renamed.iter().copied().collect();
+    let rename = |id: &mut u64| {
+        if let Some(&new) = renamed.get(id) {
+            *id = new;
+        }
+    };
+    for task in tasks {
+        rename(&mut task.id);
+        for subtask in &mut task.subtasks {
+            subtask.depends_on.iter_mut().for_each(rename);
+        }
+        task.depends_on.iter_mut().for_each(rename);
+    }
+}
+
+// Hand each top-level task of a file in the layout `save` writes to `each`
+// as it is read, without keeping the list, for counting a large archive
+pub(crate) fn for_each_saved_task(path: &str, each: impl FnMut(Task)) -> Result<(), TodoError> {
+    log!(Trace, "reading {} task by task", path);
+    let mut reader = BufReader::with_capacity(READ_BUFFER, File::open(path)?);
+    let array = starts_with_array(&mut reader)?;
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    let read = if array {
+        EachTask(each).deserialize(&mut deserializer)
+    } else {
+        deserializer.deserialize_map(EachTask(each))
+    };
+    // Unknown statuses are only reported for the task list
+    UNKNOWN_STATUSES.with_borrow_mut(Vec::clear);
+    read.and_then(|()| deserializer.end()).map_err(json_error)
+}
+
+// Reads a task array, or the object holding one under "tasks", one task at
+// a time; the object's other keys are skipped
+struct EachTask<F>(F);
+
+impl<'de, F: FnMut(Task)> DeserializeSeed<'de> for EachTask<F> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F: FnMut(Task)> Visitor<'de> for EachTask<F> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a list of tasks")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(mut self, mut tasks: A) -> Result<(), A::Error> {
+        while let Some(task) = tasks.next_element()? {
+            (self.0)(task);
+        }
+        Ok(())
+    }
+
+    fn visit_map<A: MapAccess<'de>>(mut self, mut file: A) -> Result<(), A::Error> {
+        while let Some(key) = file.next_key::<String>()? {
+            if key == "tasks" {
+                file.next_value_seed(EachTask(&mut self.0))?;
+            } else {
+                file.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,68 @@
+use std::ops::Range;
+
+use crate::todo::ListFilter;
+
+/// Rows shown per page unless `--page-size` says otherwise
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+
+/// Pagination state for `list` and `more`, kept for the whole session
+#[derive(Debug, Clone)]
+pub struct ListView {
+    // 0 turns paging off
+    page_size: usize,
+    filter: ListFilter,
+    // Start of the next page, or None when there is nothing left to show
+    next: Option<usize>,
+}
+
+impl ListView {
+    /// A view showing `page_size` rows a page, or every row for 0
+    pub fn new(page_size: usize) -> Self {
+        ListView {
+            page_size,
+            filter: ListFilter::default(),
+            next: None,
+        }
+    }
+
+    /// Begin a fresh listing and return the rows of its first page
+    pub fn first_page(&mut self, filter: &ListFilter, total: usize) -> Range<usize> {
+        self.filter = filter.clone();
+        self.page_from(0, total)
+    }
+
+    /// Rows of the page after the last one shown, if any remain
+    pub fn next_page(&mut self, total: usize) -> Option<Range<usize>> {
+        let start = self.next?;
+        if start >= total {
+            self.next = None;
+            return None;
+        }
+        Some(self.page_from(start, total))
+    }
+
+    fn page_from(&mut self, start: usize, total: usize) -> Range<usize> {
+        let end = if self.page_size == 0 {
+            total
+        } else {
+            (start + self.page_size).min(total)
+        };
+        self.next = if end < total { Some(end) } else { None };
+        start..end
+    }
+
+    /// Filter of the listing being paged through
+    pub fn filter(&self) -> &ListFilter {
+        &self.filter
+    }
+
+    /// Whether `more` has a page left to show
+    pub fn has_more(&self) -> bool {
+        self.next.is_some()
+    }
+
+    /// Forget the cursor so stale pages are never continued
+    pub fn reset(&mut self) {
+        self.next = None;
+    }
+}
@@ -0,0 +1,155 @@
+// Display width helpers - terminals draw CJK and most emoji two cells wide,
+// combining marks and joiners take no cells at all. Text is measured and cut
+// by extended grapheme cluster, so an emoji sequence, an accented letter or a
+// syllable of a script like Devanagari is never split in two.
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+const ELLIPSIS: char = '…';
+
+/// Number of terminal cells a single character occupies, on its own
+pub fn char_width(c: char) -> usize {
+    c.width().unwrap_or(0)
+}
+
+/// Split text into the clusters a terminal draws as one, the extended
+/// grapheme clusters of Unicode: a character with the marks, selectors and
+/// skin tones after it, emoji linked by zero-width joiners, flags, Hangul
+/// syllables spelled in jamo and the like
+pub fn clusters(text: &str) -> Vec<&str> {
+    text.graphemes(true).collect()
+}
+
+// Number of terminal cells one cluster occupies. Control characters are
+// not drawn, so they take none.
+fn cluster_width(cluster: &str) -> usize {
+    if cluster.starts_with(char::is_control) {
+        0
+    } else {
+        cluster.width()
+    }
+}
+
+/// Number of terminal cells a string occupies
+pub fn display_width(text: &str) -> usize {
+    text.graphemes(true).map(cluster_width).sum()
+}
+
+/// Cut text down to at most `max` cells, ending with an ellipsis when shortened
+pub fn truncate(text: &str, max: usize) -> String {
+    if display_width(text) <= max {
+        return text.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+
+    let mut result = String::new();
+    let mut used = 0;
+    for cluster in clusters(text) {
+        let width = cluster_width(cluster);
+        if used + width > max - 1 {
+            break;
+        }
+        result.push_str(cluster);
+        used += width;
+    }
+    result.push(ELLIPSIS);
+    result
+}
+
+/// Break text into lines of at most `width` cells, at spaces where it can.
+/// Words wider than a line are split; a width of 0 is taken as 1.
+pub fn wrap(text: &str, width: usize) -> Vec<String> {
+    let width = width.max(1);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    let mut used = 0;
+    for word in text.split_whitespace() {
+        let word_width = display_width(word);
+        if used > 0 && used + 1 + word_width <= width {
+            line.push(' ');
+            line.push_str(word);
+            used += 1 + word_width;
+            continue;
+        }
+        if used > 0 {
+            lines.push(std::mem::take(&mut line));
+        }
+        let mut rest = word;
+        while display_width(rest) > width {
+            let (head, tail) = split_at_width(rest, width);
+            lines.push(head.to_string());
+            rest = tail;
+        }
+        line.push_str(rest);
+        used = display_width(rest);
+    }
+    if !line.is_empty() || lines.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+// Split off as much of the text as fits in `width` cells, but never nothing
+fn split_at_width(text: &str, width: usize) -> (&str, &str) {
+    let mut used = 0;
+    let mut end = 0;
+    for cluster in clusters(text) {
+        used += cluster_width(cluster);
+        if used > width && end > 0 {
+            return text.split_at(end);
+        }
+        end += cluster.len();
+    }
+    (text, "")
+}
+
+/// Pad text with spaces on the right up to `width` cells
+pub fn pad_right(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}", text, " ".repeat(padding))
+}
+
+/// Pad text with spaces on the left up to `width` cells
+pub fn pad_left(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}", " ".repeat(padding), text)
+}
+
+// Words of this length or shorter may be one edit away from a suggestion;
+// longer ones two
+const SHORT_WORD: usize = 3;
+
+/// How many edits a mistyped word may be from what was meant, for
+/// suggestions
+pub fn typo_limit(word: &str) -> usize {
+    if word.chars().count() <= SHORT_WORD {
+        1
+    } else {
+        2
+    }
+}
+
+/// Levenshtein distance, counting two swapped neighbouring letters as one
+/// edit like a single wrong letter: the fewest such edits turning one word
+/// into the other
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![(0..=b.len()).collect::<Vec<usize>>()];
+    for i in 1..=a.len() {
+        let mut row = vec![i];
+        for j in 1..=b.len() {
+            let substitute = rows[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitute.min(rows[i - 1][j] + 1).min(row[j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            row.push(distance);
+        }
+        rows.push(row);
+    }
+    rows[a.len()][b.len()]
+}
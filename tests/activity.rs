@@ -0,0 +1,183 @@
+mod common;
+
+use common::local_time;
+use rust_todo_cli::date::{Date, SECONDS_PER_DAY};
+use rust_todo_cli::todo::{DayActivity, Status, Task, TodoList};
+
+const LAST_SECOND: i64 = SECONDS_PER_DAY - 1;
+
+fn date(month: u32, day: u32) -> Date {
+    Date::new(2025, month, day).unwrap()
+}
+
+// 2025-06-30 is a Monday and 2025-07-06 the Sunday after it
+fn monday() -> Date {
+    date(6, 30)
+}
+
+fn sunday() -> Date {
+    date(7, 6)
+}
+
+// A task added `seconds` into the local day `added`, and completed the
+// same way when `completed` is given
+fn task(added: (Date, i64), completed: Option<(Date, i64)>) -> Task {
+    let mut task = Task::new("Task".to_string()).unwrap();
+    task.created_at = Some(local_time(added.0, added.1));
+    if let Some((day, seconds)) = completed {
+        task.status = Status::Completed;
+        task.completed_at = Some(local_time(day, seconds));
+    }
+    task
+}
+
+fn counts(days: &[DayActivity]) -> Vec<(Date, usize, usize)> {
+    days.iter()
+        .map(|day| (day.date, day.added, day.completed))
+        .collect()
+}
+
+#[test]
+fn activity_falls_on_the_local_day_either_side_of_midnight() {
+    let todo: TodoList = [
+        task((monday(), LAST_SECOND), Some((date(7, 1), 0))),
+        task((date(7, 1), 0), Some((date(7, 1), LAST_SECOND))),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(
+        counts(&todo.daily_activity(date(7, 2), 3)),
+        [(monday(), 1, 0), (date(7, 1), 1, 2), (date(7, 2), 0, 0)]
+    );
+}
+
+#[test]
+fn activity_outside_the_window_or_without_times_is_left_out() {
+    let mut undated = task((monday(), 0), None);
+    undated.created_at = None;
+    let todo: TodoList = [
+        // The day before the window, up to its last second
+        task((date(6, 29), LAST_SECOND), Some((date(6, 29), LAST_SECOND))),
+        // Later than today, from a clock that was ahead
+        task((date(7, 7), 0), Some((date(7, 7), 0))),
+        undated,
+    ]
+    .into_iter()
+    .collect();
+    let days = todo.daily_activity(sunday(), 7);
+    assert_eq!(days.len(), 7);
+    assert_eq!((days[0].date, days[6].date), (monday(), sunday()));
+    assert!(days.iter().all(|day| day.added == 0 && day.completed == 0));
+
+    // A window of fourteen days reaches back far enough
+    let days = todo.daily_activity(sunday(), 14);
+    assert_eq!(days[0].date, date(6, 23));
+    assert_eq!((days[6].added, days[6].completed), (1, 1));
+}
+
+#[test]
+fn a_week_ending_on_sunday_starts_at_monday_midnight() {
+    let todo: TodoList = [
+        // Sunday night before the week: left out
+        task((date(6, 29), LAST_SECOND), None),
+        // The first moment of the week: counted
+        task((monday(), 0), None),
+        task((date(7, 2), 3_600), Some((date(7, 3), 3_600))),
+        // The last moment of the week
+        task((sunday(), LAST_SECOND), Some((sunday(), LAST_SECOND))),
+        task((date(7, 4), 0), None),
+    ]
+    .into_iter()
+    .collect();
+    let summary = todo.weekly_summary(local_time(sunday(), LAST_SECOND));
+    assert_eq!(summary.days.len(), 7);
+    assert_eq!(summary.days[0].date, monday());
+    assert_eq!(summary.days[6].date, sunday());
+    assert_eq!((summary.added, summary.completed), (4, 2));
+    assert_eq!(summary.still_open, 2);
+    // Two of five tasks are done
+    assert_eq!(summary.completion_rate, 40.0);
+
+    // A minute into Monday, the week before has moved on by a day
+    let summary = todo.weekly_summary(local_time(date(7, 7), 60));
+    assert_eq!(summary.days[0].date, date(7, 1));
+    assert_eq!((summary.added, summary.completed), (3, 2));
+    assert_eq!(summary.still_open, 1);
+}
+
+#[test]
+fn a_week_without_activity_has_seven_empty_days() {
+    let summary = TodoList::new().weekly_summary(local_time(sunday(), 12 * 3_600));
+    assert_eq!(
+        counts(&summary.days),
+        (0..7)
+            .map(|offset| (monday().add_days(offset), 0, 0))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        (summary.added, summary.completed, summary.still_open),
+        (0, 0, 0)
+    );
+    assert_eq!(summary.completion_rate, 0.0);
+}
+
+// Tasks completed at these moments, each `seconds` into a local day
+fn completed_at(moments: &[(Date, i64)]) -> TodoList {
+    moments
+        .iter()
+        .map(|&moment| task((date(6, 1), 0), Some(moment)))
+        .collect()
+}
+
+fn streaks(todo: &TodoList, today: Date) -> (usize, usize) {
+    let streaks = todo.streaks(today);
+    (streaks.current, streaks.best)
+}
+
+#[test]
+fn no_completions_make_no_streak() {
+    assert_eq!(streaks(&TodoList::new(), sunday()), (0, 0));
+    let todo: TodoList = [task((monday(), 0), None)].into_iter().collect();
+    assert_eq!(streaks(&todo, sunday()), (0, 0));
+}
+
+#[test]
+fn midnight_splits_a_streak_into_days() {
+    // A second apart, but on two days
+    let todo = completed_at(&[(monday(), LAST_SECOND), (date(7, 1), 0)]);
+    assert_eq!(streaks(&todo, date(7, 1)), (2, 2));
+    // Several on one day count once
+    let todo = completed_at(&[(monday(), 0), (monday(), 3_600), (monday(), LAST_SECOND)]);
+    assert_eq!(streaks(&todo, monday()), (1, 1));
+}
+
+#[test]
+fn a_day_without_completions_ends_the_streak() {
+    let todo = completed_at(&[
+        (monday(), 0),
+        (date(7, 1), 0),
+        (date(7, 2), 0),
+        // Nothing on Thursday
+        (date(7, 4), 0),
+        (date(7, 5), 0),
+    ]);
+    assert_eq!(streaks(&todo, date(7, 5)), (2, 3));
+
+    // Streaks come from the tasks, so cleared ones take theirs along
+    let mut todo = todo;
+    todo.clear_closed();
+    assert_eq!(streaks(&todo, date(7, 5)), (0, 0));
+}
+
+#[test]
+fn the_current_streak_lasts_until_a_whole_day_is_missed() {
+    let todo = completed_at(&[(date(7, 3), 0), (date(7, 4), 0), (date(7, 5), 0)]);
+    // Ending today
+    assert_eq!(streaks(&todo, date(7, 5)), (3, 3));
+    // Ending yesterday, while today has nothing done yet
+    assert_eq!(streaks(&todo, sunday()), (3, 3));
+    // Gone once a day has passed without any
+    assert_eq!(streaks(&todo, date(7, 7)), (0, 3));
+    // Completions after today, from a clock that was ahead, do not count
+    assert_eq!(streaks(&todo, date(7, 4)), (2, 2));
+}
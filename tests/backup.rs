@@ -0,0 +1,280 @@
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::scratch_dir;
+use rust_todo_cli::backup::{self, BACKUP_VERSION, Bundle, FORMAT, SECTIONS};
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::outcome::execute;
+use rust_todo_cli::store::Store;
+use rust_todo_cli::todo::{Status, TodoError, TodoList};
+use serde_json::{Value, json};
+
+// A list with something in every file: tasks with a template, a saved
+// filter and a snippet, an archived task, a removed one and their history
+fn fill(store: &Store) {
+    let mut todo = TodoList::new();
+    for text in ["Buy milk", "Ship it", "Old idea", "Pack +travel"] {
+        todo.add_tasks(text.to_string()).unwrap();
+    }
+    todo.save_template("trip", &[4]).unwrap();
+    todo.save_filter("travel", "+travel").unwrap();
+    todo.save_snippet("pr", "Review PR #{num}").unwrap();
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+    store.archive(todo.archive_completed()).unwrap();
+    let removed = todo.remove_task(todo.task_ref(2).unwrap()).unwrap();
+    store.move_to_trash(vec![removed]).unwrap();
+    store.save(&todo).unwrap();
+}
+
+// The list as a session started on `store` would have it
+fn current(store: &Store) -> TodoList {
+    let mut todo = store.load().unwrap();
+    store.load_history(&mut todo).unwrap();
+    todo
+}
+
+// The file of every section as JSON, None for a missing one
+fn on_disk(store: &Store) -> Vec<Option<Value>> {
+    SECTIONS
+        .iter()
+        .map(|section| {
+            let text = fs::read_to_string((section.file)(store)).ok()?;
+            Some(serde_json::from_str(&text).unwrap())
+        })
+        .collect()
+}
+
+fn write_value(path: &Path, value: &Value) {
+    fs::write(path, serde_json::to_string(value).unwrap()).unwrap();
+}
+
+#[test]
+fn a_restored_backup_brings_back_every_file_as_it_was() {
+    let dir = scratch_dir("round-trip");
+    let store = Store::in_dir(&dir.join("app"));
+    fs::create_dir_all(dir.join("app")).unwrap();
+    fill(&store);
+    let before = on_disk(&store);
+    assert!(before.iter().all(Option::is_some), "{:?}", before);
+
+    let path = dir.join("backup.json").to_string_lossy().into_owned();
+    let bundle = backup::create(&store, &current(&store)).unwrap();
+    assert_eq!(bundle.names(), ["archive", "history", "tasks", "trash"]);
+    bundle.write(&path, &store.settings).unwrap();
+
+    // Wiped, and then something else written in its place
+    fs::remove_dir_all(dir.join("app")).unwrap();
+    fs::create_dir_all(dir.join("app")).unwrap();
+    let mut other = TodoList::new();
+    other.add_tasks("Not this".to_string()).unwrap();
+    store.mark_read();
+    store.save(&other).unwrap();
+    let replaced = on_disk(&store);
+
+    let restored = backup::restore(&store, &backup::read(&path).unwrap(), &other).unwrap();
+    assert_eq!(restored.sections, ["tasks", "archive", "trash", "history"]);
+    assert!(restored.skipped.is_empty());
+    assert_eq!(on_disk(&store), before);
+
+    // What was there before the restore is a backup of its own
+    assert_eq!(restored.previous, store.restore_backup());
+    let previous = backup::read(&restored.previous).unwrap();
+    assert_eq!(previous.sections["tasks"], replaced[0].clone().unwrap());
+    assert_eq!(previous.sections["archive"], Value::Null);
+
+    let mut todo = store.load().unwrap();
+    assert_eq!(todo.len(), 2);
+    assert!(todo.templates().contains_key("trip"));
+    assert_eq!(store.load_archive().unwrap().len(), 1);
+    assert_eq!(store.load_trash().unwrap().len(), 1);
+    store.load_history(&mut todo).unwrap();
+    assert!(!todo.log().is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn changes_not_saved_yet_are_backed_up_before_a_restore() {
+    let dir = scratch_dir("unsaved");
+    let store = Store::in_dir(&dir);
+    let mut todo = TodoList::new();
+    todo.add_tasks("one".to_string()).unwrap();
+    store.save(&todo).unwrap();
+    let path = dir.join("b.json").to_string_lossy().into_owned();
+    backup::create(&store, &todo)
+        .unwrap()
+        .write(&path, &store.settings)
+        .unwrap();
+
+    // Added in the session and never saved
+    todo.add_tasks("two".to_string()).unwrap();
+    todo.add_tasks("three".to_string()).unwrap();
+    let mine = backup::create(&store, &todo).unwrap();
+    let restored = backup::restore(&store, &backup::read(&path).unwrap(), &todo).unwrap();
+    assert_eq!(store.load().unwrap().len(), 1);
+
+    let previous = backup::read(&restored.previous).unwrap();
+    assert_eq!(previous.sections["tasks"], mine.sections["tasks"]);
+    assert_eq!(previous.sections["history"].as_array().unwrap().len(), 3);
+    // And restoring that brings them back
+    backup::restore(&store, &previous, &store.load().unwrap()).unwrap();
+    let back = store.load().unwrap();
+    let descriptions: Vec<&str> = back
+        .tasks()
+        .iter()
+        .map(|task| task.description.as_str())
+        .collect();
+    assert_eq!(descriptions, ["one", "two", "three"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn sections_from_a_later_version_are_skipped() {
+    let dir = scratch_dir("unknown");
+    let store = Store::in_dir(&dir);
+    fill(&store);
+    let before = on_disk(&store);
+    let mut bundle = backup::create(&store, &current(&store)).unwrap();
+    bundle
+        .sections
+        .insert("aliases".to_string(), json!({"ls": "list"}));
+    let path = dir.join("backup.json");
+    bundle
+        .write(path.to_str().unwrap(), &store.settings)
+        .unwrap();
+
+    let read = backup::read(path.to_str().unwrap()).unwrap();
+    let restored = backup::restore(&store, &read, &current(&store)).unwrap();
+    assert_eq!(restored.skipped, ["aliases"]);
+    assert_eq!(restored.sections.len(), SECTIONS.len());
+    assert_eq!(on_disk(&store), before);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_bad_backup_changes_nothing() {
+    let dir = scratch_dir("bad");
+    let store = Store::in_dir(&dir);
+    fill(&store);
+    let before = on_disk(&store);
+    let path = dir.join("backup.json");
+    let mut bundle =
+        serde_json::to_value(backup::create(&store, &current(&store)).unwrap()).unwrap();
+
+    // A later format version
+    bundle["version"] = json!(BACKUP_VERSION + 1);
+    write_value(&path, &bundle);
+    let error = backup::read(path.to_str().unwrap()).unwrap_err();
+    assert!(
+        matches!(
+            error,
+            TodoError::BackupTooNew {
+                version: 2,
+                supported: 1
+            }
+        ),
+        "{:?}",
+        error
+    );
+    assert_eq!(error.code(), "BackupTooNew");
+
+    // The trash holds something that is not a task; the tasks before it in
+    // the registry are not written either
+    bundle["version"] = json!(BACKUP_VERSION);
+    bundle["sections"]["tasks"] = json!([]);
+    bundle["sections"]["trash"] = json!([{"description": 42}]);
+    write_value(&path, &bundle);
+    let read = backup::read(path.to_str().unwrap()).unwrap();
+    let error = backup::restore(&store, &read, &current(&store)).unwrap_err();
+    let TodoError::BadBackupSection { section, .. } = &error else {
+        panic!("{:?}", error);
+    };
+    assert_eq!(section, "trash");
+    assert!(error.to_string().starts_with("the backup's trash section"));
+    assert_eq!(on_disk(&store), before);
+
+    // Not a backup at all
+    for text in ["[]", "{\"format\": \"something else\"}", "not json"] {
+        fs::write(&path, text).unwrap();
+        assert!(matches!(
+            backup::read(path.to_str().unwrap()),
+            Err(TodoError::NotABackup(_))
+        ));
+    }
+    assert!(!Path::new(&store.restore_backup()).exists());
+    let mut names: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(
+        names,
+        [
+            "archive.json",
+            "backup.json",
+            "tasks.json",
+            "tasks.log.json",
+            "trash.json"
+        ]
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn missing_files_are_null_and_restore_as_missing() {
+    let dir = scratch_dir("missing");
+    let store = Store::in_dir(&dir);
+    let mut todo = TodoList::new();
+    todo.add_tasks("Only tasks".to_string()).unwrap();
+    store.save(&todo).unwrap();
+    let bundle = backup::create(&store, &current(&store)).unwrap();
+    assert_eq!(bundle.format, FORMAT);
+    assert_eq!(bundle.sections["trash"], Value::Null);
+
+    fill(&store);
+    backup::restore(&store, &bundle, &current(&store)).unwrap();
+    assert!(!Path::new(&store.trash).exists());
+    assert!(!Path::new(&store.archive).exists());
+    assert_eq!(store.load().unwrap().len(), 1);
+    // The archive read before the restore is not used after it
+    assert!(store.load_archive().unwrap().is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn read_only_stores_are_not_restored_over() {
+    let dir = scratch_dir("read-only");
+    let store = Store {
+        read_only: true,
+        ..Store::in_dir(&dir)
+    };
+    let bundle: Bundle = backup::create(&store, &TodoList::new()).unwrap();
+    assert!(matches!(
+        backup::restore(&store, &bundle, &TodoList::new()),
+        Err(TodoError::ReadOnly("backup"))
+    ));
+    assert!(!Path::new(&store.restore_backup()).exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn backup_parses_create_and_restore() {
+    assert_eq!(
+        parse_command("backup create my backup.json").unwrap(),
+        Command::BackupCreate("my backup.json".to_string())
+    );
+    assert_eq!(
+        parse_command("backup restore old.json --yes").unwrap(),
+        Command::BackupRestore("old.json".to_string(), true)
+    );
+    for line in ["backup", "backup create", "backup restore", "backup list x"] {
+        assert!(parse_command(line).is_err(), "{:?}", line);
+    }
+    let restore = parse_command("backup restore old.json").unwrap();
+    assert!(restore.writes() && !restore.mutates());
+    assert!(!parse_command("backup create x").unwrap().writes());
+    let error = execute(&mut TodoList::new(), restore).unwrap_err();
+    assert!(matches!(error, TodoError::CliOnly("backup")));
+}
@@ -0,0 +1,222 @@
+mod common;
+
+use common::list_of;
+use rust_todo_cli::changes::{Change, ChangeSet, SessionStats};
+use rust_todo_cli::command::parse_command;
+use rust_todo_cli::merge::{Side, merge};
+use rust_todo_cli::outcome::execute;
+use rust_todo_cli::todo::{Status, Task, TodoList};
+
+// The changes `change` makes to `todo`
+fn changes_of(todo: &mut TodoList, change: impl FnOnce(&mut TodoList)) -> ChangeSet {
+    let before = todo.tasks().to_vec();
+    change(todo);
+    ChangeSet::between(&before, todo.tasks())
+}
+
+fn lines(changes: &ChangeSet) -> Vec<String> {
+    changes.changes.iter().map(Change::to_string).collect()
+}
+
+#[test]
+fn nothing_changed_is_an_empty_change_set() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    let changes = changes_of(&mut todo, |todo| {
+        todo.update_all_status(Some(Status::Completed), Status::Todo, false);
+    });
+    assert!(changes.is_empty());
+    assert_eq!(changes.len(), 0);
+}
+
+#[test]
+fn added_tasks_are_numbered_as_they_are_now() {
+    let mut todo = list_of(&["Buy milk"]);
+    let changes = changes_of(&mut todo, |todo| {
+        todo.add_tasks("Call mum".to_string()).unwrap();
+    });
+    assert_eq!(
+        changes.changes,
+        [Change::Added {
+            index: 2,
+            description: "Call mum".to_string()
+        }]
+    );
+    assert_eq!(lines(&changes), ["+ 2. Call mum"]);
+}
+
+#[test]
+fn bulk_status_changes_show_the_old_and_new_status() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Book dentist"]);
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+    let changes = changes_of(&mut todo, |todo| {
+        todo.update_all_status(Some(Status::Todo), Status::InProgress, false);
+    });
+    assert_eq!(
+        lines(&changes),
+        [
+            "~ 1. Buy milk: TODO → IN-PROGRESS",
+            "~ 3. Book dentist: TODO → IN-PROGRESS"
+        ]
+    );
+}
+
+#[test]
+fn a_new_description_shows_the_old_one() {
+    let mut todo = list_of(&["Buy milk"]);
+    let changes = changes_of(&mut todo, |todo| {
+        todo.set_description(todo.task_ref(1).unwrap(), "Buy oat milk".to_string())
+            .unwrap();
+        todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+            .unwrap();
+    });
+    assert_eq!(
+        changes.changes,
+        [Change::Modified {
+            index: 1,
+            description: "Buy oat milk".to_string(),
+            was: Some("Buy milk".to_string()),
+            status: Some((Status::Todo, Status::Completed)),
+        }]
+    );
+    assert_eq!(
+        lines(&changes),
+        ["~ 1. Buy milk → Buy oat milk: TODO → DONE"]
+    );
+}
+
+#[test]
+fn cleared_tasks_are_numbered_as_they_were() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Book dentist"]);
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(3).unwrap(), Status::Cancelled, false)
+        .unwrap();
+    let changes = changes_of(&mut todo, |todo| {
+        todo.clear_closed();
+    });
+    assert_eq!(lines(&changes), ["- 1. Buy milk", "- 3. Book dentist"]);
+}
+
+#[test]
+fn archiving_removes_the_completed_tasks() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+    let changes = changes_of(&mut todo, |todo| {
+        todo.archive_completed();
+    });
+    assert_eq!(lines(&changes), ["- 2. Call mum"]);
+}
+
+#[test]
+fn dedupe_removes_the_copies_it_merged() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    // Spacing like this is only left in files from older versions
+    let mut copy = Task::new("buy milk".to_string()).unwrap();
+    copy.description = "buy  milk".to_string();
+    todo.add(copy);
+    let changes = changes_of(&mut todo, |todo| {
+        todo.deduplicate(false);
+    });
+    assert_eq!(lines(&changes), ["- 3. buy  milk"]);
+
+    let changes = changes_of(&mut todo, |todo| {
+        todo.deduplicate(true);
+    });
+    assert!(changes.is_empty());
+}
+
+#[test]
+fn merging_shows_tasks_taken_from_the_other_copy() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    let mut theirs: Vec<Task> = todo.tasks().to_vec();
+    theirs[1].status = Status::Completed;
+    theirs.push(Task::builder("Book dentist").build().unwrap());
+
+    let changes = changes_of(&mut todo, |todo| {
+        let mut report = merge(todo.tasks(), &theirs);
+        report.resolve_all(Side::Ours);
+        todo.apply_merge(report, "laptop.json");
+    });
+    assert_eq!(
+        lines(&changes),
+        ["~ 2. Call mum: TODO → DONE", "+ 3. Book dentist"]
+    );
+}
+
+#[test]
+fn removals_come_first_then_the_rest_in_list_order() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    let changes = changes_of(&mut todo, |todo| {
+        todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+            .unwrap();
+        todo.remove_task(todo.task_ref(1).unwrap()).unwrap();
+        todo.add_tasks("Book dentist".to_string()).unwrap();
+    });
+    assert_eq!(
+        lines(&changes),
+        [
+            "- 1. Buy milk",
+            "~ 1. Call mum: TODO → DONE",
+            "+ 2. Book dentist"
+        ]
+    );
+}
+
+// What a session running `lines` one after another comes to
+fn session(todo: &mut TodoList, lines: &[&str]) -> SessionStats {
+    let started = todo.tasks().to_vec();
+    for line in lines {
+        execute(todo, parse_command(line).unwrap()).unwrap();
+    }
+    SessionStats::between(&started, todo.tasks())
+}
+
+#[test]
+fn a_session_counts_what_it_left_changed() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Pay rent", "Fix sink"]);
+    let stats = session(
+        &mut todo,
+        &[
+            "add Water plants; Book dentist; Read book",
+            "done 1",
+            "done 5",
+            "update 2 in-progress",
+            "append 3 today",
+            "sub 4 Buy washer",
+            "remove 6 --yes",
+            "remove 4 --yes",
+        ],
+    );
+    let expected = SessionStats {
+        added: 2,
+        completed: 2,
+        removed: 1,
+        edited: 2,
+    };
+    assert_eq!(stats, expected);
+    assert!(!stats.is_empty());
+}
+
+#[test]
+fn changes_taken_back_within_a_session_count_for_nothing() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    let stats = session(
+        &mut todo,
+        &[
+            "add Water plants",
+            "remove 3 --yes",
+            "done 1",
+            "toggle 1",
+            "update 2 in-progress",
+            "update 2 todo",
+        ],
+    );
+    assert!(stats.is_empty(), "{:?}", stats);
+    assert!(session(&mut todo, &["list", "stats"]).is_empty());
+
+    // A recurring task done once is one completion and one new task
+    let stats = session(&mut todo, &["repeat 1 weekly", "done 1"]);
+    assert_eq!((stats.added, stats.completed, stats.edited), (1, 1, 0));
+}
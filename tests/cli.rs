@@ -0,0 +1,1915 @@
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use common::{app, scratch_dir};
+
+#[test]
+fn version_flag_prints_the_version_without_touching_files() {
+    let dir = scratch_dir("version");
+    for flag in ["--version", "-V"] {
+        let output = app().arg(flag).current_dir(&dir).output().unwrap();
+        assert!(output.status.success());
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        assert!(stdout.contains(env!("CARGO_PKG_VERSION")), "{}", stdout);
+        assert!(stdout.contains("tasks.json"), "{}", stdout);
+    }
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn version_command_prints_the_version() {
+    let dir = scratch_dir("version-command");
+    let output = app().arg("version").current_dir(&dir).output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.starts_with(&format!("rust-todo-cli {}", env!("CARGO_PKG_VERSION"))),
+        "{}",
+        stdout
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Ctrl-C, `kill` and a closed terminal save the list like `exit` does
+#[cfg(unix)]
+#[test]
+fn signals_save_before_exiting() {
+    use std::io::{Read, Write};
+    use std::process::Stdio;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    for signal in ["INT", "TERM", "HUP"] {
+        let dir = scratch_dir(&format!("signal-{}", signal));
+        let mut child = app()
+            .current_dir(&dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        // stdin stays open, so the session waits for more input afterwards
+        let mut stdin = child.stdin.take().unwrap();
+        writeln!(stdin, "add Buy milk").unwrap();
+        thread::sleep(Duration::from_millis(500));
+
+        let killed = Command::new("kill")
+            .arg(format!("-{}", signal))
+            .arg(child.id().to_string())
+            .status()
+            .unwrap();
+        assert!(killed.success());
+        let started = Instant::now();
+        while child.try_wait().unwrap().is_none() {
+            if started.elapsed() > Duration::from_secs(5) {
+                child.kill().unwrap();
+                panic!("SIG{} did not end the session", signal);
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        let mut stdout = String::new();
+        child
+            .stdout
+            .take()
+            .unwrap()
+            .read_to_string(&mut stdout)
+            .unwrap();
+        assert!(stdout.contains("Tasks saved successfully!"), "{}", stdout);
+        let saved = fs::read_to_string(dir.join("tasks.json")).unwrap();
+        assert!(saved.contains("Buy milk"), "SIG{}: {}", signal, saved);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
+
+// During `pomodoro` Ctrl-C only ends the focus session; the next one ends
+// the program again
+#[cfg(unix)]
+#[test]
+fn ctrl_c_during_a_pomodoro_only_stops_the_countdown() {
+    use std::io::{Read, Write};
+    use std::process::Stdio;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    let interrupt = |pid: u32| {
+        let killed = Command::new("kill")
+            .args(["-INT", &pid.to_string()])
+            .status()
+            .unwrap();
+        assert!(killed.success());
+    };
+
+    let dir = scratch_dir("signal-pomodoro");
+    let mut child = app()
+        .arg("--plain")
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    writeln!(stdin, "add Write report").unwrap();
+    writeln!(stdin, "pomodoro 1 1").unwrap();
+
+    // Output as it comes, so each Ctrl-C is sent once the last has been seen to
+    let (chunks, received) = mpsc::channel();
+    let mut pipe = child.stdout.take().unwrap();
+    thread::spawn(move || {
+        let mut buffer = [0; 1024];
+        while let Ok(read @ 1..) = pipe.read(&mut buffer) {
+            let _ = chunks.send(String::from_utf8_lossy(&buffer[..read]).into_owned());
+        }
+    });
+    let mut stdout = String::new();
+    let mut wait_for = |text: &str| {
+        while !stdout.contains(text) {
+            match received.recv_timeout(Duration::from_secs(10)) {
+                Ok(chunk) => stdout.push_str(&chunk),
+                Err(_) => panic!("never printed {:?}: {}", text, stdout),
+            }
+        }
+    };
+
+    wait_for("left on task 1");
+    interrupt(child.id());
+    wait_for("Session stopped after");
+    assert!(
+        child.try_wait().unwrap().is_none(),
+        "the first Ctrl-C ended the program"
+    );
+
+    interrupt(child.id());
+    wait_for("Tasks saved successfully!");
+    let started = Instant::now();
+    while child.try_wait().unwrap().is_none() {
+        if started.elapsed() > Duration::from_secs(5) {
+            child.kill().unwrap();
+            panic!("Ctrl-C after the pomodoro did not end the session");
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    let saved = fs::read_to_string(dir.join("tasks.json")).unwrap();
+    assert!(saved.contains("Write report"), "{}", saved);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// A change made to the file elsewhere shows up before the next command,
+// while the session's own saves go unremarked
+#[test]
+fn outside_changes_are_reloaded_between_commands() {
+    use std::io::{Read, Write};
+    use std::process::Stdio;
+    use std::thread;
+    use std::time::Duration;
+
+    let dir = scratch_dir("reload");
+    let mut child = app()
+        .arg("--plain")
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    writeln!(stdin, "add Buy milk").unwrap();
+    writeln!(stdin, "save").unwrap();
+    writeln!(stdin, "list").unwrap();
+    thread::sleep(Duration::from_millis(500));
+    let saved = fs::read_to_string(dir.join("tasks.json")).unwrap();
+    assert!(saved.contains("Buy milk"), "{}", saved);
+
+    let edited = saved.replace("Buy milk", "Buy oat milk");
+    fs::write(dir.join("tasks.json"), edited).unwrap();
+    writeln!(stdin, "list").unwrap();
+    writeln!(stdin, "exit").unwrap();
+    drop(stdin);
+    child.wait().unwrap();
+
+    let mut stdout = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut stdout)
+        .unwrap();
+    assert_eq!(stdout.matches("changed on disk").count(), 1, "{}", stdout);
+    let (before, after) = stdout.split_once("changed on disk").unwrap();
+    assert!(!before.contains("Buy oat milk"), "{}", stdout);
+    assert!(after.contains("Buy oat milk"), "{}", stdout);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Unsaved changes and ones made to the file elsewhere meanwhile are both
+// kept when there is no one to ask which should win
+#[test]
+fn a_file_changed_elsewhere_is_not_saved_over_without_asking() {
+    use std::io::{Read, Write};
+    use std::process::Stdio;
+    use std::thread;
+    use std::time::Duration;
+
+    let dir = scratch_dir("concurrent-session");
+    let session = |lines: &[&str], edit: Option<&str>| {
+        let mut child = app()
+            .arg("--plain")
+            .current_dir(&dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        let mut stdin = child.stdin.take().unwrap();
+        writeln!(stdin, "add Ours").unwrap();
+        thread::sleep(Duration::from_millis(500));
+        if let Some(description) = edit {
+            let theirs = format!(r#"[{{"id": 1, "description": "{}"}}]"#, description);
+            fs::write(dir.join("tasks.json"), theirs).unwrap();
+        }
+        for line in lines {
+            writeln!(stdin, "{}", line).unwrap();
+        }
+        drop(stdin);
+        child.wait().unwrap();
+        let mut output = String::new();
+        child
+            .stdout
+            .take()
+            .unwrap()
+            .read_to_string(&mut output)
+            .unwrap();
+        child
+            .stderr
+            .take()
+            .unwrap()
+            .read_to_string(&mut output)
+            .unwrap();
+        output
+    };
+
+    let output = session(&["exit"], Some("Theirs"));
+    assert!(output.contains("changed elsewhere"), "{}", output);
+    let saved = fs::read_to_string(dir.join("tasks.json")).unwrap();
+    assert!(
+        saved.contains("Theirs") && !saved.contains("Ours"),
+        "{}",
+        saved
+    );
+    let aside = fs::read_to_string(dir.join("tasks.json.unsaved")).unwrap();
+    assert!(
+        aside.contains("Ours") && !aside.contains("Theirs"),
+        "{}",
+        aside
+    );
+    assert!(output.contains("merge tasks.json.unsaved"), "{}", output);
+
+    // `save --force` is the go-ahead to replace them
+    let output = session(&["save --force", "exit"], Some("Later"));
+    assert!(output.contains("Tasks saved to tasks.json"), "{}", output);
+    let saved = fs::read_to_string(dir.join("tasks.json")).unwrap();
+    assert!(!saved.contains("Later"), "{}", saved);
+    assert_eq!(saved.matches("Ours").count(), 1, "{}", saved);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// A saved filter or snippet not yet written is an unsaved change too, so a
+// file changed elsewhere is not reloaded over it
+#[test]
+fn unsaved_filters_and_snippets_survive_a_change_elsewhere() {
+    use std::io::{Read, Write};
+    use std::process::Stdio;
+    use std::thread;
+    use std::time::Duration;
+
+    let dir = scratch_dir("unsaved-filter");
+    let mut child = app()
+        .arg("--plain")
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    writeln!(stdin, "add Buy milk").unwrap();
+    writeln!(stdin, "save").unwrap();
+    writeln!(stdin, "filter save mine todo").unwrap();
+    writeln!(stdin, "snippet save call Call {{name}}").unwrap();
+    thread::sleep(Duration::from_millis(500));
+
+    let added = app()
+        .args(["add", "Call mum"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(added.status.success());
+    writeln!(stdin, "filter list").unwrap();
+    writeln!(stdin, "snippet list").unwrap();
+    drop(stdin);
+    child.wait().unwrap();
+
+    let mut stdout = String::new();
+    child
+        .stdout
+        .take()
+        .unwrap()
+        .read_to_string(&mut stdout)
+        .unwrap();
+    assert!(!stdout.contains("No saved filters yet"), "{}", stdout);
+    assert!(!stdout.contains("No snippets"), "{}", stdout);
+    assert!(stdout.contains("your changes are not saved"), "{}", stdout);
+    let aside = fs::read_to_string(dir.join("tasks.json.unsaved")).unwrap();
+    assert!(aside.contains("\"mine\""), "{}", aside);
+    assert!(aside.contains("Call {name}"), "{}", aside);
+    let saved = fs::read_to_string(dir.join("tasks.json")).unwrap();
+    assert!(saved.contains("Call mum"), "{}", saved);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn sync_without_a_remote_says_how_to_set_one() {
+    let dir = scratch_dir("sync-off");
+    let output = app().arg("sync").current_dir(&dir).output().unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--sync-url"), "{}", stderr);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn sync_merges_the_remote_copy_and_sends_the_result() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::thread;
+
+    let dir = scratch_dir("sync");
+    fs::write(
+        dir.join("tasks.json"),
+        r#"[{"id": 1, "description": "Buy milk", "status": "Todo", "created_at": 1700000000}]"#,
+    )
+    .unwrap();
+
+    // Answers the GET with a copy holding another task, then takes the PUT
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let copy = r#"[{"id": 1, "description": "Walk dog", "status": "Todo", "created_at": 1}]"#;
+    let responses = [
+        format!(
+            "HTTP/1.1 200 OK\r\nETag: \"1\"\r\nContent-Length: {}\r\n\r\n{}",
+            copy.len(),
+            copy
+        ),
+        "HTTP/1.1 204 No Content\r\nETag: \"2\"\r\n\r\n".to_string(),
+    ];
+    let server = thread::spawn(move || {
+        let mut requests = Vec::new();
+        for response in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut request = String::new();
+            let mut length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap() == 0 {
+                    return requests;
+                }
+                if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+                request.push_str(&line);
+                if line == "\r\n" {
+                    break;
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            request.push_str(&String::from_utf8(body).unwrap());
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            requests.push(request);
+        }
+        requests
+    });
+
+    let output = app()
+        .args([
+            "--sync-url",
+            &format!("http://{}/tasks.json", address),
+            "sync",
+        ])
+        .env("TODO_SYNC_TOKEN", "secret")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    // Lets the server go if a request never came
+    let _ = TcpStream::connect(address);
+    let requests = server.join().unwrap();
+
+    assert_eq!(requests.len(), 2, "{}", stdout);
+    assert!(requests[0].starts_with("GET "), "{:?}", requests);
+    assert!(requests[0].contains("Bearer secret"), "{:?}", requests);
+    assert!(requests[1].starts_with("PUT "), "{:?}", requests);
+    // Header names are not case-sensitive
+    assert!(
+        requests[1].to_lowercase().contains("if-match: \"1\""),
+        "{:?}",
+        requests
+    );
+    assert!(
+        requests[1].contains("Buy milk") && requests[1].contains("Walk dog"),
+        "{:?}",
+        requests
+    );
+    let saved = fs::read_to_string(dir.join("tasks.json")).unwrap();
+    assert!(saved.contains("Walk dog"), "{}\n{}", stdout, saved);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_push_without_a_pull_first_needs_force() {
+    use std::net::TcpListener;
+
+    let dir = scratch_dir("sync-push");
+    // Nothing listens here, so a request sent would fail to connect
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let output = app()
+        .args([
+            "--sync-url",
+            &format!("http://127.0.0.1:{}/tasks.json", port),
+            "sync",
+            "push",
+        ])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("sync push --force"), "{}", stderr);
+    assert!(!stderr.contains("could not reach"), "{}", stderr);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn lang_picks_the_language_of_messages() {
+    let dir = scratch_dir("lang");
+    let run = |lang: &str| {
+        let output = app()
+            .env("LC_ALL", lang)
+            .args(["list"])
+            .current_dir(&dir)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert!(run("es_ES.UTF-8").contains("Aún no hay tareas"));
+    // Unbundled languages fall back to English
+    assert!(run("fr_FR.UTF-8").contains("No tasks yet"));
+
+    let output = app()
+        .env("LC_ALL", "es_ES.UTF-8")
+        .args(["--lang", "en", "list"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("No tasks yet"), "{}", stdout);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn report_md_only_replaces_a_file_with_force() {
+    let dir = scratch_dir("report");
+    fs::write(dir.join("standup.md"), "notes").unwrap();
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    run(&["add", "Buy milk"]);
+
+    let output = run(&["report", "md", "standup.md"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--force"), "{}", stderr);
+    assert_eq!(fs::read_to_string(dir.join("standup.md")).unwrap(), "notes");
+
+    run(&["report", "md", "standup.md", "--force"]);
+    let report = fs::read_to_string(dir.join("standup.md")).unwrap();
+    assert!(report.starts_with("# Status report, "), "{}", report);
+    assert!(report.contains("## Todo\n\n- Buy milk\n"), "{}", report);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn quiet_leaves_only_requested_output_on_stdout() {
+    let dir = scratch_dir("quiet");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+
+    for (flag, description) in [("--quiet", "Buy milk"), ("-q", "Call mum")] {
+        let output = run(&[flag, "add", description]);
+        assert!(output.status.success());
+        assert!(output.stdout.is_empty(), "{:?}", output);
+        assert!(output.stderr.is_empty(), "{:?}", output);
+    }
+
+    let output = run(&["-q", "list", "--json"]);
+    assert!(output.status.success());
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 2);
+
+    let output = run(&["-q", "show", "1"]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Buy milk"), "{}", stdout);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn errors_go_to_stderr_and_fail_the_command() {
+    let dir = scratch_dir("exit-code");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+
+    for args in [
+        &["-q", "done", "9"][..],
+        &["done", "9"],
+        &["-q", "frobnicate"],
+        &["-q", "show", "9", "--json"],
+    ] {
+        let output = run(args);
+        assert!(!output.status.success(), "{:?}", args);
+        assert!(output.stdout.is_empty(), "{:?}: {:?}", args, output);
+        assert!(!output.stderr.is_empty(), "{:?}", args);
+    }
+
+    // Nothing to do is not a failure
+    let output = run(&["-q", "archive"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(output.stdout.is_empty(), "{:?}", output);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn json_flag_makes_show_and_its_errors_machine_readable() {
+    let dir = scratch_dir("json-errors");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    assert!(run(&["add", "Buy milk"]).status.success());
+
+    let output = run(&["show", "1", "--json"]);
+    let task: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!((&task["index"], &task["id"]), (&1.into(), &1.into()));
+    assert_eq!(task["description"], "Buy milk");
+
+    let output = run(&["show", "7", "--json"]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty(), "{:?}", output);
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"], "IndexOutOfBound");
+    assert_eq!(error["index"], 7);
+    assert!(error["message"].is_string());
+
+    // Without the flag errors stay text
+    let output = run(&["show", "7"]);
+    assert!(serde_json::from_slice::<serde_json::Value>(&output.stderr).is_err());
+    assert!(String::from_utf8(output.stderr).unwrap().contains('7'));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn escalate_raises_tasks_left_in_todo() {
+    let dir = scratch_dir("escalate");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    // A task from 2001 and one made just now
+    fs::write(
+        dir.join("tasks.json"),
+        r#"[{ "id": 1, "description": "Old", "created_at": 1000000000 }]"#,
+    )
+    .unwrap();
+    assert!(run(&["add", "New"]).status.success());
+
+    // The aging rule is off until asked for
+    assert!(!stdout(&["list"]).contains("[stale]"));
+    let output = run(&["escalate"]);
+    assert!(!output.status.success());
+    assert!(!output.stderr.is_empty());
+    let listed = stdout(&["--escalate-after", "7", "list"]);
+    let stale: Vec<&str> = listed.lines().filter(|l| l.contains("[stale]")).collect();
+    assert_eq!(stale.len(), 1, "{}", listed);
+    assert!(stale[0].contains("Old"), "{}", listed);
+
+    let escalated = stdout(&["escalate", "7"]);
+    assert!(escalated.contains("1 (HIGH)"), "{}", escalated);
+    assert!(stdout(&["show", "1"]).contains("HIGH"));
+    assert!(!stdout(&["--escalate-after", "7", "list"]).contains("[stale]"));
+
+    // Automatic escalation keeps one-shot output clean
+    fs::write(
+        dir.join("tasks.json"),
+        r#"[{ "id": 1, "description": "Old", "created_at": 1000000000 }]"#,
+    )
+    .unwrap();
+    let json = stdout(&["--escalate-after", "7", "--auto-escalate", "list", "--json"]);
+    let tasks: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(tasks[0]["priority"], "High");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Without a display there is no clipboard to reach on Linux
+#[test]
+fn pick_refuses_without_a_terminal_to_ask_on() {
+    let dir = scratch_dir("pick");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    assert!(run(&["add", "Buy milk"]).status.success());
+
+    let output = run(&["done", "--pick"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("--pick"));
+    let output = run(&["pick", "--json"]);
+    let error: serde_json::Value = serde_json::from_slice(&output.stderr).unwrap();
+    assert_eq!(error["error"], "NotInteractive");
+    assert!(
+        String::from_utf8(run(&["list"]).stdout)
+            .unwrap()
+            .contains("[TODO]")
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn copy_without_a_clipboard_fails_pointing_at_show() {
+    let dir = scratch_dir("copy");
+    let run = |args: &[&str]| {
+        app()
+            .args(args)
+            .current_dir(&dir)
+            .env_remove("DISPLAY")
+            .env_remove("WAYLAND_DISPLAY")
+            .output()
+            .unwrap()
+    };
+
+    assert!(run(&["add", "Buy milk"]).status.success());
+    for args in [&["copy", "1"][..], &["copy", "list"]] {
+        let output = run(args);
+        assert!(!output.status.success(), "{:?}", args);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("use show"), "{}", stderr);
+    }
+    let output = run(&["copy", "9"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!stderr.contains("clipboard"), "{}", stderr);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn verbose_logs_the_save_to_stderr() {
+    let dir = scratch_dir("verbose");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+
+    let output = run(&["add", "Buy milk"]);
+    assert!(output.stderr.is_empty(), "{:?}", output);
+
+    for args in [
+        &["-v", "add", "Call mum"][..],
+        &["--verbose", "add", "Walk dog"],
+    ] {
+        let output = run(args);
+        assert!(output.status.success());
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        let lines: Vec<&str> = stderr.lines().collect();
+        let running = lines
+            .iter()
+            .position(|line| line.starts_with("[DEBUG rust_todo_cli::handlers] running Add"));
+        let saving = lines
+            .iter()
+            .position(|line| line.starts_with("[DEBUG rust_todo_cli::store] saving"));
+        let saved = lines
+            .iter()
+            .position(|line| *line == "[DEBUG rust_todo_cli::store] saved tasks.json");
+        assert!(running < saving && saving < saved, "{}", stderr);
+        assert!(running.is_some(), "{}", stderr);
+        assert!(!stderr.contains("[TRACE"), "{}", stderr);
+        assert!(!String::from_utf8(output.stdout).unwrap().contains("[DEBUG"));
+    }
+
+    let output = app()
+        .env("RUST_LOG", "rust_todo_cli=trace")
+        .args(["list", "--json"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("[TRACE rust_todo_cli::todo] reading tasks.json"),
+        "{}",
+        stderr
+    );
+    let tasks: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(tasks.as_array().unwrap().len(), 3);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn verbose_logs_the_causes_of_a_failed_save() {
+    let dir = scratch_dir("verbose-error");
+    // A directory where the temporary copy goes makes the save fail
+    fs::create_dir(dir.join("tasks.json.tmp")).unwrap();
+    let output = app()
+        .args(["-v", "add", "Buy milk"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("[ERROR rust_todo_cli::store] tasks.json failed"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("[ERROR rust_todo_cli::store]   caused by: "),
+        "{}",
+        stderr
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn completion_scripts_offer_the_flags_the_app_takes() {
+    let dir = scratch_dir("completions");
+    let output = app()
+        .args(["completions", "fish"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let script = String::from_utf8(output.stdout).unwrap();
+    let flags: Vec<(String, bool)> = script
+        .lines()
+        .filter_map(|line| line.strip_prefix("complete -c rust-todo-cli -n __fish_use_subcommand "))
+        .filter_map(|rest| {
+            let (kind, rest) = rest.split_once(' ')?;
+            let name = rest.split_whitespace().next()?;
+            let dashes = match kind {
+                "-l" => "--",
+                "-s" => "-",
+                _ => return None,
+            };
+            Some((format!("{}{}", dashes, name), rest.ends_with(" -r")))
+        })
+        .collect();
+    assert!(flags.len() > 10, "{}", script);
+
+    for (flag, takes_value) in &flags {
+        if flag == "--version" || flag == "-V" {
+            continue;
+        }
+        let mut args = vec![flag.as_str()];
+        if *takes_value {
+            args.push(if flag == "--lang" { "en" } else { "5" });
+        }
+        args.extend(["completions", "bash"]);
+        let output = app().args(&args).current_dir(&dir).output().unwrap();
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(!stderr.contains("unknown argument"), "{}: {}", flag, stderr);
+        assert!(
+            stdout.starts_with("# bash completion"),
+            "{}: {}",
+            flag,
+            stdout
+        );
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn list_hides_waiting_tasks_and_says_how_many() {
+    let dir = scratch_dir("waiting");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    assert!(run(&["add", "Buy milk; Renew passport"]).status.success());
+    assert!(run(&["wait", "2", "new photos"]).status.success());
+
+    let list = stdout(&["list"]);
+    assert!(list.contains("Buy milk"));
+    assert!(!list.contains("Renew passport"), "{}", list);
+    assert!(list.contains("1 waiting task not shown"), "{}", list);
+    for args in [["list", "all"], ["list", "waiting"]] {
+        let list = stdout(&args);
+        assert!(list.contains("Renew passport"), "{}", list);
+        assert!(!list.contains("not shown"), "{}", list);
+    }
+    assert!(stdout(&["show", "2"]).contains("new photos"));
+
+    // A list of nothing but waiting tasks is not empty
+    assert!(run(&["done", "1"]).status.success());
+    assert!(run(&["clear", "--yes"]).status.success());
+    let list = stdout(&["list"]);
+    assert!(!list.contains("No tasks yet"), "{}", list);
+    assert!(list.contains("1 waiting task not shown"), "{}", list);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn run_executes_a_script_and_stops_at_the_first_failure() {
+    let dir = scratch_dir("run");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    fs::write(
+        dir.join("setup.todo"),
+        "# a project's usual tasks\nadd Write spec\n\nfrobnicate\nadd Review spec\n",
+    )
+    .unwrap();
+
+    let output = run(&["run", "setup.todo"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("setup.todo:2> add Write spec"),
+        "{}",
+        stdout
+    );
+    assert!(
+        stdout.contains("ran 2 lines, 1 succeeded, 1 failed"),
+        "{}",
+        stdout
+    );
+    assert!(!stdout.contains("Review spec"), "{}", stdout);
+
+    let output = run(&["run", "setup.todo", "--continue-on-error"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("ran 3 lines, 2 succeeded, 1 failed"),
+        "{}",
+        stdout
+    );
+    let list = String::from_utf8(run(&["list"]).stdout).unwrap();
+    assert_eq!(list.matches("Write spec").count(), 2, "{}", list);
+    assert_eq!(list.matches("Review spec").count(), 1, "{}", list);
+
+    // A script that runs itself gives up instead of going on forever
+    fs::write(dir.join("loop.todo"), "run loop.todo\n").unwrap();
+    let output = run(&["run", "loop.todo"]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("8 deep"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn chained_commands_see_the_numbers_earlier_steps_left() {
+    let dir = scratch_dir("chain");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    let output = run(&["add", "Old", "&&", "add", "Buy milk", "&&", "done", "2"]);
+    assert!(output.status.success(), "{:?}", output);
+
+    // Removing task 1 makes Buy milk task 1 for the next step
+    let output = run(&[
+        "remove", "1", "--yes", "&&", "update", "1", "todo", "&&", "list",
+    ]);
+    assert!(output.status.success(), "{:?}", output);
+    let list = String::from_utf8(output.stdout).unwrap();
+    assert!(list.contains("1. Buy milk [TODO]"), "{}", list);
+    assert!(!list.contains("Old [TODO]"), "{}", list);
+
+    // A failing step stops the chain and sets the exit code
+    let output = run(&["done", "5", "&&", "add", "Never"]);
+    assert!(!output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("Step 1 of 2 failed")
+    );
+    // A step that does not parse keeps every step from running
+    let output = run(&["add", "Never", "&&", "lsit"]);
+    assert!(!output.status.success());
+    assert!(!stdout(&["list"]).contains("Never"));
+
+    // Quoted or escaped, && is part of the description
+    assert!(run(&["add", r"Tom \&& Jerry"]).status.success());
+    assert!(stdout(&["list"]).contains("Tom && Jerry"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn adding_a_duplicate_warns_and_no_duplicates_leaves_it_out() {
+    let dir = scratch_dir("duplicates");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let list = || String::from_utf8(run(&["list"]).stdout).unwrap();
+    assert!(run(&["add", "Buy milk"]).status.success());
+
+    // Without a terminal to ask on, the copy is added with a warning
+    let output = run(&["add", "buy milk."]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("'buy milk.' looks like task 1: Buy milk"),
+        "{}",
+        stdout
+    );
+    assert!(list().contains("2. buy milk."));
+
+    // Quiet, the warning still shows, on stderr
+    let output = run(&["--quiet", "add", "BUY MILK"]);
+    assert!(
+        String::from_utf8(output.stderr)
+            .unwrap()
+            .contains("looks like task 1")
+    );
+
+    fs::write(dir.join("plan.txt"), "Buy milk\nCall mum\n").unwrap();
+    let output = run(&["add", "--from", "plan.txt", "--no-duplicates"]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Added 1 task from plan.txt"), "{}", stdout);
+    assert_eq!(list().matches("Call mum").count(), 1);
+    assert_eq!(list().to_lowercase().matches("buy").count(), 3);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn import_github_counts_what_it_did_and_leaves_the_list_alone_on_errors() {
+    let dir = scratch_dir("import");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let list = || String::from_utf8(run(&["list", "all"]).stdout).unwrap();
+    fs::write(
+        dir.join("issues.json"),
+        r#"[{"number": 12, "title": "Fix login", "state": "OPEN", "labels": [{"name": "bug"}]}]"#,
+    )
+    .unwrap();
+
+    let output = run(&["import", "github", "issues.json"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Imported issues.json: 1 added, 0 updated, 0 already up to date"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("+ 1. Fix login"), "{}", stdout);
+    let stdout = String::from_utf8(run(&["import", "github", "issues.json"]).stdout).unwrap();
+    assert!(
+        stdout.contains("0 added, 0 updated, 1 already"),
+        "{}",
+        stdout
+    );
+
+    fs::write(
+        dir.join("bad.json"),
+        r#"[{"number": 13, "title": "Fine"}, {"title": "No number"}]"#,
+    )
+    .unwrap();
+    let output = run(&["import", "github", "bad.json"]);
+    assert!(!output.status.success());
+    assert!(!list().contains("Fine"));
+    assert_eq!(list().matches("Fix login").count(), 1);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn list_shows_how_far_off_due_dates_are_unless_absolute() {
+    let dir = scratch_dir("relative-due");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    assert!(run(&["add", "Undated; Late; Soon"]).status.success());
+    assert!(run(&["due", "2", "2020-01-01"]).status.success());
+    assert!(run(&["due", "3", "tomorrow"]).status.success());
+
+    let list = stdout(&["list"]);
+    assert!(list.contains("2. Late [TODO]"), "{}", list);
+    assert!(list.contains("d overdue)"), "{}", list);
+    assert!(list.contains("(!"), "{}", list);
+    assert!(list.contains("(due tomorrow)"), "{}", list);
+
+    let list = stdout(&["list", "--absolute", "--by-due"]);
+    assert!(list.contains("(2020-01-01)"), "{}", list);
+    let late = list.find("Late").unwrap();
+    let soon = list.find("Soon").unwrap();
+    let undated = list.find("Undated").unwrap();
+    assert!(late < soon && soon < undated, "{}", list);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_read_only_session_leaves_the_task_file_alone() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = scratch_dir("read-only");
+    let tasks = dir.join("tasks.json");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    assert!(run(&["add", "Buy milk; Call mum"]).status.success());
+    let content = fs::read(&tasks).unwrap();
+    let modified = fs::metadata(&tasks).unwrap().modified().unwrap();
+    let untouched = || {
+        assert_eq!(fs::read(&tasks).unwrap(), content);
+        assert_eq!(fs::metadata(&tasks).unwrap().modified().unwrap(), modified);
+        assert!(!dir.join("trash.json").exists());
+    };
+
+    for args in [
+        &["--read-only", "add", "Book dentist"][..],
+        &["--read-only", "update", "1", "done"],
+        &["--read-only", "remove", "2", "--yes"],
+        &["--read-only", "clear", "--yes"],
+        &["--read-only", "append", "1", "oat"],
+        &["--read-only", "save"],
+    ] {
+        let output = run(args);
+        assert!(!output.status.success(), "{:?}", args);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("open read-only"), "{:?}: {}", args, stderr);
+        untouched();
+    }
+    let output = app()
+        .args(["remove", "1", "--yes"])
+        .env("TODO_READONLY", "1")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    untouched();
+
+    // Looking and exporting still work
+    for args in [
+        &["--read-only", "list"][..],
+        &["--read-only", "search", "milk"],
+        &["--read-only", "show", "1", "--json"],
+        &["--read-only", "stats"],
+        &["--read-only", "report", "md", "report.md"],
+    ] {
+        let output = run(args);
+        assert!(output.status.success(), "{:?}: {:?}", args, output);
+    }
+    let list = String::from_utf8(run(&["--read-only", "list"]).stdout).unwrap();
+    assert!(list.contains("Buy milk"), "{}", list);
+    assert!(dir.join("report.md").exists());
+
+    // An interactive session turns the changes down and leaves without saving
+    let mut child = app()
+        .arg("--read-only")
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(b"add Book dentist\nupdate 1 done\nsave\nlist\nexit\n")
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.contains("Read-only"), "{}", stdout);
+    assert!(stdout.contains("Goodbye"), "{}", stdout);
+    assert!(!stdout.contains("Tasks saved"), "{}", stdout);
+    assert!(!stderr.contains("Failed to save"), "{}", stderr);
+    assert_eq!(stderr.matches("open read-only").count(), 3, "{}", stderr);
+    untouched();
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn a_task_file_that_cannot_be_written_opens_read_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = scratch_dir("not-writable");
+    let tasks = dir.join("tasks.json");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    assert!(run(&["add", "Buy milk"]).status.success());
+    fs::set_permissions(&tasks, fs::Permissions::from_mode(0o444)).unwrap();
+    let content = fs::read(&tasks).unwrap();
+
+    let output = run(&["add", "Call mum"]);
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.contains("cannot be written"), "{}", stdout);
+    assert!(stderr.contains("open read-only"), "{}", stderr);
+    assert_eq!(fs::read(&tasks).unwrap(), content);
+
+    let output = run(&["list"]);
+    assert!(output.status.success());
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("Buy milk")
+    );
+
+    fs::set_permissions(&tasks, fs::Permissions::from_mode(0o644)).unwrap();
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn count_prints_a_bare_number_for_prompts() {
+    let dir = scratch_dir("count");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let count = |args: &[&str]| {
+        let output = run(args);
+        assert!(output.status.success(), "{:?}: {:?}", args, output);
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(count(&["count"]), "0\n");
+    assert_eq!(count(&["count", "overdue"]), "0\n");
+
+    assert!(
+        run(&["add", "Buy milk; Call mum; Book dentist"])
+            .status
+            .success()
+    );
+    assert!(run(&["done", "1"]).status.success());
+    assert!(run(&["due", "2", "2020-01-01"]).status.success());
+    assert_eq!(count(&["count"]), "3\n");
+    assert_eq!(count(&["count", "todo"]), "2\n");
+    assert_eq!(count(&["-q", "count", "done"]), "1\n");
+    assert_eq!(count(&["count", "overdue"]), "1\n");
+    assert_eq!(count(&["count", "due-today"]), "0\n");
+
+    let output = run(&["count", "later"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("overdue, due-today"), "{}", stderr);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn clear_dry_run_lists_what_clear_then_removes() {
+    let dir = scratch_dir("clear-dry-run");
+    let tasks = dir.join("tasks.json");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    let removed = |output: &str| -> Vec<String> {
+        output
+            .lines()
+            .filter(|line| line.trim_start().starts_with("- "))
+            .map(|line| line.trim().to_string())
+            .collect()
+    };
+    assert!(
+        run(&["add", "Buy milk; Call mum; Book dentist; Fix sink"])
+            .status
+            .success()
+    );
+    assert!(run(&["done", "1"]).status.success());
+    assert!(run(&["update", "3", "cancelled"]).status.success());
+    let content = fs::read(&tasks).unwrap();
+    let modified = fs::metadata(&tasks).unwrap().modified().unwrap();
+
+    let dry = stdout(&["clear", "--dry-run"]);
+    assert!(
+        dry.contains("2 completed or cancelled tasks would be cleared"),
+        "{}",
+        dry
+    );
+    assert_eq!(removed(&dry), ["- 1. Buy milk", "- 3. Book dentist"]);
+    assert_eq!(fs::read(&tasks).unwrap(), content);
+    assert_eq!(fs::metadata(&tasks).unwrap().modified().unwrap(), modified);
+    assert!(!dir.join("trash.json").exists());
+
+    let real = stdout(&["clear", "--yes"]);
+    assert!(real.contains("Cleared 2"), "{}", real);
+    assert_eq!(removed(&real), removed(&dry));
+    let list = stdout(&["list"]);
+    assert!(
+        !list.contains("Buy milk") && list.contains("Fix sink"),
+        "{}",
+        list
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Each target clears its own tasks, asks first unless --yes, and says so
+// when there is nothing to clear
+#[test]
+fn clear_takes_a_status_or_all() {
+    let dir = scratch_dir("clear-targets");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    let count = || stdout(&["count"]);
+    assert!(run(&["add", "A; B; C; D; E"]).status.success());
+    assert!(run(&["update", "2", "in-progress"]).status.success());
+    assert!(run(&["done", "3"]).status.success());
+
+    let dry = stdout(&["clear", "in-progress", "--dry-run"]);
+    assert!(dry.contains("  - 2. B\n"), "{}", dry);
+    assert!(
+        dry.contains("1 in-progress task would be cleared"),
+        "{}",
+        dry
+    );
+    assert_eq!(count(), "5\n");
+
+    // No one to answer the question, so nothing goes
+    let output = run(&["clear", "todo"]);
+    assert!(!output.status.success());
+    assert_eq!(count(), "5\n");
+
+    let cleared = stdout(&["clear", "todo", "--yes"]);
+    assert!(cleared.contains("Cleared 3 todo tasks"), "{}", cleared);
+    assert_eq!(count(), "2\n");
+
+    let output = run(&["clear", "waiting", "--yes"]);
+    assert!(output.status.success());
+    let none = String::from_utf8(output.stdout).unwrap();
+    assert!(none.contains("No waiting tasks to clear"), "{}", none);
+
+    let cleared = stdout(&["clear", "--yes"]);
+    assert!(
+        cleared.contains("Cleared 1 completed or cancelled task"),
+        "{}",
+        cleared
+    );
+    let cleared = stdout(&["clear", "all", "--yes"]);
+    assert!(cleared.contains("Cleared 1 task"), "{}", cleared);
+    assert_eq!(count(), "0\n");
+    let none = stdout(&["clear", "all", "--yes"]);
+    assert!(none.contains("No tasks to clear"), "{}", none);
+
+    // Everything cleared can be brought back
+    let trash = stdout(&["trash"]);
+    assert_eq!(trash.matches("\n[").count(), 5, "{}", trash);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn doctor_reports_broken_tasks_at_startup_and_fixes_them() {
+    let dir = scratch_dir("doctor");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let text = |bytes: Vec<u8>| String::from_utf8(bytes).unwrap();
+    assert!(run(&["add", "Buy milk"]).status.success());
+    let healthy = run(&["doctor"]);
+    assert!(text(healthy.stderr).is_empty());
+    assert!(text(healthy.stdout).contains("No problems found in 1 task"));
+
+    let broken = format!("{}/tests/fixtures/broken.json", env!("CARGO_MANIFEST_DIR"));
+    fs::copy(broken, dir.join("tasks.json")).unwrap();
+    let listed = run(&["list"]);
+    let stderr = text(listed.stderr);
+    assert!(stderr.contains("4 problems in the task list"), "{}", stderr);
+
+    let report = text(run(&["doctor"]).stdout);
+    assert!(
+        report.contains("Task 2 has ID 1, the same as task 1"),
+        "{}",
+        report
+    );
+    assert!(report.contains("Task 2 waits on ID 7"), "{}", report);
+    assert!(report.contains("Task 3 has no description"), "{}", report);
+    assert!(
+        report.contains("'doctor --fix' repairs 3 of these"),
+        "{}",
+        report
+    );
+
+    let fixed = text(run(&["doctor", "--fix"]).stdout);
+    assert!(fixed.contains("Repaired 3 problems"), "{}", fixed);
+    assert!(fixed.contains("1 needs fixing by hand"), "{}", fixed);
+    let stderr = text(run(&["list"]).stderr);
+    assert!(stderr.contains("1 problem in the task list"), "{}", stderr);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn agenda_lays_out_the_coming_days() {
+    let dir = scratch_dir("agenda");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    assert!(stdout(&["agenda"]).contains("Nothing due in the next 7 days"));
+
+    assert!(
+        run(&["add", "Pay rent; Buy milk; Call mum; Taxes"])
+            .status
+            .success()
+    );
+    assert!(run(&["due", "1", "2020-01-01"]).status.success());
+    assert!(run(&["due", "2", "today"]).status.success());
+    assert!(run(&["due", "3", "in 3 days"]).status.success());
+
+    let agenda = stdout(&["agenda"]);
+    let overdue = agenda.find("Overdue").unwrap();
+    let today = agenda.find("Today").unwrap();
+    assert!(overdue < agenda.find("1. Pay rent").unwrap(), "{}", agenda);
+    assert!(overdue < today && today < agenda.find("2. Buy milk").unwrap());
+    assert!(!agenda.contains("Tomorrow"), "{}", agenda);
+    assert!(agenda.contains("No date: 1 task"), "{}", agenda);
+
+    let empty = stdout(&["agenda", "2", "--empty"]);
+    assert!(empty.contains("Tomorrow\n  —"), "{}", empty);
+    assert!(
+        empty.contains("Later\n  3. Call mum (due in 3d)"),
+        "{}",
+        empty
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn add_stdin_adds_lines_without_running_them() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    let dir = scratch_dir("add-stdin");
+    let pipe = |input: String| {
+        let mut child = app()
+            .args(["add", "--stdin"])
+            .current_dir(&dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(input.as_bytes())
+            .unwrap();
+        child.wait_with_output().unwrap()
+    };
+    let output = pipe("Buy milk\n# later\n\nremove old fence\nclear all --yes\n".to_string());
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("Added 3 tasks"), "{}", stdout);
+
+    // A bad line is reported after the rest are added, and fails the run
+    let output = pipe(format!("Call mum\n{}\n", "x".repeat(500)));
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stdout.contains("Added 1 task"), "{}", stdout);
+    assert!(stderr.contains("Line 2 skipped"), "{}", stderr);
+
+    let list = app().arg("list").current_dir(&dir).output().unwrap();
+    let list = String::from_utf8(list.stdout).unwrap();
+    for task in [
+        "Buy milk",
+        "remove old fence",
+        "clear all --yes",
+        "Call mum",
+    ] {
+        assert!(list.contains(task), "{}", list);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn emptying_a_full_list_needs_a_go_ahead() {
+    let dir = scratch_dir("wipe");
+    let tasks = dir.join("tasks.json");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let descriptions: Vec<String> = (1..=12).map(|n| format!("Task {}", n)).collect();
+    assert!(run(&["add", &descriptions.join("; ")]).status.success());
+    let before = fs::read(&tasks).unwrap();
+
+    // Removing them one by one is turned down without --force
+    let remove_all = ["remove 1 --yes"; 12].join(" && ");
+    let output = run(&[&remove_all]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--force"), "{}", stderr);
+    assert_eq!(fs::read(&tasks).unwrap(), before);
+
+    // `clear` asks for itself, so --yes is the go-ahead, once the file is
+    // copied aside
+    let output = run(&["clear", "all", "--yes"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("tasks.json.pre-wipe"), "{}", stdout);
+    assert_eq!(fs::read(dir.join("tasks.json.pre-wipe")).unwrap(), before);
+    assert!(dir.join("trash.json").exists());
+    let count = run(&["count"]);
+    assert_eq!(String::from_utf8(count.stdout).unwrap(), "0\n");
+
+    // So it is in a session
+    fs::write(&tasks, &before).unwrap();
+    let mut child = app()
+        .current_dir(&dir)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::io::Write::write_all(
+        &mut child.stdin.take().unwrap(),
+        b"clear all --yes\ncount\nexit\n",
+    )
+    .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(run(&["count"]).stdout, b"0\n");
+
+    // --force lets the removals through too, as does a guard above the
+    // number of tasks
+    fs::write(&tasks, &before).unwrap();
+    let output = run(&["--force", &remove_all]);
+    assert!(output.status.success(), "{:?}", output);
+    fs::write(&tasks, &before).unwrap();
+    let output = run(&["--wipe-guard", "20", &remove_all]);
+    assert!(output.status.success(), "{:?}", output);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// A task file that does not load is left as it is, and the command that
+// would have saved over it fails
+#[test]
+fn an_unreadable_task_file_is_not_saved_over() {
+    let dir = scratch_dir("unreadable");
+    let tasks = dir.join("tasks.json");
+    fs::write(&tasks, "{bad").unwrap();
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+
+    let output = run(&["add", "new"]);
+    assert!(!output.status.success(), "{:?}", output);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("could not be read"), "{}", stderr);
+    assert_eq!(fs::read_to_string(&tasks).unwrap(), "{bad");
+
+    // Nor by the save on the way out of a session, which fails and keeps
+    // the list beside the file
+    let mut child = app()
+        .current_dir(&dir)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    std::io::Write::write_all(&mut child.stdin.take().unwrap(), b"add new\nexit\n").unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(!output.status.success(), "{:?}", output);
+    assert_eq!(fs::read_to_string(&tasks).unwrap(), "{bad");
+    let aside = fs::read_to_string(dir.join("tasks.json.unsaved")).unwrap();
+    assert!(aside.contains("new"), "{}", aside);
+
+    // --force replaces it, keeping a copy
+    let output = run(&["--force", "add", "new"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("tasks.json.unreadable"), "{}", stdout);
+    assert_eq!(
+        fs::read_to_string(dir.join("tasks.json.unreadable")).unwrap(),
+        "{bad"
+    );
+    assert_eq!(run(&["count"]).stdout, b"1\n");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Commands now run through the library's execute and only print what it
+// hands back; their wording stays what it was when they printed directly
+#[test]
+fn one_shot_commands_print_what_they_did() {
+    let dir = scratch_dir("printed");
+    let run = |line: &str| {
+        let args: Vec<&str> = line.split(' ').collect();
+        let output = app().args(&args).current_dir(&dir).output().unwrap();
+        let mut printed = String::from_utf8(output.stdout).unwrap();
+        printed.push_str(&String::from_utf8(output.stderr).unwrap());
+        printed
+    };
+    let script = [
+        ("add Buy milk", "* Task added successfully!\n"),
+        (
+            "add buy milk",
+            "!  'buy milk' looks like task 1: Buy milk\n* Task added successfully!\n",
+        ),
+        (
+            "add Buy milk --no-duplicates",
+            "!  'Buy milk' looks like task 1: Buy milk\n* Not added; task 1 is already there\n",
+        ),
+        ("tag 1 shop", "* Task 1: #shop\n"),
+        ("due 2 2020-01-01", "* Task 2 is due 2020-01-01\n"),
+        ("repeat 2 weekly", "* Task 2 repeats every week\n"),
+        ("sub 1 Oat milk", "* Subtask 1.1 added\n"),
+        ("blocks 1 on 2", "[blocked] Task 1 now waits on task 2\n"),
+        (
+            "done 1.1",
+            "* Task 1.1 marked done\n\
+             * All subtasks of task 1 are done. Mark it done with: done 1\n",
+        ),
+        (
+            "done 1",
+            "!  Task 1 is still blocked by task 2\n* Task 1 marked done\n",
+        ),
+        (
+            "done 2 9",
+            "* Task 2 marked done\n\
+             * Next occurrence added as task 3, due 2020-01-08\n\
+             Error: No task exists at that index 9\n",
+        ),
+        ("estimate 3 1h", "* Task 3 should take 1h\n"),
+        (
+            "due 3 2020-01-08 23:30",
+            "* Task 3 is due 2020-01-08 23:30\n",
+        ),
+        (
+            "snooze 3 2h",
+            "! Task 3 snoozed: 2020-01-08 23:30 -> 2020-01-09 01:30\n",
+        ),
+        (
+            "clear --dry-run",
+            "  - 1. Buy milk\n  - 2. buy milk\n\
+             * 2 completed or cancelled tasks would be cleared; nothing was changed\n",
+        ),
+        (
+            "template save rent 3",
+            "* Saved template \"rent\" with 1 task\n",
+        ),
+        ("stop", "* No timer is running\n"),
+    ];
+    for (line, printed) in script {
+        assert_eq!(run(line), printed, "{}", line);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn old_completed_tasks_are_archived_at_startup_when_asked() {
+    let dir = scratch_dir("auto-archive");
+    let tasks = dir.join("tasks.json");
+    let archive = dir.join("archive.json");
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let write_tasks = || {
+        let json = format!(
+            r#"[
+              {{ "id": 1, "description": "Old news", "status": "Completed", "completed_at": 1000 }},
+              {{ "id": 2, "description": "Just done", "status": "Completed", "completed_at": {} }},
+              {{ "id": 3, "description": "Still open" }}
+            ]"#,
+            now
+        );
+        fs::write(&tasks, json).unwrap();
+    };
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let descriptions = |path: &PathBuf| -> Vec<String> {
+        let list: serde_json::Value = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+        list.as_array()
+            .unwrap()
+            .iter()
+            .map(|task| task["description"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    // Off unless asked for, and off for a run with --no-auto-archive
+    write_tasks();
+    assert!(run(&["list"]).status.success());
+    let output = run(&["--auto-archive-after", "14", "--no-auto-archive", "count"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "3\n");
+    assert!(!archive.exists());
+
+    // An archive that can't be read keeps the tasks in the list
+    fs::write(&archive, "not json").unwrap();
+    let output = run(&["--auto-archive-after", "14", "count"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "3\n");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("stay in the list"), "{}", stderr);
+    assert_eq!(fs::read_to_string(&archive).unwrap(), "not json");
+    assert_eq!(descriptions(&tasks).len(), 3);
+    // So does one that can't be written
+    fs::remove_file(&archive).unwrap();
+    fs::create_dir(&archive).unwrap();
+    let output = run(&["--auto-archive-after", "14", "count"]);
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "3\n");
+    assert_eq!(descriptions(&tasks).len(), 3);
+    fs::remove_dir(&archive).unwrap();
+
+    // A missing archive is started; one-shot output stays just the answer
+    let output = app()
+        .arg("count")
+        .env("TODO_AUTO_ARCHIVE_AFTER", "14")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), "2\n");
+    assert_eq!(descriptions(&archive), ["Old news"]);
+    assert_eq!(descriptions(&tasks), ["Just done", "Still open"]);
+
+    // A session says what it moved
+    write_tasks();
+    fs::remove_file(&archive).unwrap();
+    let mut child = app()
+        .args(["--auto-archive-after", "14"])
+        .current_dir(&dir)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    {
+        use std::io::Write;
+        writeln!(child.stdin.take().unwrap(), "exit").unwrap();
+    }
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        stdout.contains("Archived 1 task completed more than 14 days ago"),
+        "{}",
+        stdout
+    );
+    assert_eq!(descriptions(&tasks), ["Just done", "Still open"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn everyday_commands_never_read_the_archive() {
+    let dir = scratch_dir("archive-untouched");
+    // A directory where the archive should be fails any attempt to read it
+    fs::create_dir(dir.join("archive.json")).unwrap();
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+
+    for args in [
+        &["-v", "-v", "add", "Buy milk"][..],
+        &["-v", "-v", "list"],
+        &["-v", "-v", "done", "1"],
+        &["-v", "-v", "list", "done"],
+        &["-v", "-v", "stats"],
+    ] {
+        let output = run(args);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(output.status.success(), "{:?}: {}", args, stderr);
+        assert!(!stderr.contains("archive.json"), "{:?}: {}", args, stderr);
+    }
+
+    // Whereas the commands that need it do read it
+    for args in [&["list", "archived"][..], &["archive", "stats"]] {
+        let output = run(args);
+        assert!(!output.status.success(), "{:?}", args);
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains("archive.json"), "{:?}: {}", args, stderr);
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn strict_transitions_hold_statuses_to_one_step_unless_forced() {
+    let dir = scratch_dir("strict-transitions");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    assert!(
+        run(&["add", "Buy milk; Call mum; Pay rent"])
+            .status
+            .success()
+    );
+
+    let output = run(&["--strict-transitions", "done", "1"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr
+            .contains("from todo to done; from todo it can go to waiting, in-progress, cancelled"),
+        "{}",
+        stderr
+    );
+    let output = app()
+        .args(["update", "1", "done"])
+        .env("TODO_STRICT_TRANSITIONS", "1")
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(stdout(&["count", "done"]), "0\n");
+
+    let forced = stdout(&["--strict-transitions", "done", "1", "--force"]);
+    assert!(forced.contains("Task 1 marked done"), "{}", forced);
+    assert!(
+        run(&["--strict-transitions", "update", "2", "in-progress"])
+            .status
+            .success()
+    );
+    let all = stdout(&["--strict-transitions", "update", "all", "done"]);
+    assert!(all.contains("Marked 1 task done"), "{}", all);
+    assert!(
+        all.contains("1 task was left alone: strict transitions don't allow done"),
+        "{}",
+        all
+    );
+
+    // Without the flag, nothing changes from before
+    assert!(run(&["toggle", "1"]).status.success());
+    assert!(run(&["done", "1"]).status.success());
+    assert_eq!(stdout(&["count", "done"]), "2\n");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn saved_filters_say_what_they_stand_for_and_yield_to_statuses() {
+    let dir = scratch_dir("saved-filters");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    assert!(run(&["add", "Fix login; Water plants"]).status.success());
+    assert!(run(&["update", "1", "in-progress"]).status.success());
+    let saved = stdout(&["filter", "save", "urgent", "\"in-progress", "login\""]);
+    assert!(
+        saved.contains("Saved filter \"urgent\": in-progress login"),
+        "{}",
+        saved
+    );
+
+    let listed = stdout(&["list", "urgent"]);
+    assert!(
+        listed.contains("Using urgent: list in-progress \"login\""),
+        "{}",
+        listed
+    );
+    assert!(
+        listed.contains("1. Fix") && !listed.contains("Water plants"),
+        "{}",
+        listed
+    );
+    let json = stdout(&["list", "urgent", "--json"]);
+    assert!(json.trim_start().starts_with('['), "{}", json);
+    let output = run(&["filter", "save", "done", "todo"]);
+    assert!(!output.status.success());
+
+    // A name a newer version took for a status is warned about and left alone
+    let file = dir.join("tasks.json");
+    let text = fs::read_to_string(&file)
+        .unwrap()
+        .replace("\"urgent\"", "\"waiting\"");
+    fs::write(&file, text).unwrap();
+    let output = run(&["list", "waiting"]);
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("Saved filter \"waiting\" is now a word of list's own"),
+        "{}",
+        stderr
+    );
+    let listed = String::from_utf8(output.stdout).unwrap();
+    assert!(!listed.contains("Using"), "{}", listed);
+    assert!(run(&["filter", "delete", "waiting"]).status.success());
+    assert!(stdout(&["filters"]).contains("No saved filters yet"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn snippets_expand_at_add_time_and_list_what_is_missing() {
+    let dir = scratch_dir("snippets");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+    let stdout = |args: &[&str]| String::from_utf8(run(args).stdout).unwrap();
+    let saved = stdout(&["snippet", "save", "pr", "Review PR #{num} for {repo}"]);
+    assert!(saved.contains("Saved snippet \"pr\""), "{}", saved);
+
+    let added = stdout(&["add", "@pr", "num=482", "repo=todo-cli"]);
+    assert!(
+        added.contains("From @pr: Review PR #482 for todo-cli"),
+        "{}",
+        added
+    );
+    let output = run(&["add", "@pr", "num=483"]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(
+        stderr.contains("Missing a value for repo. This snippet takes: num=… repo=…"),
+        "{}",
+        stderr
+    );
+    assert!(stdout(&["list"]).contains("1. Review PR #482 for todo-cli"));
+    assert!(stdout(&["snippets"]).contains("@pr  Review PR #{num} for {repo}"));
+    assert!(run(&["snippet", "delete", "pr"]).status.success());
+    assert!(stdout(&["snippet", "list"]).contains("No snippets yet"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// `list --json` and `show --json` give the tasks as the file keeps them,
+// each with its number, and nothing else on stdout
+#[test]
+fn json_output_parses_back_into_the_saved_tasks() {
+    use rust_todo_cli::todo::{Storable, Task, TodoList};
+    use serde_json::Value;
+
+    let dir = scratch_dir("json-round-trip");
+    let run = |args: &[&str]| app().args(args).current_dir(&dir).output().unwrap();
+
+    // An empty list is still an array
+    let output = run(&["list", "--json"]);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8(output.stdout).unwrap().trim(), "[]");
+
+    for args in [
+        &["add", "Buy milk #errand +home due:2025-07-01"][..],
+        &["add", "Call mum 📞"],
+        &["sub", "2", "Find number"],
+        &["done", "1"],
+        &["tag", "2", "family"],
+    ] {
+        assert!(run(args).status.success(), "{:?}", args);
+    }
+    let saved = TodoList::load(dir.join("tasks.json").to_str().unwrap()).unwrap();
+
+    let output = run(&["list", "--json"]);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty(), "{:?}", output);
+    let listed: Vec<Value> = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(listed.len(), saved.len());
+    for (n, mut row) in listed.into_iter().enumerate() {
+        assert_eq!(row["index"], n + 1);
+        row.as_object_mut().unwrap().remove("index");
+        let task: Task = serde_json::from_value(row).unwrap();
+        assert_eq!(task, saved[n]);
+    }
+
+    let output = run(&["show", "2", "--json"]);
+    let mut shown: Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(shown["index"], 2);
+    shown.as_object_mut().unwrap().remove("index");
+    let task: Task = serde_json::from_value(shown).unwrap();
+    assert_eq!(task, saved[1]);
+    assert_eq!(task.subtasks[0].description, "Find number");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Nothing can ask for the next page after a single command or down a pipe,
+// so both get every task
+#[test]
+fn one_shot_and_piped_lists_are_not_paged() {
+    use std::io::Write;
+    use std::process::Stdio;
+
+    use rust_todo_cli::store::Store;
+    use rust_todo_cli::todo::TodoList;
+
+    let dir = scratch_dir("unpaged");
+    let mut todo = TodoList::new();
+    for n in 1..=300 {
+        todo.add_tasks(format!("Task number {}", n)).unwrap();
+    }
+    Store::in_dir(&dir).save(&todo).unwrap();
+    let rows = |stdout: &str| {
+        stdout
+            .lines()
+            .filter(|line| line.contains("Task number"))
+            .count()
+    };
+
+    let output = app().arg("list").current_dir(&dir).output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(rows(&stdout), 300);
+    assert!(stdout.contains("Task number 300"));
+    assert!(!stdout.contains("'more'"), "{}", stdout);
+
+    let mut child = app()
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    writeln!(child.stdin.take().unwrap(), "list\nmore\nexit").unwrap();
+    let output = child.wait_with_output().unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(rows(&stdout), 300);
+    assert!(stdout.contains("Nothing more to show"), "{}", stdout);
+
+    // Asking for pages still doesn't page a single command
+    let output = app()
+        .args(["--page-size", "10", "list"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert_eq!(rows(&String::from_utf8(output.stdout).unwrap()), 300);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Escape sequences in a file written by something else never reach the
+// terminal, whether it is listed or merged in
+#[test]
+fn escape_sequences_in_loaded_files_are_not_printed() {
+    let dir = scratch_dir("escapes-loaded");
+    let evil = r#"[{"id": 1, "description": "evil \u001b]0;pwned\u0007 \u001b[31mred", "status": "Todo"}]"#;
+    fs::write(dir.join("tasks.json"), evil).unwrap();
+    let output = app().args(["list"]).current_dir(&dir).output().unwrap();
+    assert!(output.status.success());
+    assert!(!output.stdout.contains(&0x1b), "{:?}", output);
+    assert!(
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .contains("evil red")
+    );
+
+    let theirs =
+        r#"[{"id": 2, "description": "\u001b[2Jsneaky \u001b[5mblink", "status": "Todo"}]"#;
+    fs::write(dir.join("theirs.json"), theirs).unwrap();
+    let output = app()
+        .args(["merge", "theirs.json"])
+        .current_dir(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{:?}", output);
+    assert!(!output.stdout.contains(&0x1b), "{:?}", output);
+    assert!(!output.stderr.contains(&0x1b), "{:?}", output);
+    let saved = fs::read_to_string(dir.join("tasks.json")).unwrap();
+    assert!(saved.contains("sneaky blink"), "{}", saved);
+    assert!(!saved.contains("\\u001b"), "{}", saved);
+    fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,101 @@
+use rust_todo_cli::clipboard::{Clipboard, Encoding, copy_list, copy_task, list_text, task_text};
+use rust_todo_cli::command::OutputFormat;
+use rust_todo_cli::todo::{Status, Task, TodoError, TodoList};
+
+// Keeps what it is given instead of touching the system clipboard
+#[derive(Default)]
+struct Recorder {
+    texts: Vec<String>,
+    broken: bool,
+}
+
+impl Clipboard for Recorder {
+    fn set_text(&mut self, text: &str) -> Result<(), TodoError> {
+        if self.broken {
+            return Err(TodoError::ClipboardError("no display".to_string()));
+        }
+        self.texts.push(text.to_string());
+        Ok(())
+    }
+}
+
+fn sample() -> TodoList {
+    let milk = Task::new("Buy milk #shop".to_string()).unwrap();
+    let mut report = Task::new("Write report".to_string()).unwrap();
+    report.status = Status::InProgress;
+    report
+        .subtasks
+        .push(Task::new("Outline".to_string()).unwrap());
+    [milk, report].into_iter().collect()
+}
+
+#[test]
+fn a_task_copies_as_its_description() {
+    let todo = sample();
+    assert_eq!(
+        task_text(&todo, 1, OutputFormat::Human).unwrap(),
+        "Buy milk #shop"
+    );
+}
+
+#[test]
+fn json_copies_the_whole_task_with_its_number() {
+    let todo = sample();
+    let text = task_text(&todo, 2, OutputFormat::Json).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(value["index"], 2);
+    assert_eq!(value["description"], "Write report");
+    assert_eq!(value["status"], "InProgress");
+    assert_eq!(value["subtasks"][0]["description"], "Outline");
+}
+
+#[test]
+fn the_list_copies_as_plain_text() {
+    let todo = sample();
+    let text = list_text(&todo);
+    assert_eq!(text, format!("{:#}", todo));
+    assert!(text.contains("Buy milk"), "{}", text);
+    assert!(text.contains("Outline"), "{}", text);
+    assert!(text.is_ascii(), "{}", text);
+}
+
+#[test]
+fn copying_hands_the_text_to_the_clipboard() {
+    let todo = sample();
+    let mut clipboard = Recorder::default();
+    let copied = copy_task(&mut clipboard, &todo, 1, OutputFormat::Human).unwrap();
+    assert_eq!(copied, "Buy milk #shop");
+    let listed = copy_list(&mut clipboard, &todo).unwrap();
+    assert_eq!(clipboard.texts, [copied, listed]);
+}
+
+#[test]
+fn bad_numbers_and_clipboard_failures_are_errors() {
+    let todo = sample();
+    let mut clipboard = Recorder::default();
+    assert!(matches!(
+        copy_task(&mut clipboard, &todo, 3, OutputFormat::Human),
+        Err(TodoError::IndexOutOfBound(3))
+    ));
+    assert!(clipboard.texts.is_empty());
+
+    clipboard.broken = true;
+    let error = copy_list(&mut clipboard, &todo).unwrap_err();
+    assert!(matches!(error, TodoError::ClipboardError(_)));
+    assert!(error.to_string().contains("use show"), "{}", error);
+}
+
+// What Windows' clip is sent: a byte order mark, then UTF-16 little-endian,
+// surrogate pairs and all
+#[test]
+fn clip_gets_utf16_after_a_byte_order_mark() {
+    assert_eq!(
+        Encoding::Utf16Le.encode("Café ✓ 🥛"),
+        [
+            0xFF, 0xFE, 0x43, 0x00, 0x61, 0x00, 0x66, 0x00, 0xE9, 0x00, 0x20, 0x00, 0x13, 0x27,
+            0x20, 0x00, 0x3E, 0xD8, 0x5B, 0xDD,
+        ]
+    );
+    assert_eq!(Encoding::Utf16Le.encode(""), [0xFF, 0xFE]);
+    assert_eq!(Encoding::Utf8.encode("Café"), "Café".as_bytes());
+}
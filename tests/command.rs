@@ -0,0 +1,851 @@
+use std::time::Duration;
+
+use rust_todo_cli::command::{
+    COMMANDS, ClearTarget, Command, CountTarget, DEFAULT_AGENDA_DAYS, Limit, OutputFormat,
+    ParseError, SyncAction, find_command, parse_command, resolve_command, split_chain, suggestions,
+};
+use rust_todo_cli::import::Format;
+use rust_todo_cli::merge::Side;
+use rust_todo_cli::theme::Color;
+use rust_todo_cli::todo::{Estimate, ListFilter, Marker, Selector, Status, TaskIndex, TodoError};
+
+fn number(task: usize) -> Selector {
+    Selector::Index(TaskIndex { task, sub: None })
+}
+
+#[test]
+fn add_splits_on_semicolons() {
+    let command = parse_command(r"add Buy milk; Call mum\; later").unwrap();
+    assert_eq!(
+        command,
+        Command::Add(
+            vec!["Buy milk".to_string(), "Call mum; later".to_string()],
+            false
+        )
+    );
+}
+
+#[test]
+fn mark_takes_a_marker_a_color_or_clear() {
+    assert_eq!(
+        parse_command("mark 3 🔥 red").unwrap(),
+        Command::Mark(
+            number(3),
+            Some(Marker {
+                text: Some("🔥".to_string()),
+                color: Some(Color::Red)
+            })
+        )
+    );
+    assert_eq!(
+        parse_command("mark 3 --clear").unwrap(),
+        Command::Mark(number(3), None)
+    );
+    assert!(parse_command("mark 3").is_err());
+    let error = parse_command("mark 3 🔥 purple").unwrap_err();
+    assert_eq!(error.code(), "UnknownColor");
+}
+
+#[test]
+fn import_takes_a_format_and_a_path() {
+    assert_eq!(
+        parse_command("import GitHub my issues.json").unwrap(),
+        Command::Import(Format::GitHub, "my issues.json".to_string())
+    );
+    assert!(parse_command("import github").is_err());
+    assert!(parse_command("import jira issues.json").is_err());
+}
+
+#[test]
+fn add_takes_no_duplicates_anywhere_in_the_line() {
+    assert_eq!(
+        parse_command("add Buy milk --no-duplicates").unwrap(),
+        Command::Add(vec!["Buy milk".to_string()], true)
+    );
+    assert_eq!(
+        parse_command("add --no-duplicates --from plan.txt").unwrap(),
+        Command::AddFrom("plan.txt".to_string(), true)
+    );
+    assert_eq!(
+        parse_command("add --stdin --no-duplicates").unwrap(),
+        Command::AddStdin(true)
+    );
+    assert!(matches!(
+        parse_command("add --stdin ideas.txt"),
+        Err(ParseError::Usage(_))
+    ));
+    assert!(parse_command("add --no-duplicates").is_err());
+}
+
+#[test]
+fn count_takes_a_status_or_a_due_date_target() {
+    assert_eq!(parse_command("count").unwrap(), Command::Count(None));
+    assert_eq!(
+        parse_command("count In-Progress").unwrap(),
+        Command::Count(Some(CountTarget::Status(Status::InProgress)))
+    );
+    assert_eq!(
+        parse_command("count overdue").unwrap(),
+        Command::Count(Some(CountTarget::Overdue))
+    );
+    assert_eq!(
+        parse_command("count due-today").unwrap(),
+        Command::Count(Some(CountTarget::DueToday))
+    );
+    assert!(!Command::Count(None).mutates());
+
+    let error = parse_command("count later").unwrap_err();
+    assert_eq!(error.code(), "UnknownCountTarget");
+    let message = error.to_string();
+    for target in ["todo", "in-progress", "cancelled", "overdue", "due-today"] {
+        assert!(message.contains(target), "{}", message);
+    }
+    assert!(matches!(
+        parse_command("count todo done"),
+        Err(ParseError::Usage(_))
+    ));
+}
+
+#[test]
+fn list_takes_a_status_a_limit_and_json() {
+    let command = parse_command("list done --limit 5 --json").unwrap();
+    let filter = ListFilter {
+        status: Some(Status::Completed),
+        ..ListFilter::default()
+    };
+    assert_eq!(
+        command,
+        Command::List {
+            filter,
+            limit: Some(Limit::First(5)),
+            format: OutputFormat::Json,
+        }
+    );
+}
+
+#[test]
+fn list_sorts_by_recency_and_filters_by_time_untouched() {
+    let Command::List { filter, .. } = parse_command("list todo --stale 2w --recent").unwrap()
+    else {
+        panic!("list parses to a listing");
+    };
+    assert_eq!(filter.untouched, Some(Duration::from_secs(14 * 86_400)));
+    assert!(filter.recent);
+    assert_eq!(filter.status, Some(Status::Todo));
+    assert!(matches!(
+        parse_command("list --stale soon"),
+        Err(ParseError::Invalid(TodoError::InvalidDuration(_)))
+    ));
+    assert!(matches!(
+        parse_command("list --stale"),
+        Err(ParseError::Usage(_))
+    ));
+}
+
+#[test]
+fn list_grouped_is_a_way_of_showing_not_a_filter() {
+    let Command::List { filter, .. } = parse_command("list todo --grouped").unwrap() else {
+        panic!("list parses to a listing");
+    };
+    assert!(filter.grouped);
+    assert_eq!(filter.status, Some(Status::Todo));
+    assert_eq!(filter.to_string(), "todo --grouped");
+}
+
+#[test]
+fn selectors_are_numbers_or_quoted_text() {
+    assert_eq!(
+        parse_command("done 1 \"call mum\"").unwrap(),
+        Command::Done(
+            vec![number(1), Selector::Text("call mum".to_string())],
+            false
+        )
+    );
+    assert_eq!(
+        parse_command("remove 2 --yes").unwrap(),
+        Command::Remove(number(2), true)
+    );
+}
+
+#[test]
+fn update_takes_one_task_or_a_whole_status() {
+    assert_eq!(
+        parse_command("update 3 done").unwrap(),
+        Command::Update(number(3), "done".to_string(), false)
+    );
+    assert_eq!(
+        parse_command("update todo in-progress").unwrap(),
+        Command::UpdateAll(Some(Status::Todo), Status::InProgress, false)
+    );
+    assert!(matches!(
+        parse_command("update all"),
+        Err(ParseError::MissingStatus(_))
+    ));
+}
+
+#[test]
+fn merge_takes_a_path_and_a_side() {
+    assert_eq!(
+        parse_command("merge laptop/tasks.json").unwrap(),
+        Command::Merge("laptop/tasks.json".to_string(), None)
+    );
+    assert_eq!(
+        parse_command("merge --theirs old copy.json").unwrap(),
+        Command::Merge("old copy.json".to_string(), Some(Side::Theirs))
+    );
+    assert_eq!(
+        parse_command("merge a.json --ours").unwrap(),
+        Command::Merge("a.json".to_string(), Some(Side::Ours))
+    );
+    for input in ["merge", "merge --ours", "merge a.json --ours --theirs"] {
+        assert!(
+            matches!(parse_command(input), Err(ParseError::Usage(_))),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn sync_takes_a_direction_and_a_side() {
+    let cases = [
+        ("sync", Command::Sync(SyncAction::Both, None)),
+        (
+            "sync --theirs",
+            Command::Sync(SyncAction::Both, Some(Side::Theirs)),
+        ),
+        ("sync pull", Command::Sync(SyncAction::Pull, None)),
+        (
+            "sync --ours pull",
+            Command::Sync(SyncAction::Pull, Some(Side::Ours)),
+        ),
+        ("sync push", Command::Sync(SyncAction::Push(false), None)),
+        (
+            "sync push --force",
+            Command::Sync(SyncAction::Push(true), None),
+        ),
+    ];
+    for (input, expected) in cases {
+        assert_eq!(parse_command(input).unwrap(), expected, "{:?}", input);
+    }
+    for input in [
+        "sync both",
+        "sync pull push",
+        "sync --ours --theirs",
+        "sync push --theirs",
+        "sync pull --force",
+        "sync --force",
+    ] {
+        assert!(
+            matches!(parse_command(input), Err(ParseError::Usage(_))),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn report_md_takes_a_path_and_force() {
+    assert_eq!(parse_command("report").unwrap(), Command::Week);
+    assert_eq!(
+        parse_command("report md standup.md").unwrap(),
+        Command::Report("standup.md".to_string(), false)
+    );
+    assert_eq!(
+        parse_command("report md --force notes/stand up.md").unwrap(),
+        Command::Report("notes/stand up.md".to_string(), true)
+    );
+    for input in ["report md", "report md --force", "report pdf standup.pdf"] {
+        assert!(
+            matches!(parse_command(input), Err(ParseError::Usage(_))),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn random_takes_the_filters_of_list() {
+    let filter = ListFilter {
+        status: Some(Status::Todo),
+        tags: vec!["home".to_string()],
+        projects: vec!["garden".to_string()],
+        ..ListFilter::default()
+    };
+    assert_eq!(
+        parse_command("random todo tag:home +garden").unwrap(),
+        Command::Random(filter)
+    );
+    assert_eq!(
+        parse_command("random").unwrap(),
+        Command::Random(ListFilter::default())
+    );
+    assert!(matches!(
+        parse_command("random priority:high"),
+        Err(ParseError::UnknownFilter(key)) if key == "priority"
+    ));
+}
+
+#[test]
+fn estimate_takes_a_time_or_none() {
+    assert_eq!(
+        parse_command("estimate 3 1h 30m").unwrap(),
+        Command::Estimate(number(3), Some(Estimate { minutes: 90 }))
+    );
+    assert_eq!(
+        parse_command("estimate 3 none").unwrap(),
+        Command::Estimate(number(3), None)
+    );
+    assert!(matches!(
+        parse_command("estimate 3 soon"),
+        Err(ParseError::Invalid(TodoError::InvalidEstimate(_)))
+    ));
+    assert!(matches!(
+        parse_command("estimate 3"),
+        Err(ParseError::Usage(_))
+    ));
+}
+
+#[test]
+fn copy_takes_a_task_or_the_list() {
+    assert_eq!(
+        parse_command("copy 3").unwrap(),
+        Command::Copy(number(3), OutputFormat::Human)
+    );
+    assert_eq!(
+        parse_command("copy \"groceries\" --json").unwrap(),
+        Command::Copy(Selector::Text("groceries".to_string()), OutputFormat::Json)
+    );
+    assert_eq!(parse_command("copy list").unwrap(), Command::CopyList);
+    for input in ["copy", "copy 1 2"] {
+        assert!(
+            matches!(parse_command(input), Err(ParseError::Usage(_))),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn agenda_takes_a_number_of_days_and_empty() {
+    assert_eq!(
+        parse_command("agenda").unwrap(),
+        Command::Agenda(DEFAULT_AGENDA_DAYS, false)
+    );
+    assert_eq!(
+        parse_command("agenda --empty 14").unwrap(),
+        Command::Agenda(14, true)
+    );
+    for input in ["agenda 0", "agenda soon", "agenda 3 4"] {
+        assert!(
+            matches!(parse_command(input), Err(ParseError::Usage(_))),
+            "{:?}",
+            input
+        );
+    }
+}
+
+#[test]
+fn clear_takes_a_target() {
+    assert_eq!(
+        parse_command("clear all -y").unwrap(),
+        Command::Clear(Some(ClearTarget::All), true, false)
+    );
+    assert_eq!(
+        parse_command("clear done --dry-run").unwrap(),
+        Command::Clear(Some(ClearTarget::Status(Status::Completed)), false, true)
+    );
+    assert!(!Command::Clear(None, false, true).mutates());
+    assert!(matches!(
+        parse_command("clear soon"),
+        Err(ParseError::Usage(_))
+    ));
+}
+
+#[test]
+fn mistakes_are_errors_not_commands() {
+    assert!(matches!(parse_command(""), Err(ParseError::Empty)));
+    assert!(matches!(
+        parse_command("fly away"),
+        Err(ParseError::UnknownCommand(word, _)) if word == "fly"
+    ));
+    assert!(matches!(parse_command("add"), Err(ParseError::Usage(_))));
+    assert!(matches!(
+        parse_command("list priority:high"),
+        Err(ParseError::UnknownFilter(key)) if key == "priority"
+    ));
+    // The message names every filter there is
+    let error = parse_command("list priority:high").unwrap_err().to_string();
+    assert!(error.ends_with("status:<status>, tag:<name>, waiting-on:<name>"));
+    assert!(matches!(
+        parse_command("due 1 someday"),
+        Err(ParseError::Invalid(_))
+    ));
+}
+
+#[test]
+fn only_changes_mutate() {
+    assert!(parse_command("add x").unwrap().mutates());
+    assert!(!parse_command("list").unwrap().mutates());
+    assert!(!parse_command("dedupe --dry-run").unwrap().mutates());
+    assert!(!parse_command("doctor").unwrap().mutates());
+    assert!(parse_command("doctor --fix").unwrap().mutates());
+}
+
+#[test]
+fn typos_suggest_the_closest_command() {
+    let cases: &[(&str, &[&str])] = &[
+        ("lsit", &["list"]),
+        ("ad", &["add"]),
+        ("shwo", &["show"]),
+        ("histroy", &["history"]),
+        ("pomodro", &["pomodoro"]),
+        ("stat", &["stats"]),
+        // A close alias suggests the command it belongs to
+        ("gerp", &["search"]),
+        ("quti", &["exit"]),
+        // Equally close commands are all offered, in help order
+        ("dne", &["due", "done"]),
+        ("ext", &["next", "exit"]),
+        // Short words only get suggestions one edit away
+        ("tod", &[]),
+        ("fly", &[]),
+        ("qwxyz", &[]),
+    ];
+    for (word, expected) in cases {
+        assert_eq!(&suggestions(word), expected, "suggestions for {:?}", word);
+    }
+}
+
+#[test]
+fn unknown_commands_carry_their_suggestions() {
+    let error = parse_command("lsit done").unwrap_err();
+    assert!(matches!(
+        &error,
+        ParseError::UnknownCommand(word, suggestions) if word == "lsit" && suggestions == &["list"]
+    ));
+    assert_eq!(
+        error.to_string(),
+        "Unknown command 'lsit'. Did you mean 'list'?"
+    );
+    assert_eq!(
+        parse_command("ext").unwrap_err().to_string(),
+        "Unknown command 'ext'. Did you mean 'next' or 'exit'?"
+    );
+    assert_eq!(
+        parse_command("fly away").unwrap_err().to_string(),
+        "Unknown command 'fly'"
+    );
+}
+
+#[test]
+fn every_command_in_the_table_is_parsed() {
+    for spec in COMMANDS {
+        for word in std::iter::once(&spec.name).chain(spec.aliases) {
+            assert_eq!(find_command(word).map(|found| found.name), Some(spec.name));
+            assert!(
+                !matches!(parse_command(word), Err(ParseError::UnknownCommand(..))),
+                "{} is listed but not parsed",
+                word
+            );
+        }
+    }
+}
+
+#[test]
+fn short_forms_and_prefixes_resolve() {
+    let cases = [
+        ("a", "add"),
+        ("l", "list"),
+        ("ls", "list"),
+        ("u", "update"),
+        ("status", "update"),
+        ("d", "done"),
+        ("rm", "remove"),
+        ("delete", "remove"),
+        ("h", "help"),
+        ("q", "exit"),
+        ("quit", "exit"),
+        ("li", "list"),
+        ("sea", "search"),
+        ("gr", "search"),
+        ("sho", "show"),
+        ("upd", "update"),
+        ("temp", "template"),
+        ("pom", "pomodoro"),
+        ("ov", "overdue"),
+        ("untag", "untag"),
+        ("unt", "untag"),
+        // A whole name wins over being the start of a longer one
+        ("tag", "tag"),
+        ("due", "due"),
+    ];
+    for (word, name) in cases {
+        assert_eq!(
+            resolve_command(word).map(|spec| spec.name).ok(),
+            Some(name),
+            "{:?}",
+            word
+        );
+    }
+}
+
+#[test]
+fn chains_split_on_a_bare_ampersand_pair_outside_quotes() {
+    assert_eq!(
+        split_chain("add Buy  milk && done 1 &&list"),
+        ["add Buy milk", "done 1 &&list"]
+    );
+    assert_eq!(split_chain("list"), ["list"]);
+    assert_eq!(split_chain("add Tom \\&& Jerry"), ["add Tom && Jerry"]);
+    assert_eq!(
+        split_chain("add \"Tom && Jerry\" && list"),
+        ["add \"Tom && Jerry\"", "list"]
+    );
+    assert_eq!(split_chain("list &&"), ["list", ""]);
+}
+
+#[test]
+fn run_takes_a_path_and_the_continue_flag_anywhere() {
+    assert_eq!(
+        parse_command("run setup.todo").unwrap(),
+        Command::Run("setup.todo".to_string(), false)
+    );
+    assert_eq!(
+        parse_command("run --continue-on-error my tasks.todo").unwrap(),
+        Command::Run("my tasks.todo".to_string(), true)
+    );
+    assert!(parse_command("run --continue-on-error").is_err());
+    assert!(parse_command("run a.todo").unwrap().mutates());
+}
+
+#[test]
+fn save_takes_only_force() {
+    assert_eq!(parse_command("save").unwrap(), Command::Save(false));
+    assert_eq!(parse_command("save --force").unwrap(), Command::Save(true));
+    assert!(parse_command("save tasks.json").is_err());
+    assert!(parse_command("save").unwrap().writes());
+}
+
+#[test]
+fn every_command_has_a_short_form() {
+    // The shortest word that runs each command
+    let shortest = [
+        ("add", "a"),
+        ("list", "l"),
+        ("search", "se"),
+        ("fuzzy", "fu"),
+        ("show", "sh"),
+        ("more", "m"),
+        ("next", "n"),
+        ("random", "ra"),
+        ("pick", "pi"),
+        ("open", "op"),
+        ("copy", "cop"),
+        ("stats", "stats"),
+        ("count", "cou"),
+        ("projects", "pro"),
+        ("contexts", "con"),
+        ("week", "w"),
+        ("chart", "ch"),
+        ("review", "rev"),
+        ("due", "du"),
+        ("repeat", "repe"),
+        ("snooze", "sno"),
+        ("estimate", "est"),
+        ("escalate", "esc"),
+        ("track", "trac"),
+        ("stop", "sto"),
+        ("pomodoro", "po"),
+        ("today", "tod"),
+        ("overdue", "ov"),
+        ("agenda", "ag"),
+        ("update", "u"),
+        ("wait", "wait"),
+        ("delegate", "deleg"),
+        ("waiting", "waiti"),
+        ("done", "d"),
+        ("toggle", "tog"),
+        ("reopen", "reo"),
+        ("sub", "su"),
+        ("tag", "ta"),
+        ("untag", "unt"),
+        ("mark", "ma"),
+        ("append", "ap"),
+        ("prepend", "pre"),
+        ("blocks", "bl"),
+        ("remove", "rm"),
+        ("clear", "cl"),
+        ("dedupe", "ded"),
+        ("doctor", "doc"),
+        ("renumber", "ren"),
+        ("archive", "ar"),
+        ("trash", "tras"),
+        ("restore", "res"),
+        ("history", "hi"),
+        ("template", "te"),
+        ("filter", "fi"),
+        ("snippet", "sni"),
+        ("run", "ru"),
+        ("save", "sa"),
+        ("reload", "rel"),
+        ("merge", "me"),
+        ("import", "i"),
+        ("sync", "sy"),
+        ("backup", "ba"),
+        ("version", "v"),
+        ("completions", "com"),
+        ("help", "h"),
+        ("exit", "q"),
+    ];
+    assert_eq!(shortest.len(), COMMANDS.len());
+    for (name, short) in shortest {
+        assert_eq!(
+            resolve_command(short).map(|spec| spec.name).ok(),
+            Some(name),
+            "{:?}",
+            short
+        );
+        assert_eq!(resolve_command(name).unwrap().name, name);
+    }
+}
+
+#[test]
+fn shared_starts_list_the_candidates() {
+    let cases: &[(&str, &[&str])] = &[
+        (
+            "c",
+            &["copy", "count", "contexts", "chart", "clear", "completions"],
+        ),
+        ("st", &["stats", "stop", "update"]),
+        // `report` is an alias of `week`
+        (
+            "re",
+            &[
+                "week", "review", "repeat", "reopen", "remove", "renumber", "restore", "reload",
+            ],
+        ),
+        ("pr", &["projects", "prepend"]),
+        ("wa", &["wait", "waiting"]),
+        // `undone` and `uncomplete` are aliases of `reopen`
+        ("un", &["reopen", "untag"]),
+        ("b", &["blocks", "backup"]),
+    ];
+    for (word, expected) in cases {
+        match resolve_command(word) {
+            Err(ParseError::AmbiguousCommand(typed, candidates)) => {
+                assert_eq!(typed, *word);
+                assert_eq!(&candidates, expected, "{:?}", word);
+            }
+            other => panic!("{:?} resolved to {:?}", word, other),
+        }
+    }
+    assert_eq!(
+        parse_command("pr 1 x").unwrap_err().to_string(),
+        "Ambiguous command 'pr': could be 'projects' or 'prepend'"
+    );
+}
+
+#[test]
+fn typos_are_not_taken_for_prefixes() {
+    for word in ["lsit", "shwo", "tmeplate", "xyz", "adds"] {
+        assert!(
+            matches!(resolve_command(word), Err(ParseError::UnknownCommand(..))),
+            "{:?}",
+            word
+        );
+    }
+}
+
+#[test]
+fn help_takes_a_command_by_any_of_its_words() {
+    for (input, name) in [
+        ("help add", "add"),
+        ("help ls", "list"),
+        ("h upd", "update"),
+    ] {
+        match parse_command(input) {
+            Ok(Command::Help(Some(spec))) => assert_eq!(spec.name, name),
+            other => panic!("{:?} parsed as {:?}", input, other),
+        }
+    }
+    assert_eq!(parse_command("help").unwrap(), Command::Help(None));
+    assert_eq!(
+        parse_command("help hlep").unwrap_err().to_string(),
+        "Unknown command 'hlep'. Did you mean 'help'?"
+    );
+    assert!(matches!(
+        parse_command("help c"),
+        Err(ParseError::AmbiguousCommand(..))
+    ));
+}
+
+#[test]
+fn usage_errors_come_from_the_command_table() {
+    for (input, name) in [("update 1", "update"), ("tag 1", "tag"), ("rm", "remove")] {
+        let spec = find_command(name).unwrap();
+        match parse_command(input) {
+            Err(ParseError::Usage(text)) => assert_eq!(text, spec.synopsis()),
+            other => panic!("{:?} parsed as {:?}", input, other),
+        }
+    }
+    assert_eq!(
+        find_command("update").unwrap().synopsis(),
+        "update <num> <status> | update all <status>"
+    );
+}
+
+#[test]
+fn every_spec_is_documented_and_its_examples_parse() {
+    for spec in COMMANDS {
+        assert!(!spec.usage.is_empty(), "{} has no usage", spec.name);
+        assert!(
+            !spec.description.is_empty(),
+            "{} has no description",
+            spec.name
+        );
+        assert!(!spec.examples.is_empty(), "{} has no examples", spec.name);
+        for example in spec.examples {
+            let command = parse_command(example)
+                .unwrap_or_else(|error| panic!("{:?} does not parse: {}", example, error));
+            assert!(find_command(command.name()).is_some(), "{:?}", example);
+        }
+    }
+}
+
+#[test]
+fn every_command_variant_has_a_spec() {
+    // One input for each variant
+    let inputs = [
+        "exit",
+        "help",
+        "list",
+        "list archived",
+        "search milk",
+        "show 1",
+        "random todo",
+        "open 1",
+        "copy 1",
+        "copy list",
+        "more",
+        "next",
+        "stats",
+        "projects",
+        "contexts",
+        "today",
+        "overdue",
+        "agenda 3 --empty",
+        "due 1 none",
+        "repeat 1 none",
+        "snooze 1 2d",
+        "estimate 1 2h",
+        "estimate 1 none",
+        "escalate",
+        "escalate 30",
+        "track 1",
+        "pomodoro 1",
+        "review",
+        "week",
+        "chart",
+        "stop",
+        "add milk",
+        "add --from plan.txt",
+        "update 1 done",
+        "update all done",
+        "remove 1",
+        "done 1",
+        "toggle 1",
+        "sub 1 paint",
+        "tag 1 work",
+        "untag 1 work",
+        "append 1 soon",
+        "prepend 1 now",
+        "blocks 1 on 2",
+        "clear",
+        "archive",
+        "dedupe",
+        "doctor --fix",
+        "trash",
+        "trash empty",
+        "restore 1",
+        "history",
+        "history clear",
+        "template save a 1",
+        "template apply a",
+        "template list",
+        "template delete a",
+        "save",
+        "reload --yes",
+        "merge theirs.json --ours",
+        "sync pull --theirs",
+        "report md standup.md --force",
+        "version",
+        "completions fish",
+    ];
+    for input in inputs {
+        let command = parse_command(input).unwrap();
+        let spec = find_command(command.name()).unwrap();
+        let word = input.split_whitespace().next().unwrap();
+        let expected = match word {
+            "search" => "list",
+            "report" => "week",
+            word => word,
+        };
+        assert_eq!(spec.name, expected, "{:?}", input);
+    }
+}
+
+#[test]
+fn parse_errors_serialize_with_the_code_of_their_cause() {
+    let error = parse_command("frobnicate").unwrap_err();
+    assert_eq!(error.code(), "UnknownCommand");
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["error"], "UnknownCommand");
+    assert_eq!(json["word"], "frobnicate");
+    assert_eq!(json["message"], error.to_string());
+
+    // A bad value is reported as the error it ran into
+    let error = ParseError::Invalid(TodoError::InvalidTag("a b".to_string()));
+    assert_eq!(error.code(), "InvalidTag");
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(
+        (&json["error"], &json["tag"]),
+        (&"InvalidTag".into(), &"a b".into())
+    );
+}
+
+#[test]
+fn pick_stands_in_for_a_task_number() {
+    let todo = ListFilter {
+        status: Some(Status::Todo),
+        ..ListFilter::default()
+    };
+    assert_eq!(
+        parse_command("done 1 --pick todo").unwrap(),
+        Command::Done(vec![number(1), Selector::Pick(todo.clone())], false)
+    );
+    assert_eq!(
+        parse_command("remove --pick --yes").unwrap(),
+        Command::Remove(Selector::Pick(ListFilter::default()), true)
+    );
+    // Words after the task are the command's own, not filters
+    assert_eq!(
+        parse_command("tag --pick urgent").unwrap(),
+        Command::Tag(
+            Selector::Pick(ListFilter::default()),
+            vec!["urgent".to_string()]
+        )
+    );
+    let work = ListFilter {
+        projects: vec!["work".to_string()],
+        ..todo
+    };
+    assert_eq!(
+        parse_command("pick todo +work").unwrap(),
+        Command::Show(Selector::Pick(work), OutputFormat::Human)
+    );
+    assert!(matches!(
+        parse_command("done --pick status:eventually"),
+        Err(ParseError::Invalid(TodoError::InvalidStatus(_)))
+    ));
+}
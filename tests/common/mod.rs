@@ -0,0 +1,74 @@
+// Helpers shared by the integration tests, each of which uses only some
+#![allow(dead_code)]
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+use rust_todo_cli::date::{Date, SECONDS_PER_DAY, Timestamp, utc_offset};
+use rust_todo_cli::todo::{Task, TodoList};
+
+// A fresh directory per test, so tests can run side by side
+pub fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("rust-todo-cli-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// Variables the app reads for its defaults, none of which a test should
+// pick up from the machine it runs on
+const SETTINGS: &[&str] = &[
+    "RUST_LOG",
+    "NO_COLOR",
+    "CLICOLOR_FORCE",
+    "TODO_READONLY",
+    "TODO_SYNC_TOKEN",
+    "TODO_AUTO_ARCHIVE_AFTER",
+    "TODO_STRICT_TRANSITIONS",
+];
+
+// The app, the same wherever the tests run: messages in English, 80
+// columns, no logging and none of the variables above. Output is piped, so
+// it is plain unless a test sets CLICOLOR_FORCE.
+pub fn app() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_rust-todo-cli"));
+    command.env("LC_ALL", "C").env("COLUMNS", "80");
+    for name in SETTINGS {
+        command.env_remove(name);
+    }
+    command
+}
+
+// Type each line into an interactive session of `command`, which ends
+// with the input
+pub fn typed(command: &mut Command, lines: &[&str]) -> Output {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    for line in lines {
+        writeln!(stdin, "{}", line).unwrap();
+    }
+    drop(stdin);
+    child.wait_with_output().unwrap()
+}
+
+// A list of plain tasks with these descriptions, numbered from 1
+pub fn list_of(descriptions: &[&str]) -> TodoList {
+    descriptions
+        .iter()
+        .map(|description| Task::new(description.to_string()).unwrap())
+        .collect()
+}
+
+// The moment `seconds` into a local day, so day boundaries hold in any
+// time zone
+pub fn local_time(date: Date, seconds: i64) -> Timestamp {
+    let utc = date.days() * SECONDS_PER_DAY + seconds;
+    (utc - utc_offset(utc as Timestamp)) as Timestamp
+}
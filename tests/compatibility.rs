@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use rust_todo_cli::date::Date;
+use rust_todo_cli::todo::{Priority, Status, Storable, TodoList};
+
+fn fixture(name: &str) -> TodoList {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    TodoList::load(&path).unwrap()
+}
+
+#[test]
+fn old_format_loads() {
+    let todo = fixture("old_format.json");
+    assert_eq!(todo.len(), 2);
+    assert_eq!(todo[1].status, Status::Completed);
+    // Files from before IDs and timestamps get IDs on load
+    assert_eq!(todo[0].id, 1);
+    assert_eq!(todo[1].id, 2);
+    assert_eq!(todo[0].created_at, None);
+    assert!(todo.templates().is_empty());
+    assert!(todo.unknown_statuses().is_empty());
+}
+
+#[test]
+fn current_format_loads() {
+    let todo = fixture("current_format.json");
+    assert_eq!(todo.len(), 2);
+    let trip = &todo[0];
+    assert_eq!(trip.status, Status::InProgress);
+    assert_eq!(trip.due, Date::new(2025, 6, 20));
+    assert_eq!(trip.recurrence.unwrap().to_string(), "every week");
+    assert_eq!(trip.subtasks[0].status, Status::Completed);
+    assert_eq!(todo[1].accumulated, Duration::from_secs(1500));
+    assert_eq!(todo.blockers(&todo[1]), [1]);
+    assert_eq!(todo.templates()["trip"].len(), 2);
+    assert!(todo.unknown_statuses().is_empty());
+}
+
+#[test]
+fn future_format_loads() {
+    let todo = fixture("future_format.json");
+    assert_eq!(todo.len(), 2);
+    // Unknown statuses fall back to todo and are reported, fields are ignored
+    assert_eq!(todo[0].status, Status::Todo);
+    assert_eq!(todo[1].subtasks[0].status, Status::Todo);
+    assert_eq!(todo.unknown_statuses(), ["Delegated", "Someday"]);
+    assert_eq!(todo[0].description, "Renew passport");
+    // Priorities load in any case
+    assert_eq!(todo[0].priority, Priority::High);
+    assert_eq!(todo[1].priority, Priority::Normal);
+}
+
+#[test]
+fn a_missing_status_means_todo() {
+    let dir = std::env::temp_dir().join(format!("rust-todo-cli-status-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json");
+    std::fs::write(&path, r#"[{ "description": "Bare" }]"#).unwrap();
+
+    let todo = TodoList::load(path.to_str().unwrap()).unwrap();
+    assert_eq!(todo[0].status, Status::Todo);
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,100 @@
+use rust_todo_cli::command::{COMMANDS, Command, ParseError, find_command, parse_command};
+use rust_todo_cli::completion::{Completions, Shell, script};
+use rust_todo_cli::todo::Status;
+
+const FLAGS: &[(&str, bool)] = &[("--plain", false), ("--lang", true), ("-q", false)];
+
+#[test]
+fn every_shell_gets_a_script() {
+    for shell in Shell::ALL {
+        let script = script(shell, "rust-todo-cli", FLAGS);
+        assert!(!script.trim().is_empty(), "{:?}", shell);
+        assert!(script.contains("rust-todo-cli"), "{:?}", shell);
+        for spec in COMMANDS {
+            assert!(
+                script.contains(spec.name),
+                "{:?} lacks {}",
+                shell,
+                spec.name
+            );
+        }
+    }
+}
+
+#[test]
+fn the_bash_script_has_every_command_alias_status_and_flag() {
+    let script = script(Shell::Bash, "rust-todo-cli", FLAGS);
+    let words: Vec<&str> = script
+        .split(|c: char| c.is_whitespace() || c == '"' || c == '|' || c == ')')
+        .collect();
+    for spec in COMMANDS {
+        for word in std::iter::once(&spec.name).chain(spec.aliases) {
+            assert!(words.contains(word), "{} is missing", word);
+        }
+    }
+    for status in Status::ALL {
+        assert!(words.contains(&status.keyword()), "{:?} is missing", status);
+    }
+    for word in ["--plain", "--lang", "-q", "--json", "--yes", "--force"] {
+        assert!(words.contains(&word), "{} is missing", word);
+    }
+    assert!(script.contains("complete -o default -F _rust_todo_cli rust-todo-cli"));
+}
+
+#[test]
+fn completions_come_from_the_usage_lines() {
+    let list = Completions::of(find_command("list").unwrap());
+    assert_eq!(list.words, ["blocked", "all", "archived"]);
+    assert_eq!(
+        list.flags,
+        [
+            "--json",
+            "--limit",
+            "--last",
+            "--recent",
+            "--stale",
+            "--by-due",
+            "--absolute",
+            "--grouped"
+        ]
+    );
+    assert!(list.statuses);
+
+    let trash = Completions::of(find_command("trash").unwrap());
+    assert_eq!(trash.words, ["empty"]);
+    assert_eq!(trash.flags, ["--json", "--yes"]);
+    assert!(!trash.statuses);
+
+    let sync = Completions::of(find_command("sync").unwrap());
+    assert_eq!(sync.words, ["pull", "push"]);
+    assert_eq!(sync.flags, ["--ours", "--theirs", "--force"]);
+
+    // Placeholders, bracketed or not, are not offered
+    let next = Completions::of(find_command("next").unwrap());
+    assert!(next.words.is_empty(), "{:?}", next);
+}
+
+#[test]
+fn statuses_offered_are_the_ones_parsed() {
+    for status in Status::ALL {
+        assert_eq!(status.keyword().parse::<Status>().unwrap(), status);
+    }
+}
+
+#[test]
+fn completions_takes_a_shell_name() {
+    for shell in Shell::ALL {
+        assert_eq!(
+            parse_command(&format!("completions {}", shell.name())).unwrap(),
+            Command::Completions(shell)
+        );
+        assert_eq!(Shell::from_name(&shell.name().to_uppercase()), Some(shell));
+    }
+    for input in ["completions", "completions tcsh", "completions bash zsh"] {
+        assert!(
+            matches!(parse_command(input), Err(ParseError::Usage(_))),
+            "{}",
+            input
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use rust_todo_cli::countdown::{Clock, Session, countdown};
+use rust_todo_cli::todo::TodoList;
+
+// A clock that only moves when slept on, and remembers every sleep
+#[derive(Default)]
+struct FakeClock {
+    now: Duration,
+    slept: Vec<Duration>,
+}
+
+impl Clock for FakeClock {
+    fn elapsed(&self) -> Duration {
+        self.now
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.slept.push(duration);
+        self.now += duration;
+    }
+}
+
+fn secs(seconds: f64) -> Duration {
+    Duration::from_secs_f64(seconds)
+}
+
+#[test]
+fn a_countdown_runs_to_the_end_a_second_at_a_time() {
+    let mut clock = FakeClock::default();
+    let mut ticks = Vec::new();
+    let session = countdown(&mut clock, secs(3.0), &AtomicBool::new(false), |left| {
+        ticks.push(left)
+    });
+    assert_eq!(
+        session,
+        Session {
+            spent: secs(3.0),
+            finished: true
+        }
+    );
+    assert_eq!(ticks, [secs(3.0), secs(2.0), secs(1.0)]);
+    assert_eq!(clock.slept, [secs(1.0); 3]);
+
+    // The last sleep is only as long as what is left
+    let mut clock = FakeClock::default();
+    let session = countdown(&mut clock, secs(1.5), &AtomicBool::new(false), |_| {});
+    assert!(session.finished);
+    assert_eq!(clock.slept, [secs(1.0), secs(0.5)]);
+}
+
+#[test]
+fn a_clock_running_late_still_ends_on_time() {
+    // Started 10 seconds into a 5-second countdown, say after a suspend
+    let mut clock = FakeClock {
+        now: secs(10.0),
+        ..FakeClock::default()
+    };
+    let mut ticked = false;
+    let session = countdown(&mut clock, secs(5.0), &AtomicBool::new(false), |_| {
+        ticked = true
+    });
+    assert_eq!(session.spent, secs(5.0));
+    assert!(session.finished && !ticked);
+}
+
+#[test]
+fn cancelling_stops_with_the_time_spent_so_far() {
+    let cancel = AtomicBool::new(false);
+    let mut clock = FakeClock::default();
+    let mut ticks = 0;
+    let session = countdown(&mut clock, secs(25.0 * 60.0), &cancel, |_| {
+        ticks += 1;
+        if ticks == 90 {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    });
+    assert_eq!(
+        session,
+        Session {
+            spent: secs(90.0),
+            finished: false
+        }
+    );
+    assert_eq!(clock.slept.len(), 90);
+
+    // Cancelled before it starts, nothing is spent or ticked
+    let mut clock = FakeClock::default();
+    let session = countdown(&mut clock, secs(60.0), &AtomicBool::new(true), |_| {
+        panic!("ticked")
+    });
+    assert_eq!(session.spent, Duration::ZERO);
+    assert!(!session.finished);
+}
+
+#[test]
+fn a_cancelled_session_logs_its_partial_time() {
+    let mut todo = TodoList::new();
+    todo.add_tasks("Write report".to_string()).unwrap();
+    let task = todo.task_ref(1).unwrap();
+
+    let cancel = AtomicBool::new(false);
+    let mut clock = FakeClock::default();
+    let session = countdown(&mut clock, secs(25.0 * 60.0), &cancel, |left| {
+        if left <= secs(15.0 * 60.0) {
+            cancel.store(true, Ordering::Relaxed);
+        }
+    });
+    assert!(!session.finished);
+    assert_eq!(session.spent, secs(10.0 * 60.0 + 1.0));
+
+    todo.log_time(task, session.spent).unwrap();
+    let finished = countdown(
+        &mut FakeClock::default(),
+        secs(60.0),
+        &AtomicBool::new(false),
+        |_| {},
+    );
+    todo.log_time(task, finished.spent).unwrap();
+    assert_eq!(todo[0].accumulated, secs(11.0 * 60.0 + 1.0));
+    assert!(todo.log()[todo.log().len() - 2].details.contains("10m"));
+}
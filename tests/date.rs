@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use rust_todo_cli::date::{Date, humanize_days, parse_duration, whole_days};
+use rust_todo_cli::todo::TodoError;
+
+fn date(year: i32, month: u32, day: u32) -> Date {
+    Date::new(year, month, day).unwrap()
+}
+
+#[test]
+fn ages_step_from_days_to_weeks_months_and_years() {
+    let steps = [
+        (0, "0d"),
+        (1, "1d"),
+        (6, "6d"),
+        (7, "1w"),
+        (13, "1w"),
+        (14, "2w"),
+        (29, "4w"),
+        (30, "1mo"),
+        (59, "1mo"),
+        (60, "2mo"),
+        (364, "12mo"),
+        (365, "1y"),
+        (729, "1y"),
+        (730, "2y"),
+    ];
+    for (days, age) in steps {
+        assert_eq!(humanize_days(days), age, "{} days", days);
+    }
+}
+
+// A clock set back, or a task created after it was finished, is no age at all
+#[test]
+fn negative_ages_read_as_none() {
+    assert_eq!(humanize_days(-1), "0d");
+    assert_eq!(humanize_days(i64::MIN), "0d");
+}
+
+#[test]
+fn ages_count_across_the_turn_of_the_year() {
+    let age = |from: Date, to: Date| humanize_days(from.days_until(to));
+    assert_eq!(age(date(2024, 12, 31), date(2025, 1, 1)), "1d");
+    assert_eq!(age(date(2024, 12, 25), date(2025, 1, 1)), "1w");
+    // Years are 365 days, so across a leap day one shows a day early
+    assert_eq!(age(date(2024, 1, 1), date(2024, 12, 30)), "12mo");
+    assert_eq!(age(date(2024, 1, 1), date(2024, 12, 31)), "1y");
+    assert_eq!(age(date(2024, 1, 1), date(2025, 1, 1)), "1y");
+    assert_eq!(age(date(2025, 1, 1), date(2024, 12, 31)), "0d");
+}
+
+// A Tuesday
+fn today() -> Date {
+    date(2025, 7, 1)
+}
+
+fn natural(text: &str) -> Date {
+    Date::parse_natural(text, today()).unwrap()
+}
+
+#[test]
+fn relative_days_count_from_today() {
+    assert_eq!(natural("today"), today());
+    assert_eq!(natural(" Today "), today());
+    assert_eq!(natural("tomorrow"), date(2025, 7, 2));
+    assert_eq!(natural("next week"), date(2025, 7, 8));
+    assert_eq!(natural("\"next week\""), date(2025, 7, 8));
+    assert_eq!(natural("in 3 days"), date(2025, 7, 4));
+    assert_eq!(natural("in 2w"), date(2025, 7, 15));
+    assert_eq!(natural("in 1 week"), date(2025, 7, 8));
+    // Part days round up
+    assert_eq!(natural("in 12h"), date(2025, 7, 2));
+    assert_eq!(natural("2025-02-28"), date(2025, 2, 28));
+}
+
+#[test]
+fn weekdays_are_the_next_one_and_never_today() {
+    let days = [
+        ("monday", 7),
+        ("tuesday", 8),
+        ("Wednesday", 2),
+        ("thu", 3),
+        ("fri", 4),
+        ("saturday", 5),
+        ("Sun", 6),
+    ];
+    for (name, day) in days {
+        assert_eq!(natural(name), date(2025, 7, day), "{}", name);
+    }
+    assert_eq!(natural("next tuesday"), date(2025, 7, 8));
+    assert_eq!(natural("next wed"), date(2025, 7, 2));
+}
+
+#[test]
+fn relative_dates_cross_months_and_years() {
+    let new_year = |text| Date::parse_natural(text, date(2025, 12, 30)).unwrap();
+    assert_eq!(new_year("in 3 days"), date(2026, 1, 2));
+    assert_eq!(new_year("friday"), date(2026, 1, 2));
+    assert_eq!(new_year("next week"), date(2026, 1, 6));
+    let leap = |text| Date::parse_natural(text, date(2024, 2, 28)).unwrap();
+    assert_eq!(leap("tomorrow"), date(2024, 2, 29));
+    assert_eq!(leap("in 2 days"), date(2024, 3, 1));
+}
+
+#[test]
+fn unrecognized_dates_say_what_was_typed() {
+    let invalid = [
+        "",
+        "yesterday",
+        "in",
+        "in three days",
+        "tu",
+        "next",
+        "next month",
+        "friday please",
+        "2025-02-30",
+        "2025-07",
+    ];
+    for text in invalid {
+        assert!(
+            matches!(
+                Date::parse_natural(text, today()),
+                Err(TodoError::InvalidDate(typed)) if typed == text
+            ),
+            "{:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn durations_add_up_their_parts() {
+    let minutes = |count: u64| Duration::from_secs(count * 60);
+    let durations = [
+        ("90m", minutes(90)),
+        ("12h", minutes(12 * 60)),
+        ("2d", minutes(2 * 24 * 60)),
+        ("1w", minutes(7 * 24 * 60)),
+        ("1h30m", minutes(90)),
+        ("1h 30m", minutes(90)),
+        ("2 days", minutes(2 * 24 * 60)),
+        ("1 Week 1 day", minutes(8 * 24 * 60)),
+        (" 3 HRS ", minutes(180)),
+    ];
+    for (text, duration) in durations {
+        assert_eq!(parse_duration(text).unwrap(), duration, "{}", text);
+    }
+}
+
+#[test]
+fn durations_need_a_count_and_a_unit() {
+    let invalid = [
+        "",
+        "d",
+        "2",
+        "2x",
+        "2 fortnights",
+        "-1d",
+        "1.5h",
+        "0d",
+        "0h0m",
+        "h2",
+        "99999999999999999w",
+    ];
+    for text in invalid {
+        assert!(
+            matches!(
+                parse_duration(text),
+                Err(TodoError::InvalidDuration(typed)) if typed == text
+            ),
+            "{:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn whole_days_round_part_days_up() {
+    let hours = |count: u64| Duration::from_secs(count * 3_600);
+    assert_eq!(whole_days(hours(1)), 1);
+    assert_eq!(whole_days(hours(24)), 1);
+    assert_eq!(whole_days(hours(25)), 2);
+    assert_eq!(whole_days(Duration::ZERO), 0);
+}
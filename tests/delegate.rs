@@ -0,0 +1,256 @@
+mod common;
+
+use std::fs;
+use std::time::Duration;
+
+use common::{list_of, scratch_dir};
+use rust_todo_cli::command::{Command, OutputFormat, ParseError, parse_command};
+use rust_todo_cli::date::{SECONDS_PER_DAY, Timestamp};
+use rust_todo_cli::merge::merge_task;
+use rust_todo_cli::todo::{
+    ListFilter, Selector, Status, Storable, Task, TaskIndex, TodoError, TodoList, normalize_person,
+};
+
+const DAY: Timestamp = SECONDS_PER_DAY as Timestamp;
+
+// A task handed to `person` at `at`, or by hand without a time
+fn handed(description: &str, person: &str, at: Option<Timestamp>) -> Task {
+    let mut task = Task::new(description.to_string()).unwrap();
+    task.status = Status::Waiting;
+    task.waiting_on = Some(person.to_string());
+    task.delegated_at = at;
+    task
+}
+
+fn numbers(todo: &TodoList, filter: &ListFilter) -> Vec<usize> {
+    todo.filter(filter).iter().map(|(i, _)| *i).collect()
+}
+
+#[test]
+fn delegating_parks_the_task_and_notes_when() {
+    let mut todo = list_of(&["Review budget", "Book venue"]);
+    let before = rust_todo_cli::date::now();
+    todo.delegate(todo.task_ref(1).unwrap(), Some("@Alice"))
+        .unwrap();
+    assert_eq!(todo[0].status, Status::Waiting);
+    assert_eq!(todo[0].waiting_on.as_deref(), Some("Alice"));
+    assert!(todo[0].delegated_at.unwrap() >= before);
+    assert_eq!(todo.log().last().unwrap().action, "delegate");
+    assert!(todo.log().last().unwrap().details.contains("Alice"));
+    assert_eq!(todo[1].waiting_on, None);
+}
+
+#[test]
+fn only_a_new_person_restarts_the_wait() {
+    let mut todo: TodoList = [handed("Budget", "Alice", Some(DAY))].into_iter().collect();
+    todo.delegate(todo.task_ref(1).unwrap(), Some("alice"))
+        .unwrap();
+    assert_eq!(todo[0].delegated_at, Some(DAY));
+    assert_eq!(todo[0].waiting_on.as_deref(), Some("alice"));
+
+    todo.delegate(todo.task_ref(1).unwrap(), Some("bob"))
+        .unwrap();
+    assert!(todo[0].delegated_at.unwrap() > DAY);
+    assert_eq!(todo[0].waiting_on.as_deref(), Some("bob"));
+}
+
+#[test]
+fn clearing_forgets_the_person_but_not_the_status() {
+    let mut todo = list_of(&["Review budget"]);
+    let task = todo.task_ref(1).unwrap();
+    todo.wait(task, Some("the numbers".to_string())).unwrap();
+    todo.delegate(task, Some("alice")).unwrap();
+    assert_eq!(todo[0].wait_reason.as_deref(), Some("the numbers"));
+
+    todo.delegate(task, None).unwrap();
+    assert_eq!(todo[0].waiting_on, None);
+    assert_eq!(todo[0].delegated_at, None);
+    assert_eq!(todo[0].status, Status::Waiting);
+    assert_eq!(todo[0].wait_reason.as_deref(), Some("the numbers"));
+}
+
+#[test]
+fn completing_a_task_clears_who_it_waited_on() {
+    let mut todo = list_of(&["Review budget", "Book venue"]);
+    for number in [1, 2] {
+        todo.delegate(todo.task_ref(number).unwrap(), Some("alice"))
+            .unwrap();
+    }
+    // Moving on otherwise still waits on them
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::InProgress, false)
+        .unwrap();
+    assert_eq!(todo[1].waiting_on.as_deref(), Some("alice"));
+
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    assert_eq!(todo[0].waiting_on, None);
+    assert_eq!(todo[0].delegated_at, None);
+
+    let error = todo
+        .delegate(todo.task_ref(1).unwrap(), Some("bob"))
+        .unwrap_err();
+    assert!(matches!(error, TodoError::AlreadyCompleted(1)));
+}
+
+#[test]
+fn names_are_words_with_an_optional_at() {
+    assert_eq!(normalize_person("@alice").as_deref(), Some("alice"));
+    assert_eq!(normalize_person(" Mary-Jo ").as_deref(), Some("Mary-Jo"));
+    assert_eq!(normalize_person("o'neil").as_deref(), Some("o'neil"));
+    for bad in ["", "@", "-x", "two words", "a,b"] {
+        assert_eq!(normalize_person(bad), None, "{:?}", bad);
+    }
+
+    let mut todo = list_of(&["Review budget"]);
+    let error = todo
+        .delegate(todo.task_ref(1).unwrap(), Some("a,b"))
+        .unwrap_err();
+    assert!(matches!(error, TodoError::InvalidPerson(_)));
+    assert_eq!(error.code(), "InvalidPerson");
+    assert_eq!(todo[0].status, Status::Todo);
+}
+
+#[test]
+fn waiting_groups_by_person_ignoring_case() {
+    let now = 100 * DAY;
+    let mut done = handed("Sent", "alice", Some(now - 9 * DAY));
+    done.status = Status::Cancelled;
+    let todo: TodoList = [
+        handed("Budget", "alice", Some(now - 2 * DAY)),
+        Task::new("Mine".to_string()).unwrap(),
+        handed("Venue", "Bob", Some(now - DAY)),
+        handed("Slides", "Alice", Some(now - 5 * DAY)),
+        done,
+        handed("Catering", "alice", None),
+    ]
+    .into_iter()
+    .collect();
+
+    let groups = todo.delegated(now);
+    let people: Vec<&str> = groups.iter().map(|group| group.person.as_str()).collect();
+    // Named as first seen, in alphabetical order
+    assert_eq!(people, ["alice", "Bob"]);
+
+    // Longest wait first, those without a time last; closed tasks are left out
+    let alice: Vec<(usize, Option<Duration>)> = groups[0]
+        .tasks
+        .iter()
+        .map(|&(index, _, waited)| (index, waited))
+        .collect();
+    assert_eq!(
+        alice,
+        [
+            (4, Some(Duration::from_secs(5 * DAY))),
+            (1, Some(Duration::from_secs(2 * DAY))),
+            (6, None),
+        ]
+    );
+    assert_eq!(groups[1].tasks.len(), 1);
+    assert_eq!(groups[1].tasks[0].0, 3);
+    assert_eq!(groups[1].tasks[0].2, Some(Duration::from_secs(DAY)));
+}
+
+#[test]
+fn waits_never_run_backwards() {
+    // A clock set back since the task was handed over reads as no wait
+    let now = 100 * DAY;
+    let todo: TodoList = [handed("Budget", "alice", Some(now + 60))]
+        .into_iter()
+        .collect();
+    assert_eq!(todo.delegated(now)[0].tasks[0].2, Some(Duration::ZERO));
+    assert!(TodoList::new().delegated(now).is_empty());
+}
+
+#[test]
+fn the_filter_matches_the_person_in_any_case() {
+    let todo: TodoList = [
+        handed("Budget", "alice", Some(DAY)),
+        Task::new("Mine".to_string()).unwrap(),
+        handed("Venue", "Bob", Some(DAY)),
+    ]
+    .into_iter()
+    .collect();
+
+    let Command::List { filter, .. } = parse_command("list waiting-on:@ALICE").unwrap() else {
+        panic!("a person filter is a listing");
+    };
+    assert_eq!(filter.waiting_on.as_deref(), Some("ALICE"));
+    assert!(!filter.is_empty());
+    // Waiting tasks would be hidden otherwise
+    assert_eq!(numbers(&todo, &filter), [1]);
+    assert_eq!(filter.to_string(), "waiting-on:ALICE");
+    assert!(parse_command("list waiting-on:").is_err());
+}
+
+#[test]
+fn old_files_load_with_no_one_waited_on() {
+    let dir = scratch_dir("delegate-old");
+    let path = dir.join("tasks.json");
+    let path = path.to_str().unwrap();
+    fs::write(
+        path,
+        r#"[{"id": 1, "description": "Budget", "status": "Waiting"}]"#,
+    )
+    .unwrap();
+    let mut todo = TodoList::load(path).unwrap();
+    assert_eq!(todo[0].waiting_on, None);
+    assert_eq!(todo[0].delegated_at, None);
+
+    // Unset fields stay out of the file, set ones come back
+    todo.save(path).unwrap();
+    assert!(!fs::read_to_string(path).unwrap().contains("waiting_on"));
+    todo.delegate(todo.task_ref(1).unwrap(), Some("alice"))
+        .unwrap();
+    todo.save(path).unwrap();
+    let loaded = TodoList::load(path).unwrap();
+    assert_eq!(loaded[0].waiting_on.as_deref(), Some("alice"));
+    assert_eq!(loaded[0].delegated_at, todo[0].delegated_at);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn the_commands_parse() {
+    let task = Selector::Index(TaskIndex { task: 4, sub: None });
+    assert_eq!(
+        parse_command("delegate 4 @alice").unwrap(),
+        Command::Delegate(task.clone(), Some("alice".to_string()))
+    );
+    assert_eq!(
+        parse_command("delegate 4 --clear").unwrap(),
+        Command::Delegate(task, None)
+    );
+    assert!(parse_command("delegate 4").is_err());
+    assert!(parse_command("delegate 4 alice bob").is_err());
+    assert!(matches!(
+        parse_command("delegate 4 a,b"),
+        Err(ParseError::Invalid(TodoError::InvalidPerson(_)))
+    ));
+    assert!(parse_command("delegate 4 --clear").unwrap().mutates());
+
+    assert_eq!(
+        parse_command("waiting --json").unwrap(),
+        Command::Waiting(OutputFormat::Json)
+    );
+    assert!(parse_command("waiting alice").is_err());
+    assert!(!parse_command("waiting").unwrap().mutates());
+}
+
+#[test]
+fn merging_keeps_the_later_delegation() {
+    let ours = list_of(&["Budget"]);
+    let mut theirs = ours.clone();
+    theirs
+        .delegate(theirs.task_ref(1).unwrap(), Some("alice"))
+        .unwrap();
+    let merged = merge_task(&ours[0], &theirs[0]);
+    assert_eq!(merged.waiting_on.as_deref(), Some("alice"));
+    assert_eq!(merged.delegated_at, theirs[0].delegated_at);
+
+    let mut done = ours.clone();
+    done.update_task_status(done.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    let merged = merge_task(&done[0], &theirs[0]);
+    assert_eq!(merged.status, Status::Completed);
+    assert_eq!(merged.waiting_on, None);
+    assert_eq!(merged.delegated_at, None);
+}
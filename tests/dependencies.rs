@@ -0,0 +1,113 @@
+mod common;
+
+use common::list_of;
+use rust_todo_cli::todo::{Status, TodoError, TodoList};
+
+fn list() -> TodoList {
+    list_of(&["Write", "Review", "Test", "Ship", "Announce"])
+}
+
+// Make task `index` wait on task `on`
+fn wait(todo: &mut TodoList, index: usize, on: usize) -> Result<(), TodoError> {
+    let (task, on) = (todo.task_ref(index)?, todo.task_ref(on)?);
+    todo.add_dependency(task, on)
+}
+
+fn blocked(todo: &TodoList) -> Vec<usize> {
+    todo.list_tasks()
+        .filter(|(_, task)| todo.is_blocked(task))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+#[test]
+fn a_task_cannot_wait_on_itself() {
+    let mut todo = list();
+    assert!(matches!(
+        wait(&mut todo, 2, 2),
+        Err(TodoError::DependencyCycle(2, 2))
+    ));
+    assert!(todo[1].depends_on.is_empty());
+}
+
+#[test]
+fn two_tasks_cannot_wait_on_each_other() {
+    let mut todo = list();
+    wait(&mut todo, 2, 1).unwrap();
+    assert!(matches!(
+        wait(&mut todo, 1, 2),
+        Err(TodoError::DependencyCycle(1, 2))
+    ));
+    assert!(todo[0].depends_on.is_empty());
+}
+
+#[test]
+fn longer_cycles_are_refused_wherever_they_would_close() {
+    let mut todo = list();
+    // Ship waits on Test, which waits on Review, which waits on Write
+    wait(&mut todo, 4, 3).unwrap();
+    wait(&mut todo, 3, 2).unwrap();
+    wait(&mut todo, 2, 1).unwrap();
+    for (index, on) in [(1, 4), (1, 3), (2, 4)] {
+        assert!(
+            matches!(
+                wait(&mut todo, index, on),
+                Err(TodoError::DependencyCycle(i, o)) if (i, o) == (index, on)
+            ),
+            "{} on {}",
+            index,
+            on
+        );
+    }
+
+    // Waiting on something further down the chain, or on two tasks that
+    // share a dependency, closes no loop
+    wait(&mut todo, 4, 1).unwrap();
+    wait(&mut todo, 5, 4).unwrap();
+    wait(&mut todo, 5, 2).unwrap();
+    // Nor does asking again, which adds nothing
+    wait(&mut todo, 5, 2).unwrap();
+    assert_eq!(todo[4].depends_on, [todo[3].id, todo[1].id]);
+}
+
+#[test]
+fn a_task_is_blocked_until_what_it_waits_on_is_closed() {
+    let mut todo = list();
+    wait(&mut todo, 4, 3).unwrap();
+    wait(&mut todo, 3, 2).unwrap();
+    wait(&mut todo, 5, 2).unwrap();
+    wait(&mut todo, 5, 4).unwrap();
+    assert_eq!(blocked(&todo), [3, 4, 5]);
+    assert_eq!(todo.blockers(&todo[4]), [2, 4]);
+
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+    assert_eq!(blocked(&todo), [4, 5]);
+    assert_eq!(todo.blockers(&todo[4]), [4]);
+
+    // Blocking goes by the tasks waited on directly: with Test cancelled,
+    // Ship is free even though Test's own wait was never finished
+    todo.update_task_status(todo.task_ref(3).unwrap(), Status::Cancelled, false)
+        .unwrap();
+    assert_eq!(blocked(&todo), [5]);
+
+    // Reopening a task blocks what waits on it again
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Todo, false)
+        .unwrap();
+    assert_eq!(blocked(&todo), [3, 5]);
+}
+
+#[test]
+fn removing_a_task_frees_what_waited_on_it() {
+    let mut todo = list();
+    wait(&mut todo, 2, 1).unwrap();
+    wait(&mut todo, 3, 1).unwrap();
+    assert_eq!(blocked(&todo), [2, 3]);
+
+    todo.remove_task(todo.task_ref(1).unwrap()).unwrap();
+    assert!(blocked(&todo).is_empty());
+    assert!(
+        todo.list_tasks()
+            .all(|(_, task)| task.depends_on.is_empty())
+    );
+}
@@ -0,0 +1,162 @@
+use rust_todo_cli::doctor::{self, Finding, Problem, RULES, UNTITLED};
+use rust_todo_cli::todo::{Storable, Task, TaskIndex, TodoList};
+use serde_json::{Value, json};
+
+// Tasks as a hand-edited or old file could hold them
+fn tasks(value: Value) -> Vec<Task> {
+    serde_json::from_value(value).unwrap()
+}
+
+fn at(task: usize, sub: Option<usize>, problem: Problem) -> Finding {
+    Finding {
+        at: TaskIndex { task, sub },
+        problem,
+    }
+}
+
+#[test]
+fn a_sound_list_has_no_problems() {
+    let list = tasks(json!([
+        { "id": 1, "description": "Plan trip", "created_at": 100, "completed_at": 200,
+          "status": "Completed", "subtasks": [{ "description": "Book hotel" }] },
+        { "id": 2, "description": "Pack", "depends_on": [1] },
+        // Tasks not yet in a list all have ID 0
+        { "description": "Unsaved" },
+        { "description": "Unsaved too" }
+    ]));
+    assert!(doctor::check(&list).is_empty());
+    for rule in RULES {
+        assert!((rule.check)(&list).is_empty(), "{}", rule.name);
+    }
+}
+
+#[test]
+fn later_tasks_with_a_taken_id_get_new_ones() {
+    let mut list = tasks(json!([
+        { "id": 3, "description": "Buy milk" },
+        { "id": 5, "description": "Call mum" },
+        { "id": 3, "description": "Book dentist" },
+        { "id": 3, "description": "Fix sink", "depends_on": [5] }
+    ]));
+    let found = doctor::duplicate_ids(&list);
+    assert_eq!(
+        found,
+        [
+            at(3, None, Problem::DuplicateId { id: 3, first: 1 }),
+            at(4, None, Problem::DuplicateId { id: 3, first: 1 }),
+        ]
+    );
+
+    assert_eq!(doctor::fix(&mut list), found);
+    let ids: Vec<u64> = list.iter().map(|task| task.id).collect();
+    assert_eq!(ids, [3, 5, 6, 7]);
+    assert_eq!(list[3].depends_on, [5]);
+    assert!(doctor::check(&list).is_empty());
+}
+
+#[test]
+fn blank_descriptions_are_flagged_subtasks_included() {
+    let mut list = tasks(json!([
+        { "id": 1, "description": "" },
+        { "id": 2, "description": "Plan trip",
+          "subtasks": [{ "description": "Book hotel" }, { "description": " \t" }] }
+    ]));
+    let found = doctor::empty_descriptions(&list);
+    assert_eq!(
+        found,
+        [
+            at(1, None, Problem::EmptyDescription),
+            at(2, Some(2), Problem::EmptyDescription),
+        ]
+    );
+
+    assert_eq!(doctor::fix(&mut list), found);
+    assert_eq!(list[0].description, UNTITLED);
+    assert_eq!(list[1].subtasks[1].description, UNTITLED);
+    assert_eq!(list[1].subtasks[0].description, "Book hotel");
+}
+
+#[test]
+fn completion_before_creation_is_only_reported() {
+    let mut list = tasks(json!([
+        { "id": 1, "description": "Time travel", "status": "Completed",
+          "created_at": 200, "completed_at": 100 },
+        // Missing times are from older versions, not a problem
+        { "id": 2, "description": "Old task", "status": "Completed", "completed_at": 100 },
+        { "id": 3, "description": "Plan trip",
+          "subtasks": [{ "description": "Book", "created_at": 50, "completed_at": 40 }] }
+    ]));
+    let found = doctor::completed_before_created(&list);
+    assert_eq!(
+        found,
+        [
+            at(1, None, Problem::CompletedBeforeCreated),
+            at(3, Some(1), Problem::CompletedBeforeCreated),
+        ]
+    );
+    assert!(found.iter().all(|finding| !finding.problem.fixable()));
+
+    assert!(doctor::fix(&mut list).is_empty());
+    assert_eq!(doctor::check(&list), found);
+}
+
+#[test]
+fn dependencies_on_missing_tasks_are_dropped() {
+    let mut list = tasks(json!([
+        { "id": 1, "description": "Buy paint" },
+        { "id": 2, "description": "Paint fence", "depends_on": [1, 9, 4] }
+    ]));
+    let found = doctor::dangling_dependencies(&list);
+    assert_eq!(
+        found,
+        [
+            at(2, None, Problem::DanglingDependency(9)),
+            at(2, None, Problem::DanglingDependency(4)),
+        ]
+    );
+
+    assert_eq!(doctor::fix(&mut list), found);
+    assert_eq!(list[1].depends_on, [1]);
+}
+
+#[test]
+fn every_rule_fixes_exactly_the_fixable_problems_it_finds() {
+    let broken = json!([
+        { "id": 1, "description": "", "depends_on": [8] },
+        { "id": 1, "description": "Twin", "created_at": 9, "completed_at": 1 }
+    ]);
+    for rule in RULES {
+        let mut list = tasks(broken.clone());
+        let found = (rule.check)(&list);
+        assert!(!found.is_empty(), "{}", rule.name);
+        match rule.fix {
+            Some(fix) => {
+                assert!(found.iter().all(|f| f.problem.fixable()), "{}", rule.name);
+                assert_eq!(fix(&mut list), found, "{}", rule.name);
+                assert!((rule.check)(&list).is_empty(), "{}", rule.name);
+            }
+            None => assert!(found.iter().all(|f| !f.problem.fixable()), "{}", rule.name),
+        }
+    }
+}
+
+#[test]
+fn a_broken_file_loads_and_repairs_what_it_safely_can() {
+    let path = format!("{}/tests/fixtures/broken.json", env!("CARGO_MANIFEST_DIR"));
+    let mut todo = TodoList::load(&path).unwrap();
+    let problems = todo.problems();
+    assert_eq!(problems.len(), 4, "{:?}", problems);
+
+    let fixed = todo.repair();
+    assert_eq!(fixed.len(), 3);
+    assert_eq!(todo[1].id, 3);
+    assert!(todo[1].depends_on.is_empty());
+    assert_eq!(todo[2].description, UNTITLED);
+    assert_eq!(
+        todo.problems(),
+        [at(3, None, Problem::CompletedBeforeCreated)]
+    );
+    assert_eq!(todo.log().last().unwrap().action, "doctor");
+    // Nothing left to repair is a no-op
+    assert!(todo.repair().is_empty());
+}
@@ -0,0 +1,287 @@
+mod common;
+
+use std::time::Duration;
+
+use common::list_of;
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::date::{Date, Time, due_later, format_due, parse_due};
+use rust_todo_cli::listing::{due_date, relative_due_at};
+use rust_todo_cli::merge::merge_task;
+use rust_todo_cli::outcome::execute;
+use rust_todo_cli::todo::{ListFilter, Recurrence, Selector, Status, Task, TaskIndex};
+
+fn date(month: u32, day: u32) -> Date {
+    Date::new(2025, month, day).unwrap()
+}
+
+fn time(hour: u32, minute: u32) -> Time {
+    Time::new(hour, minute).unwrap()
+}
+
+fn hours(count: u64) -> Duration {
+    Duration::from_secs(count * 3_600)
+}
+
+// A Tuesday, standing in for the local date in every test
+fn today() -> Date {
+    date(7, 1)
+}
+
+#[test]
+fn times_parse_in_24_and_12_hour_forms() {
+    assert_eq!(Time::parse("14:30").unwrap(), time(14, 30));
+    assert_eq!(Time::parse("9:05").unwrap(), time(9, 5));
+    assert_eq!(Time::parse("9am").unwrap(), time(9, 0));
+    assert_eq!(Time::parse("9:15PM").unwrap(), time(21, 15));
+    assert_eq!(Time::parse("12am").unwrap(), time(0, 0));
+    assert_eq!(Time::parse("12pm").unwrap(), time(12, 0));
+    for text in [
+        "24:00", "9:60", "13pm", "0am", "9", "9:5", "nine", "1:2:3", "",
+    ] {
+        assert!(Time::parse(text).is_err(), "{:?}", text);
+    }
+    assert_eq!(time(9, 5).to_string(), "09:05");
+    assert_eq!(time(14, 30).minutes(), 870);
+}
+
+#[test]
+fn a_due_date_may_end_in_a_time() {
+    let due = |text| parse_due(text, today()).unwrap();
+    assert_eq!(due("2025-07-04 14:30"), (date(7, 4), Some(time(14, 30))));
+    assert_eq!(due("\"tomorrow 9am\""), (date(7, 2), Some(time(9, 0))));
+    assert_eq!(due("friday at 5 pm"), (date(7, 4), Some(time(17, 0))));
+    assert_eq!(due("in 3 days 08:00"), (date(7, 4), Some(time(8, 0))));
+    // A time alone is today, and a date alone has no time
+    assert_eq!(due("16:45"), (today(), Some(time(16, 45))));
+    assert_eq!(due("next week"), (date(7, 8), None));
+    assert_eq!(due("in 2h"), (date(7, 2), None));
+
+    for text in [
+        "tomorrow 25:00",
+        "someday 9am",
+        "tomorrow at",
+        "at 9am friday",
+    ] {
+        let error = parse_due(text, today()).unwrap_err();
+        assert!(error.to_string().contains(text), "{}", error);
+    }
+}
+
+#[test]
+fn same_day_times_read_as_hours_or_minutes_away() {
+    let now = time(10, 0);
+    let on = |day, at| relative_due_at(date(7, day), at, today(), now);
+    assert_eq!(on(1, Some(time(12, 30))), "due in 2h");
+    assert_eq!(on(1, Some(time(11, 0))), "due in 1h");
+    assert_eq!(on(1, Some(time(10, 45))), "due in 45m");
+    // Once the time has passed it is still only today
+    assert_eq!(on(1, Some(time(10, 0))), "due today 10:00");
+    assert_eq!(on(1, Some(time(9, 0))), "due today 09:00");
+    assert_eq!(on(1, None), "due today");
+}
+
+#[test]
+fn other_days_carry_their_time_except_when_overdue() {
+    let now = time(23, 0);
+    let on = |day, at| relative_due_at(date(7, day), at, today(), now);
+    assert_eq!(on(2, Some(time(9, 0))), "due tomorrow 09:00");
+    assert_eq!(on(5, Some(time(9, 0))), "due in 4d 09:00");
+    assert_eq!(on(5, None), "due in 4d");
+    assert_eq!(
+        relative_due_at(date(12, 1), Some(time(9, 0)), today(), now),
+        "2025-12-01 09:00"
+    );
+    assert_eq!(
+        relative_due_at(date(6, 29), Some(time(9, 0)), today(), now),
+        "2d overdue"
+    );
+}
+
+#[test]
+fn timed_tasks_come_first_on_their_day_earliest_first() {
+    let mut todo = list_of(&["No date", "All day", "Afternoon", "Tomorrow", "Morning"]);
+    let due = [
+        (2, date(7, 1), None),
+        (3, date(7, 1), Some(time(15, 0))),
+        (4, date(7, 2), Some(time(8, 0))),
+        (5, date(7, 1), Some(time(9, 0))),
+    ];
+    for (n, day, at) in due {
+        todo.set_due_at(todo.task_ref(n).unwrap(), Some(day), at)
+            .unwrap();
+    }
+
+    let filter = ListFilter {
+        by_due: true,
+        ..ListFilter::default()
+    };
+    let order: Vec<usize> = todo.filter(&filter).iter().map(|(i, _)| *i).collect();
+    assert_eq!(order, [5, 3, 2, 4, 1]);
+
+    let order: Vec<usize> = todo
+        .due_on_or_before(today())
+        .iter()
+        .map(|(i, _)| *i)
+        .collect();
+    assert_eq!(order, [5, 3, 2]);
+
+    let agenda = todo.agenda(today(), 2);
+    let days: Vec<Vec<usize>> = agenda
+        .days
+        .iter()
+        .map(|day| day.tasks.iter().map(|(i, _)| *i).collect())
+        .collect();
+    assert_eq!(days, [vec![5, 3, 2], vec![4]]);
+    assert_eq!(todo.suggest_next(2)[0].0, 5);
+}
+
+#[test]
+fn setting_a_date_alone_drops_the_time() {
+    let mut todo = list_of(&["Call mum"]);
+    let call = todo.task_ref(1).unwrap();
+    todo.set_due_at(call, Some(today()), Some(time(18, 0)))
+        .unwrap();
+    assert_eq!(due_date(&todo[0]).unwrap(), "2025-07-01 18:00");
+    assert_eq!(
+        todo.log().last().unwrap().details,
+        "set #1 due 2025-07-01 18:00"
+    );
+
+    todo.set_due(call, Some(date(7, 2))).unwrap();
+    assert_eq!((todo[0].due, todo[0].due_time), (Some(date(7, 2)), None));
+    todo.set_due_at(call, None, Some(time(18, 0))).unwrap();
+    assert_eq!((todo[0].due, todo[0].due_time), (None, None));
+    assert_eq!(due_date(&todo[0]), None);
+}
+
+#[test]
+fn due_parses_a_time_after_the_date() {
+    let command = parse_command("due 3 2025-07-01 14:30").unwrap();
+    assert_eq!(
+        command,
+        Command::Due(
+            Selector::Index(TaskIndex { task: 3, sub: None }),
+            Some((date(7, 1), Some(time(14, 30))))
+        )
+    );
+    assert!(matches!(
+        parse_command("due 3 none").unwrap(),
+        Command::Due(_, None)
+    ));
+
+    let mut todo = list_of(&["Pay rent"]);
+    execute(&mut todo, parse_command("due 1 2025-07-01 9pm").unwrap()).unwrap();
+    assert_eq!(todo[0].due_time, Some(time(21, 0)));
+    execute(&mut todo, parse_command("due 1 none").unwrap()).unwrap();
+    assert_eq!(todo[0].due_time, None);
+}
+
+#[test]
+fn files_without_times_load_and_untimed_tasks_save_as_before() {
+    let json = r#"[
+        {"description": "Dated", "status": "Todo", "due": "2025-07-01"},
+        {"description": "Timed", "status": "Todo", "due": "2025-07-01", "due_time": "08:15"},
+        {"description": "Undated", "status": "Todo"}
+    ]"#;
+    let tasks: Vec<Task> = serde_json::from_str(json).unwrap();
+    let times: Vec<Option<Time>> = tasks.iter().map(|task| task.due_time).collect();
+    assert_eq!(times, [None, Some(time(8, 15)), None]);
+
+    let saved = serde_json::to_value(&tasks).unwrap();
+    assert!(saved[0].get("due_time").is_none());
+    assert_eq!(saved[1]["due_time"], "08:15");
+
+    let bad = r#"{"description": "Bad", "due": "2025-07-01", "due_time": "8.15"}"#;
+    assert!(serde_json::from_str::<Task>(bad).is_err());
+}
+
+#[test]
+fn snoozing_moves_the_due_time_across_midnight() {
+    assert_eq!(
+        due_later(today(), Some(time(14, 30)), hours(2)),
+        (today(), Some(time(16, 30)))
+    );
+    assert_eq!(
+        due_later(today(), Some(time(23, 30)), hours(2)),
+        (date(7, 2), Some(time(1, 30)))
+    );
+    assert_eq!(
+        due_later(today(), Some(time(14, 30)), hours(48)),
+        (date(7, 3), Some(time(14, 30)))
+    );
+    // Part minutes round up
+    assert_eq!(
+        due_later(today(), Some(time(14, 30)), Duration::from_secs(61)),
+        (today(), Some(time(14, 32)))
+    );
+    // A date without a time moves by whole days
+    assert_eq!(due_later(today(), None, hours(2)), (date(7, 2), None));
+    assert_eq!(due_later(today(), None, hours(48)), (date(7, 3), None));
+
+    assert_eq!(format_due(today(), Some(time(9, 5))), "2025-07-01 09:05");
+    assert_eq!(format_due(today(), None), "2025-07-01");
+}
+
+#[test]
+fn snoozing_a_task_keeps_its_due_time_in_step() {
+    let mut todo = list_of(&["Call back", "Renew passport", "Water plants"]);
+    let call = todo.task_ref(1).unwrap();
+    todo.set_due_at(call, Some(today()), Some(time(23, 30)))
+        .unwrap();
+    assert_eq!(
+        todo.snooze(call, hours(2), today(), time(12, 0)).unwrap(),
+        (
+            Some((today(), Some(time(23, 30)))),
+            (date(7, 2), Some(time(1, 30)))
+        )
+    );
+    assert_eq!(
+        (todo[0].due, todo[0].due_time),
+        (Some(date(7, 2)), Some(time(1, 30)))
+    );
+
+    // With no due date, hours count from now and days from today
+    let passport = todo.task_ref(2).unwrap();
+    assert_eq!(
+        todo.snooze(passport, hours(2), today(), time(22, 45))
+            .unwrap(),
+        (None, (date(7, 2), Some(time(0, 45))))
+    );
+    let plants = todo.task_ref(3).unwrap();
+    assert_eq!(
+        todo.snooze(plants, hours(48), today(), time(22, 45))
+            .unwrap(),
+        (None, (date(7, 3), None))
+    );
+}
+
+#[test]
+fn the_time_follows_a_task_through_repeats_snoozes_and_merges() {
+    let mut todo = list_of(&["Standup"]);
+    let standup = todo.task_ref(1).unwrap();
+    todo.set_due_at(standup, Some(today()), Some(time(9, 30)))
+        .unwrap();
+    todo.set_recurrence(standup, Some(Recurrence::parse("daily").unwrap()))
+        .unwrap();
+    todo.snooze(standup, hours(24), today(), time(12, 0))
+        .unwrap();
+    assert_eq!(todo[0].due_order(), Some((date(7, 2), 570)));
+
+    let next = todo
+        .update_task_status(standup, Status::Completed, false)
+        .unwrap()
+        .unwrap();
+    assert_eq!(todo[next - 1].due_time, Some(time(9, 30)));
+
+    // The later due date wins, and brings its own time along
+    let mut ours = todo[0].clone();
+    let mut theirs = ours.clone();
+    ours.due_time = None;
+    theirs.due = Some(date(7, 9));
+    theirs.due_time = Some(time(7, 0));
+    let merged = merge_task(&ours, &theirs);
+    assert_eq!((merged.due, merged.due_time), (theirs.due, theirs.due_time));
+    theirs.due = ours.due;
+    let merged = merge_task(&ours, &theirs);
+    assert_eq!(merged.due_time, Some(time(7, 0)));
+}
@@ -0,0 +1,105 @@
+mod common;
+
+use common::list_of;
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::fuzzy::{self, LIMIT, MIN_SCORE, score};
+use rust_todo_cli::outcome::{CommandOutcome, query};
+use rust_todo_cli::todo::TodoList;
+
+// Numbers of the tasks `rank` finds, best first
+fn ranked(todo: &TodoList, query: &str) -> Vec<usize> {
+    fuzzy::rank(todo, query, LIMIT)
+        .iter()
+        .map(|found| found.index)
+        .collect()
+}
+
+const FIXTURE: &[&str] = &[
+    "Book dentist appointment",
+    "Call the dentist about the bill +health",
+    "Buy milk",
+    "Make lists for the move",
+    "Milestone review with the team",
+    "Buy oat milk for the week",
+    "Water the plants",
+];
+
+#[test]
+fn the_fixture_ranks_as_pinned() {
+    let todo = list_of(FIXTURE);
+    // Whole words first, shorter descriptions breaking ties
+    assert_eq!(ranked(&todo, "milk"), [3, 6, 5]);
+    assert_eq!(ranked(&todo, "dentist"), [1, 2]);
+    // A misspelling still finds the word, and nothing that merely shares letters
+    assert_eq!(ranked(&todo, "dentsit"), [1, 2]);
+    assert_eq!(ranked(&todo, "buy mlik"), [3, 6]);
+    assert_eq!(ranked(&todo, "plant"), [7]);
+}
+
+#[test]
+fn a_prefix_beats_a_scattered_subsequence() {
+    // "mil" starts "milk" and "milestone" but is only spread over "mail"
+    let prefix = score("mil", "Buy milk").unwrap();
+    let scattered = score("mil", "Mail list").unwrap();
+    assert!(prefix > scattered, "{} <= {}", prefix, scattered);
+    let todo = list_of(&["Mail list", "Milestone review"]);
+    assert_eq!(ranked(&todo, "mil"), [2, 1]);
+}
+
+#[test]
+fn shorter_descriptions_win_ties() {
+    let todo = list_of(&["Pay the rent today", "Pay rent", "Pay the rent"]);
+    assert_eq!(ranked(&todo, "rent"), [2, 3, 1]);
+}
+
+#[test]
+fn case_and_punctuation_are_ignored() {
+    assert_eq!(score("MILK!", "buy milk"), Some(100));
+    assert_eq!(score("re-view", "Review the re view"), Some(100));
+    assert_eq!(score("health", "Call +health"), Some(100));
+}
+
+#[test]
+fn poor_matches_are_left_out() {
+    assert_eq!(score("xyzzy", "Buy milk"), None);
+    assert_eq!(score("", "Buy milk"), None);
+    assert_eq!(score("!!", "Buy milk"), None);
+    // One word of three found is not enough
+    assert_eq!(score("milk bread eggs", "Buy milk"), None);
+    assert!(score("milk bread", "Buy milk").unwrap() >= MIN_SCORE);
+    assert!(ranked(&list_of(FIXTURE), "zebra").is_empty());
+}
+
+#[test]
+fn at_most_ten_matches_are_kept_with_their_real_numbers() {
+    let mut todo = list_of(&["Unrelated"]);
+    for i in 0..15 {
+        todo.add_tasks(format!("Report {}", i)).unwrap();
+    }
+    let found = fuzzy::rank(&todo, "report", LIMIT);
+    assert_eq!(found.len(), 10);
+    assert_eq!(found[0].index, 2);
+    assert!(
+        found
+            .iter()
+            .all(|found| found.task == &todo[found.index - 1])
+    );
+}
+
+#[test]
+fn the_command_needs_some_text_and_ranks_through_query() {
+    assert_eq!(
+        parse_command("fuzzy buy  mlik").unwrap(),
+        Command::Fuzzy("buy mlik".to_string())
+    );
+    assert!(parse_command("fuzzy").is_err());
+    assert!(parse_command("fuzzy ?!").is_err());
+    assert!(!parse_command("fuzzy milk").unwrap().mutates());
+
+    let todo = list_of(FIXTURE);
+    let Ok(CommandOutcome::Ranked(found)) = query(&todo, Command::Fuzzy("milk".to_string())) else {
+        panic!("fuzzy gives its matches");
+    };
+    let scores: Vec<u32> = found.iter().map(|found| found.score).collect();
+    assert_eq!(scores, [100, 100, 50]);
+}
@@ -0,0 +1,102 @@
+use rust_todo_cli::import::{Format, ImportSummary, parse};
+use rust_todo_cli::todo::{Status, TodoList};
+
+const EXPORT: &str = r#"[
+    {"number": 12, "title": "Fix login", "state": "OPEN",
+     "labels": [{"name": "bug"}, {"name": "good first issue"}]},
+    {"number": 7, "title": "Write docs", "state": "CLOSED", "labels": []}
+]"#;
+
+#[test]
+fn issues_become_tasks_with_their_number_as_the_source() {
+    let tasks = parse(Format::GitHub, EXPORT).unwrap();
+    assert_eq!(tasks.len(), 2);
+    assert_eq!(tasks[0].description, "Fix login");
+    assert_eq!(tasks[0].status, Status::Todo);
+    assert_eq!(tasks[0].tags, ["bug", "good-first-issue"]);
+    assert_eq!(tasks[0].source.as_deref(), Some("github#12"));
+    assert_eq!(tasks[1].status, Status::Completed);
+    assert!(tasks[1].completed_at.is_some());
+}
+
+#[test]
+fn only_the_number_and_title_are_needed() {
+    let tasks = parse(
+        Format::GitHub,
+        r#"[{"number": 3, "title": "Triage", "url": "x"}]"#,
+    )
+    .unwrap();
+    assert_eq!(tasks[0].status, Status::Todo);
+    assert!(tasks[0].tags.is_empty());
+}
+
+#[test]
+fn malformed_exports_are_rejected_whole() {
+    for json in [
+        r#"{"number": 1, "title": "Not a list"}"#,
+        r#"[{"number": 1, "title": "Fine"}, {"title": "No number"}]"#,
+        r#"[{"number": 1, "title": "Fine"}, {"number": 2, "title": "  "}]"#,
+        "[",
+    ] {
+        assert!(parse(Format::GitHub, json).is_err(), "{}", json);
+    }
+}
+
+#[test]
+fn importing_again_updates_instead_of_adding() {
+    let mut todo = TodoList::new();
+    todo.add_tasks("Buy milk".to_string()).unwrap();
+    let summary = todo.import(parse(Format::GitHub, EXPORT).unwrap(), "issues.json");
+    assert_eq!(
+        summary,
+        ImportSummary {
+            created: 2,
+            updated: 0,
+            skipped: 0
+        }
+    );
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::InProgress, false)
+        .unwrap();
+    todo.add_tags(todo.task_ref(2).unwrap(), &["urgent".to_string()])
+        .unwrap();
+
+    // Issue 12 was closed and renamed; issue 7 is as it was
+    let export = r#"[
+        {"number": 12, "title": "Fix login on Safari", "state": "CLOSED",
+         "labels": [{"name": "bug"}]},
+        {"number": 7, "title": "Write docs", "state": "CLOSED"},
+        {"number": 15, "title": "Add dark mode", "state": "OPEN"}
+    ]"#;
+    let summary = todo.import(parse(Format::GitHub, export).unwrap(), "issues.json");
+    assert_eq!(
+        summary,
+        ImportSummary {
+            created: 1,
+            updated: 1,
+            skipped: 1
+        }
+    );
+    assert_eq!(todo.len(), 4);
+    let task = todo.get_task(2).unwrap();
+    assert_eq!(task.description, "Fix login on Safari");
+    assert_eq!(task.status, Status::Completed);
+    // Tags added here stay
+    assert_eq!(task.tags, ["bug", "good-first-issue", "urgent"]);
+}
+
+#[test]
+fn an_open_issue_leaves_the_task_in_progress() {
+    let mut todo = TodoList::new();
+    todo.import(parse(Format::GitHub, EXPORT).unwrap(), "issues.json");
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::InProgress, false)
+        .unwrap();
+    let summary = todo.import(parse(Format::GitHub, EXPORT).unwrap(), "issues.json");
+    assert_eq!(summary.skipped, 2);
+    assert_eq!(todo.get_task(1).unwrap().status, Status::InProgress);
+}
+
+#[test]
+fn formats_are_named_in_any_case() {
+    assert_eq!(Format::from_name("GitHub"), Some(Format::GitHub));
+    assert_eq!(Format::from_name("gitlab"), None);
+}
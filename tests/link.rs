@@ -0,0 +1,72 @@
+use rust_todo_cli::link::find_urls;
+
+#[test]
+fn every_link_is_found_in_order() {
+    assert_eq!(
+        find_urls("Compare https://a.example/x and http://b.example/y?q=1#top"),
+        ["https://a.example/x", "http://b.example/y?q=1#top"]
+    );
+    assert_eq!(
+        find_urls("https://a.example https://a.example"),
+        ["https://a.example"; 2]
+    );
+    assert!(find_urls("Buy milk").is_empty());
+    // A scheme with nothing after it is not a link
+    assert!(find_urls("type https:// here").is_empty());
+    assert!(find_urls("ftp://files.example").is_empty());
+}
+
+#[test]
+fn punctuation_after_a_link_is_left_out() {
+    assert_eq!(
+        find_urls("See https://example.com."),
+        ["https://example.com"]
+    );
+    assert_eq!(
+        find_urls("https://example.com/a, then"),
+        ["https://example.com/a"]
+    );
+    assert_eq!(
+        find_urls("Really https://example.com?!"),
+        ["https://example.com"]
+    );
+    assert_eq!(
+        find_urls("\"https://example.com/q\";"),
+        ["https://example.com/q"]
+    );
+    assert_eq!(
+        find_urls("(see https://example.com)."),
+        ["https://example.com"]
+    );
+    assert_eq!(find_urls("<https://example.com>"), ["https://example.com"]);
+    // Text glued to the front of a link is not part of it
+    assert_eq!(
+        find_urls("docs:https://example.com"),
+        ["https://example.com"]
+    );
+}
+
+#[test]
+fn brackets_opened_inside_a_link_are_kept() {
+    assert_eq!(
+        find_urls("https://en.wikipedia.org/wiki/Rust_(language)"),
+        ["https://en.wikipedia.org/wiki/Rust_(language)"]
+    );
+    assert_eq!(
+        find_urls("(https://en.wikipedia.org/wiki/Rust_(language))."),
+        ["https://en.wikipedia.org/wiki/Rust_(language)"]
+    );
+    assert_eq!(
+        find_urls("[https://example.com/a[1]]"),
+        ["https://example.com/a[1]"]
+    );
+}
+
+#[test]
+fn links_keep_characters_a_shell_would_act_on() {
+    // Handed to the browser as one argument, never through cmd or a shell
+    assert_eq!(
+        find_urls("https://example.com/?a=1&calc|x^y"),
+        ["https://example.com/?a=1&calc|x^y"]
+    );
+}
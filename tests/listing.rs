@@ -0,0 +1,244 @@
+use rust_todo_cli::date::Date;
+use rust_todo_cli::listing::{
+    GROUP_ORDER, RELATIVE_DUE_DAYS, age_label, flat, group_by_status, grouped, relative_due,
+};
+use rust_todo_cli::theme::Theme;
+use rust_todo_cli::todo::{ListFilter, Status, Task, TodoList};
+
+// Tasks without a creation time, so no age depends on when the test runs
+fn undated(description: &str) -> Task {
+    let mut task = Task::new(description.to_string()).unwrap();
+    task.created_at = None;
+    task
+}
+
+fn sample() -> TodoList {
+    let mut milk = undated("Buy milk");
+    milk.tags = vec!["shop".to_string()];
+    milk.due = Date::new(2025, 6, 1);
+    let mut report = undated("Write report");
+    report.set_status(Status::InProgress);
+    report.subtasks.push(undated("Outline"));
+    let mut call = undated("Call mum");
+    call.set_status(Status::Completed);
+    call.completed_at = None;
+    [milk, report, call].into_iter().collect()
+}
+
+#[test]
+fn display_lists_every_task_with_emoji() {
+    let expected = "\
+⚪ 1. Buy milk [TODO] #shop (2025-06-01)
+🔵 2. Write report [IN-PROGRESS]
+   ⚪ 2.1. Outline [TODO]
+✅ 3. Call mum [DONE]";
+    assert_eq!(sample().to_string(), expected);
+}
+
+#[test]
+fn alternate_display_is_plain_ascii() {
+    let expected = "\
+[ ] 1. Buy milk [TODO] #shop (2025-06-01)
+[~] 2. Write report [IN-PROGRESS]
+   [ ] 2.1. Outline [TODO]
+[x] 3. Call mum [DONE]";
+    let listing = format!("{:#}", sample());
+    assert_eq!(listing, expected);
+    assert!(listing.is_ascii());
+}
+
+#[test]
+fn display_marks_blocked_tasks_and_links() {
+    let mut todo: TodoList = [undated("Read https://example.com"), undated("Reply")]
+        .into_iter()
+        .collect();
+    todo.add_dependency(todo.task_ref(2).unwrap(), todo.task_ref(1).unwrap())
+        .unwrap();
+    let expected = "\
+[ ] 1. Read https://example.com [TODO] [link]
+[ ] 2. Reply [TODO] [blocked]";
+    assert_eq!(format!("{:#}", todo), expected);
+}
+
+#[test]
+fn display_shows_estimates_after_the_status() {
+    let todo: TodoList = [undated("Write report ~1h30m"), undated("Reply")]
+        .into_iter()
+        .collect();
+    let expected = "\
+[ ] 1. Write report [TODO] ~1h30m
+[ ] 2. Reply [TODO]";
+    assert_eq!(format!("{:#}", todo), expected);
+}
+
+#[test]
+fn grouped_listing_has_a_section_per_status_in_progress_first() {
+    let todo = sample();
+    let tasks = todo.filter(&ListFilter::default());
+    let expected = "\
+IN PROGRESS (1)
+[~] 2. Write report [IN-PROGRESS]
+   [ ] 2.1. Outline [TODO]
+
+TODO (1)
+[ ] 1. Buy milk [TODO] #shop (2025-06-01)
+
+DONE (1)
+[x] 3. Call mum [DONE]";
+    assert_eq!(
+        grouped(&todo, &tasks, &Theme::ascii(), Date::today()),
+        expected
+    );
+}
+
+#[test]
+fn flat_listing_keeps_list_order() {
+    let todo = sample();
+    let tasks = todo.filter(&ListFilter::default());
+    let expected = "\
+[ ] 1. Buy milk [TODO] #shop (2025-06-01)
+[~] 2. Write report [IN-PROGRESS]
+   [ ] 2.1. Outline [TODO]
+[x] 3. Call mum [DONE]";
+    assert_eq!(
+        flat(&todo, &tasks, &Theme::ascii(), Date::today()),
+        expected
+    );
+}
+
+#[test]
+fn filtered_grouped_listing_is_one_section_with_the_real_numbers() {
+    let mut todo = sample();
+    todo.add(undated("Post letter"));
+    let filter = ListFilter {
+        status: Some(Status::Todo),
+        grouped: true,
+        ..ListFilter::default()
+    };
+    let tasks = todo.filter(&filter);
+    let expected = "\
+TODO (2)
+[ ] 1. Buy milk [TODO] #shop (2025-06-01)
+[ ] 4. Post letter [TODO]";
+    assert_eq!(
+        grouped(&todo, &tasks, &Theme::ascii(), Date::today()),
+        expected
+    );
+}
+
+#[test]
+fn groups_follow_the_group_order_and_skip_empty_statuses() {
+    let mut one = undated("One");
+    one.set_status(Status::Cancelled);
+    let mut three = undated("Three");
+    three.set_status(Status::Waiting);
+    let todo: TodoList = [one, undated("Two"), three].into_iter().collect();
+    let tasks: Vec<(usize, &Task)> = todo.list_tasks().collect();
+    let groups = group_by_status(&tasks);
+    let statuses: Vec<Status> = groups.iter().map(|group| group.status).collect();
+    assert_eq!(statuses, [Status::Todo, Status::Waiting, Status::Cancelled]);
+    assert!(
+        statuses
+            .windows(2)
+            .all(|pair| GROUP_ORDER.iter().position(|s| *s == pair[0])
+                < GROUP_ORDER.iter().position(|s| *s == pair[1]))
+    );
+    let numbers: Vec<usize> = groups
+        .iter()
+        .flat_map(|g| g.tasks.iter().map(|(i, _)| *i))
+        .collect();
+    assert_eq!(numbers, [2, 3, 1]);
+    assert!(group_by_status(&[]).is_empty());
+}
+
+#[test]
+fn empty_list_says_so() {
+    let todo = TodoList::new();
+    assert_eq!(todo.to_string(), "No tasks yet");
+    assert_eq!(format!("{:#}", todo), "No tasks yet");
+    assert_eq!(todo.summary(), "No tasks");
+}
+
+#[test]
+fn summary_counts_each_status() {
+    assert_eq!(sample().summary(), "3 tasks: 1 todo, 1 in-progress, 1 done");
+
+    let mut todo = sample();
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Cancelled, false)
+        .unwrap();
+    assert_eq!(
+        todo.summary(),
+        "3 tasks: 0 todo, 1 in-progress, 1 done, 1 cancelled"
+    );
+
+    let single: TodoList = [undated("Only")].into_iter().collect();
+    assert_eq!(single.summary(), "1 task: 1 todo, 0 in-progress, 0 done");
+}
+
+#[test]
+fn relative_due_counts_days_from_today() {
+    let today = Date::new(2025, 6, 10).unwrap();
+    let on = |day| relative_due(Date::new(2025, 6, day).unwrap(), today);
+    assert_eq!(on(10), "due today");
+    assert_eq!(on(11), "due tomorrow");
+    assert_eq!(on(13), "due in 3d");
+    assert_eq!(on(9), "1d overdue");
+    assert_eq!(on(8), "2d overdue");
+}
+
+#[test]
+fn relative_due_crosses_months_and_years() {
+    let new_year = Date::new(2025, 12, 31).unwrap();
+    assert_eq!(
+        relative_due(Date::new(2026, 1, 1).unwrap(), new_year),
+        "due tomorrow"
+    );
+    assert_eq!(
+        relative_due(new_year, Date::new(2026, 1, 2).unwrap()),
+        "2d overdue"
+    );
+    // 2024 is a leap year
+    assert_eq!(
+        relative_due(
+            Date::new(2024, 3, 1).unwrap(),
+            Date::new(2024, 2, 28).unwrap()
+        ),
+        "due in 2d"
+    );
+}
+
+#[test]
+fn far_off_due_dates_are_shown_as_dates() {
+    let today = Date::new(2025, 6, 10).unwrap();
+    let ahead = |days| relative_due(today.add_days(days), today);
+    assert_eq!(
+        ahead(RELATIVE_DUE_DAYS),
+        format!("due in {}d", RELATIVE_DUE_DAYS)
+    );
+    assert_eq!(ahead(RELATIVE_DUE_DAYS + 1), "2025-08-10");
+    assert_eq!(ahead(400), "2026-07-15");
+    // Long overdue still says how long
+    assert_eq!(ahead(-400), "400d overdue");
+}
+
+#[test]
+fn open_tasks_show_their_age_and_finished_ones_how_long_they_took() {
+    // 2025-01-15 12:00 UTC; ages count from its local date, whatever the zone
+    const CREATED: u64 = 1_736_942_400;
+    let created = Date::from_timestamp(CREATED);
+    let mut task = undated("Renew passport");
+    assert_eq!(age_label(&task, created), None);
+
+    task.created_at = Some(CREATED);
+    assert_eq!(age_label(&task, created).as_deref(), Some("0d"));
+    assert_eq!(age_label(&task, created.add_days(6)).as_deref(), Some("6d"));
+    assert_eq!(age_label(&task, created.add_days(7)).as_deref(), Some("1w"));
+
+    task.set_status(Status::Completed);
+    task.completed_at = Some(CREATED + 3 * 86_400);
+    let later = created.add_days(400);
+    assert_eq!(age_label(&task, later).as_deref(), Some("took 3d"));
+    // Reopened, it is aged from its creation again
+    task.set_status(Status::Todo);
+    assert_eq!(age_label(&task, later).as_deref(), Some("1y"));
+}
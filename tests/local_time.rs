@@ -0,0 +1,53 @@
+// Local time follows TZ, which is read by the whole process, so this file
+// holds a single test that sets it step by step. The zone is cached per
+// thread, so each one is looked at from a thread of its own
+
+use rust_todo_cli::date::{Date, Timestamp, format_timestamp, utc_offset};
+
+// 2025-01-15 and 2025-07-15, both at 12:00 UTC
+const WINTER: Timestamp = 1_736_942_400;
+const SUMMER: Timestamp = 1_752_580_800;
+
+fn in_zone(zone: &str, check: impl FnOnce() + Send) {
+    // SAFETY: no other test in this binary reads or writes the environment
+    unsafe { std::env::set_var("TZ", zone) };
+    std::thread::scope(|scope| scope.spawn(check).join().unwrap());
+}
+
+#[test]
+fn offsets_follow_the_time_zone_and_its_summer_time() {
+    in_zone("UTC", || {
+        assert_eq!(utc_offset(WINTER), 0);
+        assert_eq!(format_timestamp(WINTER), "2025-01-15 12:00");
+    });
+
+    in_zone("Asia/Kolkata", || {
+        assert_eq!(utc_offset(WINTER), 5 * 3600 + 1800);
+        assert_eq!(utc_offset(SUMMER), 5 * 3600 + 1800);
+        assert_eq!(format_timestamp(WINTER), "2025-01-15 17:30");
+    });
+
+    // Summer time moves the offset by an hour
+    in_zone("America/New_York", || {
+        assert_eq!(utc_offset(WINTER), -5 * 3600);
+        assert_eq!(utc_offset(SUMMER), -4 * 3600);
+        assert_eq!(format_timestamp(SUMMER), "2025-07-15 08:00");
+    });
+
+    // The local date is the one on the wall, not in Greenwich
+    in_zone("Pacific/Kiritimati", || {
+        assert_eq!(utc_offset(WINTER), 14 * 3600);
+        assert_eq!(
+            Date::from_timestamp(WINTER + 11 * 3600),
+            Date::new(2025, 1, 16).unwrap()
+        );
+    });
+
+    // A rule given in place of a zone name
+    in_zone("<+0545>-5:45", || {
+        assert_eq!(utc_offset(SUMMER), 5 * 3600 + 45 * 60);
+    });
+
+    // Moments past what a signed 64-bit time holds have no offset
+    assert_eq!(utc_offset(u64::MAX), 0);
+}
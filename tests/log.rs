@@ -0,0 +1,54 @@
+use std::io;
+
+use rust_todo_cli::log::{Level, chain, format_line};
+use rust_todo_cli::todo::TodoError;
+
+#[test]
+fn filters_pick_the_level_for_this_crate() {
+    for (filter, expected) in [
+        ("debug", Some(Level::Debug)),
+        ("TRACE", Some(Level::Trace)),
+        ("rust_todo_cli=debug", Some(Level::Debug)),
+        ("rust_todo_cli::store=trace", Some(Level::Trace)),
+        ("rust_todo_cli", Some(Level::Trace)),
+        ("hyper=trace,rust_todo_cli=info", Some(Level::Info)),
+        ("warn,rust_todo_cli=debug", Some(Level::Debug)),
+        ("hyper=trace", None),
+        ("rust_todo_cli_extra=trace", None),
+        ("loud", None),
+        ("", None),
+    ] {
+        assert_eq!(Level::from_filter(filter), expected, "{}", filter);
+    }
+}
+
+#[test]
+fn level_names_round_trip() {
+    for level in Level::ALL {
+        assert_eq!(Level::from_name(level.name()), Some(level));
+    }
+    assert!(Level::Off < Level::Error && Level::Debug < Level::Trace);
+}
+
+#[test]
+fn chains_list_every_cause() {
+    let error = TodoError::from(io::Error::new(io::ErrorKind::PermissionDenied, "denied"));
+    let causes = chain(&error);
+    assert_eq!(causes.len(), 2, "{:?}", causes);
+    assert!(causes[0].contains("denied"), "{:?}", causes);
+    assert_eq!(causes[1], "denied");
+
+    assert_eq!(chain(&TodoError::InvalidIndex).len(), 1);
+}
+
+#[test]
+fn lines_name_the_level_and_module() {
+    assert_eq!(
+        format_line(
+            Level::Debug,
+            "rust_todo_cli::store",
+            format_args!("saved {}", 3)
+        ),
+        "[DEBUG rust_todo_cli::store] saved 3"
+    );
+}
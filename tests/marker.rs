@@ -0,0 +1,123 @@
+mod common;
+
+use common::list_of;
+use rust_todo_cli::theme::Color;
+use rust_todo_cli::todo::{Marker, Storable, TodoError, TodoList};
+
+fn marker(text: Option<&str>, color: Option<Color>) -> Marker {
+    Marker {
+        text: text.map(str::to_string),
+        color,
+    }
+}
+
+#[test]
+fn a_marker_is_a_word_a_color_or_both() {
+    assert_eq!(Marker::parse(&["🔥"]).unwrap(), marker(Some("🔥"), None));
+    assert_eq!(Marker::parse(&["VIP"]).unwrap(), marker(Some("VIP"), None));
+    assert_eq!(
+        Marker::parse(&["Red"]).unwrap(),
+        marker(None, Some(Color::Red))
+    );
+    assert_eq!(
+        Marker::parse(&["🔥", "dim-green"]).unwrap(),
+        marker(Some("🔥"), Some(Color::DimGreen))
+    );
+}
+
+#[test]
+fn several_words_and_unknown_colors_are_rejected() {
+    assert!(matches!(
+        Marker::parse(&["on", "fire", "red"]),
+        Err(TodoError::InvalidMarker(marker)) if marker == "on fire red"
+    ));
+    assert!(matches!(
+        Marker::parse(&["\"on", "fire\""]),
+        Err(TodoError::InvalidMarker(marker)) if marker == "on fire"
+    ));
+    // A misspelled color is not taken for a marker
+    let error = Marker::parse(&["gren"]).unwrap_err();
+    assert!(matches!(&error, TodoError::UnknownColor(color) if color == "gren"));
+    assert!(error.to_string().contains(Color::NAMES), "{}", error);
+    assert!(matches!(
+        Marker::parse(&["🔥", "purple"]),
+        Err(TodoError::UnknownColor(color)) if color == "purple"
+    ));
+}
+
+#[test]
+fn marking_sets_what_is_given_and_clearing_takes_both() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    todo.set_marker(todo.task_ref(2).unwrap(), Some(marker(Some("🔥"), None)))
+        .unwrap();
+    todo.set_marker(
+        todo.task_ref(2).unwrap(),
+        Some(marker(None, Some(Color::Red))),
+    )
+    .unwrap();
+    assert_eq!(todo[1].marker.as_deref(), Some("🔥"));
+    assert_eq!(todo[1].marker_color.as_deref(), Some("red"));
+    assert_eq!(todo[1].color(), Some(Color::Red));
+    assert!(todo[1].updated_at.is_some());
+
+    todo.set_marker(todo.task_ref(2).unwrap(), None).unwrap();
+    assert_eq!((&todo[1].marker, &todo[1].marker_color), (&None, &None));
+    assert!(matches!(
+        todo.task_ref(3),
+        Err(TodoError::IndexOutOfBound(3))
+    ));
+}
+
+#[test]
+fn the_plain_listing_shows_the_marker_after_the_status() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    todo.set_marker(
+        todo.task_ref(1).unwrap(),
+        Some(marker(Some("🔥"), Some(Color::Red))),
+    )
+    .unwrap();
+    let listing = format!("{:#}", todo);
+    let lines: Vec<&str> = listing.lines().collect();
+    assert!(
+        lines[0].starts_with("[ ] 🔥 1. Buy milk [TODO]"),
+        "{}",
+        listing
+    );
+    assert!(
+        lines[1].starts_with("[ ] 2. Call mum [TODO]"),
+        "{}",
+        listing
+    );
+    assert!(!listing.contains('\x1b'), "{:?}", listing);
+}
+
+#[test]
+fn markers_survive_saving_and_old_files_load_without_them() {
+    let dir = std::env::temp_dir().join(format!("rust-todo-cli-marker-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json");
+    let path = path.to_str().unwrap();
+
+    let mut todo = list_of(&["Buy milk"]);
+    todo.set_marker(
+        todo.task_ref(1).unwrap(),
+        Some(marker(Some("VIP"), Some(Color::Magenta))),
+    )
+    .unwrap();
+    todo.save(path).unwrap();
+    let loaded = TodoList::load(path).unwrap();
+    assert_eq!(loaded[0].marker.as_deref(), Some("VIP"));
+    assert_eq!(loaded[0].color(), Some(Color::Magenta));
+
+    // A color from a newer version is kept but not used
+    std::fs::write(
+        path,
+        r#"[{ "description": "Bare" }, { "description": "New", "marker_color": "teal" }]"#,
+    )
+    .unwrap();
+    let old = TodoList::load(path).unwrap();
+    assert_eq!((&old[0].marker, &old[0].marker_color), (&None, &None));
+    assert_eq!(old[1].marker_color.as_deref(), Some("teal"));
+    assert_eq!(old[1].color(), None);
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,90 @@
+use std::io::Cursor;
+
+use rust_todo_cli::menu::{confirm, select_task};
+use rust_todo_cli::theme::Theme;
+use rust_todo_cli::todo::{ListFilter, Status, TodoList};
+
+fn sample() -> TodoList {
+    let mut todo = TodoList::new();
+    for description in ["Buy milk", "Call mum", "Write report"] {
+        todo.add_tasks(description.to_string()).unwrap();
+    }
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo
+}
+
+// The pick made from `answers`, and everything the menu printed
+fn select(todo: &TodoList, filter: &ListFilter, answers: &str) -> (Option<usize>, String) {
+    let mut out = Vec::new();
+    let picked = select_task(
+        todo,
+        filter,
+        &Theme::ascii(),
+        &mut Cursor::new(answers),
+        &mut out,
+    )
+    .unwrap();
+    (picked, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn menu_lists_the_tasks_and_takes_a_number() {
+    let (picked, shown) = select(&sample(), &ListFilter::default(), "3\n");
+    assert_eq!(picked, Some(3));
+    assert!(shown.contains("[ ] 1. Buy milk"), "{}", shown);
+    assert!(shown.contains("[x] 2. Call mum"), "{}", shown);
+    assert!(shown.contains("[ ] 3. Write report"), "{}", shown);
+}
+
+#[test]
+fn answers_outside_the_menu_are_asked_again() {
+    let todo_only = ListFilter {
+        status: Some(Status::Todo),
+        ..ListFilter::default()
+    };
+    let (picked, shown) = select(&sample(), &todo_only, "2\nseven\n1\n");
+    assert_eq!(picked, Some(1));
+    assert!(!shown.contains("Call mum"), "{}", shown);
+    assert_eq!(shown.matches("is not one of the tasks above").count(), 2);
+}
+
+#[test]
+fn empty_answer_escape_or_end_of_input_cancel() {
+    let filter = ListFilter::default();
+    for answers in ["\n", "\u{1b}\n", "", "9\n"] {
+        assert_eq!(select(&sample(), &filter, answers).0, None, "{:?}", answers);
+    }
+}
+
+#[test]
+fn nothing_matching_picks_nothing_without_asking() {
+    let filter = ListFilter {
+        text: vec!["groceries".to_string()],
+        ..ListFilter::default()
+    };
+    let (picked, shown) = select(&sample(), &filter, "1\n");
+    assert_eq!(picked, None);
+    assert!(shown.is_empty(), "{}", shown);
+}
+
+// The answer read from `typed`, and what the question printed
+fn ask(typed: &str) -> (bool, String) {
+    let mut out = Vec::new();
+    let yes = confirm("Remove it?", &mut Cursor::new(typed), &mut out).unwrap();
+    (yes, String::from_utf8(out).unwrap())
+}
+
+#[test]
+fn only_y_or_yes_confirms() {
+    for typed in ["y\n", "yes\n", "Y\n", "  YES \r\n", "y"] {
+        assert!(ask(typed).0, "{:?}", typed);
+    }
+    // No, an empty answer, the end of input and anything else
+    for typed in ["n\n", "no\n", "\n", "", "yep\n", "y es\n", "\u{1b}\n"] {
+        assert!(!ask(typed).0, "{:?}", typed);
+    }
+    // Only the first line is the answer
+    assert!(!ask("\nyes\n").0);
+    assert_eq!(ask("y\n").1, "Remove it? [y/N] ");
+}
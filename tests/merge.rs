@@ -0,0 +1,413 @@
+use std::fs;
+use std::time::Duration;
+
+use rust_todo_cli::date::Date;
+use rust_todo_cli::merge::{Side, match_tasks, merge, merge_task, read_tasks};
+use rust_todo_cli::todo::{Recurrence, Status, Task, TaskIndex, TodoList};
+
+// A task as saved, with a fixed creation time so copies of it match
+fn task(id: u64, description: &str) -> Task {
+    let mut task = Task::builder(description).build().unwrap();
+    task.id = id;
+    task.created_at = Some(1_000 * id);
+    task
+}
+
+fn with_status(mut task: Task, status: Status, completed_at: Option<u64>) -> Task {
+    task.status = status;
+    task.completed_at = completed_at;
+    task
+}
+
+fn descriptions(tasks: &[Task]) -> Vec<&str> {
+    tasks.iter().map(|task| task.description.as_str()).collect()
+}
+
+#[test]
+fn tasks_are_matched_by_id() {
+    let ours = [task(1, "Buy milk"), task(2, "Call mum")];
+    let theirs = [task(2, "Call mum tonight"), task(1, "Buy milk")];
+    assert_eq!(
+        match_tasks(&ours, &theirs),
+        [(Some(0), Some(1)), (Some(1), Some(0))]
+    );
+}
+
+#[test]
+fn tasks_without_ids_are_matched_by_description() {
+    let ours = [task(1, "Buy milk"), task(2, "Call mum")];
+    let theirs = [task(0, "call MUM "), task(0, "Pay rent")];
+    assert_eq!(
+        match_tasks(&ours, &theirs),
+        [(Some(0), None), (Some(1), Some(0)), (None, Some(1))]
+    );
+}
+
+#[test]
+fn tasks_with_different_ids_are_not_matched_by_description() {
+    let ours = [task(1, "Buy milk")];
+    let theirs = [task(7, "Buy milk")];
+    assert_eq!(
+        match_tasks(&ours, &theirs),
+        [(Some(0), None), (None, Some(0))]
+    );
+}
+
+#[test]
+fn renumbered_tasks_are_matched_by_description_and_creation() {
+    // Our copy was renumbered: Call mum was 7 and Buy milk, waiting on it, 9
+    let mut ours = [task(7, "Call mum"), task(9, "Buy milk")];
+    ours[0].id = 1;
+    ours[1].id = 2;
+    ours[1].depends_on = vec![1];
+    let mut theirs = [task(7, "Call mum"), task(9, "Buy milk")];
+    theirs[1].depends_on = vec![7];
+    theirs[1].status = Status::Completed;
+    assert_eq!(
+        match_tasks(&ours, &theirs),
+        [(Some(0), Some(0)), (Some(1), Some(1))]
+    );
+    let report = merge(&ours, &theirs);
+    assert_eq!(descriptions(&report.tasks), ["Call mum", "Buy milk"]);
+    assert_eq!(report.tasks[1].id, 2);
+    assert_eq!(report.tasks[1].depends_on, [1]);
+    assert_eq!(report.tasks[1].status, Status::Completed);
+}
+
+#[test]
+fn same_id_but_created_apart_are_different_tasks() {
+    let ours = [task(3, "Ours new")];
+    let mut theirs = task(3, "Theirs new");
+    theirs.created_at = Some(99_999);
+    let pairs = match_tasks(&ours, std::slice::from_ref(&theirs));
+    assert_eq!(pairs, [(Some(0), None), (None, Some(0))]);
+
+    let report = merge(&ours, &[theirs]);
+    assert_eq!(descriptions(&report.tasks), ["Ours new", "Theirs new"]);
+    assert!(report.conflicts.is_empty());
+    assert_eq!(report.tasks[1].id, 4);
+}
+
+#[test]
+fn duplicate_descriptions_pair_up_in_order() {
+    let ours = [task(0, "Water plants"), task(0, "Water plants")];
+    let theirs = [task(0, "Water plants")];
+    assert_eq!(
+        match_tasks(&ours, &theirs),
+        [(Some(0), Some(0)), (Some(1), None)]
+    );
+}
+
+#[test]
+fn tasks_in_one_copy_only_are_kept() {
+    let ours = [task(1, "Buy milk"), task(2, "Ours only")];
+    let theirs = [task(1, "Buy milk"), task(5, "Theirs only")];
+    let report = merge(&ours, &theirs);
+    assert_eq!(
+        descriptions(&report.tasks),
+        ["Buy milk", "Ours only", "Theirs only"]
+    );
+    assert_eq!(
+        (report.matched, report.only_ours, report.only_theirs),
+        (1, 1, 1)
+    );
+    assert_eq!(report.updated, 0);
+    assert_eq!(report.tasks[2].id, 5);
+}
+
+#[test]
+fn identical_copies_merge_to_themselves() {
+    let ours = [task(1, "Buy milk"), task(2, "Call mum")];
+    let report = merge(&ours, &ours);
+    assert_eq!(report.tasks, ours);
+    assert_eq!((report.matched, report.updated), (2, 0));
+    assert!(report.conflicts.is_empty());
+}
+
+#[test]
+fn merging_with_an_empty_copy_changes_nothing() {
+    let ours = [task(1, "Buy milk")];
+    assert_eq!(merge(&ours, &[]).tasks, ours);
+    let report = merge(&[], &ours);
+    assert_eq!(report.tasks, ours);
+    assert_eq!(report.only_theirs, 1);
+}
+
+#[test]
+fn the_status_furthest_along_wins() {
+    use Status::*;
+    // (ours, theirs, merged)
+    let matrix = [
+        (Todo, Todo, Todo),
+        (Todo, InProgress, InProgress),
+        (Todo, Completed, Completed),
+        (Todo, Cancelled, Cancelled),
+        (InProgress, Todo, InProgress),
+        (InProgress, InProgress, InProgress),
+        (InProgress, Completed, Completed),
+        (InProgress, Cancelled, Cancelled),
+        (Completed, Todo, Completed),
+        (Completed, InProgress, Completed),
+        (Cancelled, Todo, Cancelled),
+        (Cancelled, InProgress, Cancelled),
+    ];
+    for (our_status, their_status, expected) in matrix {
+        let closed = |status: Status| (status == Completed || status == Cancelled).then_some(50);
+        let ours = with_status(task(1, "Buy milk"), our_status, closed(our_status));
+        let theirs = with_status(task(1, "Buy milk"), their_status, closed(their_status));
+        let merged = merge_task(&ours, &theirs);
+        assert_eq!(
+            merged.status, expected,
+            "{:?} + {:?}",
+            our_status, their_status
+        );
+        assert_eq!(merged.completed_at, closed(expected));
+    }
+}
+
+#[test]
+fn between_closed_tasks_the_later_one_wins() {
+    let ours = with_status(task(1, "Buy milk"), Status::Completed, Some(100));
+    let theirs = with_status(task(1, "Buy milk"), Status::Cancelled, Some(200));
+    let merged = merge_task(&ours, &theirs);
+    assert_eq!(merged.status, Status::Cancelled);
+    assert_eq!(merged.completed_at, Some(200));
+
+    let merged = merge_task(&theirs, &ours);
+    assert_eq!(merged.status, Status::Cancelled);
+
+    let theirs = with_status(task(1, "Buy milk"), Status::Completed, Some(300));
+    assert_eq!(merge_task(&ours, &theirs).completed_at, Some(300));
+}
+
+#[test]
+fn newer_dates_are_kept() {
+    let mut ours = task(1, "Pay rent");
+    ours.due = Some(Date::new(2025, 6, 1).unwrap());
+    ours.accumulated = Duration::from_secs(600);
+    let mut theirs = task(1, "Pay rent");
+    theirs.due = Some(Date::new(2025, 6, 8).unwrap());
+    theirs.accumulated = Duration::from_secs(60);
+    theirs.timer_started = Some(5_000);
+
+    let merged = merge_task(&ours, &theirs);
+    assert_eq!(merged.due, Date::new(2025, 6, 8));
+    assert_eq!(merged.accumulated, Duration::from_secs(600));
+    assert_eq!(merged.timer_started, Some(5_000));
+    assert_eq!(merged.created_at, Some(1_000));
+}
+
+#[test]
+fn missing_details_are_filled_in_from_either_side() {
+    let mut ours = task(0, "Water plants");
+    ours.created_at = None;
+    let mut theirs = task(0, "Water plants");
+    theirs.created_at = Some(42);
+    theirs.recurrence = Recurrence::parse("weekly").ok();
+    theirs.due = Date::new(2025, 6, 1);
+
+    let merged = merge_task(&ours, &theirs);
+    assert_eq!(merged.created_at, Some(42));
+    assert_eq!(merged.recurrence, Recurrence::parse("weekly").ok());
+    assert_eq!(merged.due, Date::new(2025, 6, 1));
+}
+
+#[test]
+fn description_matches_keep_the_earlier_creation() {
+    let mut ours = task(0, "Water plants");
+    ours.created_at = Some(500);
+    let mut theirs = task(0, "Water plants");
+    theirs.created_at = Some(300);
+    assert_eq!(merge_task(&ours, &theirs).created_at, Some(300));
+}
+
+#[test]
+fn labels_are_combined_in_order() {
+    let mut ours = task(1, "Plan trip");
+    ours.tags = vec!["travel".into(), "fun".into()];
+    ours.projects = vec!["summer".into()];
+    ours.depends_on = vec![2];
+    let mut theirs = task(1, "Plan trip");
+    theirs.tags = vec!["fun".into(), "family".into()];
+    theirs.contexts = vec!["home".into()];
+    theirs.depends_on = vec![2, 3];
+
+    let merged = merge_task(&ours, &theirs);
+    assert_eq!(merged.tags, ["travel", "fun", "family"]);
+    assert_eq!(merged.projects, ["summer"]);
+    assert_eq!(merged.contexts, ["home"]);
+    assert_eq!(merged.depends_on, [2, 3]);
+}
+
+#[test]
+fn subtasks_are_merged_like_tasks() {
+    let mut ours = task(1, "Move house");
+    ours.subtasks = vec![
+        with_status(task(0, "Pack books"), Status::Completed, Some(10)),
+        task(0, "Book van"),
+    ];
+    let mut theirs = task(1, "Move house");
+    theirs.subtasks = vec![
+        task(0, "Pack books"),
+        with_status(task(0, "book van"), Status::InProgress, None),
+        task(0, "Change address"),
+    ];
+
+    let merged = merge_task(&ours, &theirs);
+    assert_eq!(
+        descriptions(&merged.subtasks),
+        ["Pack books", "Book van", "Change address"]
+    );
+    assert_eq!(merged.subtasks[0].status, Status::Completed);
+    assert_eq!(merged.subtasks[1].status, Status::InProgress);
+}
+
+#[test]
+fn changes_from_their_copy_count_as_updates() {
+    let ours = [task(1, "Buy milk"), task(2, "Call mum")];
+    let theirs = [
+        with_status(task(1, "Buy milk"), Status::Completed, Some(10)),
+        task(2, "Call mum"),
+    ];
+    let report = merge(&ours, &theirs);
+    assert_eq!((report.matched, report.updated), (2, 1));
+    assert_eq!(report.tasks[0].status, Status::Completed);
+}
+
+#[test]
+fn descriptions_edited_differently_are_conflicts() {
+    let ours = [task(1, "Call mum"), task(2, "Buy milk")];
+    let theirs = [task(1, "Call mum tonight"), task(2, "Buy milk")];
+    let report = merge(&ours, &theirs);
+    assert_eq!(report.conflicts.len(), 1);
+    let conflict = &report.conflicts[0];
+    assert_eq!(conflict.at, TaskIndex { task: 1, sub: None });
+    assert_eq!(conflict.ours, "Call mum");
+    assert_eq!(conflict.theirs, "Call mum tonight");
+    // Ours stands until the conflict is resolved
+    assert_eq!(report.tasks[0].description, "Call mum");
+}
+
+#[test]
+fn conflicts_resolve_to_either_side() {
+    let ours = [task(1, "Call mum"), task(2, "Buy milk")];
+    let theirs = [task(1, "Call mum tonight"), task(2, "Buy oat milk")];
+
+    let mut report = merge(&ours, &theirs);
+    report.resolve(1, Side::Theirs);
+    assert_eq!(descriptions(&report.tasks), ["Call mum", "Buy oat milk"]);
+    report.resolve(1, Side::Ours);
+    assert_eq!(descriptions(&report.tasks), ["Call mum", "Buy milk"]);
+
+    report.resolve_all(Side::Theirs);
+    assert_eq!(
+        descriptions(&report.tasks),
+        ["Call mum tonight", "Buy oat milk"]
+    );
+    assert_eq!(report.conflicts.len(), 2);
+
+    // Resolving a conflict that is not there does nothing
+    report.resolve(5, Side::Ours);
+    assert_eq!(
+        descriptions(&report.tasks),
+        ["Call mum tonight", "Buy oat milk"]
+    );
+}
+
+#[test]
+fn subtask_conflicts_point_at_the_subtask() {
+    let mut ours = task(1, "Move house");
+    ours.subtasks = vec![task(4, "Pack books"), task(5, "Book van")];
+    let mut theirs = task(1, "Move house");
+    theirs.subtasks = vec![task(4, "Pack books"), task(5, "Book a big van")];
+
+    let mut report = merge(&[task(9, "First"), ours], &[theirs]);
+    assert_eq!(report.conflicts.len(), 1);
+    let at = report.conflicts[0].at;
+    assert_eq!(
+        at,
+        TaskIndex {
+            task: 2,
+            sub: Some(2)
+        }
+    );
+    report.resolve_all(Side::Theirs);
+    assert_eq!(report.tasks[1].subtasks[1].description, "Book a big van");
+}
+
+#[test]
+fn description_matches_differing_in_case_are_conflicts() {
+    let ours = [task(1, "buy milk")];
+    let theirs = [task(0, "Buy milk")];
+    let report = merge(&ours, &theirs);
+    assert_eq!(report.matched, 1);
+    assert_eq!(report.conflicts.len(), 1);
+}
+
+#[test]
+fn their_ids_are_kept_unless_taken() {
+    let ours = [task(1, "Buy milk"), task(2, "Call mum")];
+    let mut clashing = task(2, "Pay rent");
+    clashing.created_at = Some(77);
+    let theirs = [task(8, "Walk dog"), clashing, task(0, "Legacy")];
+
+    let report = merge(&ours, &theirs);
+    let ids: Vec<u64> = report.tasks.iter().map(|task| task.id).collect();
+    assert_eq!(ids, [1, 2, 8, 9, 10]);
+    assert_eq!(
+        descriptions(&report.tasks),
+        ["Buy milk", "Call mum", "Walk dog", "Pay rent", "Legacy"]
+    );
+}
+
+#[test]
+fn their_dependencies_follow_their_tasks() {
+    let ours = [task(1, "Buy milk"), task(2, "Bake cake")];
+    let mut clashing = task(2, "Buy eggs");
+    clashing.created_at = Some(77);
+    let mut cake = task(0, "Bake cake");
+    // Their cake waits on their eggs, which take the first ID neither copy
+    // uses, and on a task that is gone
+    cake.depends_on = vec![2, 6];
+    let mut icing = task(4, "Ice cake");
+    icing.depends_on = vec![2];
+
+    let report = merge(&ours, &[clashing, cake, icing]);
+    assert_eq!(
+        descriptions(&report.tasks),
+        ["Buy milk", "Bake cake", "Buy eggs", "Ice cake"]
+    );
+    let ids: Vec<u64> = report.tasks.iter().map(|task| task.id).collect();
+    assert_eq!(ids, [1, 2, 5, 4]);
+    assert_eq!(report.tasks[1].depends_on, [5]);
+    assert_eq!(report.tasks[3].depends_on, [5]);
+}
+
+#[test]
+fn their_copy_is_read_without_giving_out_ids() {
+    let dir = std::env::temp_dir().join(format!("rust-todo-cli-merge-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("theirs.json");
+    fs::write(
+        &path,
+        r#"[{"id": 3, "description": "Buy milk"}, {"description": "Call mum"}]"#,
+    )
+    .unwrap();
+
+    let tasks = read_tasks(path.to_str().unwrap()).unwrap();
+    let ids: Vec<u64> = tasks.iter().map(|task| task.id).collect();
+    assert_eq!(ids, [3, 0]);
+    assert!(read_tasks(dir.join("missing.json").to_str().unwrap()).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn applying_a_merge_records_it() {
+    let mut todo = TodoList::new();
+    todo.add_tasks("Buy milk".to_string()).unwrap();
+    let report = merge(todo.tasks(), &[task(0, "Call mum")]);
+    todo.apply_merge(report, "laptop.json");
+    assert_eq!(todo.len(), 2);
+    let last = todo.log().last().unwrap();
+    assert!(last.details.contains("laptop.json"), "{:?}", last);
+}
@@ -0,0 +1,154 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use rust_todo_cli::messages::{Language, lookup, render};
+
+// The `{name}` and `{name|one|other}` names a message fills in
+fn placeholders(text: &str) -> BTreeSet<&str> {
+    text.split('{')
+        .skip(1)
+        .filter_map(|rest| rest.split_once('}'))
+        .map(|(field, _)| field.split('|').next().unwrap_or_default())
+        .collect()
+}
+
+fn keys(language: Language) -> Vec<&'static str> {
+    language.catalog().iter().map(|(key, _)| *key).collect()
+}
+
+#[test]
+fn every_key_is_in_every_language() {
+    let english: BTreeSet<&str> = keys(Language::English).into_iter().collect();
+    for language in Language::ALL {
+        let keys = keys(language);
+        let unique: BTreeSet<&str> = keys.iter().copied().collect();
+        assert_eq!(unique.len(), keys.len(), "{:?} repeats a key", language);
+        let missing: Vec<_> = english.difference(&unique).collect();
+        assert!(missing.is_empty(), "{:?} lacks {:?}", language, missing);
+        let extra: Vec<_> = unique.difference(&english).collect();
+        assert!(extra.is_empty(), "{:?} has unused {:?}", language, extra);
+    }
+}
+
+#[test]
+fn translations_fill_in_the_same_placeholders() {
+    for &(key, english) in Language::English.catalog() {
+        for language in Language::ALL {
+            assert_eq!(
+                placeholders(lookup(language, key)),
+                placeholders(english),
+                "{} in {:?}",
+                key,
+                language
+            );
+        }
+    }
+}
+
+// Every key given to msg! in the sources, found without running the code
+fn used_keys(dir: &Path, keys: &mut BTreeSet<String>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            used_keys(&path, keys);
+        } else if path.extension().is_some_and(|ext| ext == "rs") {
+            let source = fs::read_to_string(&path).unwrap();
+            for call in source.split("msg!(").skip(1) {
+                if let Some(rest) = call.trim_start().strip_prefix('"')
+                    && let Some((key, _)) = rest.split_once('"')
+                {
+                    keys.insert(key.to_string());
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn every_key_in_use_has_a_message() {
+    let mut used = BTreeSet::new();
+    used_keys(
+        &Path::new(env!("CARGO_MANIFEST_DIR")).join("src"),
+        &mut used,
+    );
+    assert!(used.len() > 100, "only found {:?}", used);
+    let known = keys(Language::English);
+    let missing: Vec<_> = used
+        .iter()
+        .filter(|key| !known.contains(&key.as_str()))
+        .collect();
+    assert!(missing.is_empty(), "no message for {:?}", missing);
+}
+
+#[test]
+fn counts_pick_the_singular_or_plural() {
+    let text = "{count} {count|task|tasks} left";
+    let render_count = |count: usize| render(Language::English, text, &[("count", &count)]);
+    assert_eq!(render_count(1), "1 task left");
+    assert_eq!(render_count(0), "0 tasks left");
+    assert_eq!(render_count(3), "3 tasks left");
+
+    // Counts padded for a column still count
+    let padded = format!("{:>3}", 1);
+    assert_eq!(
+        render(Language::English, text, &[("count", &padded)]),
+        "  1 task left"
+    );
+}
+
+#[test]
+fn messages_render_in_spanish() {
+    let text = lookup(Language::Spanish, "summary.counts");
+    let args: [(&str, &dyn std::fmt::Display); 4] = [
+        ("total", &1),
+        ("todo", &1),
+        ("in_progress", &0),
+        ("done", &0),
+    ];
+    assert_eq!(
+        render(Language::Spanish, text, &args),
+        "1 tarea: 1 por hacer, 0 en curso, 0 hechas"
+    );
+    assert_eq!(
+        render(
+            Language::English,
+            lookup(Language::English, "summary.counts"),
+            &args
+        ),
+        "1 task: 1 todo, 0 in-progress, 0 done"
+    );
+}
+
+#[test]
+fn unknown_keys_and_placeholders_are_left_as_they_are() {
+    assert_eq!(lookup(Language::Spanish, "no.such.key"), "no.such.key");
+    assert_eq!(
+        render(
+            Language::English,
+            "Task {index} of {total}",
+            &[("index", &2)]
+        ),
+        "Task 2 of {total}"
+    );
+    assert_eq!(render(Language::English, "a { b", &[]), "a { b");
+}
+
+#[test]
+fn locales_map_to_bundled_languages() {
+    for (locale, expected) in [
+        ("es_ES.UTF-8", Some(Language::Spanish)),
+        ("es", Some(Language::Spanish)),
+        ("ES-mx", Some(Language::Spanish)),
+        ("en_GB.UTF-8", Some(Language::English)),
+        ("C", Some(Language::English)),
+        ("POSIX", Some(Language::English)),
+        ("fr_FR.UTF-8", None),
+        ("", None),
+    ] {
+        assert_eq!(Language::from_locale(locale), expected, "{}", locale);
+    }
+    for language in Language::ALL {
+        assert_eq!(Language::from_locale(language.code()), Some(language));
+    }
+}
@@ -0,0 +1,515 @@
+mod common;
+
+use std::fs;
+
+use common::list_of;
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::date::Date;
+use rust_todo_cli::doctor::Problem;
+use rust_todo_cli::merge;
+use rust_todo_cli::outcome::{CHART_DAYS, CommandOutcome, Duplicate, execute, query};
+use rust_todo_cli::todo::{Status, Storable, TaskIndex, TodoError, TodoList};
+
+fn command(line: &str) -> Command {
+    parse_command(line).unwrap()
+}
+
+fn run<'a>(todo: &'a mut TodoList, line: &str) -> CommandOutcome<'a> {
+    execute(todo, command(line)).unwrap()
+}
+
+// Numbers of the tasks a listing outcome holds
+fn numbers(outcome: CommandOutcome) -> Vec<usize> {
+    match outcome {
+        CommandOutcome::Tasks(tasks) => tasks.iter().map(|(index, _)| *index).collect(),
+        other => panic!("not a listing: {:?}", other),
+    }
+}
+
+fn at(task: usize, sub: Option<usize>) -> TaskIndex {
+    TaskIndex { task, sub }
+}
+
+#[test]
+fn listings_give_the_tasks_with_their_numbers() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Pay rent"]);
+    run(&mut todo, "done 2");
+
+    assert_eq!(numbers(run(&mut todo, "list todo")), [1, 3]);
+    assert_eq!(numbers(run(&mut todo, "list --last 1")), [3]);
+    assert_eq!(numbers(run(&mut todo, "next 5")), [1, 3]);
+    match run(&mut todo, "show 3") {
+        CommandOutcome::Task(3, task) => assert_eq!(task.description, "Pay rent"),
+        other => panic!("{:?}", other),
+    }
+    match run(&mut todo, "random") {
+        CommandOutcome::Picked(Some((index, _))) => assert!([1, 3].contains(&index)),
+        other => panic!("{:?}", other),
+    }
+    assert!(matches!(
+        run(&mut todo, "random done"),
+        CommandOutcome::Picked(None)
+    ));
+    assert!(matches!(run(&mut todo, "exit"), CommandOutcome::Nothing));
+}
+
+#[test]
+fn summaries_count_what_the_list_holds() {
+    let mut todo = list_of(&["Weed beds +garden", "Call mum @phone", "Pay rent"]);
+    run(&mut todo, "done 3");
+
+    match run(&mut todo, "stats") {
+        CommandOutcome::Stats { stats, streaks } => {
+            assert_eq!((stats.total, stats.completed), (3, 1));
+            assert_eq!(streaks.current, 1);
+        }
+        other => panic!("{:?}", other),
+    }
+    for (line, expected) in [("count", 3), ("count todo", 2), ("count overdue", 0)] {
+        assert!(
+            matches!(run(&mut todo, line), CommandOutcome::Count(count) if count == expected),
+            "{}",
+            line
+        );
+    }
+    match run(&mut todo, "projects") {
+        CommandOutcome::Tokens(counts) => assert_eq!(counts.get("garden"), Some(&1)),
+        other => panic!("{:?}", other),
+    }
+    match run(&mut todo, "contexts") {
+        CommandOutcome::Tokens(counts) => assert_eq!(counts.get("phone"), Some(&1)),
+        other => panic!("{:?}", other),
+    }
+    match run(&mut todo, "week") {
+        CommandOutcome::Week(summary) => {
+            assert_eq!(summary.days.len(), 7);
+            assert_eq!((summary.added, summary.completed), (3, 1));
+        }
+        other => panic!("{:?}", other),
+    }
+    match run(&mut todo, "chart") {
+        CommandOutcome::Chart(days) => {
+            assert_eq!(days.len(), CHART_DAYS);
+            assert_eq!(days.last().unwrap().completed, 1);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn due_views_and_the_agenda_go_by_date() {
+    let mut todo = list_of(&["Pay rent", "Call mum", "Book dentist", "Someday"]);
+    let today = Date::today();
+    for (index, days) in [(1, -2), (2, 0), (3, 3)] {
+        let task = todo.task_ref(index).unwrap();
+        todo.set_due(task, Some(today.add_days(days))).unwrap();
+    }
+
+    assert_eq!(numbers(run(&mut todo, "overdue")), [1]);
+    assert_eq!(numbers(run(&mut todo, "today")), [1, 2]);
+    match run(&mut todo, "agenda 7") {
+        CommandOutcome::Agenda(agenda) => {
+            let overdue: Vec<usize> = agenda.overdue.iter().map(|(i, _)| *i).collect();
+            assert_eq!(overdue, [1]);
+            assert_eq!(agenda.days[0].tasks[0].0, 2);
+            assert_eq!(agenda.days[3].tasks[0].0, 3);
+            assert_eq!(agenda.undated, 1);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn edits_give_the_task_as_they_left_it() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Pay rent"]);
+    let edited = |outcome: CommandOutcome| match outcome {
+        CommandOutcome::Edited(index, task) => (index, task.clone()),
+        other => panic!("not an edit: {:?}", other),
+    };
+
+    let (index, task) = edited(run(&mut todo, "due 1 2025-06-01"));
+    assert_eq!((index, task.due), (at(1, None), Date::new(2025, 6, 1)));
+    assert!(
+        edited(run(&mut todo, "repeat 1 weekly"))
+            .1
+            .recurrence
+            .is_some()
+    );
+    let estimate = edited(run(&mut todo, "estimate 1 1h30m")).1.estimate;
+    assert_eq!(estimate.unwrap().to_string(), "1h30m");
+    let task = edited(run(&mut todo, "wait 2 reply from Sam")).1;
+    assert_eq!(task.status, Status::Waiting);
+    assert_eq!(task.wait_reason.as_deref(), Some("reply from Sam"));
+    assert_eq!(
+        edited(run(&mut todo, "tag 1 work home")).1.tags,
+        ["work", "home"]
+    );
+    assert_eq!(edited(run(&mut todo, "untag 1 work")).1.tags, ["home"]);
+    assert!(edited(run(&mut todo, "mark 1 red")).1.color().is_some());
+    assert!(edited(run(&mut todo, "mark 1 --clear")).1.color().is_none());
+    let task = edited(run(&mut todo, "append 1 today")).1;
+    assert_eq!(task.description, "Buy milk today");
+    let task = edited(run(&mut todo, "prepend 3 Urgent:")).1;
+    assert_eq!(task.description, "Urgent: Pay rent");
+
+    assert!(matches!(
+        run(&mut todo, "sub 1 Oat milk"),
+        CommandOutcome::SubtaskAdded(index) if index == at(1, Some(1))
+    ));
+    let (index, task) = edited(run(&mut todo, "append 1.1 please"));
+    assert_eq!(
+        (index, task.description.as_str()),
+        (at(1, Some(1)), "Oat milk please")
+    );
+
+    let first = todo[0].id;
+    let (index, task) = edited(run(&mut todo, "blocks 3 on 1"));
+    assert_eq!((index, task.depends_on.clone()), (at(3, None), vec![first]));
+
+    match run(&mut todo, "snooze 1 2d") {
+        CommandOutcome::Snoozed { task, old, new } => {
+            assert_eq!(
+                (task, old),
+                (1, Some((Date::new(2025, 6, 1).unwrap(), None)))
+            );
+            assert_eq!(new, (Date::new(2025, 6, 3).unwrap(), None));
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn status_changes_say_what_followed_from_them() {
+    let mut todo = list_of(&["Pay rent", "Plan trip", "Book flights"]);
+    run(&mut todo, "blocks 2 on 3");
+    run(&mut todo, "repeat 1 weekly");
+    run(&mut todo, "due 1 2025-06-01");
+    run(&mut todo, "sub 2 Pick dates");
+
+    let CommandOutcome::StatusChanged(changes) = run(&mut todo, "done 1 2.1 2") else {
+        panic!("done changes statuses");
+    };
+    let changes: Vec<_> = changes.into_iter().map(Result::unwrap).collect();
+    assert_eq!(changes[0].at, at(1, None));
+    assert_eq!(changes[0].next, Some(4));
+    assert_eq!(
+        (changes[1].at, changes[1].parent_ready),
+        (at(2, Some(1)), true)
+    );
+    assert_eq!(changes[2].blocked_by, [3]);
+    assert!(
+        changes
+            .iter()
+            .all(|change| change.status == Status::Completed)
+    );
+    assert!(!changes[0].parent_ready && changes[0].blocked_by.is_empty());
+
+    let CommandOutcome::StatusChanged(changes) = run(&mut todo, "toggle 2") else {
+        panic!("toggle changes statuses");
+    };
+    assert_eq!(changes[0].as_ref().unwrap().status, Status::Todo);
+    let CommandOutcome::StatusChanged(changes) = run(&mut todo, "update 3 in-progress") else {
+        panic!("update changes statuses");
+    };
+    assert_eq!(changes[0].as_ref().unwrap().status, Status::InProgress);
+
+    // A number past the end fails on its own; text that matches nothing
+    // stops the command before anything changes
+    let CommandOutcome::StatusChanged(changes) = run(&mut todo, "done 3 9") else {
+        panic!("done changes statuses");
+    };
+    assert!(changes[0].is_ok());
+    assert!(matches!(changes[1], Err(TodoError::IndexOutOfBound(9))));
+    assert!(matches!(
+        execute(&mut todo, command("done 2 \"no such task\"")),
+        Err(TodoError::NoMatch(_))
+    ));
+    assert_eq!(todo[1].status, Status::Todo);
+
+    match run(&mut todo, "update all todo cancelled") {
+        CommandOutcome::UpdatedAll {
+            count,
+            skipped,
+            changes,
+        } => {
+            assert_eq!((count, skipped), (2, 0));
+            assert_eq!(changes.len(), 2);
+        }
+        other => panic!("{:?}", other),
+    }
+}
+
+#[test]
+fn adding_reports_repeats_and_rejected_descriptions() {
+    let mut todo = list_of(&["Buy milk"]);
+    let added = |outcome: CommandOutcome| match outcome {
+        CommandOutcome::Added(added) => added,
+        other => panic!("not an add: {:?}", other),
+    };
+
+    let result = added(run(&mut todo, "add buy milk."));
+    assert_eq!(result.added, [2]);
+    let repeat = Duplicate {
+        description: "buy milk.".to_string(),
+        of: 1,
+        added: true,
+    };
+    assert_eq!(result.duplicates, [repeat]);
+
+    let result = added(run(&mut todo, "add Buy milk --no-duplicates"));
+    assert!(result.added.is_empty());
+    assert!(!result.duplicates[0].added);
+
+    let long = "x".repeat(300);
+    let result = added(run(&mut todo, &format!("add Call mum; {}", long)));
+    assert_eq!(result.added, [3]);
+    assert!(matches!(
+        result.rejected[..],
+        [(2, TodoError::DescriptionTooLong { .. })]
+    ));
+    assert!(matches!(
+        execute(&mut todo, command(&format!("add {}", long))),
+        Err(TodoError::DescriptionTooLong { .. })
+    ));
+
+    let path = std::env::temp_dir().join(format!("outcome-add-{}.txt", std::process::id()));
+    fs::write(
+        &path,
+        format!("Water plants\n# not a task\n\n{}\ncall mum\n", long),
+    )
+    .unwrap();
+    let result = added(run(&mut todo, &format!("add --from {}", path.display())));
+    assert_eq!(result.added, [4, 5]);
+    assert_eq!(result.duplicates[0].of, 3);
+    assert_eq!(result.rejected[0].0, 4);
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn removing_and_clearing_hand_back_the_tasks() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Pay rent", "Call mum"]);
+    run(&mut todo, "sub 2 Find number");
+
+    match run(&mut todo, "remove 2") {
+        CommandOutcome::Removed(task) => {
+            assert_eq!(task.description, "Call mum");
+            assert_eq!(task.subtasks.len(), 1);
+        }
+        other => panic!("{:?}", other),
+    }
+    match run(&mut todo, "dedupe --dry-run") {
+        CommandOutcome::Deduplicated { groups, changes } => {
+            assert!(groups.is_empty());
+            assert!(changes.is_empty());
+        }
+        other => panic!("{:?}", other),
+    }
+
+    run(&mut todo, "done 1 3");
+    assert_eq!(numbers(run(&mut todo, "clear --dry-run")), [1, 3]);
+    assert_eq!(todo.len(), 3);
+    match run(&mut todo, "clear") {
+        CommandOutcome::Cleared { tasks, changes } => {
+            let cleared: Vec<&str> = tasks.iter().map(|task| task.description.as_str()).collect();
+            assert_eq!(cleared, ["Buy milk", "Call mum"]);
+            assert_eq!(changes.len(), 2);
+        }
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(todo.len(), 1);
+}
+
+#[test]
+fn doctor_history_templates_and_timers_report_their_results() {
+    let mut todo = TodoList::load("tests/fixtures/broken.json").unwrap();
+    let CommandOutcome::Checked { fixed, left } = run(&mut todo, "doctor") else {
+        panic!("doctor checks");
+    };
+    assert!(fixed.is_empty());
+    let found = left.len();
+    let CommandOutcome::Checked { fixed, left } = run(&mut todo, "doctor --fix") else {
+        panic!("doctor checks");
+    };
+    assert_eq!(fixed.len() + left.len(), found);
+    assert!(
+        left.iter()
+            .all(|finding| finding.problem == Problem::CompletedBeforeCreated)
+    );
+
+    let mut todo = list_of(&["Pack", "Book hotel"]);
+    match run(&mut todo, "history 1") {
+        CommandOutcome::History(entries) => assert_eq!(entries.len(), 1),
+        other => panic!("{:?}", other),
+    }
+    assert!(matches!(
+        run(&mut todo, "history clear --yes"),
+        CommandOutcome::HistoryCleared(2)
+    ));
+
+    assert!(matches!(
+        run(&mut todo, "template save trip 1 2"),
+        CommandOutcome::TemplateSaved { replaced: false }
+    ));
+    assert!(matches!(
+        run(&mut todo, "template save trip 1"),
+        CommandOutcome::TemplateSaved { replaced: true }
+    ));
+    match run(&mut todo, "template list") {
+        CommandOutcome::Templates(templates) => assert_eq!(templates["trip"], ["Pack"]),
+        other => panic!("{:?}", other),
+    }
+    assert!(matches!(
+        run(&mut todo, "template apply trip"),
+        CommandOutcome::TemplateApplied(1)
+    ));
+    match run(&mut todo, "template delete trip") {
+        CommandOutcome::TemplateDeleted(descriptions) => assert_eq!(descriptions, ["Pack"]),
+        other => panic!("{:?}", other),
+    }
+
+    assert!(matches!(
+        run(&mut todo, "track 1"),
+        CommandOutcome::TimerStarted {
+            task: 1,
+            stopped: None
+        }
+    ));
+    assert!(matches!(
+        run(&mut todo, "track 2"),
+        CommandOutcome::TimerStarted {
+            task: 2,
+            stopped: Some(1)
+        }
+    ));
+    assert!(matches!(
+        run(&mut todo, "stop"),
+        CommandOutcome::TimerStopped(Some((2, _)))
+    ));
+    assert!(matches!(
+        run(&mut todo, "stop"),
+        CommandOutcome::TimerStopped(None)
+    ));
+
+    match run(&mut todo, "escalate 30") {
+        CommandOutcome::Escalated { days, raised } => {
+            assert_eq!(days, 30);
+            assert!(raised.is_empty());
+        }
+        other => panic!("{:?}", other),
+    }
+    // How long is too long otherwise comes from the CLI's options
+    assert!(matches!(
+        execute(&mut todo, command("escalate")),
+        Err(TodoError::CliOnly("escalate"))
+    ));
+}
+
+#[test]
+fn imports_say_what_came_in() {
+    let path = std::env::temp_dir().join(format!("outcome-import-{}.json", std::process::id()));
+    fs::write(
+        &path,
+        r#"[{"number": 12, "title": "Fix login", "state": "OPEN"}]"#,
+    )
+    .unwrap();
+    let mut todo = list_of(&["Buy milk"]);
+    match run(&mut todo, &format!("import github {}", path.display())) {
+        CommandOutcome::Imported { summary, changes } => {
+            assert_eq!(summary.created, 1);
+            assert_eq!(changes.len(), 1);
+        }
+        other => panic!("{:?}", other),
+    }
+    fs::remove_file(&path).unwrap();
+    assert!(matches!(
+        execute(
+            &mut todo,
+            command(&format!("import github {}", path.display()))
+        ),
+        Err(TodoError::FileError(_))
+    ));
+}
+
+// `merge` and `sync` settle the conflicts, then hand the merge over
+#[test]
+fn settled_merges_are_taken_in_whole() {
+    let mut todo = list_of(&["Buy milk"]);
+    let mut theirs = todo.clone();
+    theirs.add_tasks("Call mum".to_string()).unwrap();
+    let report = merge::merge(todo.tasks(), theirs.tasks());
+    assert!(matches!(
+        query(
+            &todo,
+            Command::TakeMerge(Box::new(report.clone()), "phone".to_string())
+        ),
+        Err(TodoError::ReadOnly("merge"))
+    ));
+    match execute(
+        &mut todo,
+        Command::TakeMerge(Box::new(report), "phone".to_string()),
+    )
+    .unwrap()
+    {
+        CommandOutcome::Merged(changes) => assert_eq!(changes.len(), 1),
+        other => panic!("{:?}", other),
+    }
+    assert_eq!(todo.len(), 2);
+    assert_eq!(todo.log().last().unwrap().action, "merge");
+}
+
+#[test]
+fn commands_needing_the_cli_are_turned_down_untouched() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    let before = todo.tasks().to_vec();
+    for line in [
+        "help",
+        "version",
+        "completions bash",
+        "more",
+        "open 1",
+        "copy 1",
+        "copy list",
+        "pomodoro 1",
+        "review",
+        "report md standup.md",
+        "add --stdin",
+        "archive",
+        "list archived",
+        "trash",
+        "trash empty --yes",
+        "restore 1",
+        "run setup.todo",
+        "save",
+        "reload",
+        "merge backup.json",
+        "sync",
+    ] {
+        let command = command(line);
+        let name = command.name();
+        match execute(&mut todo, command) {
+            Err(TodoError::CliOnly(command)) => assert_eq!(command, name, "{}", line),
+            other => panic!("{}: {:?}", line, other),
+        }
+    }
+    assert_eq!(todo.tasks(), before);
+}
+
+#[test]
+fn queries_only_read_and_picks_need_a_terminal() {
+    let mut todo = list_of(&["Buy milk"]);
+    assert_eq!(numbers(query(&todo, command("list")).unwrap()), [1]);
+    for line in ["done 1", "add Call mum", "dedupe --dry-run", "doctor --fix"] {
+        let command = command(line);
+        let name = command.name();
+        assert!(
+            matches!(query(&todo, command), Err(TodoError::ReadOnly(command)) if command == name),
+            "{}",
+            line
+        );
+    }
+    assert!(matches!(
+        execute(&mut todo, command("done --pick")),
+        Err(TodoError::NotInteractive)
+    ));
+    assert_eq!(TodoError::CliOnly("save").code(), "CliOnly");
+}
@@ -0,0 +1,382 @@
+// Snapshots of what a session prints, in the default look and in plain mode.
+// The default look is what a terminal gets, so CLICOLOR_FORCE stands in for
+// one here; --no-color keeps the ANSI codes out of the snapshot.
+
+mod common;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Output;
+
+use common::{app, scratch_dir, typed};
+use rust_todo_cli::width::display_width;
+
+const SCRIPT: &[&str] = &[
+    "add Buy milk",
+    "add Call mum +home",
+    "add Pay rent",
+    "done 1",
+    "update 2 in-progress",
+    "list",
+    "remove 3 --yes",
+    "done 9",
+    "exit",
+];
+
+// What the app printed before plain mode and everything after it, for a
+// script whose messages are still the same. The banner has since gained
+// the version, which is the one change made on purpose.
+const BASELINE_SCRIPT: &[&str] = &[
+    "add Buy milk",
+    "add Call mum +home",
+    "update 2 in-progress",
+    "",
+    "update 1 completed",
+    "exit",
+];
+
+fn run(name: &str, env: &[(&str, &str)], args: &[&str]) -> Output {
+    run_script(name, SCRIPT, env, args)
+}
+
+// The script typed into a session started with the banner, with `env` set
+// on top, next to an empty task file
+fn run_script(name: &str, script: &[&str], env: &[(&str, &str)], args: &[&str]) -> Output {
+    let dir = scratch_dir(name);
+    fs::write(dir.join("tasks.json"), "[]").unwrap();
+    let output = typed(
+        app().envs(env.iter().copied()).current_dir(&dir).args(args),
+        script,
+    );
+    fs::remove_dir_all(dir).unwrap();
+    output
+}
+
+fn snapshot(name: &str) -> String {
+    let path = format!(
+        "{}/tests/fixtures/snapshots/{}.txt",
+        env!("CARGO_MANIFEST_DIR"),
+        name
+    );
+    fs::read_to_string(path)
+        .unwrap()
+        .replace("{version}", env!("CARGO_PKG_VERSION"))
+}
+
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+#[test]
+fn the_default_look_is_unchanged_byte_for_byte() {
+    let output = run_script("baseline", BASELINE_SCRIPT, &[("CLICOLOR_FORCE", "1")], &[]);
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    let baseline = snapshot("baseline_session").replacen(
+        "Welcome to the Todo CLI!",
+        &format!("Welcome to the Todo CLI {}!", env!("CARGO_PKG_VERSION")),
+        1,
+    );
+    assert_eq!(text(&output.stdout), baseline);
+    assert_eq!(text(&output.stderr), "");
+}
+
+#[test]
+fn the_default_look_matches_its_snapshot() {
+    let output = run("default", &[("CLICOLOR_FORCE", "1")], &["--no-color"]);
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert_eq!(text(&output.stdout), snapshot("default_session"));
+    assert_eq!(
+        text(&output.stderr),
+        "Error: No task exists at that index 9\n"
+    );
+}
+
+#[test]
+fn piped_output_is_plain() {
+    let output = run("piped", &[], &[]);
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert_eq!(text(&output.stdout), snapshot("plain_session"));
+    assert!(text(&output.stdout).is_ascii());
+}
+
+#[test]
+fn the_flag_and_no_color_turn_a_terminal_plain() {
+    let plain = snapshot("plain_session");
+    let flagged = run("flag", &[("CLICOLOR_FORCE", "1")], &["--plain"]);
+    assert_eq!(text(&flagged.stdout), plain);
+    let no_color = run(
+        "no-color",
+        &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")],
+        &[],
+    );
+    assert_eq!(text(&no_color.stdout), plain);
+    // An empty NO_COLOR and CLICOLOR_FORCE=0 count as unset
+    let unset = run(
+        "unset",
+        &[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "")],
+        &["--no-color"],
+    );
+    assert_eq!(text(&unset.stdout), snapshot("default_session"));
+    let zero = run("zero", &[("CLICOLOR_FORCE", "0")], &[]);
+    assert_eq!(text(&zero.stdout), plain);
+}
+
+#[test]
+fn both_looks_say_the_same_things() {
+    let default = text(&run("same-default", &[("CLICOLOR_FORCE", "1")], &["--no-color"]).stdout);
+    let plain = text(&run("same-plain", &[], &[]).stdout);
+    // Messages differ only in their markers
+    let messages = |text: &str| -> Vec<String> {
+        text.lines()
+            .filter_map(|line| line.strip_prefix("> "))
+            .filter_map(|line| line.split_once(' ').map(|(_, rest)| rest.to_string()))
+            .collect()
+    };
+    assert_eq!(messages(&default), messages(&plain));
+    assert!(messages(&plain).contains(&"Task 1 marked done".to_string()));
+    // Rules are decoration, left out of plain mode along with the emoji
+    let rule = |line: &str| !line.is_empty() && line.chars().all(|c| "-─".contains(c));
+    assert_eq!(default.lines().filter(|line| rule(line)).count(), 3);
+    assert!(!plain.lines().any(rule));
+}
+
+#[test]
+fn escape_codes_only_reach_a_terminal_with_color_on() {
+    let piped = run("escapes-piped", &[], &[]);
+    assert!(!piped.stdout.contains(&0x1b), "{}", text(&piped.stdout));
+    assert!(!piped.stderr.contains(&0x1b));
+    let no_color = run("escapes-off", &[("CLICOLOR_FORCE", "1")], &["--no-color"]);
+    assert!(!no_color.stdout.contains(&0x1b));
+
+    // A terminal gets each row in the color of its status, whole
+    let colored = text(&run("escapes-on", &[("CLICOLOR_FORCE", "1")], &[]).stdout);
+    let row = |description: &str| {
+        colored
+            .lines()
+            .find(|line| line.contains(description))
+            .unwrap()
+            .to_string()
+    };
+    for (description, status) in [("Buy milk", "DONE"), ("Call mum", "IN-PROGRESS")] {
+        let row = row(description);
+        assert!(row.starts_with("\x1b["), "{:?}", row);
+        assert!(row.ends_with("\x1b[0m"), "{:?}", row);
+        assert!(row.contains(status), "{:?}", row);
+    }
+    assert_ne!(
+        row("Buy milk").split('m').next(),
+        row("Call mum").split('m').next()
+    );
+}
+
+// A one-shot command in `dir` at the given width, piped and so plain
+fn piped(dir: &std::path::Path, columns: &str, args: &[&str]) -> Output {
+    app()
+        .env("COLUMNS", columns)
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+// The same in the terminal look, without color
+fn one_shot(dir: &std::path::Path, columns: &str, args: &[&str]) -> Output {
+    app()
+        .env("COLUMNS", columns)
+        .env("CLICOLOR_FORCE", "1")
+        .current_dir(dir)
+        .arg("--no-color")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn wide_and_joined_characters_keep_the_columns_in_line() {
+    let dir = scratch_dir("columns");
+    let descriptions = [
+        "Buy milk",
+        "牛乳を買う",
+        "Family 👨‍👩‍👧 dinner",
+        "🇯🇵 trip ✈️ 東京",
+        "नमस्ते दुनिया",
+    ];
+    for (n, description) in descriptions.iter().enumerate() {
+        assert!(one_shot(&dir, "80", &["add", description]).status.success());
+        if n % 2 == 1 {
+            let number = (n + 1).to_string();
+            assert!(one_shot(&dir, "80", &["done", &number]).status.success());
+        }
+    }
+    let listed = text(&one_shot(&dir, "80", &["list"]).stdout);
+    // Where the description starts on each row, in cells
+    let starts: Vec<usize> = descriptions
+        .iter()
+        .map(|description| {
+            let line = listed
+                .lines()
+                .find(|line| line.ends_with(description))
+                .unwrap_or_else(|| panic!("{:?} in {}", description, listed));
+            display_width(&line[..line.len() - description.len()])
+        })
+        .collect();
+    assert!(
+        starts.iter().all(|&start| start == starts[0]),
+        "{:?}",
+        starts
+    );
+    let header = listed.lines().find(|line| line.starts_with('#')).unwrap();
+    assert_eq!(header.find("DESCRIPTION"), Some(starts[0]));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// A long description, one in Japanese, one with emoji and a done one
+fn table_dir(name: &str) -> PathBuf {
+    let dir = scratch_dir(name);
+    for description in [
+        "Write the quarterly report for the board including every appendix and the budget",
+        "牛乳とパンと卵を買ってから郵便局に寄って荷物を出す",
+        "Plan 🎉 party with 👨‍👩‍👧 and 🇯🇵 snacks",
+        "Short",
+    ] {
+        assert!(piped(&dir, "80", &["add", description]).status.success());
+    }
+    assert!(piped(&dir, "80", &["done", "4"]).status.success());
+    dir
+}
+
+#[test]
+fn tables_of_long_wide_and_emoji_text_match_their_snapshots() {
+    let dir = table_dir("table");
+    let listed = text(&one_shot(&dir, "40", &["list"]).stdout);
+    assert_eq!(listed, snapshot("table_default"));
+    // Rows are cut to the width, on whole characters
+    for line in listed.lines() {
+        assert!(display_width(line) <= 40, "{:?}", line);
+    }
+    let wrapped = text(&one_shot(&dir, "40", &["--wrap", "list"]).stdout);
+    assert_eq!(wrapped, snapshot("table_wrapped"));
+    // Plain mode keeps to a line per task, whatever the width
+    let plain = text(&piped(&dir, "40", &["list"]).stdout);
+    assert_eq!(plain, snapshot("table_plain"));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// The progress line under a listing of `total` tasks with `done` of them done
+fn progress(done: usize, total: usize) -> (String, String) {
+    let dir = scratch_dir(&format!("progress-{}-{}", done, total));
+    for n in 1..=total {
+        assert!(
+            piped(&dir, "80", &["add", &format!("Task {}", n)])
+                .status
+                .success()
+        );
+    }
+    for n in 1..=done {
+        assert!(
+            piped(&dir, "80", &["done", &n.to_string()])
+                .status
+                .success()
+        );
+    }
+    let bar = |listed: Output| {
+        text(&listed.stdout)
+            .lines()
+            .find(|line| line.ends_with("%)"))
+            .unwrap_or_else(|| panic!("{}", text(&listed.stdout)))
+            .to_string()
+    };
+    let plain = bar(piped(&dir, "80", &["list"]));
+    let default = bar(one_shot(&dir, "80", &["list"]));
+    fs::remove_dir_all(dir).unwrap();
+    (plain, default)
+}
+
+#[test]
+fn progress_bars_round_to_the_nearest_cell() {
+    // A third of 15 cells is 5, and 33.3% shows as 33%
+    let (plain, default) = progress(1, 3);
+    assert_eq!(plain, "[#####----------] 1/3 done (33%)");
+    assert_eq!(
+        default,
+        format!("[{}{}] 1/3 done (33%)", "█".repeat(5), "░".repeat(10))
+    );
+    // Two thirds is 10 cells and rounds up to 67%
+    let (plain, _) = progress(2, 3);
+    assert_eq!(plain, "[##########-----] 2/3 done (67%)");
+    // Half of 15 cells rounds up to 8
+    let (plain, _) = progress(1, 2);
+    assert_eq!(plain, "[########-------] 1/2 done (50%)");
+    // Only every task done fills the bar
+    let (plain, default) = progress(3, 3);
+    assert_eq!(plain, "[###############] 3/3 done (100%)");
+    assert_eq!(default, format!("[{}] 3/3 done (100%)", "█".repeat(15)));
+    let (plain, _) = progress(0, 3);
+    assert_eq!(plain, "[---------------] 0/3 done (0%)");
+}
+
+// What `line` shows from cell `start` on
+fn after_cells(line: &str, start: usize) -> &str {
+    let mut width = 0;
+    for (at, c) in line.char_indices() {
+        if width >= start {
+            return &line[at..];
+        }
+        width += display_width(&c.to_string());
+    }
+    ""
+}
+
+#[test]
+fn terminals_under_thirty_columns_keep_the_description_header_wide() {
+    let dir = table_dir("narrow");
+    for columns in ["29", "20", "5", "1"] {
+        let listed = one_shot(&dir, columns, &["list"]);
+        assert!(listed.status.success(), "{}", text(&listed.stderr));
+        assert!(listed.stderr.is_empty(), "{}", text(&listed.stderr));
+        let listed = text(&listed.stdout);
+        // The description column shrinks to its header and no further, so
+        // every row is as wide as the header line at most
+        let header = listed.lines().find(|line| line.starts_with('#')).unwrap();
+        let start = header.find("DESCRIPTION").unwrap();
+        assert_eq!(display_width(header), start + "DESCRIPTION".len());
+        let rows: Vec<&str> = listed
+            .lines()
+            .filter(|line| line.starts_with(|c: char| c.is_ascii_digit()))
+            .collect();
+        assert_eq!(rows.len(), 4, "{}", listed);
+        for row in &rows {
+            assert!(display_width(row) <= display_width(header), "{:?}", row);
+        }
+        assert!(rows[0].ends_with('…'), "{:?}", rows[0]);
+
+        // Wrapping there loses nothing, on lines no wider than the header
+        let wrapped = one_shot(&dir, columns, &["--wrap", "list"]);
+        assert!(wrapped.status.success(), "{}", text(&wrapped.stderr));
+        let wrapped = text(&wrapped.stdout);
+        let table: Vec<&str> = wrapped
+            .lines()
+            .skip_while(|line| !line.starts_with('#'))
+            .skip(1)
+            .take_while(|line| !line.starts_with('─'))
+            .collect();
+        for line in &table {
+            assert!(display_width(line) <= display_width(header), "{:?}", line);
+        }
+        let kept: String = table
+            .iter()
+            .map(|line| after_cells(line, start))
+            .collect::<String>()
+            .split_whitespace()
+            .collect();
+        assert!(kept.contains("牛乳とパンと卵を買ってから郵便局に寄って荷物を出す"));
+        assert!(kept.contains("Plan🎉partywith👨‍👩‍👧and🇯🇵snacks"), "{}", kept);
+        assert!(kept.starts_with("Writethequarterlyreport"), "{}", kept);
+
+        // Plain mode never cuts at all
+        let plain = piped(&dir, columns, &["list"]);
+        assert!(plain.status.success());
+        assert!(text(&plain.stdout).contains("including every appendix and the budget"));
+    }
+    fs::remove_dir_all(dir).unwrap();
+}
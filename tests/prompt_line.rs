@@ -0,0 +1,74 @@
+use rust_todo_cli::prompt_line::{DEFAULT_PROMPT, PromptStatus, render, unknown_placeholders};
+use rust_todo_cli::todo::{Status, TodoList};
+
+fn status(dirty: bool) -> PromptStatus {
+    PromptStatus {
+        list: "work".to_string(),
+        open: 4,
+        done: 7,
+        total: 12,
+        dirty,
+    }
+}
+
+#[test]
+fn placeholders_show_the_state_of_the_list() {
+    let template = "{list} [{open}/{total}] {dirty}> ";
+    assert_eq!(render(template, &status(true)), "work [4/12] *> ");
+    assert_eq!(render(template, &status(false)), "work [4/12] > ");
+    assert_eq!(render("{done} done> ", &status(false)), "7 done> ");
+}
+
+#[test]
+fn the_default_prompt_stays_as_it_was() {
+    assert_eq!(render(DEFAULT_PROMPT, &status(true)), "> ");
+}
+
+#[test]
+fn an_empty_template_gives_an_empty_prompt() {
+    assert_eq!(render("", &status(true)), "");
+}
+
+#[test]
+fn unknown_placeholders_are_shown_as_written() {
+    assert_eq!(render("{user}@{list}> ", &status(false)), "{user}@work> ");
+    assert_eq!(render("{} {open", &status(false)), "{} {open");
+    assert_eq!(
+        unknown_placeholders("{user}@{list} {host}> "),
+        ["user", "host"]
+    );
+    assert!(unknown_placeholders("{list} {{user}} {").is_empty());
+}
+
+#[test]
+fn doubled_braces_are_literal() {
+    assert_eq!(render("{{{open}}}> ", &status(false)), "{4}> ");
+    assert_eq!(render("{{list}}> ", &status(false)), "{list}> ");
+    assert_eq!(render("}> ", &status(false)), "}> ");
+}
+
+#[test]
+fn status_counts_open_tasks_and_names_the_list_after_its_file() {
+    let mut todo = TodoList::new();
+    for description in ["Buy milk", "Call mum", "Book dentist", "Paint shed"] {
+        todo.add_tasks(description.to_string()).unwrap();
+    }
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::InProgress, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(3).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(4).unwrap(), Status::Cancelled, false)
+        .unwrap();
+
+    let status = PromptStatus::new(&todo, "lists/work.json", true);
+    assert_eq!(
+        status,
+        PromptStatus {
+            list: "work".to_string(),
+            open: 2,
+            done: 1,
+            total: 4,
+            dirty: true,
+        }
+    );
+}
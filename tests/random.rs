@@ -0,0 +1,41 @@
+use rust_todo_cli::random::{Rng, XorShift};
+
+#[test]
+fn the_same_seed_gives_the_same_numbers() {
+    let mut one = XorShift::new(42);
+    let mut two = XorShift::new(42);
+    let first: Vec<u64> = (0..5).map(|_| one.next_u64()).collect();
+    let second: Vec<u64> = (0..5).map(|_| two.next_u64()).collect();
+    assert_eq!(first, second);
+    assert_ne!(
+        first,
+        (0..5)
+            .map(|_| XorShift::new(43).next_u64())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn a_zero_seed_still_varies() {
+    let mut rng = XorShift::new(0);
+    let numbers: Vec<u64> = (0..3).map(|_| rng.next_u64()).collect();
+    assert!(numbers.iter().all(|number| *number != 0), "{:?}", numbers);
+    assert_ne!(numbers[0], numbers[1]);
+}
+
+#[test]
+fn picks_stay_below_the_bound_and_reach_every_value() {
+    let mut rng = XorShift::new(7);
+    let mut seen = [0; 6];
+    for _ in 0..6000 {
+        let pick = rng.below(6);
+        assert!(pick < 6);
+        seen[pick] += 1;
+    }
+    // Each of six values comes up about a thousand times
+    assert!(
+        seen.iter().all(|count| (800..1200).contains(count)),
+        "{:?}",
+        seen
+    );
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use common::list_of;
+use rust_todo_cli::date::{Date, Time};
+use rust_todo_cli::todo::{REMINDER_LIMIT, Status, TodoList};
+
+// A Tuesday
+fn today() -> Date {
+    Date::new(2025, 7, 1).unwrap()
+}
+
+// Tasks named after their due dates, `days` from today
+fn due_in(days: &[i64]) -> TodoList {
+    let names: Vec<String> = days.iter().map(|days| format!("In {}", days)).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let mut todo = list_of(&names);
+    for (i, &days) in days.iter().enumerate() {
+        let task = todo.task_ref(i + 1).unwrap();
+        todo.set_due(task, Some(today().add_days(days))).unwrap();
+    }
+    todo
+}
+
+fn top(todo: &TodoList) -> Vec<usize> {
+    let reminders = todo.reminders(today());
+    reminders.top.iter().map(|(index, _)| *index).collect()
+}
+
+#[test]
+fn a_clean_list_has_nothing_to_remind_of() {
+    assert!(TodoList::new().reminders(today()).is_empty());
+    let mut todo = due_in(&[1, 30]);
+    todo.add_tasks("No date".to_string()).unwrap();
+    let reminders = todo.reminders(today());
+    assert!(reminders.is_empty());
+    assert!(reminders.top.is_empty());
+}
+
+#[test]
+fn reminders_count_overdue_and_due_today_apart() {
+    let todo = due_in(&[0, -1, 1, -10, 0]);
+    let reminders = todo.reminders(today());
+    assert_eq!((reminders.overdue, reminders.due_today), (2, 2));
+    assert!(!reminders.is_empty());
+    // Most overdue first, ties in list order
+    assert_eq!(top(&todo), [4, 2, 1]);
+}
+
+#[test]
+fn closed_tasks_are_left_out() {
+    let mut todo = due_in(&[-3, -2, 0]);
+    for (index, status) in [(1, Status::Completed), (3, Status::Cancelled)] {
+        todo.update_task_status(todo.task_ref(index).unwrap(), status, false)
+            .unwrap();
+    }
+    let reminders = todo.reminders(today());
+    assert_eq!((reminders.overdue, reminders.due_today), (1, 0));
+    assert_eq!(top(&todo), [2]);
+}
+
+#[test]
+fn only_the_most_pressing_few_are_listed() {
+    let todo = due_in(&[0, -1, -2, -3, -4, 0]);
+    let reminders = todo.reminders(today());
+    assert_eq!((reminders.overdue, reminders.due_today), (4, 2));
+    assert_eq!(reminders.top.len(), REMINDER_LIMIT);
+    assert_eq!(top(&todo), [5, 4, 3]);
+}
+
+// Whatever the time, a task due today is due today, and earlier times of
+// the same day come first
+#[test]
+fn times_order_tasks_due_the_same_day() {
+    let mut todo = due_in(&[0, 0, 0]);
+    for (index, hour) in [(1, 17), (2, 8)] {
+        let task = todo.task_ref(index).unwrap();
+        let time = Time::new(hour, 0);
+        todo.set_due_at(task, Some(today()), time).unwrap();
+    }
+    let reminders = todo.reminders(today());
+    assert_eq!((reminders.overdue, reminders.due_today), (0, 3));
+    assert_eq!(top(&todo), [2, 1, 3]);
+}
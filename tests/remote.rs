@@ -0,0 +1,414 @@
+mod common;
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use common::list_of;
+use rust_todo_cli::remote::{Expected, Fetched, HttpRemote, MAX_BODY, RemoteStore, pull, push};
+use rust_todo_cli::todo::{Status, TodoError};
+
+// A remote kept in memory, numbering its versions like a server would
+#[derive(Default)]
+struct MockRemote {
+    body: Option<String>,
+    version: u32,
+    // Simulates the network being down
+    offline: bool,
+}
+
+impl MockRemote {
+    fn holding(body: &str) -> Self {
+        MockRemote {
+            body: Some(body.to_string()),
+            version: 1,
+            offline: false,
+        }
+    }
+}
+
+impl RemoteStore for MockRemote {
+    fn get(&mut self) -> Result<Option<Fetched>, TodoError> {
+        if self.offline {
+            return Err(TodoError::SyncError("could not reach mock".to_string()));
+        }
+        Ok(self.body.clone().map(|body| Fetched {
+            body,
+            version: Some(self.version.to_string()),
+        }))
+    }
+
+    fn put(&mut self, body: &str, expected: &Expected) -> Result<Option<String>, TodoError> {
+        if self.offline {
+            return Err(TodoError::SyncError("could not reach mock".to_string()));
+        }
+        let holds = match expected {
+            Expected::Version(version) => {
+                self.body.is_some() && *version == self.version.to_string()
+            }
+            Expected::Nothing => self.body.is_none(),
+            Expected::Any => true,
+        };
+        if !holds {
+            return Err(TodoError::SyncError("remote copy changed".to_string()));
+        }
+        self.body = Some(body.to_string());
+        self.version += 1;
+        Ok(Some(self.version.to_string()))
+    }
+}
+
+#[test]
+fn pulling_merges_the_remote_copy() {
+    let todo = list_of(&["Buy milk", "Call mum"]);
+    let created = todo.get_task(1).unwrap().created_at;
+    let mut remote_list = todo.clone();
+    remote_list
+        .update_task_status(remote_list.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    let mut remote = MockRemote::holding(&serde_json::to_string(remote_list.tasks()).unwrap());
+
+    let pulled = pull(&mut remote, todo.tasks()).unwrap();
+    assert_eq!(pulled.expected, Some(Expected::Version("1".to_string())));
+    assert_eq!(pulled.report.matched, 2);
+    assert_eq!(pulled.report.tasks[0].status, Status::Completed);
+    assert_eq!(pulled.report.tasks[0].created_at, created);
+}
+
+#[test]
+fn pulling_from_an_empty_remote_keeps_everything() {
+    let mut remote = MockRemote::default();
+    let todo = list_of(&["Buy milk"]);
+    let pulled = pull(&mut remote, todo.tasks()).unwrap();
+    assert_eq!(pulled.expected, Some(Expected::Nothing));
+    assert_eq!(pulled.report.tasks, todo.tasks());
+}
+
+#[test]
+fn invalid_remote_json_is_a_sync_error() {
+    let todo = list_of(&["Buy milk"]);
+    for body in ["<html>Bad gateway</html>", "", r#"{"tasks": 3}"#] {
+        let mut remote = MockRemote::holding(body);
+        match pull(&mut remote, todo.tasks()) {
+            Err(TodoError::SyncError(message)) => {
+                assert!(message.contains("not a task list"), "{}", message)
+            }
+            other => panic!(
+                "{:?} pulled as {:?}",
+                body,
+                other.map(|pulled| pulled.report)
+            ),
+        }
+    }
+}
+
+#[test]
+fn network_failures_are_sync_errors() {
+    let mut remote = MockRemote {
+        offline: true,
+        ..MockRemote::default()
+    };
+    let todo = list_of(&["Buy milk"]);
+    assert!(matches!(
+        pull(&mut remote, todo.tasks()),
+        Err(TodoError::SyncError(_))
+    ));
+    assert!(matches!(
+        push(&mut remote, &todo, &Expected::Any),
+        Err(TodoError::SyncError(_))
+    ));
+}
+
+#[test]
+fn pushing_sends_the_list_as_saved() {
+    let mut remote = MockRemote::default();
+    let todo = list_of(&["Buy milk", "Call mum"]);
+    assert_eq!(
+        push(&mut remote, &todo, &Expected::Nothing)
+            .unwrap()
+            .as_deref(),
+        Some("1")
+    );
+
+    // What was sent pulls back as the same tasks
+    let pulled = pull(&mut remote, &[]).unwrap();
+    assert_eq!(pulled.report.tasks, todo.tasks());
+}
+
+#[test]
+fn pushing_over_a_newer_remote_copy_fails() {
+    let mut remote = MockRemote::default();
+    let mut todo = list_of(&["Buy milk"]);
+    push(&mut remote, &todo, &Expected::Nothing).unwrap();
+    let pulled = pull(&mut remote, todo.tasks()).unwrap();
+
+    // Someone else pushes in between
+    let other = list_of(&["Buy milk", "Walk dog"]);
+    push(&mut remote, &other, &Expected::Any).unwrap();
+
+    todo.apply_merge(pulled.report, "mock");
+    assert!(matches!(
+        push(&mut remote, &todo, &pulled.expected.unwrap()),
+        Err(TodoError::SyncError(_))
+    ));
+    assert!(remote.body.unwrap().contains("Walk dog"));
+}
+
+#[test]
+fn only_a_forced_push_replaces_a_copy_never_seen() {
+    let mut remote = MockRemote::holding("[]");
+    let todo = list_of(&["Buy milk"]);
+    // Pushed from elsewhere while this side thought there was none
+    assert!(matches!(
+        push(&mut remote, &todo, &Expected::Nothing),
+        Err(TodoError::SyncError(_))
+    ));
+    assert_eq!(remote.body.as_deref(), Some("[]"));
+
+    push(&mut remote, &todo, &Expected::Any).unwrap();
+    assert!(remote.body.unwrap().contains("Buy milk"));
+}
+
+#[test]
+fn sync_pulls_then_pushes_the_merged_list() {
+    // Pushed from another machine, earlier on
+    let mut remote = MockRemote::holding(
+        r#"[{"id": 1, "description": "Buy milk", "status": "Todo", "created_at": 1700000000}]"#,
+    );
+    let mut desktop = list_of(&["Call mum"]);
+    let pulled = pull(&mut remote, desktop.tasks()).unwrap();
+    desktop.apply_merge(pulled.report, "mock");
+    push(&mut remote, &desktop, &pulled.expected.unwrap()).unwrap();
+
+    let body = remote.body.unwrap();
+    assert!(
+        body.contains("Buy milk") && body.contains("Call mum"),
+        "{}",
+        body
+    );
+}
+
+#[test]
+fn only_http_and_https_urls_are_accepted() {
+    assert!(HttpRemote::new("http://example.com/tasks.json", None).is_ok());
+    assert!(HttpRemote::new("HTTPS://example.com:8443", Some("secret")).is_ok());
+    for url in [
+        "ftp://example.com/tasks.json",
+        "example.com/tasks.json",
+        "http://example.com:port/",
+        "https:///tasks.json",
+    ] {
+        assert!(
+            matches!(HttpRemote::new(url, None), Err(TodoError::SyncError(_))),
+            "{}",
+            url
+        );
+    }
+}
+
+#[test]
+fn a_token_only_goes_unencrypted_to_this_machine() {
+    for url in [
+        "http://example.com/tasks.json",
+        "http://192.168.1.5:8080/",
+        "HTTP://nas.local",
+    ] {
+        match HttpRemote::new(url, Some("secret")) {
+            Err(TodoError::SyncError(message)) => {
+                assert!(message.contains("https://"), "{}", message)
+            }
+            other => panic!("{} gave {:?}", url, other),
+        }
+    }
+    for url in [
+        "http://localhost:8080/tasks.json",
+        "http://127.0.0.1/",
+        "http://[::1]:8080/",
+        "https://example.com/tasks.json",
+    ] {
+        assert!(HttpRemote::new(url, Some("secret")).is_ok(), "{}", url);
+    }
+}
+
+#[test]
+fn answers_past_the_size_limit_are_turned_down() {
+    // A length no one could hold in memory, over a few bytes
+    let (url, server) =
+        serve_once("HTTP/1.1 200 OK\r\nContent-Length: 18446744073709551615\r\n\r\n[]");
+    assert!(matches!(
+        HttpRemote::new(&url, None).unwrap().get(),
+        Err(TodoError::SyncError(_))
+    ));
+    server.join().unwrap();
+
+    // A body that really is too long
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut head = [0; 1024];
+        let _ = stream.read(&mut head).unwrap();
+        let chunk = vec![b' '; 1024 * 1024];
+        let mut answer = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n".to_vec();
+        let _ = stream.write_all(&answer);
+        for _ in 0..=MAX_BODY / chunk.len() as u64 {
+            answer = format!("{:x}\r\n", chunk.len()).into_bytes();
+            answer.extend(&chunk);
+            answer.extend(b"\r\n");
+            if stream.write_all(&answer).is_err() {
+                break;
+            }
+        }
+        let _ = stream.write_all(b"0\r\n\r\n");
+    });
+    match HttpRemote::new(&url, None).unwrap().get() {
+        Err(TodoError::SyncError(message)) => {
+            assert!(message.contains(&MAX_BODY.to_string()), "{}", message)
+        }
+        other => panic!("{:?}", other.map(|_| ())),
+    }
+    server.join().unwrap();
+}
+
+#[test]
+fn the_token_stays_out_of_debug_output() {
+    let remote = HttpRemote::new("https://example.com/", Some("secret")).unwrap();
+    assert!(!format!("{:?}", remote).contains("secret"));
+}
+
+// Answer one request with `response`, handing back the request as received
+// but for header names, which are lowercased since their case means nothing
+fn serve_once(response: &'static str) -> (String, thread::JoinHandle<String>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/lists/tasks.json", listener.local_addr().unwrap());
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream);
+        let mut request = String::new();
+        let mut length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                length = value.trim().parse().unwrap();
+            }
+            match line.split_once(':') {
+                Some((name, value)) if !request.is_empty() => {
+                    request.push_str(&format!("{}:{}", name.to_lowercase(), value))
+                }
+                _ => request.push_str(&line),
+            }
+            if line == "\r\n" {
+                break;
+            }
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body).unwrap();
+        request.push_str(&String::from_utf8(body).unwrap());
+        reader.get_mut().write_all(response.as_bytes()).unwrap();
+        request
+    });
+    (url, server)
+}
+
+#[test]
+fn http_get_reads_the_body_and_etag() {
+    let (url, server) = serve_once(
+        "HTTP/1.1 200 OK\r\nETag: \"v7\"\r\nTransfer-Encoding: chunked\r\n\r\n\
+         5\r\n[{\"de\r\n18\r\nscription\": \"Buy milk\"}]\r\n0\r\n\r\n",
+    );
+    let mut remote = HttpRemote::new(&url, Some("secret")).unwrap();
+    let fetched = remote.get().unwrap().unwrap();
+    assert_eq!(fetched.body, r#"[{"description": "Buy milk"}]"#);
+    assert_eq!(fetched.version.as_deref(), Some("\"v7\""));
+
+    let request = server.join().unwrap();
+    assert!(
+        request.starts_with("GET /lists/tasks.json HTTP/1.1\r\n"),
+        "{}",
+        request
+    );
+    assert!(
+        request.contains("authorization: Bearer secret\r\n"),
+        "{}",
+        request
+    );
+}
+
+#[test]
+fn http_get_of_a_missing_list_is_empty() {
+    let (url, server) = serve_once("HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n");
+    assert_eq!(HttpRemote::new(&url, None).unwrap().get().unwrap(), None);
+    server.join().unwrap();
+}
+
+#[test]
+fn http_put_sends_the_body_with_if_match() {
+    let (url, server) = serve_once("HTTP/1.1 204 No Content\r\nETag: \"v8\"\r\n\r\n");
+    let mut remote = HttpRemote::new(&url, None).unwrap();
+    let expected = Expected::Version("\"v7\"".to_string());
+    let version = remote.put("[]", &expected).unwrap();
+    assert_eq!(version.as_deref(), Some("\"v8\""));
+
+    let request = server.join().unwrap();
+    assert!(request.starts_with("PUT /lists/tasks.json"), "{}", request);
+    assert!(request.contains("if-match: \"v7\"\r\n"), "{}", request);
+    assert!(!request.contains("if-none-match"), "{}", request);
+    assert!(!request.contains("authorization"), "{}", request);
+    assert!(request.ends_with("\r\n\r\n[]"), "{}", request);
+}
+
+#[test]
+fn http_put_expecting_no_copy_sends_if_none_match() {
+    let (url, server) = serve_once("HTTP/1.1 201 Created\r\nContent-Length: 0\r\n\r\n");
+    let mut remote = HttpRemote::new(&url, None).unwrap();
+    assert_eq!(remote.put("[]", &Expected::Nothing).unwrap(), None);
+    let request = server.join().unwrap();
+    assert!(request.contains("if-none-match: *\r\n"), "{}", request);
+    assert!(!request.contains("if-match"), "{}", request);
+
+    // A forced push holds the server to nothing
+    let (url, server) = serve_once("HTTP/1.1 204 No Content\r\n\r\n");
+    let mut remote = HttpRemote::new(&url, None).unwrap();
+    remote.put("[]", &Expected::Any).unwrap();
+    let request = server.join().unwrap();
+    assert!(!request.contains("match"), "{}", request);
+}
+
+#[test]
+fn http_failures_are_sync_errors() {
+    let cases = [
+        (
+            "HTTP/1.1 412 Precondition Failed\r\nContent-Length: 0\r\n\r\n",
+            "changed since",
+        ),
+        (
+            "HTTP/1.1 401 Unauthorized\r\nContent-Length: 0\r\n\r\n",
+            "refused the token",
+        ),
+        (
+            "HTTP/1.1 500 Internal Server Error\r\nContent-Length: 0\r\n\r\n",
+            "HTTP 500",
+        ),
+    ];
+    for (response, expected) in cases {
+        let (url, server) = serve_once(response);
+        let version = Expected::Version("1".to_string());
+        match HttpRemote::new(&url, None).unwrap().put("[]", &version) {
+            Err(TodoError::SyncError(message)) => {
+                assert!(message.contains(expected), "{}", message)
+            }
+            other => panic!("{:?}", other),
+        }
+        server.join().unwrap();
+    }
+
+    // Nothing listening
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let mut remote = HttpRemote::new(&format!("http://127.0.0.1:{}/", port), None).unwrap();
+    assert!(matches!(remote.get(), Err(TodoError::SyncError(_))));
+}
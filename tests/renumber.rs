@@ -0,0 +1,158 @@
+mod common;
+
+use std::fs;
+
+use common::scratch_dir;
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::outcome::{CommandOutcome, execute};
+use rust_todo_cli::store::Store;
+use rust_todo_cli::todo::{Status, Storable, TodoError, TodoList, rename_ids};
+
+fn ids(todo: &TodoList) -> Vec<u64> {
+    todo.tasks().iter().map(|task| task.id).collect()
+}
+
+// Five tasks with the first and third removed, leaving IDs 2, 4 and 5:
+// Write waits on Test, and Test on Ship
+fn sparse_list() -> TodoList {
+    let mut todo = TodoList::new();
+    for description in ["Plan", "Write", "Review", "Test", "Ship"] {
+        todo.add_tasks(description.to_string()).unwrap();
+    }
+    for number in [3, 1] {
+        todo.remove_task(todo.task_ref(number).unwrap()).unwrap();
+    }
+    let (write, test, ship) = (
+        todo.task_ref(1).unwrap(),
+        todo.task_ref(2).unwrap(),
+        todo.task_ref(3).unwrap(),
+    );
+    todo.add_dependency(write, test).unwrap();
+    todo.add_dependency(test, ship).unwrap();
+    todo
+}
+
+#[test]
+fn ids_become_one_to_n_and_dependencies_follow_them() {
+    let mut todo = sparse_list();
+    assert_eq!(ids(&todo), [2, 4, 5]);
+    assert_eq!(todo.renumbering().unwrap(), [(2, 1), (4, 2), (5, 3)]);
+
+    let renumbered = todo.renumber().unwrap();
+    assert_eq!(renumbered, [(2, 1), (4, 2), (5, 3)]);
+    assert_eq!(ids(&todo), [1, 2, 3]);
+    assert_eq!(todo[0].depends_on, [2]);
+    assert_eq!(todo[1].depends_on, [3]);
+    // Still blocked by the same tasks
+    assert_eq!(todo.blockers(&todo[0]), [2]);
+    assert_eq!(todo.blockers(&todo[1]), [3]);
+    assert!(todo.problems().is_empty());
+    assert_eq!(todo.log().last().unwrap().action, "renumber");
+    // New tasks carry on from there
+    todo.add_tasks("Celebrate".to_string()).unwrap();
+    assert_eq!(todo[3].id, 4);
+}
+
+#[test]
+fn ids_can_trade_places() {
+    let dir = scratch_dir("renumber-trade");
+    let path = dir.join("tasks.json");
+    let path = path.to_str().unwrap();
+    // Edited by hand so the IDs run backwards
+    fs::write(
+        path,
+        r#"[{"id": 2, "description": "Ship"},
+            {"id": 1, "description": "Write", "depends_on": [2]}]"#,
+    )
+    .unwrap();
+    let mut todo = TodoList::load(path).unwrap();
+
+    assert_eq!(todo.renumber().unwrap(), [(2, 1), (1, 2)]);
+    assert_eq!(todo[0].description, "Ship");
+    assert_eq!(ids(&todo), [1, 2]);
+    assert_eq!(todo[1].depends_on, [1]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn compact_ids_are_left_alone() {
+    let mut todo = sparse_list();
+    todo.renumber().unwrap();
+    let before = todo.clone();
+    let entries = todo.log().len();
+    assert!(todo.renumber().unwrap().is_empty());
+    assert_eq!(todo.tasks(), before.tasks());
+    assert_eq!(todo.log().len(), entries);
+    assert!(TodoList::new().renumber().unwrap().is_empty());
+}
+
+#[test]
+fn lists_a_renumber_would_garble_are_turned_down() {
+    let dir = scratch_dir("renumber-blocked");
+    let path = dir.join("tasks.json");
+    let path = path.to_str().unwrap();
+    fs::write(
+        path,
+        r#"[{"id": 4, "description": "Write", "depends_on": [9]},
+            {"id": 7, "description": "Test"}]"#,
+    )
+    .unwrap();
+    let mut todo = TodoList::load(path).unwrap();
+    let before = todo.tasks().to_vec();
+    let error = todo.renumber().unwrap_err();
+    assert!(matches!(error, TodoError::RenumberBlocked(1)));
+    assert_eq!(error.code(), "RenumberBlocked");
+    assert!(error.to_string().contains("doctor --fix"), "{}", error);
+    assert_eq!(todo.tasks(), before);
+
+    // Once repaired it goes ahead
+    todo.repair();
+    assert_eq!(todo.renumber().unwrap(), [(4, 1), (7, 2)]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn the_archive_is_left_untouched() {
+    let dir = scratch_dir("renumber-archive");
+    let store = Store::in_dir(&dir);
+    let mut todo = sparse_list();
+    for number in [1, 2] {
+        let task = todo.task_ref(number).unwrap();
+        todo.update_task_status(task, Status::Completed, false)
+            .unwrap();
+    }
+    store.archive(todo.archive_completed()).unwrap();
+    let archived = fs::read(&store.archive).unwrap();
+    assert_eq!(ids(&store.load_archive().unwrap()), [2, 4]);
+
+    let Ok(CommandOutcome::Renumbered(renumbered)) =
+        execute(&mut todo, parse_command("renumber --yes").unwrap())
+    else {
+        panic!("renumber gives the IDs it changed");
+    };
+    assert_eq!(renumbered, [(5, 1)]);
+    assert_eq!(ids(&todo), [1]);
+    // The live list may reuse archived IDs; the archive keeps its own
+    assert_eq!(fs::read(&store.archive).unwrap(), archived);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn copies_taken_before_can_follow_the_new_ids() {
+    let mut todo = sparse_list();
+    let mut earlier = todo.tasks().to_vec();
+    let renumbered = todo.renumber().unwrap();
+    rename_ids(&mut earlier, &renumbered);
+    assert_eq!(earlier, todo.tasks());
+}
+
+#[test]
+fn the_command_takes_only_yes() {
+    assert_eq!(parse_command("renumber").unwrap(), Command::Renumber(false));
+    assert_eq!(
+        parse_command("renumber --yes").unwrap(),
+        Command::Renumber(true)
+    );
+    assert!(parse_command("renumber 3").is_err());
+    assert!(parse_command("renumber").unwrap().mutates());
+}
@@ -0,0 +1,178 @@
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::date::Timestamp;
+use rust_todo_cli::outcome::{CommandOutcome, execute, query};
+use rust_todo_cli::todo::{Recurrence, Selector, Status, Task, TaskIndex, TodoError, TodoList};
+
+fn at(task: usize, sub: Option<usize>) -> TaskIndex {
+    TaskIndex { task, sub }
+}
+
+// A task completed at the given time, or open with None
+fn task(description: &str, completed_at: Option<Timestamp>) -> Task {
+    let mut task = Task::new(description.to_string()).unwrap();
+    if completed_at.is_some() {
+        task.status = Status::Completed;
+        task.completed_at = completed_at;
+    }
+    task
+}
+
+// Buy milk done first, Pay rent last and Call mum's subtask in between
+fn done_list() -> TodoList {
+    let mut call = task("Call mum", None);
+    call.subtasks.push(task("Dial", Some(2_000)));
+    [
+        task("Buy milk", Some(1_000)),
+        call,
+        task("Pay rent", Some(3_000)),
+        task("Water plants", None),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn reopening_takes_a_completed_task_back_to_todo() {
+    let mut todo = done_list();
+    todo.reopen(at(1, None)).unwrap();
+    assert_eq!(todo[0].status, Status::Todo);
+    assert_eq!(todo[0].completed_at, None);
+    todo.reopen(at(2, Some(1))).unwrap();
+    assert_eq!(todo[1].subtasks[0].status, Status::Todo);
+    assert_eq!(todo.log().last().unwrap().action, "status");
+}
+
+#[test]
+fn only_completed_tasks_can_be_reopened() {
+    let mut todo = done_list();
+    let error = todo.reopen(at(4, None)).unwrap_err();
+    assert!(
+        matches!(error, TodoError::NotCompleted(index, Status::Todo) if index == at(4, None)),
+        "{:?}",
+        error
+    );
+    assert_eq!(
+        error.to_string(),
+        "Task 4 is todo, not done, so there is nothing to reopen"
+    );
+    todo.update_status_at(at(4, None), Status::Cancelled, false)
+        .unwrap();
+    let error = todo.reopen(at(4, None)).unwrap_err();
+    assert!(error.to_string().contains("is cancelled"), "{}", error);
+    assert!(matches!(
+        todo.reopen(at(9, None)),
+        Err(TodoError::IndexOutOfBound(9))
+    ));
+}
+
+#[test]
+fn the_latest_completion_is_found_among_tasks_and_subtasks() {
+    let mut todo = done_list();
+    assert_eq!(todo.most_recently_completed(), Some(at(3, None)));
+    let order: Vec<TaskIndex> = todo
+        .recently_completed()
+        .iter()
+        .map(|(index, _)| *index)
+        .collect();
+    assert_eq!(order, [at(3, None), at(2, Some(1)), at(1, None)]);
+
+    todo.reopen(at(3, None)).unwrap();
+    assert_eq!(todo.most_recently_completed(), Some(at(2, Some(1))));
+    todo.reopen(at(2, Some(1))).unwrap();
+    todo.reopen(at(1, None)).unwrap();
+    assert_eq!(todo.most_recently_completed(), None);
+}
+
+#[test]
+fn completions_without_a_time_are_listed_last_and_never_the_latest() {
+    let mut todo: TodoList = [task("Old", None), task("New", Some(5_000))]
+        .into_iter()
+        .collect();
+    // As loaded from a file written before completion times were kept
+    todo.update_status_at(at(1, None), Status::Completed, false)
+        .unwrap();
+    let mut todo: TodoList = todo
+        .iter()
+        .cloned()
+        .map(|mut task| {
+            if task.description == "Old" {
+                task.completed_at = None;
+            }
+            task
+        })
+        .collect();
+    let order: Vec<TaskIndex> = todo
+        .recently_completed()
+        .iter()
+        .map(|(index, _)| *index)
+        .collect();
+    assert_eq!(order, [at(2, None), at(1, None)]);
+    todo.reopen(at(2, None)).unwrap();
+    assert_eq!(todo.most_recently_completed(), None);
+}
+
+#[test]
+fn reopening_a_recurring_task_adds_no_occurrence() {
+    let mut todo = TodoList::new();
+    let mut bins = task("Take out bins", None);
+    bins.recurrence = Some(Recurrence::parse("weekly").unwrap());
+    todo.add(bins);
+    let first = todo.task_ref(1).unwrap();
+    let next = todo
+        .update_task_status(first, Status::Completed, false)
+        .unwrap();
+    assert_eq!(next, Some(2));
+
+    todo.reopen(at(1, None)).unwrap();
+    assert_eq!(todo.len(), 2);
+    assert_eq!(todo[0].recurrence, None);
+    assert!(todo[1].recurrence.is_some());
+    // Done again, it still carries nothing on
+    let first = todo.task_ref(1).unwrap();
+    let next = todo
+        .update_task_status(first, Status::Completed, false)
+        .unwrap();
+    assert_eq!((next, todo.len()), (None, 2));
+}
+
+#[test]
+fn reopen_parses_a_task_last_or_nothing() {
+    assert_eq!(
+        parse_command("reopen 2.1").unwrap(),
+        Command::Reopen(Selector::Index(at(2, Some(1))))
+    );
+    assert_eq!(
+        parse_command("undone \"milk\"").unwrap(),
+        Command::Reopen(Selector::Text("milk".to_string()))
+    );
+    assert_eq!(parse_command("reopen LAST").unwrap(), Command::ReopenLast);
+    assert_eq!(
+        parse_command("uncomplete").unwrap(),
+        Command::RecentlyCompleted
+    );
+    let Command::Reopen(Selector::Pick(filter)) = parse_command("reopen --pick").unwrap() else {
+        panic!("a pick");
+    };
+    assert_eq!(filter.status, Some(Status::Completed));
+    assert!(parse_command("reopen 1 2").is_err());
+    assert!(parse_command("reopen last").unwrap().mutates());
+    assert!(!parse_command("reopen").unwrap().mutates());
+}
+
+#[test]
+fn reopen_commands_run_through_the_library() {
+    let mut todo = done_list();
+    let Ok(CommandOutcome::RecentlyCompleted(done)) = query(&todo, Command::RecentlyCompleted)
+    else {
+        panic!("lists");
+    };
+    assert_eq!(done.len(), 3);
+    let outcome = execute(&mut todo, Command::ReopenLast).unwrap();
+    assert!(matches!(outcome, CommandOutcome::Reopened(index) if index == at(3, None)));
+    let error = execute(&mut todo, parse_command("reopen 3").unwrap()).unwrap_err();
+    assert_eq!(error.code(), "NotCompleted");
+
+    let mut todo = TodoList::new();
+    let error = execute(&mut todo, Command::ReopenLast).unwrap_err();
+    assert!(matches!(error, TodoError::NothingCompleted));
+}
@@ -0,0 +1,137 @@
+use rust_todo_cli::date::{Date, SECONDS_PER_DAY, Timestamp, utc_offset};
+use rust_todo_cli::report::markdown;
+use rust_todo_cli::todo::{Status, Task, TodoList};
+
+// Local noon on a day, so reports come out the same in any time zone
+fn noon(date: Date) -> Timestamp {
+    let utc = (date.days() * SECONDS_PER_DAY + SECONDS_PER_DAY / 2) as Timestamp;
+    (utc as i64 - utc_offset(utc)) as Timestamp
+}
+
+fn today() -> Date {
+    Date::new(2025, 6, 10).unwrap()
+}
+
+fn task(description: &str, created: i64) -> Task {
+    let mut task = Task::new(description.to_string()).unwrap();
+    task.created_at = Some(noon(today().add_days(-created)));
+    task
+}
+
+fn closed(description: &str, status: Status, days_ago: i64) -> Task {
+    let mut task = task(description, days_ago + 1);
+    task.status = status;
+    task.completed_at = Some(noon(today().add_days(-days_ago)));
+    task
+}
+
+fn sample() -> TodoList {
+    let mut milk = task("Buy milk", 3);
+    milk.tags = vec!["shop".to_string()];
+    milk.due = Date::new(2025, 6, 12);
+    let mut report = task("Write report +work", 1);
+    report.status = Status::InProgress;
+    report.projects = vec!["work".to_string()];
+    report.subtasks.push(task("Outline", 1));
+    report
+        .subtasks
+        .push(closed("Gather numbers", Status::Completed, 0));
+    [
+        milk,
+        report,
+        closed("Call mum", Status::Completed, 0),
+        closed("Book dentist", Status::Completed, 1),
+        closed("File taxes", Status::Completed, 4),
+        closed("Paint shed", Status::Cancelled, 0),
+    ]
+    .into_iter()
+    .collect()
+}
+
+#[test]
+fn report_has_the_numbers_completions_and_tasks_by_status() {
+    let expected = "\
+# Status report, 2025-06-10
+
+## Summary
+
+- Total: 6
+- Todo: 1 (17%)
+- In progress: 1 (17%)
+- Done: 3 (50%)
+- Cancelled: 1 (17%)
+- Completed today: 2, this week: 4
+- Longest open: 1. Buy milk (3 days)
+- Streak: 2 days, best 2 days
+
+## Completed since yesterday
+
+- Call mum
+- Book dentist
+
+## In progress
+
+- Write report +work
+  - [ ] Outline
+  - [x] Gather numbers
+
+## Todo
+
+- Buy milk #shop (due 2025-06-12)
+
+## Done
+
+- Call mum
+- Book dentist
+- File taxes
+
+## Cancelled
+
+- Paint shed
+";
+    assert_eq!(markdown(&sample(), noon(today())), expected);
+}
+
+#[test]
+fn report_numbers_match_stats() {
+    let todo = sample();
+    let now = noon(today());
+    let stats = todo.statistics_at(now);
+    let report = markdown(&todo, now);
+    assert!(report.contains(&format!("- Total: {}\n", stats.total)));
+    assert!(report.contains(&format!(
+        "- Completed today: {}, this week: {}\n",
+        stats.completed_today, stats.completed_this_week
+    )));
+}
+
+#[test]
+fn empty_lists_get_a_short_report() {
+    assert_eq!(
+        markdown(&TodoList::new(), noon(today())),
+        "# Status report, 2025-06-10\n\nNo tasks yet.\n"
+    );
+}
+
+#[test]
+fn completions_are_left_out_without_timestamps() {
+    let mut done = task("Call mum", 2);
+    done.status = Status::Completed;
+    let todo: TodoList = [task("Buy milk", 1), done].into_iter().collect();
+    let report = markdown(&todo, noon(today()));
+    assert!(!report.contains("Completed since yesterday"), "{}", report);
+    assert!(report.contains("## Done\n\n- Call mum\n"), "{}", report);
+}
+
+#[test]
+fn quiet_days_say_so() {
+    let todo: TodoList = [closed("File taxes", Status::Completed, 4)]
+        .into_iter()
+        .collect();
+    let report = markdown(&todo, noon(today()));
+    assert!(
+        report.contains("## Completed since yesterday\n\nNothing yet.\n"),
+        "{}",
+        report
+    );
+}
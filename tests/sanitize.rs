@@ -0,0 +1,125 @@
+use std::io::Cursor;
+
+use rust_todo_cli::sanitize::{PREVIEW_LEN, sanitize, sanitize_line, truncate};
+use rust_todo_cli::settings::Settings;
+use rust_todo_cli::todo::{Storable, Task, TodoError, TodoList, read_tasks};
+
+#[test]
+fn escape_sequences_are_taken_out() {
+    assert_eq!(sanitize("Pay \x1b[31;1mrent\x1b[0m"), "Pay rent");
+    // Clearing the screen and moving the cursor
+    assert_eq!(sanitize("\x1b[2J\x1b[HHello"), "Hello");
+    // A window title, ended by BEL or by ESC \
+    assert_eq!(sanitize("a\x1b]0;owned\x07b"), "ab");
+    assert_eq!(sanitize("a\x1b]8;;http://x\x1b\\link"), "alink");
+    // The one-byte CSI and a bare two-character sequence
+    assert_eq!(sanitize("a\u{9b}31mb\x1bMc"), "abc");
+    // Sequences cut off by the end of the line take nothing else with them
+    assert_eq!(sanitize("Done\x1b[31"), "Done");
+    assert_eq!(sanitize("Done\x1b"), "Done");
+}
+
+#[test]
+fn control_characters_go_and_whitespace_runs_become_one_space() {
+    assert_eq!(sanitize("Buy\0 milk\x07"), "Buy milk");
+    assert_eq!(sanitize("Buy milk\r"), "Buy milk");
+    assert_eq!(
+        sanitize("  Buy\t\tmilk \r\n and  eggs  "),
+        "Buy milk and eggs"
+    );
+    assert_eq!(sanitize("a\u{a0}\u{2003}b"), "a b");
+    assert_eq!(sanitize("\0\x1b[0m \t"), "");
+    // Text that needs nothing done stays as it is
+    let fine = "Café ☕ at 9 — #errand +town";
+    assert_eq!(sanitize(fine), fine);
+}
+
+#[test]
+fn long_text_is_cut_on_a_character_boundary() {
+    // Two bytes each
+    let accents = "é".repeat(5_000);
+    assert_eq!(truncate(&accents, 7), "ééé");
+    assert_eq!(truncate(&accents, 8), "éééé");
+    // Four bytes each
+    let emoji = "🎉".repeat(1_000);
+    assert_eq!(truncate(&emoji, 6), "🎉");
+    assert_eq!(truncate(&emoji, 3), "");
+    assert_eq!(truncate("short", 10), "short");
+}
+
+#[test]
+fn lines_over_the_limit_are_turned_down_with_their_start() {
+    let line = format!("add {}", "日本".repeat(10_000));
+    let error = sanitize_line(&line, 4_096).unwrap_err();
+    let TodoError::LineTooLong { len, max, start } = &error else {
+        panic!("{:?}", error);
+    };
+    assert_eq!((*len, *max), (60_004, 4_096));
+    assert!(start.len() <= PREVIEW_LEN);
+    assert!(start.starts_with("add 日本"), "{}", start);
+    assert!(
+        error
+            .to_string()
+            .starts_with("Line is 60004 bytes long, the limit is 4096")
+    );
+    assert_eq!(error.code(), "LineTooLong");
+
+    // The start shown is sanitized too
+    let error = sanitize_line(&format!("\x1b[2J{}", "x".repeat(100)), 50).unwrap_err();
+    assert!(!error.to_string().contains('\x1b'));
+
+    assert_eq!(sanitize_line(&line, 0).unwrap().len(), line.len());
+    assert_eq!(sanitize_line("  list\tdone\r", 12).unwrap(), "list done");
+}
+
+#[test]
+fn descriptions_are_sanitized_however_a_task_is_made() {
+    let task = Task::new("Call \x1b[5mMUM\x1b[0m\r\nnow".to_string()).unwrap();
+    assert_eq!(task.description, "Call MUM now");
+    assert!(matches!(
+        Task::new("\x1b[31m\0".to_string()),
+        Err(TodoError::EmptyDescription)
+    ));
+}
+
+#[test]
+fn files_read_in_are_sanitized_line_by_line() {
+    let long = "x".repeat(5_000);
+    let text = format!(
+        "Buy\0 milk\r\n\x1b[1mCall mum\x1b[0m\n{}\n \x07\n# note\n",
+        long
+    );
+    let file = read_tasks(Cursor::new(text), &Settings::default()).unwrap();
+    let descriptions: Vec<&str> = file
+        .tasks
+        .iter()
+        .map(|task| task.description.as_str())
+        .collect();
+    assert_eq!(descriptions, ["Buy milk", "Call mum"]);
+    assert_eq!(file.failed.len(), 1);
+    assert!(matches!(
+        file.failed[0],
+        (3, TodoError::LineTooLong { len: 5_000, .. })
+    ));
+}
+
+// What a hand-edited or hostile tasks.json might hold
+const EVIL: &str = r#"[{"description": "evil \u001b]0;pwned\u0007 \u001b[31mred\u001b[0m", "status": "Todo",
+    "subtasks": [{"description": "\u001b[2Jwiped\r\nsub", "status": "Todo"}]}]"#;
+
+#[test]
+fn descriptions_loaded_from_a_file_are_sanitized() {
+    let path = std::env::temp_dir().join(format!(
+        "rust-todo-cli-sanitize-load-{}.json",
+        std::process::id()
+    ));
+    std::fs::write(&path, EVIL).unwrap();
+    let todo = TodoList::load(path.to_str().unwrap()).unwrap();
+    assert_eq!(todo[0].description, "evil red");
+    assert_eq!(todo[0].subtasks[0].description, "wiped sub");
+    std::fs::remove_file(path).unwrap();
+
+    // However a task is read, not only from the task file
+    let task: Task = serde_json::from_str(r#"{"description": "\u009b1mbold"}"#).unwrap();
+    assert_eq!(task.description, "bold");
+}
@@ -0,0 +1,229 @@
+mod common;
+
+use std::fs;
+
+use common::list_of;
+use rust_todo_cli::command::{Command, expand_filter, parse_command, parse_filter};
+use rust_todo_cli::outcome::{CommandOutcome, query};
+use rust_todo_cli::todo::{ListFilter, Status, Storable, TodoError, TodoList};
+
+fn list_filter(input: &str) -> ListFilter {
+    let Command::List { filter, .. } = parse_command(input).unwrap() else {
+        panic!("{:?} is a listing", input);
+    };
+    filter
+}
+
+// Numbers of the tasks `list` shows for the input
+fn listed(todo: &TodoList, input: &str) -> Vec<usize> {
+    let Ok(CommandOutcome::Tasks(tasks)) = query(todo, parse_command(input).unwrap()) else {
+        panic!("{:?} lists tasks", input);
+    };
+    tasks.iter().map(|(index, _)| *index).collect()
+}
+
+// Fix login is in progress and tagged work, Deploy tagged work, Water
+// plants neither
+fn work_list() -> TodoList {
+    let mut todo = list_of(&["Fix login", "Deploy fix", "Water plants"]);
+    for index in [1, 2] {
+        todo.add_tags(todo.task_ref(index).unwrap(), &["work".to_string()])
+            .unwrap();
+    }
+    let fix = todo.task_ref(1).unwrap();
+    todo.update_task_status(fix, Status::InProgress, false)
+        .unwrap();
+    todo
+}
+
+#[test]
+fn filter_commands_parse_with_or_without_quotes() {
+    let quoted = parse_command("filter save Urgent \"in-progress tag:work\"").unwrap();
+    let bare = parse_command("filter save urgent in-progress tag:work").unwrap();
+    let expected = Command::FilterSave("urgent".to_string(), "in-progress tag:work".to_string());
+    assert_eq!((quoted, bare), (expected.clone(), expected));
+    assert_eq!(parse_command("filters").unwrap(), Command::FilterList);
+    assert_eq!(parse_command("filter list").unwrap(), Command::FilterList);
+    assert_eq!(
+        parse_command("filter delete Urgent").unwrap(),
+        Command::FilterDelete("urgent".to_string())
+    );
+    assert!(parse_command("filter save urgent").is_err());
+    assert!(parse_command("filter delete").is_err());
+    // What would not list can't be saved
+    assert!(parse_command("filter save bad tag:").is_err());
+    assert!(parse_command("filter save bad status:nope").is_err());
+    assert!(parse_command("filter save short todo --limit 3").is_err());
+    assert!(parse_command("filter save urgent todo").unwrap().mutates());
+    assert!(!parse_command("filter list").unwrap().mutates());
+}
+
+#[test]
+fn a_saved_filter_stands_in_for_its_words() {
+    let mut todo = work_list();
+    todo.save_filter("urgent", "in-progress tag:work").unwrap();
+    assert_eq!(listed(&todo, "list urgent"), [1]);
+    assert_eq!(listed(&todo, "list URGENT"), [1]);
+
+    let (expanded, used) = expand_filter(&list_filter("list urgent --recent"), &todo).unwrap();
+    assert_eq!(used, ["urgent"]);
+    assert_eq!(expanded.to_string(), "in-progress tag:work --recent");
+    assert_eq!(expanded, list_filter("list in-progress tag:work --recent"));
+
+    // The status given alongside wins over the saved one
+    let (expanded, _) = expand_filter(&list_filter("list urgent todo"), &todo).unwrap();
+    assert_eq!(expanded.status, Some(Status::Todo));
+    assert_eq!(listed(&todo, "list urgent todo"), [2]);
+}
+
+#[test]
+fn words_naming_no_filter_are_looked_for_in_descriptions() {
+    let mut todo = work_list();
+    todo.save_filter("mine", "tag:work fix").unwrap();
+    let (expanded, used) = expand_filter(&list_filter("list plants"), &todo).unwrap();
+    assert!(used.is_empty());
+    assert_eq!(expanded.text, ["plants"]);
+    assert_eq!(listed(&todo, "list plants"), [3]);
+    // Nor are names looked up within a saved filter
+    todo.save_filter("nested", "mine").unwrap();
+    assert!(listed(&todo, "list nested").is_empty());
+    assert_eq!(listed(&todo, "list mine"), [1, 2]);
+    // Search only ever looks for text
+    assert!(listed(&todo, "search mine").is_empty());
+}
+
+#[test]
+fn statuses_and_list_words_win_over_saved_filters() {
+    let mut todo = work_list();
+    for name in [
+        "done",
+        "Todo",
+        "in-progress",
+        "someday",
+        "all",
+        "blocked",
+        "archived",
+    ] {
+        assert!(
+            matches!(
+                todo.save_filter(name, "tag:work"),
+                Err(TodoError::ReservedFilterName(_))
+            ),
+            "{:?}",
+            name
+        );
+    }
+    for name in [
+        "",
+        "9lives",
+        "+web",
+        "@home",
+        "tag:work",
+        "two words",
+        "--json",
+    ] {
+        assert!(
+            matches!(
+                todo.save_filter(name, "tag:work"),
+                Err(TodoError::InvalidFilterName(_))
+            ),
+            "{:?}",
+            name
+        );
+    }
+    assert!(todo.filters().is_empty());
+    assert!(todo.save_filter("work-2_b", "tag:work").is_ok());
+    assert_eq!(list_filter("list done").status, Some(Status::Completed));
+}
+
+#[test]
+fn saved_filters_survive_saving_and_shadowed_ones_are_found() {
+    let dir = std::env::temp_dir().join(format!("rust-todo-cli-filters-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json");
+    let path = path.to_str().unwrap();
+
+    let mut todo = work_list();
+    todo.save_template("trip", &[3]).unwrap();
+    todo.save(path).unwrap();
+    assert!(!fs::read_to_string(path).unwrap().contains("filters"));
+
+    todo.save_filter("urgent", "in-progress tag:work").unwrap();
+    todo.save_filter("plants", "plants --by-due").unwrap();
+    todo.save(path).unwrap();
+    let loaded = TodoList::load(path).unwrap();
+    assert_eq!(loaded.filters(), todo.filters());
+    assert_eq!(loaded.templates(), todo.templates());
+    assert_eq!(listed(&loaded, "list urgent"), [1]);
+
+    // Filters alone keep the file an object, and deleting the last makes it
+    // the bare array older versions read
+    let mut todo = work_list();
+    todo.save_filter("urgent", "in-progress").unwrap();
+    todo.save(path).unwrap();
+    assert_eq!(TodoList::load(path).unwrap().filters(), todo.filters());
+    assert_eq!(todo.delete_filter("URGENT").unwrap(), "in-progress");
+    assert!(matches!(
+        todo.delete_filter("urgent"),
+        Err(TodoError::UnknownSavedFilter(_))
+    ));
+    todo.save(path).unwrap();
+    assert!(
+        fs::read_to_string(path)
+            .unwrap()
+            .trim_start()
+            .starts_with('[')
+    );
+
+    // A name saved before the word meant something to list, or by hand
+    fs::write(
+        path,
+        r#"{"tasks": [{"description": "Fix login"}], "templates": {},
+            "filters": {"done": "todo", "mine": "fix"}}"#,
+    )
+    .unwrap();
+    let mut loaded = TodoList::load(path).unwrap();
+    assert_eq!(loaded.shadowed_filters(), ["done"]);
+    let (expanded, used) = expand_filter(&list_filter("list done"), &loaded).unwrap();
+    assert!(used.is_empty());
+    assert_eq!(expanded.status, Some(Status::Completed));
+    assert_eq!(loaded.delete_filter("done").unwrap(), "todo");
+    assert!(loaded.shadowed_filters().is_empty());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_broken_saved_filter_fails_when_used() {
+    let mut todo = work_list();
+    // Saving takes the filter as given; only the parser checks it
+    todo.save_filter("broken", "tag: --stale").unwrap();
+    let error = query(&todo, parse_command("list broken").unwrap()).unwrap_err();
+    assert_eq!(error.code(), "InvalidSavedFilter");
+    assert!(error.to_string().contains("broken"), "{}", error);
+    assert!(parse_filter("tag: --stale").is_err());
+    // Other listings are unaffected
+    assert_eq!(listed(&todo, "list"), [1, 2, 3]);
+}
+
+#[test]
+fn merging_keeps_filters_changed_on_either_side() {
+    let mut base = work_list();
+    base.save_filter("urgent", "in-progress").unwrap();
+    base.save_filter("old", "todo").unwrap();
+    let mut ours = base.clone();
+    ours.save_filter("urgent", "in-progress tag:work").unwrap();
+    ours.delete_filter("old").unwrap();
+    let mut theirs = base.clone();
+    theirs.save_filter("mine", "tag:work").unwrap();
+
+    ours.merge(&base, theirs);
+    let names: Vec<(&str, &str)> = ours
+        .filters()
+        .iter()
+        .map(|(name, filter)| (name.as_str(), filter.as_str()))
+        .collect();
+    assert_eq!(
+        names,
+        [("mine", "tag:work"), ("urgent", "in-progress tag:work")]
+    );
+}
@@ -0,0 +1,107 @@
+use std::ops::Range;
+
+use rust_todo_cli::todo::{ListFilter, find_matches};
+
+// Ranges as (start, end) pairs, which compare more readably in a list
+fn found(text: &str, term: &str) -> Vec<(usize, usize)> {
+    pairs(find_matches(text, term))
+}
+
+fn pairs(ranges: Vec<Range<usize>>) -> Vec<(usize, usize)> {
+    ranges
+        .iter()
+        .map(|range| (range.start, range.end))
+        .collect()
+}
+
+fn filter(terms: &[&str]) -> ListFilter {
+    ListFilter {
+        text: terms.iter().map(|term| term.to_string()).collect(),
+        ..ListFilter::default()
+    }
+}
+
+#[test]
+fn matches_ignore_case_and_do_not_overlap() {
+    assert_eq!(found("Buy MILK, then milk", "Milk"), [(4, 8), (15, 19)]);
+    assert_eq!(found("aaaa", "aa"), [(0, 2), (2, 4)]);
+    assert!(found("Buy milk", "bread").is_empty());
+    assert!(found("Buy milk", "").is_empty());
+    assert!(found("", "milk").is_empty());
+}
+
+#[test]
+fn matches_are_byte_ranges_of_multibyte_text() {
+    // é and è take two bytes each
+    assert_eq!(found("Café crème", "CRÈME"), [(6, 12)]);
+    assert_eq!(found("Café crème", "é"), [(3, 5)]);
+    assert_eq!(found("🔥 hot 🔥", "🔥"), [(0, 4), (9, 13)]);
+    // Σ ends a word as ς and is σ elsewhere; either matches it
+    assert_eq!(found("Κύκλος ΚΎΚΛΟΣ", "κύκλος"), [(0, 12), (13, 25)]);
+    assert_eq!(found("ΚΎΚΛΟΣ", "ΚΎΚΛΟΣ"), [(0, 12)]);
+    assert_eq!(found("Σοφία", "ς"), [(0, 2)]);
+}
+
+#[test]
+fn matches_hold_where_lowercasing_changes_the_length() {
+    // The Kelvin sign takes three bytes and lowercases to a one-byte k
+    assert_eq!(found("5 \u{212A}m away", "km"), [(2, 6)]);
+    // İ takes two bytes and lowercases to i and a combining dot, three
+    assert_eq!(found("İstanbul", "i\u{307}stanbul"), [(0, 9)]);
+    // A match may not end halfway through a character's lowercase form
+    assert!(found("İstanbul", "i").is_empty());
+    assert!(found("İstanbul", "istanbul").is_empty());
+}
+
+// Text is not normalized: a letter and a combining accent only match the
+// same pair, and a plain letter matches without taking the accent along
+#[test]
+fn combining_accents_match_as_written() {
+    let decomposed = "Cafe\u{301} au lait";
+    assert_eq!(found(decomposed, "cafe\u{301}"), [(0, 6)]);
+    assert_eq!(found(decomposed, "cafe"), [(0, 4)]);
+    assert!(found(decomposed, "café").is_empty());
+    assert!(found("café", "cafe\u{301}").is_empty());
+}
+
+#[test]
+fn every_match_falls_on_character_boundaries() {
+    let texts = [
+        "Café crème",
+        "Cafe\u{301} au lait",
+        "İstanbul İSTANBUL",
+        "5 \u{212A}m, 10 \u{212A}M",
+        "Straße STRASSE",
+        "🔥🔥 fire",
+        "ǅemal Džemal",
+    ];
+    let terms = ["e", "É", "i\u{307}", "km", "ss", "ß", "🔥", "dž", "\u{301}"];
+    for text in texts {
+        for term in terms {
+            for range in find_matches(text, term) {
+                assert!(
+                    text.get(range.clone()).is_some(),
+                    "{:?} in {:?} gave {:?}",
+                    term,
+                    text,
+                    range
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn highlights_merge_overlapping_terms_in_order() {
+    let description = "Buy oat milk at the café";
+    assert_eq!(
+        pairs(filter(&["CAFÉ", "milk", "oat m"]).highlights(description)),
+        [(4, 12), (20, 25)]
+    );
+    assert_eq!(
+        pairs(filter(&["at"]).highlights(description)),
+        [(5, 7), (13, 15)]
+    );
+    assert!(filter(&["tea"]).highlights(description).is_empty());
+    assert!(filter(&[]).highlights(description).is_empty());
+}
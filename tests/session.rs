@@ -0,0 +1,350 @@
+// End-to-end runs of the binary: scripted sessions over stdin and one-shot
+// commands, against a task file in a directory of their own
+
+mod common;
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Output, Stdio};
+
+use common::{scratch_dir, typed};
+use serde_json::Value;
+
+// The app on the task file at `file`, run from somewhere else so nothing
+// but --file can lead it there, without the banner
+fn app(file: &Path) -> Command {
+    let mut command = common::app();
+    command
+        .current_dir(std::env::temp_dir())
+        .arg("--no-banner")
+        .arg("--file")
+        .arg(file);
+    command
+}
+
+// Type each line of the script into an interactive session
+fn session(file: &Path, script: &[&str]) -> Output {
+    typed(&mut app(file), script)
+}
+
+fn one_shot(file: &Path, args: &[&str]) -> Output {
+    app(file).args(args).output().unwrap()
+}
+
+fn text(bytes: &[u8]) -> String {
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn saved_tasks(file: &Path) -> Vec<Value> {
+    let saved: Value = serde_json::from_str(&fs::read_to_string(file).unwrap()).unwrap();
+    match saved {
+        Value::Array(tasks) => tasks,
+        Value::Object(mut file) => match file.remove("tasks") {
+            Some(Value::Array(tasks)) => tasks,
+            other => panic!("tasks: {:?}", other),
+        },
+        other => panic!("{:?}", other),
+    }
+}
+
+// Names of the files in the directory, in order
+fn files_in(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn a_fresh_start_adds_lists_and_saves() {
+    let dir = scratch_dir("fresh");
+    let file = dir.join("work.json");
+    let output = session(
+        &file,
+        &[
+            "add Buy milk",
+            "add Call mum",
+            "list",
+            "done 1",
+            "save",
+            "exit",
+        ],
+    );
+    assert!(output.status.success());
+    assert_eq!(text(&output.stderr), "");
+    let expected = format!(
+        "\n> * Task added successfully!\n\
+         \n> * Task added successfully!\n\
+         \n> \n* Your Tasks:\n\
+         [ ] 1. Buy milk [TODO] (0d)\n\
+         [ ] 2. Call mum [TODO] (0d)\n\
+         [---------------] 0/2 done (0%)\n\
+         \n> * Task 1 marked done\n\
+         \n>  Tasks saved to {}\n\
+         \n> * Tasks saved successfully!\n \
+         Goodbye!\n",
+        file.display()
+    );
+    assert_eq!(text(&output.stdout), expected);
+
+    let tasks = saved_tasks(&file);
+    let described: Vec<(&str, &str)> = tasks
+        .iter()
+        .map(|task| {
+            (
+                task["description"].as_str().unwrap(),
+                task["status"].as_str().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(described, [("Buy milk", "Completed"), ("Call mum", "Todo")]);
+    assert_eq!(files_in(&dir), ["tasks.log.json", "work.json"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_prepared_file_is_loaded_and_kept_as_it_was() {
+    let dir = scratch_dir("fixture");
+    let file = dir.join("tasks.json");
+    let fixture = format!(
+        "{}/tests/fixtures/current_format.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    fs::copy(&fixture, &file).unwrap();
+
+    let output = one_shot(&file, &["list", "--json"]);
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    let listed: Value = serde_json::from_slice(&output.stdout).unwrap();
+    let descriptions: Vec<&str> = listed
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["description"].as_str().unwrap())
+        .collect();
+    assert_eq!(descriptions, ["Plan trip +travel", "Pack"]);
+
+    let output = session(&file, &["add Water plants", "exit"]);
+    assert!(output.status.success());
+    assert!(
+        text(&output.stdout).contains("Loaded 2 tasks"),
+        "{}",
+        text(&output.stdout)
+    );
+    let tasks = saved_tasks(&file);
+    assert_eq!(tasks.len(), 3);
+    assert_eq!(tasks[1]["depends_on"], serde_json::json!([1]));
+    // The template the fixture holds is saved along with the tasks
+    let saved: Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    assert_eq!(
+        saved["templates"]["trip"],
+        serde_json::json!(["Pack", "Water plants"])
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn one_shot_errors_fail_and_leave_the_file_alone() {
+    let dir = scratch_dir("errors");
+    let file = dir.join("tasks.json");
+    assert!(one_shot(&file, &["add", "Buy milk"]).status.success());
+    let before = fs::read_to_string(&file).unwrap();
+
+    for args in [
+        &["done", "9"][..],
+        &["frobnicate"],
+        &["update", "1", "sideways"],
+        &["add"],
+    ] {
+        let output = one_shot(&file, args);
+        assert_eq!(output.status.code(), Some(1), "{:?}", args);
+        assert!(!output.stderr.is_empty(), "{:?}", args);
+    }
+    let output = one_shot(&file, &["done", "9"]);
+    assert_eq!(
+        text(&output.stderr),
+        "Error: No task exists at that index 9\n"
+    );
+    assert_eq!(fs::read_to_string(&file).unwrap(), before);
+
+    // A failed step stops the chain, and what ran before it is still saved
+    let output = one_shot(&file, &["add", "Call", "mum", "&&", "done", "9"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(saved_tasks(&file).len(), 2);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn sessions_leave_no_temporary_files_behind() {
+    let dir = scratch_dir("leftovers");
+    let file = dir.join("tasks.json");
+    let output = session(
+        &file,
+        &[
+            "add Buy milk; Call mum; Water plants",
+            "save",
+            "done 2",
+            "save",
+            "remove 3 --yes",
+            "archive",
+            "exit",
+        ],
+    );
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert!(one_shot(&file, &["add", "Pack"]).status.success());
+    // Input that ends without `exit` saves all the same
+    assert!(session(&file, &["add Book dentist"]).status.success());
+
+    assert_eq!(
+        files_in(&dir),
+        ["archive.json", "tasks.json", "tasks.log.json", "trash.json"]
+    );
+    assert_eq!(saved_tasks(&file).len(), 3);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn pasted_escapes_and_blobs_never_reach_the_list() {
+    let dir = scratch_dir("sanitize");
+    let file = dir.join("tasks.json");
+    let blob = format!("add {}", "€".repeat(2_000));
+    let output = app(&file)
+        .args(["--max-line", "1000"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .and_then(|mut child| {
+            let mut stdin = child.stdin.take().unwrap();
+            write!(
+                stdin,
+                "add Pay \x1b[31mrent\x1b[0m\r\n{}\nadd Call\0  mum\nexit\n",
+                blob
+            )?;
+            drop(stdin);
+            child.wait_with_output()
+        })
+        .unwrap();
+    assert!(output.status.success());
+    let printed = format!("{}{}", text(&output.stdout), text(&output.stderr));
+    assert!(!printed.contains('\x1b'), "{:?}", printed);
+    assert!(
+        printed.contains("Line is 6004 bytes long, the limit is 1000"),
+        "{}",
+        printed
+    );
+
+    let descriptions: Vec<String> = saved_tasks(&file)
+        .iter()
+        .map(|task| task["description"].as_str().unwrap().to_string())
+        .collect();
+    assert_eq!(descriptions, ["Pay rent", "Call mum"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_backup_restored_in_a_session_replaces_the_list() {
+    let dir = scratch_dir("backup");
+    let file = dir.join("tasks.json");
+    let bundle = dir.join("saved.backup.json");
+    let output = session(
+        &file,
+        &[
+            "add Buy milk",
+            "save",
+            &format!("backup create {}", bundle.display()),
+            "add Call mum",
+            &format!("backup restore {}", bundle.display()),
+            "n",
+            &format!("backup restore {} --yes", bundle.display()),
+            "list",
+            "exit",
+        ],
+    );
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    let printed = text(&output.stdout);
+    assert!(printed.contains("Nothing was restored"), "{}", printed);
+    assert!(
+        printed.contains("Restored tasks, archive, trash, history from"),
+        "{}",
+        printed
+    );
+    assert!(!printed.contains("2. Call mum"), "{}", printed);
+
+    // The list read back from the backup is what the session saves
+    let tasks = saved_tasks(&file);
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0]["description"], "Buy milk");
+    assert_eq!(
+        files_in(&dir),
+        [
+            "saved.backup.json",
+            "tasks.json",
+            "tasks.json.pre-restore",
+            "tasks.log.json"
+        ]
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn tasks_added_since_the_last_save_survive_a_restore() {
+    let dir = scratch_dir("backup-unsaved");
+    let file = dir.join("tasks.json");
+    let bundle = dir.join("b.json");
+    let output = session(
+        &file,
+        &[
+            "add one",
+            "save",
+            &format!("backup create {}", bundle.display()),
+            "add two",
+            "add three",
+            &format!("backup restore {} --yes", bundle.display()),
+            "exit",
+        ],
+    );
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert_eq!(saved_tasks(&file).len(), 1);
+
+    // The backup of what the restore replaced has them
+    let previous = dir.join("tasks.json.pre-restore");
+    let saved: Value = serde_json::from_str(&fs::read_to_string(&previous).unwrap()).unwrap();
+    let descriptions: Vec<&str> = saved["sections"]["tasks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|task| task["description"].as_str().unwrap())
+        .collect();
+    assert_eq!(descriptions, ["one", "two", "three"]);
+
+    let restore = format!("{}", previous.display());
+    let output = one_shot(&file, &["backup", "restore", &restore, "--yes"]);
+    assert!(output.status.success(), "{}", text(&output.stderr));
+    assert_eq!(saved_tasks(&file).len(), 3);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Reminders are for someone at a terminal, not for a piped session
+#[test]
+fn piped_sessions_start_without_reminders() {
+    let dir = scratch_dir("reminders");
+    let file = dir.join("tasks.json");
+    assert!(one_shot(&file, &["add", "Pay rent"]).status.success());
+    assert!(
+        one_shot(&file, &["due", "1", "2020-01-01"])
+            .status
+            .success()
+    );
+    let output = session(&file, &["exit"]);
+    assert!(output.status.success());
+    let stdout = text(&output.stdout);
+    assert!(
+        !stdout.contains("overdue") && !stdout.contains("Pay rent"),
+        "{}",
+        stdout
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
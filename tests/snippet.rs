@@ -0,0 +1,187 @@
+use std::fs;
+
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::outcome::{CommandOutcome, execute};
+use rust_todo_cli::snippet::{expand, placeholders};
+use rust_todo_cli::todo::{Storable, TodoError, TodoList};
+
+fn values(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+const PR: &str = "Review PR #{num} for {repo}";
+
+#[test]
+fn placeholders_are_filled_in_from_the_values() {
+    let text = expand(PR, &values(&[("repo", "todo-cli"), ("num", "482")])).unwrap();
+    assert_eq!(text, "Review PR #482 for todo-cli");
+    assert_eq!(placeholders(PR).unwrap(), ["num", "repo"]);
+    assert_eq!(expand("Plain", &[]).unwrap(), "Plain");
+}
+
+#[test]
+fn a_placeholder_used_twice_takes_one_value() {
+    let text = "{name} called; call {name} back";
+    assert_eq!(placeholders(text).unwrap(), ["name"]);
+    let filled = expand(text, &values(&[("name", "Ann")])).unwrap();
+    assert_eq!(filled, "Ann called; call Ann back");
+    // The later of two values given for a name wins
+    let filled = expand("{a}", &values(&[("a", "1"), ("a", "2")])).unwrap();
+    assert_eq!(filled, "2");
+}
+
+#[test]
+fn missing_values_are_named_along_with_every_placeholder() {
+    let error = expand(PR, &values(&[("num", "482")])).unwrap_err();
+    let TodoError::MissingPlaceholders { missing, needed } = &error else {
+        panic!("{:?}", error);
+    };
+    assert_eq!(missing, &["repo"]);
+    assert_eq!(needed, &["num", "repo"]);
+    assert_eq!(
+        error.to_string(),
+        "Missing a value for repo. This snippet takes: num=… repo=…"
+    );
+    let error = expand(PR, &[]).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .starts_with("Missing values for num, repo")
+    );
+}
+
+#[test]
+fn a_value_for_no_placeholder_is_an_error() {
+    let given = values(&[("num", "1"), ("repo", "a"), ("rpeo", "b")]);
+    let error = expand(PR, &given).unwrap_err();
+    assert!(
+        matches!(&error, TodoError::UnknownPlaceholder { key, .. } if key == "rpeo"),
+        "{:?}",
+        error
+    );
+    let error = expand("Plain", &values(&[("x", "1")])).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "This snippet has no placeholder x. It takes: no values"
+    );
+}
+
+#[test]
+fn doubled_braces_are_literal_and_stray_ones_rejected() {
+    let text = "Fill {{ {field} }} in {{json}}";
+    assert_eq!(placeholders(text).unwrap(), ["field"]);
+    let filled = expand(text, &values(&[("field", "id")])).unwrap();
+    assert_eq!(filled, "Fill { id } in {json}");
+    // A value with braces in it goes in as it is
+    let filled = expand("{a}", &values(&[("a", "{b}")])).unwrap();
+    assert_eq!(filled, "{b}");
+
+    for text in ["a {b", "a } b", "{}", "{two words}", "{1st}"] {
+        assert!(
+            matches!(placeholders(text), Err(TodoError::InvalidSnippet(_))),
+            "{:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn snippet_commands_parse() {
+    assert_eq!(
+        parse_command("snippet save PR \"Review PR #{num} for {repo}\"").unwrap(),
+        Command::SnippetSave("pr".to_string(), PR.to_string())
+    );
+    assert_eq!(parse_command("snippets").unwrap(), Command::SnippetList);
+    assert_eq!(
+        parse_command("snippet delete @pr").unwrap(),
+        Command::SnippetDelete("pr".to_string())
+    );
+    assert!(parse_command("snippet save pr").is_err());
+    assert!(parse_command("snippet save pr a {b").is_err());
+    assert!(parse_command("snippet save 1st text").is_err());
+    assert!(parse_command("snippet save pr {x}").unwrap().mutates());
+}
+
+#[test]
+fn add_at_a_snippet_takes_key_value_words() {
+    assert_eq!(
+        parse_command("add @pr num=482 repo=todo cli").unwrap(),
+        Command::AddSnippet(
+            "pr".to_string(),
+            values(&[("num", "482"), ("repo", "todo cli")]),
+            false
+        )
+    );
+    assert_eq!(
+        parse_command("add @standup --no-duplicates").unwrap(),
+        Command::AddSnippet("standup".to_string(), Vec::new(), true)
+    );
+    // A context followed by words is still a description
+    assert_eq!(
+        parse_command("add @home Buy milk").unwrap(),
+        Command::Add(vec!["@home Buy milk".to_string()], false)
+    );
+}
+
+#[test]
+fn adding_from_a_snippet_adds_the_expanded_task() {
+    let mut todo = TodoList::new();
+    todo.save_snippet("pr", PR).unwrap();
+    let command = parse_command("add @pr num=482 repo=todo-cli").unwrap();
+    let Ok(CommandOutcome::Added(added)) = execute(&mut todo, command) else {
+        panic!("adds a task");
+    };
+    assert_eq!(added.added, [1]);
+    assert_eq!(todo[0].description, "Review PR #482 for todo-cli");
+
+    let command = parse_command("add @pr num=483").unwrap();
+    let error = execute(&mut todo, command).unwrap_err();
+    assert_eq!(error.code(), "MissingPlaceholders");
+    let command = parse_command("add @nope").unwrap();
+    let error = execute(&mut todo, command).unwrap_err();
+    assert!(matches!(error, TodoError::UnknownSnippet(name) if name == "nope"));
+    assert_eq!(todo.len(), 1);
+}
+
+#[test]
+fn snippets_survive_saving_and_merging() {
+    let dir = std::env::temp_dir().join(format!("rust-todo-cli-snippets-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json");
+    let path = path.to_str().unwrap();
+
+    let mut todo = TodoList::new();
+    assert!(!todo.save_snippet("pr", PR).unwrap());
+    assert!(todo.save_snippet("PR", PR).unwrap());
+    todo.save(path).unwrap();
+    let loaded = TodoList::load(path).unwrap();
+    assert_eq!(loaded.snippets(), todo.snippets());
+
+    // Deleting the last one makes the file the bare array again
+    assert_eq!(todo.delete_snippet("@pr").unwrap(), PR);
+    assert!(matches!(
+        todo.delete_snippet("pr"),
+        Err(TodoError::UnknownSnippet(_))
+    ));
+    todo.save(path).unwrap();
+    assert!(
+        fs::read_to_string(path)
+            .unwrap()
+            .trim_start()
+            .starts_with('[')
+    );
+    fs::remove_dir_all(dir).unwrap();
+
+    let mut base = TodoList::new();
+    base.save_snippet("pr", PR).unwrap();
+    let mut ours = base.clone();
+    ours.delete_snippet("pr").unwrap();
+    let mut theirs = base.clone();
+    theirs.save_snippet("call", "Call {name}").unwrap();
+    ours.merge(&base, theirs);
+    let names: Vec<&str> = ours.snippets().keys().map(String::as_str).collect();
+    assert_eq!(names, ["call"]);
+}
@@ -0,0 +1,139 @@
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::todo::{Selector, Status, TaskIndex, TodoError};
+
+#[test]
+fn every_synonym_parses_to_its_status() {
+    let cases = [
+        ("todo", Status::Todo),
+        ("to-do", Status::Todo),
+        ("open", Status::Todo),
+        ("waiting", Status::Waiting),
+        ("wait", Status::Waiting),
+        ("someday", Status::Waiting),
+        ("in-progress", Status::InProgress),
+        ("inprogress", Status::InProgress),
+        ("doing", Status::InProgress),
+        ("done", Status::Completed),
+        ("completed", Status::Completed),
+        ("complete", Status::Completed),
+        ("finished", Status::Completed),
+        ("cancelled", Status::Cancelled),
+        ("canceled", Status::Cancelled),
+        ("wontdo", Status::Cancelled),
+        ("won't-do", Status::Cancelled),
+    ];
+    assert_eq!(cases.len(), Status::SYNONYMS.len());
+    for (word, status) in cases {
+        assert_eq!(word.parse::<Status>().unwrap(), status, "{}", word);
+    }
+}
+
+#[test]
+fn case_spaces_and_punctuation_are_forgiven() {
+    let cases = [
+        ("Done!", Status::Completed),
+        ("  DONE  ", Status::Completed),
+        ("done.", Status::Completed),
+        ("\"in progress\"", Status::InProgress),
+        ("In Progress", Status::InProgress),
+        ("in_progress", Status::InProgress),
+        ("in   progress", Status::InProgress),
+        ("'todo'", Status::Todo),
+        ("To Do?", Status::Todo),
+        ("Won't do", Status::Cancelled),
+        ("Finished!!", Status::Completed),
+    ];
+    for (text, status) in cases {
+        assert_eq!(text.parse::<Status>().unwrap(), status, "{:?}", text);
+    }
+}
+
+#[test]
+fn near_misses_suggest_the_closest_status() {
+    let cases = [
+        ("dnoe", Some("done")),
+        ("don", Some("done")),
+        ("tood", Some("todo")),
+        ("in-progres", Some("in-progress")),
+        ("in progess", Some("in-progress")),
+        ("doign", Some("in-progress")),
+        ("cancled", Some("cancelled")),
+        ("finshed", Some("done")),
+        ("Opne!", Some("todo")),
+        ("later", None),
+        ("x", None),
+        ("", None),
+    ];
+    for (text, suggestion) in cases {
+        let error = text.parse::<Status>().unwrap_err();
+        assert!(matches!(error, TodoError::InvalidStatus(_)), "{:?}", text);
+        assert_eq!(Status::suggest(text), suggestion, "{:?}", text);
+        let message = error.to_string();
+        assert!(message.contains(&Status::names()), "{}", message);
+        if let Some(suggestion) = suggestion {
+            assert!(message.contains(suggestion), "{}", message);
+            let json = serde_json::to_value(&error).unwrap();
+            assert_eq!(json["suggestion"], suggestion);
+        }
+    }
+}
+
+#[test]
+fn names_come_from_the_synonym_table() {
+    assert_eq!(
+        Status::names(),
+        "todo, waiting, in-progress, done, cancelled"
+    );
+    for status in Status::ALL {
+        assert_eq!(status.keyword().parse::<Status>().unwrap(), status);
+        assert!(
+            status
+                .synonyms()
+                .all(|word| word.parse::<Status>().unwrap() == status)
+        );
+    }
+    let done: Vec<&str> = Status::Completed.synonyms().collect();
+    assert_eq!(done, ["done", "completed", "complete", "finished"]);
+}
+
+#[test]
+fn update_takes_statuses_of_more_than_one_word() {
+    let three = Selector::Index(TaskIndex { task: 3, sub: None });
+    for input in [
+        "update 3 in progress",
+        "update 3 \"in progress\"",
+        "update 3 Doing!",
+    ] {
+        let Command::Update(selector, status, false) = parse_command(input).unwrap() else {
+            panic!("{} is not an update", input);
+        };
+        assert_eq!(selector, three, "{}", input);
+        assert_eq!(
+            status.parse::<Status>().unwrap(),
+            Status::InProgress,
+            "{}",
+            input
+        );
+    }
+    assert_eq!(
+        parse_command("update all in progress").unwrap(),
+        Command::UpdateAll(None, Status::InProgress, false)
+    );
+    assert_eq!(
+        parse_command("update all todo done").unwrap(),
+        Command::UpdateAll(Some(Status::Todo), Status::Completed, false)
+    );
+    assert_eq!(
+        parse_command("update open finished").unwrap(),
+        Command::UpdateAll(Some(Status::Todo), Status::Completed, false)
+    );
+    // A quoted description is still a task, even one that reads as a status
+    assert_eq!(
+        parse_command("update \"open\" done").unwrap(),
+        Command::Update(
+            Selector::Text("open".to_string()),
+            "done".to_string(),
+            false
+        )
+    );
+}
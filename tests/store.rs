@@ -0,0 +1,461 @@
+mod common;
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use common::scratch_dir;
+use rust_todo_cli::settings::Settings;
+use rust_todo_cli::store::{ArchiveStats, Store, Watch};
+use rust_todo_cli::todo::{self, Status, TodoError, TodoList};
+
+#[test]
+fn tasks_and_history_survive_a_round_trip() {
+    let dir = scratch_dir("round-trip");
+    let store = Store::in_dir(&dir);
+    let mut todo = TodoList::new();
+    todo.add_tasks("Buy milk".to_string()).unwrap();
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::InProgress, false)
+        .unwrap();
+    store.save(&todo).unwrap();
+
+    let mut loaded = store.load().unwrap();
+    store.load_history(&mut loaded).unwrap();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded.get_task(1).unwrap().status, Status::InProgress);
+    assert_eq!(loaded.log().len(), todo.log().len());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn missing_side_files_are_empty() {
+    let dir = scratch_dir("missing");
+    let store = Store::in_dir(&dir);
+    assert!(store.load_trash().unwrap().is_empty());
+    assert!(store.load_archive().unwrap().is_empty());
+    assert!(store.load().is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn trash_keeps_removed_tasks_in_order() {
+    let dir = scratch_dir("trash");
+    let store = Store::in_dir(&dir);
+    let mut todo = TodoList::new();
+    todo.add_tasks("First".to_string()).unwrap();
+    todo.add_tasks("Second".to_string()).unwrap();
+
+    let first = todo.remove_task(todo.task_ref(1).unwrap()).unwrap();
+    assert_eq!(store.move_to_trash(vec![first]).unwrap(), 1);
+    let second = todo.remove_task(todo.task_ref(1).unwrap()).unwrap();
+    assert_eq!(store.move_to_trash(vec![second]).unwrap(), 2);
+
+    let trash = store.load_trash().unwrap();
+    assert_eq!(trash.get_task(1).unwrap().description, "First");
+    assert_eq!(trash.get_task(2).unwrap().description, "Second");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn archive_collects_tasks() {
+    let dir = scratch_dir("archive");
+    let store = Store::in_dir(&dir);
+    let mut todo = TodoList::new();
+    todo.add_tasks("Ship it".to_string()).unwrap();
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+
+    store.archive(todo.archive_completed()).unwrap();
+    assert!(todo.is_empty());
+    assert_eq!(store.load_archive().unwrap().len(), 1);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn archive_stats_count_the_file_without_loading_it() {
+    let dir = scratch_dir("archive-stats");
+    let store = Store::in_dir(&dir);
+    assert_eq!(store.archive_stats().unwrap(), ArchiveStats::default());
+
+    let tasks = r#"[
+        {"description": "Ship it", "status": "Completed", "completed_at": 2000,
+         "subtasks": [{"description": "Tag"}, {"description": "Upload"}]},
+        {"description": "Drop it", "status": "Cancelled"},
+        {"description": "Moved by hand", "status": "Someday"},
+        {"description": "Old one", "status": "Completed", "completed_at": 1000}
+    ]"#;
+    let expected = |bytes: usize| ArchiveStats {
+        tasks: 4,
+        subtasks: 2,
+        completed: 2,
+        cancelled: 1,
+        other: 1,
+        first_completed: Some(1000),
+        last_completed: Some(2000),
+        bytes: bytes as u64,
+    };
+    fs::write(&store.archive, tasks).unwrap();
+    assert_eq!(store.archive_stats().unwrap(), expected(tasks.len()));
+    // The object layout, whatever order its keys come in
+    let object = format!(
+        r#"{{"templates": {{"x": ["a"]}}, "tasks": {}, "filters": {{}}}}"#,
+        tasks
+    );
+    fs::write(&store.archive, &object).unwrap();
+    assert_eq!(store.archive_stats().unwrap(), expected(object.len()));
+    // Counting the loaded copy gives the same
+    assert_eq!(store.load_archive().unwrap().len(), 4);
+    assert_eq!(store.archive_stats().unwrap(), expected(object.len()));
+
+    fs::write(&store.archive, "[{\"description\": 1}]").unwrap();
+    assert!(matches!(
+        store.archive_stats(),
+        Err(TodoError::SerializationError(_))
+    ));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn the_archive_is_read_once_until_it_changes() {
+    let dir = scratch_dir("archive-cache");
+    let store = Store::in_dir(&dir);
+    let mut todo = TodoList::new();
+    for description in ["Ship it", "Tag it"] {
+        todo.add_tasks(description.to_string()).unwrap();
+        let task = todo.task_ref(todo.len()).unwrap();
+        todo.update_task_status(task, Status::Completed, false)
+            .unwrap();
+    }
+    store.archive(todo.archive_completed()).unwrap();
+
+    let first = store.load_archive().unwrap();
+    let again = store.load_archive().unwrap();
+    assert!(std::rc::Rc::ptr_eq(&first, &again));
+    assert_eq!(first.len(), 2);
+
+    // A change made elsewhere is read afresh
+    fs::write(&store.archive, r#"[{"description": "Edited elsewhere"}]"#).unwrap();
+    let edited = store.load_archive().unwrap();
+    assert_eq!(edited.len(), 1);
+    assert_eq!(edited.get_task(1).unwrap().description, "Edited elsewhere");
+    assert_eq!(store.archive_stats().unwrap().tasks, 1);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn unreadable_files_keep_their_errors() {
+    let dir = scratch_dir("errors");
+    let store = Store::in_dir(&dir);
+    match store.load() {
+        Err(TodoError::FileError(error)) => assert_eq!(error.kind(), io::ErrorKind::NotFound),
+        other => panic!(
+            "expected a file error, got {:?}",
+            other.map(|list| list.len())
+        ),
+    }
+
+    fs::write(&store.tasks, "[{\"description\": ").unwrap();
+    assert!(matches!(
+        store.load(),
+        Err(TodoError::SerializationError(_))
+    ));
+    fs::write(&store.tasks, "  \n").unwrap();
+    assert!(matches!(
+        store.load(),
+        Err(TodoError::SerializationError(_))
+    ));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn compact_files_load_like_indented_ones() {
+    let dir = scratch_dir("compact");
+    let store = Store {
+        settings: Settings {
+            compact_files: true,
+            ..Settings::default()
+        },
+        ..Store::in_dir(&dir)
+    };
+    let mut todo = TodoList::new();
+    todo.add_tasks("Buy milk".to_string()).unwrap();
+    todo.add_tasks("Call mom".to_string()).unwrap();
+    store.save(&todo).unwrap();
+
+    let json = fs::read_to_string(&store.tasks).unwrap();
+    assert!(!json.contains('\n'));
+    let loaded = store.load().unwrap();
+    assert_eq!(loaded.len(), 2);
+    assert_eq!(loaded.get_task(2).unwrap().description, "Call mom");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn watching_notices_changes_made_elsewhere() {
+    let dir = scratch_dir("watch");
+    let store = Store::in_dir(&dir);
+    let mut watch = Watch::new(&store.tasks);
+    assert!(!watch.changed());
+
+    let mut todo = TodoList::new();
+    todo.add_tasks("Buy milk".to_string()).unwrap();
+    store.save(&todo).unwrap();
+    assert!(watch.changed());
+    watch.mark();
+    assert!(!watch.changed());
+
+    // Writing the same contents again is no change
+    let json = fs::read_to_string(&store.tasks).unwrap();
+    thread::sleep(Duration::from_millis(20));
+    fs::write(&store.tasks, &json).unwrap();
+    assert!(!watch.changed());
+
+    fs::write(&store.tasks, json.replace("Buy milk", "Buy bread")).unwrap();
+    assert!(watch.changed());
+    assert!(watch.changed());
+    watch.mark();
+    assert!(!watch.changed());
+
+    fs::remove_file(&store.tasks).unwrap();
+    assert!(watch.changed());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// A list of `count` tasks saved in the store's file
+fn saved_list(store: &Store, count: usize) -> TodoList {
+    let mut todo = TodoList::new();
+    for n in 1..=count {
+        todo.add_tasks(format!("Task {}", n)).unwrap();
+    }
+    store.save(&todo).unwrap();
+    todo
+}
+
+#[test]
+fn an_empty_list_is_not_saved_over_a_full_file() {
+    let dir = scratch_dir("wipe-refused");
+    let store = Store::in_dir(&dir);
+    saved_list(&store, 12);
+    let before = fs::read(&store.tasks).unwrap();
+
+    let empty = TodoList::new();
+    assert_eq!(store.would_wipe(&empty), Some(12));
+    assert!(matches!(store.save(&empty), Err(TodoError::WouldWipe(12))));
+    assert_eq!(fs::read(&store.tasks).unwrap(), before);
+    assert!(!PathBuf::from(store.wipe_backup()).exists());
+    assert_eq!(TodoError::WouldWipe(12).code(), "WouldWipe");
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_forced_wipe_keeps_a_copy_of_the_file_first() {
+    let dir = scratch_dir("wipe-forced");
+    let store = Store::in_dir(&dir);
+    saved_list(&store, 12);
+    let before = fs::read(&store.tasks).unwrap();
+
+    store.save_anyway(&TodoList::new()).unwrap();
+    assert!(store.load().unwrap().is_empty());
+    assert_eq!(fs::read(store.wipe_backup()).unwrap(), before);
+
+    // The same goes for a store made to force every save
+    saved_list(&store, 11);
+    let forced = Store {
+        force: true,
+        ..store.clone()
+    };
+    forced.save(&TodoList::new()).unwrap();
+    let backup = Store {
+        tasks: store.wipe_backup(),
+        ..store.clone()
+    };
+    assert_eq!(backup.load().unwrap().len(), 11);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn small_lists_and_lists_with_tasks_save_as_before() {
+    let dir = scratch_dir("wipe-small");
+    let store = Store::in_dir(&dir);
+    // Up to the guard an empty list saves without a word
+    saved_list(&store, 10);
+    store.save(&TodoList::new()).unwrap();
+    assert!(store.load().unwrap().is_empty());
+
+    // A list with any task in it may replace any file
+    saved_list(&store, 30);
+    let mut one = TodoList::new();
+    one.add_tasks("Buy milk".to_string()).unwrap();
+    store.save(&one).unwrap();
+    assert_eq!(store.load().unwrap().len(), 1);
+
+    // A guard of 0 turns the check off
+    saved_list(&store, 30);
+    let unguarded = Store {
+        wipe_guard: 0,
+        ..store.clone()
+    };
+    unguarded.save(&TodoList::new()).unwrap();
+    assert!(!PathBuf::from(store.wipe_backup()).exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+// Someone else's version of the file, written straight to it
+fn written_elsewhere(store: &Store, description: &str) {
+    let mut theirs = TodoList::new();
+    theirs.add_tasks(description.to_string()).unwrap();
+    todo::Storable::save(&theirs, &store.tasks).unwrap();
+}
+
+#[test]
+fn a_file_changed_since_it_was_read_is_not_saved_over() {
+    let dir = scratch_dir("concurrent");
+    let store = Store::in_dir(&dir);
+    let mut ours = saved_list(&store, 2);
+    ours.add_tasks("Ours".to_string()).unwrap();
+    written_elsewhere(&store, "Theirs");
+    let theirs = fs::read(&store.tasks).unwrap();
+    assert!(store.changed_since_read());
+
+    let error = store.save(&ours).unwrap_err();
+    assert!(matches!(error, TodoError::ConcurrentModification(ref path) if *path == store.tasks));
+    assert_eq!(error.code(), "ConcurrentModification");
+    assert!(error.to_string().contains("save --force"), "{}", error);
+    assert_eq!(fs::read(&store.tasks).unwrap(), theirs);
+    // Forcing past the wipe guard is no go-ahead for this
+    assert!(store.save_anyway(&ours).is_err());
+    assert_eq!(fs::read(&store.tasks).unwrap(), theirs);
+
+    // Kept aside, ours is not lost either
+    let aside = store.save_aside(&ours).unwrap();
+    assert_eq!(aside, store.unsaved_copy());
+    let kept: TodoList = todo::Storable::load(&aside).unwrap();
+    assert_eq!(kept.len(), 3);
+    assert_eq!(fs::read(&store.tasks).unwrap(), theirs);
+
+    // Once their version is read, as a merge does, saving goes ahead
+    let mut merged = store.load().unwrap();
+    merged.add_tasks("Ours".to_string()).unwrap();
+    store.save(&merged).unwrap();
+    assert!(!store.changed_since_read());
+    let saved = store.load().unwrap();
+    let descriptions: Vec<&str> = saved
+        .tasks()
+        .iter()
+        .map(|t| t.description.as_str())
+        .collect();
+    assert_eq!(descriptions, ["Theirs", "Ours"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn marking_the_file_read_lets_the_list_replace_it() {
+    let dir = scratch_dir("concurrent-force");
+    let store = Store::in_dir(&dir);
+    let ours = saved_list(&store, 2);
+    written_elsewhere(&store, "Theirs");
+
+    store.mark_read();
+    assert!(!store.changed_since_read());
+    store.save(&ours).unwrap();
+    assert_eq!(store.load().unwrap().len(), 2);
+    // Another write after that is caught again
+    written_elsewhere(&store, "Later");
+    assert!(store.save(&ours).is_err());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_file_created_after_it_was_found_missing_is_not_saved_over() {
+    let dir = scratch_dir("concurrent-created");
+    let store = Store::in_dir(&dir);
+    assert!(store.load().is_err());
+    written_elsewhere(&store, "Theirs");
+    let mut ours = TodoList::new();
+    ours.add_tasks("Ours".to_string()).unwrap();
+    assert!(matches!(
+        store.save(&ours),
+        Err(TodoError::ConcurrentModification(_))
+    ));
+
+    // A store that never read the file has nothing to go by
+    let other = scratch_dir("concurrent-unread");
+    let unread = Store::in_dir(&other);
+    written_elsewhere(&unread, "Theirs");
+    unread.save(&ours).unwrap();
+    fs::remove_dir_all(dir).unwrap();
+    fs::remove_dir_all(other).unwrap();
+}
+
+#[test]
+fn a_file_that_failed_to_load_is_not_saved_over() {
+    let dir = scratch_dir("unreadable");
+    let store = Store::in_dir(&dir);
+    fs::write(&store.tasks, "{bad").unwrap();
+    assert!(store.load().is_err());
+    assert!(store.is_unreadable());
+    let mut ours = TodoList::new();
+    ours.add_tasks("Ours".to_string()).unwrap();
+
+    let error = store.save(&ours).unwrap_err();
+    assert!(matches!(error, TodoError::UnreadableTasks(ref path) if *path == store.tasks));
+    assert_eq!(error.code(), "UnreadableTasks");
+    assert_eq!(fs::read_to_string(&store.tasks).unwrap(), "{bad");
+    assert!(!PathBuf::from(store.unreadable_copy()).exists());
+
+    // Mended elsewhere meanwhile, it is a change not to save over either
+    fs::write(&store.tasks, "[]").unwrap();
+    assert!(matches!(
+        store.save(&ours),
+        Err(TodoError::ConcurrentModification(_))
+    ));
+    // Once it loads again, it is an ordinary file
+    store.load().unwrap();
+    assert!(!store.is_unreadable());
+    store.save(&ours).unwrap();
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn a_go_ahead_replaces_an_unreadable_file_after_copying_it() {
+    let dir = scratch_dir("unreadable-forced");
+    let store = Store::in_dir(&dir);
+    let mut ours = TodoList::new();
+    ours.add_tasks("Ours".to_string()).unwrap();
+
+    // Forced, as by --force
+    fs::write(&store.tasks, "{bad").unwrap();
+    assert!(store.load().is_err());
+    store.save_anyway(&ours).unwrap();
+    assert_eq!(fs::read_to_string(store.unreadable_copy()).unwrap(), "{bad");
+    assert_eq!(store.load().unwrap().len(), 1);
+    assert!(!store.is_unreadable());
+
+    // Marked read, as by `save --force`
+    fs::write(&store.tasks, "{worse").unwrap();
+    assert!(store.load().is_err());
+    store.mark_read();
+    store.save(&ours).unwrap();
+    assert_eq!(
+        fs::read_to_string(store.unreadable_copy()).unwrap(),
+        "{worse"
+    );
+    assert_eq!(store.load().unwrap().len(), 1);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn rewriting_the_same_contents_is_no_change() {
+    let dir = scratch_dir("concurrent-same");
+    let store = Store::in_dir(&dir);
+    let ours = saved_list(&store, 2);
+    // Touched by a sync client that wrote back what was there
+    let contents = fs::read(&store.tasks).unwrap();
+    thread::sleep(Duration::from_millis(20));
+    fs::write(&store.tasks, contents).unwrap();
+    assert!(!store.changed_since_read());
+    store.save(&ours).unwrap();
+    fs::remove_dir_all(dir).unwrap();
+}
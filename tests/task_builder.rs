@@ -0,0 +1,75 @@
+use rust_todo_cli::date::Date;
+use rust_todo_cli::todo::{Recurrence, Status, Task, TodoError, TodoList};
+
+#[test]
+fn builder_sets_every_option() {
+    let due = Date::new(2025, 6, 1).unwrap();
+    let task = Task::builder("Water plants @home")
+        .status(Status::InProgress)
+        .due(due)
+        .recurrence(Recurrence::parse("weekly").unwrap())
+        .tag("#Garden")
+        .tag("garden")
+        .build()
+        .unwrap();
+
+    assert_eq!(task.description, "Water plants @home");
+    assert_eq!(task.status, Status::InProgress);
+    assert_eq!(task.due, Some(due));
+    assert_eq!(task.recurrence.unwrap().to_string(), "every week");
+    assert_eq!(task.tags, ["garden"]);
+    assert_eq!(task.contexts, ["home"]);
+    assert_eq!(task.id, 0);
+}
+
+#[test]
+fn completed_tasks_get_a_completion_time() {
+    let task = Task::builder("Ship it")
+        .status(Status::Completed)
+        .build()
+        .unwrap();
+    assert!(task.completed_at.is_some());
+}
+
+#[test]
+fn builder_rejects_empty_descriptions() {
+    assert!(matches!(
+        Task::builder("  ").build(),
+        Err(TodoError::EmptyDescription)
+    ));
+    assert!(matches!(
+        Task::builder("+taxes @desk").build(),
+        Err(TodoError::EmptyDescription)
+    ));
+}
+
+#[test]
+fn builder_rejects_empty_tags() {
+    assert!(matches!(
+        Task::builder("Buy milk").tag("#").build(),
+        Err(TodoError::InvalidTag(tag)) if tag == "#"
+    ));
+}
+
+#[test]
+fn closed_tasks_cannot_repeat() {
+    let daily = Recurrence::parse("daily").unwrap();
+    for status in [Status::Completed, Status::Cancelled] {
+        let built = Task::builder("Stretch")
+            .recurrence(daily)
+            .status(status)
+            .build();
+        assert!(matches!(built, Err(TodoError::ConflictingOptions(_))));
+    }
+}
+
+#[test]
+fn lists_take_built_tasks() {
+    let mut todo = TodoList::new();
+    todo.add_tasks("Buy milk".to_string()).unwrap();
+    let task = Task::builder("Call mum").tag("family").build().unwrap();
+
+    assert_eq!(todo.add(task), 2);
+    assert_eq!(todo[1].tags, ["family"]);
+    assert_ne!(todo[0].id, todo[1].id);
+}
@@ -0,0 +1,218 @@
+mod common;
+
+use common::list_of;
+use rust_todo_cli::todo::{ListFilter, Recurrence, Selector, Status, TaskIndex, TodoError};
+
+fn containing(text: &str) -> ListFilter {
+    ListFilter {
+        text: vec![text.to_string()],
+        ..ListFilter::default()
+    }
+}
+
+#[test]
+fn a_ref_keeps_its_task_when_an_earlier_one_is_removed() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Book dentist"]);
+    let dentist = todo.task_ref(3).unwrap();
+    todo.remove_task(todo.task_ref(1).unwrap()).unwrap();
+
+    todo.update_task_status(dentist, Status::Completed, false)
+        .unwrap();
+    assert_eq!(todo[1].description, "Book dentist");
+    assert_eq!(todo[1].status, Status::Completed);
+    assert_eq!(todo[0].status, Status::Todo);
+    // Messages still show the number it was looked up by
+    assert_eq!(dentist.index(), 3);
+    assert_eq!(dentist.to_string(), "3");
+}
+
+#[test]
+fn a_ref_to_a_removed_task_changes_nothing() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    let milk = todo.task_ref(1).unwrap();
+    todo.remove_task(milk).unwrap();
+
+    // Call mum is number 1 now, but the ref does not move on to it
+    assert!(matches!(
+        todo.update_task_status(milk, Status::Completed, false),
+        Err(TodoError::TaskGone(1))
+    ));
+    assert!(matches!(
+        todo.remove_task(milk),
+        Err(TodoError::TaskGone(1))
+    ));
+    assert!(todo.get(milk).is_err());
+    assert_eq!(todo[0].status, Status::Todo);
+    assert_eq!(todo.len(), 1);
+}
+
+#[test]
+fn refs_taken_up_front_survive_removals_in_between() {
+    let mut todo = list_of(&["a", "b", "c", "d", "e"]);
+    let refs: Vec<_> = (1..=5).map(|n| todo.task_ref(n).unwrap()).collect();
+    // Removing by the numbers shown would take out a, c and e instead
+    for task in &refs[..3] {
+        todo.remove_task(*task).unwrap();
+    }
+    let left: Vec<&str> = todo
+        .tasks()
+        .iter()
+        .map(|t| t.description.as_str())
+        .collect();
+    assert_eq!(left, ["d", "e"]);
+    todo.set_description(refs[4], "e, renamed".to_string())
+        .unwrap();
+    assert_eq!(todo[1].description, "e, renamed");
+}
+
+#[test]
+fn a_view_only_hands_out_the_tasks_it_shows() {
+    let mut todo = list_of(&["Buy milk at home", "Call mum", "Fix sink at home"]);
+    let home = containing("home");
+
+    let sink = todo.ref_in(&home, 3).unwrap();
+    assert_eq!(todo.get(sink).unwrap().description, "Fix sink at home");
+    // Row 2 of the view is task 3; task 2 is not in it at all
+    assert!(matches!(
+        todo.ref_in(&home, 2),
+        Err(TodoError::NotInView(2))
+    ));
+    assert!(matches!(
+        todo.ref_in(&home, 9),
+        Err(TodoError::IndexOutOfBound(9))
+    ));
+
+    todo.update_task_status(sink, Status::Completed, false)
+        .unwrap();
+    assert_eq!(todo[2].status, Status::Completed);
+    assert_eq!(todo[1].status, Status::Todo);
+}
+
+#[test]
+fn numbers_on_a_later_page_mean_the_same_tasks() {
+    let descriptions: Vec<String> = (1..=12)
+        .map(|n| match n % 3 {
+            0 => format!("Task {} for work", n),
+            _ => format!("Task {}", n),
+        })
+        .collect();
+    let descriptions: Vec<&str> = descriptions.iter().map(String::as_str).collect();
+    let mut todo = list_of(&descriptions);
+    let work = containing("work");
+
+    // Pages of two: the second one shows tasks 9 and 12
+    let pages: Vec<Vec<usize>> = todo
+        .filter(&work)
+        .chunks(2)
+        .map(|page| page.iter().map(|(n, _)| *n).collect())
+        .collect();
+    assert_eq!(pages, [vec![3, 6], vec![9, 12]]);
+
+    for &n in &pages[1] {
+        let task = todo.ref_in(&work, n).unwrap();
+        todo.update_task_status(task, Status::InProgress, false)
+            .unwrap();
+    }
+    let started: Vec<&str> = todo
+        .tasks()
+        .iter()
+        .filter(|task| task.status == Status::InProgress)
+        .map(|task| task.description.as_str())
+        .collect();
+    assert_eq!(started, ["Task 9 for work", "Task 12 for work"]);
+}
+
+#[test]
+fn a_view_ref_still_holds_once_the_task_leaves_the_view() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    let open = ListFilter {
+        status: Some(Status::Todo),
+        ..ListFilter::default()
+    };
+    let mum = todo.ref_in(&open, 2).unwrap();
+    todo.update_task_status(mum, Status::InProgress, false)
+        .unwrap();
+    assert!(matches!(
+        todo.ref_in(&open, 2),
+        Err(TodoError::NotInView(2))
+    ));
+    todo.add_tags(mum, &["family".to_string()]).unwrap();
+    assert_eq!(todo[1].tags, ["family"]);
+}
+
+#[test]
+fn refs_come_from_ids_and_descriptions_too() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Call plumber"]);
+    let id = todo[1].id;
+    let by_id = todo.ref_by_id(id).unwrap();
+    assert_eq!((by_id.id(), by_id.index()), (id, 2));
+    assert!(matches!(todo.ref_by_id(99), Err(TodoError::UnknownId(99))));
+
+    let milk = todo.ref_matching("MILK").unwrap();
+    assert_eq!(milk.index(), 1);
+    assert!(matches!(
+        todo.ref_matching("call"),
+        Err(TodoError::AmbiguousMatch(_, 2))
+    ));
+    assert!(matches!(
+        todo.ref_matching("dentist"),
+        Err(TodoError::NoMatch(_))
+    ));
+
+    // After a removal the ID finds the task at its new number
+    todo.remove_task(milk).unwrap();
+    assert_eq!(todo.ref_by_id(id).unwrap().index(), 1);
+}
+
+#[test]
+fn selecting_a_task_turns_down_subtasks() {
+    let mut todo = list_of(&["Plan trip", "Pack"]);
+    todo.add_subtask(todo.task_ref(1).unwrap(), "Book hotel".to_string())
+        .unwrap();
+
+    let task = todo
+        .select_task(&Selector::Text("pack".to_string()))
+        .unwrap();
+    assert_eq!(task.index(), 2);
+    let sub = TaskIndex {
+        task: 1,
+        sub: Some(1),
+    };
+    assert!(matches!(
+        todo.select_task(&Selector::Index(sub)),
+        Err(TodoError::SubtaskNotAllowed(_))
+    ));
+    assert!(matches!(
+        todo.select_task(&Selector::Index(TaskIndex { task: 5, sub: None })),
+        Err(TodoError::IndexOutOfBound(5))
+    ));
+}
+
+#[test]
+fn bulk_status_changes_go_by_task_not_by_number() {
+    let mut todo = list_of(&["Water plants", "Buy milk", "Call mum"]);
+    todo.set_recurrence(
+        todo.task_ref(1).unwrap(),
+        Some(Recurrence::parse("daily").unwrap()),
+    )
+    .unwrap();
+    // Completing the recurring task adds its next occurrence at the end,
+    // which is not among the tasks that were to be completed
+    assert_eq!(
+        todo.update_all_status(Some(Status::Todo), Status::Completed, false),
+        3
+    );
+    assert_eq!(todo.len(), 4);
+    assert_eq!(todo[3].status, Status::Todo);
+}
+
+#[test]
+fn lookup_errors_serialize_with_their_values() {
+    let json = serde_json::to_value(TodoError::NotInView(2)).unwrap();
+    assert_eq!(json["error"], "NotInView");
+    assert_eq!(json["index"], 2);
+    let json = serde_json::to_value(TodoError::UnknownId(41)).unwrap();
+    assert_eq!(json["error"], "UnknownId");
+    assert_eq!(json["id"], 41);
+    assert_eq!(TodoError::TaskGone(1).code(), "TaskGone");
+}
@@ -0,0 +1,120 @@
+use rust_todo_cli::date::Date;
+use rust_todo_cli::listing;
+use rust_todo_cli::theme::{Color, Theme};
+use rust_todo_cli::todo::{Status, Task, TodoList};
+
+// Tasks without a creation time, so no age depends on when the test runs
+fn undated(description: &str) -> Task {
+    let mut task = Task::new(description.to_string()).unwrap();
+    task.created_at = None;
+    task
+}
+
+fn sample() -> TodoList {
+    let mut report = undated("Write report");
+    report.set_status(Status::InProgress);
+    let mut call = undated("Call mum");
+    call.set_status(Status::Cancelled);
+    let mut todo: TodoList = [
+        undated("Read https://example.com"),
+        undated("Reply"),
+        report,
+        call,
+    ]
+    .into_iter()
+    .collect();
+    todo.add_dependency(todo.task_ref(2).unwrap(), todo.task_ref(1).unwrap())
+        .unwrap();
+    todo
+}
+
+fn render(todo: &TodoList, theme: &Theme) -> String {
+    let today = Date::new(2025, 6, 1).unwrap();
+    let lines: Vec<String> = listing::rows(todo.list_tasks())
+        .map(|row| row.line(todo, theme, &row.task.description, None, today))
+        .collect();
+    lines.join("\n")
+}
+
+#[test]
+fn default_theme_draws_the_emoji_listing() {
+    let expected = "\
+⚪ 1. Read https://example.com [TODO] 🔗
+⚪ 2. Reply [TODO] 🔒
+🔵 3. Write report [IN-PROGRESS]
+🚫 4. Call mum [CANCELLED]";
+    assert_eq!(render(&sample(), &Theme::default()), expected);
+    assert_eq!(sample().to_string(), expected);
+}
+
+#[test]
+fn ascii_theme_draws_the_plain_listing() {
+    let expected = "\
+[ ] 1. Read https://example.com [TODO] [link]
+[ ] 2. Reply [TODO] [blocked]
+[~] 3. Write report [IN-PROGRESS]
+[-] 4. Call mum [CANCELLED]";
+    let listing = render(&sample(), &Theme::ascii());
+    assert_eq!(listing, expected);
+    assert!(listing.is_ascii());
+    assert_eq!(format!("{:#}", sample()), expected);
+    assert!(!Theme::ascii().emoji);
+}
+
+#[test]
+fn presets_are_found_by_name() {
+    assert_eq!(Theme::preset("default"), Some(Theme::default()));
+    assert_eq!(Theme::preset("ASCII"), Some(Theme::ascii()));
+    assert_eq!(Theme::preset("solarized"), None);
+}
+
+#[test]
+fn theme_section_overrides_the_preset_it_names() {
+    let text = r#"
+[general]
+todo = "ignored"
+
+[theme]
+# Starts from the ASCII markers
+preset = "ascii"
+todo = "o"
+in_progress = ">"
+separator = "="
+banner = ""
+completed_color = dim-green
+stale_color = "Magenta"
+"#;
+    let (theme, warnings) = Theme::parse(text);
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    assert_eq!(theme.status(Status::Todo), "o");
+    assert_eq!(theme.status(Status::InProgress), ">");
+    assert_eq!(theme.status(Status::Completed), "[x]");
+    assert_eq!(theme.separator, "=");
+    assert_eq!(theme.banner, "");
+    assert_eq!(theme.color(Status::Completed), Color::DimGreen);
+    assert_eq!(theme.stale_color, Color::Magenta);
+    assert!(!theme.emoji);
+}
+
+#[test]
+fn invalid_theme_values_keep_the_defaults_with_a_warning() {
+    let text = "\
+[theme]
+preset = solarized
+todo = \"\"
+todo_color = mauve
+emoji = maybe
+shape = round
+not a setting
+";
+    let (theme, warnings) = Theme::parse(text);
+    assert_eq!(theme, Theme::default());
+    assert_eq!(warnings.len(), 6, "{:?}", warnings);
+    assert!(warnings.iter().any(|warning| warning.contains("mauve")));
+    assert!(warnings.iter().any(|warning| warning.contains("shape")));
+}
+
+#[test]
+fn missing_theme_file_is_an_error() {
+    assert!(Theme::load("no/such/theme.toml").is_err());
+}
@@ -0,0 +1,98 @@
+mod common;
+
+use std::time::Duration;
+
+use common::list_of;
+use rust_todo_cli::date::Timestamp;
+use rust_todo_cli::todo::{Status, TodoError, TodoList};
+
+// 2025-07-01 09:00 UTC; every moment below is counted from it
+const START: Timestamp = 1_751_360_400;
+
+fn minutes(count: u64) -> Duration {
+    Duration::from_secs(count * 60)
+}
+
+fn start(todo: &mut TodoList, index: usize, at: Timestamp) -> Result<Option<usize>, TodoError> {
+    todo.start_timer(todo.task_ref(index)?, at)
+}
+
+#[test]
+fn a_session_runs_from_start_to_stop() {
+    let mut todo = list_of(&["Write report"]);
+    assert_eq!(start(&mut todo, 1, START).unwrap(), None);
+    assert_eq!(todo.running_timer(), Some(1));
+    // A running session counts towards the time spent so far
+    assert_eq!(todo[0].time_spent(START + 600), minutes(10));
+    assert_eq!(todo[0].accumulated, Duration::ZERO);
+
+    assert_eq!(todo.stop_timer(START + 1_500), Some((1, minutes(25))));
+    assert_eq!(todo.running_timer(), None);
+    assert_eq!(todo[0].accumulated, minutes(25));
+    assert_eq!(todo[0].time_spent(START + 9_999), minutes(25));
+    // Nothing left to stop
+    assert_eq!(todo.stop_timer(START + 2_000), None);
+}
+
+#[test]
+fn sessions_add_up() {
+    let mut todo = list_of(&["Write report"]);
+    start(&mut todo, 1, START).unwrap();
+    todo.stop_timer(START + 600);
+    start(&mut todo, 1, START + 3_600).unwrap();
+    todo.stop_timer(START + 4_500);
+    todo.log_time(todo.task_ref(1).unwrap(), minutes(5))
+        .unwrap();
+    assert_eq!(todo[0].accumulated, minutes(30));
+
+    start(&mut todo, 1, START + 7_200).unwrap();
+    assert_eq!(todo[0].time_spent(START + 7_260), minutes(31));
+}
+
+#[test]
+fn starting_a_running_timer_again_keeps_its_start() {
+    let mut todo = list_of(&["Write report"]);
+    start(&mut todo, 1, START).unwrap();
+    assert_eq!(start(&mut todo, 1, START + 600).unwrap(), None);
+    assert_eq!(todo[0].timer_started, Some(START));
+    assert_eq!(todo.stop_timer(START + 900), Some((1, minutes(15))));
+}
+
+#[test]
+fn starting_another_task_stops_the_running_one() {
+    let mut todo = list_of(&["Write report", "Answer email"]);
+    start(&mut todo, 1, START).unwrap();
+    assert_eq!(start(&mut todo, 2, START + 1_200).unwrap(), Some(1));
+    assert_eq!(todo.running_timer(), Some(2));
+    assert_eq!(
+        (todo[0].accumulated, todo[0].timer_started),
+        (minutes(20), None)
+    );
+    assert_eq!(todo.stop_timer(START + 1_500), Some((2, minutes(5))));
+}
+
+#[test]
+fn completed_tasks_take_no_timer() {
+    let mut todo = list_of(&["Write report"]);
+    // Completing a task stops its timer, at the time it is completed
+    start(&mut todo, 1, START).unwrap();
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    assert_eq!(todo.running_timer(), None);
+    assert!(todo[0].accumulated > Duration::ZERO);
+    assert!(matches!(
+        start(&mut todo, 1, START),
+        Err(TodoError::AlreadyCompleted(1))
+    ));
+    assert_eq!(todo.running_timer(), None);
+}
+
+// A clock set back mid-session counts nothing rather than underflowing
+#[test]
+fn a_clock_set_back_counts_no_time() {
+    let mut todo = list_of(&["Write report"]);
+    start(&mut todo, 1, START).unwrap();
+    assert_eq!(todo[0].time_spent(START - 60), Duration::ZERO);
+    assert_eq!(todo.stop_timer(START - 60), Some((1, Duration::ZERO)));
+    assert_eq!(todo[0].accumulated, Duration::ZERO);
+}
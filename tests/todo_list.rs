@@ -0,0 +1,831 @@
+mod common;
+
+use common::{list_of, local_time};
+use rust_todo_cli::date::{Date, SECONDS_PER_DAY, Timestamp};
+use rust_todo_cli::random::Rng;
+use rust_todo_cli::settings::Settings;
+use rust_todo_cli::todo::{
+    Estimate, ListFilter, Priority, Recurrence, Selector, Status, Task, TaskIndex, TodoError,
+    TodoList,
+};
+
+#[test]
+fn tasks_are_numbered_from_one() {
+    let todo = list_of(&["Buy milk", "Call mum"]);
+    let numbers: Vec<usize> = todo.list_tasks().map(|(index, _)| index).collect();
+    assert_eq!(numbers, [1, 2]);
+    assert_eq!(todo.get_task(1).unwrap().description, "Buy milk");
+    assert!(matches!(todo.get_task(0), Err(TodoError::InvalidIndex)));
+    assert!(matches!(
+        todo.get_task(3),
+        Err(TodoError::IndexOutOfBound(3))
+    ));
+}
+
+#[test]
+fn empty_descriptions_are_rejected() {
+    let mut todo = TodoList::new();
+    assert!(matches!(
+        todo.add_tasks("   ".to_string()),
+        Err(TodoError::EmptyDescription)
+    ));
+    assert!(todo.is_empty());
+}
+
+#[test]
+fn status_changes_are_recorded() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+
+    assert_eq!(todo.filter_by_status(Status::Completed).count(), 1);
+    assert_eq!(todo.filter_by_status(Status::Todo).next().unwrap().0, 1);
+    let last = todo.log().last().unwrap();
+    assert_eq!(last.action, "status");
+}
+
+#[test]
+fn statuses_parse_as_typed() {
+    assert_eq!("in-progress".parse::<Status>().unwrap(), Status::InProgress);
+    assert_eq!("DONE".parse::<Status>().unwrap(), Status::Completed);
+    assert!(matches!(
+        "later".parse::<Status>(),
+        Err(TodoError::InvalidStatus(_))
+    ));
+}
+
+#[test]
+fn selectors_find_tasks_by_number_or_text() {
+    let todo = list_of(&["Buy milk", "Call mum"]);
+    let by_text = todo.select(&Selector::parse("mum")).unwrap();
+    assert_eq!(by_text, TaskIndex { task: 2, sub: None });
+    let by_number = todo.select(&Selector::parse("1")).unwrap();
+    assert_eq!(by_number, TaskIndex { task: 1, sub: None });
+    // A menu needs someone to answer it, which the list cannot do
+    assert!(matches!(
+        todo.select(&Selector::Pick(ListFilter::default())),
+        Err(TodoError::NotInteractive)
+    ));
+}
+
+#[test]
+fn filters_combine_status_tags_and_text() {
+    let mut todo = list_of(&["Buy milk", "Buy bread", "Call mum"]);
+    todo.add_tags(todo.task_ref(2).unwrap(), &["shop".to_string()])
+        .unwrap();
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+
+    let filter = ListFilter {
+        status: Some(Status::Todo),
+        text: vec!["buy".to_string()],
+        ..ListFilter::default()
+    };
+    let found: Vec<usize> = todo.filter(&filter).iter().map(|(i, _)| *i).collect();
+    assert_eq!(found, [2]);
+
+    let tagged = ListFilter {
+        tags: vec!["shop".to_string()],
+        ..ListFilter::default()
+    };
+    assert_eq!(todo.filter(&tagged).len(), 1);
+}
+
+// Gives back the numbers it was made with, in turn, as picks out of 100
+struct Sequence(Vec<u64>);
+
+impl Rng for Sequence {
+    fn next_u64(&mut self) -> u64 {
+        // `below` scales the full range down, so spread each pick over it
+        (u64::MAX / 100) * self.0.remove(0)
+    }
+}
+
+#[test]
+fn random_picks_come_from_the_open_matching_tasks() {
+    let mut todo = list_of(&["Buy milk", "Buy bread", "Call mum", "Buy eggs"]);
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(4).unwrap(), Status::Cancelled, false)
+        .unwrap();
+    let all = ListFilter::default();
+
+    // Open tasks are 1 and 3, so the low half of the range picks 1
+    let mut rng = Sequence(vec![10, 90]);
+    assert_eq!(todo.pick_random(&all, &mut rng, None), Some(1));
+    assert_eq!(todo.pick_random(&all, &mut rng, None), Some(3));
+
+    let buy = ListFilter {
+        text: vec!["buy".to_string()],
+        ..ListFilter::default()
+    };
+    assert_eq!(
+        todo.pick_random(&buy, &mut Sequence(vec![99]), None),
+        Some(1)
+    );
+    let done = ListFilter {
+        status: Some(Status::Completed),
+        ..ListFilter::default()
+    };
+    assert_eq!(todo.pick_random(&done, &mut Sequence(vec![0]), None), None);
+    assert_eq!(
+        TodoList::new().pick_random(&all, &mut Sequence(vec![0]), None),
+        None
+    );
+}
+
+#[test]
+fn random_picks_avoid_the_last_one_unless_it_is_alone() {
+    let todo = list_of(&["Buy milk", "Call mum"]);
+    let all = ListFilter::default();
+    let first = todo.get_task(1).unwrap().id;
+    for pick in [0, 50, 99] {
+        let mut rng = Sequence(vec![pick]);
+        assert_eq!(todo.pick_random(&all, &mut rng, Some(first)), Some(2));
+    }
+
+    let alone = list_of(&["Buy milk"]);
+    let id = alone.get_task(1).unwrap().id;
+    assert_eq!(
+        alone.pick_random(&all, &mut Sequence(vec![0]), Some(id)),
+        Some(1)
+    );
+}
+
+const DAY: Timestamp = SECONDS_PER_DAY as Timestamp;
+
+// A task created `age` seconds before `now`
+fn aged(now: Timestamp, age: Timestamp) -> Task {
+    let mut task = Task::new("Task".to_string()).unwrap();
+    task.created_at = Some(now - age);
+    task
+}
+
+// A task closed `seconds` into a local day
+fn closed_on(date: Date, seconds: i64, status: Status) -> Task {
+    let mut task = Task::new(format!("Done on {}", date)).unwrap();
+    task.status = status;
+    task.completed_at = Some(local_time(date, seconds));
+    task
+}
+
+#[test]
+fn tasks_are_archivable_only_once_past_the_days_given() {
+    let today = Date::new(2025, 6, 30).unwrap();
+    let last_second = SECONDS_PER_DAY - 1;
+    let mut undated = closed_on(today.add_days(-40), 0, Status::Completed);
+    undated.completed_at = None;
+    let mut reopened = closed_on(today.add_days(-40), 0, Status::Completed);
+    reopened.status = Status::Todo;
+    let mut todo: TodoList = [
+        // Exactly fourteen days is not more than fourteen, to the last second
+        closed_on(today.add_days(-14), last_second, Status::Completed),
+        closed_on(today.add_days(-15), 0, Status::Completed),
+        closed_on(today.add_days(-15), last_second, Status::Completed),
+        closed_on(today.add_days(-40), 0, Status::Cancelled),
+        undated,
+        reopened,
+        closed_on(today, 0, Status::Completed),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(todo.archivable(today, 14), [2, 3]);
+    assert_eq!(todo.archivable(today.add_days(1), 14), [1, 2, 3]);
+    assert_eq!(todo.archivable(today, 0), [1, 2, 3]);
+    assert!(todo.archivable(today, 15).is_empty());
+
+    let first = todo[0].id;
+    let (task, on) = (todo.task_ref(1).unwrap(), todo.task_ref(2).unwrap());
+    todo.add_dependency(task, on).unwrap();
+    let archived = todo.archive_tasks(&[3, 2, 99]);
+    let dates: Vec<&str> = archived
+        .iter()
+        .map(|task| task.description.as_str())
+        .collect();
+    assert_eq!(dates, ["Done on 2025-06-15", "Done on 2025-06-15"]);
+    assert_eq!(todo.len(), 5);
+    assert_eq!(todo[0].id, first);
+    assert!(todo[0].depends_on.is_empty());
+    assert_eq!(todo.log().last().unwrap().action, "archive");
+}
+
+#[test]
+fn tasks_go_stale_only_past_the_threshold() {
+    let now = 100 * DAY;
+    let mut started = aged(now, 30 * DAY);
+    started.status = Status::InProgress;
+    let mut done = aged(now, 30 * DAY);
+    done.status = Status::Completed;
+    let mut undated = aged(now, 30 * DAY);
+    undated.created_at = None;
+    let todo: TodoList = [
+        aged(now, 6 * DAY),
+        // Exactly seven days is not more than seven
+        aged(now, 7 * DAY),
+        aged(now, 7 * DAY + 1),
+        started,
+        done,
+        undated,
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(todo.stale_tasks(now, 7), [3]);
+    assert_eq!(todo.stale_tasks(now, 5), [1, 2, 3]);
+    assert!(todo.stale_tasks(now, 30).is_empty());
+}
+
+#[test]
+fn escalating_raises_stale_tasks_one_level_at_a_time() {
+    let now = 100 * DAY;
+    let mut urgent = aged(now, 10 * DAY);
+    urgent.priority = Priority::Urgent;
+    let mut todo: TodoList = [aged(now, 10 * DAY), aged(now, 0), urgent]
+        .into_iter()
+        .collect();
+
+    assert_eq!(todo.escalate(now, 7), [1]);
+    assert_eq!(todo.get_task(1).unwrap().priority, Priority::High);
+    assert_eq!(todo.get_task(2).unwrap().priority, Priority::Normal);
+    assert_eq!(todo.get_task(3).unwrap().priority, Priority::Urgent);
+    assert_eq!(todo.log().last().unwrap().action, "escalate");
+
+    // Raised again only once as many days go by once more
+    assert!(todo.escalate(now + 7 * DAY, 7).is_empty());
+    assert_eq!(todo.stale_tasks(now + 7 * DAY, 7), [3]);
+    assert_eq!(todo.escalate(now + 7 * DAY + 1, 7), [1, 2]);
+    assert_eq!(todo.get_task(1).unwrap().priority, Priority::Urgent);
+    assert_eq!(todo.get_task(2).unwrap().priority, Priority::High);
+    assert_eq!(todo.escalate(now + 30 * DAY, 7), [2]);
+    // Everything is urgent now, so nothing is left to raise
+    assert!(todo.escalate(now + 60 * DAY, 7).is_empty());
+}
+
+#[test]
+fn normal_priority_is_left_out_of_the_file() {
+    let normal = Task::new("Buy milk".to_string()).unwrap();
+    let json = serde_json::to_string(&normal).unwrap();
+    assert!(!json.contains("priority"), "{}", json);
+    let back: Task = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.priority, Priority::Normal);
+
+    let mut high = normal.clone();
+    high.priority = Priority::High;
+    let json = serde_json::to_string(&high).unwrap();
+    assert!(json.contains("\"priority\":\"High\""), "{}", json);
+    assert_eq!(serde_json::from_str::<Task>(&json).unwrap(), high);
+}
+
+#[test]
+fn removed_tasks_can_be_restored() {
+    let mut todo = list_of(&["Buy milk", "Call mum"]);
+    let task = todo.remove_task(todo.task_ref(1).unwrap()).unwrap();
+    assert_eq!(todo.len(), 1);
+    let position = todo.restore(task);
+    assert_eq!(todo.get_task(position).unwrap().description, "Buy milk");
+}
+
+#[test]
+fn templates_add_fresh_copies() {
+    let mut todo = list_of(&["Write notes", "Tag release"]);
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    assert!(!todo.save_template("Release", &[1, 2]).unwrap());
+
+    assert_eq!(todo.apply_template("release").unwrap(), 2);
+    assert_eq!(todo.len(), 4);
+    assert_eq!(todo.get_task(3).unwrap().status, Status::Todo);
+    assert!(matches!(
+        todo.apply_template("missing"),
+        Err(TodoError::UnknownTemplate(_))
+    ));
+}
+
+#[test]
+fn statistics_count_each_status() {
+    let mut todo = list_of(&["a", "b", "c"]);
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::InProgress, false)
+        .unwrap();
+
+    let stats = todo.statistics();
+    assert_eq!(stats.total, 3);
+    assert_eq!(stats.completed, 1);
+    assert_eq!(stats.in_progress, 1);
+    assert_eq!(stats.todo, 1);
+}
+
+#[test]
+fn estimates_parse_like_durations_and_print_back() {
+    for (text, minutes, shown) in [
+        ("45m", 45, "45m"),
+        ("2h", 120, "2h"),
+        ("1h30m", 90, "1h30m"),
+        ("1h 30m", 90, "1h30m"),
+        ("1d", 1440, "24h"),
+    ] {
+        let estimate = Estimate::parse(text).unwrap();
+        assert_eq!(estimate.minutes, minutes, "{:?}", text);
+        assert_eq!(estimate.to_string(), shown);
+        assert_eq!(Estimate::parse(shown).unwrap(), estimate);
+    }
+    for text in ["", "soon", "0m", "90", "2x"] {
+        assert!(
+            matches!(Estimate::parse(text), Err(TodoError::InvalidEstimate(t)) if t == text),
+            "{:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn a_tilde_word_in_a_description_is_the_estimate() {
+    let mut todo = list_of(&["Write report ~90m +work", "Buy milk", "Save ~5% on rent"]);
+    let report = todo.get_task(1).unwrap();
+    assert_eq!(report.description, "Write report +work");
+    assert_eq!(report.estimate, Some(Estimate { minutes: 90 }));
+    assert_eq!(todo.get_task(2).unwrap().estimate, None);
+    // Words that are not amounts of time stay in the text
+    assert_eq!(todo.get_task(3).unwrap().description, "Save ~5% on rent");
+    assert!(matches!(
+        todo.add_tasks("~2h".to_string()),
+        Err(TodoError::EmptyDescription)
+    ));
+
+    todo.set_estimate(todo.task_ref(2).unwrap(), Some(Estimate { minutes: 20 }))
+        .unwrap();
+    assert_eq!(
+        todo.get_task(2).unwrap().estimate.unwrap().to_string(),
+        "20m"
+    );
+    todo.set_estimate(todo.task_ref(1).unwrap(), None).unwrap();
+    assert_eq!(todo.get_task(1).unwrap().estimate, None);
+    assert_eq!(todo.log().last().unwrap().action, "estimate");
+    assert!(todo.task_ref(4).is_err());
+}
+
+#[test]
+fn statistics_add_up_estimates_by_status() {
+    let mut todo = list_of(&["a ~1h", "b ~30m", "c ~2h", "d ~15m", "e"]);
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::InProgress, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(3).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(4).unwrap(), Status::Cancelled, false)
+        .unwrap();
+
+    let stats = todo.statistics();
+    assert_eq!(stats.estimated(Status::Todo).minutes, 30);
+    assert_eq!(stats.estimated(Status::InProgress).minutes, 60);
+    assert_eq!(stats.estimated(Status::Completed).minutes, 120);
+    assert_eq!(stats.estimated(Status::Cancelled).minutes, 15);
+    assert_eq!(stats.estimated_left().to_string(), "1h30m");
+}
+
+#[test]
+fn indexing_counts_from_zero() {
+    let todo = list_of(&["Buy milk", "Call mum"]);
+    assert_eq!(todo[0].description, "Buy milk");
+    assert_eq!(todo[1].description, todo.get_task(2).unwrap().description);
+}
+
+#[test]
+#[should_panic]
+fn indexing_past_the_end_panics() {
+    let todo = list_of(&["Buy milk"]);
+    let _ = &todo[1];
+}
+
+#[test]
+fn iterating_borrows_or_takes_the_tasks() {
+    let todo = list_of(&["Buy milk", "Call mum"]);
+    let borrowed: Vec<&str> = (&todo)
+        .into_iter()
+        .map(|task| task.description.as_str())
+        .collect();
+    assert_eq!(borrowed, ["Buy milk", "Call mum"]);
+
+    let mut count = 0;
+    for task in &todo {
+        assert!(!task.is_closed());
+        count += 1;
+    }
+    assert_eq!(count, 2);
+
+    let owned: Vec<Task> = todo.into_iter().collect();
+    assert_eq!(owned[1].description, "Call mum");
+}
+
+#[test]
+fn collecting_tasks_gives_them_ids() {
+    let tasks = ["Buy milk", "Call mum"]
+        .iter()
+        .map(|description| Task::new(description.to_string()).unwrap());
+    let todo: TodoList = tasks.collect();
+    assert_eq!(todo.len(), 2);
+    assert_eq!(todo[0].id, 1);
+    assert_eq!(todo[1].id, 2);
+    assert_eq!(todo.log().len(), 2);
+}
+
+#[test]
+fn extending_adds_after_existing_tasks() {
+    let mut todo = list_of(&["Buy milk"]);
+    let mut done = Task::new("Call mum".to_string()).unwrap();
+    done.set_status(Status::Completed);
+    todo.extend(vec![done]);
+
+    assert_eq!(todo.len(), 2);
+    assert_eq!(todo[1].status, Status::Completed);
+    assert_ne!(todo[0].id, todo[1].id);
+}
+
+#[test]
+fn default_is_an_empty_list() {
+    let todo = TodoList::default();
+    assert!(todo.is_empty());
+    assert!(todo.log().is_empty());
+    assert_eq!(todo.list_tasks().count(), 0);
+}
+
+fn descriptions(todo: &TodoList) -> Vec<&str> {
+    todo.tasks()
+        .iter()
+        .map(|task| task.description.as_str())
+        .collect()
+}
+
+#[test]
+fn merging_takes_changes_from_both_sides() {
+    let base = list_of(&["Buy milk", "Call mum", "Pay rent", "Walk dog"]);
+    let mut ours = base.clone();
+    ours.update_task_status(ours.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    ours.remove_task(ours.task_ref(3).unwrap()).unwrap();
+    ours.add_tasks("Ours".to_string()).unwrap();
+    let mut theirs = base.clone();
+    theirs
+        .update_task_status(theirs.task_ref(2).unwrap(), Status::InProgress, false)
+        .unwrap();
+    theirs.add_tasks("Theirs".to_string()).unwrap();
+    theirs.remove_task(theirs.task_ref(4).unwrap()).unwrap();
+
+    ours.merge(&base, theirs);
+    assert_eq!(
+        descriptions(&ours),
+        ["Buy milk", "Call mum", "Theirs", "Ours"]
+    );
+    assert_eq!(ours.get_task(1).unwrap().status, Status::Completed);
+    assert_eq!(ours.get_task(2).unwrap().status, Status::InProgress);
+    // Both sides gave their new task the next ID
+    let ids: Vec<u64> = ours.tasks().iter().map(|task| task.id).collect();
+    assert_eq!(ids, [1, 2, 5, 6]);
+}
+
+#[test]
+fn merging_keeps_our_version_of_a_task_changed_on_both_sides() {
+    let base = list_of(&["Buy milk", "Call mum"]);
+    let mut ours = base.clone();
+    ours.update_task_status(ours.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    ours.update_task_status(ours.task_ref(2).unwrap(), Status::Cancelled, false)
+        .unwrap();
+    let mut theirs = base.clone();
+    theirs
+        .update_task_status(theirs.task_ref(1).unwrap(), Status::InProgress, false)
+        .unwrap();
+    theirs.remove_task(theirs.task_ref(2).unwrap()).unwrap();
+
+    ours.merge(&base, theirs);
+    assert_eq!(descriptions(&ours), ["Buy milk", "Call mum"]);
+    assert_eq!(ours.get_task(1).unwrap().status, Status::Completed);
+    assert_eq!(ours.get_task(2).unwrap().status, Status::Cancelled);
+}
+
+#[test]
+fn merging_without_changes_here_takes_theirs() {
+    let base = list_of(&["Buy milk", "Call mum"]);
+    let mut ours = base.clone();
+    let mut theirs = base.clone();
+    theirs.remove_task(theirs.task_ref(1).unwrap()).unwrap();
+    theirs.add_tasks("Pay rent".to_string()).unwrap();
+
+    ours.merge(&base, theirs.clone());
+    assert_eq!(ours.tasks(), theirs.tasks());
+}
+
+#[test]
+fn errors_serialize_with_a_code_and_their_values() {
+    let error = TodoError::IndexOutOfBound(7);
+    assert_eq!(error.code(), "IndexOutOfBound");
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["error"], "IndexOutOfBound");
+    assert_eq!(json["message"], error.to_string());
+    assert_eq!(json["index"], 7);
+
+    // A subtask index is split so `index` stays a number
+    let error = TodoError::LinkOutOfBound(
+        TaskIndex {
+            task: 3,
+            sub: Some(2),
+        },
+        1,
+    );
+    let json = serde_json::to_value(&error).unwrap();
+    assert_eq!(json["error"], "LinkOutOfBound");
+    assert_eq!(
+        (&json["index"], &json["sub"], &json["count"]),
+        (&3.into(), &2.into(), &1.into())
+    );
+
+    let json = serde_json::to_value(TodoError::EmptyDescription).unwrap();
+    assert_eq!(json.as_object().unwrap().len(), 2, "{}", json);
+}
+
+#[test]
+fn find_similar_ignores_case_spacing_and_trailing_punctuation() {
+    let mut todo = list_of(&["Write report", "Buy milk", "Call mum"]);
+    let (index, task) = todo.find_similar("  buy \t milk!").unwrap();
+    assert_eq!((index, task.description.as_str()), (2, "Buy milk"));
+    assert_eq!(todo.find_similar("CALL MUM.").unwrap().0, 3);
+    assert!(todo.find_similar("Buy milk tomorrow").is_none());
+    assert!(todo.find_similar("...").is_none());
+
+    // Finished tasks may be added again
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(3).unwrap(), Status::Cancelled, false)
+        .unwrap();
+    assert!(todo.find_similar("write report").is_none());
+    assert!(todo.find_similar("call mum").is_none());
+}
+
+#[test]
+fn by_due_sorts_by_date_with_undated_tasks_last() {
+    let mut todo = list_of(&["No date", "Later", "Sooner", "Also undated"]);
+    todo.set_due(todo.task_ref(2).unwrap(), Date::new(2025, 7, 1))
+        .unwrap();
+    todo.set_due(todo.task_ref(3).unwrap(), Date::new(2025, 6, 1))
+        .unwrap();
+    let filter = ListFilter {
+        by_due: true,
+        ..ListFilter::default()
+    };
+    let order: Vec<usize> = todo.filter(&filter).iter().map(|(i, _)| *i).collect();
+    assert_eq!(order, [3, 2, 1, 4]);
+    assert_eq!(filter.to_string(), "--by-due");
+    assert!(filter.is_empty());
+}
+
+#[test]
+fn counts_by_status_and_due_date_skip_closed_tasks_for_dates() {
+    let today = Date::new(2025, 6, 10).unwrap();
+    let mut todo = list_of(&["Late", "Today", "Done late", "Someday", "Later"]);
+    for (n, due) in [(1, 5), (2, 10), (3, 1), (5, 20)] {
+        todo.set_due(todo.task_ref(n).unwrap(), Date::new(2025, 6, due))
+            .unwrap();
+    }
+    todo.update_task_status(todo.task_ref(3).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo.wait(todo.task_ref(4).unwrap(), None).unwrap();
+
+    assert_eq!(todo.count_status(Status::Todo), 3);
+    assert_eq!(todo.count_status(Status::Completed), 1);
+    assert_eq!(todo.count_status(Status::Waiting), 1);
+    assert_eq!(todo.count_status(Status::Cancelled), 0);
+    assert_eq!(todo.count_overdue(today), 1);
+    assert_eq!(todo.count_due_on(today), 1);
+    assert_eq!(todo.count_due_on(today.add_days(1)), 0);
+}
+
+#[test]
+fn clearing_closed_tasks_removes_what_the_query_listed() {
+    let mut todo = list_of(&["Buy milk", "Call mum", "Book dentist", "Fix sink"]);
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    todo.update_task_status(todo.task_ref(3).unwrap(), Status::Cancelled, false)
+        .unwrap();
+
+    let listed: Vec<(usize, String)> = todo
+        .closed_tasks()
+        .map(|(n, task)| (n, task.description.clone()))
+        .collect();
+    assert_eq!(
+        listed,
+        [(1, "Buy milk".to_string()), (3, "Book dentist".to_string())]
+    );
+    // Asking changes nothing
+    assert_eq!(todo.len(), 4);
+
+    assert_eq!(todo.clear_closed(), 2);
+    let left: Vec<&str> = todo.iter().map(|t| t.description.as_str()).collect();
+    assert_eq!(left, ["Call mum", "Fix sink"]);
+    assert_eq!(todo.closed_tasks().count(), 0);
+}
+
+#[test]
+fn clearing_a_status_or_everything_removes_only_those_tasks() {
+    let statuses = [
+        Status::Todo,
+        Status::InProgress,
+        Status::Completed,
+        Status::Cancelled,
+        Status::Waiting,
+        Status::Todo,
+    ];
+    let full: TodoList = statuses
+        .iter()
+        .map(|&status| {
+            let mut task = Task::new(format!("{:?}", status)).unwrap();
+            task.set_status(status);
+            task
+        })
+        .collect();
+
+    for status in Status::ALL {
+        let mut todo = full.clone();
+        let expected = statuses.iter().filter(|&&each| each == status).count();
+        assert_eq!(todo.clear_by_status(status), expected, "{:?}", status);
+        assert_eq!(todo.len(), statuses.len() - expected);
+        assert!(todo.iter().all(|task| task.status != status));
+        // Nothing is left to clear the second time, and nothing is logged
+        let logged = todo.log().len();
+        assert_eq!(todo.clear_by_status(status), 0);
+        assert_eq!(todo.log().len(), logged);
+    }
+
+    let mut todo = full.clone();
+    let (task, on) = (todo.task_ref(6).unwrap(), todo.task_ref(1).unwrap());
+    todo.add_dependency(task, on).unwrap();
+    assert_eq!(todo.clear_by_status(Status::Todo), 2);
+    assert!(todo.iter().all(|task| task.depends_on.is_empty()));
+    assert_eq!(todo.log().last().unwrap().details, "cleared 2 todo task(s)");
+
+    let mut todo = full;
+    assert_eq!(todo.clear_all(), 6);
+    assert!(todo.is_empty());
+    assert_eq!(todo.log().last().unwrap().details, "cleared 6 task(s)");
+    assert_eq!(todo.clear_all(), 0);
+    assert_eq!(todo.clear_closed(), 0);
+}
+
+#[test]
+fn the_agenda_groups_open_tasks_by_day_across_a_month_end() {
+    let start = Date::new(2025, 6, 29).unwrap();
+    let mut todo = list_of(&[
+        "Rent",
+        "Milk",
+        "Dentist",
+        "Taxes",
+        "Someday",
+        "Done already",
+        "Older bill",
+        "Dentist forms",
+    ]);
+    let dates = [
+        (1, Date::new(2025, 6, 20)),
+        (2, Date::new(2025, 6, 29)),
+        (3, Date::new(2025, 7, 1)),
+        (4, Date::new(2025, 7, 6)),
+        (6, Date::new(2025, 6, 30)),
+        (7, Date::new(2025, 6, 1)),
+        (8, Date::new(2025, 7, 1)),
+    ];
+    for (n, due) in dates {
+        todo.set_due(todo.task_ref(n).unwrap(), due).unwrap();
+    }
+    todo.update_task_status(todo.task_ref(6).unwrap(), Status::Completed, false)
+        .unwrap();
+
+    let agenda = todo.agenda(start, 7);
+    let numbers =
+        |tasks: &[(usize, &Task)]| -> Vec<usize> { tasks.iter().map(|(n, _)| *n).collect() };
+    // Most overdue first, whatever the list order
+    assert_eq!(numbers(&agenda.overdue), [7, 1]);
+    assert_eq!(agenda.days.len(), 7);
+    assert_eq!(agenda.days[0].date, start);
+    assert_eq!(numbers(&agenda.days[0].tasks), [2]);
+    // The completed task due on the 30th is left out
+    assert!(agenda.days[1].tasks.is_empty());
+    assert_eq!(agenda.days[2].date, Date::new(2025, 7, 1).unwrap());
+    assert_eq!(numbers(&agenda.days[2].tasks), [3, 8]);
+    assert_eq!(agenda.days[6].date, Date::new(2025, 7, 5).unwrap());
+    // The 6th is the day after the window
+    assert_eq!(numbers(&agenda.later), [4]);
+    assert_eq!(agenda.undated, 1);
+
+    let short = todo.agenda(start, 1);
+    assert_eq!(numbers(&short.later), [3, 8, 4]);
+    assert_eq!(agenda.days[2].date.short_label(), "Tue 1 Jul");
+}
+
+#[test]
+fn read_tasks_takes_every_line_as_a_description() {
+    let settings = Settings::default();
+    let too_long = "x".repeat(settings.max_length + 1);
+    let input = format!(
+        "remove old fence\n\n# a comment\n  update all done  \n{}\nexit\n",
+        too_long
+    );
+    let read = rust_todo_cli::todo::read_tasks(input.as_bytes(), &settings).unwrap();
+    let descriptions: Vec<&str> = read.tasks.iter().map(|t| t.description.as_str()).collect();
+    assert_eq!(
+        descriptions,
+        ["remove old fence", "update all done", "exit"]
+    );
+    assert_eq!(read.lines, descriptions);
+    assert_eq!(read.failed.len(), 1);
+    assert_eq!(read.failed[0].0, 5);
+}
+
+#[test]
+fn completing_a_recurring_task_again_and_again_chains_its_due_dates() {
+    let mut todo = list_of(&["Water plants", "Pay rent"]);
+    let plants = todo.task_ref(1).unwrap();
+    todo.set_due(plants, Date::new(2024, 2, 19)).unwrap();
+    todo.set_recurrence(plants, Some(Recurrence::parse("every 1w").unwrap()))
+        .unwrap();
+
+    // Each next occurrence is due a week after the last, through a leap day
+    // and a month end, and is the one completed next
+    let mut current = 1;
+    let mut dues = vec![todo[0].due];
+    for _ in 0..4 {
+        let task = todo.task_ref(current).unwrap();
+        current = todo
+            .update_task_status(task, Status::Completed, false)
+            .unwrap()
+            .unwrap();
+        dues.push(todo[current - 1].due);
+    }
+    assert_eq!(current, 6);
+    let expected = [(2, 19), (2, 26), (3, 4), (3, 11), (3, 18)];
+    assert_eq!(
+        dues,
+        expected.map(|(month, day)| Date::new(2024, month, day))
+    );
+
+    // Only the open copy repeats; the done ones keep their own dates
+    let repeating: Vec<usize> = todo
+        .list_tasks()
+        .filter(|(_, task)| task.recurrence.is_some())
+        .map(|(index, _)| index)
+        .collect();
+    assert_eq!(repeating, [6]);
+    assert_eq!(todo[5].status, Status::Todo);
+    for index in [1, 3, 4, 5] {
+        assert_eq!(todo[index - 1].status, Status::Completed, "{}", index);
+    }
+    assert_eq!(todo[1].description, "Pay rent");
+    assert_eq!(todo.len(), 6);
+
+    // Completing a done copy again adds nothing
+    let done = todo.task_ref(3).unwrap();
+    assert_eq!(
+        todo.update_task_status(done, Status::Completed, false)
+            .unwrap(),
+        None
+    );
+
+    // Without a due date the first one comes due an interval from today
+    let rent = todo.task_ref(2).unwrap();
+    todo.set_recurrence(rent, Some(Recurrence::parse("every 3d").unwrap()))
+        .unwrap();
+    let next = todo
+        .update_task_status(rent, Status::Completed, false)
+        .unwrap()
+        .unwrap();
+    assert_eq!(todo[next - 1].due, Some(Date::today().add_days(3)));
+    let next = todo
+        .update_task_status(todo.task_ref(next).unwrap(), Status::Completed, false)
+        .unwrap()
+        .unwrap();
+    assert_eq!(todo[next - 1].due, Some(Date::today().add_days(6)));
+}
+
+#[test]
+fn saves_as_compares_everything_that_is_saved() {
+    let base = list_of(&["Buy milk", "Call mum"]);
+    let mut todo = base.clone();
+    assert!(todo.saves_as(&base));
+
+    todo.save_filter("mine", "todo").unwrap();
+    assert!(!todo.saves_as(&base));
+    let mut todo = base.clone();
+    todo.save_snippet("call", "Call {name}").unwrap();
+    assert!(!todo.saves_as(&base));
+    let mut todo = base.clone();
+    todo.save_template("both", &[1, 2]).unwrap();
+    assert!(!todo.saves_as(&base));
+    let mut todo = base.clone();
+    todo.add_tasks("Pay rent".to_string()).unwrap();
+    assert!(!todo.saves_as(&base));
+
+    // The log is kept in a file of its own
+    let mut logged = TodoList::new();
+    logged.add_tasks("Buy milk".to_string()).unwrap();
+    let mut todo = logged.clone();
+    assert!(todo.clear_log() > 0);
+    assert!(todo.saves_as(&logged));
+}
@@ -0,0 +1,87 @@
+use rust_todo_cli::todo::{Task, TodoError, Token, Tokens, extract_tokens};
+
+fn tokens(projects: &[&str], contexts: &[&str]) -> Tokens {
+    Tokens {
+        projects: projects.iter().map(|name| name.to_string()).collect(),
+        contexts: contexts.iter().map(|name| name.to_string()).collect(),
+    }
+}
+
+#[test]
+fn tokens_come_out_from_anywhere_in_the_description() {
+    let cases = [
+        ("+taxes Call accountant", "Call accountant"),
+        ("Call +taxes accountant", "Call accountant"),
+        ("Call accountant +taxes", "Call accountant"),
+    ];
+    for (text, words) in cases {
+        assert_eq!(
+            extract_tokens(text),
+            (words.to_string(), tokens(&["taxes"], &[])),
+            "{}",
+            text
+        );
+    }
+    assert_eq!(
+        extract_tokens("@phone Call +taxes   accountant @office"),
+        (
+            "Call accountant".to_string(),
+            tokens(&["taxes"], &["phone", "office"])
+        )
+    );
+}
+
+#[test]
+fn punctuation_after_a_token_is_not_part_of_its_name() {
+    assert_eq!(
+        extract_tokens("Call @phone, then file +taxes."),
+        ("Call then file".to_string(), tokens(&["taxes"], &["phone"]))
+    );
+    assert_eq!(
+        Token::parse("+home-office!"),
+        Some(Token::Project("home-office".to_string()))
+    );
+    assert_eq!(
+        Token::parse("@in_person?"),
+        Some(Token::Context("in_person".to_string()))
+    );
+    assert_eq!(Token::parse("+!"), None);
+}
+
+#[test]
+fn only_a_sign_followed_by_a_letter_makes_a_token() {
+    let text = "Pay +1 at @5pm to me@example.com + tip";
+    assert_eq!(extract_tokens(text), (text.to_string(), Tokens::default()));
+    assert_eq!(
+        extract_tokens("See @café"),
+        ("See".to_string(), tokens(&[], &["café"]))
+    );
+}
+
+#[test]
+fn tokens_are_lowercased_and_kept_once() {
+    assert_eq!(
+        extract_tokens("File +Taxes +taxes +TAXES. @Phone @phone"),
+        ("File".to_string(), tokens(&["taxes"], &["phone"]))
+    );
+}
+
+#[test]
+fn a_description_of_only_tokens_is_empty() {
+    assert_eq!(
+        extract_tokens(" +taxes  @phone "),
+        (String::new(), tokens(&["taxes"], &["phone"]))
+    );
+    assert!(matches!(
+        Task::new("+taxes @phone".to_string()),
+        Err(TodoError::EmptyDescription)
+    ));
+}
+
+#[test]
+fn tasks_keep_the_description_as_typed_and_take_its_tokens() {
+    let task = Task::new("Call accountant +taxes, @phone".to_string()).unwrap();
+    assert_eq!(task.description, "Call accountant +taxes, @phone");
+    assert_eq!(task.projects, ["taxes"]);
+    assert_eq!(task.contexts, ["phone"]);
+}
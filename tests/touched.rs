@@ -0,0 +1,210 @@
+use std::time::Duration;
+
+use rust_todo_cli::date::{Date, SECONDS_PER_DAY, Time, Timestamp, now};
+use rust_todo_cli::todo::{
+    Estimate, ListFilter, Recurrence, Status, Storable, Task, TaskIndex, TodoList,
+};
+
+// A while before any test runs
+const LONG_AGO: Timestamp = 1_000_000_000;
+
+// Three tasks, the first with a subtask, none changed since LONG_AGO
+fn untouched_list() -> TodoList {
+    let mut todo = TodoList::new();
+    for description in ["Write report", "Buy milk", "Call mum"] {
+        let mut task = Task::new(description.to_string()).unwrap();
+        task.created_at = Some(LONG_AGO);
+        task.updated_at = Some(LONG_AGO);
+        if description == "Write report" {
+            let mut sub = Task::new("Outline".to_string()).unwrap();
+            sub.created_at = Some(LONG_AGO);
+            task.subtasks.push(sub);
+        }
+        todo.add(task);
+    }
+    todo
+}
+
+fn task(index: usize) -> TaskIndex {
+    TaskIndex {
+        task: index,
+        sub: None,
+    }
+}
+
+#[test]
+fn every_change_to_a_task_touches_it() {
+    type Change = fn(&mut TodoList);
+    let changes: [(&str, Change); 17] = [
+        ("status", |todo| {
+            todo.update_task_status(todo.task_ref(2).unwrap(), Status::InProgress, false)
+                .unwrap();
+        }),
+        ("toggle", |todo| {
+            todo.toggle_task(task(2), false).unwrap();
+        }),
+        ("wait", |todo| {
+            todo.wait(todo.task_ref(2).unwrap(), None).unwrap()
+        }),
+        ("subtask", |todo| {
+            todo.add_subtask(todo.task_ref(2).unwrap(), "Check date".to_string())
+                .unwrap();
+        }),
+        ("repeat", |todo| {
+            todo.set_recurrence(todo.task_ref(2).unwrap(), Some(Recurrence { days: 7 }))
+                .unwrap();
+        }),
+        ("append", |todo| {
+            todo.append_description(task(2), "today").unwrap();
+        }),
+        ("prepend", |todo| {
+            todo.prepend_description(task(2), "Go").unwrap();
+        }),
+        ("edit", |todo| {
+            todo.set_description(todo.task_ref(2).unwrap(), "Buy oat milk".to_string())
+                .unwrap();
+        }),
+        ("due", |todo| {
+            todo.set_due(todo.task_ref(2).unwrap(), Date::new(2030, 1, 1))
+                .unwrap()
+        }),
+        ("estimate", |todo| {
+            todo.set_estimate(todo.task_ref(2).unwrap(), Some(Estimate { minutes: 30 }))
+                .unwrap();
+        }),
+        ("snooze", |todo| {
+            todo.snooze(
+                todo.task_ref(2).unwrap(),
+                Duration::from_secs(86_400),
+                Date::today(),
+                Time::now(),
+            )
+            .unwrap();
+        }),
+        ("track", |todo| {
+            todo.start_timer(todo.task_ref(2).unwrap(), now()).unwrap();
+        }),
+        ("log time", |todo| {
+            todo.log_time(todo.task_ref(2).unwrap(), Duration::from_secs(60))
+                .unwrap();
+        }),
+        ("tag", |todo| {
+            todo.add_tags(todo.task_ref(2).unwrap(), &["shop".to_string()])
+                .unwrap();
+        }),
+        ("untag", |todo| {
+            todo.remove_tags(todo.task_ref(2).unwrap(), &["shop".to_string()])
+                .unwrap();
+        }),
+        ("blocks", |todo| {
+            todo.add_dependency(todo.task_ref(2).unwrap(), todo.task_ref(3).unwrap())
+                .unwrap()
+        }),
+        ("restore", |todo| {
+            let removed = todo.remove_task(todo.task_ref(2).unwrap()).unwrap();
+            todo.restore(removed);
+        }),
+    ];
+    for (name, change) in changes {
+        let mut todo = untouched_list();
+        change(&mut todo);
+        // Found by ID, since `restore` puts the task back at the end
+        let touched = &todo[todo.index_of(2).unwrap() - 1];
+        assert!(
+            touched.updated_at > Some(LONG_AGO),
+            "{} did not touch",
+            name
+        );
+        assert_eq!(
+            todo[0].updated_at,
+            Some(LONG_AGO),
+            "{} touched another",
+            name
+        );
+    }
+}
+
+#[test]
+fn changing_a_subtask_touches_its_task_too() {
+    let mut todo = untouched_list();
+    let outline = TaskIndex {
+        task: 1,
+        sub: Some(1),
+    };
+    todo.update_status_at(outline, Status::Completed, false)
+        .unwrap();
+    assert!(todo[0].updated_at > Some(LONG_AGO));
+    assert!(todo[0].subtasks[0].updated_at.is_some());
+    assert_eq!(todo[1].updated_at, Some(LONG_AGO));
+}
+
+#[test]
+fn looking_and_failing_leave_tasks_untouched() {
+    let mut todo = untouched_list();
+    todo.filter(&ListFilter::default());
+    todo.get_task(2).unwrap();
+    assert!(todo.task_ref(7).is_err());
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+    let completed_at = todo[1].updated_at;
+    assert!(
+        todo.snooze(
+            todo.task_ref(2).unwrap(),
+            Duration::from_secs(60),
+            Date::today(),
+            Time::now()
+        )
+        .is_err()
+    );
+    assert_eq!(todo[1].updated_at, completed_at);
+    assert_eq!(todo[2].updated_at, Some(LONG_AGO));
+}
+
+#[test]
+fn recent_sorts_by_last_change_and_stale_by_age() {
+    let mut todo = untouched_list();
+    let mut fresh = Task::new("Fresh".to_string()).unwrap();
+    fresh.created_at = Some(now());
+    todo.add(fresh);
+    todo.add_tags(todo.task_ref(3).unwrap(), &["phone".to_string()])
+        .unwrap();
+
+    let recent = ListFilter {
+        recent: true,
+        ..ListFilter::default()
+    };
+    let order: Vec<usize> = todo.filter(&recent).iter().map(|(i, _)| *i).collect();
+    // Equally recent tasks keep their list order
+    assert_eq!(order[2..], [1, 2]);
+    assert!(order[..2].contains(&3) && order[..2].contains(&4));
+
+    let stale = ListFilter {
+        untouched: Some(Duration::from_secs(14 * SECONDS_PER_DAY as u64)),
+        ..ListFilter::default()
+    };
+    let old: Vec<usize> = todo.filter(&stale).iter().map(|(i, _)| *i).collect();
+    assert_eq!(old, [1, 2]);
+    assert_eq!(stale.to_string(), "--stale 14d");
+}
+
+#[test]
+fn the_change_time_survives_saving_and_old_files_load_without_it() {
+    let dir = std::env::temp_dir().join(format!("rust-todo-cli-touched-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json");
+    let path = path.to_str().unwrap();
+
+    let mut todo = untouched_list();
+    todo.add_tags(todo.task_ref(2).unwrap(), &["shop".to_string()])
+        .unwrap();
+    todo.save(path).unwrap();
+    let loaded = TodoList::load(path).unwrap();
+    assert_eq!(loaded[0].updated_at, Some(LONG_AGO));
+    assert_eq!(loaded[1].updated_at, todo[1].updated_at);
+
+    std::fs::write(path, r#"[{ "description": "Bare", "created_at": 5 }]"#).unwrap();
+    let old = TodoList::load(path).unwrap();
+    assert_eq!(old[0].updated_at, None);
+    assert_eq!(old[0].last_touched(), Some(5));
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,161 @@
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::todo::{Selector, Status, TaskIndex, TodoError, TodoList};
+
+use Status::*;
+
+// Whether strict transitions allow each move, a row per status to move from
+// and a column per status to move to, both in `Status::ALL` order
+const ALLOWED: [(Status, [bool; 5]); 5] = [
+    //  to: todo, waiting, in-progress, done, cancelled
+    (Todo, [true, true, true, false, true]),
+    (Waiting, [true, true, true, false, true]),
+    (InProgress, [true, true, true, true, true]),
+    (Completed, [false, false, true, true, false]),
+    (Cancelled, [true, false, false, false, true]),
+];
+
+// A list of one task with a subtask, both brought to `status` the lax way
+fn one_task(status: Status) -> TodoList {
+    let mut todo = TodoList::new();
+    todo.add_tasks("Plan trip".to_string()).unwrap();
+    let task = todo.task_ref(1).unwrap();
+    todo.add_subtask(task, "Book flights".to_string()).unwrap();
+    todo.update_task_status(task, status, false).unwrap();
+    todo.update_status_at(subtask(), status, false).unwrap();
+    todo
+}
+
+fn subtask() -> TaskIndex {
+    TaskIndex {
+        task: 1,
+        sub: Some(1),
+    }
+}
+
+#[test]
+fn the_transition_table_covers_every_pair() {
+    for (from, row) in ALLOWED {
+        for (to, allowed) in Status::ALL.into_iter().zip(row) {
+            assert_eq!(
+                from.is_valid_transition(to),
+                allowed,
+                "{:?} -> {:?}",
+                from,
+                to
+            );
+        }
+    }
+}
+
+#[test]
+fn strict_updates_make_only_the_allowed_moves() {
+    for (from, row) in ALLOWED {
+        for (to, allowed) in Status::ALL.into_iter().zip(row) {
+            let mut todo = one_task(from);
+            let task = todo.task_ref(1).unwrap();
+            let result = todo.update_task_status(task, to, true);
+            let sub = todo.update_status_at(subtask(), to, true);
+            if allowed {
+                assert!(result.is_ok() && sub.is_ok(), "{:?} -> {:?}", from, to);
+                assert_eq!(todo[0].status, to);
+                assert_eq!(todo[0].subtasks[0].status, to);
+            } else {
+                for result in [result, sub] {
+                    assert!(
+                        matches!(result, Err(TodoError::InvalidTransition { from: f, to: t }) if (f, t) == (from, to)),
+                        "{:?} -> {:?}",
+                        from,
+                        to
+                    );
+                }
+                assert_eq!(todo[0].status, from);
+                assert_eq!(todo[0].subtasks[0].status, from);
+            }
+        }
+    }
+}
+
+#[test]
+fn lax_updates_make_every_move() {
+    for from in Status::ALL {
+        for to in Status::ALL {
+            let mut todo = one_task(from);
+            let task = todo.task_ref(1).unwrap();
+            todo.update_task_status(task, to, false).unwrap();
+            todo.update_status_at(subtask(), to, false).unwrap();
+            assert_eq!(todo[0].status, to, "{:?} -> {:?}", from, to);
+            assert_eq!(todo[0].subtasks[0].status, to, "{:?} -> {:?}", from, to);
+        }
+    }
+}
+
+#[test]
+fn a_refused_move_says_where_the_task_can_go() {
+    let mut todo = one_task(Todo);
+    let task = todo.task_ref(1).unwrap();
+    let error = todo.update_task_status(task, Completed, true).unwrap_err();
+    assert_eq!(error.code(), "InvalidTransition");
+    let message = error.to_string();
+    assert!(
+        message.contains("from todo to done")
+            && message.contains("waiting, in-progress, cancelled"),
+        "{}",
+        message
+    );
+    assert!(message.contains("--force"), "{}", message);
+    // Nothing was recorded for a change that didn't happen
+    assert!(
+        todo.log()
+            .iter()
+            .all(|entry| !entry.details.contains("done"))
+    );
+}
+
+#[test]
+fn strict_toggles_and_bulk_updates_leave_disallowed_tasks_alone() {
+    let mut todo = one_task(Todo);
+    let at = TaskIndex { task: 1, sub: None };
+    assert!(matches!(
+        todo.toggle_task(at, true),
+        Err(TodoError::InvalidTransition { .. })
+    ));
+    assert_eq!(todo.toggle_task(at, false).unwrap().0, Completed);
+
+    let mut todo = TodoList::new();
+    for description in ["Buy milk", "Call mum", "Pay rent"] {
+        todo.add_tasks(description.to_string()).unwrap();
+    }
+    let started = todo.task_ref(2).unwrap();
+    todo.update_task_status(started, InProgress, true).unwrap();
+    assert_eq!(todo.update_all_skipped(None, Completed), 2);
+    assert_eq!(todo.update_all_status(None, Completed, true), 1);
+    let statuses: Vec<Status> = todo.tasks().iter().map(|task| task.status).collect();
+    assert_eq!(statuses, [Todo, Completed, Todo]);
+    assert_eq!(todo.update_all_skipped(Some(Todo), Completed), 2);
+    assert_eq!(todo.update_all_status(Some(Todo), Completed, false), 2);
+}
+
+#[test]
+fn force_is_read_off_status_commands() {
+    let number = |n| Selector::Index(TaskIndex { task: n, sub: None });
+    assert_eq!(
+        parse_command("done 1 --force 2").unwrap(),
+        Command::Done(vec![number(1), number(2)], true)
+    );
+    assert_eq!(
+        parse_command("toggle --force 3").unwrap(),
+        Command::Toggle(vec![number(3)], true)
+    );
+    assert_eq!(
+        parse_command("update 3 done --force").unwrap(),
+        Command::Update(number(3), "done".to_string(), true)
+    );
+    assert_eq!(
+        parse_command("update all todo done --force").unwrap(),
+        Command::UpdateAll(Some(Todo), Completed, true)
+    );
+    assert_eq!(
+        parse_command("update 3 in progress").unwrap(),
+        Command::Update(number(3), "in progress".to_string(), false)
+    );
+}
@@ -0,0 +1,75 @@
+use rust_todo_cli::todo::ListFilter;
+use rust_todo_cli::view::ListView;
+
+// Every page `more` would show after the first, until it runs out
+fn rest(view: &mut ListView, total: usize) -> Vec<std::ops::Range<usize>> {
+    std::iter::from_fn(|| view.next_page(total)).collect()
+}
+
+#[test]
+fn an_empty_list_is_one_empty_page() {
+    let mut view = ListView::new(20);
+    assert_eq!(view.first_page(&ListFilter::default(), 0), 0..0);
+    assert!(!view.has_more());
+    assert_eq!(view.next_page(0), None);
+}
+
+#[test]
+fn an_exact_multiple_of_the_page_size_ends_on_a_full_page() {
+    let mut view = ListView::new(10);
+    assert_eq!(view.first_page(&ListFilter::default(), 30), 0..10);
+    assert!(view.has_more());
+    assert_eq!(rest(&mut view, 30), [10..20, 20..30]);
+    assert!(!view.has_more());
+
+    // Exactly one page shows it all at once
+    assert_eq!(view.first_page(&ListFilter::default(), 10), 0..10);
+    assert!(!view.has_more());
+    assert_eq!(view.next_page(10), None);
+}
+
+#[test]
+fn one_past_a_page_leaves_a_page_of_one() {
+    let mut view = ListView::new(10);
+    assert_eq!(view.first_page(&ListFilter::default(), 21), 0..10);
+    assert_eq!(rest(&mut view, 21), [10..20, 20..21]);
+    assert_eq!(view.first_page(&ListFilter::default(), 9), 0..9);
+    assert!(!view.has_more());
+}
+
+#[test]
+fn a_page_size_of_zero_shows_everything() {
+    let mut view = ListView::new(0);
+    assert_eq!(view.first_page(&ListFilter::default(), 300), 0..300);
+    assert!(!view.has_more());
+    assert_eq!(view.first_page(&ListFilter::default(), 0), 0..0);
+}
+
+#[test]
+fn a_shrunk_list_or_a_reset_ends_the_paging() {
+    let mut view = ListView::new(10);
+    view.first_page(&ListFilter::default(), 25);
+    // Tasks removed since the first page leave nothing at the cursor
+    assert_eq!(view.next_page(8), None);
+    assert!(!view.has_more());
+
+    view.first_page(&ListFilter::default(), 25);
+    // A few removed still leave the rest of the list to show
+    assert_eq!(view.next_page(15), Some(10..15));
+    view.first_page(&ListFilter::default(), 25);
+    view.reset();
+    assert!(!view.has_more());
+    assert_eq!(view.next_page(25), None);
+}
+
+#[test]
+fn the_filter_is_kept_for_the_next_page() {
+    let mut view = ListView::new(5);
+    let filter = ListFilter {
+        text: vec!["milk".to_string()],
+        ..ListFilter::default()
+    };
+    view.first_page(&filter, 12);
+    view.next_page(12);
+    assert_eq!(view.filter().text, filter.text);
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use common::list_of;
+use rust_todo_cli::command::{Command, parse_command};
+use rust_todo_cli::merge::merge_task;
+use rust_todo_cli::todo::{ListFilter, Selector, Status, TaskIndex, TodoList};
+
+fn numbers(todo: &TodoList, filter: &ListFilter) -> Vec<usize> {
+    todo.filter(filter).iter().map(|(i, _)| *i).collect()
+}
+
+#[test]
+fn waiting_tasks_are_hidden_from_the_default_list() {
+    let mut todo = list_of(&["Buy milk", "Renew passport", "Call mum"]);
+    todo.wait(todo.task_ref(2).unwrap(), None).unwrap();
+
+    let everything = ListFilter::default();
+    assert_eq!(numbers(&todo, &everything), [1, 3]);
+    assert_eq!(todo.hidden_waiting(&everything), 1);
+
+    // Other filters leave them out too, and count only those they match
+    let text = ListFilter {
+        text: vec!["passport".to_string()],
+        ..ListFilter::default()
+    };
+    assert!(numbers(&todo, &text).is_empty());
+    assert_eq!(todo.hidden_waiting(&text), 1);
+    let other = ListFilter {
+        text: vec!["milk".to_string()],
+        ..ListFilter::default()
+    };
+    assert_eq!(todo.hidden_waiting(&other), 0);
+}
+
+#[test]
+fn all_or_the_waiting_status_shows_them() {
+    let mut todo = list_of(&["Buy milk", "Renew passport"]);
+    todo.wait(todo.task_ref(2).unwrap(), None).unwrap();
+
+    let all = ListFilter {
+        all: true,
+        ..ListFilter::default()
+    };
+    assert_eq!(numbers(&todo, &all), [1, 2]);
+    assert_eq!(todo.hidden_waiting(&all), 0);
+
+    let waiting = ListFilter {
+        status: Some(Status::Waiting),
+        ..ListFilter::default()
+    };
+    assert_eq!(numbers(&todo, &waiting), [2]);
+    assert_eq!(todo.hidden_waiting(&waiting), 0);
+}
+
+#[test]
+fn waiting_keeps_its_reason_until_the_task_moves_on() {
+    let mut todo = list_of(&["Renew passport"]);
+    todo.wait(todo.task_ref(1).unwrap(), Some("photos".to_string()))
+        .unwrap();
+    assert_eq!(todo[0].status, Status::Waiting);
+    assert_eq!(todo[0].wait_reason.as_deref(), Some("photos"));
+    assert!(todo.log().last().unwrap().details.contains("photos"));
+
+    // Waiting again without one clears it
+    todo.wait(todo.task_ref(1).unwrap(), None).unwrap();
+    assert_eq!(todo[0].wait_reason, None);
+
+    todo.wait(todo.task_ref(1).unwrap(), Some("photos".to_string()))
+        .unwrap();
+    todo.update_task_status(todo.task_ref(1).unwrap(), Status::InProgress, false)
+        .unwrap();
+    assert_eq!(todo[0].wait_reason, None);
+    assert!(todo.task_ref(2).is_err());
+}
+
+#[test]
+fn statistics_count_waiting_apart_from_todo() {
+    let mut todo = list_of(&["a", "b", "c"]);
+    todo.wait(todo.task_ref(1).unwrap(), None).unwrap();
+    todo.update_task_status(todo.task_ref(2).unwrap(), Status::Completed, false)
+        .unwrap();
+
+    let stats = todo.statistics();
+    assert_eq!(stats.total, 3);
+    assert_eq!(stats.waiting, 1);
+    assert_eq!(stats.todo, 1);
+    assert_eq!(stats.completed, 1);
+    assert!(todo.summary().contains("1 waiting"), "{}", todo.summary());
+}
+
+#[test]
+fn next_suggests_no_waiting_task() {
+    let mut todo = list_of(&["a", "b"]);
+    todo.wait(todo.task_ref(1).unwrap(), None).unwrap();
+    let next: Vec<usize> = todo.suggest_next(5).iter().map(|(i, _)| *i).collect();
+    assert_eq!(next, [2]);
+}
+
+#[test]
+fn wait_parses_with_and_without_a_reason() {
+    let task = Selector::Index(TaskIndex { task: 3, sub: None });
+    assert_eq!(
+        parse_command("wait 3").unwrap(),
+        Command::Wait(task.clone(), None)
+    );
+    assert_eq!(
+        parse_command("someday 3 reply from Sam").unwrap(),
+        Command::Wait(task, Some("reply from Sam".to_string()))
+    );
+    assert!(parse_command("wait").is_err());
+    assert!(parse_command("wait 3").unwrap().mutates());
+
+    let Command::List { filter, .. } = parse_command("list all").unwrap() else {
+        panic!("list all is a listing");
+    };
+    assert!(filter.all && filter.is_empty());
+    let Command::List { filter, .. } = parse_command("list someday").unwrap() else {
+        panic!("list someday is a listing");
+    };
+    assert_eq!(filter.status, Some(Status::Waiting));
+}
+
+#[test]
+fn merging_keeps_the_reason_of_the_status_that_wins() {
+    let mut ours = list_of(&["Renew passport"]);
+    let theirs = ours.clone();
+    ours.wait(ours.task_ref(1).unwrap(), Some("photos".to_string()))
+        .unwrap();
+    let merged = merge_task(&ours[0], &theirs[0]);
+    assert_eq!(merged.status, Status::Waiting);
+    assert_eq!(merged.wait_reason.as_deref(), Some("photos"));
+
+    let mut done = theirs.clone();
+    done.update_task_status(done.task_ref(1).unwrap(), Status::Completed, false)
+        .unwrap();
+    let merged = merge_task(&ours[0], &done[0]);
+    assert_eq!(merged.status, Status::Completed);
+    assert_eq!(merged.wait_reason, None);
+}
@@ -0,0 +1,89 @@
+use rust_todo_cli::width::{clusters, display_width, pad_left, pad_right, truncate, wrap};
+
+// Mixed ASCII, CJK, emoji and joined emoji, as descriptions come
+const MIXED: &[&str] = &[
+    "Buy milk",
+    "牛乳を買う",
+    "Call mum 📞",
+    "Family 👨‍👩‍👧 dinner",
+    "🇯🇵 trip ✈️ 東京",
+    "Thumbs 👍🏽 up",
+    "Café au lait",
+    "नमस्ते दुनिया",
+];
+
+#[test]
+fn clusters_are_whole_graphemes() {
+    assert_eq!(clusters("नमस्ते"), ["न", "म", "स्ते"]);
+    assert_eq!(clusters("a\u{e31}b"), ["a\u{e31}", "b"]);
+    // Conjoining jamo spelling one Hangul syllable
+    assert_eq!(clusters("\u{1100}\u{1161}\u{11a8}").len(), 1);
+    assert_eq!(clusters("👨‍👩‍👧🇯🇵👍🏽e\u{301}"), ["👨‍👩‍👧", "🇯🇵", "👍🏽", "e\u{301}"]);
+}
+
+#[test]
+fn widths_count_cells_not_characters() {
+    assert_eq!(display_width("Buy milk"), 8);
+    assert_eq!(display_width("牛乳"), 4);
+    assert_eq!(display_width("✅"), 2);
+    assert_eq!(display_width("❤️"), 2);
+    assert_eq!(display_width("👨‍👩‍👧"), 2);
+    assert_eq!(display_width("🇯🇵"), 2);
+    assert_eq!(display_width("a\u{e31}"), 1);
+    assert_eq!(display_width("\u{1100}\u{1161}\u{11a8}"), 2);
+    assert_eq!(display_width("e\u{301}"), 1);
+    // Control characters are not drawn
+    assert_eq!(display_width("\u{1}x\u{2}"), 1);
+}
+
+#[test]
+fn padded_mixed_text_lines_up() {
+    let widest = MIXED.iter().map(|text| display_width(text)).max().unwrap();
+    for text in MIXED {
+        let line = format!("|{}|{}|", pad_right(text, widest), pad_left(text, widest));
+        assert_eq!(display_width(&line), 2 * widest + 3, "{:?}", line);
+    }
+}
+
+#[test]
+fn truncation_never_splits_a_cluster() {
+    for text in MIXED {
+        for max in 0..=display_width(text) {
+            let cut = truncate(text, max);
+            assert!(display_width(&cut) <= max, "{:?} at {}", cut, max);
+            let kept = cut.strip_suffix('…').unwrap_or(&cut);
+            // What is kept is a run of the original's clusters
+            let original = clusters(text);
+            assert_eq!(
+                clusters(kept),
+                original[..clusters(kept).len()],
+                "{:?}",
+                cut
+            );
+        }
+    }
+    assert_eq!(truncate("👨‍👩‍👧 dinner", 2), "…");
+    assert_eq!(truncate("👨‍👩‍👧 dinner", 3), "👨‍👩‍👧…");
+    assert_eq!(truncate("牛乳を買う", 6), "牛乳…");
+}
+
+#[test]
+fn wrapped_lines_fit_and_keep_clusters_whole() {
+    for text in MIXED {
+        for width in 1..=12 {
+            let lines = wrap(text, width);
+            for line in &lines {
+                // A lone wide cluster is the only thing let past a 1-cell line
+                assert!(
+                    display_width(line) <= width.max(2),
+                    "{:?} at {}",
+                    line,
+                    width
+                );
+            }
+            let joined: String = lines.concat().split_whitespace().collect();
+            let without_spaces: String = text.split_whitespace().collect();
+            assert_eq!(joined, without_spaces, "{:?} at {}", text, width);
+        }
+    }
+}